@@ -15,12 +15,12 @@ fn main() {
   if target.contains("windows") {
     std::env::set_var(
       "TAURI_CONFIG",
-      r#"{ "bundle": { "resources": ["../public/tools/ffprobe.exe", "../public/tools/ffmpeg.exe", "../public/tools/mkvmerge.exe", "../public/tools/mkvinfo.exe"] } }"#,
+      r#"{ "bundle": { "resources": ["../public/tools/ffprobe.exe", "../public/tools/ffmpeg.exe", "../public/tools/mkvmerge.exe", "../public/tools/mkvinfo.exe", "../public/tools/mkvextract.exe", "../public/tools/mkvpropedit.exe"] } }"#,
     );
   } else if target.contains("linux") {
     std::env::set_var(
       "TAURI_CONFIG",
-      r#"{ "bundle": { "resources": ["../public/tools/ffprobe", "../public/tools/ffmpeg", "../public/tools/mkvmerge", "../public/tools/mkvinfo"] } }"#,
+      r#"{ "bundle": { "resources": ["../public/tools/ffprobe", "../public/tools/ffmpeg", "../public/tools/mkvmerge", "../public/tools/mkvinfo", "../public/tools/mkvextract", "../public/tools/mkvpropedit"] } }"#,
     );
   }
 
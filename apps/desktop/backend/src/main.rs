@@ -5,93 +5,42 @@
 mod services;
 
 use tauri::Manager;
+use tauri_plugin_deep_link::init as deep_link_plugin;
 use tauri_plugin_dialog::init as dialog_plugin;
-
-// 在 Windows 下创建一个指向数据目录的“junction”。
-// 目的：让用户在安装目录下看到 user-data，方便查找/迁移。
-fn ensure_data_link(app: &tauri::AppHandle) {
-  #[cfg(target_os = "windows")]
-  {
-    use std::env;
-    use std::fs;
-    use std::process::Command;
-
-    // 应用数据目录（系统推荐的位置）。
-    let data_dir = match app.path().app_data_dir() {
-      Ok(dir) => dir.join("hanamirip-cn"),
-      Err(err) => {
-        eprintln!("failed to resolve app data dir: {err}");
-        return;
-      }
-    };
-
-    // 确保数据目录存在。
-    if let Err(err) = fs::create_dir_all(&data_dir) {
-      eprintln!("failed to create app data dir: {err}");
-      return;
-    }
-
-    // 获取 exe 所在目录（真实安装位置）。
-    let install_dir = match env::current_exe() {
-      Ok(exe_path) => exe_path
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| exe_path.clone()),
-      Err(err) => {
-        eprintln!("failed to get current exe path: {err}");
-        return;
-      }
-    };
-
-    // 想在安装目录下创建的链接路径。
-    let link_path = install_dir.join("user-data");
-
-    // 如果已存在，跳过创建。
-    if link_path.exists() {
-      return;
-    }
-
-    // 尝试创建 junction（目录链接）。
-    let status = if cfg!(target_os = "windows") {
-      use std::os::windows::process::CommandExt;
-      const CREATE_NO_WINDOW: u32 = 0x08000000;
-
-      Command::new("cmd")
-        .args([
-          "/C",
-          "mklink",
-          "/J",
-          link_path.to_string_lossy().as_ref(),
-          data_dir.to_string_lossy().as_ref(),
-        ])
-        .creation_flags(CREATE_NO_WINDOW)
-        .status()
-    } else {
-      Ok(std::process::ExitStatus::default())
-    };
-
-    // 根据执行结果打印日志。
-    match status {
-      Ok(status) => {
-        if status.success() {
-          eprintln!("created data link at: {}", link_path.display());
-        } else {
-          eprintln!("mklink failed: {}", link_path.display());
-        }
-      }
-      Err(err) => {
-        eprintln!("failed to execute mklink: {err}");
-      }
-    }
-  }
-}
+use tauri_plugin_single_instance::init as single_instance_plugin;
 
 // 程序入口：初始化插件、注册命令、启动应用。
 fn main() {
   tauri::Builder::default()
+    // 单实例必须最先注册：再次启动（例如点击磁力链接）时，
+    // 链接会转交给已运行的实例（经 deep-link 插件处理），新进程直接退出。
+    .plugin(single_instance_plugin(|app, _argv, _cwd| {
+      services::magnet::focus_main_window(app);
+    }))
+    .plugin(deep_link_plugin())
     .plugin(dialog_plugin())
     .setup(|app| {
-      ensure_data_link(app.handle());
+      // 尽早安装崩溃报告钩子，后续初始化出错也能留下现场。
+      services::diagnostics::install_crash_reporter(app.handle());
+      // 启用运行日志（诊断包会附上最近的日志）。
+      services::diagnostics::init_log_writer(app.handle());
+      // 读取设置并应用并发上限等全局配置。
+      services::settings::init_settings(app.handle());
+      // 启动后台定时任务。
+      services::scheduler::start_scheduler(app.handle().clone());
+      // 启动新剧集通知轮询。
+      services::notifications::start_episode_notifier(app.handle().clone());
+      // 记录密钥覆盖文件（credentials.toml）与 Bangumi 缓存所在目录。
+      if let Ok(dir) = app.path().app_data_dir() {
+        let data_dir = dir.join("hanamirip-cn");
+        services::bangumi::init_cache_dir(data_dir.join("cache").join("bangumi"));
+        services::metadata::init_metadata_dir(data_dir.join("cache").join("metadata"));
+        services::bangumi::init_filter_taxonomy(data_dir.clone());
+        services::bangumi::init_credentials_dir(data_dir);
+      }
+      services::system::ensure_data_link(app.handle());
+      // 注册 magnet: 协议，并处理启动时带来的磁力链接。
+      services::magnet::init_magnet_handler(app.handle());
       Ok(())
     })
     // 注册可被前端调用的命令列表。
@@ -99,25 +48,124 @@ fn main() {
       services::bangumi::commands::get_season_subjects,
       services::bangumi::commands::get_subject_origin,
       services::bangumi::commands::get_subject_aired_count,
+      services::bangumi::commands::get_subject_episodes,
+      services::bangumi::commands::get_next_episode_info,
+      services::bangumi::commands::get_subject_relations,
       services::bangumi::commands::get_subject_filters,
+      services::bangumi::commands::get_subject_tags,
+      services::bangumi::commands::get_subject_infobox,
+      services::bangumi::commands::reload_filter_taxonomy,
       services::bangumi::commands::get_subject_staff,
       services::bangumi::commands::get_subject_characters,
+      services::bangumi::commands::get_character_detail,
+      services::bangumi::commands::get_person_works,
+      services::bangumi::commands::get_studio_works,
+      services::bangumi::commands::get_subject_artwork,
+      services::bangumi::commands::get_subject_rating_detail,
       services::bangumi::commands::get_subject_summary_cn,
+      services::bangumi::commands::translate_batch,
+      services::bangumi::commands::convert_chinese,
+      services::bangumi::commands::get_episode_titles_cn,
+      services::bangumi::commands::list_translation_glossary,
+      services::bangumi::commands::add_translation_glossary_entry,
+      services::bangumi::commands::remove_translation_glossary_entry,
       services::bangumi::commands::get_subject_brief,
-      services::bangumi::commands::fetch_search_html,
+      services::bangumi::commands::get_subject_briefs,
+      services::bangumi::commands::compare_subjects,
       services::bangumi::commands::get_subject_aliases,
+      services::bangumi::commands::search_subjects,
+      services::bangumi::commands::get_ranked_subjects,
+      services::bangumi::commands::obfuscate_credential,
+      services::bangumi::commands::set_translation_credentials,
+      services::bangumi::commands::test_translation_credentials,
+      services::bangumi::commands::sync_collections_push,
+      services::bangumi::commands::sync_collections_pull,
+      services::torrent::preview_torrent,
       services::torrent::start_torrent_download,
       services::torrent::get_torrent_status,
+      services::torrent::list_torrents,
+      services::torrent::subscribe_torrent_events,
+      services::torrent::unsubscribe_torrent_events,
       services::torrent::finalize_torrent_download,
       services::media::parse_media_tracks,
       services::media::get_media_file_size,
       services::media::mix_media_tracks,
+      services::media::batch::mix_media_batch,
+      services::media::extract::extract_media_tracks,
+      services::media::propedit::edit_mkv_properties,
+      services::media::screenshot::extract_screenshots,
+      services::media::jobs::get_media_job,
+      services::media::jobs::list_media_jobs,
+      services::media::jobs::cancel_media_job,
+      services::media::fonts::scan_subtitle_fonts,
+      services::media::fonts::subset_subtitle_fonts,
+      services::media::subtitle::convert_subtitle,
+      services::media::subtitle::shift_subtitle,
+      services::media::subtitle::convert_subtitle_chinese,
+      services::media::transcode::list_transcode_presets,
+      services::media::transcode::transcode_video,
+      services::media::transcode::transcode_audio,
+      services::checksum::hash_file,
       services::torrent::pause_torrent,
       services::torrent::resume_torrent,
       services::torrent::delete_torrent,
+      services::torrent::recheck_torrent,
+      services::torrent::peers::get_torrent_peers,
+      services::torrent::sequential::set_torrent_sequential,
+      services::torrent::stream::get_stream_url,
+      services::torrent::seeding::set_torrent_seeding_override,
+      services::torrent::queue::get_queue,
+      services::torrent::queue::set_queue_position,
+      services::torrent::trackers::get_trackers,
+      services::torrent::trackers::add_trackers,
+      services::torrent::create::create_torrent,
+      services::torrent::session::get_torrent_settings,
+      services::torrent::session::set_torrent_settings,
+      services::torrent::bandwidth::get_bandwidth_schedule,
+      services::torrent::bandwidth::set_bandwidth_schedule,
+      services::torrent::blocklist::get_blocklist_status,
+      services::torrent::blocklist::set_blocklist_source,
+      services::hooks::get_torrent_hooks,
+      services::hooks::set_torrent_hooks,
+      services::hooks::list_hook_jobs,
+      services::hooks::clear_hook_jobs,
       services::external::open_external_link,
+      services::magnet::handle_magnet,
+      services::magnet::take_pending_magnets,
+      services::images::get_cached_image,
+      services::metadata::offline_db::map_subject_ids,
+      services::metadata::mikan::search_mikan,
+      services::paths::pick_path,
+      services::system::recreate_data_link,
+      services::system::get_system_info,
+      services::diagnostics::export_diagnostics_bundle,
+      services::rss::list_rss_subscriptions,
+      services::rss::add_rss_subscription,
+      services::rss::update_rss_subscription,
+      services::rss::delete_rss_subscription,
+      services::rss::preview_rss_matches,
+      services::rss::run_rss_now,
+      services::rules::list_download_rules,
+      services::rules::save_download_rule,
+      services::rules::delete_download_rule,
+      services::rules::test_rule,
+      services::search::search_torrents,
+      services::release::parse_release_title,
+      services::naming::render_filename_template,
+      services::scheduler::list_scheduled_tasks,
+      services::scheduler::run_scheduled_task_now,
+      services::notifications::get_episode_notification_settings,
+      services::notifications::set_episode_poll_interval,
+      services::notifications::set_subject_notification_muted,
+      services::settings::get_app_settings,
+      services::settings::save_app_settings,
+      services::setup::get_setup_status,
+      services::setup::check_tools,
+      services::setup::apply_initial_setup,
       services::storage::list_tracked_subjects,
-      services::storage::save_tracked_subject
+      services::storage::save_tracked_subject,
+      services::storage::history::list_download_history,
+      services::storage::history::clear_download_history
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
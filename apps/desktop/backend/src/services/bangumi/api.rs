@@ -6,33 +6,86 @@
 
 // 时间处理库：用来把“字符串日期”变成真正的日期对象，便于比较。
 use chrono::{NaiveDate, Utc};
-// HTTP 客户端：负责发起网络请求。
+// 异步流工具：用于限量并发地拉取分页数据。
+use futures::future::join3;
+use futures::stream::{self, StreamExt};
+// HTTP 客户端：负责发起网络请求（全模块共享同一个实例）。
 use reqwest::Client;
+use super::client::shared_client;
+// 原始 JSON：缓存层按原样保存接口响应。
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+// 倒序排序键。
+use std::cmp::Reverse;
+
+// 带磁盘缓存的 JSON 请求。
+use super::cache::{get_json_cached, get_json_cached_within, load_snapshot, save_snapshot};
+use crate::services::error::AppError;
+use crate::services::metadata::{lookup_fallback, tmdb, FallbackMetadata};
+
+// Bangumi 请求发送助手（并发上限 + 限流 + 重试）。
+use super::request::{read_json, send_bangumi};
 
 // 这些是“过滤/解析工具函数”，把 Bangumi 返回的杂乱数据整理成可用信息。
-use super::filters::{extract_aliases, extract_filter_groups, extract_origin, collect_subject_tags, map_tags_to_official};
+use super::filters::{
+  collect_subject_tags, dedupe_terms, extract_aliases, extract_filter_groups, extract_infobox_values, extract_origin,
+  map_tags_to_official, official_tag_name,
+};
+use super::taxonomy::current_taxonomy;
 use super::models::{
+  ComparedSubject,
+  SharedStaffResponse,
+  StudioWorksResponse,
+  StaffSubjectRoles,
+  SubjectComparisonResponse,
+  CharacterActorResponse,
+  CharacterActorSubject,
+  CharacterDetail,
+  CharacterDetailResponse,
   CharacterLinkResponse,
+  CharacterPersonItem,
   Episode,
+  EpisodeItemResponse,
   EpisodePage,
+  NextEpisodeResponse,
+  InfoboxEntry,
+  PagedPerson,
   PagedSubject,
+  PersonSubjectItem,
+  PersonWorkItem,
+  PersonWorksResponse,
+  RankedSubjectItem,
+  RankedSubjectsResponse,
+  RelatedSubjectItem,
+  RelatedSubjectResponse,
+  RelationGroupResponse,
+  SearchSubjectsResponse,
   SeasonAnime,
   SeasonMonth,
+  SeasonMonthError,
   SeasonResponse,
   StaffGroupResponse,
   StaffPersonResponse,
   Subject,
   SubjectAiredResponse,
   SubjectAliasesResponse,
+  SubjectArtworkResponse,
+  SubjectRatingResponse,
+  SubjectTagItem,
+  SubjectTagsResponse,
   SubjectBriefResponse,
+  SubjectBriefsResponse,
   SubjectCharacterItem,
   SubjectCharacterPayload,
   SubjectCharactersResponse,
   SubjectDetail,
+  SubjectEpisodesResponse,
   SubjectFiltersResponse,
+  SubjectInfoboxResponse,
   SubjectOriginResponse,
   SubjectPersonItem,
   SubjectPersonPayload,
+  SubjectRelationsResponse,
   SubjectStaffResponse,
 };
 
@@ -40,27 +93,50 @@ use super::models::{
 const API_BASE: &str = "https://api.bgm.tv";
 // 获取条目信息的路径。
 const SUBJECTS_PATH: &str = "/v0/subjects";
+// 获取人物信息的路径。
+const PERSONS_PATH: &str = "/v0/persons";
+// 关键词搜索人物的路径。
+const PERSON_SEARCH_PATH: &str = "/v0/search/persons";
+// 获取角色信息的路径。
+const CHARACTERS_PATH: &str = "/v0/characters";
+// 关键词搜索条目的路径。
+const SEARCH_PATH: &str = "/v0/search/subjects";
 // 获取分集信息的路径。
 const EPISODES_PATH: &str = "/v0/episodes";
+// 分集列表的缓存有效期上限（秒）：播出状态变化快，只做短时缓存。
+const EPISODES_TTL_SECS: u64 = 10 * 60;
+// 排行榜每页条目数（与 bgm.tv 网页一致）。
+const RANK_PAGE_SIZE: u32 = 24;
+// 拉取月度列表时同时进行的分页请求数。
+const MONTH_PAGE_CONCURRENCY: usize = 4;
+// 条目对比允许的数量范围。
+const COMPARE_MIN_SUBJECTS: usize = 2;
+const COMPARE_MAX_SUBJECTS: usize = 4;
+// 批量获取简要信息时同时进行的请求数。
+const BRIEF_BATCH_CONCURRENCY: usize = 8;
+// 默认的条目类型：动画。
+pub(crate) const ANIME_SUBJECT_TYPE: u32 = 2;
+// 支持浏览的条目类型：书籍 / 动画 / 音乐 / 游戏 / 三次元（5 在 Bangumi 中未使用）。
+const SUPPORTED_SUBJECT_TYPES: [u32; 5] = [1, 2, 3, 4, 6];
 // 当条目没有图片时使用的默认图片。
 const DEFAULT_IMAGE: &str = "https://lain.bgm.tv/img/no_icon_subject.png";
 
 // 把“季节字符串”转换成对应月份列表。
 // 例如 winter -> [1,2,3]，spring -> [4,5,6]。
 // 返回 Result 是因为传入的季节可能不合法。
-pub(crate) fn season_months(season: &str) -> Result<Vec<u32>, String> {
+pub(crate) fn season_months(season: &str) -> Result<Vec<u32>, AppError> {
   match season {
     "winter" => Ok(vec![1, 2, 3]),
     "spring" => Ok(vec![4, 5, 6]),
     "summer" => Ok(vec![7, 8, 9]),
     "autumn" => Ok(vec![10, 11, 12]),
-    _ => Err("invalid season".into()),
+    _ => Err(AppError::invalid_input("invalid season")),
   }
 }
 
 // 从一组可选图片地址里挑一个“可用的”。
 // 优先顺序：common -> medium -> large；都没有就返回默认图。
-fn resolve_image(images: Option<super::models::Images>) -> String {
+pub(crate) fn resolve_image(images: Option<super::models::Images>) -> String {
   if let Some(images) = images {
     if let Some(url) = images.common {
       return url;
@@ -98,26 +174,24 @@ fn is_aired(episode: &Episode, today: NaiveDate) -> bool {
   false
 }
 
+// 把缓存层返回的原始 JSON 转换成目标结构；结构不符时归为解析错误。
+fn parse_payload<T: DeserializeOwned>(raw: Value) -> Result<T, AppError> {
+  serde_json::from_value(raw).map_err(|e| AppError::parse(format!("解析 Bangumi 响应失败: {e}")))
+}
+
 // 获取条目的“制作人员/声优等人物列表”。
 // 这里会处理分页/非分页两种返回格式。
 pub(crate) async fn fetch_subject_persons(
   client: &Client,
   id: u32,
-) -> Result<Vec<SubjectPersonItem>, String> {
-  // 发起网络请求。
-  let response = client
-    .get(format!("{API_BASE}{SUBJECTS_PATH}/{id}/persons"))
-    .send()
-    .await
-    .map_err(|e| e.to_string())?;
-
-  // 非 2xx 都视为失败，并给出明确的错误信息。
-  if !response.status().is_success() {
-    return Err(format!("Bangumi API 请求失败: {}", response.status()));
-  }
+  force_refresh: bool,
+) -> Result<Vec<SubjectPersonItem>, AppError> {
+  // 发起网络请求（有效期内直接读取缓存）。
+  let request = client.get(format!("{API_BASE}{SUBJECTS_PATH}/{id}/persons"));
+  let raw = get_json_cached(&format!("persons-{id}"), force_refresh, request).await?;
 
   // 解析 JSON，如果失败直接把错误转成字符串返回。
-  let payload: SubjectPersonPayload = response.json().await.map_err(|e| e.to_string())?;
+  let payload: SubjectPersonPayload = parse_payload(raw)?;
   // Bangumi 可能返回“列表”或“分页结构”，这里统一成 Vec。
   let items = match payload {
     SubjectPersonPayload::List(list) => list,
@@ -185,18 +259,12 @@ pub(crate) fn build_staff_groups(items: Vec<SubjectPersonItem>) -> Vec<StaffGrou
 pub(crate) async fn fetch_subject_characters(
   client: &Client,
   id: u32,
-) -> Result<Vec<SubjectCharacterItem>, String> {
-  let response = client
-    .get(format!("{API_BASE}{SUBJECTS_PATH}/{id}/characters"))
-    .send()
-    .await
-    .map_err(|e| e.to_string())?;
-
-  if !response.status().is_success() {
-    return Err(format!("Bangumi API 请求失败: {}", response.status()));
-  }
+  force_refresh: bool,
+) -> Result<Vec<SubjectCharacterItem>, AppError> {
+  let request = client.get(format!("{API_BASE}{SUBJECTS_PATH}/{id}/characters"));
+  let raw = get_json_cached(&format!("characters-{id}"), force_refresh, request).await?;
 
-  let payload: SubjectCharacterPayload = response.json().await.map_err(|e| e.to_string())?;
+  let payload: SubjectCharacterPayload = parse_payload(raw)?;
   let items = match payload {
     SubjectCharacterPayload::List(list) => list,
     SubjectCharacterPayload::Page { data, .. } => data,
@@ -205,6 +273,16 @@ pub(crate) async fn fetch_subject_characters(
   Ok(items)
 }
 
+// 校验条目类型参数；未传时默认动画。
+pub(crate) fn resolve_subject_type(subject_type: Option<u32>) -> Result<u32, AppError> {
+  let subject_type = subject_type.unwrap_or(ANIME_SUBJECT_TYPE);
+  if SUPPORTED_SUBJECT_TYPES.contains(&subject_type) {
+    Ok(subject_type)
+  } else {
+    Err(AppError::invalid_input(format!("不支持的条目类型: {subject_type}")))
+  }
+}
+
 // 把 Bangumi 的 Subject 数据转换成我们前端更好用的 SeasonAnime。
 // 这里会：
 // - 补默认图
@@ -216,6 +294,9 @@ pub(crate) fn map_subject(subject: Subject) -> SeasonAnime {
   let date = subject.date.unwrap_or_default();
   let rating = subject.rating.and_then(|value| value.score);
   let url = format!("https://bgm.tv/subject/{}", subject.id);
+  // 空字符串与 0 都视为“未知”。
+  let platform = subject.platform.filter(|value| !value.trim().is_empty());
+  let volumes = subject.volumes.filter(|value| *value > 0);
   SeasonAnime {
     id: subject.id,
     name: subject.name,
@@ -225,34 +306,41 @@ pub(crate) fn map_subject(subject: Subject) -> SeasonAnime {
     rating,
     summary,
     url,
+    subject_type: subject.subject_type.unwrap_or(ANIME_SUBJECT_TYPE),
+    platform,
+    volumes,
   }
 }
 
+// 获取条目详情的原始 JSON（有效期内直接读取缓存）。
+// 简介/原作/筛选标签/别名都来自同一个接口，共用一份缓存。
+async fn fetch_subject_json(client: &Client, id: u32, force_refresh: bool) -> Result<Value, AppError> {
+  let request = client.get(format!("{API_BASE}{SUBJECTS_PATH}/{id}"));
+  get_json_cached(&format!("subject-{id}"), force_refresh, request).await
+}
+
 // 获取“条目简介”。这是一个轻量级接口：
 // 只保留前端最常用字段。
-pub(crate) async fn get_subject_brief(id: u32) -> Result<SubjectBriefResponse, String> {
-  // 构建 HTTP 客户端，并设置 user_agent。
-  let client = reqwest::Client::builder()
-    .user_agent("HanamiRIP-CN/0.1")
-    .build()
-    .map_err(|e| e.to_string())?;
+// 条目缺少简介或封面时用备用数据源（AniList）补全；
+// Bangumi 请求失败时，如果之前已经建立过 ID 映射，则直接用备用数据源的数据。
+pub(crate) async fn get_subject_brief(id: u32, force_refresh: bool) -> Result<SubjectBriefResponse, AppError> {
+  let client = shared_client();
 
   // 请求条目详情。
-  let response = client
-    .get(format!("{API_BASE}{SUBJECTS_PATH}/{id}"))
-    .send()
-    .await
-    .map_err(|e| e.to_string())?;
-
-  // 状态码不成功则直接返回错误。
-  if !response.status().is_success() {
-    return Err(format!("Bangumi API 请求失败: {}", response.status()));
-  }
+  let raw = match fetch_subject_json(&client, id, force_refresh).await {
+    Ok(raw) => raw,
+    Err(err) => {
+      return match lookup_fallback(id, &[]).await {
+        Some(meta) => Ok(brief_from_fallback(id, meta)),
+        None => Err(err),
+      };
+    }
+  };
 
   // 把 JSON 解析成 Subject 结构体。
-  let subject: Subject = response.json().await.map_err(|e| e.to_string())?;
+  let subject: Subject = parse_payload(raw)?;
   let mapped = map_subject(subject);
-  Ok(SubjectBriefResponse {
+  let mut brief = SubjectBriefResponse {
     id: mapped.id,
     name: mapped.name,
     name_cn: mapped.name_cn,
@@ -261,90 +349,257 @@ pub(crate) async fn get_subject_brief(id: u32) -> Result<SubjectBriefResponse, S
     rating: mapped.rating,
     summary: mapped.summary,
     url: mapped.url,
-  })
+    fallback_provider: None,
+  };
+
+  let missing_summary = brief.summary.trim().is_empty();
+  let missing_image = brief.image == DEFAULT_IMAGE;
+  if missing_summary || missing_image {
+    let titles = vec![brief.name.clone(), brief.name_cn.clone()];
+    if let Some(meta) = lookup_fallback(id, &titles).await {
+      let mut used = false;
+      if missing_summary {
+        if let Some(summary) = meta.summary {
+          brief.summary = summary;
+          used = true;
+        }
+      }
+      if missing_image {
+        if let Some(image) = meta.image {
+          brief.image = image;
+          used = true;
+        }
+      }
+      if used {
+        brief.fallback_provider = Some(meta.provider.to_string());
+      }
+    }
+  }
+  Ok(brief)
 }
 
-// 拉取“某年某月”的番剧列表。
-// Bangumi API 有分页，所以这里需要循环拉取直到数据结束。
+// 完全使用备用数据源的数据生成条目简介（Bangumi 不可用时）。
+fn brief_from_fallback(id: u32, meta: FallbackMetadata) -> SubjectBriefResponse {
+  SubjectBriefResponse {
+    id,
+    name: meta.title.unwrap_or_default(),
+    name_cn: String::new(),
+    image: meta.image.unwrap_or_else(|| DEFAULT_IMAGE.to_string()),
+    date: meta.date.unwrap_or_default(),
+    rating: meta.rating,
+    summary: meta.summary.unwrap_or_default(),
+    url: format!("https://bgm.tv/subject/{id}"),
+    fallback_provider: Some(meta.provider.to_string()),
+  }
+}
+
+// 批量获取条目简介：并发请求（最多 BRIEF_BATCH_CONCURRENCY 个同时进行），
+// 单个条目失败不影响其它条目，错误按 ID 单独返回。
+pub(crate) async fn get_subject_briefs_impl(
+  ids: Vec<u32>,
+  force_refresh: bool,
+) -> Result<SubjectBriefsResponse, AppError> {
+  // 去重，避免同一条目重复请求。
+  let mut unique = ids;
+  unique.sort_unstable();
+  unique.dedup();
+
+  let results: Vec<(u32, Result<SubjectBriefResponse, AppError>)> = stream::iter(unique)
+    .map(|id| async move { (id, get_subject_brief(id, force_refresh).await) })
+    .buffer_unordered(BRIEF_BATCH_CONCURRENCY)
+    .collect()
+    .await;
+
+  let mut response = SubjectBriefsResponse {
+    briefs: std::collections::HashMap::new(),
+    errors: std::collections::HashMap::new(),
+  };
+  for (id, result) in results {
+    match result {
+      Ok(brief) => {
+        response.briefs.insert(id, brief);
+      }
+      Err(err) => {
+        response.errors.insert(id, err.to_string());
+      }
+    }
+  }
+  Ok(response)
+}
+
+// 拉取“某年某月”条目列表的其中一页。
+async fn fetch_month_page(
+  client: &Client,
+  subject_type: u32,
+  year: u32,
+  month: u32,
+  offset: u32,
+  limit: u32,
+  force_refresh: bool,
+) -> Result<PagedSubject, AppError> {
+  // 分页请求：带上 offset 和 limit。
+  let request = client
+    .get(format!("{API_BASE}{SUBJECTS_PATH}"))
+    .query(&[
+      ("type", &subject_type.to_string()),
+      ("year", &year.to_string()),
+      ("month", &month.to_string()),
+      ("limit", &limit.to_string()),
+      ("offset", &offset.to_string()),
+    ]);
+  // 每一页单独缓存。
+  let key = format!("month-{subject_type}-{year}-{month}-{offset}");
+  let raw = get_json_cached(&key, force_refresh, request).await?;
+
+  // 解析分页数据。
+  parse_payload(raw)
+}
+
+// 拉取“某年某月”的条目列表（subject_type 为条目类型，动画为 2）。
+// Bangumi API 有分页：先拉第一页拿到 total，
+// 再并发拉取剩余页（最多 MONTH_PAGE_CONCURRENCY 个同时进行），按页码顺序合并。
 pub(crate) async fn fetch_month_subjects(
   client: &Client,
+  subject_type: u32,
   year: u32,
   month: u32,
-) -> Result<Vec<SeasonAnime>, String> {
-  // offset：从第几条开始；limit：每次拉取数量。
-  let mut offset = 0u32;
+  force_refresh: bool,
+) -> Result<Vec<SeasonAnime>, AppError> {
+  // limit：每次拉取数量。
   let limit = 50u32;
+
+  let first = fetch_month_page(client, subject_type, year, month, 0, limit, force_refresh).await?;
+  if first.data.is_empty() {
+    return Ok(Vec::new());
+  }
+  // 页大小以服务端实际返回为准，确保各页不重复。
+  let step = first.limit.max(limit);
+  let total = first.total;
   // items 用来累积所有结果。
-  let mut items = Vec::new();
+  let mut items: Vec<SeasonAnime> = first.data.into_iter().map(map_subject).collect();
+
+  // buffered 会保持输入顺序输出结果，合并后的列表与顺序拉取时一致。
+  let pages: Vec<Result<PagedSubject, AppError>> = stream::iter((step..total).step_by(step as usize))
+    .map(|offset| fetch_month_page(client, subject_type, year, month, offset, limit, force_refresh))
+    .buffered(MONTH_PAGE_CONCURRENCY)
+    .collect()
+    .await;
+  for page in pages {
+    // 把每条 Subject 映射成 SeasonAnime 并累积。
+    items.extend(page?.data.into_iter().map(map_subject));
+  }
 
-  loop {
-    // 分页请求：带上 offset 和 limit。
-    let response = client
-      .get(format!("{API_BASE}{SUBJECTS_PATH}"))
-      .query(&[
-        ("type", "2"),
-        ("year", &year.to_string()),
-        ("month", &month.to_string()),
-        ("limit", &limit.to_string()),
-        ("offset", &offset.to_string()),
-      ])
-      .send()
-      .await
-      .map_err(|e| e.to_string())?;
+  Ok(items)
+}
 
-    if !response.status().is_success() {
-      return Err(format!("Bangumi API 请求失败: {}", response.status()));
-    }
+// 获取排行榜（按 Bangumi 排名从高到低）的第 page 页，page 从 1 开始。
+// 使用浏览接口的 sort=rank，每页单独缓存。
+pub(crate) async fn get_ranked_subjects_impl(
+  subject_type: Option<u32>,
+  page: Option<u32>,
+  force_refresh: bool,
+) -> Result<RankedSubjectsResponse, AppError> {
+  let subject_type = resolve_subject_type(subject_type)?;
+  let page = page.unwrap_or(1).max(1);
+  let offset = (page - 1).saturating_mul(RANK_PAGE_SIZE);
+
+  let client = shared_client();
+  let request = client
+    .get(format!("{API_BASE}{SUBJECTS_PATH}"))
+    .query(&[
+      ("type", subject_type.to_string()),
+      ("sort", "rank".to_string()),
+      ("limit", RANK_PAGE_SIZE.to_string()),
+      ("offset", offset.to_string()),
+    ]);
+  let key = format!("rank-{subject_type}-{page}");
+  let raw = get_json_cached(&key, force_refresh, request).await?;
+  let payload: PagedSubject = parse_payload(raw)?;
+
+  let list = payload
+    .data
+    .into_iter()
+    .map(|subject| {
+      let rank = subject
+        .rating
+        .as_ref()
+        .and_then(|rating| rating.rank)
+        .filter(|rank| *rank > 0);
+      RankedSubjectItem {
+        rank,
+        subject: map_subject(subject),
+      }
+    })
+    .collect();
 
-    // 解析分页数据。
-    let payload: PagedSubject = response.json().await.map_err(|e| e.to_string())?;
-    if payload.data.is_empty() {
-      break;
-    }
+  Ok(RankedSubjectsResponse {
+    subject_type,
+    page,
+    page_size: RANK_PAGE_SIZE,
+    total: payload.total,
+    list,
+  })
+}
 
-    // 把每条 Subject 映射成 SeasonAnime 并累积。
-    items.extend(payload.data.into_iter().map(map_subject));
+// 按关键词搜索条目。
+// subject_type 为 Bangumi 条目类型（2 = 动画），默认只搜动画；
+// limit 限制在 1..=50（接口单页上限），结果不走缓存。
+pub(crate) async fn search_subjects_impl(
+  keyword: String,
+  subject_type: Option<u32>,
+  limit: Option<u32>,
+  offset: Option<u32>,
+) -> Result<SearchSubjectsResponse, AppError> {
+  let keyword = keyword.trim().to_string();
+  if keyword.is_empty() {
+    return Err(AppError::invalid_input("搜索关键词不能为空"));
+  }
+  let subject_type = resolve_subject_type(subject_type)?;
+  let limit = limit.unwrap_or(20).clamp(1, 50);
+  let offset = offset.unwrap_or(0);
+
+  let client = shared_client();
+  // 搜索接口是 POST：分页参数放在 query，关键词与过滤条件放在 JSON 请求体。
+  let request = client
+    .post(format!("{API_BASE}{SEARCH_PATH}"))
+    .query(&[("limit", limit.to_string()), ("offset", offset.to_string())])
+    .json(&serde_json::json!({
+      "keyword": keyword,
+      "filter": { "type": [subject_type] },
+    }));
+  let response = send_bangumi(request).await?;
 
-    // 递增 offset，确保下一页不重复。
-    offset += payload.limit.max(limit);
-    if offset >= payload.total {
-      break;
-    }
+  if !response.status().is_success() {
+    let status = response.status();
+    return Err(AppError::api(status.as_u16(), format!("Bangumi API 请求失败: {status}")));
   }
 
-  Ok(items)
+  let payload: PagedSubject = read_json(response).await?;
+  Ok(SearchSubjectsResponse {
+    keyword,
+    total: payload.total,
+    limit,
+    offset,
+    list: payload.data.into_iter().map(map_subject).collect(),
+  })
 }
 
 // 获取“原作来源”（例如：漫画、小说、原创）。
-pub(crate) async fn get_subject_origin_impl(id: u32) -> Result<SubjectOriginResponse, String> {
-  let client = reqwest::Client::builder()
-    .user_agent("HanamiRIP-CN/0.1")
-    .build()
-    .map_err(|e| e.to_string())?;
-
-  let response = client
-    .get(format!("{API_BASE}{SUBJECTS_PATH}/{id}"))
-    .send()
-    .await
-    .map_err(|e| e.to_string())?;
+pub(crate) async fn get_subject_origin_impl(id: u32, force_refresh: bool) -> Result<SubjectOriginResponse, AppError> {
+  let client = shared_client();
 
-  if !response.status().is_success() {
-    return Err(format!("Bangumi API 请求失败: {}", response.status()));
-  }
+  let raw = fetch_subject_json(&client, id, force_refresh).await?;
 
   // 解析完整详情，随后从 infobox 中提取“原作”。
-  let payload: SubjectDetail = response.json().await.map_err(|e| e.to_string())?;
+  let payload: SubjectDetail = parse_payload(raw)?;
   let origin = extract_origin(payload.infobox);
   Ok(SubjectOriginResponse { id, origin })
 }
 
 // 获取“已播出集数”。
 // 逻辑：分页拉取分集列表，然后根据状态/日期判断是否已播出。
-pub(crate) async fn get_subject_aired_count_impl(id: u32) -> Result<SubjectAiredResponse, String> {
-  let client = reqwest::Client::builder()
-    .user_agent("HanamiRIP-CN/0.1")
-    .build()
-    .map_err(|e| e.to_string())?;
+pub(crate) async fn get_subject_aired_count_impl(id: u32) -> Result<SubjectAiredResponse, AppError> {
+  let client = shared_client();
 
   // 分页参数与统计计数。
   let mut offset = 0u32;
@@ -355,23 +610,22 @@ pub(crate) async fn get_subject_aired_count_impl(id: u32) -> Result<SubjectAired
   let today = Utc::now().date_naive();
 
   loop {
-    let response = client
+    let request = client
       .get(format!("{API_BASE}{EPISODES_PATH}"))
       .query(&[
         ("subject_id", &id.to_string()),
         ("limit", &limit.to_string()),
         ("offset", &offset.to_string()),
-      ])
-      .send()
-      .await
-      .map_err(|e| e.to_string())?;
+      ]);
+    let response = send_bangumi(request).await?;
 
     if !response.status().is_success() {
-      return Err(format!("Bangumi API 请求失败: {}", response.status()));
+      let status = response.status();
+      return Err(AppError::api(status.as_u16(), format!("Bangumi API 请求失败: {status}")));
     }
 
     // 解析分集分页数据。
-    let payload: EpisodePage = response.json().await.map_err(|e| e.to_string())?;
+    let payload: EpisodePage = read_json(response).await?;
     if total_count == 0 {
       total_count = payload.total;
     }
@@ -400,134 +654,114 @@ pub(crate) async fn get_subject_aired_count_impl(id: u32) -> Result<SubjectAired
   })
 }
 
+// 获取完整分集列表（集数、标题、播出日期、时长、状态）。
+// 分页在内部处理，返回按接口顺序排列的全部分集；
+// 每一页走短时缓存（EPISODES_TTL_SECS），分集列表与下一集信息共用。
+pub(crate) async fn get_subject_episodes_impl(id: u32) -> Result<SubjectEpisodesResponse, AppError> {
+  let client = shared_client();
+
+  let mut offset = 0u32;
+  let limit = 100u32;
+  let mut episodes = Vec::new();
+  let today = Utc::now().date_naive();
+
+  // 以最后一页返回的总数为准。
+  let total = loop {
+    let request = client
+      .get(format!("{API_BASE}{EPISODES_PATH}"))
+      .query(&[
+        ("subject_id", &id.to_string()),
+        ("limit", &limit.to_string()),
+        ("offset", &offset.to_string()),
+      ]);
+    let key = format!("episodes-{id}-{offset}");
+    let raw = get_json_cached_within(&key, false, request, EPISODES_TTL_SECS).await?;
+    let payload: EpisodePage = parse_payload(raw)?;
+    if payload.data.is_empty() {
+      break payload.total;
+    }
+
+    for episode in payload.data.iter() {
+      episodes.push(EpisodeItemResponse {
+        id: episode.id,
+        episode_type: episode.episode_type.unwrap_or(0),
+        ep: episode.ep,
+        sort: episode.sort,
+        name: episode.name.clone().unwrap_or_default(),
+        name_cn: episode.name_cn.clone().unwrap_or_default(),
+        airdate: episode.airdate.clone().filter(|value| !value.is_empty()),
+        duration: episode.duration.clone().filter(|value| !value.is_empty()),
+        status: episode.status.clone(),
+        aired: is_aired(episode, today),
+      });
+    }
+
+    offset += payload.limit.max(limit);
+    if offset >= payload.total {
+      break payload.total;
+    }
+  };
+
+  Ok(SubjectEpisodesResponse { id, total, episodes })
+}
+
+// 计算下一集的播出信息：集数、播出日期与倒计时天数。
+// 复用完整分集列表，只统计正片（episode_type = 0），按 sort 排序后取第一个未播出的。
+pub(crate) async fn get_next_episode_info_impl(id: u32) -> Result<NextEpisodeResponse, AppError> {
+  let response = get_subject_episodes_impl(id).await?;
+  let mut episodes: Vec<EpisodeItemResponse> = response
+    .episodes
+    .into_iter()
+    .filter(|episode| episode.episode_type == 0)
+    .collect();
+  episodes.sort_by(|a, b| {
+    a.sort
+      .unwrap_or(f64::MAX)
+      .partial_cmp(&b.sort.unwrap_or(f64::MAX))
+      .unwrap_or(std::cmp::Ordering::Equal)
+  });
+
+  let total_count = episodes.len() as u32;
+  let aired_count = episodes.iter().filter(|episode| episode.aired).count() as u32;
+  let next = episodes.into_iter().find(|episode| !episode.aired);
+  let today = Utc::now().date_naive();
+  let days_until = next
+    .as_ref()
+    .and_then(|episode| parse_airdate(&episode.airdate))
+    .map(|date| (date - today).num_days());
+
+  Ok(NextEpisodeResponse {
+    id,
+    aired_count,
+    total_count,
+    finished: total_count > 0 && next.is_none(),
+    next,
+    days_until,
+  })
+}
+
 // 获取“条目的筛选标签”（类型/地区/受众）。
 // 会把官方标签 + infobox 信息 + 别名统一规范化。
-pub(crate) async fn get_subject_filters_impl(id: u32) -> Result<SubjectFiltersResponse, String> {
-  let client = reqwest::Client::builder()
-    .user_agent("HanamiRIP-CN/0.1")
-    .build()
-    .map_err(|e| e.to_string())?;
-
-  let response = client
-    .get(format!("{API_BASE}{SUBJECTS_PATH}/{id}"))
-    .send()
-    .await
-    .map_err(|e| e.to_string())?;
+pub(crate) async fn get_subject_filters_impl(id: u32, force_refresh: bool) -> Result<SubjectFiltersResponse, AppError> {
+  let client = shared_client();
 
-  if !response.status().is_success() {
-    return Err(format!("Bangumi API 请求失败: {}", response.status()));
-  }
+  let raw = fetch_subject_json(&client, id, force_refresh).await?;
 
-  let payload: SubjectDetail = response.json().await.map_err(|e| e.to_string())?;
+  let payload: SubjectDetail = parse_payload(raw)?;
+  // 官方标签、别名与 infobox 字段名都来自分类表（filter-taxonomy.toml）。
+  let taxonomy = current_taxonomy();
   // 从 infobox 中提取类型/地区/受众。
-  let (info_types, info_regions, info_audiences) = extract_filter_groups(payload.infobox);
+  let (info_types, info_regions, info_audiences) = extract_filter_groups(payload.infobox, &taxonomy);
   // 收集原始标签，再把 infobox 的结果合并进去。
   let mut tags = collect_subject_tags(payload.tags, payload.meta_tags);
   tags.extend(info_types.clone());
   tags.extend(info_regions.clone());
   tags.extend(info_audiences.clone());
 
-  // 官方规范“类型列表”。
-  const OFFICIAL_TYPES: &[&str] = &[
-    "科幻",
-    "喜剧",
-    "同人",
-    "百合",
-    "校园",
-    "惊悚",
-    "后宫",
-    "机战",
-    "悬疑",
-    "恋爱",
-    "奇幻",
-    "推理",
-    "运动",
-    "耽美",
-    "音乐",
-    "战斗",
-    "冒险",
-    "萌系",
-    "穿越",
-    "玄幻",
-    "乙女",
-    "恐怖",
-    "历史",
-    "日常",
-    "剧情",
-    "武侠",
-    "美食",
-    "职场",
-  ];
-
-  // 官方规范“地区列表”。
-  const OFFICIAL_REGIONS: &[&str] = &[
-    "日本",
-    "欧美",
-    "中国",
-    "美国",
-    "韩国",
-    "法国",
-    "中国香港",
-    "英国",
-    "俄罗斯",
-    "苏联",
-    "捷克",
-    "中国台湾",
-    "马来西亚",
-  ];
-
-  // 官方规范“受众列表”。
-  const OFFICIAL_AUDIENCES: &[&str] = &[
-    "BL",
-    "GL",
-    "子供向",
-    "女性向",
-    "少女向",
-    "少年向",
-    "青年向",
-  ];
-
-  // 类型别名映射：把常见说法统一成官方标准。
-  const TYPE_ALIASES: &[(&str, &str)] = &[
-    ("搞笑", "喜剧"),
-    ("恋愛", "恋爱"),
-    ("日常系", "日常"),
-    ("熱血", "战斗"),
-    ("机甲", "机战"),
-    ("萌", "萌系"),
-  ];
-
-  // 地区别名映射。
-  const REGION_ALIASES: &[(&str, &str)] = &[
-    ("大陆", "中国"),
-    ("中国大陆", "中国"),
-    ("香港", "中国香港"),
-    ("台湾", "中国台湾"),
-    ("欧美动画", "欧美"),
-    ("欧洲", "欧美"),
-    ("俄国", "俄罗斯"),
-  ];
-
-  // 受众别名映射。
-  const AUDIENCE_ALIASES: &[(&str, &str)] = &[
-    ("少年", "少年向"),
-    ("少女", "少女向"),
-    ("青年", "青年向"),
-    ("儿童", "子供向"),
-    ("儿童向", "子供向"),
-    ("女性向", "女性向"),
-    ("男性向", "男性向"),
-    ("男向", "男性向"),
-    ("女向", "女性向"),
-    ("百合", "GL"),
-    ("耽美", "BL"),
-    ("腐向", "BL"),
-  ];
-
   // 把所有标签归一化到官方列表里。
-  let types = map_tags_to_official(&tags, OFFICIAL_TYPES, TYPE_ALIASES);
-  let regions = map_tags_to_official(&tags, OFFICIAL_REGIONS, REGION_ALIASES);
-  let audiences = map_tags_to_official(&tags, OFFICIAL_AUDIENCES, AUDIENCE_ALIASES);
+  let types = map_tags_to_official(&tags, &taxonomy.types);
+  let regions = map_tags_to_official(&tags, &taxonomy.regions);
+  let audiences = map_tags_to_official(&tags, &taxonomy.audiences);
   Ok(SubjectFiltersResponse {
     id,
     types,
@@ -536,49 +770,367 @@ pub(crate) async fn get_subject_filters_impl(id: u32) -> Result<SubjectFiltersRe
   })
 }
 
-// 获取条目的“别名列表”。
-// 别名可能来自 infobox 的多种字段（中英日等）。
-pub(crate) async fn get_subject_aliases_impl(id: u32) -> Result<SubjectAliasesResponse, String> {
-  let client = reqwest::Client::builder()
-    .user_agent("HanamiRIP-CN/0.1")
-    .build()
-    .map_err(|e| e.to_string())?;
-
-  let response = client
-    .get(format!("{API_BASE}{SUBJECTS_PATH}/{id}"))
-    .send()
-    .await
-    .map_err(|e| e.to_string())?;
+// 获取人物参与的作品（只保留 subject_type 类型，默认动画），按条目合并职位。
+pub(crate) async fn get_person_works_impl(
+  person_id: u32,
+  subject_type: Option<u32>,
+  force_refresh: bool,
+) -> Result<PersonWorksResponse, AppError> {
+  let subject_type = resolve_subject_type(subject_type)?;
+  let client = shared_client();
+
+  let request = client.get(format!("{API_BASE}{PERSONS_PATH}/{person_id}/subjects"));
+  let raw = get_json_cached(&format!("person-works-{person_id}"), force_refresh, request).await?;
+  let items: Vec<PersonSubjectItem> = parse_payload(raw)?;
+
+  // 同一作品可能出现多次（担任多个职位），按条目 ID 合并，保持接口顺序。
+  let mut works: Vec<PersonWorkItem> = Vec::new();
+  for item in items {
+    if item.subject_type.unwrap_or(ANIME_SUBJECT_TYPE) != subject_type {
+      continue;
+    }
+    let relation = item.staff.filter(|value| !value.trim().is_empty());
+    if let Some(work) = works.iter_mut().find(|work| work.id == item.id) {
+      if let Some(relation) = relation {
+        if !work.relations.contains(&relation) {
+          work.relations.push(relation);
+        }
+      }
+      continue;
+    }
+    works.push(PersonWorkItem {
+      id: item.id,
+      name: item.name.unwrap_or_default(),
+      name_cn: item.name_cn.unwrap_or_default(),
+      image: item.image.filter(|value| !value.is_empty()),
+      url: format!("https://bgm.tv/subject/{}", item.id),
+      subject_type,
+      relations: relation.into_iter().collect(),
+    });
+  }
 
+  Ok(PersonWorksResponse {
+    id: person_id,
+    url: format!("https://bgm.tv/person/{person_id}"),
+    works,
+  })
+}
+
+// 按名称查找制作公司并列出其作品。
+// 搜索结果优先取“公司”类型的人物，没有则取第一个结果。
+pub(crate) async fn get_studio_works_impl(
+  keyword: String,
+  subject_type: Option<u32>,
+  force_refresh: bool,
+) -> Result<StudioWorksResponse, AppError> {
+  let keyword = keyword.trim().to_string();
+  if keyword.is_empty() {
+    return Err(AppError::invalid_input("搜索关键词不能为空"));
+  }
+
+  let client = shared_client();
+  // 搜索接口是 POST，结果不走缓存。
+  let request = client
+    .post(format!("{API_BASE}{PERSON_SEARCH_PATH}"))
+    .query(&[("limit", "10")])
+    .json(&serde_json::json!({ "keyword": keyword }));
+  let response = send_bangumi(request).await?;
   if !response.status().is_success() {
-    return Err(format!("Bangumi API 请求失败: {}", response.status()));
+    let status = response.status();
+    return Err(AppError::api(status.as_u16(), format!("Bangumi API 请求失败: {status}")));
   }
+  let payload: PagedPerson = read_json(response).await?;
+
+  let studio = payload
+    .data
+    .iter()
+    .find(|person| person.person_type == Some(2))
+    .or_else(|| payload.data.first());
+  let (studio_id, studio_name) = match studio {
+    Some(person) => (person.id, person.name.clone()),
+    None => {
+      return Ok(StudioWorksResponse {
+        keyword,
+        studio_id: None,
+        studio_name: None,
+        works: Vec::new(),
+      })
+    }
+  };
+
+  let works = get_person_works_impl(studio_id, subject_type, force_refresh).await?.works;
+  Ok(StudioWorksResponse {
+    keyword,
+    studio_id: Some(studio_id),
+    studio_name: Some(studio_name),
+    works,
+  })
+}
 
-  let payload: SubjectDetail = response.json().await.map_err(|e| e.to_string())?;
+// 对比单个条目：简介、评分、集数与制作人员。
+// 只有第一次请求按 force_refresh 刷新，之后的请求直接复用刚写入的缓存。
+async fn compare_one(
+  client: &Client,
+  id: u32,
+  force_refresh: bool,
+) -> Result<(ComparedSubject, Vec<SubjectPersonItem>), AppError> {
+  let brief = get_subject_brief(id, force_refresh).await?;
+  let rating = get_subject_rating_detail_impl(id, false).await?;
+  let raw = fetch_subject_json(client, id, false).await?;
+  let subject: Subject = parse_payload(raw)?;
+  let persons = fetch_subject_persons(client, id, force_refresh).await?;
+
+  let staff_count = persons
+    .iter()
+    .map(|person| person.id)
+    .collect::<std::collections::HashSet<_>>()
+    .len();
+  let compared = ComparedSubject {
+    brief,
+    rating,
+    episodes: subject.eps.or(subject.total_episodes).filter(|count| *count > 0),
+    staff_count,
+  };
+  Ok((compared, persons))
+}
+
+// 对比 2~4 个条目：简介、评分、集数，以及共同参与的制作人员。
+pub(crate) async fn compare_subjects_impl(
+  ids: Vec<u32>,
+  force_refresh: bool,
+) -> Result<SubjectComparisonResponse, AppError> {
+  // 去重但保持请求顺序。
+  let mut seen = std::collections::HashSet::new();
+  let ids: Vec<u32> = ids.into_iter().filter(|id| seen.insert(*id)).collect();
+  if ids.len() < COMPARE_MIN_SUBJECTS || ids.len() > COMPARE_MAX_SUBJECTS {
+    return Err(AppError::invalid_input(format!(
+      "对比条目数量需要在 {COMPARE_MIN_SUBJECTS}~{COMPARE_MAX_SUBJECTS} 个之间"
+    )));
+  }
+
+  let client = shared_client();
+  let results: Vec<Result<(ComparedSubject, Vec<SubjectPersonItem>), AppError>> = stream::iter(ids.iter().copied())
+    .map(|id| {
+      let client = &client;
+      async move {
+        compare_one(client, id, force_refresh)
+          .await
+          .map_err(|err| err.map_message(|message| format!("条目 {id}: {message}")))
+      }
+    })
+    .buffered(COMPARE_MAX_SUBJECTS)
+    .collect()
+    .await;
+
+  // 人物 ID -> (姓名, 各条目中的职位)；职位在同一条目内去重。
+  let mut staff: std::collections::HashMap<u32, (String, Vec<StaffSubjectRoles>)> = std::collections::HashMap::new();
+  let mut subjects = Vec::new();
+  for result in results {
+    let (compared, persons) = result?;
+    let subject_id = compared.brief.id;
+    for person in persons {
+      let entry = staff
+        .entry(person.id)
+        .or_insert_with(|| (person.name.clone(), Vec::new()));
+      if !entry.1.iter().any(|roles| roles.subject_id == subject_id) {
+        entry.1.push(StaffSubjectRoles {
+          subject_id,
+          roles: Vec::new(),
+        });
+      }
+      if let Some(relation) = person.relation.filter(|value| !value.trim().is_empty()) {
+        if let Some(roles) = entry.1.iter_mut().find(|roles| roles.subject_id == subject_id) {
+          if !roles.roles.contains(&relation) {
+            roles.roles.push(relation);
+          }
+        }
+      }
+    }
+    subjects.push(compared);
+  }
+
+  let mut shared_staff: Vec<SharedStaffResponse> = staff
+    .into_iter()
+    .filter(|(_, (_, roles))| roles.len() >= 2)
+    .map(|(id, (name, roles))| SharedStaffResponse {
+      id,
+      name,
+      url: format!("https://bgm.tv/person/{id}"),
+      subjects: roles,
+    })
+    .collect();
+  // 参与条目多的在前，其余按人物 ID 排序保证输出稳定。
+  shared_staff.sort_by(|a, b| b.subjects.len().cmp(&a.subjects.len()).then(a.id.cmp(&b.id)));
+
+  Ok(SubjectComparisonResponse {
+    subjects,
+    shared_staff,
+  })
+}
+
+// 获取条目的标签列表（带标注人数），按人数从多到少排序，
+// 并标记哪些标签能归到分类表里的官方标签。
+pub(crate) async fn get_subject_tags_impl(id: u32, force_refresh: bool) -> Result<SubjectTagsResponse, AppError> {
+  let client = shared_client();
+
+  let raw = fetch_subject_json(&client, id, force_refresh).await?;
+  let payload: SubjectDetail = parse_payload(raw)?;
+  let taxonomy = current_taxonomy();
+  let groups = [
+    ("type", &taxonomy.types),
+    ("region", &taxonomy.regions),
+    ("audience", &taxonomy.audiences),
+  ];
+
+  let mut seen = std::collections::HashSet::new();
+  let mut tags: Vec<SubjectTagItem> = payload
+    .tags
+    .unwrap_or_default()
+    .into_iter()
+    .filter_map(|tag| {
+      let name = tag.name.trim().to_string();
+      if name.is_empty() || !seen.insert(name.clone()) {
+        return None;
+      }
+      let matched = groups
+        .iter()
+        .find_map(|(group, items)| official_tag_name(&name, items).map(|official| (*group, official)));
+      Some(SubjectTagItem {
+        official: matched.is_some(),
+        official_name: matched.as_ref().map(|(_, official)| official.clone()),
+        group: matched.map(|(group, _)| group.to_string()),
+        count: tag.count,
+        name,
+      })
+    })
+    .collect();
+  // 稳定排序：人数相同的保持接口原有顺序。
+  tags.sort_by_key(|tag| Reverse(tag.count));
+
+  Ok(SubjectTagsResponse { id, tags })
+}
+
+// 获取条目的完整 infobox：按接口顺序输出“字段 → 多个值”，
+// 同名字段合并、值去重，没有值的字段跳过。
+pub(crate) async fn get_subject_infobox_impl(id: u32, force_refresh: bool) -> Result<SubjectInfoboxResponse, AppError> {
+  let client = shared_client();
+
+  let raw = fetch_subject_json(&client, id, force_refresh).await?;
+  let payload: SubjectDetail = parse_payload(raw)?;
+
+  let mut items: Vec<InfoboxEntry> = Vec::new();
+  for item in payload.infobox.unwrap_or_default() {
+    let key = item.key.trim().to_string();
+    let values = extract_infobox_values(&item.value);
+    if key.is_empty() || values.is_empty() {
+      continue;
+    }
+    match items.iter_mut().find(|entry| entry.key == key) {
+      Some(entry) => entry.values.extend(values),
+      None => items.push(InfoboxEntry { key, values }),
+    }
+  }
+  for entry in items.iter_mut() {
+    entry.values = dedupe_terms(std::mem::take(&mut entry.values));
+  }
+
+  Ok(SubjectInfoboxResponse { id, items })
+}
+
+// 获取条目的“别名列表”。
+// 别名可能来自 infobox 的多种字段（中英日等）。
+pub(crate) async fn get_subject_aliases_impl(id: u32, force_refresh: bool) -> Result<SubjectAliasesResponse, AppError> {
+  let client = shared_client();
+
+  let raw = fetch_subject_json(&client, id, force_refresh).await?;
+
+  let payload: SubjectDetail = parse_payload(raw)?;
   let aliases = extract_aliases(payload.infobox);
   Ok(SubjectAliasesResponse { id, aliases })
 }
 
+// 获取条目的评分详情：分数、排名、评分人数与 1~10 分的人数分布。
+pub(crate) async fn get_subject_rating_detail_impl(
+  id: u32,
+  force_refresh: bool,
+) -> Result<SubjectRatingResponse, AppError> {
+  let client = shared_client();
+
+  let raw = fetch_subject_json(&client, id, force_refresh).await?;
+  let subject: Subject = parse_payload(raw)?;
+  let rating = subject.rating;
+
+  let counts = rating.as_ref().and_then(|value| value.count.as_ref());
+  let histogram: Vec<u32> = (1..=10)
+    .map(|score| {
+      counts
+        .and_then(|counts| counts.get(&score.to_string()))
+        .copied()
+        .unwrap_or(0)
+    })
+    .collect();
+  // 接口没有给 total 时用分布求和。
+  let total = rating
+    .as_ref()
+    .and_then(|value| value.total)
+    .unwrap_or_else(|| histogram.iter().sum());
+
+  Ok(SubjectRatingResponse {
+    id,
+    score: rating.as_ref().and_then(|value| value.score),
+    rank: rating.as_ref().and_then(|value| value.rank).filter(|rank| *rank > 0),
+    total,
+    histogram,
+  })
+}
+
+// 获取条目图片：Bangumi 封面，加上用名称与别名在 TMDB 上查到的背景图/海报/标志。
+// TMDB 失败（未配置密钥、网络错误）只记录在 tmdb_error 里，不影响封面。
+pub(crate) async fn get_subject_artwork_impl(id: u32, force_refresh: bool) -> Result<SubjectArtworkResponse, AppError> {
+  let client = shared_client();
+
+  let raw = fetch_subject_json(&client, id, force_refresh).await?;
+  let subject: Subject = parse_payload(raw.clone())?;
+  let detail: SubjectDetail = parse_payload(raw)?;
+
+  let cover = subject
+    .images
+    .clone()
+    .and_then(|images| images.large)
+    .unwrap_or_else(|| resolve_image(subject.images));
+
+  // 搜索顺序：原名、中文名，再是别名（英文名/罗马音等）。
+  let mut titles = vec![subject.name, subject.name_cn];
+  titles.extend(extract_aliases(detail.infobox));
+  titles.retain(|title| !title.trim().is_empty());
+  titles.dedup();
+
+  let (tmdb, tmdb_error) = match tmdb::lookup_artwork(id, &titles).await {
+    Ok(found) => (found, None),
+    Err(err) => (None, Some(err)),
+  };
+  Ok(SubjectArtworkResponse {
+    id,
+    cover,
+    tmdb,
+    tmdb_error,
+  })
+}
+
 // 获取并分组条目的“制作人员”。
-pub(crate) async fn get_subject_staff_impl(id: u32) -> Result<SubjectStaffResponse, String> {
-  let client = reqwest::Client::builder()
-    .user_agent("HanamiRIP-CN/0.1")
-    .build()
-    .map_err(|e| e.to_string())?;
+pub(crate) async fn get_subject_staff_impl(id: u32, force_refresh: bool) -> Result<SubjectStaffResponse, AppError> {
+  let client = shared_client();
 
-  let persons = fetch_subject_persons(&client, id).await?;
+  let persons = fetch_subject_persons(&client, id, force_refresh).await?;
   let groups = build_staff_groups(persons);
   Ok(SubjectStaffResponse { id, groups })
 }
 
 // 获取条目的“角色列表”，并转换为前端需要的结构。
-pub(crate) async fn get_subject_characters_impl(id: u32) -> Result<SubjectCharactersResponse, String> {
-  let client = reqwest::Client::builder()
-    .user_agent("HanamiRIP-CN/0.1")
-    .build()
-    .map_err(|e| e.to_string())?;
+pub(crate) async fn get_subject_characters_impl(id: u32, force_refresh: bool) -> Result<SubjectCharactersResponse, AppError> {
+  let client = shared_client();
 
-  let characters = fetch_subject_characters(&client, id).await?;
+  let characters = fetch_subject_characters(&client, id, force_refresh).await?;
   // 映射角色名称：优先中文名，没有就用原名。
   let mapped = characters
     .into_iter()
@@ -596,37 +1148,208 @@ pub(crate) async fn get_subject_characters_impl(id: u32) -> Result<SubjectCharac
   })
 }
 
-// 获取“某年某季”的番剧列表。
-// 会先把季节转换成月份，再逐月拉取并汇总。
+// 获取关联条目（续集、前传、番外篇等），按关系类型分组。
+// 常见关系按固定顺序排在前面，其余关系按名称排序，保证输出稳定。
+pub(crate) async fn get_subject_relations_impl(
+  id: u32,
+  force_refresh: bool,
+) -> Result<SubjectRelationsResponse, AppError> {
+  let client = shared_client();
+
+  let request = client.get(format!("{API_BASE}{SUBJECTS_PATH}/{id}/subjects"));
+  let raw = get_json_cached(&format!("relations-{id}"), force_refresh, request).await?;
+  let items: Vec<RelatedSubjectItem> = parse_payload(raw)?;
+
+  // 按关系名称分组，缺少关系名的归入“其他”。
+  let mut grouped: std::collections::HashMap<String, Vec<RelatedSubjectResponse>> = std::collections::HashMap::new();
+  for item in items {
+    let relation = item
+      .relation
+      .filter(|value| !value.trim().is_empty())
+      .unwrap_or_else(|| "其他".to_string());
+    grouped.entry(relation).or_default().push(RelatedSubjectResponse {
+      id: item.id,
+      subject_type: item.subject_type,
+      name_cn: item.name_cn.unwrap_or_default(),
+      name: item.name,
+      image: resolve_image(item.images),
+      url: format!("https://bgm.tv/subject/{}", item.id),
+    });
+  }
+
+  // 同一系列里最常用的关系排在前面。
+  let ordered_relations = [
+    "前传", "续集", "总集篇", "番外篇", "主线故事", "全集", "不同演绎", "相同世界观", "衍生", "改编",
+  ];
+  let mut groups = Vec::new();
+  for relation in ordered_relations {
+    if let Some(subjects) = grouped.remove(relation) {
+      groups.push(RelationGroupResponse {
+        relation: relation.to_string(),
+        subjects,
+      });
+    }
+  }
+  let mut remaining: Vec<_> = grouped.into_iter().collect();
+  remaining.sort_by(|a, b| a.0.cmp(&b.0));
+  for (relation, subjects) in remaining {
+    groups.push(RelationGroupResponse { relation, subjects });
+  }
+
+  Ok(SubjectRelationsResponse { id, groups })
+}
+
+// 获取角色详情：简介、图片、性别，以及声优列表。
+// 声优接口按“声优 × 条目”返回，这里按声优合并，条目列在 subjects 里。
+pub(crate) async fn get_character_detail_impl(
+  id: u32,
+  force_refresh: bool,
+) -> Result<CharacterDetailResponse, AppError> {
+  let client = shared_client();
+
+  let request = client.get(format!("{API_BASE}{CHARACTERS_PATH}/{id}"));
+  let raw = get_json_cached(&format!("character-{id}"), force_refresh, request).await?;
+  let detail: CharacterDetail = parse_payload(raw)?;
+
+  let request = client.get(format!("{API_BASE}{CHARACTERS_PATH}/{id}/persons"));
+  let raw = get_json_cached(&format!("character-persons-{id}"), force_refresh, request).await?;
+  let persons: Vec<CharacterPersonItem> = parse_payload(raw)?;
+
+  // 按声优合并，保持接口返回的先后顺序。
+  let mut actors: Vec<CharacterActorResponse> = Vec::new();
+  for person in persons {
+    let subject = person.subject_id.map(|subject_id| CharacterActorSubject {
+      id: subject_id,
+      name: person.subject_name.clone().unwrap_or_default(),
+      name_cn: person.subject_name_cn.clone().unwrap_or_default(),
+    });
+    match actors.iter_mut().find(|actor| actor.id == person.id) {
+      Some(actor) => actor.subjects.extend(subject),
+      None => actors.push(CharacterActorResponse {
+        id: person.id,
+        url: format!("https://bgm.tv/person/{}", person.id),
+        image: person.images.map(|images| resolve_image(Some(images))),
+        name: person.name,
+        subjects: subject.into_iter().collect(),
+      }),
+    }
+  }
+
+  // 中文名在 infobox 的“简体中文名”里。
+  let name_cn = detail.infobox.and_then(|items| {
+    items
+      .into_iter()
+      .find(|item| item.key == "简体中文名")
+      .and_then(|item| item.value.as_str().map(|value| value.trim().to_string()))
+      .filter(|value| !value.is_empty())
+  });
+
+  Ok(CharacterDetailResponse {
+    id: detail.id,
+    name: detail.name,
+    name_cn,
+    summary: detail.summary.unwrap_or_default(),
+    gender: detail.gender.filter(|value| !value.is_empty()),
+    image: detail.images.map(|images| resolve_image(Some(images))),
+    url: format!("https://bgm.tv/character/{}", detail.id),
+    actors,
+  })
+}
+
+// 获取“某年某季”的条目列表（默认动画，也可以浏览书籍/音乐/游戏/三次元）。
+// 拉取成功后把整季结果保存为快照；拉取失败时（非强制刷新）退回最近一次的快照，
+// 并标记 stale = true，fetched_at 保持快照的原始时间。
 pub(crate) async fn get_season_subjects_impl(
   year: u32,
   season: String,
-) -> Result<SeasonResponse, String> {
-  // 如果季节字符串不合法，这里会直接返回错误。
+  subject_type: Option<u32>,
+  force_refresh: bool,
+) -> Result<SeasonResponse, AppError> {
+  // 如果季节字符串或条目类型不合法，这里会直接返回错误（不走快照）。
+  season_months(&season)?;
+  let subject_type = resolve_subject_type(subject_type)?;
+  let key = format!("season-{subject_type}-{year}-{season}");
+
+  match fetch_season_subjects(year, season, subject_type, force_refresh).await {
+    Ok(response) => {
+      // 只有完整结果才覆盖快照，避免部分失败时丢掉之前完整的数据。
+      if response.errors.is_empty() {
+        save_snapshot(&key, &response);
+      }
+      Ok(response)
+    }
+    Err(err) if !force_refresh => match load_snapshot::<SeasonResponse>(&key) {
+      Some(mut snapshot) => {
+        snapshot.stale = true;
+        Ok(snapshot)
+      }
+      None => Err(err),
+    },
+    Err(err) => Err(err),
+  }
+}
+
+// 在线拉取“某年某季”的条目列表。
+// 会先把季节转换成月份，再同时拉取三个月并汇总。
+// 单个月失败时记录到 errors 并返回其它月份；三个月都失败才返回错误。
+async fn fetch_season_subjects(
+  year: u32,
+  season: String,
+  subject_type: u32,
+  force_refresh: bool,
+) -> Result<SeasonResponse, AppError> {
   let months = season_months(&season)?;
-  let client = reqwest::Client::builder()
-    .user_agent("HanamiRIP-CN/0.1")
-    .build()
-    .map_err(|e| e.to_string())?;
+  let client = shared_client();
 
-  // month_payloads 用于保存每个月的结果。
+  // 三个月同时拉取（并发量仍受 send_bangumi 的限流约束）。
+  let client = &client;
+  let fetch = move |month: u32| async move {
+    (month, fetch_month_subjects(client, subject_type, year, month, force_refresh).await)
+  };
+  let (first, second, third) = join3(fetch(months[0]), fetch(months[1]), fetch(months[2])).await;
+
+  // month_payloads 用于保存每个月的结果，errors 记录失败的月份。
+  // first_error 保留第一个错误的类别，三个月都失败时沿用它。
   let mut month_payloads = Vec::new();
-  for month in months {
-    let list = fetch_month_subjects(&client, year, month).await?;
-    let count = list.len();
-    month_payloads.push(SeasonMonth {
-      year,
-      month,
-      count,
-      list,
-    });
+  let mut errors = Vec::new();
+  let mut first_error = None;
+  for (month, result) in [first, second, third] {
+    match result {
+      Ok(list) => {
+        let count = list.len();
+        month_payloads.push(SeasonMonth {
+          year,
+          month,
+          count,
+          list,
+        });
+      }
+      Err(error) => {
+        errors.push(SeasonMonthError {
+          month,
+          error: error.to_string(),
+        });
+        first_error.get_or_insert(error);
+      }
+    }
+  }
+  if let (true, Some(error)) = (month_payloads.is_empty(), first_error) {
+    let message = errors
+      .into_iter()
+      .map(|item| format!("{} 月: {}", item.month, item.error))
+      .collect::<Vec<_>>()
+      .join("; ");
+    return Err(error.map_message(|_| message));
   }
 
   Ok(SeasonResponse {
     year,
     season,
+    subject_type,
     fetched_at: Utc::now().to_rfc3339(),
     source: format!("{API_BASE}{SUBJECTS_PATH}"),
     months: month_payloads,
+    errors,
+    stale: false,
   })
 }
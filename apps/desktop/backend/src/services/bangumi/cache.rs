@@ -0,0 +1,280 @@
+/*
+  Bangumi 响应的磁盘缓存：
+  把条目详情、制作人员、角色、季度列表等接口的原始 JSON 写到数据目录，
+  在有效期（TTL）内再次请求时直接读取本地文件，避免反复请求 api.bgm.tv。
+
+  - 缓存的是接口原始响应，解析逻辑与不走缓存时完全一致；
+  - force_refresh 为 true 时跳过缓存，请求成功后覆盖旧文件；
+  - 网络请求失败时，如果本地有过期缓存，则退回使用过期数据（离线也能浏览）；
+  - 缓存会记录响应的 ETag / Last-Modified，重新请求时带上条件请求头，
+    服务端返回 304 时直接沿用本地数据，只刷新写入时间；
+  - TTL 为 0 表示关闭缓存。
+
+  另外提供“快照”：整份响应按 key 保存在 snapshots/ 下，不受 TTL 影响，
+  只在接口完全不可用时作为离线数据使用（例如季度列表）。
+
+  以及“翻译缓存”：简介、分集标题的中文译文按 种类 + ID + 原文哈希 保存在 translations/ 下，
+  原文不变就一直复用，避免每次打开详情页都消耗翻译额度。
+*/
+
+use chrono::Utc;
+use once_cell::sync::OnceCell;
+use reqwest::header::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::services::checksum::fnv1a;
+use crate::services::diagnostics::app_log;
+use crate::services::error::AppError;
+use crate::services::settings::CacheSettings;
+
+use super::request::{read_json, send_bangumi};
+
+// 缓存目录（应用数据目录 / cache / bangumi），启动时设置。
+static CACHE_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+// 当前缓存有效期（秒），初始取默认设置。
+static TTL_SECS: AtomicU64 = AtomicU64::new(CacheSettings::DEFAULT_TTL_MINUTES * 60);
+
+// 单个缓存文件的内容。
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+  /// 写入时间（Unix 秒）。
+  stored_at: i64,
+  payload: Value,
+  /// 响应的 ETag（旧版本缓存文件里没有）。
+  #[serde(default)]
+  etag: Option<String>,
+  /// 响应的 Last-Modified。
+  #[serde(default)]
+  last_modified: Option<String>,
+}
+
+// 条件请求的结果。
+enum Fetched {
+  /// 服务端返回了新数据（附带新的校验信息）。
+  Fresh {
+    payload: Value,
+    etag: Option<String>,
+    last_modified: Option<String>,
+  },
+  /// 304：本地缓存仍然有效。
+  NotModified,
+}
+
+// 记录缓存目录（由 main.rs 在启动时调用）。
+pub(crate) fn init_cache_dir(dir: PathBuf) {
+  let _ = CACHE_DIR.set(dir);
+}
+
+// 应用新的缓存设置（启动时与保存设置时调用）。
+pub(crate) fn apply_cache_settings(settings: &CacheSettings) {
+  TTL_SECS.store(settings.bangumi_ttl_minutes.saturating_mul(60), Ordering::Relaxed);
+}
+
+// 缓存文件路径；缓存目录未初始化时返回 None。
+fn entry_path(key: &str) -> Option<PathBuf> {
+  CACHE_DIR.get().map(|dir| dir.join(format!("{key}.json")))
+}
+
+// 读取缓存条目（不判断是否过期）。
+fn read_entry(key: &str) -> Option<CacheEntry> {
+  let content = fs::read_to_string(entry_path(key)?).ok()?;
+  serde_json::from_str(&content).ok()
+}
+
+// 写入缓存条目；写入失败只影响下次是否命中，所以只打印日志。
+fn write_entry(key: &str, entry: &CacheEntry) {
+  let path = match entry_path(key) {
+    Some(path) => path,
+    None => return,
+  };
+  let result = path
+    .parent()
+    .map(fs::create_dir_all)
+    .unwrap_or(Ok(()))
+    .and_then(|_| fs::write(&path, serde_json::to_vec(entry).unwrap_or_default()));
+  if let Err(err) = result {
+    app_log!("写入 Bangumi 缓存失败 ({key}): {err}");
+  }
+}
+
+// 快照文件路径；缓存目录未初始化时返回 None。
+fn snapshot_path(key: &str) -> Option<PathBuf> {
+  CACHE_DIR
+    .get()
+    .map(|dir| dir.join("snapshots").join(format!("{key}.json")))
+}
+
+// 保存快照（覆盖旧文件）；写入失败只打印日志。
+pub(crate) fn save_snapshot<T: Serialize>(key: &str, value: &T) {
+  let path = match snapshot_path(key) {
+    Some(path) => path,
+    None => return,
+  };
+  let result = serde_json::to_vec(value)
+    .map_err(|e| e.to_string())
+    .and_then(|bytes| {
+      path
+        .parent()
+        .map(fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .and_then(|_| fs::write(&path, bytes))
+        .map_err(|e| e.to_string())
+    });
+  if let Err(err) = result {
+    app_log!("写入快照失败 ({key}): {err}");
+  }
+}
+
+// 读取快照；不存在或格式不对时返回 None。
+pub(crate) fn load_snapshot<T: DeserializeOwned>(key: &str) -> Option<T> {
+  let content = fs::read_to_string(snapshot_path(key)?).ok()?;
+  serde_json::from_str(&content).ok()
+}
+
+// 翻译缓存文件路径；缓存目录未初始化时返回 None。
+// kind 区分译文种类（"summary" 为条目简介，"episode" 为分集标题），id 为对应的条目/分集 ID。
+fn translation_path(kind: &str, id: u32, source: &str) -> Option<PathBuf> {
+  CACHE_DIR.get().map(|dir| {
+    dir
+      .join("translations")
+      .join(format!("{kind}-{id}-{:016x}.txt", fnv1a(source.as_bytes())))
+  })
+}
+
+// 读取缓存译文；原文变化（哈希不同）时视为未缓存。
+pub(crate) fn load_translation(kind: &str, id: u32, source: &str) -> Option<String> {
+  let text = fs::read_to_string(translation_path(kind, id, source)?).ok()?;
+  Some(text).filter(|text| !text.trim().is_empty())
+}
+
+// 保存译文；写入失败只打印日志。
+pub(crate) fn save_translation(kind: &str, id: u32, source: &str, text: &str) {
+  let path = match translation_path(kind, id, source) {
+    Some(path) => path,
+    None => return,
+  };
+  let result = path
+    .parent()
+    .map(fs::create_dir_all)
+    .unwrap_or(Ok(()))
+    .and_then(|_| fs::write(&path, text));
+  if let Err(err) = result {
+    app_log!("写入翻译缓存失败 ({kind}-{id}): {err}");
+  }
+}
+
+// 读取响应头的字符串值。
+fn header_value(response: &Response, name: HeaderName) -> Option<String> {
+  response
+    .headers()
+    .get(name)
+    .and_then(|value| value.to_str().ok())
+    .map(str::to_string)
+}
+
+// 判断缓存条目是否仍在有效期内。
+fn is_fresh(entry: &CacheEntry, ttl_secs: u64) -> bool {
+  let age = Utc::now().timestamp().saturating_sub(entry.stored_at);
+  age >= 0 && (age as u64) < ttl_secs
+}
+
+// 带缓存地请求 JSON 接口。
+// key 只能包含文件名安全的字符（如 "subject-123"），由调用方保证。
+pub(crate) async fn get_json_cached(
+  key: &str,
+  force_refresh: bool,
+  request: RequestBuilder,
+) -> Result<Value, AppError> {
+  get_json_cached_within(key, force_refresh, request, u64::MAX).await
+}
+
+// 同 get_json_cached，但有效期不超过 max_ttl_secs（用于变化较快的数据）。
+// 设置里关闭缓存（有效期为 0）时同样不缓存。
+pub(crate) async fn get_json_cached_within(
+  key: &str,
+  force_refresh: bool,
+  request: RequestBuilder,
+  max_ttl_secs: u64,
+) -> Result<Value, AppError> {
+  let ttl_secs = TTL_SECS.load(Ordering::Relaxed).min(max_ttl_secs);
+  let cached = if ttl_secs > 0 { read_entry(key) } else { None };
+  if !force_refresh {
+    if let Some(entry) = cached.as_ref().filter(|entry| is_fresh(entry, ttl_secs)) {
+      return Ok(entry.payload.clone());
+    }
+  }
+
+  // 有本地缓存时带上条件请求头（强制刷新也带：304 同样说明数据没变）。
+  let mut request = request;
+  if let Some(entry) = cached.as_ref() {
+    if let Some(etag) = entry.etag.as_deref() {
+      request = request.header(IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = entry.last_modified.as_deref() {
+      request = request.header(IF_MODIFIED_SINCE, last_modified);
+    }
+  }
+
+  let fetched = async {
+    let response = send_bangumi(request).await?;
+    if response.status() == StatusCode::NOT_MODIFIED {
+      return Ok(Fetched::NotModified);
+    }
+    if !response.status().is_success() {
+      let status = response.status();
+      return Err(AppError::api(status.as_u16(), format!("Bangumi API 请求失败: {status}")));
+    }
+    let etag = header_value(&response, ETAG);
+    let last_modified = header_value(&response, LAST_MODIFIED);
+    let payload = read_json::<Value>(response).await?;
+    Ok(Fetched::Fresh {
+      payload,
+      etag,
+      last_modified,
+    })
+  }
+  .await;
+
+  match fetched {
+    Ok(Fetched::Fresh {
+      payload,
+      etag,
+      last_modified,
+    }) => {
+      if ttl_secs > 0 {
+        write_entry(
+          key,
+          &CacheEntry {
+            stored_at: Utc::now().timestamp(),
+            payload: payload.clone(),
+            etag,
+            last_modified,
+          },
+        );
+      }
+      Ok(payload)
+    }
+    // 只有带了条件请求头才会收到 304，此时 cached 一定存在；刷新写入时间后沿用本地数据。
+    Ok(Fetched::NotModified) => match cached {
+      Some(mut entry) => {
+        entry.stored_at = Utc::now().timestamp();
+        write_entry(key, &entry);
+        Ok(entry.payload)
+      }
+      None => Err(AppError::api(304, "Bangumi API 返回 304，但本地没有缓存")),
+    },
+    // 请求失败时退回使用过期缓存；强制刷新时调用方需要知道刷新失败，直接报错。
+    Err(err) => match cached {
+      Some(entry) if !force_refresh => Ok(entry.payload),
+      _ => Err(err),
+    },
+  }
+}
@@ -0,0 +1,63 @@
+/*
+  共享 HTTP 客户端：
+  reqwest::Client 内部自带连接池，克隆只是增加引用计数。
+  所有 Bangumi / 翻译 / 搜索请求都复用同一个实例，
+  避免每次请求都重新握手 TLS、丢掉已建立的连接。
+
+  超时时间来自设置模块的 NetworkSettings，代理来自设置里的代理地址与账号；
+  修改设置时整体换一个新客户端，正在进行的请求继续使用旧客户端，不受影响。
+*/
+
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::services::diagnostics::app_log;
+use crate::services::settings::AppSettings;
+
+// 统一的 user_agent。
+const USER_AGENT: &str = "HanamiRIP-CN/0.1";
+
+// 当前使用的客户端，初始按默认设置构建。
+static CLIENT: Lazy<RwLock<Client>> = Lazy::new(|| {
+  let client = build_client(&AppSettings::default()).unwrap_or_else(|err| {
+    app_log!("{err}");
+    Client::new()
+  });
+  RwLock::new(client)
+});
+
+// 按网络设置构建客户端。
+// 未配置代理时沿用 reqwest 的默认行为（读取系统环境变量中的代理）。
+fn build_client(settings: &AppSettings) -> Result<Client, String> {
+  let network = &settings.network;
+  let mut builder = Client::builder()
+    .user_agent(USER_AGENT)
+    .connect_timeout(Duration::from_secs(network.connect_timeout_secs))
+    .timeout(Duration::from_secs(network.request_timeout_secs))
+    .pool_idle_timeout(Duration::from_secs(90));
+  if let Some(proxy) = settings.proxy()? {
+    builder = builder.proxy(proxy);
+  }
+  builder
+    .build()
+    .map_err(|e| format!("创建 HTTP 客户端失败: {e}"))
+}
+
+// 应用新的网络设置（启动时与保存设置时调用）。
+// 构建失败时保留原来的客户端。
+pub(crate) fn apply_network_settings(settings: &AppSettings) {
+  match build_client(settings) {
+    Ok(client) => {
+      // 锁中毒只说明之前某个线程 panic 了，里面的数据仍然可用。
+      *CLIENT.write().unwrap_or_else(|e| e.into_inner()) = client;
+    }
+    Err(err) => app_log!("{err}"),
+  }
+}
+
+// 取得共享客户端：复用连接池与 TLS 会话，克隆开销很小，可以随用随取。
+pub(crate) fn shared_client() -> Client {
+  CLIENT.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
@@ -4,92 +4,254 @@
   简单理解：这里是“前端到后端”的桥梁。
 */
 
-// HTTP 客户端，用于少量需要直接请求的命令。
-use reqwest::Client;
+use std::collections::HashMap;
+
+// 命令统一返回的错误类型。
+use crate::services::error::AppError;
+
+// 共享的 HTTP 客户端，用于少量需要直接请求的命令。
+use super::client::shared_client;
+
+// Bangumi 专用的限流重试请求发送函数。
+use super::request::send_bangumi;
+// 简介译文的本地缓存。
+use super::cache::{load_translation, save_translation};
 
 // 这些是实际业务实现（真正干活的函数）。
 // commands 里只是“转发/包装”。
 use super::api::{
+  compare_subjects_impl,
+  get_character_detail_impl,
+  get_person_works_impl,
+  get_studio_works_impl,
+  get_ranked_subjects_impl,
+  get_subject_artwork_impl,
+  get_subject_rating_detail_impl,
   get_season_subjects_impl,
   get_subject_aired_count_impl,
   get_subject_aliases_impl,
   get_subject_brief as get_subject_brief_impl,
+  get_subject_briefs_impl,
   get_subject_characters_impl,
+  get_subject_episodes_impl,
+  get_next_episode_info_impl,
   get_subject_filters_impl,
+  get_subject_infobox_impl,
   get_subject_origin_impl,
+  get_subject_relations_impl,
   get_subject_staff_impl,
+  get_subject_tags_impl,
+  search_subjects_impl,
 };
 
+// 筛选分类表的重新加载入口。
+use super::taxonomy::{reload_taxonomy, FilterTaxonomyStatus};
+
 // 对外返回的数据结构。
 use super::models::{
+  CharacterDetailResponse,
+  PersonWorksResponse,
+  RankedSubjectsResponse,
+  StudioWorksResponse,
+  SubjectComparisonResponse,
+  SearchSubjectsResponse,
   SeasonResponse,
   SubjectAiredResponse,
   SubjectAliasesResponse,
+  SubjectArtworkResponse,
+  SubjectRatingResponse,
   SubjectBriefResponse,
+  SubjectBriefsResponse,
   SubjectCharactersResponse,
+  SubjectEpisodesResponse,
+  EpisodeTitleItem,
+  EpisodeTitlesResponse,
+  NextEpisodeResponse,
   SubjectFiltersResponse,
+  SubjectInfoboxResponse,
   SubjectOriginResponse,
+  SubjectRelationsResponse,
   SubjectStaffResponse,
+  SubjectTagsResponse,
   SubjectSummaryResponse,
+  SyncConflictStrategy,
+  SyncReport,
+  TranslateBatchItem,
+  TranslateBatchResponse,
+  TranslationCredentialsStatus,
+  TranslationTestResponse,
 };
 
+// 追番列表与 bgm.tv 收藏同步。
+use super::sync::{pull_collections, push_collections};
+
 // 文本语言判断与翻译工具。
-use super::translate::{is_chinese_text, translate_to_cn_baidu};
+use super::translate::{
+  add_glossary_entry,
+  cache_source,
+  convert_chinese_text,
+  detect_language,
+  is_chinese_text,
+  is_traditional_chinese,
+  list_glossary,
+  obfuscate_plain_credential,
+  remove_glossary_entry,
+  translate_batch_to_cn,
+  translate_to_cn,
+  to_simplified,
+  translate_with,
+  write_translation_credentials,
+  GlossaryEntry,
+};
+
+// 测试翻译服务密钥时使用的文本。
+const TRANSLATION_TEST_TEXT: &str = "今日はいい天気ですね。";
 
 // 下面的函数带有 #[tauri::command]，表示它们能被前端调用。
 // 每个命令都尽量保持“薄封装”：不做复杂逻辑，直接调用实现层。
+// 带 force_refresh 的命令默认读取磁盘缓存，传 true 时跳过缓存重新请求。
 
 #[tauri::command]
-pub async fn get_subject_origin(id: u32) -> Result<SubjectOriginResponse, String> {
-  get_subject_origin_impl(id).await
+pub async fn get_subject_origin(id: u32, force_refresh: Option<bool>) -> Result<SubjectOriginResponse, AppError> {
+  get_subject_origin_impl(id, force_refresh.unwrap_or(false)).await
 }
 
 #[tauri::command]
-pub async fn get_subject_aired_count(id: u32) -> Result<SubjectAiredResponse, String> {
+pub async fn get_subject_aired_count(id: u32) -> Result<SubjectAiredResponse, AppError> {
   get_subject_aired_count_impl(id).await
 }
 
 #[tauri::command]
-pub async fn get_subject_filters(id: u32) -> Result<SubjectFiltersResponse, String> {
-  get_subject_filters_impl(id).await
+pub async fn get_subject_filters(id: u32, force_refresh: Option<bool>) -> Result<SubjectFiltersResponse, AppError> {
+  get_subject_filters_impl(id, force_refresh.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn get_subject_aliases(id: u32, force_refresh: Option<bool>) -> Result<SubjectAliasesResponse, AppError> {
+  get_subject_aliases_impl(id, force_refresh.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn get_subject_staff(id: u32, force_refresh: Option<bool>) -> Result<SubjectStaffResponse, AppError> {
+  get_subject_staff_impl(id, force_refresh.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn get_subject_characters(id: u32, force_refresh: Option<bool>) -> Result<SubjectCharactersResponse, AppError> {
+  get_subject_characters_impl(id, force_refresh.unwrap_or(false)).await
+}
+
+// 获取人物（导演、声优等）参与的其它作品；subject_type 不传时只列动画。
+#[tauri::command]
+pub async fn get_person_works(
+  person_id: u32,
+  subject_type: Option<u32>,
+  force_refresh: Option<bool>,
+) -> Result<PersonWorksResponse, AppError> {
+  get_person_works_impl(person_id, subject_type, force_refresh.unwrap_or(false)).await
+}
+
+// 按名称查找制作公司并列出其作品；subject_type 不传时只列动画。
+#[tauri::command]
+pub async fn get_studio_works(
+  keyword: String,
+  subject_type: Option<u32>,
+  force_refresh: Option<bool>,
+) -> Result<StudioWorksResponse, AppError> {
+  get_studio_works_impl(keyword, subject_type, force_refresh.unwrap_or(false)).await
+}
+
+// 对比 2~4 个条目（简介、评分、集数、共同制作人员）。
+#[tauri::command]
+pub async fn compare_subjects(
+  ids: Vec<u32>,
+  force_refresh: Option<bool>,
+) -> Result<SubjectComparisonResponse, AppError> {
+  compare_subjects_impl(ids, force_refresh.unwrap_or(false)).await
+}
+
+// 获取条目的完整 infobox（有序的“字段 → 多个值”）。
+#[tauri::command]
+pub async fn get_subject_infobox(id: u32, force_refresh: Option<bool>) -> Result<SubjectInfoboxResponse, AppError> {
+  get_subject_infobox_impl(id, force_refresh.unwrap_or(false)).await
 }
 
+// 获取条目标签（带人数，按热度排序，标记是否为官方分类标签）。
 #[tauri::command]
-pub async fn get_subject_aliases(id: u32) -> Result<SubjectAliasesResponse, String> {
-  get_subject_aliases_impl(id).await
+pub async fn get_subject_tags(id: u32, force_refresh: Option<bool>) -> Result<SubjectTagsResponse, AppError> {
+  get_subject_tags_impl(id, force_refresh.unwrap_or(false)).await
 }
 
+// 立即重新加载筛选分类表（filter-taxonomy.toml），返回加载结果。
+// 文件修改后本来就会自动生效，这个命令用于手动刷新并查看解析错误。
 #[tauri::command]
-pub async fn get_subject_staff(id: u32) -> Result<SubjectStaffResponse, String> {
-  get_subject_staff_impl(id).await
+pub fn reload_filter_taxonomy() -> FilterTaxonomyStatus {
+  reload_taxonomy()
 }
 
+// 获取评分详情（分数、排名、评分人数、1~10 分分布）。
 #[tauri::command]
-pub async fn get_subject_characters(id: u32) -> Result<SubjectCharactersResponse, String> {
-  get_subject_characters_impl(id).await
+pub async fn get_subject_rating_detail(
+  id: u32,
+  force_refresh: Option<bool>,
+) -> Result<SubjectRatingResponse, AppError> {
+  get_subject_rating_detail_impl(id, force_refresh.unwrap_or(false)).await
+}
+
+// 获取条目图片（Bangumi 封面 + TMDB 背景图/海报/标志）。
+#[tauri::command]
+pub async fn get_subject_artwork(
+  id: u32,
+  force_refresh: Option<bool>,
+) -> Result<SubjectArtworkResponse, AppError> {
+  get_subject_artwork_impl(id, force_refresh.unwrap_or(false)).await
+}
+
+// 获取角色详情（简介、图片、性别、声优列表）。
+#[tauri::command]
+pub async fn get_character_detail(
+  id: u32,
+  force_refresh: Option<bool>,
+) -> Result<CharacterDetailResponse, AppError> {
+  get_character_detail_impl(id, force_refresh.unwrap_or(false)).await
+}
+
+// 获取关联条目（续集、前传、番外篇等），用于系列视图。
+#[tauri::command]
+pub async fn get_subject_relations(
+  id: u32,
+  force_refresh: Option<bool>,
+) -> Result<SubjectRelationsResponse, AppError> {
+  get_subject_relations_impl(id, force_refresh.unwrap_or(false)).await
+}
+
+// 获取完整分集列表（用于分集清单）。
+#[tauri::command]
+pub async fn get_subject_episodes(id: u32) -> Result<SubjectEpisodesResponse, AppError> {
+  get_subject_episodes_impl(id).await
+}
+
+// 获取下一集的播出信息（集数、播出日期与倒计时）。
+#[tauri::command]
+pub async fn get_next_episode_info(id: u32) -> Result<NextEpisodeResponse, AppError> {
+  get_next_episode_info_impl(id).await
 }
 
 // 获取条目简介的中文摘要：
 // - 如果传入摘要为空，则先从 Bangumi 获取原始摘要；
-// - 如果摘要已经是中文，就直接返回；
-// - 否则调用百度翻译，得到中文结果。
+// - 如果摘要已经是中文，就直接返回（繁体会先在本地转换成简体）；
+// - 同一段原文之前翻译过，直接返回缓存的译文；
+// - 否则按设置的顺序调用翻译服务，得到中文结果并写入缓存。
 #[tauri::command]
-pub async fn get_subject_summary_cn(id: u32, summary: String) -> Result<SubjectSummaryResponse, String> {
-  // 构建 HTTP 客户端。
-  let client = reqwest::Client::builder()
-    .user_agent("HanamiRIP-CN/0.1")
-    .build()
-    .map_err(|e| e.to_string())?;
+pub async fn get_subject_summary_cn(id: u32, summary: String) -> Result<SubjectSummaryResponse, AppError> {
+  let client = shared_client();
 
   // raw 是“待处理的摘要”。
   let mut raw = summary;
   if raw.trim().is_empty() {
     // 如果前端没有传摘要，就从 Bangumi 获取。
-    let response = client
-      .get(format!("https://api.bgm.tv/v0/subjects/{id}"))
-      .send()
-      .await
-      .map_err(|e| e.to_string())?;
+    let request = client.get(format!("https://api.bgm.tv/v0/subjects/{id}"));
+    let response = send_bangumi(request).await?;
 
     // 只有请求成功才尝试解析 JSON。
     if response.status().is_success() {
@@ -105,22 +267,42 @@ pub async fn get_subject_summary_cn(id: u32, summary: String) -> Result<SubjectS
       id,
       summary: "".to_string(),
       translated: false,
+      converted: false,
+      source_language: None,
       error: None,
     });
   }
 
-  // 如果已经是中文，就不翻译，避免多余请求。
-  if is_chinese_text(&raw) {
+  // 识别原文语言（随结果一起返回，前端可显示“译自日语”等）。
+  let source_language = detect_language(&raw);
+
+  // 如果已经是中文，就不翻译，避免多余请求；繁体在本地转换成简体。
+  if source_language.as_ref().is_some_and(|language| language.code == "zh") {
+    let converted = is_traditional_chinese(&raw);
     return Ok(SubjectSummaryResponse {
       id,
-      summary: raw,
+      summary: if converted { to_simplified(&raw) } else { raw },
       translated: false,
+      converted,
+      source_language,
+      error: None,
+    });
+  }
+
+  // 原文没变就直接用缓存的译文，不再消耗翻译额度。
+  if let Some(cached) = load_translation("summary", id, &cache_source(&raw)) {
+    return Ok(SubjectSummaryResponse {
+      id,
+      summary: cached,
+      translated: true,
+      converted: false,
+      source_language,
       error: None,
     });
   }
 
   // 非中文则尝试翻译。
-  match translate_to_cn_baidu(&client, &raw).await {
+  match translate_to_cn(&client, &raw).await {
     Ok(result) => {
       // 去掉首尾空白，避免“看起来有内容但其实空”。
       let normalized = result.trim().to_string();
@@ -130,14 +312,19 @@ pub async fn get_subject_summary_cn(id: u32, summary: String) -> Result<SubjectS
           id,
           summary: raw,
           translated: false,
+          converted: false,
+          source_language,
           error: Some("翻译失败或未产出中文结果".to_string()),
         })
       } else {
         // 翻译成功。
+        save_translation("summary", id, &cache_source(&raw), &normalized);
         Ok(SubjectSummaryResponse {
           id,
           summary: normalized,
           translated: true,
+          converted: false,
+          source_language,
           error: None,
         })
       }
@@ -147,36 +334,257 @@ pub async fn get_subject_summary_cn(id: u32, summary: String) -> Result<SubjectS
       id,
       summary: raw,
       translated: false,
+      converted: false,
+      source_language,
       error: Some(error),
     }),
   }
 }
 
+// 把本地追番列表推送到 bgm.tv 收藏。
+// 状态冲突默认以本地为准（strategy 可选 prefer-local / prefer-remote / skip）。
 #[tauri::command]
-pub async fn get_subject_brief(id: u32) -> Result<SubjectBriefResponse, String> {
-  get_subject_brief_impl(id).await
+pub async fn sync_collections_push(
+  app: tauri::AppHandle,
+  strategy: Option<SyncConflictStrategy>,
+) -> Result<SyncReport, AppError> {
+  push_collections(&app, strategy.unwrap_or(SyncConflictStrategy::PreferLocal)).await
 }
 
-// 获取搜索站点的 HTML 原文，通常用于爬取/解析。
+// 从 bgm.tv 收藏拉取到本地追番列表。
+// 状态冲突默认以 bgm.tv 为准。
 #[tauri::command]
-pub async fn fetch_search_html(url: String) -> Result<String, String> {
-  // 构建 HTTP 客户端。
-  let client = Client::builder()
-    .user_agent("HanamiRIP-CN/0.1")
-    .build()
-    .map_err(|e| e.to_string())?;
+pub async fn sync_collections_pull(
+  app: tauri::AppHandle,
+  strategy: Option<SyncConflictStrategy>,
+) -> Result<SyncReport, AppError> {
+  pull_collections(&app, strategy.unwrap_or(SyncConflictStrategy::PreferRemote)).await
+}
 
-  // 拉取目标页面内容。
-  let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
-  if !response.status().is_success() {
-    return Err(format!("搜索站点请求失败: {}", response.status()));
+// 把明文密钥混淆成 credentials.toml 可用的 "enc:<hex>" 形式（只防随手翻看，不是加密）。
+// 前端可用它帮助用户生成覆盖文件内容。
+#[tauri::command]
+pub fn obfuscate_credential(value: String) -> Result<String, AppError> {
+  let value = value.trim();
+  if value.is_empty() {
+    return Err(AppError::invalid_input("密钥不能为空"));
   }
+  Ok(obfuscate_plain_credential(value))
+}
 
-  let body = response.text().await.map_err(|e| e.to_string())?;
-  Ok(body)
+// 获取分集标题的中文译名：
+// - Bangumi 已有中文名（name_cn）的直接使用；
+// - 原标题已经是中文的原样使用；
+// - 其余标题先查翻译缓存，没有的再一次性批量翻译，成功后写入缓存。
+#[tauri::command]
+pub async fn get_episode_titles_cn(subject_id: u32) -> Result<EpisodeTitlesResponse, AppError> {
+  let client = shared_client();
+  let episodes = get_subject_episodes_impl(subject_id).await?.episodes;
+
+  let mut titles: Vec<EpisodeTitleItem> = Vec::with_capacity(episodes.len());
+  // 需要调用翻译服务的标题在 titles 中的位置。
+  let mut pending: Vec<usize> = Vec::new();
+  for episode in episodes {
+    let original = episode.name.trim().to_string();
+    let name_cn = episode.name_cn.trim().to_string();
+    let mut item = EpisodeTitleItem {
+      id: episode.id,
+      episode_type: episode.episode_type,
+      ep: episode.ep,
+      sort: episode.sort,
+      original,
+      translated: None,
+      machine_translated: false,
+      error: None,
+    };
+    if !name_cn.is_empty() {
+      item.translated = Some(name_cn);
+    } else if item.original.is_empty() {
+      // 没有标题，不需要翻译。
+    } else if is_chinese_text(&item.original) {
+      item.translated = Some(item.original.clone());
+    } else if let Some(cached) = load_translation("episode", item.id, &cache_source(&item.original)) {
+      item.translated = Some(cached);
+      item.machine_translated = true;
+    } else {
+      pending.push(titles.len());
+    }
+    titles.push(item);
+  }
+
+  if !pending.is_empty() {
+    let sources: Vec<String> = pending.iter().map(|index| titles[*index].original.clone()).collect();
+    let outputs = translate_batch_to_cn(&client, &sources).await;
+    for (index, output) in pending.into_iter().zip(outputs) {
+      let item = &mut titles[index];
+      match output.map(|value| value.trim().to_string()) {
+        Ok(value) if !value.is_empty() && is_chinese_text(&value) => {
+          save_translation("episode", item.id, &cache_source(&item.original), &value);
+          item.translated = Some(value);
+          item.machine_translated = true;
+        }
+        Ok(_) => item.error = Some("翻译失败或未产出中文结果".to_string()),
+        Err(err) => item.error = Some(err),
+      }
+    }
+  }
+
+  Ok(EpisodeTitlesResponse { subject_id, titles })
+}
+
+// 批量翻译（例如一部作品的全部分集标题），返回与输入一一对应的结果：
+// - 空文本与已经是中文的文本原样返回，不发请求；
+// - 其余文本交给翻译服务批量处理（百度会合并成多行并分块，尽量减少请求次数）。
+#[tauri::command]
+pub async fn translate_batch(texts: Vec<String>) -> Result<TranslateBatchResponse, AppError> {
+  let client = shared_client();
+
+  // 需要翻译的文本及其位置。
+  let pending: Vec<usize> = texts
+    .iter()
+    .enumerate()
+    .filter(|(_, text)| !text.trim().is_empty() && !is_chinese_text(text))
+    .map(|(index, _)| index)
+    .collect();
+  let sources: Vec<String> = pending.iter().map(|index| texts[*index].trim().to_string()).collect();
+  let outputs = if sources.is_empty() {
+    Vec::new()
+  } else {
+    translate_batch_to_cn(&client, &sources).await
+  };
+
+  let mut items: Vec<TranslateBatchItem> = texts
+    .into_iter()
+    .map(|text| TranslateBatchItem {
+      text,
+      translated: false,
+      error: None,
+    })
+    .collect();
+  for (index, output) in pending.into_iter().zip(outputs) {
+    let item = &mut items[index];
+    match output.map(|value| value.trim().to_string()) {
+      Ok(value) if !value.is_empty() && is_chinese_text(&value) => {
+        item.text = value;
+        item.translated = true;
+      }
+      Ok(_) => item.error = Some("翻译失败或未产出中文结果".to_string()),
+      Err(err) => item.error = Some(err),
+    }
+  }
+  Ok(TranslateBatchResponse { items })
+}
+
+// 中文繁简转换（本地完成，不调用接口）。
+// target 可为 "simplified" / "traditional"，或地区代码如 "zh-cn" / "zh-tw" / "zh-hk"。
+#[tauri::command]
+pub fn convert_chinese(text: String, target: String) -> Result<String, AppError> {
+  convert_chinese_text(&text, &target).map_err(AppError::invalid_input)
+}
+
+// 列出翻译术语表（原文术语 -> 指定中文译名）。
+#[tauri::command]
+pub fn list_translation_glossary() -> Result<Vec<GlossaryEntry>, AppError> {
+  Ok(list_glossary())
+}
+
+// 新增或修改术语，返回修改后的术语表。
+#[tauri::command]
+pub fn add_translation_glossary_entry(term: String, translation: String) -> Result<Vec<GlossaryEntry>, AppError> {
+  add_glossary_entry(&term, &translation).map_err(AppError::invalid_input)
+}
+
+// 删除术语，返回修改后的术语表。
+#[tauri::command]
+pub fn remove_translation_glossary_entry(term: String) -> Result<Vec<GlossaryEntry>, AppError> {
+  remove_glossary_entry(&term).map_err(AppError::invalid_input)
+}
+
+// 保存用户自己的翻译服务密钥（混淆后写入 credentials.toml，优先于动态库内置密钥）。
+// credentials 为 字段名 -> 明文值，值为空表示删除该项。
+#[tauri::command]
+pub fn set_translation_credentials(
+  provider: String,
+  credentials: HashMap<String, String>,
+) -> Result<TranslationCredentialsStatus, AppError> {
+  let provider = provider.trim().to_string();
+  let fields = write_translation_credentials(&provider, &credentials).map_err(AppError::translation)?;
+  Ok(TranslationCredentialsStatus { provider, fields })
+}
+
+// 用一小段日文测试某个翻译服务当前的密钥是否可用（不回退到其它服务）。
+#[tauri::command]
+pub async fn test_translation_credentials(provider: String) -> Result<TranslationTestResponse, AppError> {
+  let provider = provider.trim().to_string();
+  let client = shared_client();
+  let result = translate_with(&provider, &client, TRANSLATION_TEST_TEXT)
+    .await
+    .and_then(|output| {
+      if is_chinese_text(&output) {
+        Ok(output)
+      } else {
+        Err(format!("翻译结果不是中文: {output}"))
+      }
+    });
+  Ok(match result {
+    Ok(output) => TranslationTestResponse {
+      provider,
+      ok: true,
+      output: Some(output),
+      error: None,
+    },
+    Err(error) => TranslationTestResponse {
+      provider,
+      ok: false,
+      output: None,
+      error: Some(error),
+    },
+  })
+}
+
+#[tauri::command]
+pub async fn get_subject_brief(id: u32, force_refresh: Option<bool>) -> Result<SubjectBriefResponse, AppError> {
+  get_subject_brief_impl(id, force_refresh.unwrap_or(false)).await
+}
+
+// 批量获取条目简介（刷新追番列表时一次调用即可）。
+// 返回 briefs（成功）与 errors（失败原因），键均为条目 ID。
+#[tauri::command]
+pub async fn get_subject_briefs(
+  ids: Vec<u32>,
+  force_refresh: Option<bool>,
+) -> Result<SubjectBriefsResponse, AppError> {
+  get_subject_briefs_impl(ids, force_refresh.unwrap_or(false)).await
+}
+
+// 按关键词搜索条目（默认只搜动画，subject_type 可选 1/2/3/4/6），返回与季度列表相同结构的条目。
+#[tauri::command]
+pub async fn search_subjects(
+  keyword: String,
+  subject_type: Option<u32>,
+  limit: Option<u32>,
+  offset: Option<u32>,
+) -> Result<SearchSubjectsResponse, AppError> {
+  search_subjects_impl(keyword, subject_type, limit, offset).await
+}
+
+// 获取排行榜（按排名分页，page 从 1 开始）；subject_type 不传时为动画。
+#[tauri::command]
+pub async fn get_ranked_subjects(
+  subject_type: Option<u32>,
+  page: Option<u32>,
+  force_refresh: Option<bool>,
+) -> Result<RankedSubjectsResponse, AppError> {
+  get_ranked_subjects_impl(subject_type, page, force_refresh.unwrap_or(false)).await
 }
 
+// 按季度获取条目列表；subject_type 不传时为动画。
 #[tauri::command]
-pub async fn get_season_subjects(year: u32, season: String) -> Result<SeasonResponse, String> {
-  get_season_subjects_impl(year, season).await
+pub async fn get_season_subjects(
+  year: u32,
+  season: String,
+  subject_type: Option<u32>,
+  force_refresh: Option<bool>,
+) -> Result<SeasonResponse, AppError> {
+  get_season_subjects_impl(year, season, subject_type, force_refresh.unwrap_or(false)).await
 }
@@ -10,6 +10,8 @@ use serde_json::{Map, Value};
 
 // 只使用到少量模型：InfoboxItem 与 SubjectTag。
 use super::models::{InfoboxItem, SubjectTag};
+// 筛选分类表（官方标签、别名、infobox 字段名）。
+use super::taxonomy::{FilterTaxonomy, TaxonomyGroup};
 
 // 从 JSON 对象里提取“最可能是文本的字段”。
 // Bangumi infobox 的字段不统一，所以我们尝试多个 key。
@@ -67,7 +69,7 @@ pub(crate) fn extract_infobox_value(value: &Value) -> Option<String> {
 // 例如："动作/冒险" -> ["动作","冒险"]。
 fn split_infobox_terms(value: &str) -> Vec<String> {
   value
-    .split(|ch| matches!(ch, '/' | '／' | '、' | ',' | '，' | '|'))
+    .split(['/', '／', '、', ',', '，', '|'])
     .map(|item| item.trim())
     .filter(|item| !item.is_empty())
     .map(|item| item.to_string())
@@ -129,22 +131,18 @@ fn normalize_tag(value: &str) -> String {
   value.trim().to_lowercase()
 }
 
-// 把一组标签映射到“官方规范列表”。
+// 把一组标签映射到分组的“官方规范列表”。
 // 支持别名转换（如“机甲” -> “机战”）。
-pub(crate) fn map_tags_to_official(
-  tags: &[String],
-  official: &[&str],
-  aliases: &[(&str, &str)],
-) -> Vec<String> {
+pub(crate) fn map_tags_to_official(tags: &[String], group: &TaxonomyGroup) -> Vec<String> {
   // official_map：规范标签的快速查找表。
   let mut official_map = std::collections::HashMap::new();
-  for name in official {
-    official_map.insert(normalize_tag(name), (*name).to_string());
+  for name in &group.official {
+    official_map.insert(normalize_tag(name), name.clone());
   }
   // alias_map：别名 -> 规范标签。
   let mut alias_map = std::collections::HashMap::new();
-  for (from, to) in aliases {
-    alias_map.insert(normalize_tag(from), (*to).to_string());
+  for (from, to) in &group.aliases {
+    alias_map.insert(normalize_tag(from), to.clone());
   }
   // output 保存最终结果；seen 用来去重。
   let mut output = Vec::new();
@@ -166,6 +164,19 @@ pub(crate) fn map_tags_to_official(
   output
 }
 
+// 查找单个标签在分组里对应的官方标签（直接匹配或通过别名），找不到时返回 None。
+pub(crate) fn official_tag_name(tag: &str, group: &TaxonomyGroup) -> Option<String> {
+  let normalized = normalize_tag(tag);
+  if let Some(name) = group.official.iter().find(|name| normalize_tag(name) == normalized) {
+    return Some(name.clone());
+  }
+  group
+    .aliases
+    .iter()
+    .find(|(from, _)| normalize_tag(from) == normalized)
+    .map(|(_, to)| to.clone())
+}
+
 // 去重工具：保持插入顺序，只去掉重复项。
 pub(crate) fn dedupe_terms(values: Vec<String>) -> Vec<String> {
   let mut seen = std::collections::HashSet::new();
@@ -178,10 +189,11 @@ pub(crate) fn dedupe_terms(values: Vec<String>) -> Vec<String> {
   output
 }
 
-// 从 infobox 中提取“类型 / 地区 / 受众”。
+// 从 infobox 中提取“类型 / 地区 / 受众”（字段名来自分类表）。
 // 返回 (types, regions, audiences) 三个列表。
 pub(crate) fn extract_filter_groups(
   infobox: Option<Vec<InfoboxItem>>,
+  taxonomy: &FilterTaxonomy,
 ) -> (Vec<String>, Vec<String>, Vec<String>) {
   let mut types = Vec::new();
   let mut regions = Vec::new();
@@ -198,11 +210,13 @@ pub(crate) fn extract_filter_groups(
       continue;
     }
     // 根据 key 判断它属于哪个分组。
-    match item.key.as_str() {
-      "类型" | "题材" | "动画类型" | "分类" | "类别" => types.extend(values),
-      "地区" | "国家/地区" | "国家地区" | "国家" | "发行地区" => regions.extend(values),
-      "受众" | "对象" | "读者对象" => audiences.extend(values),
-      _ => {}
+    let key = item.key.trim();
+    if taxonomy.types.infobox_keys.iter().any(|name| name == key) {
+      types.extend(values);
+    } else if taxonomy.regions.infobox_keys.iter().any(|name| name == key) {
+      regions.extend(values);
+    } else if taxonomy.audiences.infobox_keys.iter().any(|name| name == key) {
+      audiences.extend(values);
     }
   }
   (dedupe_terms(types), dedupe_terms(regions), dedupe_terms(audiences))
@@ -11,6 +11,12 @@
 // 这里通常放“对外接口调用/请求逻辑”，比如请求 Bangumi 的网络 API。
 // 注意：`mod api;` 表示这个模块只在本模块内部使用，不对外暴露。
 mod api;
+// 声明 `cache` 子模块：
+// Bangumi 响应的磁盘缓存（按有效期复用本地数据）。
+mod cache;
+// 声明 `client` 子模块：
+// 全局共享的 HTTP 客户端（连接池复用、统一超时与 user_agent）。
+mod client;
 // 声明并公开 `commands` 子模块：
 // `pub` 的意思是“外部也能用”，常用于暴露给其他模块或前端调用的命令入口。
 pub mod commands;
@@ -20,6 +26,34 @@ mod filters;
 // 声明 `models` 子模块：
 // 放数据结构定义（结构体/枚举等），描述 Bangumi 相关的“数据长什么样”。
 mod models;
+// 声明 `request` 子模块：
+// Bangumi 请求发送助手（限流、指数退避重试、结构化错误）。
+mod request;
+// 声明 `sync` 子模块：
+// 追番列表与 bgm.tv 收藏的双向同步。
+mod sync;
+// 声明 `taxonomy` 子模块：
+// 筛选标签分类表（官方标签、别名），从数据目录的 filter-taxonomy.toml 加载并热更新。
+mod taxonomy;
 // 声明 `translate` 子模块：
-// 通常负责字段映射/文本转换，比如把外部数据转成内部需要的格式。
+// 简介翻译：多个翻译服务（百度 / DeepL / Google / OpenAI 兼容接口）按顺序回退，以及密钥读取。
 mod translate;
+
+// 导出密钥覆盖文件的初始化入口，供 main.rs 在启动时调用。
+pub(crate) use translate::{deobfuscate_credential_value, init_credentials_dir, obfuscate_credential_value};
+// 导出密钥读取入口，供其它模块（如 TMDB）读取第三方服务密钥。
+pub(crate) use translate::get_secret;
+// 导出中文繁简转换，供字幕繁简转换使用。
+pub(crate) use translate::convert_chinese_text;
+// 导出翻译服务列表与翻译设置的应用入口，供设置模块与首次启动向导使用。
+pub(crate) use translate::{apply_translation_settings, TRANSLATION_PROVIDERS};
+// 导出网络设置的应用入口，供设置模块在启动/保存设置时调用。
+pub(crate) use client::apply_network_settings;
+// 导出共享 HTTP 客户端，供图片缓存等其它模块复用同一套连接池与代理设置。
+pub(crate) use client::shared_client;
+// 导出 Bangumi 请求限流/重试设置的应用入口，以及请求错误类型（供统一错误类型转换）。
+pub(crate) use request::{apply_request_settings, BangumiError};
+// 导出缓存目录初始化与缓存设置的应用入口。
+pub(crate) use cache::{apply_cache_settings, init_cache_dir};
+// 导出筛选分类表的初始化入口。
+pub(crate) use taxonomy::init_filter_taxonomy;
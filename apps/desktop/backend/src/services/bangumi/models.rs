@@ -8,6 +8,11 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::services::metadata::tmdb::TmdbArtwork;
+
+use super::translate::DetectedLanguage;
 
 /// 分页条目列表：Bangumi 返回的“分页结构”。
 /// total = 总条目数；limit = 每页条数；data = 当前页数据。
@@ -29,10 +34,21 @@ pub(crate) struct Subject {
   pub images: Option<Images>,
   pub summary: Option<String>,
   pub rating: Option<Rating>,
+  /// 条目类型（1 书籍 / 2 动画 / 3 音乐 / 4 游戏 / 6 三次元）。
+  #[serde(rename = "type")]
+  pub subject_type: Option<u32>,
+  /// 平台（如 TV / 剧场版 / 漫画 / PS5），不同类型取值不同。
+  pub platform: Option<String>,
+  /// 卷数（书籍），其它类型通常为 0。
+  pub volumes: Option<u32>,
+  /// 正片集数（数据库登记的集数）。
+  pub eps: Option<u32>,
+  /// 全部集数（含 SP 等）。
+  pub total_episodes: Option<u32>,
 }
 
 /// 条目图片集合（可能为空）。
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub(crate) struct Images {
   pub common: Option<String>,
   pub medium: Option<String>,
@@ -43,6 +59,12 @@ pub(crate) struct Images {
 #[derive(Deserialize)]
 pub(crate) struct Rating {
   pub score: Option<f64>,
+  /// 排名（未上榜时为 0 或缺失）。
+  pub rank: Option<u32>,
+  /// 评分人数。
+  pub total: Option<u32>,
+  /// 各分数的人数，键为 "1" ~ "10"。
+  pub count: Option<HashMap<String, u32>>,
 }
 
 /// 分集分页数据。
@@ -53,9 +75,22 @@ pub(crate) struct EpisodePage {
   pub data: Vec<Episode>,
 }
 
-/// 分集信息：只用到“播出日期”和“播出状态”。
+/// 分集信息：统计已播出集数只用到“播出日期”和“播出状态”，
+/// 其余字段用于分集列表。
 #[derive(Deserialize)]
 pub(crate) struct Episode {
+  #[serde(default)]
+  pub id: u32,
+  /// 分集类型：0 本篇、1 SP、2 OP、3 ED 等。
+  #[serde(rename = "type")]
+  pub episode_type: Option<u32>,
+  /// 本篇集数（可能为小数，如 12.5）。
+  pub ep: Option<f64>,
+  /// 条目内排序序号。
+  pub sort: Option<f64>,
+  pub name: Option<String>,
+  pub name_cn: Option<String>,
+  pub duration: Option<String>,
   pub airdate: Option<String>,
   pub status: Option<String>,
 }
@@ -73,6 +108,9 @@ pub(crate) struct SubjectDetail {
 #[derive(Deserialize)]
 pub(crate) struct SubjectTag {
   pub name: String,
+  /// 标注该标签的人数。
+  #[serde(default)]
+  pub count: u32,
 }
 
 /// infobox 里的一个键值项。
@@ -126,9 +164,109 @@ pub(crate) enum SubjectCharacterPayload {
   },
 }
 
+/// 角色详情（原始结构）。
+#[derive(Deserialize)]
+pub(crate) struct CharacterDetail {
+  pub id: u32,
+  pub name: String,
+  pub summary: Option<String>,
+  pub gender: Option<String>,
+  pub images: Option<Images>,
+  pub infobox: Option<Vec<InfoboxItem>>,
+}
+
+/// 角色的声优记录：同一声优在不同条目中各有一条。
+#[derive(Deserialize)]
+pub(crate) struct CharacterPersonItem {
+  pub id: u32,
+  pub name: String,
+  pub images: Option<Images>,
+  pub subject_id: Option<u32>,
+  pub subject_name: Option<String>,
+  pub subject_name_cn: Option<String>,
+}
+
+/// 人物参与的条目（原始结构）。
+#[derive(Deserialize)]
+pub(crate) struct PersonSubjectItem {
+  pub id: u32,
+  #[serde(rename = "type")]
+  pub subject_type: Option<u32>,
+  /// 在该条目中的职位（如 "导演"、"动画制作"）。
+  pub staff: Option<String>,
+  pub name: Option<String>,
+  pub name_cn: Option<String>,
+  pub image: Option<String>,
+}
+
+/// 人物搜索结果中的一项。
+#[derive(Deserialize)]
+pub(crate) struct PersonSearchItem {
+  pub id: u32,
+  pub name: String,
+  /// 人物类型：1 个人 / 2 公司 / 3 组合。
+  #[serde(rename = "type")]
+  pub person_type: Option<u32>,
+}
+
+/// 人物搜索分页数据。
+#[derive(Deserialize)]
+pub(crate) struct PagedPerson {
+  pub data: Vec<PersonSearchItem>,
+}
+
+/// 关联条目（续集、前传、番外篇等）。
+#[derive(Deserialize)]
+pub(crate) struct RelatedSubjectItem {
+  pub id: u32,
+  /// 条目类型：1 书籍、2 动画、3 音乐、4 游戏、6 三次元。
+  #[serde(rename = "type")]
+  pub subject_type: Option<u32>,
+  pub name: String,
+  pub name_cn: Option<String>,
+  pub images: Option<Images>,
+  pub relation: Option<String>,
+}
+
+/// 当前登录用户（/v0/me）。
+#[derive(Deserialize)]
+pub(crate) struct BangumiUser {
+  pub username: String,
+}
+
+/// 用户收藏分页数据。
+#[derive(Deserialize)]
+pub(crate) struct CollectionPage {
+  pub total: u32,
+  pub limit: u32,
+  pub data: Vec<CollectionItem>,
+}
+
+/// 单条收藏记录。
+/// type：1 想看、2 看过、3 在看、4 搁置、5 抛弃。
+#[derive(Deserialize)]
+pub(crate) struct CollectionItem {
+  pub subject_id: u32,
+  #[serde(rename = "type")]
+  pub collection_type: u32,
+  pub subject: Option<CollectionSubject>,
+}
+
+/// 收藏记录里附带的精简条目信息。
+#[derive(Deserialize)]
+pub(crate) struct CollectionSubject {
+  pub name: String,
+  pub name_cn: Option<String>,
+  pub images: Option<Images>,
+  pub date: Option<String>,
+  pub score: Option<f64>,
+  pub short_summary: Option<String>,
+  pub eps: Option<u32>,
+}
+
 /// 前端使用的“季度番剧条目”。
 /// 使用 camelCase 以符合前端常用命名习惯。
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SeasonAnime {
   pub id: u32,
@@ -139,10 +277,16 @@ pub struct SeasonAnime {
   pub rating: Option<f64>,
   pub summary: String,
   pub url: String,
+  /// 条目类型（1 书籍 / 2 动画 / 3 音乐 / 4 游戏 / 6 三次元）。
+  pub subject_type: u32,
+  /// 平台（动画为 TV / 剧场版 等，游戏为主机平台，书籍为 漫画 / 小说 等）。
+  pub platform: Option<String>,
+  /// 卷数（仅书籍有意义，未知时为空）。
+  pub volumes: Option<u32>,
 }
 
 /// 某个月的番剧列表及统计信息。
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SeasonMonth {
   pub year: u32,
@@ -151,15 +295,66 @@ pub struct SeasonMonth {
   pub list: Vec<SeasonAnime>,
 }
 
-/// 某一季的完整响应。
-#[derive(Serialize)]
+/// 某个月拉取失败的原因（其它月份照常返回）。
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonMonthError {
+  pub month: u32,
+  pub error: String,
+}
+
+/// 某一季的完整响应（同时作为离线快照写入数据目录）。
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SeasonResponse {
   pub year: u32,
   pub season: String,
+  /// 本次拉取的条目类型。
+  pub subject_type: u32,
   pub fetched_at: String,
   pub source: String,
   pub months: Vec<SeasonMonth>,
+  /// 拉取失败的月份（部分结果时非空，这些月份不在 months 中）。
+  #[serde(default)]
+  pub errors: Vec<SeasonMonthError>,
+  /// 是否为离线快照（api.bgm.tv 不可用时返回），此时 fetched_at 为快照的原始拉取时间。
+  #[serde(default)]
+  pub stale: bool,
+}
+
+/// 关键词搜索响应。
+/// total 为匹配总数，list 为当前页结果（与季度列表条目结构一致）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSubjectsResponse {
+  pub keyword: String,
+  pub total: u32,
+  pub limit: u32,
+  pub offset: u32,
+  pub list: Vec<SeasonAnime>,
+}
+
+/// 排行榜中的条目：季度列表条目 + 排名。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedSubjectItem {
+  /// 排名（未上榜时为空）。
+  pub rank: Option<u32>,
+  #[serde(flatten)]
+  pub subject: SeasonAnime,
+}
+
+/// 排行榜响应（按排名从高到低分页）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedSubjectsResponse {
+  pub subject_type: u32,
+  /// 页码（从 1 开始）。
+  pub page: u32,
+  pub page_size: u32,
+  /// 可浏览的条目总数。
+  pub total: u32,
+  pub list: Vec<RankedSubjectItem>,
 }
 
 /// 原作信息响应。
@@ -204,6 +399,34 @@ pub struct SubjectStaffResponse {
   pub groups: Vec<StaffGroupResponse>,
 }
 
+/// 单个关联条目（输出给前端）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedSubjectResponse {
+  pub id: u32,
+  pub subject_type: Option<u32>,
+  pub name: String,
+  pub name_cn: String,
+  pub image: String,
+  pub url: String,
+}
+
+/// 关联条目分组（按关系类型）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationGroupResponse {
+  pub relation: String,
+  pub subjects: Vec<RelatedSubjectResponse>,
+}
+
+/// 关联条目列表响应。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectRelationsResponse {
+  pub id: u32,
+  pub groups: Vec<RelationGroupResponse>,
+}
+
 /// 角色链接信息（用于前端展示）。
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -214,6 +437,41 @@ pub struct CharacterLinkResponse {
   pub relation: Option<String>,
 }
 
+/// 声优信息（输出给前端），subjects 为该声优为此角色配音的条目。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterActorResponse {
+  pub id: u32,
+  pub name: String,
+  pub image: Option<String>,
+  pub url: String,
+  pub subjects: Vec<CharacterActorSubject>,
+}
+
+/// 声优参与的条目。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterActorSubject {
+  pub id: u32,
+  pub name: String,
+  pub name_cn: String,
+}
+
+/// 角色详情响应。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterDetailResponse {
+  pub id: u32,
+  pub name: String,
+  /// 简体中文名（来自 infobox，可能为空）。
+  pub name_cn: Option<String>,
+  pub summary: String,
+  pub gender: Option<String>,
+  pub image: Option<String>,
+  pub url: String,
+  pub actors: Vec<CharacterActorResponse>,
+}
+
 /// 角色列表响应。
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -237,6 +495,72 @@ pub struct SubjectSummaryResponse {
   pub id: u32,
   pub summary: String,
   pub translated: bool,
+  /// 原文为繁体中文，已在本地转换成简体。
+  pub converted: bool,
+  /// 识别出的原文语言（含置信度）；摘要为空时为空。
+  pub source_language: Option<DetectedLanguage>,
+  pub error: Option<String>,
+}
+
+/// 单集标题及其中文译名。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeTitleItem {
+  pub id: u32,
+  pub episode_type: u32,
+  pub ep: Option<f64>,
+  pub sort: Option<f64>,
+  /// 原标题（通常为日文）。
+  pub original: String,
+  /// 中文标题：优先使用 Bangumi 的官方中文名，其次为翻译结果；翻译失败时为空。
+  pub translated: Option<String>,
+  /// 中文标题是否来自机器翻译（含缓存）。
+  pub machine_translated: bool,
+  pub error: Option<String>,
+}
+
+/// 分集标题翻译响应。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeTitlesResponse {
+  pub subject_id: u32,
+  pub titles: Vec<EpisodeTitleItem>,
+}
+
+/// 批量翻译中的单条结果。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslateBatchItem {
+  /// 译文；无需翻译或翻译失败时为原文。
+  pub text: String,
+  pub translated: bool,
+  pub error: Option<String>,
+}
+
+/// 批量翻译响应（与输入顺序一一对应）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslateBatchResponse {
+  pub items: Vec<TranslateBatchItem>,
+}
+
+/// 写入翻译服务密钥后的状态。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationCredentialsStatus {
+  pub provider: String,
+  /// 覆盖文件中该服务已配置的字段名。
+  pub fields: Vec<String>,
+}
+
+/// 翻译服务密钥测试结果。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationTestResponse {
+  pub provider: String,
+  pub ok: bool,
+  /// 测试文本的译文（失败时为空）。
+  pub output: Option<String>,
   pub error: Option<String>,
 }
 
@@ -250,6 +574,234 @@ pub struct SubjectFiltersResponse {
   pub audiences: Vec<String>,
 }
 
+/// 单集信息（输出给前端）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeItemResponse {
+  pub id: u32,
+  pub episode_type: u32,
+  pub ep: Option<f64>,
+  pub sort: Option<f64>,
+  pub name: String,
+  pub name_cn: String,
+  pub airdate: Option<String>,
+  pub duration: Option<String>,
+  pub status: Option<String>,
+  /// 是否已播出（与已播出集数统计使用同一规则）。
+  pub aired: bool,
+}
+
+/// 分集列表响应。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectEpisodesResponse {
+  pub id: u32,
+  pub total: u32,
+  pub episodes: Vec<EpisodeItemResponse>,
+}
+
+/// 下一集播出信息（只看正片）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NextEpisodeResponse {
+  pub id: u32,
+  /// 已播出的正片集数与正片总集数。
+  pub aired_count: u32,
+  pub total_count: u32,
+  /// 下一集未播出的正片（全部播完时为空）。
+  pub next: Option<EpisodeItemResponse>,
+  /// 距离下一集播出的天数（0 表示今天，播出日期未知时为空）。
+  pub days_until: Option<i64>,
+  /// 正片是否已全部播出。
+  pub finished: bool,
+}
+
+/// 批量获取简要信息的响应：成功的放在 briefs，失败的放在 errors（键均为条目 ID）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectBriefsResponse {
+  pub briefs: HashMap<u32, SubjectBriefResponse>,
+  pub errors: HashMap<u32, String>,
+}
+
+/// 同步冲突处理策略（本地与 bgm.tv 状态不一致时）。
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncConflictStrategy {
+  /// 以本地状态为准。
+  PreferLocal,
+  /// 以 bgm.tv 状态为准。
+  PreferRemote,
+  /// 两边都不改，只在结果里列出冲突。
+  Skip,
+}
+
+/// 一条状态冲突（状态值为 "watching" / "backlog" / "watched"，
+/// bgm.tv 一侧还可能是 "on-hold" / "dropped"）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+  pub id: u32,
+  pub local: String,
+  pub remote: String,
+}
+
+/// 同步结果。
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+  /// 在目标一侧新增的条目（push 为 bgm.tv，pull 为本地）。
+  pub created: Vec<u32>,
+  /// 按冲突策略修改了本地状态的条目。
+  pub updated_local: Vec<u32>,
+  /// 按冲突策略修改了 bgm.tv 状态的条目。
+  pub updated_remote: Vec<u32>,
+  /// 两边状态一致、无需处理的条目数。
+  pub unchanged: usize,
+  /// 未处理的冲突（策略为 skip，或 bgm.tv 上是无法对应的搁置/抛弃状态）。
+  pub conflicts: Vec<SyncConflict>,
+  /// 单个条目同步失败的原因。
+  pub errors: HashMap<u32, String>,
+}
+
+/// 条目图片响应：Bangumi 封面 + TMDB 背景图/海报/标志。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectArtworkResponse {
+  pub id: u32,
+  /// Bangumi 封面（优先大图）。
+  pub cover: String,
+  /// TMDB 图片；未找到对应作品时为空。
+  pub tmdb: Option<TmdbArtwork>,
+  /// TMDB 查询失败的原因（例如未配置密钥），不影响 Bangumi 封面。
+  pub tmdb_error: Option<String>,
+}
+
+/// 对比中的单个条目。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparedSubject {
+  pub brief: SubjectBriefResponse,
+  pub rating: SubjectRatingResponse,
+  /// 正片集数（未知时为空）。
+  pub episodes: Option<u32>,
+  /// 制作人员人数（按人物去重）。
+  pub staff_count: usize,
+}
+
+/// 某位制作人员在某个条目中的职位。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaffSubjectRoles {
+  pub subject_id: u32,
+  pub roles: Vec<String>,
+}
+
+/// 参与了多个对比条目的制作人员。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedStaffResponse {
+  pub id: u32,
+  pub name: String,
+  pub url: String,
+  pub subjects: Vec<StaffSubjectRoles>,
+}
+
+/// 条目对比响应：subjects 与请求顺序一致。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectComparisonResponse {
+  pub subjects: Vec<ComparedSubject>,
+  /// 在两个及以上条目中出现的制作人员（按人物 ID 去重），参与条目多的在前。
+  pub shared_staff: Vec<SharedStaffResponse>,
+}
+
+/// 人物（或制作公司）参与的作品。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonWorkItem {
+  pub id: u32,
+  pub name: String,
+  pub name_cn: String,
+  pub image: Option<String>,
+  pub url: String,
+  pub subject_type: u32,
+  /// 在该作品中的职位（同一作品的多个职位合并在一起）。
+  pub relations: Vec<String>,
+}
+
+/// 人物作品列表响应。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonWorksResponse {
+  pub id: u32,
+  pub url: String,
+  pub works: Vec<PersonWorkItem>,
+}
+
+/// 制作公司作品列表响应。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StudioWorksResponse {
+  pub keyword: String,
+  /// 匹配到的公司（找不到时为空，works 也为空）。
+  pub studio_id: Option<u32>,
+  pub studio_name: Option<String>,
+  pub works: Vec<PersonWorkItem>,
+}
+
+/// infobox 中的一项（同名字段已合并）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InfoboxEntry {
+  pub key: String,
+  pub values: Vec<String>,
+}
+
+/// 完整 infobox 响应（保持接口中的字段顺序）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectInfoboxResponse {
+  pub id: u32,
+  pub items: Vec<InfoboxEntry>,
+}
+
+/// 单个标签（带人数与分类表匹配结果）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectTagItem {
+  pub name: String,
+  pub count: u32,
+  /// 是否能归到分类表里的官方标签（直接匹配或通过别名）。
+  pub official: bool,
+  /// 匹配到的官方标签名。
+  pub official_name: Option<String>,
+  /// 所属分组："type" / "region" / "audience"。
+  pub group: Option<String>,
+}
+
+/// 条目标签响应（按人数从多到少排序）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectTagsResponse {
+  pub id: u32,
+  pub tags: Vec<SubjectTagItem>,
+}
+
+/// 评分详情响应（用于渲染评分分布柱状图）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectRatingResponse {
+  pub id: u32,
+  pub score: Option<f64>,
+  /// 排名，未上榜时为空。
+  pub rank: Option<u32>,
+  /// 评分人数。
+  pub total: u32,
+  /// 1~10 分各自的人数，下标 0 对应 1 分。
+  pub histogram: Vec<u32>,
+}
+
 /// 条目简要信息响应。
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -262,4 +814,6 @@ pub struct SubjectBriefResponse {
   pub rating: Option<f64>,
   pub summary: String,
   pub url: String,
+  /// 补全数据所用的备用数据源（如 "anilist"），完全来自 Bangumi 时为空。
+  pub fallback_provider: Option<String>,
 }
@@ -0,0 +1,170 @@
+/*
+  Bangumi 请求发送助手：
+  所有发往 api.bgm.tv 的请求都经过 send_bangumi，统一处理：
+  1) 每秒请求数上限（按固定间隔放行，避免触发服务端限流）；
+  2) 遇到 429 / 5xx / 网络错误时按指数退避重试，并加入随机抖动，
+     服务端返回 Retry-After 时以它为准；
+  3) 重试用尽后返回结构化错误 BangumiError（可直接用 ? 转换成 AppError，保留状态码）。
+  响应体统一用 read_json 读取，读取失败与内容不符分别归为网络错误与解析错误。
+  上限与重试次数来自设置模块的 NetworkSettings。
+*/
+
+use once_cell::sync::Lazy;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::services::error::AppError;
+use crate::services::limits::send_limited;
+use crate::services::settings::NetworkSettings;
+
+// 首次重试前的等待时间，之后每次翻倍。
+const BASE_BACKOFF_MS: u64 = 500;
+// 单次等待的上限。
+const MAX_BACKOFF_MS: u64 = 10_000;
+
+// 每秒请求数上限（0 表示不限制）与最大重试次数，初始取默认设置。
+static REQUESTS_PER_SECOND: Lazy<AtomicU32> =
+  Lazy::new(|| AtomicU32::new(NetworkSettings::default().bangumi_requests_per_second));
+static MAX_RETRIES: Lazy<AtomicU32> = Lazy::new(|| AtomicU32::new(NetworkSettings::default().max_retries));
+
+// 下一个请求最早可以发出的时间。
+static NEXT_SLOT: Lazy<Mutex<Instant>> = Lazy::new(|| Mutex::new(Instant::now()));
+
+/// Bangumi 请求错误。
+#[derive(Debug)]
+pub(crate) enum BangumiError {
+  /// 请求无法重试（例如请求体是流，无法复制）。
+  Request(String),
+  /// 重试用尽：status 为最后一次的状态码（网络错误时为 None）。
+  RetriesExhausted {
+    attempts: u32,
+    status: Option<StatusCode>,
+    message: String,
+  },
+}
+
+impl fmt::Display for BangumiError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      BangumiError::Request(message) => write!(f, "Bangumi API 请求失败: {message}"),
+      BangumiError::RetriesExhausted {
+        attempts,
+        status: Some(status),
+        ..
+      } => write!(f, "Bangumi API 请求失败（已尝试 {attempts} 次）: {status}"),
+      BangumiError::RetriesExhausted {
+        attempts,
+        status: None,
+        message,
+      } => write!(f, "Bangumi API 请求失败（已尝试 {attempts} 次）: {message}"),
+    }
+  }
+}
+
+// 应用新的限流与重试设置（启动时与保存设置时调用）。
+pub(crate) fn apply_request_settings(settings: &NetworkSettings) {
+  REQUESTS_PER_SECOND.store(settings.bangumi_requests_per_second, Ordering::Relaxed);
+  MAX_RETRIES.store(settings.max_retries, Ordering::Relaxed);
+}
+
+// 等待下一个可用的发送时间点。
+// 每个请求预约一个间隔为 1/rps 秒的时间槽，锁只在计算时持有。
+async fn wait_for_slot() {
+  let rps = REQUESTS_PER_SECOND.load(Ordering::Relaxed);
+  if rps == 0 {
+    return;
+  }
+  let interval = Duration::from_secs(1) / rps;
+  let wait = {
+    let mut next = NEXT_SLOT.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    let slot = (*next).max(now);
+    *next = slot + interval;
+    slot - now
+  };
+  if !wait.is_zero() {
+    tokio::time::sleep(wait).await;
+  }
+}
+
+// 是否值得重试：限流或服务端错误。
+fn is_retryable(status: StatusCode) -> bool {
+  status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// 计算第 attempt 次重试前的等待时间：指数退避 + 0~50% 的随机抖动。
+// 抖动只是为了错开并发请求，用时间戳的纳秒部分即可，无需引入随机数库。
+fn backoff(attempt: u32) -> Duration {
+  let base = BASE_BACKOFF_MS
+    .saturating_mul(1u64 << attempt.min(16))
+    .min(MAX_BACKOFF_MS);
+  let nanos = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.subsec_nanos() as u64)
+    .unwrap_or(0);
+  Duration::from_millis(base + nanos % (base / 2 + 1))
+}
+
+// 读取 Retry-After（只支持秒数形式），上限与退避上限一致。
+fn retry_after(response: &Response) -> Option<Duration> {
+  let seconds: u64 = response
+    .headers()
+    .get(reqwest::header::RETRY_AFTER)?
+    .to_str()
+    .ok()?
+    .trim()
+    .parse()
+    .ok()?;
+  Some(Duration::from_secs(seconds).min(Duration::from_millis(MAX_BACKOFF_MS)))
+}
+
+// 发送 Bangumi 请求（限流 + 重试）。
+// 返回的响应可能仍是 4xx（例如 404），由调用方按原有逻辑判断；
+// 只有 429 / 5xx / 网络错误在重试用尽后才会变成 Err。
+pub(crate) async fn send_bangumi(request: RequestBuilder) -> Result<Response, BangumiError> {
+  let max_retries = MAX_RETRIES.load(Ordering::Relaxed);
+  let mut attempt = 0u32;
+  loop {
+    let current = request
+      .try_clone()
+      .ok_or_else(|| BangumiError::Request("请求无法复制，不能重试".to_string()))?;
+    wait_for_slot().await;
+    let result = send_limited(current).await;
+    let attempts = attempt + 1;
+
+    let (wait, status, message) = match result {
+      Ok(response) if !is_retryable(response.status()) => return Ok(response),
+      Ok(response) => {
+        let status = response.status();
+        (retry_after(&response), Some(status), status.to_string())
+      }
+      Err(err) => (None, None, err),
+    };
+
+    if attempt >= max_retries {
+      return Err(BangumiError::RetriesExhausted {
+        attempts,
+        status,
+        message,
+      });
+    }
+    tokio::time::sleep(wait.unwrap_or_else(|| backoff(attempt))).await;
+    attempt += 1;
+  }
+}
+
+// 读取 Bangumi 响应体并解析成 JSON：
+// 读取中断归为网络错误，内容与预期结构不符归为解析错误。
+pub(crate) async fn read_json<T: DeserializeOwned>(response: Response) -> Result<T, AppError> {
+  response.json().await.map_err(|e| {
+    if e.is_decode() {
+      AppError::parse(format!("解析 Bangumi 响应失败: {e}"))
+    } else {
+      AppError::network(format!("读取 Bangumi 响应失败: {e}"))
+    }
+  })
+}
@@ -0,0 +1,327 @@
+/*
+  追番列表与 bgm.tv 收藏的双向同步：
+  - 本地三态与 Bangumi 收藏类型的对应关系：
+      在看(watching) <-> 3，想看(backlog) <-> 1，看过(watched) <-> 2；
+    bgm.tv 上的“搁置(4) / 抛弃(5)”在本地没有对应状态，只会作为冲突列出。
+  - push：以本地列表为准，把 bgm.tv 上没有的条目新增为收藏；
+    pull：以 bgm.tv 收藏为准，把本地没有的条目加入追番列表。
+  - 两边都有但状态不同时，按冲突策略处理（以本地为准 / 以 bgm.tv 为准 / 跳过）。
+  - 同步不会删除任何一侧的条目。
+
+  访问 bgm.tv 需要个人访问令牌，从 credentials.toml 的 [bangumi] access_token 读取。
+*/
+
+use reqwest::Client;
+use std::collections::HashMap;
+
+use crate::services::error::AppError;
+use crate::services::storage::{load_tracked, persist_tracked, TrackedSubject};
+
+use super::api::resolve_image;
+use super::client::shared_client;
+use super::models::{BangumiUser, CollectionItem, CollectionPage, SyncConflict, SyncConflictStrategy, SyncReport};
+use super::request::{read_json, send_bangumi};
+use super::translate::read_credential_override;
+
+// Bangumi API 的基础地址。
+const API_BASE: &str = "https://api.bgm.tv";
+// 拉取收藏时每页数量。
+const COLLECTION_PAGE_LIMIT: u32 = 50;
+// 动画条目类型。
+const SUBJECT_TYPE_ANIME: u32 = 2;
+
+// 读取个人访问令牌。
+fn access_token() -> Result<String, AppError> {
+  read_credential_override("bangumi", "access_token")
+    .map_err(AppError::parse)?
+    .ok_or_else(|| {
+      AppError::invalid_input("未配置 Bangumi 访问令牌，请在 credentials.toml 的 [bangumi] 中填写 access_token")
+    })
+}
+
+// 本地条目的状态（与前端显示规则一致：看过 > 在看 > 想看）。
+fn tracked_state(subject: &TrackedSubject) -> Option<&'static str> {
+  if subject.watched {
+    Some("watched")
+  } else if subject.watching {
+    Some("watching")
+  } else if subject.backlog {
+    Some("backlog")
+  } else {
+    None
+  }
+}
+
+// 收藏类型转换成状态名。
+fn collection_state(collection_type: u32) -> Option<&'static str> {
+  match collection_type {
+    1 => Some("backlog"),
+    2 => Some("watched"),
+    3 => Some("watching"),
+    4 => Some("on-hold"),
+    5 => Some("dropped"),
+    _ => None,
+  }
+}
+
+// 本地状态转换成收藏类型；搁置/抛弃在本地不存在，返回 None。
+fn collection_type(state: &str) -> Option<u32> {
+  match state {
+    "backlog" => Some(1),
+    "watched" => Some(2),
+    "watching" => Some(3),
+    _ => None,
+  }
+}
+
+// 把状态写回本地条目（三态互斥）。
+fn apply_local_state(subject: &mut TrackedSubject, state: &str) {
+  subject.watching = state == "watching";
+  subject.backlog = state == "backlog";
+  subject.watched = state == "watched";
+}
+
+// 获取令牌对应的用户名。
+async fn fetch_username(client: &Client, token: &str) -> Result<String, AppError> {
+  let request = client.get(format!("{API_BASE}/v0/me")).bearer_auth(token);
+  let response = send_bangumi(request).await?;
+  if !response.status().is_success() {
+    let status = response.status();
+    return Err(AppError::api(status.as_u16(), format!("Bangumi 令牌校验失败: {status}")));
+  }
+  let user: BangumiUser = read_json(response).await?;
+  Ok(user.username)
+}
+
+// 拉取用户的全部动画收藏（key=条目 ID）。
+async fn fetch_remote_collections(
+  client: &Client,
+  token: &str,
+  username: &str,
+) -> Result<HashMap<u32, CollectionItem>, AppError> {
+  let mut offset = 0u32;
+  let mut items = HashMap::new();
+  loop {
+    let request = client
+      .get(format!("{API_BASE}/v0/users/{username}/collections"))
+      .bearer_auth(token)
+      .query(&[
+        ("subject_type", SUBJECT_TYPE_ANIME.to_string()),
+        ("limit", COLLECTION_PAGE_LIMIT.to_string()),
+        ("offset", offset.to_string()),
+      ]);
+    let response = send_bangumi(request).await?;
+    if !response.status().is_success() {
+      let status = response.status();
+      return Err(AppError::api(status.as_u16(), format!("获取 Bangumi 收藏失败: {status}")));
+    }
+    let page: CollectionPage = read_json(response).await?;
+    if page.data.is_empty() {
+      break;
+    }
+    for item in page.data {
+      items.insert(item.subject_id, item);
+    }
+    offset += page.limit.max(COLLECTION_PAGE_LIMIT);
+    if offset >= page.total {
+      break;
+    }
+  }
+  Ok(items)
+}
+
+// 新增或修改 bgm.tv 上的单个收藏。
+async fn update_remote(client: &Client, token: &str, id: u32, collection_type: u32) -> Result<(), AppError> {
+  let request = client
+    .post(format!("{API_BASE}/v0/users/-/collections/{id}"))
+    .bearer_auth(token)
+    .json(&serde_json::json!({ "type": collection_type }));
+  let response = send_bangumi(request).await?;
+  if !response.status().is_success() {
+    let status = response.status();
+    return Err(AppError::api(status.as_u16(), format!("更新 Bangumi 收藏失败: {status}")));
+  }
+  Ok(())
+}
+
+// 用收藏记录里附带的条目信息构建本地条目。
+fn tracked_from_collection(item: &CollectionItem, state: &str) -> Option<TrackedSubject> {
+  let subject = item.subject.as_ref()?;
+  let mut tracked = TrackedSubject {
+    id: item.subject_id,
+    name: subject.name.clone(),
+    name_cn: subject.name_cn.clone().unwrap_or_default(),
+    image: resolve_image(subject.images.clone()),
+    url: format!("https://bgm.tv/subject/{}", item.subject_id),
+    watching: false,
+    backlog: false,
+    watched: false,
+    date: subject.date.clone().unwrap_or_default(),
+    rating: subject.score.filter(|score| *score > 0.0),
+    summary: subject.short_summary.clone().unwrap_or_default(),
+    aliases: None,
+    aired_count: None,
+    total_count: subject.eps.filter(|eps| *eps > 0),
+  };
+  apply_local_state(&mut tracked, state);
+  Some(tracked)
+}
+
+// 状态冲突的统一处理：按策略修改一侧，或记录为未处理的冲突。
+// 返回 true 表示本地数据被修改。
+async fn resolve_conflict(
+  client: &Client,
+  token: &str,
+  strategy: SyncConflictStrategy,
+  subject: Option<&mut TrackedSubject>,
+  conflict: SyncConflict,
+  report: &mut SyncReport,
+) -> bool {
+  let id = conflict.id;
+  match strategy {
+    SyncConflictStrategy::PreferLocal => match collection_type(&conflict.local) {
+      Some(collection_type) => match update_remote(client, token, id, collection_type).await {
+        Ok(()) => report.updated_remote.push(id),
+        Err(err) => {
+          report.errors.insert(id, err.to_string());
+        }
+      },
+      None => report.conflicts.push(conflict),
+    },
+    // bgm.tv 上是搁置/抛弃时本地无法表示，只能列为冲突。
+    SyncConflictStrategy::PreferRemote => match (subject, collection_type(&conflict.remote)) {
+      (Some(subject), Some(_)) => {
+        apply_local_state(subject, &conflict.remote);
+        report.updated_local.push(id);
+        return true;
+      }
+      _ => report.conflicts.push(conflict),
+    },
+    SyncConflictStrategy::Skip => report.conflicts.push(conflict),
+  }
+  false
+}
+
+// push：把本地追番列表同步到 bgm.tv。
+pub(crate) async fn push_collections(
+  app: &tauri::AppHandle,
+  strategy: SyncConflictStrategy,
+) -> Result<SyncReport, AppError> {
+  let token = access_token()?;
+  let client = shared_client();
+  let username = fetch_username(&client, &token).await?;
+  let remote = fetch_remote_collections(&client, &token, &username).await?;
+  let mut local = load_tracked(app)?;
+
+  let mut report = SyncReport::default();
+  let mut local_changed = false;
+  let mut ids: Vec<u32> = local.keys().copied().collect();
+  ids.sort_unstable();
+  for id in ids {
+    let subject = match local.get_mut(&id) {
+      Some(subject) => subject,
+      None => continue,
+    };
+    let local_state = match tracked_state(subject) {
+      Some(state) => state,
+      None => continue,
+    };
+    match remote.get(&id).and_then(|item| collection_state(item.collection_type)) {
+      // bgm.tv 上没有这个条目：直接新增收藏。
+      None => match collection_type(local_state) {
+        Some(collection_type) => match update_remote(&client, &token, id, collection_type).await {
+          Ok(()) => report.created.push(id),
+          Err(err) => {
+            report.errors.insert(id, err.to_string());
+          }
+        },
+        None => continue,
+      },
+      Some(remote_state) if remote_state == local_state => report.unchanged += 1,
+      Some(remote_state) => {
+        local_changed |= resolve_conflict(
+          &client,
+          &token,
+          strategy,
+          Some(subject),
+          SyncConflict {
+            id,
+            local: local_state.to_string(),
+            remote: remote_state.to_string(),
+          },
+          &mut report,
+        )
+        .await;
+      }
+    }
+  }
+
+  if local_changed {
+    persist_tracked(app, &local)?;
+  }
+  Ok(report)
+}
+
+// pull：把 bgm.tv 收藏同步到本地追番列表。
+pub(crate) async fn pull_collections(
+  app: &tauri::AppHandle,
+  strategy: SyncConflictStrategy,
+) -> Result<SyncReport, AppError> {
+  let token = access_token()?;
+  let client = shared_client();
+  let username = fetch_username(&client, &token).await?;
+  let remote = fetch_remote_collections(&client, &token, &username).await?;
+  let mut local = load_tracked(app)?;
+
+  let mut report = SyncReport::default();
+  let mut local_changed = false;
+  let mut ids: Vec<u32> = remote.keys().copied().collect();
+  ids.sort_unstable();
+  for id in ids {
+    let item = &remote[&id];
+    let remote_state = match collection_state(item.collection_type) {
+      Some(state) => state,
+      None => continue,
+    };
+    let local_state = local.get(&id).and_then(tracked_state);
+    match local_state {
+      // 本地没有这个条目：新增（搁置/抛弃的条目不导入）。
+      None => {
+        if collection_type(remote_state).is_none() {
+          continue;
+        }
+        match tracked_from_collection(item, remote_state) {
+          Some(tracked) => {
+            local.insert(id, tracked);
+            report.created.push(id);
+            local_changed = true;
+          }
+          None => {
+            report.errors.insert(id, "收藏记录缺少条目信息".to_string());
+          }
+        }
+      }
+      Some(local_state) if local_state == remote_state => report.unchanged += 1,
+      Some(local_state) => {
+        local_changed |= resolve_conflict(
+          &client,
+          &token,
+          strategy,
+          local.get_mut(&id),
+          SyncConflict {
+            id,
+            local: local_state.to_string(),
+            remote: remote_state.to_string(),
+          },
+          &mut report,
+        )
+        .await;
+      }
+    }
+  }
+
+  if local_changed {
+    persist_tracked(app, &local)?;
+  }
+  Ok(report)
+}
@@ -0,0 +1,234 @@
+/*
+  筛选标签分类表（类型 / 地区 / 受众）：
+  官方标签列表、别名映射以及 infobox 里对应的字段名，
+  保存在数据目录的 filter-taxonomy.toml 中，用户可以自行添加别名而无需重新编译。
+
+  - 启动时加载；文件不存在时写出内置默认值，方便用户在此基础上修改；
+  - 每次使用前检查文件修改时间，变化后自动重新加载（热更新）；
+  - 文件解析失败时继续使用上一次成功加载的内容（或内置默认值），错误可通过
+    reload_filter_taxonomy 命令查看。
+*/
+
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use crate::services::diagnostics::app_log;
+
+// 分类表文件名（位于数据目录下）。
+const TAXONOMY_FILE: &str = "filter-taxonomy.toml";
+
+// 分类表文件路径，启动时设置。
+static TAXONOMY_PATH: OnceCell<PathBuf> = OnceCell::new();
+
+// 当前生效的分类表。
+static TAXONOMY: Lazy<RwLock<LoadedTaxonomy>> = Lazy::new(|| {
+  RwLock::new(LoadedTaxonomy {
+    taxonomy: Arc::new(FilterTaxonomy::default()),
+    modified: None,
+    error: None,
+  })
+});
+
+/// 一个筛选分组（类型 / 地区 / 受众）。
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaxonomyGroup {
+  /// 官方规范标签。
+  pub official: Vec<String>,
+  /// infobox 中属于该分组的字段名（如 "类型"、"题材"）。
+  pub infobox_keys: Vec<String>,
+  /// 别名 -> 官方标签（如 "机甲" -> "机战"）。
+  /// 放在最后：TOML 里子表必须写在普通字段之后。
+  pub aliases: BTreeMap<String, String>,
+}
+
+/// 筛选标签分类表。
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FilterTaxonomy {
+  pub types: TaxonomyGroup,
+  pub regions: TaxonomyGroup,
+  pub audiences: TaxonomyGroup,
+}
+
+/// 重新加载分类表的结果。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterTaxonomyStatus {
+  /// 分类表文件路径（未初始化时为空）。
+  pub path: Option<String>,
+  /// 类型 / 地区 / 受众的官方标签数量。
+  pub types: usize,
+  pub regions: usize,
+  pub audiences: usize,
+  /// 三个分组的别名总数。
+  pub aliases: usize,
+  /// 最近一次加载失败的原因（此时仍在使用旧内容）。
+  pub error: Option<String>,
+}
+
+// 已加载的分类表及其文件修改时间。
+struct LoadedTaxonomy {
+  taxonomy: Arc<FilterTaxonomy>,
+  modified: Option<SystemTime>,
+  error: Option<String>,
+}
+
+// 把 &str 列表转换成 Vec<String>。
+fn strings(values: &[&str]) -> Vec<String> {
+  values.iter().map(|value| value.to_string()).collect()
+}
+
+// 把 (别名, 官方标签) 列表转换成映射。
+fn alias_map(values: &[(&str, &str)]) -> BTreeMap<String, String> {
+  values
+    .iter()
+    .map(|(from, to)| (from.to_string(), to.to_string()))
+    .collect()
+}
+
+// 内置默认值（文件不存在时写出，也作为解析失败时的兜底）。
+impl Default for FilterTaxonomy {
+  fn default() -> Self {
+    Self {
+      types: TaxonomyGroup {
+        official: strings(&[
+          "科幻", "喜剧", "同人", "百合", "校园", "惊悚", "后宫", "机战", "悬疑", "恋爱", "奇幻", "推理",
+          "运动", "耽美", "音乐", "战斗", "冒险", "萌系", "穿越", "玄幻", "乙女", "恐怖", "历史", "日常",
+          "剧情", "武侠", "美食", "职场",
+        ]),
+        infobox_keys: strings(&["类型", "题材", "动画类型", "分类", "类别"]),
+        aliases: alias_map(&[
+          ("搞笑", "喜剧"),
+          ("恋愛", "恋爱"),
+          ("日常系", "日常"),
+          ("熱血", "战斗"),
+          ("机甲", "机战"),
+          ("萌", "萌系"),
+        ]),
+      },
+      regions: TaxonomyGroup {
+        official: strings(&[
+          "日本", "欧美", "中国", "美国", "韩国", "法国", "中国香港", "英国", "俄罗斯", "苏联", "捷克",
+          "中国台湾", "马来西亚",
+        ]),
+        infobox_keys: strings(&["地区", "国家/地区", "国家地区", "国家", "发行地区"]),
+        aliases: alias_map(&[
+          ("大陆", "中国"),
+          ("中国大陆", "中国"),
+          ("香港", "中国香港"),
+          ("台湾", "中国台湾"),
+          ("欧美动画", "欧美"),
+          ("欧洲", "欧美"),
+          ("俄国", "俄罗斯"),
+        ]),
+      },
+      audiences: TaxonomyGroup {
+        official: strings(&["BL", "GL", "子供向", "女性向", "少女向", "少年向", "青年向"]),
+        infobox_keys: strings(&["受众", "对象", "读者对象"]),
+        aliases: alias_map(&[
+          ("少年", "少年向"),
+          ("少女", "少女向"),
+          ("青年", "青年向"),
+          ("儿童", "子供向"),
+          ("儿童向", "子供向"),
+          ("女性向", "女性向"),
+          ("男性向", "男性向"),
+          ("男向", "男性向"),
+          ("女向", "女性向"),
+          ("百合", "GL"),
+          ("耽美", "BL"),
+          ("腐向", "BL"),
+        ]),
+      },
+    }
+  }
+}
+
+// 读取文件修改时间。
+fn modified_time(path: &Path) -> Option<SystemTime> {
+  fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+// 从文件加载分类表。
+fn load_file(path: &Path) -> Result<FilterTaxonomy, String> {
+  let content = fs::read_to_string(path).map_err(|e| format!("读取筛选分类表失败: {e}"))?;
+  toml::from_str(&content).map_err(|e| format!("解析筛选分类表失败: {e}"))
+}
+
+// 重新加载文件并更新当前分类表；失败时保留旧内容并记录错误。
+fn reload_from(path: &Path) {
+  let modified = modified_time(path);
+  let result = load_file(path);
+  let mut loaded = TAXONOMY.write().unwrap_or_else(|e| e.into_inner());
+  loaded.modified = modified;
+  match result {
+    Ok(taxonomy) => {
+      loaded.taxonomy = Arc::new(taxonomy);
+      loaded.error = None;
+    }
+    Err(err) => {
+      app_log!("{err}");
+      loaded.error = Some(err);
+    }
+  }
+}
+
+// 记录分类表路径并加载（由 main.rs 在启动时调用）。
+// 文件不存在时先写出内置默认值。
+pub(crate) fn init_filter_taxonomy(dir: PathBuf) {
+  let path = dir.join(TAXONOMY_FILE);
+  if !path.exists() {
+    let content = toml::to_string_pretty(&FilterTaxonomy::default()).unwrap_or_default();
+    let result = fs::create_dir_all(&dir).and_then(|_| fs::write(&path, content));
+    if let Err(err) = result {
+      app_log!("写入默认筛选分类表失败: {err}");
+    }
+  }
+  if path.exists() {
+    reload_from(&path);
+  }
+  let _ = TAXONOMY_PATH.set(path);
+}
+
+// 获取当前分类表；文件修改时间变化时先重新加载。
+pub(crate) fn current_taxonomy() -> Arc<FilterTaxonomy> {
+  if let Some(path) = TAXONOMY_PATH.get() {
+    let modified = modified_time(path);
+    let changed = {
+      let loaded = TAXONOMY.read().unwrap_or_else(|e| e.into_inner());
+      modified.is_some() && loaded.modified != modified
+    };
+    if changed {
+      reload_from(path);
+    }
+  }
+  TAXONOMY
+    .read()
+    .unwrap_or_else(|e| e.into_inner())
+    .taxonomy
+    .clone()
+}
+
+// 立即重新加载分类表，返回加载后的统计信息。
+pub(crate) fn reload_taxonomy() -> FilterTaxonomyStatus {
+  let path = TAXONOMY_PATH.get();
+  if let Some(path) = path {
+    reload_from(path);
+  }
+  let loaded = TAXONOMY.read().unwrap_or_else(|e| e.into_inner());
+  let taxonomy = &loaded.taxonomy;
+  FilterTaxonomyStatus {
+    path: path.map(|path| path.to_string_lossy().to_string()),
+    types: taxonomy.types.official.len(),
+    regions: taxonomy.regions.official.len(),
+    audiences: taxonomy.audiences.official.len(),
+    aliases: taxonomy.types.aliases.len() + taxonomy.regions.aliases.len() + taxonomy.audiences.aliases.len(),
+    error: loaded.error.clone(),
+  }
+}
@@ -0,0 +1,173 @@
+/*
+  百度翻译（AI 文本翻译接口）。
+  密钥为 baidu.app_id 与 baidu.api_key（credentials.toml 或动态库）。
+  动态库可以嵌入多组密钥：某组当日额度用完（错误码 54003）时自动换下一组，
+  之后的请求都从新的这组开始，全部用完才报错。
+
+  接口按行返回译文（trans_result 的每一项对应原文的一行），
+  批量翻译时把多段文本合并成多行，按长度上限分块发送，从而减少请求次数。
+*/
+
+use reqwest::Client;
+use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::services::limits::send_limited;
+
+use super::{baidu_credential_pairs, TranslationProvider};
+
+/// 百度翻译。
+pub(crate) struct BaiduProvider;
+
+// 额度用完的错误码（当日字符数额度已用尽）。
+const QUOTA_EXCEEDED_CODE: &str = "54003";
+
+// 当前使用的密钥对序号（对应 baidu_credential_pairs 的位置）。
+static ACTIVE_CREDENTIAL: AtomicUsize = AtomicUsize::new(0);
+
+// 单次请求原文的长度上限（字节），低于接口限制留出余量。
+const MAX_REQUEST_BYTES: usize = 5000;
+
+// 用一组密钥调用百度翻译 API，把文本翻译成中文，返回按行排列的译文。
+// 失败时返回错误字符串。
+async fn request_baidu(client: &Client, app_id: &str, api_key: &str, text: &str) -> Result<Vec<String>, String> {
+
+  // 用闭包解析翻译结果，兼容多种返回结构。
+  let parse_translation = |body: &str| -> Result<Option<Vec<String>>, String> {
+    let value: Value = serde_json::from_str(body).map_err(|e| format!("响应解析失败: {e}"))?;
+    // 如果响应里有 error_code，说明请求失败。
+    if let Some(code) = value.get("error_code").and_then(|v| v.as_str()) {
+      let msg = value
+        .get("error_msg")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+      return Err(format!("百度翻译错误: {code} {msg}"));
+    }
+    // 尝试从列表里提取翻译结果。
+    let extract_list = |list: &Value| {
+      list.as_array()
+        .map(|items| {
+          items
+            .iter()
+            .filter_map(|item| item.get("dst").and_then(|v| v.as_str()))
+            .map(|line| line.to_string())
+            .collect::<Vec<String>>()
+        })
+        .filter(|lines| lines.iter().any(|line| !line.trim().is_empty()))
+    };
+
+    // 兼容不同字段名：trans_result / data / result.*
+    if let Some(output) = value.get("trans_result").and_then(extract_list) {
+      return Ok(Some(output));
+    }
+    if let Some(output) = value.get("data").and_then(extract_list) {
+      return Ok(Some(output));
+    }
+    if let Some(result) = value.get("result") {
+      if let Some(output) = result.get("trans_result").and_then(extract_list) {
+        return Ok(Some(output));
+      }
+      if let Some(output) = result.get("data").and_then(extract_list) {
+        return Ok(Some(output));
+      }
+    }
+    Ok(None)
+  };
+
+  // 构造请求体。
+  let ai_request = client
+    .post("https://fanyi-api.baidu.com/ait/api/aiTextTranslate")
+    .json(&serde_json::json!({
+      "appid": app_id,
+      "q": text,
+      "from": "auto",
+      "to": "zh",
+      "model_type": "llm"
+    }))
+    .bearer_auth(api_key);
+
+  // 发送请求。
+  let ai_response = send_limited(ai_request).await?;
+
+  // 状态码成功才解析响应体。
+  let status = ai_response.status();
+  if !status.is_success() {
+    return Err(format!("百度翻译请求失败: {status}"));
+  }
+  let body = ai_response.text().await.map_err(|e| e.to_string())?;
+  // 错误码（包括 52003 鉴权失败）原样返回，由上层决定是否换密钥或换服务。
+  match parse_translation(&body)? {
+    Some(output) => Ok(output),
+    None => Err("翻译结果为空".to_string()),
+  }
+}
+
+// 调用百度翻译，从当前密钥对开始依次尝试：
+// 额度用完就换下一组（并记住，之后的请求直接从这组开始），其它错误直接返回。
+async fn translate_baidu(client: &Client, text: &str) -> Result<Vec<String>, String> {
+  let pairs = baidu_credential_pairs()?;
+  let start = ACTIVE_CREDENTIAL.load(Ordering::Relaxed) % pairs.len();
+  let mut last_error = String::new();
+  for offset in 0..pairs.len() {
+    let index = (start + offset) % pairs.len();
+    let (app_id, api_key) = &pairs[index];
+    match request_baidu(client, app_id, api_key, text).await {
+      Err(err) if err.contains(QUOTA_EXCEEDED_CODE) => {
+        ACTIVE_CREDENTIAL.store((index + 1) % pairs.len(), Ordering::Relaxed);
+        last_error = err;
+      }
+      result => return result,
+    }
+  }
+  Err(format!("全部 {} 组百度翻译密钥额度已用完: {last_error}", pairs.len()))
+}
+
+impl TranslationProvider for BaiduProvider {
+  fn name(&self) -> &'static str {
+    "baidu"
+  }
+
+  async fn translate(&self, client: &Client, text: &str) -> Result<String, String> {
+    translate_baidu(client, text).await.map(|lines| lines.concat())
+  }
+
+  // 每段文本压成一行（内部换行替换为空格），按 MAX_REQUEST_BYTES 分块，
+  // 每块一次请求；返回的行数对不上时整块视为失败。
+  async fn translate_batch(&self, client: &Client, texts: &[String]) -> Vec<Result<String, String>> {
+    let lines: Vec<String> = texts
+      .iter()
+      .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+      .collect();
+
+    // 分块：记录每块在 lines 中的起止位置。
+    let mut chunks: Vec<(usize, usize)> = Vec::new();
+    let mut start = 0;
+    let mut bytes = 0;
+    for (index, line) in lines.iter().enumerate() {
+      let size = line.len() + 1;
+      if index > start && bytes + size > MAX_REQUEST_BYTES {
+        chunks.push((start, index));
+        start = index;
+        bytes = 0;
+      }
+      bytes += size;
+    }
+    if start < lines.len() {
+      chunks.push((start, lines.len()));
+    }
+
+    let mut results = Vec::with_capacity(lines.len());
+    for (start, end) in chunks {
+      let chunk = &lines[start..end];
+      match translate_baidu(client, &chunk.join("\n")).await {
+        Ok(output) if output.len() == chunk.len() => results.extend(output.into_iter().map(Ok)),
+        Ok(output) => {
+          let err = format!("译文行数不匹配: 原文 {} 行，译文 {} 行", chunk.len(), output.len());
+          results.extend(chunk.iter().map(|_| Err(err.clone())));
+        }
+        Err(err) => results.extend(chunk.iter().map(|_| Err(err.clone()))),
+      }
+    }
+    results
+  }
+}
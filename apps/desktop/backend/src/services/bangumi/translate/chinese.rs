@@ -0,0 +1,35 @@
+/*
+  中文繁简转换（本地完成，不调用任何接口）。
+  转换规则来自 zhconv（基于 OpenCC 与 MediaWiki 的词表），
+  用于把繁体简介、字幕转换成简体，或反过来。
+*/
+
+use std::str::FromStr;
+use zhconv::{is_hans, zhconv, Variant};
+
+// 把目标名称转换成 zhconv 的变体：
+// - "simplified" / "hans" → 简体；"traditional" / "hant" → 繁体；
+// - 其它按地区代码解析（如 "zh-cn" / "zh-tw" / "zh-hk"）。
+fn parse_target(target: &str) -> Result<Variant, String> {
+  match target.trim().to_ascii_lowercase().as_str() {
+    "simplified" | "hans" | "zh-hans" => Ok(Variant::ZhHans),
+    "traditional" | "hant" | "zh-hant" => Ok(Variant::ZhHant),
+    other => Variant::from_str(other).map_err(|_| format!("不支持的转换目标: {target}")),
+  }
+}
+
+// 把中文文本转换成指定的书写形式。
+pub(crate) fn convert_chinese_text(text: &str, target: &str) -> Result<String, String> {
+  let variant = parse_target(target)?;
+  Ok(zhconv(text, variant))
+}
+
+// 文本是否为繁体中文（调用方需先确认是中文）。
+pub(crate) fn is_traditional_chinese(text: &str) -> bool {
+  !is_hans(text)
+}
+
+// 转换成简体中文。
+pub(crate) fn to_simplified(text: &str) -> String {
+  zhconv(text, Variant::ZhHans)
+}
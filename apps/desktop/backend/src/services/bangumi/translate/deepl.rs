@@ -0,0 +1,61 @@
+/*
+  DeepL 翻译。
+  密钥为 deepl.api_key；免费版密钥以 ":fx" 结尾，走 api-free.deepl.com。
+*/
+
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::services::limits::send_limited;
+
+use super::{get_secret, TranslationProvider};
+
+// 付费版与免费版的接口地址。
+const DEEPL_API: &str = "https://api.deepl.com/v2/translate";
+const DEEPL_FREE_API: &str = "https://api-free.deepl.com/v2/translate";
+
+/// DeepL 翻译。
+pub(crate) struct DeepLProvider;
+
+#[derive(Deserialize)]
+struct DeepLResponse {
+  translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeepLTranslation {
+  text: String,
+}
+
+impl TranslationProvider for DeepLProvider {
+  fn name(&self) -> &'static str {
+    "deepl"
+  }
+
+  async fn translate(&self, client: &Client, text: &str) -> Result<String, String> {
+    let api_key = get_secret("deepl", "api_key")?;
+    let endpoint = if api_key.ends_with(":fx") { DEEPL_FREE_API } else { DEEPL_API };
+    let request = client
+      .post(endpoint)
+      .header("Authorization", format!("DeepL-Auth-Key {api_key}"))
+      .json(&serde_json::json!({
+        "text": [text],
+        "target_lang": "ZH-HANS",
+      }));
+    let response = send_limited(request).await?;
+    if !response.status().is_success() {
+      return Err(format!("DeepL 翻译请求失败: {}", response.status()));
+    }
+    let payload: DeepLResponse = response
+      .json()
+      .await
+      .map_err(|e| format!("响应解析失败: {e}"))?;
+    payload
+      .translations
+      .into_iter()
+      .map(|item| item.text)
+      .next()
+      .filter(|output| !output.trim().is_empty())
+      .ok_or_else(|| "翻译结果为空".to_string())
+  }
+}
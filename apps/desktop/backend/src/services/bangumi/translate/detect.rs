@@ -0,0 +1,95 @@
+/*
+  语言识别：判断一段文本是什么语言，并给出置信度。
+  主要依靠 whatlang（按字符分布与三元组统计），
+  中日混排时按假名所占比例区分，而不是“出现一个假名就算日文”；
+  文本太短、whatlang 给不出结果时，退回按字符范围粗略判断。
+*/
+
+use serde::Serialize;
+use whatlang::Lang;
+
+// 退回按字符范围判断时使用的置信度（表示“只是猜测”）。
+const FALLBACK_CONFIDENCE: f64 = 0.3;
+
+/// 识别出的语言。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedLanguage {
+  /// 语言代码：常见语言用两位代码（zh / ja / ko / en），其它为 ISO 639-3 代码。
+  pub code: String,
+  /// 语言名称（中文）。
+  pub name: String,
+  /// 置信度（0~1）。
+  pub confidence: f64,
+}
+
+impl DetectedLanguage {
+  fn new(code: &str, name: &str, confidence: f64) -> Self {
+    Self {
+      code: code.to_string(),
+      name: name.to_string(),
+      confidence,
+    }
+  }
+}
+
+// 判断文本是否包含日文假名。
+fn contains_kana(text: &str) -> bool {
+  text.chars()
+    .any(|ch| matches!(ch as u32, 0x3040..=0x30FF | 0x31F0..=0x31FF))
+}
+
+// 判断文本是否包含中日韩统一表意文字（汉字）。
+fn contains_cjk(text: &str) -> bool {
+  text.chars().any(|ch| matches!(ch as u32, 0x4E00..=0x9FFF | 0x3400..=0x4DBF))
+}
+
+// 判断文本是否包含韩文字母。
+fn contains_hangul(text: &str) -> bool {
+  text.chars()
+    .any(|ch| matches!(ch as u32, 0xAC00..=0xD7AF | 0x1100..=0x11FF | 0x3130..=0x318F))
+}
+
+// 把 whatlang 的语言转换成输出结构。
+fn from_lang(lang: Lang, confidence: f64) -> DetectedLanguage {
+  match lang {
+    Lang::Cmn => DetectedLanguage::new("zh", "中文", confidence),
+    Lang::Jpn => DetectedLanguage::new("ja", "日语", confidence),
+    Lang::Kor => DetectedLanguage::new("ko", "韩语", confidence),
+    Lang::Eng => DetectedLanguage::new("en", "英语", confidence),
+    other => DetectedLanguage::new(other.code(), other.eng_name(), confidence),
+  }
+}
+
+// 按字符范围粗略判断（whatlang 没有结果时使用）。
+fn detect_by_script(text: &str) -> Option<DetectedLanguage> {
+  if contains_hangul(text) {
+    return Some(DetectedLanguage::new("ko", "韩语", FALLBACK_CONFIDENCE));
+  }
+  if contains_kana(text) {
+    return Some(DetectedLanguage::new("ja", "日语", FALLBACK_CONFIDENCE));
+  }
+  if contains_cjk(text) {
+    return Some(DetectedLanguage::new("zh", "中文", FALLBACK_CONFIDENCE));
+  }
+  None
+}
+
+// 识别文本语言；空文本或无法识别时返回 None。
+pub(crate) fn detect_language(text: &str) -> Option<DetectedLanguage> {
+  let text = text.trim();
+  if text.is_empty() {
+    return None;
+  }
+  match whatlang::detect(text) {
+    Some(info) => Some(from_lang(info.lang(), info.confidence())),
+    None => detect_by_script(text),
+  }
+}
+
+// 判断一段文本是否是中文（简体或繁体）。
+pub(crate) fn is_chinese_text(text: &str) -> bool {
+  detect_language(text)
+    .map(|language| language.code == "zh")
+    .unwrap_or(false)
+}
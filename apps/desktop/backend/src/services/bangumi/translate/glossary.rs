@@ -0,0 +1,123 @@
+/*
+  翻译术语表：用户维护的“原文术语 → 指定中文译名”，保存在数据目录的 translation-glossary.json。
+  用于让角色名、专有名词在各处的译法保持一致：
+
+  - 翻译前：把原文里出现的术语直接替换成指定译名（翻译服务会原样保留中文）；
+  - 翻译后：译文里如果还残留术语原文，再替换一次；
+  - 较长的术语优先替换，避免短术语把长术语拆开。
+*/
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::RwLock;
+
+use super::CREDENTIALS_DIR;
+
+// 术语表文件名（位于数据目录下）。
+const GLOSSARY_FILE: &str = "translation-glossary.json";
+
+// 已加载的术语表（首次使用时从文件读取）。
+static GLOSSARY: Lazy<RwLock<BTreeMap<String, String>>> = Lazy::new(|| RwLock::new(load_glossary()));
+
+/// 术语表中的一项。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlossaryEntry {
+  pub term: String,
+  pub translation: String,
+}
+
+// 从文件读取术语表；文件不存在或损坏时从空表开始。
+fn load_glossary() -> BTreeMap<String, String> {
+  CREDENTIALS_DIR
+    .get()
+    .and_then(|dir| fs::read_to_string(dir.join(GLOSSARY_FILE)).ok())
+    .and_then(|content| serde_json::from_str(&content).ok())
+    .unwrap_or_default()
+}
+
+// 写回术语表文件。
+fn persist_glossary(glossary: &BTreeMap<String, String>) -> Result<(), String> {
+  let dir = CREDENTIALS_DIR
+    .get()
+    .ok_or_else(|| "数据目录未初始化".to_string())?;
+  let payload = serde_json::to_string_pretty(glossary).map_err(|e| format!("序列化术语表失败: {e}"))?;
+  fs::create_dir_all(dir).map_err(|e| format!("创建数据目录失败: {e}"))?;
+  fs::write(dir.join(GLOSSARY_FILE), payload).map_err(|e| format!("写入术语表失败: {e}"))
+}
+
+// 把术语表转换成输出列表。
+fn to_entries(glossary: &BTreeMap<String, String>) -> Vec<GlossaryEntry> {
+  glossary
+    .iter()
+    .map(|(term, translation)| GlossaryEntry {
+      term: term.clone(),
+      translation: translation.clone(),
+    })
+    .collect()
+}
+
+// 返回全部术语。
+pub(crate) fn list_glossary() -> Vec<GlossaryEntry> {
+  to_entries(&GLOSSARY.read().unwrap_or_else(|e| e.into_inner()))
+}
+
+// 新增或修改一个术语，返回修改后的术语表。
+pub(crate) fn add_glossary_entry(term: &str, translation: &str) -> Result<Vec<GlossaryEntry>, String> {
+  let term = term.trim();
+  let translation = translation.trim();
+  if term.is_empty() || translation.is_empty() {
+    return Err("术语和译名都不能为空".to_string());
+  }
+  let mut glossary = GLOSSARY.write().unwrap_or_else(|e| e.into_inner());
+  glossary.insert(term.to_string(), translation.to_string());
+  persist_glossary(&glossary)?;
+  Ok(to_entries(&glossary))
+}
+
+// 删除一个术语，返回修改后的术语表；术语不存在时报错。
+pub(crate) fn remove_glossary_entry(term: &str) -> Result<Vec<GlossaryEntry>, String> {
+  let mut glossary = GLOSSARY.write().unwrap_or_else(|e| e.into_inner());
+  if glossary.remove(term.trim()).is_none() {
+    return Err(format!("术语不存在: {}", term.trim()));
+  }
+  persist_glossary(&glossary)?;
+  Ok(to_entries(&glossary))
+}
+
+// 文本中出现的术语，按术语长度从长到短排列。
+fn matched_terms(text: &str) -> Vec<(String, String)> {
+  let glossary = GLOSSARY.read().unwrap_or_else(|e| e.into_inner());
+  let mut matched: Vec<(String, String)> = glossary
+    .iter()
+    .filter(|(term, _)| text.contains(term.as_str()))
+    .map(|(term, translation)| (term.clone(), translation.clone()))
+    .collect();
+  matched.sort_by_key(|(term, _)| Reverse(term.chars().count()));
+  matched
+}
+
+// 把文本中的术语替换成指定译名。
+// 翻译前作用于原文，翻译后再作用于译文（处理翻译服务没有保留的情况）。
+pub(crate) fn apply_glossary(text: &str) -> String {
+  matched_terms(text)
+    .into_iter()
+    .fold(text.to_string(), |output, (term, translation)| output.replace(&term, &translation))
+}
+
+// 翻译缓存使用的原文标识：原文 + 其中出现的术语及译名。
+// 修改术语表后，只有包含相关术语的文本会重新翻译。
+pub(crate) fn cache_source(text: &str) -> String {
+  matched_terms(text)
+    .into_iter()
+    .fold(text.to_string(), |mut output, (term, translation)| {
+      output.push('\u{0}');
+      output.push_str(&term);
+      output.push('=');
+      output.push_str(&translation);
+      output
+    })
+}
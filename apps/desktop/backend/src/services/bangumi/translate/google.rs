@@ -0,0 +1,68 @@
+/*
+  Google Cloud Translation（v2 基础版）。
+  密钥为 google.api_key。
+*/
+
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::services::limits::send_limited;
+
+use super::{get_secret, TranslationProvider};
+
+// 接口地址。
+const GOOGLE_API: &str = "https://translation.googleapis.com/language/translate/v2";
+
+/// Google 翻译。
+pub(crate) struct GoogleProvider;
+
+#[derive(Deserialize)]
+struct GoogleResponse {
+  data: GoogleData,
+}
+
+#[derive(Deserialize)]
+struct GoogleData {
+  translations: Vec<GoogleTranslation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleTranslation {
+  translated_text: String,
+}
+
+impl TranslationProvider for GoogleProvider {
+  fn name(&self) -> &'static str {
+    "google"
+  }
+
+  async fn translate(&self, client: &Client, text: &str) -> Result<String, String> {
+    let api_key = get_secret("google", "api_key")?;
+    // format=text：按纯文本处理，避免换行被当成 HTML 丢掉、引号被转义。
+    let request = client
+      .post(GOOGLE_API)
+      .query(&[("key", api_key.as_str())])
+      .json(&serde_json::json!({
+        "q": text,
+        "target": "zh-CN",
+        "format": "text",
+      }));
+    let response = send_limited(request).await?;
+    if !response.status().is_success() {
+      return Err(format!("Google 翻译请求失败: {}", response.status()));
+    }
+    let payload: GoogleResponse = response
+      .json()
+      .await
+      .map_err(|e| format!("响应解析失败: {e}"))?;
+    payload
+      .data
+      .translations
+      .into_iter()
+      .map(|item| item.translated_text)
+      .next()
+      .filter(|output| !output.trim().is_empty())
+      .ok_or_else(|| "翻译结果为空".to_string())
+  }
+}
@@ -0,0 +1,615 @@
+/*
+  这个文件负责“翻译相关功能”，核心目标：
+  1) 判断文本是什么语言、是否已经是中文（detect.rs）；
+  2) 通过翻译服务把非中文内容翻成中文。
+  翻译服务统一实现 TranslationProvider 接口（百度 / DeepL / Google / OpenAI 兼容接口），
+  按设置里的首选服务 + 回退顺序依次尝试，前一个失败（包括没有配置密钥）就换下一个。
+  翻译前后都会套用用户的术语表（glossary.rs），保证角色名等专有名词译法一致。
+  繁体中文不需要翻译，直接在本地转换成简体（chinese.rs）。
+  各服务的密钥不是明文写在代码里，而是通过动态库读取，
+  这样可以避免密钥直接暴露在仓库中。
+  动态库只返回混淆后的字节与校验值，这里用混淆密钥还原，校验通过才使用。
+  如果应用数据目录下存在 credentials.toml，会优先使用其中的密钥，
+  方便用户填入自己的密钥而无需重新构建动态库。
+*/
+
+mod baidu;
+mod chinese;
+mod deepl;
+mod detect;
+mod glossary;
+mod google;
+mod openai;
+
+use libloading::{Library, Symbol};
+use once_cell::sync::{Lazy, OnceCell};
+use reqwest::Client;
+use std::env;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs;
+use std::os::raw::c_char;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::services::checksum::fnv1a;
+use crate::services::settings::AppSettings;
+
+use baidu::BaiduProvider;
+use deepl::DeepLProvider;
+use google::GoogleProvider;
+use openai::OpenAiProvider;
+
+pub(crate) use glossary::{add_glossary_entry, cache_source, list_glossary, remove_glossary_entry, GlossaryEntry};
+use glossary::apply_glossary;
+pub(crate) use chinese::{convert_chinese_text, is_traditional_chinese, to_simplified};
+pub(crate) use detect::{detect_language, is_chinese_text, DetectedLanguage};
+
+// 支持的翻译服务（同时也是默认的回退顺序）。
+pub(crate) const TRANSLATION_PROVIDERS: &[&str] = &["baidu", "deepl", "google", "openai"];
+
+// 当前的尝试顺序：首选服务在前，其余按回退顺序排列；启动与保存设置时更新。
+static PROVIDER_ORDER: Lazy<RwLock<Vec<String>>> =
+  Lazy::new(|| RwLock::new(TRANSLATION_PROVIDERS.iter().map(|name| name.to_string()).collect()));
+
+// 翻译服务：把文本翻译成简体中文。
+// 没有配置密钥、请求失败或结果为空时返回 Err，由调用方换下一个服务。
+pub(crate) trait TranslationProvider {
+  fn name(&self) -> &'static str;
+  async fn translate(&self, client: &Client, text: &str) -> Result<String, String>;
+
+  // 批量翻译，返回与输入一一对应的结果。
+  // 默认逐条调用 translate；接口支持一次翻译多段文本的服务可以覆盖它以减少请求次数。
+  async fn translate_batch(&self, client: &Client, texts: &[String]) -> Vec<Result<String, String>> {
+    let mut results = Vec::with_capacity(texts.len());
+    for text in texts {
+      results.push(self.translate(client, text).await);
+    }
+    results
+  }
+}
+
+// 密钥覆盖文件名（位于应用数据目录下）。
+const CREDENTIALS_FILE: &str = "credentials.toml";
+
+// 覆盖文件中“混淆值”的前缀：enc:<十六进制>。
+const OBFUSCATED_PREFIX: &str = "enc:";
+
+// 覆盖文件混淆值使用的 XOR 密钥。
+// 这只是混淆而不是加密：目的只是让文件里不出现明文，防止被随手翻看，拿到程序就能还原。
+const CREDENTIALS_XOR_KEY: &[u8] = b"HanamiRIP-CN/credentials";
+
+// 应用数据目录，启动时由 init_credentials_dir 设置。
+// 未设置时（例如初始化失败）直接跳过覆盖文件，只用动态库里的密钥。
+static CREDENTIALS_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+// 记录密钥覆盖文件所在目录。
+// 只需在应用启动时调用一次，重复调用会被忽略。
+pub(crate) fn init_credentials_dir(dir: PathBuf) {
+  let _ = CREDENTIALS_DIR.set(dir);
+}
+
+// 把明文混淆成覆盖文件可用的 "enc:<hex>" 形式。
+pub(crate) fn obfuscate_plain_credential(value: &str) -> String {
+  let hex: String = value
+    .bytes()
+    .enumerate()
+    .map(|(i, b)| format!("{:02x}", b ^ CREDENTIALS_XOR_KEY[i % CREDENTIALS_XOR_KEY.len()]))
+    .collect();
+  format!("{OBFUSCATED_PREFIX}{hex}")
+}
+
+// 保存设置里的密码时使用：已经是合法混淆值的原样保留，其它按明文混淆，重复保存不会叠加混淆。
+pub(crate) fn obfuscate_credential_value(value: String) -> String {
+  if value.starts_with(OBFUSCATED_PREFIX) && deobfuscate_credential_value(&value).is_ok() {
+    value
+  } else {
+    obfuscate_plain_credential(&value)
+  }
+}
+
+// 解析覆盖文件中的一个值：
+// - "enc:" 开头：按十六进制解码后做 XOR 还原（十六进制部分必须是 ASCII，否则按字节切分会落在字符中间）；
+// - 其它：当作明文直接使用。
+pub(crate) fn deobfuscate_credential_value(raw: &str) -> Result<String, String> {
+  let hex = match raw.strip_prefix(OBFUSCATED_PREFIX) {
+    Some(hex) => hex,
+    None => return Ok(raw.to_string()),
+  };
+  if !hex.is_ascii() {
+    return Err("密钥覆盖值格式错误: 只能包含十六进制字符".to_string());
+  }
+  if hex.len() % 2 != 0 {
+    return Err("密钥覆盖值格式错误: 十六进制长度必须为偶数".to_string());
+  }
+  let bytes = (0..hex.len())
+    .step_by(2)
+    .enumerate()
+    .map(|(i, pos)| {
+      u8::from_str_radix(&hex[pos..pos + 2], 16)
+        .map(|b| b ^ CREDENTIALS_XOR_KEY[i % CREDENTIALS_XOR_KEY.len()])
+        .map_err(|e| format!("密钥覆盖值格式错误: {e}"))
+    })
+    .collect::<Result<Vec<u8>, String>>()?;
+  String::from_utf8(bytes).map_err(|e| format!("密钥覆盖值不是合法 UTF-8: {e}"))
+}
+
+// 从 credentials.toml 读取覆盖值。
+// 文件结构示例：
+//   [baidu]
+//   app_id = "enc:2a0f..."
+//   api_key = "明文也可以"
+// 文件不存在、没有该项或值为空时返回 Ok(None)，交给动态库兜底。
+pub(crate) fn read_credential_override(provider: &str, key: &str) -> Result<Option<String>, String> {
+  let dir = match CREDENTIALS_DIR.get() {
+    Some(dir) => dir,
+    None => return Ok(None),
+  };
+  let path = dir.join(CREDENTIALS_FILE);
+  if !path.exists() {
+    return Ok(None);
+  }
+  // 每次都重新读取，用户修改文件后无需重启应用。
+  let content = fs::read_to_string(&path).map_err(|e| format!("读取密钥覆盖文件失败: {e}"))?;
+  let table: toml::Table = content
+    .parse()
+    .map_err(|e| format!("解析密钥覆盖文件失败: {e}"))?;
+  let raw = table
+    .get(provider)
+    .and_then(|section| section.get(key))
+    .and_then(|value| value.as_str())
+    .map(|value| value.trim())
+    .filter(|value| !value.is_empty());
+  match raw {
+    Some(raw) => deobfuscate_credential_value(raw).map(Some),
+    None => Ok(None),
+  }
+}
+
+// 各翻译服务允许通过 set_translation_credentials 写入的字段。
+const TRANSLATION_CREDENTIAL_FIELDS: &[(&str, &[&str])] = &[
+  ("baidu", &["app_id", "api_key"]),
+  ("deepl", &["api_key"]),
+  ("google", &["api_key"]),
+  ("openai", &["api_key", "base_url", "model"]),
+];
+
+// 把用户提供的翻译服务密钥混淆后写入 credentials.toml（覆盖文件优先于动态库）。
+// 值为空表示删除该项；文件里其它服务的配置保持不变（注释不会保留）。
+// 返回写入后该服务已配置的字段名。
+pub(crate) fn write_translation_credentials(
+  provider: &str,
+  values: &HashMap<String, String>,
+) -> Result<Vec<String>, String> {
+  let fields = TRANSLATION_CREDENTIAL_FIELDS
+    .iter()
+    .find(|(name, _)| *name == provider)
+    .map(|(_, fields)| *fields)
+    .ok_or_else(|| format!("不支持的翻译服务: {provider}"))?;
+  if let Some(key) = values.keys().find(|key| !fields.contains(&key.as_str())) {
+    return Err(format!("{provider} 不支持的密钥字段: {key}"));
+  }
+  let dir = CREDENTIALS_DIR
+    .get()
+    .ok_or_else(|| "数据目录未初始化".to_string())?;
+  let path = dir.join(CREDENTIALS_FILE);
+
+  let mut table: toml::Table = if path.exists() {
+    fs::read_to_string(&path)
+      .map_err(|e| format!("读取密钥覆盖文件失败: {e}"))?
+      .parse()
+      .map_err(|e| format!("解析密钥覆盖文件失败: {e}"))?
+  } else {
+    toml::Table::new()
+  };
+  let mut section = match table.remove(provider) {
+    Some(toml::Value::Table(section)) => section,
+    _ => toml::Table::new(),
+  };
+  for (key, value) in values {
+    let value = value.trim();
+    if value.is_empty() {
+      section.remove(key);
+    } else {
+      section.insert(key.clone(), toml::Value::String(obfuscate_plain_credential(value)));
+    }
+  }
+  let configured: Vec<String> = fields
+    .iter()
+    .filter(|field| section.contains_key(**field))
+    .map(|field| field.to_string())
+    .collect();
+  if !section.is_empty() {
+    table.insert(provider.to_string(), toml::Value::Table(section));
+  }
+
+  let content = toml::to_string_pretty(&table).map_err(|e| format!("序列化密钥覆盖文件失败: {e}"))?;
+  fs::create_dir_all(dir).map_err(|e| format!("创建数据目录失败: {e}"))?;
+  fs::write(&path, content).map_err(|e| format!("写入密钥覆盖文件失败: {e}"))?;
+  Ok(configured)
+}
+
+// 本程序能识别的密钥存储格式版本，需要与 baidu_verify 的 SECRETS_VERSION 一致。
+const SUPPORTED_SECRETS_VERSION: u32 = 3;
+
+// 动态库返回的混淆值（与 baidu_verify 的 RawSecret 布局一致）。
+// data 为空指针表示密钥不存在。
+#[repr(C)]
+struct RawSecretC {
+  data: *const u8,
+  len: usize,
+  checksum: u64,
+}
+
+// 动态库返回的混淆百度密钥对（与 baidu_verify 的 RawBaiduCredential 布局一致）。
+#[repr(C)]
+struct RawBaiduCredentialC {
+  app_id: RawSecretC,
+  api_key: RawSecretC,
+}
+
+// 动态库导出的版本查询函数签名：secrets_version()。
+type SecretsVersionFn = unsafe extern "C" fn() -> u32;
+
+// 动态库导出的混淆密钥查询函数签名：secrets_nonce()。
+type SecretsNonceFn = unsafe extern "C" fn() -> RawSecretC;
+
+// 动态库导出的密钥读取函数签名：secret_get_raw(provider, key)。
+type SecretGetRawFn = unsafe extern "C" fn(*const c_char, *const c_char) -> RawSecretC;
+
+// 动态库导出的百度密钥对数量函数签名：baidu_get_credential_count()。
+type BaiduCredentialCountFn = unsafe extern "C" fn() -> u32;
+
+// 动态库导出的百度密钥对读取函数签名：baidu_get_credential_raw(index)。
+type BaiduCredentialRawFn = unsafe extern "C" fn(u32) -> RawBaiduCredentialC;
+
+// 通过动态库读取第三方服务密钥的结构体。
+// _lib 用来持有动态库句柄，防止被提前释放；nonce 为还原密钥用的混淆密钥。
+struct SecretStore {
+  _lib: Library,
+  nonce: Vec<u8>,
+  secret_get_raw: SecretGetRawFn,
+  baidu_credential_count: BaiduCredentialCountFn,
+  baidu_credential_raw: BaiduCredentialRawFn,
+}
+
+// 取出动态库返回的混淆字节（复制一份，不持有动态库内存的引用）。
+fn raw_bytes(raw: &RawSecretC) -> Vec<u8> {
+  unsafe { std::slice::from_raw_parts(raw.data, raw.len) }.to_vec()
+}
+
+// 还原一个混淆值并校验：密钥不存在返回 Ok(None)，校验失败返回错误，
+// 还原结果去掉首尾空白后为空时也视为不存在。
+fn decode_raw_secret(nonce: &[u8], raw: &RawSecretC) -> Result<Option<String>, String> {
+  if raw.data.is_null() {
+    return Ok(None);
+  }
+  let plain: Vec<u8> = raw_bytes(raw)
+    .into_iter()
+    .enumerate()
+    .map(|(i, b)| b ^ nonce[i % nonce.len()])
+    .collect();
+  if fnv1a(&plain) != raw.checksum {
+    return Err("密钥校验失败：动态库可能已损坏或被修改，请重新构建".to_string());
+  }
+  let value = String::from_utf8(plain).map_err(|e| format!("密钥不是合法 UTF-8: {e}"))?;
+  let value = value.trim();
+  Ok((!value.is_empty()).then(|| value.to_string()))
+}
+
+// OnceCell 确保动态库只加载一次（线程安全）。
+static SECRET_STORE: OnceCell<SecretStore> = OnceCell::new();
+
+// 加载密钥动态库，并解析出读取密钥的函数地址。
+// 返回静态引用，后续重复调用不会再次加载。
+fn load_secret_store() -> Result<&'static SecretStore, String> {
+  SECRET_STORE.get_or_try_init(|| {
+    #[cfg(target_os = "windows")]
+    let lib_name = "baidu_verify.dll";
+    #[cfg(target_os = "macos")]
+    let lib_name = "libbaidu_verify.dylib";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let lib_name = "libbaidu_verify.so";
+
+    // 动态库可能存在的路径候选列表。
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    // CARGO_MANIFEST_DIR 是编译期环境变量：当前 crate 的根目录。
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let root_dir = manifest_dir
+      .parent()
+      .and_then(|p| p.parent())
+      .and_then(|p| p.parent())
+      .unwrap_or(&manifest_dir);
+    {
+      // 根据不同系统拼接动态库文件名。
+      let platform_dir = if cfg!(target_os = "windows") {
+        "windows"
+      } else if cfg!(target_os = "macos") {
+        "macos"
+      } else {
+        "linux"
+      };
+      candidates.push(root_dir.join("dist").join("baidu_verify").join(platform_dir).join(lib_name));
+    }
+
+    // 用来保存“最后一次失败原因”，方便最终报错。
+    let mut last_error = None;
+    for candidate in candidates {
+      let path = if candidate.is_absolute() {
+        candidate
+      } else {
+        candidate
+          .canonicalize()
+          .unwrap_or(candidate)
+      };
+
+      // 路径不存在就跳过。
+      if !path.exists() {
+        continue;
+      }
+
+      // 这里是 unsafe：动态库加载可能失败，且需要手动管理。
+      match unsafe { Library::new(&path) } {
+        Ok(lib) => {
+          // 先确认动态库的存储格式版本，旧版动态库没有这个函数，同样视为不兼容。
+          let version = {
+            let symbol: Symbol<SecretsVersionFn> = unsafe { lib.get(b"secrets_version") }
+              .map_err(|_| "密钥动态库版本过旧，请重新构建".to_string())?;
+            unsafe { symbol() }
+          };
+          if version != SUPPORTED_SECRETS_VERSION {
+            return Err(format!(
+              "密钥动态库版本不兼容: 需要 {SUPPORTED_SECRETS_VERSION}，实际 {version}，请重新构建"
+            ));
+          }
+          // 取出混淆密钥，并确认它本身没有被改动。
+          let nonce = {
+            let symbol: Symbol<SecretsNonceFn> = unsafe { lib.get(b"secrets_nonce") }
+              .map_err(|e| format!("解析混淆密钥函数失败: {e}"))?;
+            let raw = unsafe { symbol() };
+            if raw.data.is_null() || raw.len == 0 {
+              return Err("密钥动态库缺少混淆密钥，请重新构建".to_string());
+            }
+            let nonce = raw_bytes(&raw);
+            if fnv1a(&nonce) != raw.checksum {
+              return Err("混淆密钥校验失败：动态库可能已损坏或被修改，请重新构建".to_string());
+            }
+            nonce
+          };
+          // 解析动态库里的函数指针：按服务名与字段名读取密钥、读取百度密钥对。
+          let secret_get_raw = {
+            let symbol: Symbol<SecretGetRawFn> = unsafe { lib.get(b"secret_get_raw") }
+              .map_err(|e| format!("解析密钥读取函数失败: {e}"))?;
+            *symbol
+          };
+          let baidu_credential_count = {
+            let symbol: Symbol<BaiduCredentialCountFn> = unsafe { lib.get(b"baidu_get_credential_count") }
+              .map_err(|e| format!("解析百度密钥数量函数失败: {e}"))?;
+            *symbol
+          };
+          let baidu_credential_raw = {
+            let symbol: Symbol<BaiduCredentialRawFn> = unsafe { lib.get(b"baidu_get_credential_raw") }
+              .map_err(|e| format!("解析百度密钥读取函数失败: {e}"))?;
+            *symbol
+          };
+          return Ok(SecretStore {
+            _lib: lib,
+            nonce,
+            secret_get_raw,
+            baidu_credential_count,
+            baidu_credential_raw,
+          });
+        }
+        Err(err) => {
+          // 记录失败原因，继续尝试下一个候选路径。
+          last_error = Some(format!("加载密钥动态库失败 ({}): {}", path.display(), err));
+        }
+      }
+    }
+
+    // 所有路径都失败时，返回最接近原因的错误。
+    if let Some(err) = last_error {
+      return Err(err);
+    }
+
+    // 彻底找不到动态库时给出构建提示。
+    Err(format!(
+      "找不到密钥动态库文件: {}\n\
+       请先构建动态库：\n\
+       1. 设置环境变量 BAIDU_TRANSLATE_APP_ID 和 BAIDU_TRANSLATE_API_KEY\n\
+       2. 运行命令: yarn build:baidu-dll (Windows)\n\
+       3. 确保生成的 {} 文件位于 dist/baidu_verify/<platform>/ 目录下",
+      lib_name, lib_name
+    ))
+  })
+}
+
+// 读取某个服务的某项密钥。
+// 例如 get_secret("baidu", "app_id")、get_secret("deepl", "api_key")。
+// 优先使用 credentials.toml 中的覆盖值，没有再从动态库读取；
+// 两处都没有该项（或编译时未提供）时返回错误。
+pub(crate) fn get_secret(provider: &str, key: &str) -> Result<String, String> {
+  if let Some(value) = read_credential_override(provider, key)? {
+    return Ok(value);
+  }
+  let store = load_secret_store()?;
+  // 参数需要转换成以 \0 结尾的 C 字符串才能传给动态库。
+  let provider_c = CString::new(provider).map_err(|e| format!("密钥服务名非法: {e}"))?;
+  let key_c = CString::new(key).map_err(|e| format!("密钥字段名非法: {e}"))?;
+  // 调用动态库函数得到混淆值，在这里还原并校验。
+  let raw = unsafe { (store.secret_get_raw)(provider_c.as_ptr(), key_c.as_ptr()) };
+  decode_raw_secret(&store.nonce, &raw)
+    .map_err(|err| format!("{provider}.{key}: {err}"))?
+    .ok_or_else(|| format!("未找到密钥: {provider}.{key}"))
+}
+
+// 读取全部可用的百度翻译密钥对 (app_id, api_key)，用于额度用完时轮换：
+// - credentials.toml 中的覆盖值（用户自己的密钥）排在最前；
+// - 其后是动态库嵌入的全部密钥对，校验失败的会被跳过；
+// - 重复的只保留一次。一组都没有时返回错误。
+pub(crate) fn baidu_credential_pairs() -> Result<Vec<(String, String)>, String> {
+  let mut pairs: Vec<(String, String)> = Vec::new();
+  if let (Some(app_id), Some(api_key)) = (
+    read_credential_override("baidu", "app_id")?,
+    read_credential_override("baidu", "api_key")?,
+  ) {
+    pairs.push((app_id, api_key));
+  }
+
+  let mut last_error = None;
+  let embedded: Vec<(String, String)> = match load_secret_store() {
+    Ok(store) => (0..unsafe { (store.baidu_credential_count)() })
+      .filter_map(|index| {
+        let credential = unsafe { (store.baidu_credential_raw)(index) };
+        let decoded = decode_raw_secret(&store.nonce, &credential.app_id).and_then(|app_id| {
+          decode_raw_secret(&store.nonce, &credential.api_key).map(|api_key| (app_id, api_key))
+        });
+        match decoded {
+          Ok((Some(app_id), Some(api_key))) => Some((app_id, api_key)),
+          Ok(_) => None,
+          Err(err) => {
+            last_error = Some(format!("百度密钥 #{index}: {err}"));
+            None
+          }
+        }
+      })
+      .collect(),
+    // 已经有覆盖值时，动态库缺失不算错误。
+    Err(err) if pairs.is_empty() => return Err(err),
+    Err(_) => Vec::new(),
+  };
+  for pair in embedded {
+    if !pairs.contains(&pair) {
+      pairs.push(pair);
+    }
+  }
+
+  if pairs.is_empty() {
+    return Err(last_error.unwrap_or_else(|| "未找到密钥: baidu.app_id / baidu.api_key".to_string()));
+  }
+  Ok(pairs)
+}
+
+// 应用翻译设置（启动时与保存设置时调用）：
+// 尝试顺序为 首选服务 → translation_fallbacks，重复与不认识的服务名会被忽略。
+pub(crate) fn apply_translation_settings(settings: &AppSettings) {
+  let mut order: Vec<String> = Vec::new();
+  for name in std::iter::once(&settings.translation_provider).chain(settings.translation_fallbacks.iter()) {
+    if TRANSLATION_PROVIDERS.contains(&name.as_str()) && !order.contains(name) {
+      order.push(name.clone());
+    }
+  }
+  *PROVIDER_ORDER.write().unwrap_or_else(|e| e.into_inner()) = order;
+}
+
+// 调用某个翻译服务，错误信息前加上服务名。
+async fn run_provider<P: TranslationProvider>(provider: P, client: &Client, text: &str) -> Result<String, String> {
+  provider
+    .translate(client, text)
+    .await
+    .map_err(|err| format!("{}: {err}", provider.name()))
+}
+
+// 用指定的翻译服务翻译（不回退，用于测试某个服务的密钥）。
+pub(crate) async fn translate_with(provider: &str, client: &Client, text: &str) -> Result<String, String> {
+  match provider {
+    "baidu" => run_provider(BaiduProvider, client, text).await,
+    "deepl" => run_provider(DeepLProvider, client, text).await,
+    "google" => run_provider(GoogleProvider, client, text).await,
+    "openai" => run_provider(OpenAiProvider, client, text).await,
+    _ => Err(format!("不支持的翻译服务: {provider}")),
+  }
+}
+
+// 用指定的翻译服务批量翻译，错误信息前加上服务名。
+async fn translate_batch_with(provider: &str, client: &Client, texts: &[String]) -> Vec<Result<String, String>> {
+  let results = match provider {
+    "baidu" => BaiduProvider.translate_batch(client, texts).await,
+    "deepl" => DeepLProvider.translate_batch(client, texts).await,
+    "google" => GoogleProvider.translate_batch(client, texts).await,
+    "openai" => OpenAiProvider.translate_batch(client, texts).await,
+    _ => texts.iter().map(|_| Err("不支持的翻译服务".to_string())).collect(),
+  };
+  results
+    .into_iter()
+    .map(|result| result.map_err(|err| format!("{provider}: {err}")))
+    .collect()
+}
+
+// 批量翻译成中文，返回与输入一一对应的结果。
+// 按设置的顺序尝试各翻译服务：前一个服务失败的条目交给下一个服务重试，
+// 全部失败的条目带上每个服务的失败原因。
+pub(crate) async fn translate_batch_to_cn(client: &Client, texts: &[String]) -> Vec<Result<String, String>> {
+  let order = PROVIDER_ORDER.read().unwrap_or_else(|e| e.into_inner()).clone();
+  let texts: Vec<String> = texts.iter().map(|text| apply_glossary(text)).collect();
+  let mut results: Vec<Result<String, String>> = texts.iter().map(|_| Err(String::new())).collect();
+  for provider in &order {
+    let pending: Vec<usize> = (0..texts.len()).filter(|index| results[*index].is_err()).collect();
+    if pending.is_empty() {
+      break;
+    }
+    let batch: Vec<String> = pending.iter().map(|index| texts[*index].clone()).collect();
+    let outputs = translate_batch_with(provider, client, &batch).await;
+    for (index, output) in pending.into_iter().zip(outputs) {
+      results[index] = match (output, &results[index]) {
+        (Ok(text), _) => Ok(text),
+        (Err(err), Err(previous)) if !previous.is_empty() => Err(format!("{previous}; {err}")),
+        (Err(err), _) => Err(err),
+      };
+    }
+  }
+  results
+    .into_iter()
+    .map(|result| match result {
+      Ok(text) => Ok(apply_glossary(&text)),
+      Err(err) if err.is_empty() => Err("没有可用的翻译服务".to_string()),
+      Err(err) => Err(err),
+    })
+    .collect()
+}
+
+// 把文本翻译成中文：按设置的顺序依次尝试各翻译服务，返回第一个成功的结果。
+// 全部失败时把每个服务的失败原因合并成一条错误。
+pub(crate) async fn translate_to_cn(client: &Client, text: &str) -> Result<String, String> {
+  let order = PROVIDER_ORDER.read().unwrap_or_else(|e| e.into_inner()).clone();
+  let text = apply_glossary(text);
+  let mut errors = Vec::new();
+  for provider in &order {
+    match translate_with(provider, client, &text).await {
+      Ok(output) => return Ok(apply_glossary(&output)),
+      Err(err) => errors.push(err),
+    }
+  }
+  if errors.is_empty() {
+    return Err("没有可用的翻译服务".to_string());
+  }
+  Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn credential_value_round_trip() {
+    for value in ["", "plain-key", "密钥 with 中文"] {
+      let obfuscated = obfuscate_plain_credential(value);
+      assert!(obfuscated.starts_with(OBFUSCATED_PREFIX));
+      assert_eq!(deobfuscate_credential_value(&obfuscated).unwrap(), value);
+    }
+  }
+
+  #[test]
+  fn credential_value_without_prefix_is_plain() {
+    assert_eq!(deobfuscate_credential_value("abc").unwrap(), "abc");
+  }
+
+  #[test]
+  fn malformed_credential_values_are_rejected() {
+    // 非 ASCII 的十六进制部分不能按字节切分（以前会在字符中间 panic）。
+    assert!(deobfuscate_credential_value("enc:aé0").is_err());
+    assert!(deobfuscate_credential_value("enc:abc").is_err());
+    assert!(deobfuscate_credential_value("enc:zz").is_err());
+  }
+}
@@ -0,0 +1,76 @@
+/*
+  OpenAI 兼容接口翻译（/chat/completions）。
+  密钥为 openai.api_key；openai.base_url 可指向任意兼容服务（默认官方地址），
+  openai.model 可选（默认 DEFAULT_MODEL）。
+*/
+
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::services::limits::send_limited;
+
+use super::{get_secret, TranslationProvider};
+
+// 默认接口地址与模型。
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+// 系统提示词：只要译文，不要解释。
+const SYSTEM_PROMPT: &str = "你是动画简介翻译助手。把用户提供的文本翻译成简体中文，保留原有的换行，只输出译文。";
+
+/// OpenAI 兼容接口翻译。
+pub(crate) struct OpenAiProvider;
+
+#[derive(Deserialize)]
+struct ChatResponse {
+  choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+  message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+  content: Option<String>,
+}
+
+impl TranslationProvider for OpenAiProvider {
+  fn name(&self) -> &'static str {
+    "openai"
+  }
+
+  async fn translate(&self, client: &Client, text: &str) -> Result<String, String> {
+    let api_key = get_secret("openai", "api_key")?;
+    // base_url 与 model 不是必填项，读不到就用默认值。
+    let base_url = get_secret("openai", "base_url").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+    let model = get_secret("openai", "model").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+
+    let request = client
+      .post(format!("{}/chat/completions", base_url.trim_end_matches('/')))
+      .bearer_auth(&api_key)
+      .json(&serde_json::json!({
+        "model": model,
+        "temperature": 0.2,
+        "messages": [
+          { "role": "system", "content": SYSTEM_PROMPT },
+          { "role": "user", "content": text },
+        ],
+      }));
+    let response = send_limited(request).await?;
+    if !response.status().is_success() {
+      return Err(format!("OpenAI 兼容接口请求失败: {}", response.status()));
+    }
+    let payload: ChatResponse = response
+      .json()
+      .await
+      .map_err(|e| format!("响应解析失败: {e}"))?;
+    payload
+      .choices
+      .into_iter()
+      .find_map(|choice| choice.message.content)
+      .map(|output| output.trim().to_string())
+      .filter(|output| !output.is_empty())
+      .ok_or_else(|| "翻译结果为空".to_string())
+  }
+}
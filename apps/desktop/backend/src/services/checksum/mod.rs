@@ -0,0 +1,198 @@
+/*
+  文件校验：
+  - 计算文件的 CRC32 / MD5 / SHA-1，可以一次读取同时计算多种；
+  - 用于核对下载文件与发布页给出的校验值，或计算字幕组命名习惯里嵌在文件名中的 CRC32
+    （例如 "[Group] Title - 01 [1080p][ABCD1234].mkv"），文件名里带 CRC32 时顺便比对；
+  - 大文件读取较慢，通过 checksum://progress 事件报告进度（最多每 250ms 一次，完成时再发送一次）；
+  - 读取放在阻塞线程池里执行，并受媒体任务并发上限约束（与混流、制作种子抢同一块磁盘）；
+  - 另外提供 FNV-1a 64 位哈希（fnv1a），供缓存文件名、密钥校验等需要跨版本稳定的小哈希共用。
+*/
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use super::diagnostics::app_log;
+use super::error::AppError;
+use super::limits::{acquire, LimitKind};
+use super::paths::ensure_path_allowed;
+
+// 进度事件名。
+const PROGRESS_EVENT: &str = "checksum://progress";
+// 读取缓冲区大小。
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+// 两次进度事件之间的最短间隔。
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 校验算法。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+  Crc32,
+  Md5,
+  Sha1,
+}
+
+/// hash_file 的结果，未请求的算法为 None；校验值均为小写十六进制。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHashes {
+  pub path: String,
+  pub size: u64,
+  pub crc32: Option<String>,
+  pub md5: Option<String>,
+  pub sha1: Option<String>,
+  /// 文件名里嵌入的 CRC32（小写），没有时为 None。
+  pub filename_crc32: Option<String>,
+  /// 计算出的 CRC32 是否与文件名里的一致（没有计算 CRC32 或文件名里没有时为 None）。
+  pub filename_crc32_matches: Option<bool>,
+}
+
+/// checksum://progress 事件内容。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HashProgressEvent {
+  pub path: String,
+  pub processed_bytes: u64,
+  pub total_bytes: u64,
+}
+
+// 同时计算的各个哈希。
+struct Hashers {
+  crc32: Option<crc32fast::Hasher>,
+  md5: Option<md5::Md5>,
+  sha1: Option<Sha1>,
+}
+
+impl Hashers {
+  fn new(algorithms: &[HashAlgorithm]) -> Self {
+    Self {
+      crc32: algorithms.contains(&HashAlgorithm::Crc32).then(crc32fast::Hasher::new),
+      md5: algorithms.contains(&HashAlgorithm::Md5).then(md5::Md5::new),
+      sha1: algorithms.contains(&HashAlgorithm::Sha1).then(Sha1::new),
+    }
+  }
+
+  fn update(&mut self, data: &[u8]) {
+    if let Some(hasher) = &mut self.crc32 {
+      hasher.update(data);
+    }
+    if let Some(hasher) = &mut self.md5 {
+      hasher.update(data);
+    }
+    if let Some(hasher) = &mut self.sha1 {
+      hasher.update(data);
+    }
+  }
+}
+
+// 字节转小写十六进制。
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+// FNV-1a 64 位哈希：结果跨版本稳定（用于缓存文件名、校验还原后的密钥等），不需要加密强度。
+// 算法不能改动：翻译密钥的校验值由 baidu_verify 按同样的算法生成。
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+    (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+  })
+}
+
+// 从文件名中找出嵌入的 CRC32：方括号或圆括号里的 8 位十六进制，取最后一个。
+pub(crate) fn filename_crc32(path: &Path) -> Option<String> {
+  let stem = path.file_stem()?.to_str()?;
+  stem
+    .split(['[', '('])
+    .skip(1)
+    .filter_map(|part| {
+      part
+        .split_once([']', ')'])
+        .map(|(inner, _)| inner.trim())
+    })
+    .filter(|inner| inner.len() == 8 && inner.chars().all(|c| c.is_ascii_hexdigit()))
+    .last()
+    .map(|crc| crc.to_ascii_lowercase())
+}
+
+// 读取文件并计算哈希，期间按间隔发送进度事件。
+fn compute(app: &tauri::AppHandle, path: &Path, algorithms: &[HashAlgorithm]) -> Result<FileHashes, AppError> {
+  let display = path.to_string_lossy().to_string();
+  let mut file = File::open(path).map_err(|e| AppError::io(format!("打开 {display} 失败: {e}")))?;
+  let total = file
+    .metadata()
+    .map_err(|e| AppError::io(format!("读取 {display} 失败: {e}")))?
+    .len();
+  let mut hashers = Hashers::new(algorithms);
+  let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+  let mut processed = 0u64;
+  let mut last_emit: Option<Instant> = None;
+  let report = |processed: u64| {
+    let event = HashProgressEvent {
+      path: display.clone(),
+      processed_bytes: processed,
+      total_bytes: total,
+    };
+    if let Err(err) = app.emit(PROGRESS_EVENT, &event) {
+      app_log!("发送校验进度事件失败: {err}");
+    }
+  };
+  loop {
+    let read = file
+      .read(&mut buffer)
+      .map_err(|e| AppError::io(format!("读取 {display} 失败: {e}")))?;
+    if read == 0 {
+      break;
+    }
+    hashers.update(&buffer[..read]);
+    processed += read as u64;
+    if last_emit.is_none_or(|time| time.elapsed() >= PROGRESS_EMIT_INTERVAL) {
+      last_emit = Some(Instant::now());
+      report(processed);
+    }
+  }
+  report(processed);
+
+  let crc32 = hashers.crc32.map(|hasher| format!("{:08x}", hasher.finalize()));
+  let filename_crc32 = filename_crc32(path);
+  let filename_crc32_matches = match (&crc32, &filename_crc32) {
+    (Some(actual), Some(expected)) => Some(actual == expected),
+    _ => None,
+  };
+  Ok(FileHashes {
+    path: display,
+    size: processed,
+    crc32,
+    md5: hashers.md5.map(|hasher| to_hex(&hasher.finalize())),
+    sha1: hashers.sha1.map(|hasher| to_hex(&hasher.finalize())),
+    filename_crc32,
+    filename_crc32_matches,
+  })
+}
+
+// 计算文件的校验值；algorithms 为空时计算全部算法。
+#[tauri::command]
+pub async fn hash_file(
+  app: tauri::AppHandle,
+  path: String,
+  algorithms: Vec<HashAlgorithm>,
+) -> Result<FileHashes, AppError> {
+  let path = ensure_path_allowed(&app, &path).map_err(AppError::invalid_input)?;
+  if !path.is_file() {
+    return Err(AppError::invalid_input(format!("文件不存在: {}", path.display())));
+  }
+  let algorithms = if algorithms.is_empty() {
+    vec![HashAlgorithm::Crc32, HashAlgorithm::Md5, HashAlgorithm::Sha1]
+  } else {
+    algorithms
+  };
+
+  let _job_permit = acquire(LimitKind::MediaJob).await?;
+  tauri::async_runtime::spawn_blocking(move || compute(&app, &path, &algorithms))
+    .await
+    .map_err(|e| AppError::internal(format!("计算校验值失败: {e}")))?
+}
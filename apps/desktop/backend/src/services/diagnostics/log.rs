@@ -0,0 +1,89 @@
+/*
+  运行日志：
+  - 每天一个文件（<数据目录>/logs/hanamirip-YYYY-MM-DD.log），按行追加；
+  - 启动时只保留最近 LOG_RETAIN_DAYS 个日志文件；
+  - 同时输出到控制台，日志目录未初始化时只输出到控制台。
+  后台任务的失败信息统一用 app_log! 记录，诊断包会附上最近的日志。
+*/
+
+use chrono::Local;
+use once_cell::sync::OnceCell;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// 日志文件名前缀与后缀。
+const LOG_PREFIX: &str = "hanamirip-";
+const LOG_SUFFIX: &str = ".log";
+// 保留的日志文件数量（每天一个）。
+const LOG_RETAIN_DAYS: usize = 14;
+
+// 日志目录（启动时设置）。
+static LOG_DIR: OnceCell<PathBuf> = OnceCell::new();
+// 串行化写入，避免多线程同时写时行内容交错。
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+// 记录一行运行日志，用法与 format! 相同。
+macro_rules! app_log {
+  ($($arg:tt)*) => {
+    $crate::services::diagnostics::write_log(&format!($($arg)*))
+  };
+}
+pub(crate) use app_log;
+
+// 设置日志目录并清理过期的日志文件。
+pub(crate) fn init_log_dir(dir: PathBuf) {
+  if let Err(err) = fs::create_dir_all(&dir) {
+    eprintln!("创建日志目录失败: {err}");
+    return;
+  }
+  prune_logs(&dir);
+  let _ = LOG_DIR.set(dir);
+}
+
+// 只保留最近的日志文件（文件名里的日期可以直接按字符串排序）。
+fn prune_logs(dir: &Path) {
+  let mut logs: Vec<PathBuf> = match fs::read_dir(dir) {
+    Ok(entries) => entries
+      .filter_map(|entry| entry.ok())
+      .map(|entry| entry.path())
+      .filter(|path| {
+        path
+          .file_name()
+          .and_then(|name| name.to_str())
+          .is_some_and(|name| name.starts_with(LOG_PREFIX) && name.ends_with(LOG_SUFFIX))
+      })
+      .collect(),
+    Err(_) => return,
+  };
+  if logs.len() <= LOG_RETAIN_DAYS {
+    return;
+  }
+  logs.sort();
+  let expired = logs.len() - LOG_RETAIN_DAYS;
+  for path in logs.into_iter().take(expired) {
+    let _ = fs::remove_file(path);
+  }
+}
+
+// 写入一行日志：输出到控制台，并追加到当天的日志文件。
+pub(crate) fn write_log(message: &str) {
+  eprintln!("{message}");
+  let Some(dir) = LOG_DIR.get() else {
+    return;
+  };
+  let now = Local::now();
+  let path = dir.join(format!("{LOG_PREFIX}{}{LOG_SUFFIX}", now.format("%Y-%m-%d")));
+  let line = format!("[{}] {message}\n", now.format("%Y-%m-%d %H:%M:%S"));
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let result = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(&path)
+    .and_then(|mut file| file.write_all(line.as_bytes()));
+  // 日志本身写不进去时只能留在控制台。
+  if let Err(err) = result {
+    eprintln!("写入日志失败 ({}): {err}", path.display());
+  }
+}
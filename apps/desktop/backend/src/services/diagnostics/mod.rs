@@ -0,0 +1,327 @@
+/*
+  诊断模块：
+  - 启动时安装 panic 钩子，崩溃时把现场信息写入 crash/last-crash.txt；
+  - 运行日志按天写入 logs/（见 log.rs）；
+  - export_diagnostics_bundle 把最近日志、健康检查结果、脱敏后的设置、
+    工具版本、系统信息和最后一次崩溃报告打包成一个 zip，方便用户反馈问题时附上。
+  打包内容里不包含密钥、代理账号密码等敏感信息，用户目录会替换成 "~"。
+*/
+
+mod log;
+
+use chrono::Local;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::env;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::Manager;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::error::AppError;
+use super::paths::ensure_path_allowed;
+use super::settings::load_settings;
+use super::setup::{check_tools, ToolStatus};
+use super::storage::load_tracked;
+use super::system::get_system_info;
+
+pub(crate) use log::{app_log, write_log};
+
+// 日志目录、崩溃报告目录（都在数据目录下）。
+const LOG_DIR: &str = "logs";
+const CRASH_DIR: &str = "crash";
+const CRASH_FILE: &str = "last-crash.txt";
+// 最多打包的日志文件数量，以及每个日志保留的末尾字节数。
+const MAX_LOG_FILES: usize = 5;
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// 单项健康检查结果。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+  pub name: String,
+  pub ok: bool,
+  pub detail: String,
+}
+
+/// 导出结果。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsBundleResponse {
+  pub path: String,
+  /// 打包进 zip 的文件名列表。
+  pub entries: Vec<String>,
+}
+
+// 应用数据目录（系统推荐的位置 / hanamirip-cn）。
+fn data_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+  app
+    .path()
+    .app_data_dir()
+    .map(|dir| dir.join("hanamirip-cn"))
+    .map_err(|e| format!("无法获取数据目录: {e}"))
+}
+
+// 安装 panic 钩子：崩溃时把时间、线程、位置、信息与调用栈写入崩溃报告，
+// 然后继续执行默认钩子（保留原有的控制台输出）。
+pub(crate) fn install_crash_reporter(app: &tauri::AppHandle) {
+  let dir = match data_root(app) {
+    Ok(dir) => dir.join(CRASH_DIR),
+    Err(_) => return,
+  };
+  let default_hook = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    let thread = std::thread::current();
+    let report = format!(
+      "time: {}\nversion: {}\nos: {}\nthread: {}\n{}\n\nbacktrace:\n{}\n",
+      Local::now().to_rfc3339(),
+      env!("CARGO_PKG_VERSION"),
+      env::consts::OS,
+      thread.name().unwrap_or("<unnamed>"),
+      info,
+      std::backtrace::Backtrace::force_capture()
+    );
+    // 钩子里不能再 panic，写入失败只能忽略。
+    let _ = fs::create_dir_all(&dir);
+    let _ = fs::write(dir.join(CRASH_FILE), report);
+    default_hook(info);
+  }));
+}
+
+// 启用运行日志：写入数据目录下的 logs/。
+pub(crate) fn init_log_writer(app: &tauri::AppHandle) {
+  match data_root(app) {
+    Ok(dir) => log::init_log_dir(dir.join(LOG_DIR)),
+    Err(err) => eprintln!("{err}"),
+  }
+}
+
+// 用户主目录（用于脱敏，把路径里的用户名替换掉）。
+fn home_dir() -> Option<String> {
+  env::var("USERPROFILE")
+    .or_else(|_| env::var("HOME"))
+    .ok()
+    .filter(|home| !home.is_empty())
+}
+
+// 把文本里的用户主目录替换成 "~"。
+// JSON 里的 Windows 路径反斜杠会被转义，所以转义后的形式也要替换。
+fn redact_home(text: &str) -> String {
+  match home_dir() {
+    Some(home) => text
+      .replace(&home.replace('\\', "\\\\"), "~")
+      .replace(&home, "~"),
+    None => text.to_string(),
+  }
+}
+
+// 去掉代理地址中的账号密码。
+fn redact_proxy(url: &str) -> String {
+  match url::Url::parse(url) {
+    Ok(mut parsed) => {
+      if !parsed.username().is_empty() || parsed.password().is_some() {
+        let _ = parsed.set_username("***");
+        let _ = parsed.set_password(None);
+      }
+      parsed.to_string()
+    }
+    Err(_) => "<无法解析>".to_string(),
+  }
+}
+
+// 读取设置并脱敏，返回格式化后的 JSON。
+fn sanitized_settings(app: &tauri::AppHandle) -> Result<String, String> {
+  let mut settings = load_settings(app)?;
+  settings.proxy_url = settings.proxy_url.as_deref().map(redact_proxy);
+  settings.proxy_username = settings.proxy_username.map(|_| "***".to_string());
+  settings.proxy_password = settings.proxy_password.map(|_| "***".to_string());
+  settings.torrent_session.proxy_url = settings.torrent_session.proxy_url.as_deref().map(redact_proxy);
+  settings.torrent_session.aria2_secret = settings.torrent_session.aria2_secret.map(|_| "***".to_string());
+  settings.torrent_session.qbittorrent_username =
+    settings.torrent_session.qbittorrent_username.map(|_| "***".to_string());
+  settings.torrent_session.qbittorrent_password =
+    settings.torrent_session.qbittorrent_password.map(|_| "***".to_string());
+  let json = serde_json::to_string_pretty(&settings).map_err(|e| format!("序列化设置失败: {e}"))?;
+  Ok(redact_home(&json))
+}
+
+// 健康检查：数据目录可写、设置与追番数据可读取、内置工具可用。
+fn run_health_checks(app: &tauri::AppHandle, tools: &[ToolStatus]) -> Vec<HealthCheck> {
+  let mut checks = Vec::new();
+
+  let writable = data_root(app).and_then(|dir| {
+    fs::create_dir_all(&dir).map_err(|e| format!("创建数据目录失败: {e}"))?;
+    let probe = dir.join(".write-test");
+    fs::write(&probe, b"ok").map_err(|e| format!("写入失败: {e}"))?;
+    let _ = fs::remove_file(&probe);
+    Ok(dir.to_string_lossy().to_string())
+  });
+  checks.push(to_check("数据目录可写", writable));
+
+  checks.push(to_check(
+    "设置可读取",
+    load_settings(app).map(|_| "正常".to_string()),
+  ));
+  checks.push(to_check(
+    "追番数据可读取",
+    load_tracked(app)
+      .map(|items| format!("{} 条记录", items.len()))
+      .map_err(|err| err.to_string()),
+  ));
+
+  for tool in tools {
+    checks.push(HealthCheck {
+      name: format!("工具 {}", tool.name),
+      ok: tool.available,
+      detail: tool
+        .version
+        .clone()
+        .or_else(|| tool.error.clone())
+        .unwrap_or_default(),
+    });
+  }
+
+  for check in checks.iter_mut() {
+    check.detail = redact_home(&check.detail);
+  }
+  checks
+}
+
+// 把检查结果转换成 HealthCheck。
+fn to_check(name: &str, result: Result<String, String>) -> HealthCheck {
+  match result {
+    Ok(detail) => HealthCheck {
+      name: name.to_string(),
+      ok: true,
+      detail,
+    },
+    Err(detail) => HealthCheck {
+      name: name.to_string(),
+      ok: false,
+      detail,
+    },
+  }
+}
+
+// 按修改时间倒序列出最近的日志文件。
+fn recent_logs(dir: &Path) -> Vec<PathBuf> {
+  let mut logs: Vec<(SystemTime, PathBuf)> = match fs::read_dir(dir) {
+    Ok(entries) => entries
+      .filter_map(|entry| entry.ok())
+      .filter(|entry| entry.path().is_file())
+      .filter_map(|entry| {
+        let modified = entry.metadata().and_then(|meta| meta.modified()).ok()?;
+        Some((modified, entry.path()))
+      })
+      .collect(),
+    Err(_) => Vec::new(),
+  };
+  logs.sort_by_key(|(modified, _)| Reverse(*modified));
+  logs.into_iter().take(MAX_LOG_FILES).map(|(_, path)| path).collect()
+}
+
+// 读取文件末尾最多 limit 字节（日志可能很大，只保留最近的部分）。
+fn read_tail(path: &Path, limit: u64) -> Result<Vec<u8>, AppError> {
+  let mut file = fs::File::open(path).map_err(|e| AppError::io(format!("读取日志失败: {e}")))?;
+  let len = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+  if len > limit {
+    file
+      .seek(SeekFrom::Start(len - limit))
+      .map_err(|e| AppError::io(format!("读取日志失败: {e}")))?;
+  }
+  let mut buf = Vec::new();
+  file
+    .read_to_end(&mut buf)
+    .map_err(|e| AppError::io(format!("读取日志失败: {e}")))?;
+  Ok(buf)
+}
+
+// 往 zip 里写入一个文件，并记录文件名。
+fn add_entry<W: Write + Seek>(
+  zip: &mut ZipWriter<W>,
+  entries: &mut Vec<String>,
+  name: &str,
+  content: &[u8],
+) -> Result<(), AppError> {
+  let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+  zip
+    .start_file(name, options)
+    .map_err(|e| AppError::io(format!("写入诊断包失败: {e}")))?;
+  zip
+    .write_all(content)
+    .map_err(|e| AppError::io(format!("写入诊断包失败: {e}")))?;
+  entries.push(name.to_string());
+  Ok(())
+}
+
+// 导出诊断包到 dest（zip 文件，没有后缀时自动补 .zip）。
+// dest 必须在允许访问的范围内（通常来自 pick_path 的保存对话框）。
+#[tauri::command]
+pub async fn export_diagnostics_bundle(
+  app: tauri::AppHandle,
+  dest: String,
+) -> Result<DiagnosticsBundleResponse, AppError> {
+  let mut dest_path = PathBuf::from(dest.trim());
+  if dest_path.extension().is_none() {
+    dest_path.set_extension("zip");
+  }
+  let dest_path = ensure_path_allowed(&app, &dest_path.to_string_lossy()).map_err(AppError::invalid_input)?;
+  let root = data_root(&app).map_err(AppError::io)?;
+
+  // 先收集内容（含异步检查），再统一写入 zip。
+  let tools = check_tools(app.clone()).await?;
+  let health = run_health_checks(&app, &tools);
+  let system = get_system_info(app.clone()).await?;
+  let settings = sanitized_settings(&app).unwrap_or_else(|err| format!("读取设置失败: {err}"));
+
+  let file = fs::File::create(&dest_path).map_err(|e| AppError::io(format!("创建诊断包失败: {e}")))?;
+  let mut zip = ZipWriter::new(file);
+  let mut entries = Vec::new();
+
+  let summary = format!(
+    "HanamiRIP CN 诊断包\n生成时间: {}\n版本: {}\n系统: {} ({})\n",
+    Local::now().to_rfc3339(),
+    env!("CARGO_PKG_VERSION"),
+    env::consts::OS,
+    env::consts::ARCH
+  );
+  add_entry(&mut zip, &mut entries, "summary.txt", summary.as_bytes())?;
+
+  let health_json = serde_json::to_string_pretty(&health).map_err(|e| AppError::internal(format!("序列化健康检查失败: {e}")))?;
+  add_entry(&mut zip, &mut entries, "health.json", health_json.as_bytes())?;
+
+  add_entry(&mut zip, &mut entries, "settings.json", settings.as_bytes())?;
+
+  let tools_json = serde_json::to_string_pretty(&tools).map_err(|e| AppError::internal(format!("序列化工具信息失败: {e}")))?;
+  add_entry(&mut zip, &mut entries, "tools.json", redact_home(&tools_json).as_bytes())?;
+
+  let system_json = serde_json::to_string_pretty(&system).map_err(|e| AppError::internal(format!("序列化系统信息失败: {e}")))?;
+  add_entry(&mut zip, &mut entries, "system.json", system_json.as_bytes())?;
+
+  for log in recent_logs(&root.join(LOG_DIR)) {
+    let name = match log.file_name() {
+      Some(name) => format!("logs/{}", name.to_string_lossy()),
+      None => continue,
+    };
+    let content = read_tail(&log, MAX_LOG_BYTES)?;
+    let content = redact_home(&String::from_utf8_lossy(&content));
+    add_entry(&mut zip, &mut entries, &name, content.as_bytes())?;
+  }
+
+  let crash = root.join(CRASH_DIR).join(CRASH_FILE);
+  if crash.exists() {
+    let content = fs::read_to_string(&crash).map_err(|e| AppError::io(format!("读取崩溃报告失败: {e}")))?;
+    add_entry(&mut zip, &mut entries, "crash/last-crash.txt", redact_home(&content).as_bytes())?;
+  }
+
+  zip
+    .finish()
+    .map_err(|e| AppError::io(format!("写入诊断包失败: {e}")))?;
+  Ok(DiagnosticsBundleResponse {
+    path: dest_path.to_string_lossy().to_string(),
+    entries,
+  })
+}
@@ -0,0 +1,183 @@
+/*
+  统一的命令错误类型：
+  命令返回 Result<_, AppError>，前端拿到的是 { code, message, ... } 对象，
+  可以按 code 区分处理（例如网络错误提示重试、缺少工具提示重新安装），
+  message 仍是给用户看的中文说明。
+
+  内部函数有不少还是 Result<_, String>，String 不能用 ? 直接转换成 AppError：
+  调用方要用 map_err 选定错误类别（网络 / 解析 / 文件读写 / 参数 ...），
+  确实无法归类的才用 AppError::internal。
+*/
+
+use serde::Serialize;
+use std::fmt;
+
+use super::bangumi::BangumiError;
+use super::media::format_bytes_readable;
+
+/// 命令错误。序列化为 { code: "变体名", message, ...附加字段 }。
+#[derive(Debug, Serialize)]
+#[serde(tag = "code")]
+pub enum AppError {
+  /// 网络请求失败（连接失败、超时等，没有拿到响应）。
+  NetworkError { message: String },
+  /// 远端接口返回了失败状态码。
+  ApiError { status: u16, message: String },
+  /// 找不到内置工具（ffmpeg / ffprobe / mkvmerge 等）。
+  ToolMissing { name: String, message: String },
+  /// 工具执行失败或输出无法解析。
+  ToolFailed { name: String, message: String },
+  /// 文件读写失败。
+  IoError { message: String },
+  /// 响应或数据文件的内容无法解析。
+  ParseError { message: String },
+  /// 翻译失败（所有翻译服务都不可用，或结果无效）。
+  TranslationError { message: String },
+  /// 下载引擎报错。
+  TorrentError { message: String },
+  /// 目标磁盘剩余空间不足（字节数）。
+  InsufficientSpace {
+    path: String,
+    required: u64,
+    available: u64,
+    message: String,
+  },
+  /// 参数不合法（包括未授权的路径）。
+  InvalidInput { message: String },
+  /// 其它错误。
+  Internal { message: String },
+}
+
+impl AppError {
+  pub(crate) fn network(message: impl Into<String>) -> Self {
+    AppError::NetworkError {
+      message: message.into(),
+    }
+  }
+
+  pub(crate) fn api(status: u16, message: impl Into<String>) -> Self {
+    AppError::ApiError {
+      status,
+      message: message.into(),
+    }
+  }
+
+  pub(crate) fn tool_missing(name: &str, message: impl Into<String>) -> Self {
+    AppError::ToolMissing {
+      name: name.to_string(),
+      message: message.into(),
+    }
+  }
+
+  pub(crate) fn tool_failed(name: &str, message: impl Into<String>) -> Self {
+    AppError::ToolFailed {
+      name: name.to_string(),
+      message: message.into(),
+    }
+  }
+
+  pub(crate) fn io(message: impl Into<String>) -> Self {
+    AppError::IoError {
+      message: message.into(),
+    }
+  }
+
+  pub(crate) fn parse(message: impl Into<String>) -> Self {
+    AppError::ParseError {
+      message: message.into(),
+    }
+  }
+
+  pub(crate) fn translation(message: impl Into<String>) -> Self {
+    AppError::TranslationError {
+      message: message.into(),
+    }
+  }
+
+  pub(crate) fn torrent(message: impl Into<String>) -> Self {
+    AppError::TorrentError {
+      message: message.into(),
+    }
+  }
+
+  pub(crate) fn insufficient_space(path: &str, required: u64, available: u64) -> Self {
+    AppError::InsufficientSpace {
+      path: path.to_string(),
+      required,
+      available,
+      message: format!(
+        "磁盘空间不足：{path} 需要 {}，剩余 {}",
+        format_bytes_readable(required),
+        format_bytes_readable(available)
+      ),
+    }
+  }
+
+  pub(crate) fn invalid_input(message: impl Into<String>) -> Self {
+    AppError::InvalidInput {
+      message: message.into(),
+    }
+  }
+
+  pub(crate) fn internal(message: impl Into<String>) -> Self {
+    AppError::Internal {
+      message: message.into(),
+    }
+  }
+
+  // 改写说明文字但保留错误类别（例如给错误加上是哪个条目出的错）。
+  pub(crate) fn map_message(mut self, f: impl FnOnce(&str) -> String) -> Self {
+    let message = match &mut self {
+      AppError::NetworkError { message }
+      | AppError::ApiError { message, .. }
+      | AppError::ToolMissing { message, .. }
+      | AppError::ToolFailed { message, .. }
+      | AppError::IoError { message }
+      | AppError::ParseError { message }
+      | AppError::TranslationError { message }
+      | AppError::TorrentError { message }
+      | AppError::InsufficientSpace { message, .. }
+      | AppError::InvalidInput { message }
+      | AppError::Internal { message } => message,
+    };
+    *message = f(message);
+    self
+  }
+
+  // 给用户看的说明文字。
+  pub(crate) fn message(&self) -> &str {
+    match self {
+      AppError::NetworkError { message }
+      | AppError::ApiError { message, .. }
+      | AppError::ToolMissing { message, .. }
+      | AppError::ToolFailed { message, .. }
+      | AppError::IoError { message }
+      | AppError::ParseError { message }
+      | AppError::TranslationError { message }
+      | AppError::TorrentError { message }
+      | AppError::InsufficientSpace { message, .. }
+      | AppError::InvalidInput { message }
+      | AppError::Internal { message } => message,
+    }
+  }
+}
+
+impl fmt::Display for AppError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.message())
+  }
+}
+
+impl std::error::Error for AppError {}
+
+// Bangumi 请求错误：有状态码的归为接口错误，否则为网络错误。
+impl From<BangumiError> for AppError {
+  fn from(err: BangumiError) -> Self {
+    match &err {
+      BangumiError::RetriesExhausted {
+        status: Some(status), ..
+      } => AppError::api(status.as_u16(), err.to_string()),
+      _ => AppError::network(err.to_string()),
+    }
+  }
+}
@@ -0,0 +1,624 @@
+/*
+  下载完成后的处理（后处理钩子）：
+  任务下载完成、文件移动到最终目录后（finalize_torrent_download）按顺序执行配置的步骤：
+  - rename：按文件名模板重命名视频与字幕文件（模板写法见 naming 模块）；
+  - move：把文件移动到媒体库目录（必须位于设置里配置的目录下）；
+  - parseTracks：解析视频文件的轨道，轨道数量写入日志（顺便确认文件完整可读）；
+  - command：运行用户指定的程序（必须位于设置里的工具目录下，见 paths 模块），参数中的占位符替换为目录、文件等（见 command_args）。
+  钩子可以按订阅设置（RssSubscription.hooks，订阅开始的下载自动带上），
+  也可以按任务设置（set_torrent_hooks），都以 info hash 保存在 torrent_hooks.json，开始执行后移除。
+  每次执行是一个作业：作业按加入顺序逐个执行，某一步失败时不再执行后面的步骤；
+  每一步的结果记在作业日志里，保存在 hook_jobs.json（只保留最近 MAX_JOBS 个），
+  状态变化时发送 hooks://job 事件。应用退出时未完成的作业，下次启动后标记为失败。
+  使用 qBittorrent 引擎时文件不在本机，不执行后处理。
+*/
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::process::Command;
+use tokio::sync::Mutex as AsyncMutex;
+
+use super::checksum::filename_crc32;
+use super::diagnostics::app_log;
+use super::error::AppError;
+use super::media::parse_media_tracks;
+use super::naming::{check_template, render, FilenameContext, FilenamePlatform};
+use super::paths::{ensure_path_configured, ensure_tool_program};
+use super::release::parse_release;
+use super::storage::{data_file, load_tracked};
+
+// 按任务保存的钩子（info hash -> 钩子）。
+const HOOKS_FILE: &str = "torrent_hooks.json";
+// 作业记录。
+const JOBS_FILE: &str = "hook_jobs.json";
+// 作业状态变化时发送给前端的事件名。
+const JOB_EVENT: &str = "hooks://job";
+// 最多保留的作业数。
+const MAX_JOBS: usize = 200;
+// 单次命令的运行时间上限。
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+// 命令输出写入日志时最多保留的字符数（取末尾）。
+const MAX_OUTPUT_CHARS: usize = 2000;
+// 重命名与解析轨道处理的文件类型。
+const VIDEO_EXTENSIONS: &[&str] = &["mkv", "mp4", "m4v", "avi", "webm", "ts", "m2ts"];
+const SUBTITLE_EXTENSIONS: &[&str] = &["ass", "ssa", "srt", "sup", "vtt"];
+
+// 钩子文件读-改-写期间加锁。
+static HOOKS_LOCK: Mutex<()> = Mutex::new(());
+// 作业列表；第一次使用时从文件读取（None 表示尚未读取）。
+static JOBS: Mutex<Option<Vec<HookJob>>> = Mutex::new(None);
+// 同一时间只执行一个作业（tokio 的锁按等待顺序获得，作业按加入顺序执行）。
+static RUN_LOCK: Lazy<AsyncMutex<()>> = Lazy::new(|| AsyncMutex::new(()));
+
+/// 一个后处理步骤。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PostDownloadHook {
+  /// 按文件名模板重命名视频与字幕文件（模板不含扩展名），例如 "{title} - {episode:02}"。
+  Rename { template: String },
+  /// 把文件移动到媒体库目录。
+  #[serde(rename_all = "camelCase")]
+  Move { target_dir: String },
+  /// 解析视频文件的轨道。
+  ParseTracks,
+  /// 运行指定的程序（绝对路径，必须位于设置里的工具目录下）。
+  Command {
+    program: String,
+    #[serde(default)]
+    args: Vec<String>,
+  },
+}
+
+/// 作业状态。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HookJobStatus {
+  Queued,
+  Running,
+  Succeeded,
+  Failed,
+}
+
+/// 一条作业日志。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HookLogEntry {
+  /// 记录时间（RFC 3339）。
+  pub time: String,
+  pub message: String,
+  pub error: bool,
+}
+
+/// 一个后处理作业（也是 hooks://job 事件的内容）。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HookJob {
+  pub id: u64,
+  pub info_hash: String,
+  /// 任务名称。
+  pub name: String,
+  pub subject_id: Option<u32>,
+  pub status: HookJobStatus,
+  /// 加入与结束时间（RFC 3339）。
+  pub created_at: String,
+  pub finished_at: Option<String>,
+  pub logs: Vec<HookLogEntry>,
+}
+
+// 作业的输入：下载完成的任务与文件。
+pub(crate) struct HookContext {
+  pub info_hash: String,
+  pub name: String,
+  pub subject_id: Option<u32>,
+  // 文件所在的最终目录。
+  pub folder: PathBuf,
+  // 选中下载的文件（绝对路径）。
+  pub files: Vec<PathBuf>,
+}
+
+// 执行过程中的状态：重命名、移动后文件路径会变化。
+struct JobState {
+  info_hash: String,
+  name: String,
+  // 关联的追番条目的 (原名, 中文名)，重命名模板优先使用。
+  subject: Option<(String, String)>,
+  folder: PathBuf,
+  files: Vec<PathBuf>,
+}
+
+// 读取按任务保存的钩子（文件不存在或为空时返回空表）。
+fn load_torrent_hooks(app: &tauri::AppHandle) -> Result<HashMap<String, Vec<PostDownloadHook>>, AppError> {
+  let path = data_file(app, HOOKS_FILE)?;
+  if !path.exists() {
+    return Ok(HashMap::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| AppError::io(format!("读取后处理设置失败: {e}")))?;
+  if content.trim().is_empty() {
+    return Ok(HashMap::new());
+  }
+  serde_json::from_str(&content).map_err(|e| AppError::io(format!("解析后处理设置失败: {e}")))
+}
+
+// 在文件锁内读取、修改并写回按任务保存的钩子。
+fn modify_torrent_hooks<T>(
+  app: &tauri::AppHandle,
+  f: impl FnOnce(&mut HashMap<String, Vec<PostDownloadHook>>) -> T,
+) -> Result<T, AppError> {
+  let _guard = HOOKS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let mut hooks = load_torrent_hooks(app)?;
+  let result = f(&mut hooks);
+  let path = data_file(app, HOOKS_FILE)?;
+  let payload = serde_json::to_string_pretty(&hooks).map_err(|e| AppError::internal(format!("序列化后处理设置失败: {e}")))?;
+  fs::write(&path, payload).map_err(|e| AppError::io(format!("写入后处理设置失败: {e}")))?;
+  Ok(result)
+}
+
+// 校验并整理钩子：去掉首尾空白，模板不能为空，移动目标必须位于设置里配置的目录下
+// （作业可能在重启后执行，对话框的临时授权那时已经失效），要运行的程序必须位于工具目录下
+// （程序不校验的话前端就能运行任意命令）。
+pub(crate) fn normalize_hooks(
+  app: &tauri::AppHandle,
+  hooks: Vec<PostDownloadHook>,
+) -> Result<Vec<PostDownloadHook>, AppError> {
+  hooks
+    .into_iter()
+    .map(|hook| {
+      Ok(match hook {
+        PostDownloadHook::Rename { template } => {
+          let template = template.trim().to_string();
+          if template.is_empty() {
+            return Err(AppError::invalid_input("重命名模板不能为空"));
+          }
+          check_template(&template).map_err(AppError::invalid_input)?;
+          PostDownloadHook::Rename { template }
+        }
+        PostDownloadHook::Move { target_dir } => {
+          let target_dir = ensure_path_configured(app, &target_dir)
+            .map_err(AppError::invalid_input)?
+            .to_string_lossy()
+            .to_string();
+          PostDownloadHook::Move { target_dir }
+        }
+        PostDownloadHook::ParseTracks => PostDownloadHook::ParseTracks,
+        PostDownloadHook::Command { program, args } => {
+          if program.trim().is_empty() {
+            return Err(AppError::invalid_input("要运行的程序不能为空"));
+          }
+          let program = ensure_program(app, &program).map_err(AppError::invalid_input)?;
+          PostDownloadHook::Command {
+            program: program.to_string_lossy().to_string(),
+            args,
+          }
+        }
+      })
+    })
+    .collect()
+}
+
+// 校验要运行的程序：必须是设置里的工具目录下已存在的文件，返回规范化后的路径。
+fn ensure_program(app: &tauri::AppHandle, program: &str) -> Result<PathBuf, String> {
+  let path = ensure_tool_program(app, program)?;
+  if !path.is_file() {
+    return Err(format!("要运行的程序不存在: {}", path.display()));
+  }
+  Ok(path)
+}
+
+// 为任务设置钩子（订阅开始下载时调用），hooks 为空时什么都不做。
+pub(crate) fn assign_hooks(
+  app: &tauri::AppHandle,
+  info_hash: &str,
+  hooks: &[PostDownloadHook],
+) -> Result<(), AppError> {
+  if hooks.is_empty() {
+    return Ok(());
+  }
+  modify_torrent_hooks(app, |all| {
+    all.insert(info_hash.to_lowercase(), hooks.to_vec());
+  })
+}
+
+// 锁定作业列表，第一次使用时从文件读取，并把上次运行时未完成的作业标记为失败。
+fn jobs(app: &tauri::AppHandle) -> Result<MutexGuard<'static, Option<Vec<HookJob>>>, AppError> {
+  let mut guard = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+  if guard.is_none() {
+    let path = data_file(app, JOBS_FILE)?;
+    let content = if path.exists() {
+      fs::read_to_string(&path).map_err(|e| AppError::io(format!("读取后处理记录失败: {e}")))?
+    } else {
+      String::new()
+    };
+    let mut loaded: Vec<HookJob> = if content.trim().is_empty() {
+      Vec::new()
+    } else {
+      serde_json::from_str(&content).map_err(|e| AppError::io(format!("解析后处理记录失败: {e}")))?
+    };
+    for job in &mut loaded {
+      if matches!(job.status, HookJobStatus::Queued | HookJobStatus::Running) {
+        job.status = HookJobStatus::Failed;
+        job.logs.push(log_entry("应用退出，作业未完成".to_string(), true));
+      }
+    }
+    *guard = Some(loaded);
+  }
+  Ok(guard)
+}
+
+// 修改作业列表并写回文件。
+fn modify_jobs<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut Vec<HookJob>) -> T) -> Result<T, AppError> {
+  let mut guard = jobs(app)?;
+  let all = guard.get_or_insert_with(Vec::new);
+  let result = f(all);
+  let overflow = all.len().saturating_sub(MAX_JOBS);
+  all.drain(..overflow);
+  let path = data_file(app, JOBS_FILE)?;
+  let payload = serde_json::to_string_pretty(all).map_err(|e| AppError::internal(format!("序列化后处理记录失败: {e}")))?;
+  fs::write(&path, payload).map_err(|e| AppError::io(format!("写入后处理记录失败: {e}")))?;
+  Ok(result)
+}
+
+fn log_entry(message: String, error: bool) -> HookLogEntry {
+  HookLogEntry {
+    time: Utc::now().to_rfc3339(),
+    message,
+    error,
+  }
+}
+
+// 修改某个作业并发送 hooks://job 事件（记录失败只打印，不影响执行）。
+fn update_job(app: &tauri::AppHandle, id: u64, f: impl FnOnce(&mut HookJob)) {
+  let updated = modify_jobs(app, |all| {
+    all.iter_mut().find(|job| job.id == id).map(|job| {
+      f(job);
+      job.clone()
+    })
+  });
+  match updated {
+    Ok(Some(job)) => {
+      if let Err(err) = app.emit(JOB_EVENT, &job) {
+        app_log!("发送后处理事件失败: {err}");
+      }
+    }
+    Ok(None) => {}
+    Err(err) => app_log!("记录后处理作业失败: {err}"),
+  }
+}
+
+fn append_log(app: &tauri::AppHandle, id: u64, message: String, error: bool) {
+  update_job(app, id, |job| job.logs.push(log_entry(message, error)));
+}
+
+// 文件扩展名（小写，不含点）。
+fn extension(path: &Path) -> String {
+  path
+    .extension()
+    .map(|ext| ext.to_string_lossy().to_lowercase())
+    .unwrap_or_default()
+}
+
+fn is_video(path: &Path) -> bool {
+  VIDEO_EXTENSIONS.contains(&extension(path).as_str())
+}
+
+fn is_subtitle(path: &Path) -> bool {
+  SUBTITLE_EXTENSIONS.contains(&extension(path).as_str())
+}
+
+// 拆分文件名为 (主名, 后缀)；字幕的语言后缀（例如 .chs.ass 中的 .chs）算在后缀里，重命名后保留。
+fn split_file_name(path: &Path) -> (String, String) {
+  let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+  let Some((stem, ext)) = name.rsplit_once('.') else {
+    return (name, String::new());
+  };
+  if is_subtitle(path) {
+    if let Some((base, lang)) = stem.rsplit_once('.') {
+      if (1..=7).contains(&lang.len()) && lang.chars().all(|c| c.is_ascii_alphabetic() || c == '-' || c == '_') {
+        return (base.to_string(), format!(".{lang}.{ext}"));
+      }
+    }
+  }
+  (stem.to_string(), format!(".{ext}"))
+}
+
+// 模板的字段：从原主名解析；关联了追番条目时 {title} 为条目名（优先中文名），
+// 条目有中文名时 {title_cn} 也改用条目的中文名；{crc32} 为原文件名里嵌入的 CRC32（重命名不改变文件内容）。
+fn template_context(path: &Path, stem: &str, subject: Option<&(String, String)>) -> FilenameContext {
+  let mut context = FilenameContext::from_release(stem, parse_release(stem));
+  if let Some((name, name_cn)) = subject {
+    if name_cn.trim().is_empty() {
+      context.title = Some(name.clone());
+    } else {
+      context.title = Some(name_cn.clone());
+      context.title_cn = Some(name_cn.clone());
+    }
+  }
+  context.crc32 = filename_crc32(path);
+  context
+}
+
+// 重命名视频与字幕文件，返回日志。
+fn rename_files(state: &mut JobState, template: &str) -> Result<Vec<String>, String> {
+  let mut logs = Vec::new();
+  for file in state.files.iter_mut() {
+    if !is_video(file) && !is_subtitle(file) {
+      continue;
+    }
+    let (stem, suffix) = split_file_name(file);
+    let context = template_context(file, &stem, state.subject.as_ref());
+    let name = render(template, &context, FilenamePlatform::current())?.name;
+    if name.is_empty() {
+      return Err(format!("按模板生成的文件名为空: {}", file.display()));
+    }
+    let dest = file.with_file_name(format!("{name}{suffix}"));
+    if dest == *file {
+      continue;
+    }
+    if dest.exists() {
+      return Err(format!("目标文件已存在: {}", dest.display()));
+    }
+    fs::rename(&*file, &dest).map_err(|e| format!("重命名 {} 失败: {e}", file.display()))?;
+    logs.push(format!("重命名: {} -> {}", file.display(), dest.display()));
+    *file = dest;
+  }
+  Ok(logs)
+}
+
+// 移动单个文件：先尝试重命名，不在同一磁盘时复制后删除源文件。
+fn move_file(source: &Path, dest: &Path) -> Result<(), String> {
+  if fs::rename(source, dest).is_ok() {
+    return Ok(());
+  }
+  fs::copy(source, dest).map_err(|e| format!("复制 {} 失败: {e}", source.display()))?;
+  fs::remove_file(source).map_err(|e| format!("删除 {} 失败: {e}", source.display()))
+}
+
+// 把文件移动到目标目录（不保留子目录结构），返回移动后的路径与日志。
+fn move_files(files: Vec<PathBuf>, target: PathBuf) -> Result<(Vec<PathBuf>, Vec<String>), String> {
+  fs::create_dir_all(&target).map_err(|e| format!("创建目录 {} 失败: {e}", target.display()))?;
+  let mut moved = Vec::new();
+  let mut logs = Vec::new();
+  for file in files {
+    let Some(name) = file.file_name() else {
+      continue;
+    };
+    let dest = target.join(name);
+    if dest.exists() {
+      return Err(format!("目标文件已存在: {}", dest.display()));
+    }
+    move_file(&file, &dest)?;
+    logs.push(format!("移动: {} -> {}", file.display(), dest.display()));
+    moved.push(dest);
+  }
+  Ok((moved, logs))
+}
+
+// 解析视频文件的轨道，返回每个文件的轨道数量。
+async fn parse_tracks(app: &tauri::AppHandle, state: &JobState) -> Result<Vec<String>, String> {
+  let mut logs = Vec::new();
+  for file in state.files.iter().filter(|file| is_video(file)) {
+    let path = file.to_string_lossy().to_string();
+    let mut counts = Vec::new();
+    for (kind, label) in [("video", "视频"), ("audio", "音频"), ("subtitle", "字幕")] {
+      let parsed = parse_media_tracks(app.clone(), path.clone(), kind.to_string())
+        .await
+        .map_err(|e| format!("解析 {} 失败: {e}", file.display()))?;
+      counts.push(format!("{label} {}", parsed.tracks.len()));
+    }
+    logs.push(format!("{}: {}", file.display(), counts.join(" / ")));
+  }
+  Ok(logs)
+}
+
+// 命令参数：替换 {dir}（文件所在目录）、{name}（任务名）、{infoHash}；
+// 有参数包含 {file} 时每个文件运行一次（替换为文件路径），否则只运行一次。
+fn command_args(args: &[String], state: &JobState) -> Vec<Vec<String>> {
+  let dir = state.folder.to_string_lossy().to_string();
+  let render = |arg: &String, file: Option<&Path>| {
+    let mut arg = arg
+      .replace("{dir}", &dir)
+      .replace("{name}", &state.name)
+      .replace("{infoHash}", &state.info_hash);
+    if let Some(file) = file {
+      arg = arg.replace("{file}", &file.to_string_lossy());
+    }
+    arg
+  };
+  if args.iter().any(|arg| arg.contains("{file}")) {
+    state
+      .files
+      .iter()
+      .map(|file| args.iter().map(|arg| render(arg, Some(file.as_path()))).collect())
+      .collect()
+  } else {
+    vec![args.iter().map(|arg| render(arg, None)).collect()]
+  }
+}
+
+// 只保留输出末尾的一段。
+fn output_tail(output: &[u8]) -> String {
+  let text = String::from_utf8_lossy(output);
+  let text = text.trim();
+  let count = text.chars().count();
+  text.chars().skip(count.saturating_sub(MAX_OUTPUT_CHARS)).collect()
+}
+
+// 运行命令，返回日志；程序不在工具目录下（设置已修改）、退出码不为 0 或超时视为失败。
+async fn run_command(
+  app: &tauri::AppHandle,
+  program: &str,
+  args: &[String],
+  state: &JobState,
+) -> Result<Vec<String>, String> {
+  let program_path = ensure_program(app, program)?;
+  let mut logs = Vec::new();
+  for args in command_args(args, state) {
+    let mut command = Command::new(&program_path);
+    command.args(&args).current_dir(&state.folder).kill_on_drop(true);
+    // Windows 下不要弹出控制台窗口。
+    #[cfg(target_os = "windows")]
+    {
+      const CREATE_NO_WINDOW: u32 = 0x08000000;
+      command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = tokio::time::timeout(COMMAND_TIMEOUT, command.output())
+      .await
+      .map_err(|_| format!("{program} 运行超时"))?
+      .map_err(|e| format!("运行 {program} 失败: {e}"))?;
+    let stdout = output_tail(&output.stdout);
+    if !output.status.success() {
+      let stderr = output_tail(&output.stderr);
+      return Err(format!("{program} 运行失败（{}）: {stderr}", output.status));
+    }
+    logs.push(if stdout.is_empty() {
+      format!("运行 {program} 完成")
+    } else {
+      format!("运行 {program} 完成: {stdout}")
+    });
+  }
+  Ok(logs)
+}
+
+// 执行单个步骤，返回日志。
+async fn run_step(
+  app: &tauri::AppHandle,
+  hook: &PostDownloadHook,
+  state: &mut JobState,
+) -> Result<Vec<String>, String> {
+  match hook {
+    PostDownloadHook::Rename { template } => rename_files(state, template),
+    PostDownloadHook::Move { target_dir } => {
+      let target = ensure_path_configured(app, target_dir)?;
+      // 跨磁盘移动可能耗时较长，放到阻塞线程池里执行。
+      let files = std::mem::take(&mut state.files);
+      let (moved, logs) = tauri::async_runtime::spawn_blocking({
+        let target = target.clone();
+        move || move_files(files, target)
+      })
+      .await
+      .map_err(|e| format!("移动文件失败: {e}"))??;
+      state.files = moved;
+      state.folder = target;
+      Ok(logs)
+    }
+    PostDownloadHook::ParseTracks => parse_tracks(app, state).await,
+    PostDownloadHook::Command { program, args } => run_command(app, program, args, state).await,
+  }
+}
+
+// 执行一个作业的全部步骤。
+async fn run_job(app: tauri::AppHandle, id: u64, hooks: Vec<PostDownloadHook>, mut state: JobState) {
+  let _running = RUN_LOCK.lock().await;
+  update_job(&app, id, |job| job.status = HookJobStatus::Running);
+  let mut status = HookJobStatus::Succeeded;
+  for hook in &hooks {
+    match run_step(&app, hook, &mut state).await {
+      Ok(logs) => {
+        for message in logs {
+          append_log(&app, id, message, false);
+        }
+      }
+      Err(err) => {
+        append_log(&app, id, err, true);
+        status = HookJobStatus::Failed;
+        break;
+      }
+    }
+  }
+  update_job(&app, id, |job| {
+    job.status = status;
+    job.finished_at = Some(Utc::now().to_rfc3339());
+  });
+}
+
+// 下载收尾后调用：任务设置了钩子时加入一个作业并在后台执行，没有设置时什么都不做。
+pub(crate) fn run_hooks(app: &tauri::AppHandle, context: HookContext) -> Result<(), AppError> {
+  let key = context.info_hash.to_lowercase();
+  let Some(hooks) = modify_torrent_hooks(app, |all| all.remove(&key))? else {
+    return Ok(());
+  };
+  let subject = match context.subject_id {
+    Some(subject_id) => load_tracked(app)?
+      .get(&subject_id)
+      .map(|subject| (subject.name.clone(), subject.name_cn.clone())),
+    None => None,
+  };
+  let job = modify_jobs(app, |all| {
+    let id = all.iter().map(|job| job.id).max().unwrap_or(0) + 1;
+    let job = HookJob {
+      id,
+      info_hash: context.info_hash.clone(),
+      name: context.name.clone(),
+      subject_id: context.subject_id,
+      status: HookJobStatus::Queued,
+      created_at: Utc::now().to_rfc3339(),
+      finished_at: None,
+      logs: Vec::new(),
+    };
+    all.push(job.clone());
+    job
+  })?;
+  if let Err(err) = app.emit(JOB_EVENT, &job) {
+    app_log!("发送后处理事件失败: {err}");
+  }
+  let state = JobState {
+    info_hash: context.info_hash,
+    name: context.name,
+    subject,
+    folder: context.folder,
+    files: context.files,
+  };
+  tauri::async_runtime::spawn(run_job(app.clone(), job.id, hooks, state));
+  Ok(())
+}
+
+// 返回任务设置的钩子（未设置时为空）。
+#[tauri::command]
+pub fn get_torrent_hooks(app: tauri::AppHandle, info_hash: String) -> Result<Vec<PostDownloadHook>, AppError> {
+  Ok(
+    load_torrent_hooks(&app)?
+      .remove(&info_hash.to_lowercase())
+      .unwrap_or_default(),
+  )
+}
+
+// 设置任务下载完成后执行的钩子（为空时清除），返回整理后的钩子。
+#[tauri::command]
+pub fn set_torrent_hooks(
+  app: tauri::AppHandle,
+  info_hash: String,
+  hooks: Vec<PostDownloadHook>,
+) -> Result<Vec<PostDownloadHook>, AppError> {
+  let hooks = normalize_hooks(&app, hooks)?;
+  let key = info_hash.trim().to_lowercase();
+  modify_torrent_hooks(&app, |all| {
+    if hooks.is_empty() {
+      all.remove(&key);
+    } else {
+      all.insert(key, hooks.clone());
+    }
+  })?;
+  Ok(hooks)
+}
+
+// 返回后处理作业（最新的在前）。
+#[tauri::command]
+pub fn list_hook_jobs(app: tauri::AppHandle) -> Result<Vec<HookJob>, AppError> {
+  let mut all = jobs(&app)?.clone().unwrap_or_default();
+  all.reverse();
+  Ok(all)
+}
+
+// 清除已结束的作业记录（排队中与执行中的保留）。
+#[tauri::command]
+pub fn clear_hook_jobs(app: tauri::AppHandle) -> Result<(), AppError> {
+  modify_jobs(&app, |all| {
+    all.retain(|job| matches!(job.status, HookJobStatus::Queued | HookJobStatus::Running));
+  })
+}
@@ -0,0 +1,147 @@
+/*
+  封面图片缓存：
+  把 SeasonAnime / TrackedSubject 里引用的封面图下载到数据目录（cache/images），
+  之后再次请求同一 URL 时直接使用本地文件，离线或 lain.bgm.tv 不稳定时也能显示封面。
+
+  - 文件名由 URL 的哈希生成，同一 URL 永远对应同一文件；
+  - 封面图不会变化，所以本地有文件就直接返回，不再联网；
+  - 先写临时文件再重命名，避免并发请求或中途失败留下半张图片；
+  - 前端可以拿文件路径，也可以要求直接返回 base64 的 data URL。
+*/
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use super::bangumi::shared_client;
+use super::checksum::fnv1a;
+use super::error::AppError;
+use super::limits::send_limited;
+
+// 图片缓存目录（数据目录下）。
+const IMAGE_CACHE_DIR: &str = "cache/images";
+// 单张图片的大小上限，超过则视为异常响应。
+const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// 图片缓存结果。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedImageResponse {
+  /// 本地文件路径。
+  pub path: String,
+  /// data URL（仅在请求 inline 时返回）。
+  pub data_url: Option<String>,
+  /// 是否命中已有缓存（false 表示本次刚下载）。
+  pub cached: bool,
+}
+
+// 图片缓存目录（应用数据目录 / hanamirip-cn / cache / images）。
+fn cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+  app
+    .path()
+    .app_data_dir()
+    .map(|dir| dir.join("hanamirip-cn").join(IMAGE_CACHE_DIR))
+    .map_err(|e| format!("无法获取数据目录: {e}"))
+}
+
+// 从 URL 路径中取图片后缀，未知时使用 img。
+fn image_extension(url: &url::Url) -> &'static str {
+  let path = url.path().to_ascii_lowercase();
+  ["jpg", "jpeg", "png", "webp", "gif"]
+    .into_iter()
+    .find(|ext| path.ends_with(&format!(".{ext}")))
+    .unwrap_or("img")
+}
+
+// 根据后缀推断 MIME 类型（用于 data URL）。
+fn mime_type(path: &Path) -> &'static str {
+  match path.extension().and_then(|ext| ext.to_str()) {
+    Some("png") => "image/png",
+    Some("webp") => "image/webp",
+    Some("gif") => "image/gif",
+    _ => "image/jpeg",
+  }
+}
+
+// 下载图片并写入 dest。
+async fn download_image(url: &url::Url, dest: &Path) -> Result<(), AppError> {
+  let client = shared_client();
+  let response = send_limited(client.get(url.as_str()))
+    .await
+    .map_err(|e| AppError::network(format!("下载图片失败: {e}")))?;
+  if !response.status().is_success() {
+    return Err(AppError::api(
+      response.status().as_u16(),
+      format!("下载图片失败: {}", response.status()),
+    ));
+  }
+  let is_image = response
+    .headers()
+    .get(reqwest::header::CONTENT_TYPE)
+    .and_then(|value| value.to_str().ok())
+    .map(|value| value.starts_with("image/"))
+    .unwrap_or(true);
+  if !is_image {
+    return Err(AppError::invalid_input("下载图片失败: 响应不是图片"));
+  }
+  let bytes = response
+    .bytes()
+    .await
+    .map_err(|e| AppError::network(format!("下载图片失败: {e}")))?;
+  if bytes.is_empty() || bytes.len() > MAX_IMAGE_BYTES {
+    return Err(AppError::invalid_input(format!(
+      "下载图片失败: 图片大小异常（{} 字节）",
+      bytes.len()
+    )));
+  }
+
+  if let Some(parent) = dest.parent() {
+    fs::create_dir_all(parent).map_err(|e| AppError::io(format!("创建图片缓存目录失败: {e}")))?;
+  }
+  let temp = dest.with_extension("part");
+  fs::write(&temp, &bytes).map_err(|e| AppError::io(format!("写入图片缓存失败: {e}")))?;
+  fs::rename(&temp, dest).map_err(|e| {
+    let _ = fs::remove_file(&temp);
+    AppError::io(format!("写入图片缓存失败: {e}"))
+  })
+}
+
+// 获取封面图的本地缓存：本地已有则直接返回，否则下载后返回。
+// inline 为 true 时同时返回 base64 data URL，前端可直接用作 <img src>。
+#[tauri::command]
+pub async fn get_cached_image(
+  app: tauri::AppHandle,
+  url: String,
+  inline: Option<bool>,
+) -> Result<CachedImageResponse, AppError> {
+  let parsed = url::Url::parse(url.trim()).map_err(|e| AppError::invalid_input(format!("图片地址无效: {e}")))?;
+  if parsed.scheme() != "http" && parsed.scheme() != "https" {
+    return Err(AppError::invalid_input("图片地址只支持 http/https"));
+  }
+
+  let path = cache_dir(&app).map_err(AppError::io)?.join(format!(
+    "{:016x}.{}",
+    fnv1a(parsed.as_str().as_bytes()),
+    image_extension(&parsed)
+  ));
+  let cached = path.is_file();
+  if !cached {
+    download_image(&parsed, &path).await?;
+  }
+
+  let data_url = if inline.unwrap_or(false) {
+    let bytes = fs::read(&path).map_err(|e| AppError::io(format!("读取图片缓存失败: {e}")))?;
+    Some(format!("data:{};base64,{}", mime_type(&path), BASE64.encode(bytes)))
+  } else {
+    None
+  };
+
+  Ok(CachedImageResponse {
+    path: path.to_string_lossy().to_string(),
+    data_url,
+    cached,
+  })
+}
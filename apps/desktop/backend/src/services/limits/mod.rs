@@ -0,0 +1,77 @@
+/*
+  并发限制模块：
+  用全局信号量（Semaphore）限制各类任务的同时运行数量，
+  所有服务共享同一组信号量，上限来自设置模块的 ConcurrencyLimits。
+
+  调整上限时直接换一个新的信号量：
+  已经拿到许可的任务继续持有旧信号量的许可，不受影响；
+  之后的新任务都按新上限排队。
+*/
+
+use once_cell::sync::Lazy;
+use reqwest::{RequestBuilder, Response};
+use std::sync::{Arc, RwLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::error::AppError;
+use super::settings::ConcurrencyLimits;
+
+/// 受限的任务类型。
+#[derive(Clone, Copy)]
+pub(crate) enum LimitKind {
+  /// HTTP 请求。
+  Http,
+  /// 媒体任务（混流等）。
+  MediaJob,
+  /// 种子初始化。
+  TorrentInit,
+}
+
+// 每类任务一个可替换的信号量，初始值取默认设置。
+static HTTP_LIMIT: Lazy<RwLock<Arc<Semaphore>>> =
+  Lazy::new(|| RwLock::new(Arc::new(Semaphore::new(ConcurrencyLimits::default().max_http_requests))));
+static MEDIA_JOB_LIMIT: Lazy<RwLock<Arc<Semaphore>>> =
+  Lazy::new(|| RwLock::new(Arc::new(Semaphore::new(ConcurrencyLimits::default().max_media_jobs))));
+static TORRENT_INIT_LIMIT: Lazy<RwLock<Arc<Semaphore>>> =
+  Lazy::new(|| RwLock::new(Arc::new(Semaphore::new(ConcurrencyLimits::default().max_torrent_inits))));
+
+// 根据任务类型取对应的信号量槽位。
+fn slot(kind: LimitKind) -> &'static RwLock<Arc<Semaphore>> {
+  match kind {
+    LimitKind::Http => &HTTP_LIMIT,
+    LimitKind::MediaJob => &MEDIA_JOB_LIMIT,
+    LimitKind::TorrentInit => &TORRENT_INIT_LIMIT,
+  }
+}
+
+// 用新的上限替换信号量。
+fn replace(kind: LimitKind, permits: usize) {
+  // 锁中毒只说明之前某个线程 panic 了，里面的数据仍然可用。
+  let mut guard = slot(kind).write().unwrap_or_else(|e| e.into_inner());
+  *guard = Arc::new(Semaphore::new(permits.max(1)));
+}
+
+// 应用新的并发上限（启动时与保存设置时调用）。
+pub(crate) fn apply_limits(limits: &ConcurrencyLimits) {
+  replace(LimitKind::Http, limits.max_http_requests);
+  replace(LimitKind::MediaJob, limits.max_media_jobs);
+  replace(LimitKind::TorrentInit, limits.max_torrent_inits);
+}
+
+// 获取某类任务的许可；达到上限时异步等待。
+// 返回的许可在 drop 时自动归还，调用方只需在任务期间持有它。
+pub(crate) async fn acquire(kind: LimitKind) -> Result<OwnedSemaphorePermit, AppError> {
+  // 先克隆出 Arc，再在锁外等待，避免持锁 await。
+  let semaphore = slot(kind).read().unwrap_or_else(|e| e.into_inner()).clone();
+  semaphore
+    .acquire_owned()
+    .await
+    .map_err(|e| AppError::internal(format!("获取并发许可失败: {e}")))
+}
+
+// 在 HTTP 并发上限内发送请求。
+// 等价于 request.send()，只是先排队拿到许可，错误统一转换成字符串。
+pub(crate) async fn send_limited(request: RequestBuilder) -> Result<Response, String> {
+  let _permit = acquire(LimitKind::Http).await.map_err(|e| e.to_string())?;
+  request.send().await.map_err(|e| e.to_string())
+}
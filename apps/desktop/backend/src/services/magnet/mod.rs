@@ -0,0 +1,153 @@
+/*
+  磁力链接处理模块：
+  应用注册为系统的 magnet: 协议处理程序（deep-link 插件 + 单实例插件），
+  在浏览器里点击磁力链接时：
+  - 应用未运行：系统启动应用，启动参数里的链接在 setup 中读取；
+  - 应用已运行：新启动的进程把链接转交给已运行的实例后退出。
+  两种情况都与 handle_magnet 命令走同一流程：解析链接中的元数据、放入待处理队列，
+  把主窗口切到前台，并发送 magnet-link 事件通知前端。
+
+  前端收到事件（以及启动时）调用 take_pending_magnets 取走队列，
+  打开下载流程并预填标题。这样即使链接在前端加载完成之前到达也不会丢失。
+*/
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use super::diagnostics::app_log;
+use super::error::AppError;
+
+// 发送给前端的事件名。
+const MAGNET_EVENT: &str = "magnet-link";
+
+/// 从磁力链接中解析出的元数据。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MagnetInfo {
+  /// 原始链接（开始下载时原样传给 start_torrent_download）。
+  pub uri: String,
+  /// 种子 info hash（xt=urn:btih: / urn:btmh: 之后的部分）。
+  pub info_hash: String,
+  /// 显示名称（dn），链接里没有时为空。
+  pub name: Option<String>,
+  /// 文件总大小（xl，字节），链接里没有时为空。
+  pub size: Option<u64>,
+  /// 链接自带的 tracker 列表（tr）。
+  pub trackers: Vec<String>,
+}
+
+// 尚未被前端取走的链接。
+static PENDING: Lazy<Mutex<Vec<MagnetInfo>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+// 解析磁力链接；不是 magnet: 链接或缺少 info hash 时返回错误。
+pub(crate) fn parse_magnet(uri: &str) -> Result<MagnetInfo, String> {
+  let uri = uri.trim();
+  let url = url::Url::parse(uri).map_err(|e| format!("磁力链接格式错误: {e}"))?;
+  if url.scheme() != "magnet" {
+    return Err(format!("不是磁力链接: {uri}"));
+  }
+
+  let mut info_hash = None;
+  let mut name = None;
+  let mut size = None;
+  let mut trackers = Vec::new();
+  for (key, value) in url.query_pairs() {
+    match key.as_ref() {
+      "xt" => {
+        let hash = value
+          .strip_prefix("urn:btih:")
+          .or_else(|| value.strip_prefix("urn:btmh:"));
+        if let Some(hash) = hash.filter(|hash| !hash.is_empty()) {
+          info_hash.get_or_insert_with(|| hash.to_lowercase());
+        }
+      }
+      "dn" => name = Some(value.trim().to_string()).filter(|name| !name.is_empty()),
+      "xl" => size = value.parse().ok(),
+      "tr" => trackers.push(value.into_owned()),
+      _ => {}
+    }
+  }
+
+  Ok(MagnetInfo {
+    uri: uri.to_string(),
+    info_hash: info_hash.ok_or_else(|| "磁力链接缺少 info hash (xt=urn:btih:...)".to_string())?,
+    name,
+    size,
+    trackers,
+  })
+}
+
+// 注册协议处理程序并接收系统转交的链接（应用启动时调用一次）。
+pub(crate) fn init_magnet_handler(app: &tauri::AppHandle) {
+  // 安装包会写入协议注册；开发模式下没有安装步骤，在运行时注册。
+  #[cfg(all(debug_assertions, any(windows, target_os = "linux")))]
+  if let Err(err) = app.deep_link().register_all() {
+    app_log!("注册 magnet: 协议失败: {err}");
+  }
+  // 应用运行期间收到的链接（包括其它实例转交过来的）。
+  let handle = app.clone();
+  app.deep_link().on_open_url(move |event| {
+    handle_incoming(&handle, event.urls().iter().map(|url| url.as_str()));
+  });
+  // 应用由点击链接启动时，链接在启动参数里。
+  if let Ok(Some(urls)) = app.deep_link().get_current() {
+    handle_incoming(app, urls.iter().map(|url| url.as_str()));
+  }
+}
+
+// 把主窗口切到前台（已最小化时先还原）。
+pub(crate) fn focus_main_window(app: &tauri::AppHandle) {
+  if let Some(window) = app.get_webview_window("main") {
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+  }
+}
+
+// 处理启动参数或 deep-link 传来的一组链接，忽略其中不是磁力链接的参数。
+pub(crate) fn handle_incoming<I, S>(app: &tauri::AppHandle, uris: I)
+where
+  I: IntoIterator<Item = S>,
+  S: AsRef<str>,
+{
+  for uri in uris {
+    let uri = uri.as_ref();
+    if !uri.trim_start().starts_with("magnet:") {
+      continue;
+    }
+    if let Err(err) = enqueue(app, uri) {
+      app_log!("处理磁力链接失败: {err}");
+    }
+  }
+}
+
+// 解析链接、放入待处理队列、切到主窗口并通知前端。
+fn enqueue(app: &tauri::AppHandle, uri: &str) -> Result<MagnetInfo, String> {
+  let info = parse_magnet(uri)?;
+  PENDING
+    .lock()
+    .unwrap_or_else(|e| e.into_inner())
+    .push(info.clone());
+  focus_main_window(app);
+  // 事件发送失败时链接仍在队列里，前端下次取队列时会处理。
+  if let Err(err) = app.emit(MAGNET_EVENT, &info) {
+    app_log!("发送磁力链接事件失败: {err}");
+  }
+  Ok(info)
+}
+
+// 处理一个磁力链接：与系统打开链接时走同一流程（前端随后通过事件打开下载流程）。
+#[tauri::command]
+pub fn handle_magnet(app: tauri::AppHandle, uri: String) -> Result<MagnetInfo, AppError> {
+  enqueue(&app, &uri).map_err(AppError::invalid_input)
+}
+
+// 取走所有待处理的磁力链接（按到达顺序）。
+#[tauri::command]
+pub fn take_pending_magnets() -> Result<Vec<MagnetInfo>, AppError> {
+  Ok(std::mem::take(
+    &mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()),
+  ))
+}
@@ -0,0 +1,344 @@
+/*
+  批量混流（整季一次完成）：
+  - 分别列出视频、音频、字幕目录下的文件（不递归，按扩展名筛选），用发布名解析器识别集数（可以带语言后缀），
+    文件名就是纯数字（例如 "01.ass"）时直接当作集数；
+  - 每一集的视频与同集数的音频、字幕文件组成一个混流作业，放进媒体作业队列；
+  - 每个文件的轨道用 mkvmerge -J 识别，选中该类型的全部轨道，并沿用文件里已有的语言标记；
+    某一集有外挂音频（字幕）时不再保留视频文件内的音频（字幕）轨道，否则原样保留；
+  - 识别不出集数、同一集有多个视频、找不到对应视频或没有任何可混入文件的，汇总到 unmatched 返回。
+*/
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use super::{queue_mix, resolve_tool_path, MixTrackInput, MkvmergeOutput};
+use crate::services::error::AppError;
+use crate::services::naming::{check_template, render, FilenameContext, FilenamePlatform};
+use crate::services::paths::ensure_path_allowed;
+use crate::services::release::parse_release;
+
+// 各类型文件的扩展名。
+const VIDEO_EXTENSIONS: &[&str] = &["mkv", "mp4", "avi", "ts", "m2ts", "webm"];
+const AUDIO_EXTENSIONS: &[&str] = &[
+  "mka", "flac", "aac", "ac3", "eac3", "dts", "opus", "m4a", "mp3", "wav", "thd",
+];
+const SUBTITLE_EXTENSIONS: &[&str] = &["ass", "ssa", "srt", "sup", "vtt", "mks"];
+// 默认的输出文件名模板。
+const DEFAULT_TEMPLATE: &str = "{name}";
+
+/// 一集的混流作业。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MixBatchJob {
+  pub episode: u32,
+  pub job_id: u64,
+  pub output_path: String,
+  pub video: String,
+  pub audio: Vec<String>,
+  pub subtitles: Vec<String>,
+}
+
+/// 没有参与混流的文件。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MixBatchUnmatched {
+  pub path: String,
+  /// 文件类型：video / audio / subtitle。
+  pub kind: String,
+  /// 原因（中文说明）。
+  pub reason: String,
+}
+
+/// mix_media_batch 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MixBatchResult {
+  /// 已放进作业队列的混流作业（按集数排序）。
+  pub jobs: Vec<MixBatchJob>,
+  pub unmatched: Vec<MixBatchUnmatched>,
+}
+
+// 识别出集数的文件。
+struct EpisodeFile {
+  path: PathBuf,
+  episode: u32,
+}
+
+// 列出目录下指定扩展名的文件（不递归），按文件名排序。
+fn list_files(dir: &Path, extensions: &[&str]) -> Result<Vec<PathBuf>, AppError> {
+  let entries = fs::read_dir(dir).map_err(|e| AppError::io(format!("读取目录 {} 失败: {e}", dir.display())))?;
+  let mut files = Vec::new();
+  for entry in entries {
+    let entry = entry.map_err(|e| AppError::io(format!("读取目录 {} 失败: {e}", dir.display())))?;
+    let path = entry.path();
+    let matched = path
+      .extension()
+      .and_then(|ext| ext.to_str())
+      .is_some_and(|ext| extensions.contains(&ext.to_lowercase().as_str()));
+    if matched && path.is_file() {
+      files.push(path);
+    }
+  }
+  files.sort();
+  Ok(files)
+}
+
+// 从文件名识别集数：先用发布名解析器，合集（集数范围）不算；文件名是纯数字时直接作为集数。
+// 识别不出时再去掉最后一段后缀重试（字幕常见 "01.chs.ass"、"[...][03].JPSC.ass" 这种带语言后缀的写法）。
+fn episode_of(path: &Path) -> Option<u32> {
+  let stem = path.file_stem()?.to_str()?.trim();
+  let mut candidates = vec![stem];
+  if let Some((base, _)) = stem.rsplit_once('.') {
+    candidates.push(base.trim());
+  }
+  for name in candidates {
+    let info = parse_release(name);
+    if let (Some(episode), None) = (info.episode, info.episode_end) {
+      return Some(episode);
+    }
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_digit()) {
+      return name.parse().ok();
+    }
+  }
+  None
+}
+
+// 列出一个目录下的文件并识别集数，识别不出的记入 unmatched。
+fn collect_episodes(
+  app: &tauri::AppHandle,
+  dir: &str,
+  kind: &str,
+  extensions: &[&str],
+  unmatched: &mut Vec<MixBatchUnmatched>,
+) -> Result<Vec<EpisodeFile>, AppError> {
+  let dir = ensure_path_allowed(app, dir).map_err(AppError::invalid_input)?;
+  if !dir.is_dir() {
+    return Err(AppError::invalid_input(format!("目录不存在: {}", dir.display())));
+  }
+  let mut files = Vec::new();
+  for path in list_files(&dir, extensions)? {
+    match episode_of(&path) {
+      Some(episode) => files.push(EpisodeFile { path, episode }),
+      None => unmatched.push(MixBatchUnmatched {
+        path: path.to_string_lossy().to_string(),
+        kind: kind.to_string(),
+        reason: "无法从文件名识别集数".to_string(),
+      }),
+    }
+  }
+  Ok(files)
+}
+
+// 按文件名模板生成输出文件名（不含扩展名）：字段从视频文件名解析，集数用配对时识别出的集数；
+// 输出文件还没有生成，{crc32} 为空。生成的文件名为空时使用两位集数。
+fn render_output_name(template: &str, video: &Path, episode: u32) -> Result<String, String> {
+  let stem = video
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let mut context = FilenameContext::from_release(&stem, parse_release(&stem));
+  context.episode = Some(episode);
+  let name = render(template, &context, FilenamePlatform::current())?.name;
+  Ok(if name.is_empty() { format!("{episode:02}") } else { name })
+}
+
+// 用 mkvmerge -J 识别文件里指定类型的轨道，返回轨道 ID 与文件里已有的语言标记（und 不算）。
+async fn identify_tracks(
+  mkvmerge_path: &Path,
+  path: &Path,
+  kind: &str,
+) -> Result<(Vec<String>, HashMap<String, String>), AppError> {
+  let output = Command::new(mkvmerge_path)
+    .arg("-J")
+    .arg(path)
+    .output()
+    .await
+    .map_err(|e| AppError::tool_failed("mkvmerge", format!("调用 mkvmerge 失败: {e}")))?;
+  if !output.status.success() {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    return Err(AppError::tool_failed(
+      "mkvmerge",
+      format!("mkvmerge 无法识别 {}: {stdout}", path.display()),
+    ));
+  }
+  let parsed: MkvmergeOutput = serde_json::from_slice(&output.stdout)
+    .map_err(|e| AppError::tool_failed("mkvmerge", format!("解析 mkvmerge 输出失败: {e}")))?;
+  let mut track_ids = Vec::new();
+  let mut track_langs = HashMap::new();
+  for track in parsed.tracks.unwrap_or_default() {
+    let track_type = track.r#type.as_deref().unwrap_or("");
+    let matched = match kind {
+      "subtitle" => track_type == "subtitles" || track_type == "subtitle",
+      _ => track_type == kind,
+    };
+    if !matched {
+      continue;
+    }
+    let track_id = track.id.to_string();
+    let lang = track
+      .properties
+      .and_then(|props| props.language_ietf.or(props.language))
+      .filter(|lang| !lang.is_empty() && lang != "und");
+    if let Some(lang) = lang {
+      track_langs.insert(track_id.clone(), lang);
+    }
+    track_ids.push(track_id);
+  }
+  Ok((track_ids, track_langs))
+}
+
+// 生成一个文件指定类型的混流输入；文件里没有该类型轨道时返回 None。
+async fn track_input(mkvmerge_path: &Path, path: &Path, kind: &str) -> Result<Option<MixTrackInput>, AppError> {
+  let (track_ids, track_langs) = identify_tracks(mkvmerge_path, path, kind).await?;
+  if track_ids.is_empty() {
+    return Ok(None);
+  }
+  Ok(Some(MixTrackInput {
+    path: path.to_string_lossy().to_string(),
+    kind: kind.to_string(),
+    track_ids,
+    track_langs,
+    track_options: HashMap::new(),
+  }))
+}
+
+// 按集数把多个目录里的文件配对，每集生成一个混流作业放进作业队列，
+// 返回已创建的作业与未参与混流的文件。音频与字幕目录至少提供一个。
+#[tauri::command]
+pub async fn mix_media_batch(
+  app: tauri::AppHandle,
+  video_dir: String,
+  audio_dir: Option<String>,
+  subtitle_dir: Option<String>,
+  output_dir: String,
+  template: Option<String>,
+) -> Result<MixBatchResult, AppError> {
+  let audio_dir = audio_dir.filter(|dir| !dir.trim().is_empty());
+  let subtitle_dir = subtitle_dir.filter(|dir| !dir.trim().is_empty());
+  if audio_dir.is_none() && subtitle_dir.is_none() {
+    return Err(AppError::invalid_input("请至少选择音频目录或字幕目录"));
+  }
+  let template = template
+    .map(|template| template.trim().to_string())
+    .filter(|template| !template.is_empty())
+    .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string());
+  check_template(&template).map_err(AppError::invalid_input)?;
+  let output_dir = ensure_path_allowed(&app, &output_dir).map_err(AppError::invalid_input)?;
+  let mkvmerge_path = resolve_tool_path(&app, "mkvmerge")?;
+
+  let mut unmatched = Vec::new();
+  let videos = collect_episodes(&app, &video_dir, "video", VIDEO_EXTENSIONS, &mut unmatched)?;
+  let mut extras: Vec<(&str, EpisodeFile)> = Vec::new();
+  if let Some(dir) = &audio_dir {
+    let files = collect_episodes(&app, dir, "audio", AUDIO_EXTENSIONS, &mut unmatched)?;
+    extras.extend(files.into_iter().map(|file| ("audio", file)));
+  }
+  if let Some(dir) = &subtitle_dir {
+    let files = collect_episodes(&app, dir, "subtitle", SUBTITLE_EXTENSIONS, &mut unmatched)?;
+    extras.extend(files.into_iter().map(|file| ("subtitle", file)));
+  }
+
+  // 每集只能有一个视频，同一集有多个视频时无法判断该用哪个，全部跳过。
+  let mut by_episode: BTreeMap<u32, Vec<PathBuf>> = BTreeMap::new();
+  for video in videos {
+    by_episode.entry(video.episode).or_default().push(video.path);
+  }
+  let mut episodes: BTreeMap<u32, (PathBuf, Vec<PathBuf>, Vec<PathBuf>)> = BTreeMap::new();
+  for (episode, paths) in by_episode {
+    match <[PathBuf; 1]>::try_from(paths) {
+      Ok([video]) => {
+        episodes.insert(episode, (video, Vec::new(), Vec::new()));
+      }
+      Err(paths) => unmatched.extend(paths.into_iter().map(|path| MixBatchUnmatched {
+        path: path.to_string_lossy().to_string(),
+        kind: "video".to_string(),
+        reason: format!("第 {episode:02} 集有多个视频文件"),
+      })),
+    }
+  }
+  for (kind, file) in extras {
+    match episodes.get_mut(&file.episode) {
+      Some((_, audio, subtitles)) => {
+        if kind == "audio" {
+          audio.push(file.path);
+        } else {
+          subtitles.push(file.path);
+        }
+      }
+      None => unmatched.push(MixBatchUnmatched {
+        path: file.path.to_string_lossy().to_string(),
+        kind: kind.to_string(),
+        reason: format!("没有第 {:02} 集的视频文件", file.episode),
+      }),
+    }
+  }
+
+  let mut jobs = Vec::new();
+  for (episode, (video, audio, subtitles)) in episodes {
+    if audio.is_empty() && subtitles.is_empty() {
+      unmatched.push(MixBatchUnmatched {
+        path: video.to_string_lossy().to_string(),
+        kind: "video".to_string(),
+        reason: format!("没有第 {episode:02} 集的音频或字幕文件"),
+      });
+      continue;
+    }
+    let name = render_output_name(&template, &video, episode).map_err(AppError::invalid_input)?;
+    let output = output_dir.join(format!("{name}.mkv"));
+    if output == video {
+      unmatched.push(MixBatchUnmatched {
+        path: video.to_string_lossy().to_string(),
+        kind: "video".to_string(),
+        reason: "输出文件与视频文件相同，请更换输出目录或文件名模板".to_string(),
+      });
+      continue;
+    }
+
+    // 视频文件的视频轨道全部保留；没有外挂音频（字幕）时保留视频文件内的音频（字幕）轨道。
+    // 某一集的文件识别失败或无法混流时只跳过这一集，已放进队列的作业不受影响。
+    let mut sources = vec![(&video, "video")];
+    if audio.is_empty() {
+      sources.push((&video, "audio"));
+    }
+    if subtitles.is_empty() {
+      sources.push((&video, "subtitle"));
+    }
+    sources.extend(audio.iter().map(|path| (path, "audio")));
+    sources.extend(subtitles.iter().map(|path| (path, "subtitle")));
+    let queued = async {
+      let mut inputs = Vec::new();
+      for (path, kind) in sources {
+        inputs.extend(track_input(&mkvmerge_path, path, kind).await?);
+      }
+      queue_mix(&app, inputs, &output.to_string_lossy(), Vec::new())
+    }
+    .await;
+    let job_id = match queued {
+      Ok(job_id) => job_id,
+      Err(err) => {
+        unmatched.push(MixBatchUnmatched {
+          path: video.to_string_lossy().to_string(),
+          kind: "video".to_string(),
+          reason: format!("第 {episode:02} 集无法混流: {err}"),
+        });
+        continue;
+      }
+    };
+    jobs.push(MixBatchJob {
+      episode,
+      job_id,
+      output_path: output.to_string_lossy().to_string(),
+      video: video.to_string_lossy().to_string(),
+      audio: audio.iter().map(|path| path.to_string_lossy().to_string()).collect(),
+      subtitles: subtitles
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect(),
+    });
+  }
+
+  Ok(MixBatchResult { jobs, unmatched })
+}
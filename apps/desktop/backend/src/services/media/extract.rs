@@ -0,0 +1,538 @@
+/*
+  轨道提取（混流的逆操作）：把媒体文件里的音频、字幕轨道与附件（字体等）提取成独立文件，
+  例如从旧版本里取出质量更好的字幕，用于新的混流。
+  - MKV 系列使用 mkvextract，一次调用提取全部选中的轨道与附件；没有对应独立文件格式的编码
+    （mkvextract 无法直接写出）改用 mkvmerge 单独封装成 mka / mks；
+  - 其它格式使用 ffmpeg 按流复制（MP4 的 mov_text 字幕转成 SRT），没有对应独立格式的编码同样封装成 mka / mks；
+    附件只能从 MKV 系列文件中提取；
+  - 轨道输出为输出目录里的 "<原文件名>.track<轨道 ID>[.<语言>].<扩展名>"，
+    附件按原名写入输出目录的 "<原文件名>.attachments" 子目录；
+  - 提取作为媒体作业（kind 为 extract）在后台执行，与混流共用作业队列和并发上限。
+*/
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::jobs::{
+  add_cleanup, create_job, run_ffmpeg_with_progress, run_mkvtoolnix_with_progress, spawn_job, JobStep,
+};
+use super::transcode::probe_duration;
+use super::{
+  build_cmdline, identify_matroska, is_matroska, kind_label, probe_streams, resolve_tool_path, run_mkvmerge,
+};
+use crate::services::error::AppError;
+use crate::services::limits::{acquire, LimitKind};
+use crate::services::paths::ensure_path_allowed;
+use crate::services::power::keep_awake;
+
+// mkvextract 能直接写出的编码（MKV 的 CodecID 前缀）与对应的扩展名。
+const MATROSKA_EXTENSIONS: &[(&str, &str)] = &[
+  ("A_AAC", "aac"),
+  ("A_AC3", "ac3"),
+  ("A_EAC3", "eac3"),
+  ("A_DTS", "dts"),
+  ("A_FLAC", "flac"),
+  ("A_OPUS", "opus"),
+  ("A_VORBIS", "ogg"),
+  ("A_MPEG/L3", "mp3"),
+  ("A_MPEG/L2", "mp2"),
+  ("A_PCM/INT/LIT", "wav"),
+  ("A_TRUEHD", "thd"),
+  ("A_MLP", "mlp"),
+  ("A_TTA1", "tta"),
+  ("A_WAVPACK4", "wv"),
+  ("A_ALAC", "caf"),
+  ("S_TEXT/ASS", "ass"),
+  ("S_ASS", "ass"),
+  ("S_TEXT/SSA", "ssa"),
+  ("S_SSA", "ssa"),
+  ("S_TEXT/UTF8", "srt"),
+  ("S_TEXT/WEBVTT", "vtt"),
+  ("S_TEXT/USF", "usf"),
+  ("S_HDMV/PGS", "sup"),
+  ("S_VOBSUB", "sub"),
+  ("S_KATE", "ogg"),
+];
+
+// ffmpeg 能按流复制成独立文件的编码（ffprobe 的 codec_name）与对应的扩展名。
+const FFMPEG_EXTENSIONS: &[(&str, &str)] = &[
+  ("aac", "m4a"),
+  ("alac", "m4a"),
+  ("ac3", "ac3"),
+  ("eac3", "eac3"),
+  ("dts", "dts"),
+  ("flac", "flac"),
+  ("opus", "opus"),
+  ("vorbis", "ogg"),
+  ("mp3", "mp3"),
+  ("mp2", "mp2"),
+  ("truehd", "thd"),
+  ("pcm_s16le", "wav"),
+  ("pcm_s24le", "wav"),
+  ("pcm_s32le", "wav"),
+  ("pcm_f32le", "wav"),
+  ("ass", "ass"),
+  ("ssa", "ass"),
+  ("subrip", "srt"),
+  ("webvtt", "vtt"),
+  ("hdmv_pgs_subtitle", "sup"),
+];
+
+/// 要提取的一项。
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractSelection {
+  /// audio / subtitle / attachment。
+  pub kind: String,
+  /// 轨道 ID 或附件 ID（即 parse_media_tracks 返回的 trackId）。
+  pub id: String,
+}
+
+/// 一项提取结果（作业完成后生成）。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedFile {
+  pub kind: String,
+  pub id: String,
+  pub path: String,
+}
+
+/// extract_media_tracks 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractJob {
+  pub job_id: u64,
+  /// 各项的输出文件，顺序同 selections（重复的项只保留一次）。
+  pub files: Vec<ExtractedFile>,
+}
+
+// 一项的提取方式。
+enum ExtractMethod {
+  // mkvextract 的 tracks 模式。
+  Track,
+  // mkvextract 的 attachments 模式。
+  Attachment,
+  // mkvmerge 单独封装成 mka / mks。
+  Remux,
+  // ffmpeg 按流复制；codec 为字幕需要转换时的目标编码（例如 mov_text 转 srt）。
+  Ffmpeg { codec: Option<&'static str> },
+}
+
+// 计划中的一项。
+struct ExtractItem {
+  kind: String,
+  id: String,
+  output: PathBuf,
+  method: ExtractMethod,
+}
+
+// 轨道的信息：类型、编码、语言。
+struct TrackMeta {
+  id: String,
+  kind: String,
+  codec: String,
+  lang: Option<String>,
+}
+
+// 附件的信息：ID 与附件名。
+struct AttachmentMeta {
+  id: String,
+  file_name: Option<String>,
+}
+
+// 按编码对照表查扩展名（MKV 的 CodecID 按前缀匹配，例如 A_AAC/MPEG4/LC）。
+fn lookup_extension(table: &[(&str, &'static str)], codec: &str, prefix: bool) -> Option<&'static str> {
+  table
+    .iter()
+    .find(|(name, _)| {
+      if prefix {
+        codec == *name || codec.starts_with(&format!("{name}/"))
+      } else {
+        codec == *name
+      }
+    })
+    .map(|(_, ext)| *ext)
+}
+
+// 没有对应独立格式时封装用的容器。
+fn container_extension(kind: &str) -> &'static str {
+  if kind == "audio" {
+    "mka"
+  } else {
+    "mks"
+  }
+}
+
+// 轨道的输出路径："<原文件名>.track<轨道 ID>[.<语言>].<扩展名>"（语言为 und 时省略）。
+fn track_output(output_dir: &Path, stem: &str, track: &TrackMeta, ext: &str) -> PathBuf {
+  let lang = track
+    .lang
+    .as_deref()
+    .map(str::trim)
+    .filter(|lang| !lang.is_empty() && *lang != "und" && lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+  let name = match lang {
+    Some(lang) => format!("{stem}.track{}.{lang}.{ext}", track.id),
+    None => format!("{stem}.track{}.{ext}", track.id),
+  };
+  output_dir.join(name)
+}
+
+// 附件的输出文件名：附件名只取文件名部分，没有或与已有的重名时加上附件 ID。
+fn attachment_name(attachment: &AttachmentMeta, used: &mut HashSet<String>) -> String {
+  let name = attachment
+    .file_name
+    .as_deref()
+    .and_then(|name| Path::new(name).file_name())
+    .map(|name| name.to_string_lossy().to_string())
+    .filter(|name| !name.trim().is_empty());
+  let name = match name {
+    Some(name) if !used.contains(&name.to_lowercase()) => name,
+    Some(name) => format!("{}-{name}", attachment.id),
+    None => format!("attachment-{}", attachment.id),
+  };
+  used.insert(name.to_lowercase());
+  name
+}
+
+// 读取 MKV 系列文件的轨道与附件信息。
+async fn matroska_meta(mkvmerge_path: &Path, path: &str) -> Result<(Vec<TrackMeta>, Vec<AttachmentMeta>), AppError> {
+  let parsed = identify_matroska(mkvmerge_path, path).await?;
+  let tracks = parsed
+    .tracks
+    .unwrap_or_default()
+    .into_iter()
+    .map(|track| {
+      let kind = match track.r#type.as_deref() {
+        Some("subtitles") => "subtitle".to_string(),
+        Some(kind) => kind.to_string(),
+        None => String::new(),
+      };
+      let (codec, lang) = match track.properties {
+        Some(props) => (
+          props.codec_id.unwrap_or_default(),
+          props.language_ietf.or(props.language),
+        ),
+        None => (String::new(), None),
+      };
+      TrackMeta {
+        id: track.id.to_string(),
+        kind,
+        codec,
+        lang,
+      }
+    })
+    .collect();
+  let attachments = parsed
+    .attachments
+    .unwrap_or_default()
+    .into_iter()
+    .map(|attachment| AttachmentMeta {
+      id: attachment.id.to_string(),
+      file_name: attachment.file_name,
+    })
+    .collect();
+  Ok((tracks, attachments))
+}
+
+// 读取其它格式文件的流信息（ID 为流序号）。
+async fn stream_meta(ffprobe_path: &Path, path: &str) -> Result<Vec<TrackMeta>, AppError> {
+  let parsed = probe_streams(ffprobe_path, path).await?;
+  Ok(
+    parsed
+      .streams
+      .unwrap_or_default()
+      .into_iter()
+      .map(|stream| TrackMeta {
+        id: stream.index.unwrap_or(0).to_string(),
+        kind: stream.codec_type.unwrap_or_default(),
+        codec: stream.codec_name.unwrap_or_default(),
+        lang: stream.tags.and_then(|tags| tags.language),
+      })
+      .collect(),
+  )
+}
+
+// mkvextract 的参数：一次调用提取全部轨道与附件（mkvextract 的多模式写法）。
+fn mkvextract_args(input: &Path, items: &[ExtractItem]) -> Vec<String> {
+  let mut args = vec![input.to_string_lossy().to_string()];
+  for (mode, attachment) in [("tracks", false), ("attachments", true)] {
+    let specs: Vec<String> = items
+      .iter()
+      .filter(|item| match item.method {
+        ExtractMethod::Track => !attachment,
+        ExtractMethod::Attachment => attachment,
+        _ => false,
+      })
+      .map(|item| format!("{}:{}", item.id, item.output.to_string_lossy()))
+      .collect();
+    if !specs.is_empty() {
+      args.push(mode.to_string());
+      args.extend(specs);
+    }
+  }
+  args
+}
+
+// mkvmerge 单独封装一条轨道的参数：只保留这条轨道，不带章节、附件与全局标签。
+fn remux_args(input: &Path, item: &ExtractItem) -> Vec<String> {
+  let output = item.output.to_string_lossy();
+  let input = input.to_string_lossy();
+  let (select, drop) = if item.kind == "audio" {
+    ("-a", "-S")
+  } else {
+    ("-s", "-A")
+  };
+  let args: [&str; 10] = [
+    "-o",
+    &output,
+    select,
+    &item.id,
+    "-D",
+    drop,
+    "-M",
+    "--no-chapters",
+    "--no-global-tags",
+    &input,
+  ];
+  args.iter().map(|arg| arg.to_string()).collect()
+}
+
+// ffmpeg 的参数：一次调用按流写出全部输出。
+fn ffmpeg_args(input: &Path, items: &[ExtractItem]) -> Vec<String> {
+  let mut args = vec!["-y".to_string(), "-i".to_string(), input.to_string_lossy().to_string()];
+  for item in items {
+    let ExtractMethod::Ffmpeg { codec } = item.method else {
+      continue;
+    };
+    args.push("-map".to_string());
+    args.push(format!("0:{}", item.id));
+    args.push("-c".to_string());
+    args.push(codec.unwrap_or("copy").to_string());
+    args.push(item.output.to_string_lossy().to_string());
+  }
+  args
+}
+
+// 提取作业本体：等待并发许可后依次执行 mkvextract、mkvmerge 封装与 ffmpeg（各一个作业步骤）。
+// matroska 为 (mkvmerge 路径, mkvextract 路径)，ffmpeg 为 (ffmpeg 路径, ffprobe 路径)，按输入格式提供其一。
+async fn run_extract(
+  app: &tauri::AppHandle,
+  job_id: u64,
+  input: PathBuf,
+  items: Vec<ExtractItem>,
+  matroska: Option<(PathBuf, PathBuf)>,
+  ffmpeg: Option<(PathBuf, PathBuf)>,
+) -> Result<(), AppError> {
+  let _job_permit = acquire(LimitKind::MediaJob).await?;
+  let _awake = keep_awake("正在提取轨道");
+
+  // 中途取消时输出文件不完整，一并删除。
+  for item in &items {
+    add_cleanup(job_id, &item.output);
+  }
+  let extract_count = usize::from(
+    items
+      .iter()
+      .any(|item| matches!(item.method, ExtractMethod::Track | ExtractMethod::Attachment)),
+  );
+  let remux: Vec<&ExtractItem> = items
+    .iter()
+    .filter(|item| matches!(item.method, ExtractMethod::Remux))
+    .collect();
+  let ffmpeg_count = usize::from(
+    items
+      .iter()
+      .any(|item| matches!(item.method, ExtractMethod::Ffmpeg { .. })),
+  );
+  let step_count = extract_count + remux.len() + ffmpeg_count;
+
+  if let Some((mkvmerge_path, mkvextract_path)) = &matroska {
+    if extract_count > 0 {
+      let args = mkvextract_args(&input, &items);
+      let step = JobStep {
+        app,
+        job_id,
+        index: 0,
+        count: step_count,
+        phase: "提取轨道",
+      };
+      let (success, code, output) = run_mkvtoolnix_with_progress("mkvextract", mkvextract_path, &args, &step).await?;
+      if !success {
+        return Err(AppError::tool_failed(
+          "mkvextract",
+          format!(
+            "mkvextract 执行失败 (code {:?}): {}\n命令: {}",
+            code,
+            output,
+            build_cmdline("mkvextract", &args)
+          ),
+        ));
+      }
+    }
+    for (index, item) in remux.iter().enumerate() {
+      let phase = format!("封装{}轨道 {}", kind_label(&item.kind), item.id);
+      let step = JobStep {
+        app,
+        job_id,
+        index: extract_count + index,
+        count: step_count,
+        phase: &phase,
+      };
+      run_mkvmerge(mkvmerge_path, &remux_args(&input, item), &step).await?;
+    }
+  }
+
+  if let Some((ffmpeg_path, ffprobe_path)) = &ffmpeg {
+    let duration = probe_duration(ffprobe_path, &input).await;
+    let step = JobStep {
+      app,
+      job_id,
+      index: step_count - 1,
+      count: step_count,
+      phase: "提取轨道",
+    };
+    let (success, code, errors) =
+      run_ffmpeg_with_progress(ffmpeg_path, &ffmpeg_args(&input, &items), duration, &step).await?;
+    if !success {
+      return Err(AppError::tool_failed(
+        "ffmpeg",
+        format!("ffmpeg 执行失败 (code {code:?}): {errors}"),
+      ));
+    }
+  }
+  Ok(())
+}
+
+// 把媒体文件里选中的音频、字幕轨道与附件提取成独立文件（混流的逆操作）：校验并规划输出后
+// 创建媒体作业，立即返回作业 ID 与各项的输出路径；提取在后台执行，进度通过 media://mux-progress 事件推送。
+#[tauri::command]
+pub async fn extract_media_tracks(
+  app: tauri::AppHandle,
+  path: String,
+  selections: Vec<ExtractSelection>,
+  output_dir: String,
+) -> Result<ExtractJob, AppError> {
+  let input = ensure_path_allowed(&app, &path).map_err(AppError::invalid_input)?;
+  if !input.is_file() {
+    return Err(AppError::invalid_input(format!("输入文件不存在: {}", input.display())));
+  }
+  if output_dir.trim().is_empty() {
+    return Err(AppError::invalid_input("未指定输出目录"));
+  }
+  let output_dir = ensure_path_allowed(&app, output_dir.trim()).map_err(AppError::invalid_input)?;
+
+  // 整理选择：类型统一为小写，去掉空 ID 与重复的项。
+  let mut wanted: Vec<(String, String)> = Vec::new();
+  for selection in selections {
+    let kind = selection.kind.trim().to_lowercase();
+    let id = selection.id.trim().to_string();
+    if !["audio", "subtitle", "attachment"].contains(&kind.as_str()) {
+      return Err(AppError::invalid_input(format!(
+        "只能提取音频、字幕轨道与附件：{}",
+        selection.kind
+      )));
+    }
+    if id.is_empty() {
+      continue;
+    }
+    if !wanted.contains(&(kind.clone(), id.clone())) {
+      wanted.push((kind, id));
+    }
+  }
+  if wanted.is_empty() {
+    return Err(AppError::invalid_input("未选择要提取的轨道或附件"));
+  }
+
+  let raw_input = input.to_string_lossy().to_string();
+  let matroska = is_matroska(&input);
+  let (tracks, attachments, tools) = if matroska {
+    let mkvmerge_path = resolve_tool_path(&app, "mkvmerge")?;
+    let mkvextract_path = resolve_tool_path(&app, "mkvextract")?;
+    let (tracks, attachments) = matroska_meta(&mkvmerge_path, &raw_input).await?;
+    (tracks, attachments, (Some((mkvmerge_path, mkvextract_path)), None))
+  } else {
+    let ffmpeg_path = resolve_tool_path(&app, "ffmpeg")?;
+    let ffprobe_path = resolve_tool_path(&app, "ffprobe")?;
+    let tracks = stream_meta(&ffprobe_path, &raw_input).await?;
+    (tracks, Vec::new(), (None, Some((ffmpeg_path, ffprobe_path))))
+  };
+
+  let stem = input
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let attachments_dir = output_dir.join(format!("{stem}.attachments"));
+  let mut used_names = HashSet::new();
+  let mut items: Vec<ExtractItem> = Vec::new();
+  for (kind, id) in wanted {
+    if kind == "attachment" {
+      if !matroska {
+        return Err(AppError::invalid_input(
+          "只能从 MKV 系列文件（mkv / mka / mks）中提取附件",
+        ));
+      }
+      let attachment = attachments
+        .iter()
+        .find(|attachment| attachment.id == id)
+        .ok_or_else(|| AppError::invalid_input(format!("文件里没有附件 {id}")))?;
+      items.push(ExtractItem {
+        output: attachments_dir.join(attachment_name(attachment, &mut used_names)),
+        kind,
+        id,
+        method: ExtractMethod::Attachment,
+      });
+      continue;
+    }
+    let track = tracks
+      .iter()
+      .find(|track| track.id == id && track.kind == kind)
+      .ok_or_else(|| AppError::invalid_input(format!("文件里没有{}轨道 {id}", kind_label(&kind))))?;
+    let (ext, method) = if matroska {
+      match lookup_extension(MATROSKA_EXTENSIONS, &track.codec, true) {
+        Some(ext) => (ext, ExtractMethod::Track),
+        None => (container_extension(&kind), ExtractMethod::Remux),
+      }
+    } else if track.codec == "mov_text" {
+      ("srt", ExtractMethod::Ffmpeg { codec: Some("srt") })
+    } else {
+      let ext = lookup_extension(FFMPEG_EXTENSIONS, &track.codec, false).unwrap_or(container_extension(&kind));
+      (ext, ExtractMethod::Ffmpeg { codec: None })
+    };
+    let output = track_output(&output_dir, &stem, track, ext);
+    if output == input {
+      return Err(AppError::invalid_input("输出文件不能与输入文件相同"));
+    }
+    items.push(ExtractItem {
+      kind,
+      id,
+      output,
+      method,
+    });
+  }
+
+  fs::create_dir_all(&output_dir).map_err(|e| AppError::io(format!("创建输出目录失败: {e}")))?;
+  if items
+    .iter()
+    .any(|item| matches!(item.method, ExtractMethod::Attachment))
+  {
+    fs::create_dir_all(&attachments_dir).map_err(|e| AppError::io(format!("创建附件目录失败: {e}")))?;
+  }
+
+  let files = items
+    .iter()
+    .map(|item| ExtractedFile {
+      kind: item.kind.clone(),
+      id: item.id.clone(),
+      path: item.output.to_string_lossy().to_string(),
+    })
+    .collect();
+  let job_id = create_job(&app, "extract", &output_dir.to_string_lossy());
+  let task_app = app.clone();
+  let (matroska_tools, ffmpeg_tools) = tools;
+  spawn_job(&app, job_id, async move {
+    run_extract(&task_app, job_id, input, items, matroska_tools, ffmpeg_tools).await
+  });
+  Ok(ExtractJob { job_id, files })
+}
@@ -0,0 +1,648 @@
+/*
+  字幕字体：
+  - ASS 字幕依赖样式里指定的字体，播放端没有安装时会用替代字体，排版与特效都会走样，
+    因此发布时通常把字体作为附件封装进 MKV；
+  - scan_subtitle_fonts 解析 ASS 的样式表与对白里的 \fn / \r 覆盖标签，列出实际用到的字体，
+    并在指定的字体目录里按字体内部名称（家族名、完整名称，任意语言）查找对应的字体文件；
+  - 只统计对白实际用到的样式，未使用的样式不计入；竖排字体名前的 @ 会被去掉；
+  - subset_subtitle_fonts 在此基础上统计每个字体显示的字符，生成子集化字体（见 subset.rs），
+    并输出改用子集字体名的 ASS，发布用的附件从几百 MB 缩小到几 MB；
+  - 字幕文件的编码自动识别（UTF-8 / UTF-16 / GB18030 / Big5 / Shift-JIS，见 subtitle.rs）。
+*/
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::subset::subset_font;
+use super::subtitle::decode_subtitle;
+use crate::services::error::AppError;
+use crate::services::limits::{acquire, LimitKind};
+use crate::services::paths::ensure_path_allowed;
+
+// 字体文件后缀与对应的 MIME 类型（与新版 mkvmerge 自动识别的结果一致）。
+const FONT_MIME_TYPES: [(&str, &str); 4] = [
+  ("ttf", "font/ttf"),
+  ("otf", "font/otf"),
+  ("ttc", "font/collection"),
+  ("otc", "font/collection"),
+];
+
+/// 字幕用到的一个字体。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleFont {
+  /// 字幕里写的字体名（已去掉竖排前缀 @）。
+  pub name: String,
+  /// 匹配到的字体文件，没有找到时为 None。
+  pub file: Option<String>,
+}
+
+/// 子集化后的一个字体（同一个字体文件对应的多个字体名合并为一项）。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsetFont {
+  /// 字幕里原来的字体名。
+  pub names: Vec<String>,
+  /// 新的字体名（ASS 里已替换成这个名称）。
+  pub subset_name: String,
+  pub source_file: String,
+  /// 生成的字体文件。
+  pub file: String,
+  /// 是否做了字形子集化；CFF 轮廓或竖排使用的字体只改名，为 false。
+  pub subsetted: bool,
+  pub source_size: u64,
+  pub size: u64,
+}
+
+/// subset_subtitle_fonts 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleFontSubset {
+  /// 改写字体名后的 ASS 文件（UTF-8 带 BOM）。
+  pub ass_path: String,
+  pub fonts: Vec<SubsetFont>,
+  /// 没有找到的字体名（ASS 里保持原名）。
+  pub missing: Vec<String>,
+  /// 生成的字体文件，可以直接作为 mix_media_tracks 的 attachments。
+  pub font_files: Vec<String>,
+}
+
+/// scan_subtitle_fonts 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleFontScan {
+  /// 用到的字体（按在字幕里首次出现的顺序）。
+  pub fonts: Vec<SubtitleFont>,
+  /// 没有找到的字体名。
+  pub missing: Vec<String>,
+  /// 需要附加的字体文件（去重），可以直接作为 mix_media_tracks 的 attachments。
+  pub font_files: Vec<String>,
+}
+
+// 字体文件的 MIME 类型；不是支持的字体文件时返回 None。
+pub(crate) fn font_mime_type(path: &Path) -> Option<&'static str> {
+  let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+  FONT_MIME_TYPES
+    .iter()
+    .find(|(item, _)| *item == ext)
+    .map(|(_, mime)| *mime)
+}
+
+// 规范化字体名：去掉首尾空白与竖排前缀 @。
+fn clean_font_name(name: &str) -> &str {
+  name.trim().trim_start_matches('@').trim()
+}
+
+// 段落标题行（例如 "[Events]"）返回小写的段落名。
+pub(crate) fn section_name(line: &str) -> Option<String> {
+  (line.starts_with('[') && line.ends_with(']')).then(|| line.to_ascii_lowercase())
+}
+
+// 是否为样式表段落。
+fn is_style_section(section: &str) -> bool {
+  matches!(section, "[v4+ styles]" | "[v4 styles]")
+}
+
+// 样式名的比较键：小写并去掉前缀 *（与 libass 一致）。
+fn style_key(name: &str) -> String {
+  name.trim().trim_start_matches('*').to_lowercase()
+}
+
+// 解析 Format 行，返回小写的列名。
+pub(crate) fn parse_format(value: &str) -> Vec<String> {
+  value.split(',').map(|item| item.trim().to_ascii_lowercase()).collect()
+}
+
+// 在列名中查找指定列，找不到时使用默认位置。
+pub(crate) fn column(format: &[String], name: &str, fallback: usize) -> usize {
+  format.iter().position(|item| item == name).unwrap_or(fallback)
+}
+
+// [Events] 段的列数与样式、文本所在的列（没有 Format 行时按 ASS 的默认列）。
+struct EventColumns {
+  count: usize,
+  style: usize,
+  text: usize,
+}
+
+impl EventColumns {
+  fn parse(format: &[String]) -> Self {
+    if format.is_empty() {
+      return Self {
+        count: 10,
+        style: 3,
+        text: 9,
+      };
+    }
+    Self {
+      count: format.len(),
+      style: column(format, "style", 3),
+      text: column(format, "text", 9),
+    }
+  }
+}
+
+// 读取样式表：样式名（比较键）→ 字体名（原样，竖排字体带 @）。
+fn parse_styles(content: &str) -> HashMap<String, String> {
+  let mut styles = HashMap::new();
+  let mut section = String::new();
+  let mut format: Vec<String> = Vec::new();
+  for line in content.lines() {
+    let line = line.trim();
+    if let Some(name) = section_name(line) {
+      section = name;
+      continue;
+    }
+    let Some((key, value)) = line.split_once(':') else {
+      continue;
+    };
+    if !is_style_section(&section) {
+      continue;
+    }
+    match key.trim().to_ascii_lowercase().as_str() {
+      "format" => format = parse_format(value),
+      "style" => {
+        let fields: Vec<&str> = value.split(',').collect();
+        let name = fields.get(column(&format, "name", 0)).copied().unwrap_or("");
+        let font = fields.get(column(&format, "fontname", 1)).copied().unwrap_or("");
+        styles.insert(style_key(name), font.to_string());
+      }
+      _ => {}
+    }
+  }
+  styles
+}
+
+/// 字幕里一个字体的使用情况。
+pub(crate) struct FontUsage {
+  /// 字体名（已去掉竖排前缀 @）。
+  pub name: String,
+  /// 用这个字体显示的字符。
+  pub chars: BTreeSet<char>,
+  /// 是否以竖排（@字体名）使用。
+  pub vertical: bool,
+}
+
+// 收集的字体：按首次出现的顺序保存，不区分大小写去重（与 libass 的匹配方式一致）。
+#[derive(Default)]
+struct FontUsages {
+  usages: Vec<FontUsage>,
+  index: HashMap<String, usize>,
+}
+
+impl FontUsages {
+  // 登记字体（原样的字体名），返回序号；字体名为空时返回 None。
+  fn touch(&mut self, raw: &str) -> Option<usize> {
+    let name = clean_font_name(raw);
+    if name.is_empty() {
+      return None;
+    }
+    let index = *self.index.entry(name.to_lowercase()).or_insert_with(|| {
+      self.usages.push(FontUsage {
+        name: name.to_string(),
+        chars: BTreeSet::new(),
+        vertical: false,
+      });
+      self.usages.len() - 1
+    });
+    self.usages[index].vertical |= raw.trim().starts_with('@');
+    Some(index)
+  }
+
+  // 记录字符（绘图模式下的内容不是文字，不记录）。
+  fn add_char(&mut self, font: Option<usize>, drawing: bool, ch: char) {
+    if let (Some(font), false) = (font, drawing) {
+      self.usages[font].chars.insert(ch);
+    }
+  }
+}
+
+// 遍历一条对白：样式字体为初始字体，\fn 切换字体（空的 \fn 恢复样式字体），
+// \r 切换到指定样式（为空时恢复对白样式）；\p1 之后到 \p0 之间是绘图指令，不计字符。
+fn walk_dialogue(text: &str, style: &str, styles: &HashMap<String, String>, usages: &mut FontUsages) {
+  // 找不到的样式按 libass 的做法回退到 Default 样式。
+  let style_font = |name: &str| styles.get(&style_key(name)).or_else(|| styles.get("default")).cloned();
+  let line_font = style_font(style);
+  let mut base_font = line_font.clone();
+  let mut current = base_font.as_deref().and_then(|font| usages.touch(font));
+  let mut drawing = false;
+
+  let mut chars = text.chars().peekable();
+  while let Some(ch) = chars.next() {
+    match ch {
+      '{' => {
+        let block: String = chars.by_ref().take_while(|c| *c != '}').collect();
+        for tag in block.split('\\').skip(1) {
+          if let Some(font) = tag.strip_prefix("fn") {
+            current = if clean_font_name(font).is_empty() {
+              base_font.as_deref().and_then(|font| usages.touch(font))
+            } else {
+              usages.touch(font)
+            };
+          } else if let Some(level) = tag.strip_prefix('p') {
+            if let Ok(level) = level.trim().parse::<u32>() {
+              drawing = level > 0;
+            }
+          } else if let Some(name) = tag.strip_prefix('r') {
+            let name = name.trim().trim_end_matches(')').trim();
+            base_font = if name.is_empty() {
+              line_font.clone()
+            } else {
+              style_font(name)
+            };
+            current = base_font.as_deref().and_then(|font| usages.touch(font));
+            drawing = false;
+          }
+        }
+      }
+      // 转义：\N、\n 为换行，\h 为不换行空格。
+      '\\' => match chars.peek() {
+        Some('N' | 'n') => {
+          chars.next();
+        }
+        Some('h') => {
+          chars.next();
+          usages.add_char(current, drawing, '\u{a0}');
+        }
+        _ => usages.add_char(current, drawing, ch),
+      },
+      _ => usages.add_char(current, drawing, ch),
+    }
+  }
+}
+
+// 分析 ASS 文本用到的字体（按首次出现的顺序），以及每个字体显示的字符。
+pub(crate) fn analyze_ass_fonts(content: &str) -> Vec<FontUsage> {
+  let styles = parse_styles(content);
+  let mut usages = FontUsages::default();
+  let mut section = String::new();
+  let mut columns = EventColumns::parse(&[]);
+  for line in content.lines() {
+    let line = line.trim();
+    if let Some(name) = section_name(line) {
+      section = name;
+      continue;
+    }
+    let Some((key, value)) = line.split_once(':') else {
+      continue;
+    };
+    if section != "[events]" {
+      continue;
+    }
+    match key.trim().to_ascii_lowercase().as_str() {
+      "format" => columns = EventColumns::parse(&parse_format(value)),
+      "dialogue" => {
+        // 文本是最后一列，本身可能包含逗号，只按列数切分。
+        let fields: Vec<&str> = value.splitn(columns.count, ',').collect();
+        let style = fields.get(columns.style).copied().unwrap_or("");
+        let text = fields.get(columns.text).copied().unwrap_or("");
+        walk_dialogue(text, style, &styles, &mut usages);
+      }
+      _ => {}
+    }
+  }
+  usages.usages
+}
+
+// 替换单个字体名，保留前后空白与竖排前缀 @；不需要替换时原样返回。
+fn rename_font(raw: &str, renames: &HashMap<String, String>) -> String {
+  let name = clean_font_name(raw);
+  match renames.get(&name.to_lowercase()) {
+    Some(new_name) if !name.is_empty() => raw.replacen(name, new_name, 1),
+    _ => raw.to_string(),
+  }
+}
+
+// 替换对白文本覆盖标签里 \fn 的字体名。
+fn rename_override_fonts(text: &str, renames: &HashMap<String, String>) -> String {
+  let mut out = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find('{') {
+    out.push_str(&rest[..=start]);
+    let block = &rest[start + 1..];
+    let Some(end) = block.find('}') else {
+      out.push_str(block);
+      return out;
+    };
+    let tags: Vec<String> = block[..end]
+      .split('\\')
+      .enumerate()
+      .map(|(index, tag)| match tag.strip_prefix("fn") {
+        Some(font) if index > 0 => format!("fn{}", rename_font(font, renames)),
+        _ => tag.to_string(),
+      })
+      .collect();
+    out.push_str(&tags.join("\\"));
+    out.push('}');
+    rest = &block[end + 1..];
+  }
+  out.push_str(rest);
+  out
+}
+
+// 把 ASS 里引用的字体名（样式表与 \fn 标签）替换成新名称；renames 为 小写字体名 → 新名称。
+pub(crate) fn rewrite_ass_fonts(content: &str, renames: &HashMap<String, String>) -> String {
+  let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+  let mut lines: Vec<String> = Vec::new();
+  let mut section = String::new();
+  let mut style_format: Vec<String> = Vec::new();
+  let mut columns = EventColumns::parse(&[]);
+  for raw in content.lines() {
+    let line = raw.trim();
+    if let Some(name) = section_name(line) {
+      section = name;
+      lines.push(raw.to_string());
+      continue;
+    }
+    let rewritten = line.split_once(':').and_then(|(key, value)| {
+      let lower = key.trim().to_ascii_lowercase();
+      if is_style_section(&section) {
+        match lower.as_str() {
+          "format" => style_format = parse_format(value),
+          "style" => {
+            let mut fields: Vec<String> = value.split(',').map(str::to_string).collect();
+            let font = fields.get_mut(column(&style_format, "fontname", 1))?;
+            *font = rename_font(font, renames);
+            return Some(format!("{key}:{}", fields.join(",")));
+          }
+          _ => {}
+        }
+      } else if section == "[events]" {
+        match lower.as_str() {
+          "format" => columns = EventColumns::parse(&parse_format(value)),
+          "dialogue" => {
+            let mut fields: Vec<String> = value.splitn(columns.count, ',').map(str::to_string).collect();
+            let text = fields.get_mut(columns.text)?;
+            *text = rename_override_fonts(text, renames);
+            return Some(format!("{key}:{}", fields.join(",")));
+          }
+          _ => {}
+        }
+      }
+      None
+    });
+    lines.push(rewritten.unwrap_or_else(|| raw.to_string()));
+  }
+  let mut out = lines.join(newline);
+  out.push_str(newline);
+  out
+}
+
+// 字体目录里的一个字体：所在文件与字体集合里的序号，以及是否为常规字重的正体（同名多个文件时优先使用）。
+struct IndexedFont {
+  path: PathBuf,
+  face_index: u32,
+  regular: bool,
+}
+
+// 递归收集目录下的字体文件。
+pub(crate) fn collect_font_files(root: &Path, files: &mut Vec<PathBuf>) {
+  let mut pending = vec![root.to_path_buf()];
+  while let Some(dir) = pending.pop() {
+    let Ok(entries) = fs::read_dir(&dir) else {
+      continue;
+    };
+    for entry in entries.flatten() {
+      let path = entry.path();
+      match entry.file_type() {
+        Ok(file_type) if file_type.is_dir() => pending.push(path),
+        Ok(file_type) if file_type.is_file() && font_mime_type(&path).is_some() => files.push(path),
+        _ => {}
+      }
+    }
+  }
+}
+
+// 为字体目录建立“字体名（小写）→ 字体文件”的索引：字体集合（ttc）里的每个字体都会收录，
+// 名称取家族名、完整名称与排版家族名的全部语言版本（中文字体通常同时有中英文名称）。
+fn index_fonts(dirs: &[PathBuf]) -> HashMap<String, IndexedFont> {
+  let mut files = Vec::new();
+  for dir in dirs {
+    collect_font_files(dir, &mut files);
+  }
+  files.sort();
+
+  let mut index: HashMap<String, IndexedFont> = HashMap::new();
+  for path in files {
+    // 单个字体文件损坏不影响其它字体。
+    let Ok(data) = fs::read(&path) else {
+      continue;
+    };
+    let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+    for face_index in 0..count {
+      let Ok(face) = ttf_parser::Face::parse(&data, face_index) else {
+        continue;
+      };
+      let regular = !face.is_bold() && !face.is_italic();
+      for name in face.names() {
+        if !matches!(
+          name.name_id,
+          ttf_parser::name_id::FAMILY | ttf_parser::name_id::FULL_NAME | ttf_parser::name_id::TYPOGRAPHIC_FAMILY
+        ) {
+          continue;
+        }
+        let Some(text) = name.to_string() else {
+          continue;
+        };
+        let key = text.trim().to_lowercase();
+        if key.is_empty() {
+          continue;
+        }
+        let replace = match index.get(&key) {
+          Some(existing) => regular && !existing.regular,
+          None => true,
+        };
+        if replace {
+          index.insert(
+            key,
+            IndexedFont {
+              path: path.clone(),
+              face_index,
+              regular,
+            },
+          );
+        }
+      }
+    }
+  }
+  index
+}
+
+// 读取字幕并在字体目录里查找用到的字体。
+pub(crate) fn scan(ass_path: &Path, font_dirs: &[PathBuf]) -> Result<SubtitleFontScan, AppError> {
+  let bytes = fs::read(ass_path).map_err(|e| AppError::io(format!("读取字幕 {} 失败: {e}", ass_path.display())))?;
+  let usages = analyze_ass_fonts(&decode_subtitle(&bytes));
+  let index = index_fonts(font_dirs);
+
+  let mut fonts = Vec::new();
+  let mut missing = Vec::new();
+  let mut font_files: Vec<String> = Vec::new();
+  for FontUsage { name, .. } in usages {
+    let file = index
+      .get(&name.to_lowercase())
+      .map(|font| font.path.to_string_lossy().to_string());
+    match &file {
+      Some(file) if !font_files.contains(file) => font_files.push(file.clone()),
+      Some(_) => {}
+      None => missing.push(name.clone()),
+    }
+    fonts.push(SubtitleFont { name, file });
+  }
+  Ok(SubtitleFontScan {
+    fonts,
+    missing,
+    font_files,
+  })
+}
+
+// 同一个字体文件（字体集合里的同一个字体）用到的全部字体名与字符。
+struct SubsetGroup {
+  names: Vec<String>,
+  chars: BTreeSet<char>,
+  vertical: bool,
+}
+
+// 为字幕生成子集化字体与改写字体名后的 ASS，写入 output_dir。
+fn subset(ass_path: &Path, font_dirs: &[PathBuf], output_dir: &Path) -> Result<SubtitleFontSubset, AppError> {
+  let bytes = fs::read(ass_path).map_err(|e| AppError::io(format!("读取字幕 {} 失败: {e}", ass_path.display())))?;
+  let content = decode_subtitle(&bytes);
+  let index = index_fonts(font_dirs);
+
+  // 按字体文件分组：不同的名称可能指向同一个字体（例如中英文名称各用了一次）。
+  let mut groups: BTreeMap<(PathBuf, u32), SubsetGroup> = BTreeMap::new();
+  let mut missing = Vec::new();
+  for usage in analyze_ass_fonts(&content) {
+    let Some(font) = index.get(&usage.name.to_lowercase()) else {
+      missing.push(usage.name);
+      continue;
+    };
+    let group = groups
+      .entry((font.path.clone(), font.face_index))
+      .or_insert_with(|| SubsetGroup {
+        names: Vec::new(),
+        chars: BTreeSet::new(),
+        vertical: false,
+      });
+    group.names.push(usage.name);
+    group.chars.extend(usage.chars);
+    group.vertical |= usage.vertical;
+  }
+
+  fs::create_dir_all(output_dir).map_err(|e| AppError::io(format!("创建输出目录失败: {e}")))?;
+  let mut renames = HashMap::new();
+  let mut fonts = Vec::new();
+  for ((path, face_index), group) in groups {
+    let data = fs::read(&path).map_err(|e| AppError::io(format!("读取字体 {} 失败: {e}", path.display())))?;
+    // 新名称由字体文件与用到的字符决定，同样的输入总是得到同样的名称。
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(path.to_string_lossy().as_bytes());
+    hasher.update(&face_index.to_be_bytes());
+    hasher.update(group.chars.iter().collect::<String>().as_bytes());
+    let subset_name = format!("{:08X}", hasher.finalize());
+
+    // 竖排需要 GSUB 里的竖排字形，子集化会丢掉，只改名。
+    let output = subset_font(&data, face_index, &group.chars, &subset_name, !group.vertical)
+      .map_err(|e| AppError::invalid_input(format!("处理字体 {} 失败: {e}", path.display())))?;
+    let ext = if output.truetype { "ttf" } else { "otf" };
+    let file = output_dir.join(format!("{subset_name}.{ext}"));
+    fs::write(&file, &output.data).map_err(|e| AppError::io(format!("写入字体 {} 失败: {e}", file.display())))?;
+
+    for name in &group.names {
+      renames.insert(name.to_lowercase(), subset_name.clone());
+    }
+    fonts.push(SubsetFont {
+      names: group.names,
+      subset_name,
+      source_file: path.to_string_lossy().to_string(),
+      file: file.to_string_lossy().to_string(),
+      subsetted: output.subsetted,
+      source_size: data.len() as u64,
+      size: output.data.len() as u64,
+    });
+  }
+
+  let file_name = ass_path
+    .file_name()
+    .ok_or_else(|| AppError::invalid_input("字幕文件名无效"))?;
+  let output_ass = output_dir.join(file_name);
+  let mut rewritten = vec![0xEF, 0xBB, 0xBF];
+  rewritten.extend_from_slice(rewrite_ass_fonts(&content, &renames).as_bytes());
+  fs::write(&output_ass, rewritten)
+    .map_err(|e| AppError::io(format!("写入字幕 {} 失败: {e}", output_ass.display())))?;
+
+  Ok(SubtitleFontSubset {
+    ass_path: output_ass.to_string_lossy().to_string(),
+    font_files: fonts.iter().map(|font| font.file.clone()).collect(),
+    fonts,
+    missing,
+  })
+}
+
+// 校验字幕文件与字体目录。
+fn check_inputs(
+  app: &tauri::AppHandle,
+  ass_path: &str,
+  font_dirs: &[String],
+) -> Result<(PathBuf, Vec<PathBuf>), AppError> {
+  let ass_path = ensure_path_allowed(app, ass_path).map_err(AppError::invalid_input)?;
+  if !ass_path.is_file() {
+    return Err(AppError::invalid_input(format!(
+      "字幕文件不存在: {}",
+      ass_path.display()
+    )));
+  }
+  let mut dirs = Vec::new();
+  for dir in font_dirs.iter().filter(|dir| !dir.trim().is_empty()) {
+    let dir = ensure_path_allowed(app, dir).map_err(AppError::invalid_input)?;
+    if !dir.is_dir() {
+      return Err(AppError::invalid_input(format!("字体目录不存在: {}", dir.display())));
+    }
+    dirs.push(dir);
+  }
+  Ok((ass_path, dirs))
+}
+
+// 分析 ASS 字幕用到的字体，并在字体目录里查找对应的字体文件。
+#[tauri::command]
+pub async fn scan_subtitle_fonts(
+  app: tauri::AppHandle,
+  ass_path: String,
+  font_dirs: Vec<String>,
+) -> Result<SubtitleFontScan, AppError> {
+  let (ass_path, dirs) = check_inputs(&app, &ass_path, &font_dirs)?;
+  tauri::async_runtime::spawn_blocking(move || scan(&ass_path, &dirs))
+    .await
+    .map_err(|e| AppError::internal(format!("分析字幕字体失败: {e}")))?
+}
+
+// 子集化字幕用到的字体：输出子集字体与改写字体名后的 ASS，
+// 输出目录省略时为字幕旁边的“<字幕文件名>_subset”目录，不能与字幕所在目录相同（避免覆盖原字幕）。
+#[tauri::command]
+pub async fn subset_subtitle_fonts(
+  app: tauri::AppHandle,
+  ass_path: String,
+  font_dirs: Vec<String>,
+  output_dir: Option<String>,
+) -> Result<SubtitleFontSubset, AppError> {
+  let (ass_path, dirs) = check_inputs(&app, &ass_path, &font_dirs)?;
+  let output_dir = match output_dir.filter(|dir| !dir.trim().is_empty()) {
+    Some(dir) => ensure_path_allowed(&app, &dir).map_err(AppError::invalid_input)?,
+    None => {
+      let stem = ass_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+      ass_path.with_file_name(format!("{stem}_subset"))
+    }
+  };
+  if ass_path.parent() == Some(output_dir.as_path()) {
+    return Err(AppError::invalid_input("输出目录不能是字幕所在的目录"));
+  }
+
+  let _job_permit = acquire(LimitKind::MediaJob).await?;
+  tauri::async_runtime::spawn_blocking(move || subset(&ass_path, &dirs, &output_dir))
+    .await
+    .map_err(|e| AppError::internal(format!("子集化字体失败: {e}")))?
+}
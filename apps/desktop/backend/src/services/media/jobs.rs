@@ -0,0 +1,441 @@
+/*
+  媒体作业：
+  混流、转码等耗时任务在后台执行，命令立即返回作业 ID，
+  进度与结果通过 media://mux-progress 事件推送（内容为完整的作业），也可以用 get_media_job 查询。
+  - 作业只保存在内存中，应用退出后不保留；
+  - 作业可以取消（cancel_media_job）：作业的 future 被直接丢弃，
+    其中的 mkvmerge / mkvextract / ffmpeg 子进程都设置了 kill_on_drop，随之被结束；
+    之后删除作业登记的临时目录与未写完的输出文件，作业标记为 cancelled；
+  - 结束的作业最多保留 MAX_FINISHED_JOBS 个，更早的自动移除；
+  - 进度事件只在百分比（取整）或阶段变化时发送，避免 mkvmerge 输出过密时刷屏。
+*/
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use crate::services::diagnostics::app_log;
+use crate::services::error::AppError;
+
+// 作业进度事件名。
+const PROGRESS_EVENT: &str = "media://mux-progress";
+// 最多保留的已结束作业数。
+const MAX_FINISHED_JOBS: usize = 50;
+// MKVToolNix 工具 --gui-mode 输出的进度行前缀，例如 "#GUI#progress 42%"。
+const GUI_PROGRESS_PREFIX: &str = "#GUI#progress";
+// ffmpeg -progress 输出中已处理时长（微秒）的键，例如 "out_time_us=12345678"。
+const FFMPEG_OUT_TIME_KEY: &str = "out_time_us=";
+// ffmpeg 失败时错误信息最多保留的行数（取末尾）。
+const FFMPEG_ERROR_LINES: usize = 20;
+// 取消后删除文件的重试次数与间隔（Windows 上子进程退出后文件句柄可能稍晚才释放）。
+const CLEANUP_ATTEMPTS: usize = 5;
+const CLEANUP_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+// 作业 ID 计数器。
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+// 全部作业（按 ID 排序，ID 越大越新）。
+static JOBS: Lazy<Mutex<BTreeMap<u64, MediaJob>>> = Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// 作业状态。
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MediaJobStatus {
+  /// 等待媒体任务并发许可。
+  Queued,
+  Running,
+  Succeeded,
+  Failed,
+  Cancelled,
+}
+
+impl MediaJobStatus {
+  fn is_finished(self) -> bool {
+    matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+  }
+}
+
+/// 一个媒体作业（也是 media://mux-progress 事件的内容）。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaJob {
+  pub id: u64,
+  /// 作业类型：mux（混流）/ transcode（转码）/ extract（提取轨道）/ screenshot（截图）。
+  pub kind: String,
+  pub status: MediaJobStatus,
+  /// 当前阶段（中文说明，例如“提取音频轨道”）。
+  pub phase: String,
+  /// 整体进度（0 ~ 100）。
+  pub percent: f64,
+  pub output_path: String,
+  /// 失败原因。
+  pub error: Option<String>,
+  /// 作业日志（工具的警告、改用分步混流等说明），按时间顺序。
+  pub log: Vec<String>,
+  /// 创建与结束时间（RFC 3339）。
+  pub created_at: String,
+  pub finished_at: Option<String>,
+  // 取消信号。
+  #[serde(skip)]
+  cancel: CancellationToken,
+  // 取消时要删除的文件或目录（临时目录、未写完的输出）。
+  #[serde(skip)]
+  cleanup: Vec<PathBuf>,
+}
+
+// 作业中的一个步骤：第 index 步（从 0 开始），共 count 步，步骤内的进度按比例折算成整体进度。
+pub(crate) struct JobStep<'a> {
+  pub app: &'a tauri::AppHandle,
+  pub job_id: u64,
+  pub index: usize,
+  pub count: usize,
+  pub phase: &'a str,
+}
+
+impl JobStep<'_> {
+  // 报告步骤内的进度（0 ~ 100）。
+  fn report(&self, percent: f64) {
+    let count = self.count.max(1) as f64;
+    let overall = ((self.index as f64 + percent.clamp(0.0, 100.0) / 100.0) / count * 100.0).min(100.0);
+    update_job(self.app, self.job_id, |job| {
+      job.status = MediaJobStatus::Running;
+      job.phase = self.phase.to_string();
+      job.percent = overall;
+    });
+  }
+}
+
+// 发送作业事件（失败只打印）。
+fn emit_job(app: &tauri::AppHandle, job: &MediaJob) {
+  if let Err(err) = app.emit(PROGRESS_EVENT, job) {
+    app_log!("发送媒体作业事件失败: {err}");
+  }
+}
+
+// 修改作业；百分比（取整）、阶段或状态有变化时发送事件。
+fn update_job(app: &tauri::AppHandle, id: u64, f: impl FnOnce(&mut MediaJob)) {
+  let changed = {
+    let mut jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(job) = jobs.get_mut(&id) else {
+      return;
+    };
+    let before = (job.status, job.phase.clone(), job.percent.floor());
+    f(job);
+    (before != (job.status, job.phase.clone(), job.percent.floor())).then(|| job.clone())
+  };
+  if let Some(job) = changed {
+    emit_job(app, &job);
+  }
+}
+
+// 新建作业（排队中），返回作业 ID。
+pub(crate) fn create_job(app: &tauri::AppHandle, kind: &str, output_path: &str) -> u64 {
+  let id = NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed);
+  let job = MediaJob {
+    id,
+    kind: kind.to_string(),
+    status: MediaJobStatus::Queued,
+    phase: "等待中".to_string(),
+    percent: 0.0,
+    output_path: output_path.to_string(),
+    error: None,
+    log: Vec::new(),
+    created_at: Utc::now().to_rfc3339(),
+    finished_at: None,
+    cancel: CancellationToken::new(),
+    cleanup: Vec::new(),
+  };
+  {
+    let mut jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    jobs.insert(id, job.clone());
+    // 移除最早结束的作业，只保留最近的一部分。
+    let finished: Vec<u64> = jobs
+      .values()
+      .filter(|job| job.status.is_finished())
+      .map(|job| job.id)
+      .collect();
+    for id in finished.iter().take(finished.len().saturating_sub(MAX_FINISHED_JOBS)) {
+      jobs.remove(id);
+    }
+  }
+  emit_job(app, &job);
+  id
+}
+
+// 向作业日志追加一条记录并发送事件。
+pub(crate) fn append_job_log(app: &tauri::AppHandle, id: u64, message: impl Into<String>) {
+  let job = {
+    let mut jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(job) = jobs.get_mut(&id) else {
+      return;
+    };
+    job.log.push(message.into());
+    job.clone()
+  };
+  emit_job(app, &job);
+}
+
+// 登记取消时要删除的文件或目录（作业正常结束或失败时不删除）。
+pub(crate) fn add_cleanup(id: u64, path: &Path) {
+  let mut jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+  if let Some(job) = jobs.get_mut(&id) {
+    job.cleanup.push(path.to_path_buf());
+  }
+}
+
+// 删除文件或目录，失败时稍等后重试；不存在视为成功。
+async fn remove_path(path: &Path) {
+  for attempt in 0..CLEANUP_ATTEMPTS {
+    let result = if path.is_dir() {
+      std::fs::remove_dir_all(path)
+    } else {
+      std::fs::remove_file(path)
+    };
+    match result {
+      Ok(()) => return,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => return,
+      Err(err) if attempt + 1 == CLEANUP_ATTEMPTS => {
+        app_log!("清理 {} 失败: {err}", path.display());
+      }
+      Err(_) => tokio::time::sleep(CLEANUP_RETRY_DELAY).await,
+    }
+  }
+}
+
+// 在后台执行作业：future 结束后记录结果；作业被取消时丢弃 future（子进程随之结束），
+// 删除登记的文件并标记为已取消。
+pub(crate) fn spawn_job<F>(app: &tauri::AppHandle, id: u64, job: F)
+where
+  F: Future<Output = Result<(), AppError>> + Send + 'static,
+{
+  let Some(cancel) = JOBS
+    .lock()
+    .unwrap_or_else(|e| e.into_inner())
+    .get(&id)
+    .map(|job| job.cancel.clone())
+  else {
+    return;
+  };
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    // 作业与取消同时就绪时以作业结果为准，避免删掉已经写完的输出。
+    let result = tokio::select! {
+      biased;
+      result = job => Some(result),
+      _ = cancel.cancelled() => None,
+    };
+    let Some(result) = result else {
+      let cleanup = JOBS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&id)
+        .map(|job| job.cleanup.clone())
+        .unwrap_or_default();
+      for path in &cleanup {
+        remove_path(path).await;
+      }
+      update_job(&app, id, |job| {
+        job.status = MediaJobStatus::Cancelled;
+        job.phase = "已取消".to_string();
+        job.finished_at = Some(Utc::now().to_rfc3339());
+      });
+      return;
+    };
+    update_job(&app, id, |job| {
+      job.finished_at = Some(Utc::now().to_rfc3339());
+      match result {
+        Ok(()) => {
+          job.status = MediaJobStatus::Succeeded;
+          job.phase = "完成".to_string();
+          job.percent = 100.0;
+        }
+        Err(err) => {
+          job.status = MediaJobStatus::Failed;
+          job.error = Some(err.to_string());
+        }
+      }
+    });
+  });
+}
+
+// 从 --gui-mode 的一行输出中解析进度百分比。
+fn parse_gui_progress(line: &str) -> Option<f64> {
+  line
+    .trim()
+    .strip_prefix(GUI_PROGRESS_PREFIX)?
+    .trim()
+    .trim_end_matches('%')
+    .parse()
+    .ok()
+}
+
+// 以 --gui-mode 运行 MKVToolNix 的工具（mkvmerge / mkvextract，tool 为工具名，用于错误信息），
+// 逐行读取标准输出报告进度；返回 (是否成功, 退出码, 除进度行以外的输出)，便于调用方拼出错误信息。
+pub(crate) async fn run_mkvtoolnix_with_progress(
+  tool: &str,
+  tool_path: &Path,
+  args: &[String],
+  step: &JobStep<'_>,
+) -> Result<(bool, Option<i32>, String), AppError> {
+  let mut child = Command::new(tool_path)
+    .arg("--gui-mode")
+    .args(args)
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()
+    .map_err(|e| AppError::tool_failed(tool, format!("调用 {tool} 失败: {e}")))?;
+  let stdout = child.stdout.take();
+  let stderr = child.stderr.take();
+  step.report(0.0);
+
+  // 同时读取 stdout 与 stderr，避免某一个管道写满后子进程阻塞。
+  let read_stdout = async {
+    let mut messages = Vec::new();
+    if let Some(stdout) = stdout {
+      let mut lines = BufReader::new(stdout).lines();
+      while let Ok(Some(line)) = lines.next_line().await {
+        match parse_gui_progress(&line) {
+          Some(percent) => step.report(percent),
+          None if !line.trim().is_empty() => messages.push(line.trim().to_string()),
+          None => {}
+        }
+      }
+    }
+    messages.join("\n")
+  };
+  let read_stderr = async {
+    let mut buffer = Vec::new();
+    if let Some(mut stderr) = stderr {
+      let _ = stderr.read_to_end(&mut buffer).await;
+    }
+    String::from_utf8_lossy(&buffer).trim().to_string()
+  };
+  let (messages, errors) = tokio::join!(read_stdout, read_stderr);
+  let status = child
+    .wait()
+    .await
+    .map_err(|e| AppError::tool_failed(tool, format!("等待 {tool} 结束失败: {e}")))?;
+  let output = [messages, errors]
+    .into_iter()
+    .filter(|text| !text.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n");
+  if status.success() {
+    step.report(100.0);
+  }
+  Ok((status.success(), status.code(), output))
+}
+
+// 从 ffmpeg -progress 的一行输出中解析已处理的时长（秒）；还没有时间戳时为 N/A，返回 None。
+fn parse_ffmpeg_out_time(line: &str) -> Option<f64> {
+  let micros: f64 = line.trim().strip_prefix(FFMPEG_OUT_TIME_KEY)?.parse().ok()?;
+  Some(micros / 1_000_000.0)
+}
+
+// 运行 ffmpeg（附加 -progress pipe:1），按已处理时长与总时长 duration（秒）报告进度；
+// 总时长未知时只报告开始与结束。返回 (是否成功, 退出码, 标准错误输出的末尾几行)。
+pub(crate) async fn run_ffmpeg_with_progress(
+  ffmpeg_path: &Path,
+  args: &[String],
+  duration: Option<f64>,
+  step: &JobStep<'_>,
+) -> Result<(bool, Option<i32>, String), AppError> {
+  let mut child = Command::new(ffmpeg_path)
+    .args([
+      "-hide_banner",
+      "-nostdin",
+      "-nostats",
+      "-loglevel",
+      "error",
+      "-progress",
+      "pipe:1",
+    ])
+    .args(args)
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()
+    .map_err(|e| AppError::tool_failed("ffmpeg", format!("调用 ffmpeg 失败: {e}")))?;
+  let stdout = child.stdout.take();
+  let stderr = child.stderr.take();
+  step.report(0.0);
+
+  let read_stdout = async {
+    if let Some(stdout) = stdout {
+      let mut lines = BufReader::new(stdout).lines();
+      while let Ok(Some(line)) = lines.next_line().await {
+        let total = duration.filter(|total| *total > 0.0);
+        if let (Some(done), Some(total)) = (parse_ffmpeg_out_time(&line), total) {
+          step.report(done / total * 100.0);
+        }
+      }
+    }
+  };
+  let read_stderr = async {
+    let mut buffer = Vec::new();
+    if let Some(mut stderr) = stderr {
+      let _ = stderr.read_to_end(&mut buffer).await;
+    }
+    let text = String::from_utf8_lossy(&buffer);
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    lines[lines.len().saturating_sub(FFMPEG_ERROR_LINES)..].join("\n")
+  };
+  let ((), errors) = tokio::join!(read_stdout, read_stderr);
+  let status = child
+    .wait()
+    .await
+    .map_err(|e| AppError::tool_failed("ffmpeg", format!("等待 ffmpeg 结束失败: {e}")))?;
+  if status.success() {
+    step.report(100.0);
+  }
+  Ok((status.success(), status.code(), errors))
+}
+
+// 查询单个媒体作业（已被清理或不存在时返回 InvalidInput）。
+#[tauri::command]
+pub fn get_media_job(job_id: u64) -> Result<MediaJob, AppError> {
+  JOBS
+    .lock()
+    .unwrap_or_else(|e| e.into_inner())
+    .get(&job_id)
+    .cloned()
+    .ok_or_else(|| AppError::invalid_input(format!("媒体作业不存在: {job_id}")))
+}
+
+// 取消排队中或执行中的媒体作业；作业不存在或已经结束时返回 InvalidInput。
+// 取消是异步完成的：子进程结束、临时文件清理后发送状态为 cancelled 的 media://mux-progress 事件。
+#[tauri::command]
+pub fn cancel_media_job(job_id: u64) -> Result<(), AppError> {
+  let jobs = JOBS.lock().unwrap_or_else(|e| e.into_inner());
+  let job = jobs
+    .get(&job_id)
+    .ok_or_else(|| AppError::invalid_input(format!("媒体作业不存在: {job_id}")))?;
+  if job.status.is_finished() {
+    return Err(AppError::invalid_input("媒体作业已经结束"));
+  }
+  job.cancel.cancel();
+  Ok(())
+}
+
+// 返回全部媒体作业（最新的在前）。
+#[tauri::command]
+pub fn list_media_jobs() -> Vec<MediaJob> {
+  JOBS
+    .lock()
+    .unwrap_or_else(|e| e.into_inner())
+    .values()
+    .rev()
+    .cloned()
+    .collect()
+}
@@ -1,7 +1,13 @@
 /*
   媒体轨道相关功能：
   - 使用 mkvmerge 或 ffprobe 解析媒体文件的轨道信息；
-  - 支持混流（把视频/音频/字幕轨道合并成一个文件）；
+  - 支持混流（把视频/音频/字幕轨道合并成一个文件），可以附加字幕用到的字体；
+  - 支持整季批量混流（按集数配对视频、音频、字幕文件）；
+  - 支持把音频、字幕轨道与附件提取成独立文件（混流的逆操作）；
+  - 使用 mkvpropedit 直接修改 MKV 的轨道属性、标题与附件（不重新混流）；
+  - 使用 ffmpeg 按时间点截图（HDR 片源可以色调映射为 SDR）；
+  - 使用 ffmpeg 按预设转码视频；
+  - 字幕格式（ASS / SRT / VTT）与编码转换、时间调整、繁简转换；
   - 提供文件大小等基础信息。
   该模块主要服务于前端的“轨道选择/混流”功能。
 */
@@ -13,6 +19,25 @@ use std::path::{Path, PathBuf};
 use tokio::process::Command;
 use tauri::Manager;
 
+use super::error::AppError;
+use super::limits::{acquire, LimitKind};
+use super::paths::ensure_path_allowed;
+use super::power::keep_awake;
+
+pub mod batch;
+pub mod extract;
+pub mod fonts;
+pub mod jobs;
+pub mod propedit;
+pub mod screenshot;
+mod subset;
+pub mod subtitle;
+pub mod transcode;
+
+use fonts::font_mime_type;
+use jobs::{add_cleanup, append_job_log, create_job, run_mkvtoolnix_with_progress, spawn_job, JobStep};
+use transcode::{check_track_id, transcode_audio_track, AudioTranscode};
+
 /// 单条轨道信息（返回给前端）。
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -38,6 +63,8 @@ pub struct TrackParseResponse {
 }
 
 /// 混流输入：一个媒体文件 + 选中的轨道。
+/// 同一类型可以有多个文件（例如日语 FLAC + 评论音轨、简中 + 繁中 + 英文字幕），
+/// 输出中的轨道按视频、音频、字幕排列，同一类型按输入顺序排列。
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MixTrackInput {
@@ -46,6 +73,29 @@ pub struct MixTrackInput {
   pub track_ids: Vec<String>,
   #[serde(default)]
   pub track_langs: HashMap<String, String>,
+  /// 按轨道 ID 指定的名称、默认/强制标记与延迟。
+  #[serde(default)]
+  pub track_options: HashMap<String, MixTrackOptions>,
+}
+
+/// 单条轨道的混流选项，省略的字段使用默认行为。
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MixTrackOptions {
+  /// 轨道名称（例如“简日双语”）；省略时清空原有名称。
+  pub name: Option<String>,
+  /// 是否为默认轨道；省略时同类型第一个文件的轨道为默认轨道，
+  /// 但同类型有轨道显式设为默认时，其余省略的轨道都不是默认轨道。
+  pub default: Option<bool>,
+  /// 是否强制显示；省略时为否。
+  pub forced: Option<bool>,
+  /// 延迟（毫秒，可为负数）；省略时不调整。
+  pub delay: Option<i64>,
+  /// 时间戳拉伸系数（时间戳乘以该值，修正线性漂移，例如 25 帧 PAL 音轨配 23.976 帧视频时为 25/23.976）；
+  /// 省略时不拉伸。
+  pub stretch: Option<f64>,
+  /// 先把音轨转成 AAC / Opus（可选缩混为立体声），混流时用转码结果替换原音轨；只能用于音频轨道。
+  pub transcode: Option<AudioTranscode>,
 }
 
 // ffprobe 的 format 节点（只保留需要的字段）。
@@ -55,11 +105,12 @@ struct FFProbeFormat {
   size: Option<String>,
 }
 
-// ffprobe 的流 disposition（默认/强制标记）。
+// ffprobe 的流 disposition（默认/强制标记、是否为封面图片）。
 #[derive(Clone, Deserialize)]
 struct FFProbeDisposition {
   default: Option<i32>,
   forced: Option<i32>,
+  attached_pic: Option<i32>,
 }
 
 // ffprobe 的 tags 节点（语言/标题/编码等）。
@@ -82,6 +133,8 @@ struct FFProbeStream {
   r_frame_rate: Option<String>,
   channels: Option<u32>,
   channel_layout: Option<String>,
+  color_space: Option<String>,
+  color_transfer: Option<String>,
   disposition: Option<FFProbeDisposition>,
   tags: Option<FFProbeStreamTags>,
 }
@@ -120,6 +173,7 @@ struct MkvmergeTrackProperties {
   pixel_dimensions: Option<String>,
   audio_channels: Option<u32>,
   audio_sampling_frequency: Option<f64>,
+  uid: Option<u64>,
 }
 
 // mkvmerge 的单条轨道信息。
@@ -131,15 +185,32 @@ struct MkvmergeTrack {
   properties: Option<MkvmergeTrackProperties>,
 }
 
+// mkvmerge 的附件属性。
+#[derive(Deserialize)]
+struct MkvmergeAttachmentProperties {
+  uid: Option<u64>,
+}
+
+// mkvmerge 的附件信息（字体等）。
+#[derive(Deserialize)]
+struct MkvmergeAttachment {
+  id: u32,
+  file_name: Option<String>,
+  content_type: Option<String>,
+  size: Option<u64>,
+  properties: Option<MkvmergeAttachmentProperties>,
+}
+
 // mkvmerge 的整体输出。
 #[derive(Deserialize)]
 struct MkvmergeOutput {
   container: Option<MkvmergeContainer>,
   tracks: Option<Vec<MkvmergeTrack>>,
+  attachments: Option<Vec<MkvmergeAttachment>>,
 }
 
 // 把字节数转换成人类可读的大小（B/KB/MB/GB/TB）。
-fn format_bytes_readable(bytes: u64) -> String {
+pub(crate) fn format_bytes_readable(bytes: u64) -> String {
   const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
   let mut size = bytes as f64;
   let mut idx = 0;
@@ -156,11 +227,11 @@ fn format_bytes_readable(bytes: u64) -> String {
 
 // 解析内置工具（mkvmerge/ffprobe）的路径。
 // 会优先在打包资源里找，开发模式下也会尝试 public/tools。
-fn resolve_tool_path(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+pub(crate) fn resolve_tool_path(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, AppError> {
   let resource_dir = app
     .path()
     .resource_dir()
-    .map_err(|e| format!("无法获取资源目录: {e}"))?;
+    .map_err(|e| AppError::io(format!("无法获取资源目录: {e}")))?;
   let mut candidates = Vec::new();
   if cfg!(target_os = "windows") {
     candidates.push(resource_dir.join("bin").join(format!("{name}.exe")));
@@ -180,7 +251,137 @@ fn resolve_tool_path(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, Stri
       return Ok(path);
     }
   }
-  Err(format!("未找到内置工具 {name}，请检查打包资源是否包含对应文件"))
+  Err(AppError::tool_missing(
+    name,
+    format!("未找到内置工具 {name}，请检查打包资源是否包含对应文件"),
+  ))
+}
+
+// MKV 系列（mkv / mka / mks）的文件，这类文件用 mkvmerge / mkvextract 处理。
+fn is_matroska(path: &Path) -> bool {
+  let ext = path
+    .extension()
+    .and_then(|s| s.to_str())
+    .unwrap_or("")
+    .to_lowercase();
+  ["mkv", "mka", "mks"].contains(&ext.as_str())
+}
+
+// 调用 mkvmerge -J 读取文件的轨道与附件信息。
+async fn identify_matroska(mkvmerge_path: &Path, path: &str) -> Result<MkvmergeOutput, AppError> {
+  let output = Command::new(mkvmerge_path)
+    .args(["-J", path])
+    .output()
+    .await
+    .map_err(|e| AppError::tool_failed("mkvmerge", format!("调用 mkvmerge 失败: {e}")))?;
+
+  // mkvmerge 返回非 0 则报错。
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    return Err(AppError::tool_failed("mkvmerge", format!("mkvmerge 执行失败: {stderr}")));
+  }
+
+  // 解析 mkvmerge JSON 输出。
+  serde_json::from_slice(&output.stdout)
+    .map_err(|e| AppError::tool_failed("mkvmerge", format!("解析 mkvmerge 输出失败: {e}")))
+}
+
+// 调用 ffprobe 读取文件的容器与流信息。
+async fn probe_streams(ffprobe_path: &Path, path: &str) -> Result<FFProbeOutput, AppError> {
+  let output = Command::new(ffprobe_path)
+    .args([
+      "-v",
+      "error",
+      "-print_format",
+      "json",
+      "-show_format",
+      "-show_streams",
+      path,
+    ])
+    .output()
+    .await
+    .map_err(|e| AppError::tool_failed("ffprobe", format!("调用 ffprobe 失败: {e}")))?;
+
+  // ffprobe 返回非 0 则报错。
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    return Err(AppError::tool_failed("ffprobe", format!("ffprobe 执行失败: {stderr}")));
+  }
+
+  // 解析 ffprobe JSON 输出。
+  serde_json::from_slice(&output.stdout)
+    .map_err(|e| AppError::tool_failed("ffprobe", format!("解析 ffprobe 输出失败: {e}")))
+}
+
+// 可能存在的硬件编码器（NVIDIA / Intel / AMD / Apple / Linux VA-API）。
+const HARDWARE_ENCODERS: &[&str] = &[
+  "h264_nvenc",
+  "hevc_nvenc",
+  "av1_nvenc",
+  "h264_qsv",
+  "hevc_qsv",
+  "av1_qsv",
+  "h264_amf",
+  "hevc_amf",
+  "av1_amf",
+  "h264_videotoolbox",
+  "hevc_videotoolbox",
+  "h264_vaapi",
+  "hevc_vaapi",
+  "av1_vaapi",
+];
+
+// 探测本机真正可用的硬件编码器。
+// ffmpeg 编译时带了某个编码器，不代表本机有对应硬件，
+// 所以先用 `ffmpeg -encoders` 筛出候选，再逐个试编码 1 帧确认。
+// 找不到 ffmpeg 或探测失败时返回空列表。
+pub(crate) async fn probe_hardware_encoders(app: &tauri::AppHandle) -> Vec<String> {
+  let ffmpeg_path = match resolve_tool_path(app, "ffmpeg") {
+    Ok(path) => path,
+    Err(_) => return Vec::new(),
+  };
+  let listed = match Command::new(&ffmpeg_path)
+    .args(["-hide_banner", "-encoders"])
+    .output()
+    .await
+  {
+    Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+    Err(_) => return Vec::new(),
+  };
+  // 输出每行形如 " V....D h264_nvenc  NVIDIA NVENC H.264 encoder"，第二列是名称。
+  let candidates: Vec<&str> = HARDWARE_ENCODERS
+    .iter()
+    .copied()
+    .filter(|name| {
+      listed
+        .lines()
+        .any(|line| line.split_whitespace().nth(1) == Some(*name))
+    })
+    .collect();
+
+  let mut usable = Vec::new();
+  for name in candidates {
+    // VA-API 需要先把帧上传到 GPU，其它编码器直接接收软件帧即可。
+    let mut args: Vec<&str> = vec!["-hide_banner", "-v", "error"];
+    if name.ends_with("_vaapi") {
+      args.extend(["-vaapi_device", "/dev/dri/renderD128"]);
+    }
+    args.extend(["-f", "lavfi", "-i", "color=c=black:s=256x256:d=0.1", "-frames:v", "1"]);
+    if name.ends_with("_vaapi") {
+      args.extend(["-vf", "format=nv12,hwupload"]);
+    }
+    args.extend(["-c:v", name, "-f", "null", "-"]);
+    let ok = Command::new(&ffmpeg_path)
+      .args(&args)
+      .output()
+      .await
+      .map(|output| output.status.success())
+      .unwrap_or(false);
+    if ok {
+      usable.push(name.to_string());
+    }
+  }
+  usable
 }
 
 // 把语言代码映射为人类可读的中文名称。
@@ -259,42 +460,28 @@ fn build_mkvmerge_attributes(props: &MkvmergeTrackProperties, kind: &str) -> Opt
 }
 
 // 解析媒体文件中的轨道信息。
-// - MKV 系列使用 mkvmerge（信息更完整）
+// - MKV 系列使用 mkvmerge（信息更完整），kind 为 attachment 时返回附件
 // - 其他格式使用 ffprobe
 #[tauri::command]
 pub async fn parse_media_tracks(
   app: tauri::AppHandle,
   path: String,
   kind: String,
-) -> Result<TrackParseResponse, String> {
+) -> Result<TrackParseResponse, AppError> {
+  // 只允许访问授权范围内的文件，并改用规范化后的路径。
+  let path = ensure_path_allowed(&app, &path)
+    .map_err(AppError::invalid_input)?
+    .to_string_lossy()
+    .to_string();
   // 统一轨道类型为小写，便于比较。
   let kind_lower = kind.to_lowercase();
-  let ext = Path::new(&path)
-    .extension()
-    .and_then(|s| s.to_str())
-    .unwrap_or("")
-    .to_lowercase();
 
   // MKV 系列文件用 mkvmerge 解析。
-  if ["mkv", "mka", "mks"].contains(&ext.as_str()) {
+  if is_matroska(Path::new(&path)) {
     // 找到 mkvmerge 工具路径。
     let mkvmerge_path = resolve_tool_path(&app, "mkvmerge")?;
     // 调用 mkvmerge 输出 JSON。
-    let output = Command::new(mkvmerge_path)
-      .args(["-J", &path])
-      .output()
-      .await
-      .map_err(|e| format!("调用 mkvmerge 失败: {e}"))?;
-
-    // mkvmerge 返回非 0 则报错。
-    if !output.status.success() {
-      let stderr = String::from_utf8_lossy(&output.stderr);
-      return Err(format!("mkvmerge 执行失败: {stderr}"));
-    }
-
-    // 解析 mkvmerge JSON 输出。
-    let parsed: MkvmergeOutput = serde_json::from_slice(&output.stdout)
-      .map_err(|e| format!("解析 mkvmerge 输出失败: {e}"))?;
+    let parsed = identify_matroska(&mkvmerge_path, &path).await?;
 
     // 读取容器信息（类型 + 文件大小）。
     let container = parsed
@@ -308,6 +495,31 @@ pub async fn parse_media_tracks(
       .and_then(|p| p.file_size)
       .map(format_bytes_readable);
 
+    // 附件（字体等）：trackId 为附件 ID，codec 为 MIME 类型，trackName 为附件名。
+    if kind_lower == "attachment" {
+      let tracks = parsed
+        .attachments
+        .unwrap_or_default()
+        .into_iter()
+        .map(|attachment| TrackInfoResponse {
+          track_id: attachment.id.to_string(),
+          codec: attachment
+            .content_type
+            .unwrap_or_else(|| "application/octet-stream".to_string()),
+          lang: None,
+          language_name: None,
+          track_name: attachment.file_name,
+          is_default: None,
+          is_forced: None,
+          charset: None,
+          attributes: attachment.size.map(format_bytes_readable),
+          container: container.clone(),
+          file_size: file_size.clone(),
+        })
+        .collect();
+      return Ok(TrackParseResponse { tracks });
+    }
+
     // 过滤并映射轨道信息。
     let tracks = parsed
       .tracks
@@ -334,6 +546,7 @@ pub async fn parse_media_tracks(
           pixel_dimensions: None,
           audio_channels: None,
           audio_sampling_frequency: None,
+          uid: None,
         });
         // 优先使用 IETF 语言代码，没有再回退到旧字段。
         let lang = props.language_ietf.clone().or(props.language.clone());
@@ -369,29 +582,7 @@ pub async fn parse_media_tracks(
   // 非 MKV 格式：使用 ffprobe 解析。
   let ffprobe_path = resolve_tool_path(&app, "ffprobe")?;
   // 调用 ffprobe 输出 JSON。
-  let output = Command::new(ffprobe_path)
-    .args([
-      "-v",
-      "error",
-      "-print_format",
-      "json",
-      "-show_format",
-      "-show_streams",
-      &path,
-    ])
-    .output()
-    .await
-    .map_err(|e| format!("调用 ffprobe 失败: {e}"))?;
-
-  // ffprobe 返回非 0 则报错。
-  if !output.status.success() {
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    return Err(format!("ffprobe 执行失败: {stderr}"));
-  }
-
-  // 解析 ffprobe JSON 输出。
-  let parsed: FFProbeOutput = serde_json::from_slice(&output.stdout)
-    .map_err(|e| format!("解析 ffprobe 输出失败: {e}"))?;
+  let parsed = probe_streams(&ffprobe_path, &path).await?;
 
   // 读取容器信息（类型 + 文件大小）。
   let container = parsed
@@ -429,6 +620,7 @@ pub async fn parse_media_tracks(
         .unwrap_or(FFProbeDisposition {
           default: None,
           forced: None,
+          attached_pic: None,
         });
       TrackInfoResponse {
         track_id: stream.index.unwrap_or(0).to_string(),
@@ -451,103 +643,566 @@ pub async fn parse_media_tracks(
 
 // 获取媒体文件大小（人类可读格式）。
 #[tauri::command]
-pub async fn get_media_file_size(path: String) -> Result<Option<String>, String> {
-  let meta = fs::metadata(&path).map_err(|e| format!("读取文件大小失败: {e}"))?;
+pub async fn get_media_file_size(app: tauri::AppHandle, path: String) -> Result<Option<String>, AppError> {
+  let path = ensure_path_allowed(&app, &path).map_err(AppError::invalid_input)?;
+  let meta = fs::metadata(&path).map_err(|e| AppError::io(format!("读取文件大小失败: {e}")))?;
   Ok(Some(format_bytes_readable(meta.len())))
 }
 
-// 将不同类型的轨道混合成一个输出文件。
-// 流程：
-// 1) 校验输入；
-// 2) 为每种轨道生成临时文件；
-// 3) 再把临时文件合并成最终文件。
-#[tauri::command]
-pub async fn mix_media_tracks(
-  app: tauri::AppHandle,
-  inputs: Vec<MixTrackInput>,
-  output_path: String,
-) -> Result<String, String> {
-  // 没有输入直接报错。
-  if inputs.is_empty() {
-    return Err("未提供可合成的轨道".to_string());
+// 为不同轨道类型指定默认语言代码。
+fn lang_for_kind(kind: &str) -> &'static str {
+  match kind {
+    "video" => "ja",
+    "audio" => "ja",
+    "subtitle" => "zh-Hans",
+    _ => "und",
   }
+}
 
-  // 自动补充输出后缀名（默认 mkv）。
-  let mut output = PathBuf::from(&output_path);
-  if output.extension().is_none() {
-    output.set_extension("mkv");
+// 生成命令行参数字符串（包含必要的引号转义）。
+fn format_arg(arg: &str) -> String {
+  if arg.contains(' ') || arg.contains('\t') || arg.contains('"') {
+    format!("\"{}\"", arg.replace('"', "\\\""))
+  } else {
+    arg.to_string()
   }
-  // 确保输出目录存在。
-  if let Some(parent) = output.parent() {
-    if !parent.exists() {
-      fs::create_dir_all(parent).map_err(|e| format!("创建输出目录失败: {e}"))?;
+}
+
+// 用于错误日志：把工具名与参数拼成可读的命令行字符串。
+fn build_cmdline(tool: &str, args: &[String]) -> String {
+  let mut cmdline = Vec::new();
+  cmdline.push(format_arg(tool));
+  for arg in args {
+    cmdline.push(format_arg(arg));
+  }
+  cmdline.join(" ")
+}
+
+// mkvmerge 的退出码：1 表示有警告但输出完整，2 表示出错。
+const MKVMERGE_WARNING_CODE: i32 = 1;
+const MKVMERGE_ERROR_CODE: i32 = 2;
+
+// mkvmerge 失败时的错误（带命令行）。
+fn mkvmerge_error(code: Option<i32>, output: &str, args: &[String]) -> AppError {
+  AppError::tool_failed(
+    "mkvmerge",
+    format!(
+      "mkvmerge 执行失败 (code {:?}): {}\n命令: {}",
+      code,
+      output,
+      build_cmdline("mkvmerge", args)
+    ),
+  )
+}
+
+// 执行 mkvmerge 并报告进度：退出码 1（只有警告）视为成功，警告写入作业日志；
+// mkvmerge 报错时返回 Ok(Some((退出码, 输出)))，交给调用方决定如何处理；工具无法运行等返回 Err。
+async fn try_mkvmerge(
+  mkvmerge_path: &Path,
+  args: &[String],
+  step: &JobStep<'_>,
+) -> Result<Option<(Option<i32>, String)>, AppError> {
+  let (success, code, output) = run_mkvtoolnix_with_progress("mkvmerge", mkvmerge_path, args, step).await?;
+  if success {
+    return Ok(None);
+  }
+  if code == Some(MKVMERGE_WARNING_CODE) {
+    append_job_log(step.app, step.job_id, format!("mkvmerge 警告（{}）: {output}", step.phase));
+    return Ok(None);
+  }
+  Ok(Some((code, output)))
+}
+
+// 执行 mkvmerge，报错时返回带命令行的错误。
+async fn run_mkvmerge(mkvmerge_path: &Path, args: &[String], step: &JobStep<'_>) -> Result<(), AppError> {
+  match try_mkvmerge(mkvmerge_path, args, step).await? {
+    None => Ok(()),
+    Some((code, output)) => Err(mkvmerge_error(code, &output, args)),
+  }
+}
+
+// 检查输出目录可写：写一个探测文件再删除。混流前确认过，mkvmerge 报错就可以归因于输入，放心改用分步混流。
+fn ensure_dir_writable(dir: &Path) -> Result<(), AppError> {
+  let probe = dir.join(format!(".hanamirip-write-test-{}", std::process::id()));
+  fs::OpenOptions::new()
+    .write(true)
+    .create_new(true)
+    .open(&probe)
+    .map_err(|e| AppError::io(format!("输出目录不可写 ({}): {e}", dir.display())))?;
+  let _ = fs::remove_file(&probe);
+  Ok(())
+}
+
+// 轨道类型在输出中的排列顺序（视频、音频、字幕）；不支持的类型返回 None。
+fn kind_rank(kind: &str) -> Option<usize> {
+  ["video", "audio", "subtitle"].iter().position(|item| *item == kind)
+}
+
+// 轨道类型的中文名称（作业阶段说明用）。
+fn kind_label(kind: &str) -> &'static str {
+  match kind {
+    "video" => "视频",
+    "audio" => "音频",
+    "subtitle" => "字幕",
+    _ => "其它",
+  }
+}
+
+// 一个输入文件的 mkvmerge 参数：轨道选择与逐轨道选项，不含文件路径。
+// default_flag 为没有显式指定默认标记的轨道使用的默认标记。
+fn source_args(input: &MixTrackInput, default_flag: bool) -> Vec<String> {
+  let kind = input.kind.as_str();
+  let mut args: Vec<String> = Vec::new();
+
+  // 根据类型选择要保留的轨道。
+  match kind {
+    "video" => {
+      args.push("--video-tracks".to_string());
+      args.push(input.track_ids.join(","));
+      args.push("--audio-tracks".to_string());
+      args.push("-1".to_string());
+      args.push("--subtitle-tracks".to_string());
+      args.push("-1".to_string());
+    }
+    "audio" => {
+      args.push("--audio-tracks".to_string());
+      args.push(input.track_ids.join(","));
+      args.push("--video-tracks".to_string());
+      args.push("-1".to_string());
+      args.push("--subtitle-tracks".to_string());
+      args.push("-1".to_string());
+    }
+    "subtitle" => {
+      args.push("--subtitle-tracks".to_string());
+      args.push(input.track_ids.join(","));
+      args.push("--video-tracks".to_string());
+      args.push("-1".to_string());
+      args.push("--audio-tracks".to_string());
+      args.push("-1".to_string());
     }
+    _ => {}
   }
 
-  // 混流依赖 mkvmerge。
-  let mkvmerge_path = resolve_tool_path(&app, "mkvmerge")?;
+  // 为每条轨道设置名称/语言/默认/强制标记与延迟。
+  let lang = lang_for_kind(kind);
+  let flag = |value: bool| if value { "yes" } else { "no" };
+  for track_id in &input.track_ids {
+    let options = input.track_options.get(track_id).cloned().unwrap_or_default();
+    args.push("--track-name".to_string());
+    args.push(format!("{track_id}:{}", options.name.unwrap_or_default()));
+    args.push("--default-track-flag".to_string());
+    args.push(format!("{track_id}:{}", flag(options.default.unwrap_or(default_flag))));
+    args.push("--forced-display-flag".to_string());
+    args.push(format!("{track_id}:{}", flag(options.forced.unwrap_or(false))));
+    // --sync TID:延迟[,系数]，延迟先于拉伸生效。
+    let delay = options.delay.unwrap_or(0);
+    match options.stretch.filter(|stretch| *stretch != 1.0) {
+      Some(stretch) => {
+        args.push("--sync".to_string());
+        args.push(format!("{track_id}:{delay},{stretch}"));
+      }
+      None if delay != 0 => {
+        args.push("--sync".to_string());
+        args.push(format!("{track_id}:{delay}"));
+      }
+      None => {}
+    }
+    args.push("--language".to_string());
+    let custom_lang = input.track_langs.get(track_id).map(|v| v.as_str());
+    let final_lang = custom_lang.unwrap_or(lang);
+    args.push(format!("{track_id}:{final_lang}"));
+  }
+  args
+}
 
-  // 为不同轨道类型指定默认语言代码。
-  fn lang_for_kind(kind: &str) -> &'static str {
-    match kind {
-      "video" => "ja",
-      "audio" => "ja",
-      "subtitle" => "zh-Hans",
-      _ => "und",
+// 每个输入未显式指定默认标记时使用的值：同类型的第一个文件为默认；
+// 同类型有轨道显式设为默认时，其余轨道都不再自动设为默认。
+fn default_flags(inputs: &[MixTrackInput]) -> Vec<bool> {
+  inputs
+    .iter()
+    .enumerate()
+    .map(|(index, input)| {
+      let first = !inputs[..index].iter().any(|item| item.kind == input.kind);
+      let explicit_default = inputs
+        .iter()
+        .filter(|item| item.kind == input.kind)
+        .any(|item| item.track_options.values().any(|options| options.default == Some(true)));
+      first && !explicit_default
+    })
+    .collect()
+}
+
+// 附加字体的参数：显式指定附件名与 MIME 类型（mkvmerge 旧版本对字体的自动识别结果不统一）。
+fn attachment_args(attachments: &[PathBuf]) -> Vec<String> {
+  let mut args = Vec::new();
+  for path in attachments {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    args.push("--attachment-name".to_string());
+    args.push(name);
+    args.push("--attachment-mime-type".to_string());
+    args.push(font_mime_type(path).unwrap_or("application/octet-stream").to_string());
+    args.push("--attach-file".to_string());
+    args.push(path.to_string_lossy().to_string());
+  }
+  args
+}
+
+// 单次混流的参数：一次 mkvmerge 调用直接从各个源文件选取轨道写入输出。
+// 同一个文件出现多次时（例如分别选了其中的视频与音频轨道），只从第一次出现时带入章节、附件与全局标签，避免重复。
+fn single_pass_args(
+  inputs: &[MixTrackInput],
+  defaults: &[bool],
+  attachments: &[PathBuf],
+  output: &Path,
+) -> Vec<String> {
+  let mut args: Vec<String> = vec!["-o".to_string(), output.to_string_lossy().to_string()];
+  for (index, (input, default_flag)) in inputs.iter().zip(defaults).enumerate() {
+    args.extend(source_args(input, *default_flag));
+    if inputs[..index].iter().any(|item| item.path == input.path) {
+      args.push("--no-chapters".to_string());
+      args.push("--no-attachments".to_string());
+      args.push("--no-global-tags".to_string());
     }
+    args.push(input.path.clone());
   }
+  args.extend(attachment_args(attachments));
+  args
+}
 
-  // 生成命令行参数字符串（包含必要的引号转义）。
-  fn format_arg(arg: &str) -> String {
-    if arg.contains(' ') || arg.contains('\t') || arg.contains('"') {
-      format!("\"{}\"", arg.replace('"', "\\\""))
-    } else {
-      arg.to_string()
+// 为一个输入文件生成临时文件（只包含指定轨道）；index 为输入序号，用于区分同类型的多个文件。
+async fn build_temp(
+  index: usize,
+  input: &MixTrackInput,
+  default_flag: bool,
+  temp_root: &Path,
+  mkvmerge_path: &Path,
+  step: &JobStep<'_>,
+) -> Result<PathBuf, AppError> {
+  let kind = input.kind.as_str();
+  // 不同轨道类型使用不同容器后缀。
+  let ext = match kind {
+    "video" => "mkv",
+    "audio" => "mka",
+    "subtitle" => "mks",
+    _ => "mkv",
+  };
+  let temp_path = temp_root.join(format!("{index:02}-{kind}.{ext}"));
+  // mkvmerge 参数列表。
+  let mut args: Vec<String> = Vec::new();
+  args.push("-o".to_string());
+  args.push(temp_path.to_string_lossy().to_string());
+  args.extend(source_args(input, default_flag));
+  args.push(input.path.clone());
+  run_mkvmerge(mkvmerge_path, &args, step).await?;
+  Ok(temp_path)
+}
+
+// 在数据目录的 mix-temp 下为作业创建临时目录，取消作业时一并删除。
+fn create_temp_dir(app: &tauri::AppHandle, job_id: u64) -> Result<PathBuf, AppError> {
+  let temp_root = app
+    .path()
+    .app_data_dir()
+    .map_err(|e| AppError::io(format!("无法获取数据目录: {e}")))?
+    .join("hanamirip-cn")
+    .join("mix-temp")
+    .join(format!("{job_id}-{}", chrono::Utc::now().timestamp_millis()));
+  fs::create_dir_all(&temp_root).map_err(|e| AppError::io(format!("创建临时目录失败: {e}")))?;
+  add_cleanup(job_id, &temp_root);
+  Ok(temp_root)
+}
+
+// 需要转码的音轨数量。
+fn transcode_count(inputs: &[MixTrackInput]) -> usize {
+  inputs
+    .iter()
+    .map(|input| {
+      input
+        .track_ids
+        .iter()
+        .filter(|id| input.track_options.get(*id).is_some_and(|options| options.transcode.is_some()))
+        .count()
+    })
+    .sum()
+}
+
+// 把需要转码的音轨转成临时 mka，并在混流输入里替换原音轨：原输入按轨道拆开，
+// 转码结果（轨道 0）插在原轨道的位置，沿用原轨道的语言与选项，输出轨道顺序不变。
+// 拆开前先把默认标记固定到每条轨道上，避免拆分改变“同类型第一个文件”的判断。
+// 每条音轨的转码是一个作业步骤（共 step_count 步中的前几步）。
+async fn transcode_inputs(
+  app: &tauri::AppHandle,
+  job_id: u64,
+  inputs: Vec<MixTrackInput>,
+  temp_root: &Path,
+  ffmpeg_path: &Path,
+  ffprobe_path: Option<&Path>,
+  step_count: usize,
+) -> Result<Vec<MixTrackInput>, AppError> {
+  let defaults = default_flags(&inputs);
+  let total = transcode_count(&inputs);
+  let mut result: Vec<MixTrackInput> = Vec::new();
+  let mut index = 0;
+  for (mut input, default_flag) in inputs.into_iter().zip(defaults) {
+    for track_id in &input.track_ids {
+      let options = input.track_options.entry(track_id.clone()).or_default();
+      options.default = Some(options.default.unwrap_or(default_flag));
+    }
+    // 连续的不转码轨道保留为同一个输入。
+    let mut pending: Vec<String> = Vec::new();
+    let piece = |input: &MixTrackInput, path: String, track_ids: Vec<String>| MixTrackInput {
+      path,
+      kind: input.kind.clone(),
+      track_langs: track_ids
+        .iter()
+        .filter_map(|id| input.track_langs.get(id).map(|lang| (id.clone(), lang.clone())))
+        .collect(),
+      track_options: track_ids
+        .iter()
+        .filter_map(|id| input.track_options.get(id).map(|options| (id.clone(), options.clone())))
+        .collect(),
+      track_ids,
+    };
+    for track_id in input.track_ids.clone() {
+      let Some(transcode) = input.track_options.get(&track_id).and_then(|options| options.transcode) else {
+        pending.push(track_id);
+        continue;
+      };
+      if !pending.is_empty() {
+        result.push(piece(&input, input.path.clone(), std::mem::take(&mut pending)));
+      }
+      let phase = if total > 1 {
+        format!("转码音频（{}/{total}）", index + 1)
+      } else {
+        "转码音频".to_string()
+      };
+      let step = JobStep {
+        app,
+        job_id,
+        index,
+        count: step_count,
+        phase: &phase,
+      };
+      let temp_path = temp_root.join(format!("transcode-{index:02}.mka"));
+      transcode_audio_track(
+        ffmpeg_path,
+        ffprobe_path,
+        Path::new(&input.path),
+        &track_id,
+        &transcode,
+        &temp_path,
+        &step,
+      )
+      .await?;
+      index += 1;
+      // 转码结果只有一条轨道（ID 0），沿用原轨道的语言与选项。
+      let mut converted = piece(&input, temp_path.to_string_lossy().to_string(), vec![track_id.clone()]);
+      converted.track_ids = vec!["0".to_string()];
+      converted.track_langs = converted
+        .track_langs
+        .remove(&track_id)
+        .map(|lang| HashMap::from([("0".to_string(), lang)]))
+        .unwrap_or_default();
+      converted.track_options = converted
+        .track_options
+        .remove(&track_id)
+        .map(|options| HashMap::from([("0".to_string(), MixTrackOptions { transcode: None, ..options })]))
+        .unwrap_or_default();
+      result.push(converted);
     }
+    if !pending.is_empty() {
+      result.push(piece(&input, input.path.clone(), pending));
+    }
+  }
+  Ok(result)
+}
+
+// 分步混流：为每个输入文件生成临时文件，再合并成最终文件。
+// 单次混流失败时使用（例如某些源文件直接选取轨道时 mkvmerge 无法处理），需要额外的临时空间。
+async fn run_two_pass(
+  app: &tauri::AppHandle,
+  job_id: u64,
+  inputs: &[MixTrackInput],
+  defaults: &[bool],
+  attachments: &[PathBuf],
+  output: &Path,
+  mkvmerge_path: &Path,
+) -> Result<(), AppError> {
+  // 生成临时目录，用于存放中间文件。
+  let temp_root = create_temp_dir(app, job_id)?;
+
+  // 步骤：每个输入文件各提取一次，最后合并一次。
+  let step_count = inputs.len() + 1;
+
+  // 记录每个输入生成的临时文件（已按视频、音频、字幕排列），便于合并与最后清理。
+  let mut temp_files: Vec<PathBuf> = Vec::new();
+  for (index, input) in inputs.iter().enumerate() {
+    let same_kind = inputs.iter().filter(|item| item.kind == input.kind).count();
+    let position = inputs[..index].iter().filter(|item| item.kind == input.kind).count();
+    let phase = if same_kind > 1 {
+      format!("提取{}轨道（{}/{same_kind}）", kind_label(&input.kind), position + 1)
+    } else {
+      format!("提取{}轨道", kind_label(&input.kind))
+    };
+    let step = JobStep {
+      app,
+      job_id,
+      index,
+      count: step_count,
+      phase: &phase,
+    };
+    let path = build_temp(index, input, defaults[index], &temp_root, mkvmerge_path, &step).await?;
+    temp_files.push(path);
+  }
+
+  // 最终合并命令参数。
+  let mut merge_args: Vec<String> = Vec::new();
+  merge_args.push("-o".to_string());
+  merge_args.push(output.to_string_lossy().to_string());
+  for path in &temp_files {
+    merge_args.push(path.to_string_lossy().to_string());
   }
+  merge_args.extend(attachment_args(attachments));
+
+  let step = JobStep {
+    app,
+    job_id,
+    index: step_count - 1,
+    count: step_count,
+    phase: "合并输出",
+  };
+  run_mkvmerge(mkvmerge_path, &merge_args, &step).await?;
+
+  // 清理临时文件（失败不影响最终结果）。
+  for path in temp_files {
+    let _ = fs::remove_file(path);
+  }
+  let _ = fs::remove_dir(&temp_root);
+  Ok(())
+}
+
+// 混流作业本体：等待并发许可后，先把需要转码的音轨转成临时文件，
+// 再用一次 mkvmerge 调用直接生成输出（不产生中间文件）；mkvmerge 报错（退出码 2）时删除不完整的输出，
+// 改用分步混流（单次混流的错误写入作业日志）。输出目录在建作业时已确认可写。
+// ffmpeg 为 (ffmpeg 路径, ffprobe 路径)，有音轨需要转码时才提供。
+async fn run_mix(
+  app: &tauri::AppHandle,
+  job_id: u64,
+  inputs: Vec<MixTrackInput>,
+  attachments: Vec<PathBuf>,
+  output: PathBuf,
+  mkvmerge_path: PathBuf,
+  ffmpeg: Option<(PathBuf, Option<PathBuf>)>,
+) -> Result<(), AppError> {
+  // 受全局媒体任务上限约束：拿到许可才开始，函数返回时自动归还。
+  let _job_permit = acquire(LimitKind::MediaJob).await?;
+  // 混流期间阻止系统睡眠，避免输出文件写到一半被中断。
+  let _awake = keep_awake("正在混流媒体文件");
 
-  // 用于错误日志：把参数拼成可读的命令行字符串。
-  fn build_cmdline(args: &[String]) -> String {
-    let mut cmdline = Vec::new();
-    cmdline.push(format_arg("mkvmerge"));
-    for arg in args {
-      cmdline.push(format_arg(arg));
+  // 中途取消时输出文件不完整，一并删除。
+  add_cleanup(job_id, &output);
+  // 步骤：每条需要转码的音轨各一步，最后混流一步。
+  let step_count = transcode_count(&inputs) + 1;
+  let (inputs, temp_root) = match &ffmpeg {
+    Some((ffmpeg_path, ffprobe_path)) if step_count > 1 => {
+      let temp_root = create_temp_dir(app, job_id)?;
+      let inputs = transcode_inputs(
+        app,
+        job_id,
+        inputs,
+        &temp_root,
+        ffmpeg_path,
+        ffprobe_path.as_deref(),
+        step_count,
+      )
+      .await;
+      match inputs {
+        Ok(inputs) => (inputs, Some(temp_root)),
+        Err(err) => {
+          let _ = fs::remove_dir_all(&temp_root);
+          return Err(err);
+        }
+      }
     }
-    cmdline.join(" ")
+    _ => (inputs, None),
+  };
+
+  let defaults = default_flags(&inputs);
+  let step = JobStep {
+    app,
+    job_id,
+    index: step_count - 1,
+    count: step_count,
+    phase: "混流输出",
+  };
+  let args = single_pass_args(&inputs, &defaults, &attachments, &output);
+  let result = match try_mkvmerge(&mkvmerge_path, &args, &step).await {
+    Ok(None) => Ok(()),
+    // 输出目录可写，mkvmerge 报错多半是某个源文件的轨道组合无法一次写出，分步混流可能成功。
+    Ok(Some((code @ Some(MKVMERGE_ERROR_CODE), messages))) => {
+      let err = mkvmerge_error(code, &messages, &args);
+      append_job_log(app, job_id, format!("单次混流失败，改用分步混流: {err}"));
+      let _ = fs::remove_file(&output);
+      run_two_pass(app, job_id, &inputs, &defaults, &attachments, &output, &mkvmerge_path).await
+    }
+    Ok(Some((code, messages))) => Err(mkvmerge_error(code, &messages, &args)),
+    Err(err) => Err(err),
+  };
+  // 转码出的临时音轨用完即删。
+  if let Some(temp_root) = temp_root {
+    let _ = fs::remove_dir_all(&temp_root);
   }
+  result
+}
 
-  // 执行 mkvmerge，失败时返回带命令行的错误。
-  async fn run_mkvmerge(mkvmerge_path: &PathBuf, args: &[String]) -> Result<(), String> {
-    let output_exec = Command::new(mkvmerge_path)
-      .args(args.iter())
-      .output()
-      .await
-      .map_err(|e| format!("调用 mkvmerge 失败: {e}"))?;
+// 校验混流输入并创建媒体作业（在后台执行），返回作业 ID；mix_media_tracks 与批量混流共用。
+pub(crate) fn queue_mix(
+  app: &tauri::AppHandle,
+  inputs: Vec<MixTrackInput>,
+  output_path: &str,
+  attachments: Vec<String>,
+) -> Result<u64, AppError> {
+  // 没有输入直接报错。
+  if inputs.is_empty() {
+    return Err(AppError::invalid_input("未提供可合成的轨道"));
+  }
 
-    if !output_exec.status.success() {
-      let stderr = String::from_utf8_lossy(&output_exec.stderr);
-      let stdout = String::from_utf8_lossy(&output_exec.stdout);
-      return Err(format!(
-        "mkvmerge 执行失败 (code {:?}): {} {}\n命令: {}",
-        output_exec.status.code(),
-        stdout.trim(),
-        stderr.trim(),
-        build_cmdline(args)
-      ));
+  // 自动补充输出后缀名（默认 mkv）。
+  let mut output = PathBuf::from(output_path);
+  if output.extension().is_none() {
+    output.set_extension("mkv");
+  }
+  // 输出位置必须在授权范围内。
+  let output = ensure_path_allowed(app, &output.to_string_lossy()).map_err(AppError::invalid_input)?;
+  // 确保输出目录存在且可写。
+  if let Some(parent) = output.parent() {
+    if !parent.exists() {
+      fs::create_dir_all(parent).map_err(|e| AppError::io(format!("创建输出目录失败: {e}")))?;
     }
-    Ok(())
+    ensure_dir_writable(parent)?;
   }
 
-  // 按轨道类型聚合输入，确保每种类型最多一个文件。
-  let mut kind_inputs: HashMap<String, MixTrackInput> = HashMap::new();
+  // 混流依赖 mkvmerge。
+  let mkvmerge_path = resolve_tool_path(app, "mkvmerge")?;
+
+  // 整理输入：同一文件同一类型的多条输入合并为一条，同一类型的不同文件各自保留。
+  let mut entries: Vec<MixTrackInput> = Vec::new();
   for input in inputs {
     // 校验输入路径。
     let path = input.path.trim();
     if path.is_empty() {
-      return Err("轨道文件路径为空".to_string());
+      return Err(AppError::invalid_input("轨道文件路径为空"));
     }
-    if !Path::new(path).exists() {
-      return Err(format!("轨道文件不存在: {path}"));
+    // 校验访问权限，并改用规范化后的路径。
+    let path = ensure_path_allowed(app, path)
+      .map_err(AppError::invalid_input)?
+      .to_string_lossy()
+      .to_string();
+    if !Path::new(&path).exists() {
+      return Err(AppError::invalid_input(format!("轨道文件不存在: {path}")));
+    }
+    let kind_lower = input.kind.to_lowercase();
+    if kind_rank(&kind_lower).is_none() {
+      return Err(AppError::invalid_input(format!("不支持的轨道类型：{}", input.kind)));
     }
     // 清理并过滤轨道 ID。
     let track_ids: Vec<String> = input
@@ -559,17 +1214,23 @@ pub async fn mix_media_tracks(
     if track_ids.is_empty() {
       continue;
     }
-    let kind_lower = input.kind.to_lowercase();
-    let entry = kind_inputs.entry(kind_lower.clone()).or_insert(MixTrackInput {
-      path: path.to_string(),
-      kind: kind_lower.clone(),
-      track_ids: Vec::new(),
-      track_langs: HashMap::new(),
-    });
-    // 同一类型只允许一个文件，避免混乱。
-    if entry.path != path {
-      return Err(format!("同一类型只支持一个文件：{}", kind_lower));
-    }
+    let position = match entries
+      .iter()
+      .position(|entry| entry.path == path && entry.kind == kind_lower)
+    {
+      Some(position) => position,
+      None => {
+        entries.push(MixTrackInput {
+          path: path.clone(),
+          kind: kind_lower.clone(),
+          track_ids: Vec::new(),
+          track_langs: HashMap::new(),
+          track_options: HashMap::new(),
+        });
+        entries.len() - 1
+      }
+    };
+    let entry = &mut entries[position];
     for track_id in track_ids {
       if !entry.track_ids.iter().any(|id| id == &track_id) {
         entry.track_ids.push(track_id);
@@ -578,138 +1239,82 @@ pub async fn mix_media_tracks(
     for (track_id, lang) in input.track_langs {
       entry.track_langs.insert(track_id, lang);
     }
-  }
-
-  // 视频轨道是必须的，没有就无法生成有效文件。
-  if !kind_inputs.contains_key("video") {
-    return Err("请先检测并选择至少一个视频轨道".to_string());
-  }
-
-  // 生成临时目录，用于存放中间文件。
-  let temp_root = app
-    .path()
-    .app_data_dir()
-    .map_err(|e| format!("无法获取数据目录: {e}"))?
-    .join("hanamirip-cn")
-    .join("mix-temp")
-    .join(chrono::Utc::now().timestamp_millis().to_string());
-  fs::create_dir_all(&temp_root).map_err(|e| format!("创建临时目录失败: {e}"))?;
-
-  // 记录临时文件路径，便于最后清理。
-  let mut temp_files: Vec<PathBuf> = Vec::new();
-
-  // 根据轨道类型生成临时文件（只包含指定轨道）。
-  async fn build_temp(
-    kind: &str,
-    input: &MixTrackInput,
-    temp_root: &PathBuf,
-    mkvmerge_path: &PathBuf,
-  ) -> Result<PathBuf, String> {
-    // 不同轨道类型使用不同容器后缀。
-    let ext = match kind {
-      "video" => "mkv",
-      "audio" => "mka",
-      "subtitle" => "mks",
-      _ => "mkv",
-    };
-    let temp_path = temp_root.join(format!("{kind}.{ext}"));
-    // mkvmerge 参数列表。
-    let mut args: Vec<String> = Vec::new();
-    args.push("-o".to_string());
-    args.push(temp_path.to_string_lossy().to_string());
-
-    // 根据类型选择要保留的轨道。
-    match kind {
-      "video" => {
-        args.push("--video-tracks".to_string());
-        args.push(input.track_ids.join(","));
-        args.push("--audio-tracks".to_string());
-        args.push("-1".to_string());
-        args.push("--subtitle-tracks".to_string());
-        args.push("-1".to_string());
+    for (track_id, mut options) in input.track_options {
+      if options.stretch.is_some_and(|stretch| !stretch.is_finite() || stretch <= 0.0) {
+        return Err(AppError::invalid_input(format!("轨道 {track_id} 的拉伸系数必须是正数")));
       }
-      "audio" => {
-        args.push("--audio-tracks".to_string());
-        args.push(input.track_ids.join(","));
-        args.push("--video-tracks".to_string());
-        args.push("-1".to_string());
-        args.push("--subtitle-tracks".to_string());
-        args.push("-1".to_string());
-      }
-      "subtitle" => {
-        args.push("--subtitle-tracks".to_string());
-        args.push(input.track_ids.join(","));
-        args.push("--video-tracks".to_string());
-        args.push("-1".to_string());
-        args.push("--audio-tracks".to_string());
-        args.push("-1".to_string());
+      if let Some(transcode) = &options.transcode {
+        if kind_lower != "audio" {
+          return Err(AppError::invalid_input(format!("只有音频轨道可以转码（轨道 {track_id}）")));
+        }
+        check_track_id(track_id.trim()).map_err(AppError::invalid_input)?;
+        transcode.validate().map_err(AppError::invalid_input)?;
       }
-      _ => {}
-    }
-
-    // 为每条轨道设置语言/默认/强制标记。
-    let lang = lang_for_kind(kind);
-    for track_id in &input.track_ids {
-      args.push("--track-name".to_string());
-      args.push(format!("{track_id}:"));
-      args.push("--default-track-flag".to_string());
-      args.push(format!("{track_id}:yes"));
-      args.push("--forced-display-flag".to_string());
-      args.push(format!("{track_id}:no"));
-      args.push("--language".to_string());
-      let custom_lang = input.track_langs.get(track_id).map(|v| v.as_str());
-      let final_lang = custom_lang.unwrap_or(lang);
-      args.push(format!("{track_id}:{final_lang}"));
+      options.name = options.name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+      entry.track_options.insert(track_id.trim().to_string(), options);
     }
-
-    args.push(input.path.clone());
-    run_mkvmerge(mkvmerge_path, &args).await?;
-    Ok::<PathBuf, String>(temp_path)
   }
 
-  // 记录每种轨道生成的临时文件。
-  let mut video_temp = None;
-  let mut audio_temp = None;
-  let mut subtitle_temp = None;
-
-  if let Some(input) = kind_inputs.get("video") {
-    let path = build_temp("video", input, &temp_root, &mkvmerge_path).await?;
-    temp_files.push(path.clone());
-    video_temp = Some(path);
-  }
-  if let Some(input) = kind_inputs.get("audio") {
-    let path = build_temp("audio", input, &temp_root, &mkvmerge_path).await?;
-    temp_files.push(path.clone());
-    audio_temp = Some(path);
-  }
-  if let Some(input) = kind_inputs.get("subtitle") {
-    let path = build_temp("subtitle", input, &temp_root, &mkvmerge_path).await?;
-    temp_files.push(path.clone());
-    subtitle_temp = Some(path);
+  // 视频轨道是必须的，没有就无法生成有效文件。
+  if !entries.iter().any(|entry| entry.kind == "video") {
+    return Err(AppError::invalid_input("请先检测并选择至少一个视频轨道"));
   }
+  // 输出轨道按视频、音频、字幕排列（稳定排序，同一类型保持输入顺序）。
+  entries.sort_by_key(|entry| kind_rank(&entry.kind));
 
-  // 最终合并命令参数。
-  let mut merge_args: Vec<String> = Vec::new();
-  merge_args.push("-o".to_string());
-  merge_args.push(output.to_string_lossy().to_string());
-
-  if let Some(path) = video_temp.as_ref() {
-    merge_args.push(path.to_string_lossy().to_string());
-  }
-  if let Some(path) = audio_temp.as_ref() {
-    merge_args.push(path.to_string_lossy().to_string());
-  }
-  if let Some(path) = subtitle_temp.as_ref() {
-    merge_args.push(path.to_string_lossy().to_string());
+  // 校验附加字体：只接受字体文件，重复的路径忽略；附件名相同的不同文件无法区分，直接报错。
+  let mut attachment_paths: Vec<PathBuf> = Vec::new();
+  for raw in attachments {
+    if raw.trim().is_empty() {
+      continue;
+    }
+    let path = ensure_path_allowed(app, &raw).map_err(AppError::invalid_input)?;
+    if !path.is_file() {
+      return Err(AppError::invalid_input(format!("附件文件不存在: {}", path.display())));
+    }
+    if font_mime_type(&path).is_none() {
+      return Err(AppError::invalid_input(format!(
+        "只支持附加字体文件（ttf / otf / ttc / otc）: {}",
+        path.display()
+      )));
+    }
+    if attachment_paths.contains(&path) {
+      continue;
+    }
+    if attachment_paths.iter().any(|item| item.file_name() == path.file_name()) {
+      return Err(AppError::invalid_input(format!("附件文件名重复: {}", path.display())));
+    }
+    attachment_paths.push(path);
   }
 
-  // 执行最终合并。
-  run_mkvmerge(&mkvmerge_path, &merge_args).await?;
+  // 有音轨需要转码时还依赖 ffmpeg（ffprobe 只用于进度，没有也可以）。
+  let ffmpeg = if transcode_count(&entries) > 0 {
+    Some((resolve_tool_path(app, "ffmpeg")?, resolve_tool_path(app, "ffprobe").ok()))
+  } else {
+    None
+  };
 
-  // 清理临时文件（失败不影响最终结果）。
-  for path in temp_files {
-    let _ = fs::remove_file(path);
-  }
+  // 创建作业后在后台执行，命令立即返回作业 ID。
+  let job_id = create_job(app, "mux", &output.to_string_lossy());
+  let task_app = app.clone();
+  spawn_job(app, job_id, async move {
+    run_mix(&task_app, job_id, entries, attachment_paths, output, mkvmerge_path, ffmpeg).await
+  });
+  Ok(job_id)
+}
 
-  Ok(output.to_string_lossy().to_string())
+// 将不同类型的轨道混合成一个输出文件。
+// 流程：
+// 1) 校验输入；
+// 2) 创建媒体作业并立即返回作业 ID；
+// 3) 后台用一次 mkvmerge 调用从各个源文件选取轨道并附加字体，直接生成最终文件，
+//    失败时改为先为每个输入文件生成临时文件再合并；进度与结果通过 media://mux-progress 事件推送。
+#[tauri::command]
+pub async fn mix_media_tracks(
+  app: tauri::AppHandle,
+  inputs: Vec<MixTrackInput>,
+  output_path: String,
+  attachments: Option<Vec<String>>,
+) -> Result<u64, AppError> {
+  queue_mix(&app, inputs, &output_path, attachments.unwrap_or_default())
 }
@@ -0,0 +1,244 @@
+/*
+  MKV 属性编辑（使用内置的 mkvpropedit）：
+  直接修改文件里的轨道名称、语言、默认/强制标记、标题，以及添加、删除附件，不重新混流
+  （修正一个语言标记不需要把十几 GB 的文件重写一遍）。
+  - 只支持 MKV 系列文件（mkv / mka / mks），修改在原文件上进行；
+  - 轨道与附件先用 mkvmerge -J 核对，再按 UID 定位（没有 UID 时按顺序），避免删除附件后编号变化；
+  - mkvpropedit 的警告（退出码 1）不算失败，原样返回给前端。
+*/
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::process::Command;
+
+use super::fonts::font_mime_type;
+use super::{build_cmdline, identify_matroska, is_matroska, resolve_tool_path};
+use crate::services::error::AppError;
+use crate::services::paths::ensure_path_allowed;
+
+/// 一条轨道的属性修改，省略的字段不修改。
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MkvTrackEdit {
+  /// 轨道 ID（即 parse_media_tracks 返回的 trackId）。
+  pub track_id: String,
+  /// 轨道名称；空字符串表示删除名称。
+  pub name: Option<String>,
+  /// 语言代码（例如 ja、zh-Hans）。
+  pub language: Option<String>,
+  pub default: Option<bool>,
+  pub forced: Option<bool>,
+}
+
+/// edit_mkv_properties 的修改内容，省略的部分不修改。
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MkvPropertyEdits {
+  /// 标题（segment title）；空字符串表示删除标题。
+  pub title: Option<String>,
+  pub tracks: Vec<MkvTrackEdit>,
+  /// 要添加的附件文件路径（附件名取文件名）。
+  pub add_attachments: Vec<String>,
+  /// 要删除的附件 ID（即 parse_media_tracks 在 kind 为 attachment 时返回的 trackId）。
+  pub remove_attachments: Vec<String>,
+}
+
+/// edit_mkv_properties 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MkvPropertyEditResult {
+  /// mkvpropedit 的警告（修改已经完成）。
+  pub warnings: Vec<String>,
+}
+
+// 语言代码只允许字母、数字与连字符（ISO 639 或 BCP 47）。
+fn check_language(language: &str) -> Result<(), String> {
+  if language.is_empty() || !language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+    return Err(format!("语言代码无效：{language}"));
+  }
+  Ok(())
+}
+
+// 标记的取值。
+fn flag(value: bool) -> &'static str {
+  if value {
+    "1"
+  } else {
+    "0"
+  }
+}
+
+// 执行 mkvpropedit：退出码 0 为成功，1 为有警告但已完成，其它为失败（返回带命令行的错误）。
+async fn run_mkvpropedit(mkvpropedit_path: &Path, args: &[String]) -> Result<MkvPropertyEditResult, AppError> {
+  let output = Command::new(mkvpropedit_path)
+    .args(args)
+    .output()
+    .await
+    .map_err(|e| AppError::tool_failed("mkvpropedit", format!("调用 mkvpropedit 失败: {e}")))?;
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  match output.status.code() {
+    Some(0) | Some(1) => Ok(MkvPropertyEditResult {
+      warnings: stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("Warning:"))
+        .map(str::to_string)
+        .collect(),
+    }),
+    code => {
+      let stderr = String::from_utf8_lossy(&output.stderr);
+      let message = [stdout.trim(), stderr.trim()]
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+      Err(AppError::tool_failed(
+        "mkvpropedit",
+        format!(
+          "mkvpropedit 执行失败 (code {:?}): {}\n命令: {}",
+          code,
+          message,
+          build_cmdline("mkvpropedit", args)
+        ),
+      ))
+    }
+  }
+}
+
+// 用 mkvpropedit 修改 MKV 文件的轨道属性、标题与附件（在原文件上修改，不重新混流）。
+// 轨道或附件不存在、语言代码无效、添加的附件与已有附件重名时返回 InvalidInput，什么都没有修改时也返回 InvalidInput。
+#[tauri::command]
+pub async fn edit_mkv_properties(
+  app: tauri::AppHandle,
+  path: String,
+  edits: MkvPropertyEdits,
+) -> Result<MkvPropertyEditResult, AppError> {
+  let path = ensure_path_allowed(&app, &path).map_err(AppError::invalid_input)?;
+  if !path.is_file() {
+    return Err(AppError::invalid_input(format!("文件不存在: {}", path.display())));
+  }
+  if !is_matroska(&path) {
+    return Err(AppError::invalid_input("只能修改 MKV 系列文件（mkv / mka / mks）"));
+  }
+  let raw_path = path.to_string_lossy().to_string();
+  let mkvmerge_path = resolve_tool_path(&app, "mkvmerge")?;
+  let mkvpropedit_path = resolve_tool_path(&app, "mkvpropedit")?;
+  let parsed = identify_matroska(&mkvmerge_path, &raw_path).await?;
+  let tracks = parsed.tracks.unwrap_or_default();
+  let attachments = parsed.attachments.unwrap_or_default();
+
+  let mut args: Vec<String> = vec![raw_path];
+  let mut changed = false;
+
+  if let Some(title) = edits.title {
+    args.push("--edit".to_string());
+    args.push("info".to_string());
+    let title = title.trim();
+    if title.is_empty() {
+      args.push("--delete".to_string());
+      args.push("title".to_string());
+    } else {
+      args.push("--set".to_string());
+      args.push(format!("title={title}"));
+    }
+    changed = true;
+  }
+
+  for edit in edits.tracks {
+    let track_id = edit.track_id.trim();
+    let (position, track) = tracks
+      .iter()
+      .enumerate()
+      .find(|(_, track)| track.id.to_string() == track_id)
+      .ok_or_else(|| AppError::invalid_input(format!("文件里没有轨道 {track_id}")))?;
+    let mut properties: Vec<String> = Vec::new();
+    match edit.name.as_deref().map(str::trim) {
+      Some("") => properties.extend(["--delete".to_string(), "name".to_string()]),
+      Some(name) => properties.extend(["--set".to_string(), format!("name={name}")]),
+      None => {}
+    }
+    if let Some(language) = edit.language.as_deref().map(str::trim) {
+      check_language(language).map_err(AppError::invalid_input)?;
+      properties.extend(["--set".to_string(), format!("language={language}")]);
+    }
+    if let Some(default) = edit.default {
+      properties.extend(["--set".to_string(), format!("flag-default={}", flag(default))]);
+    }
+    if let Some(forced) = edit.forced {
+      properties.extend(["--set".to_string(), format!("flag-forced={}", flag(forced))]);
+    }
+    if properties.is_empty() {
+      continue;
+    }
+    // 优先按 UID 定位；没有 UID 时按文件里的顺序（从 1 开始）。
+    let selector = match track.properties.as_ref().and_then(|props| props.uid) {
+      Some(uid) => format!("track:={uid}"),
+      None => format!("track:{}", position + 1),
+    };
+    args.push("--edit".to_string());
+    args.push(selector);
+    args.extend(properties);
+    changed = true;
+  }
+
+  let mut removed: Vec<u32> = Vec::new();
+  for id in edits.remove_attachments {
+    let id = id.trim();
+    if id.is_empty() {
+      continue;
+    }
+    let attachment = attachments
+      .iter()
+      .find(|attachment| attachment.id.to_string() == id)
+      .ok_or_else(|| AppError::invalid_input(format!("文件里没有附件 {id}")))?;
+    if removed.contains(&attachment.id) {
+      continue;
+    }
+    removed.push(attachment.id);
+    args.push("--delete-attachment".to_string());
+    args.push(match attachment.properties.as_ref().and_then(|props| props.uid) {
+      Some(uid) => format!("={uid}"),
+      None => attachment.id.to_string(),
+    });
+    changed = true;
+  }
+  // 删除后剩下的附件名（用于检查新附件是否重名）。
+  let mut names: Vec<String> = attachments
+    .iter()
+    .filter(|attachment| !removed.contains(&attachment.id))
+    .filter_map(|attachment| attachment.file_name.as_ref().map(|name| name.to_lowercase()))
+    .collect();
+
+  for raw in edits.add_attachments {
+    if raw.trim().is_empty() {
+      continue;
+    }
+    let file = ensure_path_allowed(&app, &raw).map_err(AppError::invalid_input)?;
+    if !file.is_file() {
+      return Err(AppError::invalid_input(format!("附件文件不存在: {}", file.display())));
+    }
+    let name = file
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+    if names.contains(&name.to_lowercase()) {
+      return Err(AppError::invalid_input(format!("附件名重复: {name}")));
+    }
+    names.push(name.to_lowercase());
+    args.push("--attachment-name".to_string());
+    args.push(name);
+    // 字体显式指定 MIME 类型（同混流），其它文件由 mkvpropedit 自动识别。
+    if let Some(mime) = font_mime_type(&file) {
+      args.push("--attachment-mime-type".to_string());
+      args.push(mime.to_string());
+    }
+    args.push("--add-attachment".to_string());
+    args.push(file.to_string_lossy().to_string());
+    changed = true;
+  }
+
+  if !changed {
+    return Err(AppError::invalid_input("没有要修改的属性"));
+  }
+  run_mkvpropedit(&mkvpropedit_path, &args).await
+}
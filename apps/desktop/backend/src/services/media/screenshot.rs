@@ -0,0 +1,319 @@
+/*
+  按时间点截图（使用内置的 ffmpeg），用于发布帖的画面预览与版本对比：
+  - 时间点可以直接指定，也可以指定张数，在全片范围内等距选取（避开片头与片尾）；
+  - 只截第一条视频轨道（封面图片不算），每张图一次 ffmpeg 调用（输入前 -ss 快速定位）；
+  - 10bit 片源统一转成 8bit RGB；没有标注色彩矩阵的高清片源按 BT.709 转换，避免偏色；
+  - HDR 片源（PQ / HLG）默认用 zscale + tonemap（hable）映射为 BT.709 SDR，否则画面发灰；
+  - 截图作为媒体作业（kind 为 screenshot）在后台执行，与混流共用作业队列和并发上限。
+*/
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use super::jobs::{add_cleanup, create_job, run_ffmpeg_with_progress, spawn_job, JobStep};
+use super::transcode::probe_duration;
+use super::{probe_streams, resolve_tool_path};
+use crate::services::error::AppError;
+use crate::services::limits::{acquire, LimitKind};
+use crate::services::paths::ensure_path_allowed;
+
+// 一次最多截图的张数。
+const MAX_SCREENSHOTS: usize = 100;
+// HDR 的传输特性（ffprobe 的 color_transfer）：PQ 与 HLG。
+const HDR_TRANSFERS: [&str; 2] = ["smpte2084", "arib-std-b67"];
+
+/// 截图格式。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotFormat {
+  /// 无损，适合版本对比。
+  #[default]
+  Png,
+  Jpg,
+  Webp,
+}
+
+impl ScreenshotFormat {
+  fn extension(self) -> &'static str {
+    match self {
+      Self::Png => "png",
+      Self::Jpg => "jpg",
+      Self::Webp => "webp",
+    }
+  }
+
+  // 编码参数：JPG 用最高质量档，WebP 为有损 90。
+  fn args(self) -> &'static [&'static str] {
+    match self {
+      Self::Png => &["-update", "1"],
+      Self::Jpg => &["-q:v", "2", "-update", "1"],
+      Self::Webp => &["-c:v", "libwebp", "-quality", "90"],
+    }
+  }
+}
+
+/// 一张截图。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Screenshot {
+  /// 时间点（秒）。
+  pub timestamp: f64,
+  pub path: String,
+}
+
+/// extract_screenshots 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotJob {
+  pub job_id: u64,
+  /// 片源是否为 HDR（PQ / HLG）。
+  pub hdr: bool,
+  /// 是否做了色调映射。
+  pub tonemapped: bool,
+  /// 各张截图的输出文件（按时间排序，作业完成后生成）。
+  pub files: Vec<Screenshot>,
+}
+
+// 视频轨道的信息（选择轨道与滤镜用）。
+struct VideoStream {
+  index: u32,
+  height: u32,
+  color_space: Option<String>,
+  color_transfer: Option<String>,
+}
+
+// 截图用的滤镜：HDR 映射为 BT.709 SDR（transfer 为原传输特性），其它转成 8bit RGB，
+// 没有标注色彩矩阵的高清片源按 BT.709 转换（ffmpeg 默认按 BT.601）。
+fn screenshot_filter(video: &VideoStream, tonemap: Option<&str>) -> String {
+  if let Some(transfer) = tonemap {
+    return format!(
+      "zscale=tin={transfer}:min=bt2020nc:pin=bt2020:t=linear:npl=100,format=gbrpf32le,zscale=p=bt709,\
+       tonemap=tonemap=hable:desat=0,zscale=t=bt709:m=bt709:r=tv,format=rgb24"
+    );
+  }
+  let untagged = !matches!(video.color_space.as_deref(), Some(space) if !space.is_empty() && space != "unknown");
+  if untagged && video.height > 576 {
+    "scale=in_color_matrix=bt709,format=rgb24".to_string()
+  } else {
+    "format=rgb24".to_string()
+  }
+}
+
+// 截图文件名："<原文件名>.<时>-<分>-<秒>.<毫秒>.<扩展名>"。
+fn screenshot_name(stem: &str, timestamp: f64, format: ScreenshotFormat) -> String {
+  let millis = (timestamp * 1000.0).round() as u64;
+  format!(
+    "{stem}.{:02}-{:02}-{:02}.{:03}.{}",
+    millis / 3_600_000,
+    millis / 60_000 % 60,
+    millis / 1000 % 60,
+    millis % 1000,
+    format.extension()
+  )
+}
+
+// 截图作业本体：等待并发许可后逐张截图（每张一个作业步骤）；ffmpeg 成功但没有写出文件时
+// （时间点超出片长）报错。
+#[allow(clippy::too_many_arguments)]
+async fn run_screenshots(
+  app: &tauri::AppHandle,
+  job_id: u64,
+  input: PathBuf,
+  stream_index: u32,
+  shots: Vec<(f64, PathBuf)>,
+  filter: String,
+  format: ScreenshotFormat,
+  ffmpeg_path: PathBuf,
+) -> Result<(), AppError> {
+  let _job_permit = acquire(LimitKind::MediaJob).await?;
+
+  for (_, output) in &shots {
+    add_cleanup(job_id, output);
+  }
+  let count = shots.len();
+  for (index, (timestamp, output)) in shots.iter().enumerate() {
+    let mut args: Vec<String> = vec![
+      "-y".to_string(),
+      "-ss".to_string(),
+      format!("{timestamp:.3}"),
+      "-i".to_string(),
+      input.to_string_lossy().to_string(),
+      "-map".to_string(),
+      format!("0:{stream_index}"),
+      "-frames:v".to_string(),
+      "1".to_string(),
+      "-vf".to_string(),
+      filter.clone(),
+    ];
+    args.extend(format.args().iter().map(|arg| arg.to_string()));
+    args.push(output.to_string_lossy().to_string());
+    let phase = if count > 1 {
+      format!("截图（{}/{count}）", index + 1)
+    } else {
+      "截图".to_string()
+    };
+    let step = JobStep {
+      app,
+      job_id,
+      index,
+      count,
+      phase: &phase,
+    };
+    let (success, code, errors) = run_ffmpeg_with_progress(&ffmpeg_path, &args, None, &step).await?;
+    if !success {
+      return Err(AppError::tool_failed(
+        "ffmpeg",
+        format!("ffmpeg 执行失败 (code {code:?}): {errors}"),
+      ));
+    }
+    if !output.is_file() {
+      return Err(AppError::tool_failed(
+        "ffmpeg",
+        format!("{timestamp:.3} 秒处没有画面，可能超出了片长"),
+      ));
+    }
+  }
+  Ok(())
+}
+
+// 按时间点截图：timestamps（秒）与 count（在全片范围内等距选取的张数）二选一；
+// tonemap 省略时 HDR 片源自动做色调映射，true 时总是按 HDR（缺少标注时视为 PQ）映射，false 时不映射。
+// 校验后创建媒体作业，立即返回作业 ID 与各张截图的输出路径；截图在后台执行，进度通过 media://mux-progress 事件推送。
+#[tauri::command]
+pub async fn extract_screenshots(
+  app: tauri::AppHandle,
+  path: String,
+  timestamps: Option<Vec<f64>>,
+  count: Option<usize>,
+  output_dir: String,
+  format: Option<ScreenshotFormat>,
+  tonemap: Option<bool>,
+) -> Result<ScreenshotJob, AppError> {
+  let input = ensure_path_allowed(&app, &path).map_err(AppError::invalid_input)?;
+  if !input.is_file() {
+    return Err(AppError::invalid_input(format!("输入文件不存在: {}", input.display())));
+  }
+  if output_dir.trim().is_empty() {
+    return Err(AppError::invalid_input("未指定输出目录"));
+  }
+  let output_dir = ensure_path_allowed(&app, output_dir.trim()).map_err(AppError::invalid_input)?;
+  let format = format.unwrap_or_default();
+  let ffmpeg_path = resolve_tool_path(&app, "ffmpeg")?;
+  let ffprobe_path = resolve_tool_path(&app, "ffprobe")?;
+
+  // 第一条视频轨道（跳过封面图片）。
+  let raw_input = input.to_string_lossy().to_string();
+  let video = probe_streams(&ffprobe_path, &raw_input)
+    .await?
+    .streams
+    .unwrap_or_default()
+    .into_iter()
+    .find(|stream| {
+      stream.codec_type.as_deref() == Some("video")
+        && stream
+          .disposition
+          .as_ref()
+          .and_then(|disposition| disposition.attached_pic)
+          != Some(1)
+    })
+    .map(|stream| VideoStream {
+      index: stream.index.unwrap_or(0),
+      height: stream.height.unwrap_or(0),
+      color_space: stream.color_space,
+      color_transfer: stream.color_transfer,
+    })
+    .ok_or_else(|| AppError::invalid_input("文件里没有视频轨道"))?;
+  let duration = probe_duration(&ffprobe_path, &input).await;
+
+  let mut points: Vec<f64> = match (timestamps, count) {
+    (Some(_), Some(_)) => return Err(AppError::invalid_input("timestamps 与 count 只能指定一个")),
+    (Some(timestamps), None) => {
+      if let Some(timestamp) = timestamps.iter().find(|value| !value.is_finite() || **value < 0.0) {
+        return Err(AppError::invalid_input(format!("时间点无效：{timestamp}")));
+      }
+      if let Some(duration) = duration {
+        if let Some(timestamp) = timestamps.iter().find(|value| **value >= duration) {
+          return Err(AppError::invalid_input(format!(
+            "时间点 {timestamp} 秒超出了片长（{duration:.3} 秒）"
+          )));
+        }
+      }
+      timestamps
+    }
+    (None, Some(count)) => {
+      if count == 0 || count > MAX_SCREENSHOTS {
+        return Err(AppError::invalid_input(format!(
+          "张数必须在 1 ~ {MAX_SCREENSHOTS} 之间"
+        )));
+      }
+      let duration = duration.ok_or_else(|| AppError::invalid_input("读取不到片长，请直接指定时间点"))?;
+      (1..=count)
+        .map(|index| duration * index as f64 / (count + 1) as f64)
+        .collect()
+    }
+    (None, None) => return Err(AppError::invalid_input("请指定 timestamps 或 count")),
+  };
+  // 按时间排序，同一毫秒的时间点只截一次。
+  points.sort_by(f64::total_cmp);
+  points.dedup_by(|a, b| (*a * 1000.0).round() == (*b * 1000.0).round());
+  if points.is_empty() {
+    return Err(AppError::invalid_input("未指定截图时间点"));
+  }
+  if points.len() > MAX_SCREENSHOTS {
+    return Err(AppError::invalid_input(format!("一次最多截图 {MAX_SCREENSHOTS} 张")));
+  }
+
+  let transfer = video
+    .color_transfer
+    .as_deref()
+    .filter(|transfer| HDR_TRANSFERS.contains(transfer));
+  let hdr = transfer.is_some();
+  let tonemap_transfer = match tonemap {
+    Some(false) => None,
+    Some(true) => Some(transfer.unwrap_or(HDR_TRANSFERS[0])),
+    None => transfer,
+  };
+  let filter = screenshot_filter(&video, tonemap_transfer);
+
+  let stem = input
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let shots: Vec<(f64, PathBuf)> = points
+    .into_iter()
+    .map(|timestamp| (timestamp, output_dir.join(screenshot_name(&stem, timestamp, format))))
+    .collect();
+  fs::create_dir_all(&output_dir).map_err(|e| AppError::io(format!("创建输出目录失败: {e}")))?;
+
+  let files = shots
+    .iter()
+    .map(|(timestamp, output)| Screenshot {
+      timestamp: *timestamp,
+      path: output.to_string_lossy().to_string(),
+    })
+    .collect();
+  let tonemapped = tonemap_transfer.is_some();
+  let job_id = create_job(&app, "screenshot", &output_dir.to_string_lossy());
+  let task_app = app.clone();
+  let stream_index = video.index;
+  spawn_job(&app, job_id, async move {
+    run_screenshots(
+      &task_app,
+      job_id,
+      input,
+      stream_index,
+      shots,
+      filter,
+      format,
+      ffmpeg_path,
+    )
+    .await
+  });
+  Ok(ScreenshotJob {
+    job_id,
+    hdr,
+    tonemapped,
+    files,
+  })
+}
@@ -0,0 +1,607 @@
+/*
+  字体子集化（与 assfonts 的做法相同：只保留字幕用到的字形，并改用新的字体名）：
+  - 只处理 TrueType 轮廓（glyf）：保留用到的字符对应的字形与复合字形引用的部件，其余字形清空；
+    字形编号保持不变，因此 hmtx / GPOS / GDEF 等按字形编号索引的表可以原样保留；
+  - 重建 cmap（只包含用到的字符）与 name（改成新的字体名，避免与播放端已安装的同名完整字体冲突），
+    post 改为不含字形名的 3.0 版本；
+  - 去掉 GSUB 等字形替换表（替换出的字形没有收录，保留反而会显示成空白），
+    以及点阵字形、数字签名、可变字体数据等与清空后的字形对不上的表；
+  - CFF 轮廓（多数 .otf）与竖排使用的字体（需要 GSUB 里的竖排字形）不做字形子集化，只改名；
+  - 字体集合（ttc）里的字体会单独导出成一个字体文件。
+*/
+
+use std::collections::{BTreeMap, BTreeSet};
+
+// 子集化时去掉的表。
+const DROPPED_TABLES: [&[u8; 4]; 20] = [
+  b"GSUB", b"morx", b"mort", b"EBDT", b"EBLC", b"EBSC", b"CBDT", b"CBLC", b"sbix", b"hdmx", b"LTSH", b"VDMX", b"fvar",
+  b"gvar", b"avar", b"cvar", b"HVAR", b"VVAR", b"MVAR", b"STAT",
+];
+
+// 复合字形的标志位。
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+// name 表记录使用的平台（Windows、Unicode BMP、英语）。
+const NAME_PLATFORM: u16 = 3;
+const NAME_ENCODING: u16 = 1;
+const NAME_LANGUAGE: u16 = 0x0409;
+
+/// 子集化（或改名）后的字体。
+pub(crate) struct SubsetOutput {
+  pub data: Vec<u8>,
+  /// 是否做了字形子集化（false 表示只改了名）。
+  pub subsetted: bool,
+  /// 是否为 TrueType 轮廓（决定输出文件的后缀 ttf / otf）。
+  pub truetype: bool,
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+  data
+    .get(offset..offset + 2)
+    .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    .ok_or_else(|| "字体数据不完整".to_string())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+  data
+    .get(offset..offset + 4)
+    .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    .ok_or_else(|| "字体数据不完整".to_string())
+}
+
+// 表目录：表名 → 表数据。
+type TableDirectory<'a> = BTreeMap<[u8; 4], &'a [u8]>;
+
+// 读取字体（字体集合里的第 face_index 个）的版本号与表目录。
+fn read_tables(data: &[u8], face_index: u32) -> Result<(u32, TableDirectory<'_>), String> {
+  let mut offset = 0usize;
+  if data.get(0..4) == Some(b"ttcf".as_slice()) {
+    if face_index >= read_u32(data, 8)? {
+      return Err("字体集合里没有指定的字体".to_string());
+    }
+    offset = read_u32(data, 12 + 4 * face_index as usize)? as usize;
+  }
+  let version = read_u32(data, offset)?;
+  let count = read_u16(data, offset + 4)? as usize;
+  let mut tables = BTreeMap::new();
+  for index in 0..count {
+    let record = offset + 12 + 16 * index;
+    let tag = data
+      .get(record..record + 4)
+      .and_then(|tag| <[u8; 4]>::try_from(tag).ok())
+      .ok_or_else(|| "字体数据不完整".to_string())?;
+    let start = read_u32(data, record + 8)? as usize;
+    let length = read_u32(data, record + 12)? as usize;
+    let table = data
+      .get(start..start + length)
+      .ok_or_else(|| "字体数据不完整".to_string())?;
+    tables.insert(tag, table);
+  }
+  Ok((version, tables))
+}
+
+// 在格式 4 的 cmap 子表里查找字符。
+fn lookup_format4(cmap: &[u8], offset: usize, code: u32) -> Result<u16, String> {
+  if code > 0xFFFF {
+    return Ok(0);
+  }
+  let seg_count = read_u16(cmap, offset + 6)? as usize / 2;
+  let ends = offset + 14;
+  let starts = ends + seg_count * 2 + 2;
+  let deltas = starts + seg_count * 2;
+  let ranges = deltas + seg_count * 2;
+  for index in 0..seg_count {
+    if code > read_u16(cmap, ends + 2 * index)? as u32 {
+      continue;
+    }
+    let start = read_u16(cmap, starts + 2 * index)? as u32;
+    if code < start {
+      return Ok(0);
+    }
+    let delta = read_u16(cmap, deltas + 2 * index)?;
+    let range = read_u16(cmap, ranges + 2 * index)? as usize;
+    if range == 0 {
+      return Ok((code as u16).wrapping_add(delta));
+    }
+    let glyph = read_u16(cmap, ranges + 2 * index + range + 2 * (code - start) as usize)?;
+    return Ok(if glyph == 0 { 0 } else { glyph.wrapping_add(delta) });
+  }
+  Ok(0)
+}
+
+// 在格式 12 的 cmap 子表里查找字符。
+fn lookup_format12(cmap: &[u8], offset: usize, code: u32) -> Result<u16, String> {
+  let groups = read_u32(cmap, offset + 12)? as usize;
+  for index in 0..groups {
+    let group = offset + 16 + 12 * index;
+    let start = read_u32(cmap, group)?;
+    if code < start {
+      break;
+    }
+    if code <= read_u32(cmap, group + 4)? {
+      return Ok((read_u32(cmap, group + 8)? + (code - start)) as u16);
+    }
+  }
+  Ok(0)
+}
+
+// 查出字符对应的字形编号（字符 → 字形），字体里没有的字符不返回。
+// 优先使用完整 Unicode 的格式 12 子表，其次是 BMP 的格式 4 子表。
+fn map_chars(cmap: &[u8], chars: &BTreeSet<char>) -> Result<BTreeMap<u32, u16>, String> {
+  let mut best: Option<(u8, usize, u16)> = None;
+  for index in 0..read_u16(cmap, 2)? as usize {
+    let platform = read_u16(cmap, 4 + 8 * index)?;
+    let encoding = read_u16(cmap, 6 + 8 * index)?;
+    let offset = read_u32(cmap, 8 + 8 * index)? as usize;
+    let format = read_u16(cmap, offset)?;
+    let rank = match (platform, encoding, format) {
+      (0, _, 12) | (3, 10, 12) => 2,
+      (0, _, 4) | (3, 1, 4) => 1,
+      _ => continue,
+    };
+    if best.is_none_or(|(best_rank, _, _)| best_rank < rank) {
+      best = Some((rank, offset, format));
+    }
+  }
+  let (_, offset, format) = best.ok_or_else(|| "字体没有 Unicode 字符映射表".to_string())?;
+  let mut mapping = BTreeMap::new();
+  for ch in chars {
+    let code = *ch as u32;
+    let glyph = if format == 12 {
+      lookup_format12(cmap, offset, code)?
+    } else {
+      lookup_format4(cmap, offset, code)?
+    };
+    if glyph != 0 {
+      mapping.insert(code, glyph);
+    }
+  }
+  Ok(mapping)
+}
+
+// 字形在 glyf 表中的起止位置。
+fn glyph_range(loca: &[u8], long: bool, glyph: u16) -> Result<(usize, usize), String> {
+  let index = glyph as usize;
+  if long {
+    Ok((
+      read_u32(loca, 4 * index)? as usize,
+      read_u32(loca, 4 * index + 4)? as usize,
+    ))
+  } else {
+    Ok((
+      read_u16(loca, 2 * index)? as usize * 2,
+      read_u16(loca, 2 * index + 2)? as usize * 2,
+    ))
+  }
+}
+
+// 要保留的字形：用到的字形加上复合字形引用的部件（递归）。
+fn glyph_closure(
+  glyf: &[u8],
+  loca: &[u8],
+  long: bool,
+  num_glyphs: u16,
+  roots: impl Iterator<Item = u16>,
+) -> Result<BTreeSet<u16>, String> {
+  let mut keep = BTreeSet::new();
+  let mut pending: Vec<u16> = roots.collect();
+  while let Some(glyph) = pending.pop() {
+    if glyph >= num_glyphs || !keep.insert(glyph) {
+      continue;
+    }
+    let (start, end) = glyph_range(loca, long, glyph)?;
+    if end <= start {
+      continue;
+    }
+    let data = glyf.get(start..end).ok_or_else(|| "字形数据不完整".to_string())?;
+    // 轮廓数为负表示复合字形。
+    if (read_u16(data, 0)? as i16) >= 0 {
+      continue;
+    }
+    let mut offset = 10;
+    loop {
+      let flags = read_u16(data, offset)?;
+      pending.push(read_u16(data, offset + 2)?);
+      offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 { 8 } else { 6 };
+      offset += if flags & WE_HAVE_A_SCALE != 0 {
+        2
+      } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+        4
+      } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+        8
+      } else {
+        0
+      };
+      if flags & MORE_COMPONENTS == 0 {
+        break;
+      }
+    }
+  }
+  Ok(keep)
+}
+
+// 重建 glyf 与 loca（长格式）：保留的字形原样复制（按 4 字节对齐），其余字形长度为 0。
+fn build_glyf(
+  glyf: &[u8],
+  loca: &[u8],
+  long: bool,
+  num_glyphs: u16,
+  keep: &BTreeSet<u16>,
+) -> Result<(Vec<u8>, Vec<u8>), String> {
+  let mut new_glyf = Vec::new();
+  let mut new_loca = Vec::with_capacity(4 * (num_glyphs as usize + 1));
+  for glyph in 0..num_glyphs {
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+    if !keep.contains(&glyph) {
+      continue;
+    }
+    let (start, end) = glyph_range(loca, long, glyph)?;
+    if end > start {
+      new_glyf.extend_from_slice(glyf.get(start..end).ok_or_else(|| "字形数据不完整".to_string())?);
+      new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+    }
+  }
+  new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+  Ok((new_glyf, new_loca))
+}
+
+// 生成 cmap：格式 4（BMP，兼容旧的渲染器）与格式 12（完整 Unicode）两个子表。
+fn build_cmap(mapping: &BTreeMap<u32, u16>) -> Vec<u8> {
+  // 字符与字形编号都连续的合并成一组：(起始字符, 结束字符, 起始字形)。
+  let mut groups: Vec<(u32, u32, u32)> = Vec::new();
+  for (&code, &glyph) in mapping {
+    match groups.last_mut() {
+      Some(group) if code == group.1 + 1 && glyph as u32 == group.2 + (code - group.0) => group.1 = code,
+      _ => groups.push((code, code, glyph as u32)),
+    }
+  }
+
+  let mut format12 = Vec::new();
+  format12.extend_from_slice(&12u16.to_be_bytes());
+  format12.extend_from_slice(&0u16.to_be_bytes());
+  format12.extend_from_slice(&(16 + 12 * groups.len() as u32).to_be_bytes());
+  format12.extend_from_slice(&0u32.to_be_bytes());
+  format12.extend_from_slice(&(groups.len() as u32).to_be_bytes());
+  for (start, end, glyph) in &groups {
+    format12.extend_from_slice(&start.to_be_bytes());
+    format12.extend_from_slice(&end.to_be_bytes());
+    format12.extend_from_slice(&glyph.to_be_bytes());
+  }
+
+  // 格式 4 只能表示 BMP 字符，最后一段必须是 0xFFFF。
+  let mut segments: Vec<(u16, u16, u16)> = groups
+    .iter()
+    .filter(|(start, _, _)| *start < 0xFFFF)
+    .map(|(start, end, glyph)| {
+      let end = (*end).min(0xFFFE);
+      (*start as u16, end as u16, (*glyph as u16).wrapping_sub(*start as u16))
+    })
+    .collect();
+  segments.push((0xFFFF, 0xFFFF, 1));
+  let seg_count = segments.len();
+  let format4_length = 16 + 8 * seg_count;
+  // 字符太分散时格式 4 会超出长度上限，只保留格式 12。
+  let format4 = (format4_length <= u16::MAX as usize).then(|| {
+    let search_range = 2 * (1usize << seg_count.ilog2());
+    let mut table = Vec::with_capacity(format4_length);
+    for value in [
+      4,
+      format4_length,
+      0,
+      seg_count * 2,
+      search_range,
+      (search_range / 2).ilog2() as usize,
+      seg_count * 2 - search_range,
+    ] {
+      table.extend_from_slice(&(value as u16).to_be_bytes());
+    }
+    segments
+      .iter()
+      .for_each(|(_, end, _)| table.extend_from_slice(&end.to_be_bytes()));
+    table.extend_from_slice(&0u16.to_be_bytes());
+    segments
+      .iter()
+      .for_each(|(start, _, _)| table.extend_from_slice(&start.to_be_bytes()));
+    segments
+      .iter()
+      .for_each(|(_, _, delta)| table.extend_from_slice(&delta.to_be_bytes()));
+    segments
+      .iter()
+      .for_each(|_| table.extend_from_slice(&0u16.to_be_bytes()));
+    table
+  });
+
+  let subtables: Vec<(u16, u16, Vec<u8>)> = format4
+    .map(|table| (NAME_PLATFORM, 1, table))
+    .into_iter()
+    .chain([(NAME_PLATFORM, 10, format12)])
+    .collect();
+  let mut cmap = Vec::new();
+  cmap.extend_from_slice(&0u16.to_be_bytes());
+  cmap.extend_from_slice(&(subtables.len() as u16).to_be_bytes());
+  let mut offset = 4 + 8 * subtables.len();
+  for (platform, encoding, table) in &subtables {
+    cmap.extend_from_slice(&platform.to_be_bytes());
+    cmap.extend_from_slice(&encoding.to_be_bytes());
+    cmap.extend_from_slice(&(offset as u32).to_be_bytes());
+    offset += table.len();
+  }
+  for (_, _, table) in &subtables {
+    cmap.extend_from_slice(table);
+  }
+  cmap
+}
+
+// 读取 name 表里 Windows 平台的名称（优先英语）。
+fn read_name(name: &[u8], name_id: u16) -> Option<String> {
+  let count = read_u16(name, 2).ok()? as usize;
+  let storage = read_u16(name, 4).ok()? as usize;
+  let mut found: Option<(bool, String)> = None;
+  for index in 0..count {
+    let record = 6 + 12 * index;
+    let fields: Vec<u16> = (0..6)
+      .filter_map(|field| read_u16(name, record + 2 * field).ok())
+      .collect();
+    let [platform, _, language, id, length, offset] = fields[..] else {
+      return None;
+    };
+    if platform != NAME_PLATFORM || id != name_id {
+      continue;
+    }
+    let start = storage + offset as usize;
+    let units: Vec<u16> = name
+      .get(start..start + length as usize)?
+      .chunks_exact(2)
+      .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+      .collect();
+    let english = language == NAME_LANGUAGE;
+    if found.as_ref().is_some_and(|(best, _)| *best || !english) {
+      continue;
+    }
+    found = Some((english, String::from_utf16_lossy(&units)));
+  }
+  found.map(|(_, text)| text)
+}
+
+// 生成 name 表：家族名、子家族名、唯一标识、完整名称与 PostScript 名称。
+fn build_name(family: &str, subfamily: &str) -> Vec<u8> {
+  let full_name = if subfamily.eq_ignore_ascii_case("regular") {
+    family.to_string()
+  } else {
+    format!("{family} {subfamily}")
+  };
+  let records = [
+    (1u16, family.to_string()),
+    (2, subfamily.to_string()),
+    (3, full_name.clone()),
+    (4, full_name),
+    (6, family.to_string()),
+  ];
+  let mut strings = Vec::new();
+  let mut table = Vec::new();
+  table.extend_from_slice(&0u16.to_be_bytes());
+  table.extend_from_slice(&(records.len() as u16).to_be_bytes());
+  table.extend_from_slice(&(6 + 12 * records.len() as u16).to_be_bytes());
+  for (id, text) in &records {
+    let encoded: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    for value in [
+      NAME_PLATFORM,
+      NAME_ENCODING,
+      NAME_LANGUAGE,
+      *id,
+      encoded.len() as u16,
+      strings.len() as u16,
+    ] {
+      table.extend_from_slice(&value.to_be_bytes());
+    }
+    strings.extend_from_slice(&encoded);
+  }
+  table.extend_from_slice(&strings);
+  table
+}
+
+// 表的校验和（按 4 字节大端整数求和，不足 4 字节补 0）。
+fn checksum(data: &[u8]) -> u32 {
+  data.chunks(4).fold(0u32, |sum, chunk| {
+    let mut word = [0u8; 4];
+    word[..chunk.len()].copy_from_slice(chunk);
+    sum.wrapping_add(u32::from_be_bytes(word))
+  })
+}
+
+// 写出字体文件：表目录按表名排序，每个表按 4 字节对齐，最后填写 head 里的整体校验调整值。
+fn write_font(version: u32, tables: &BTreeMap<[u8; 4], Vec<u8>>) -> Vec<u8> {
+  let count = tables.len();
+  let power = 1usize << count.ilog2();
+  let mut font = Vec::new();
+  font.extend_from_slice(&version.to_be_bytes());
+  for value in [count, power * 16, power.ilog2() as usize, count * 16 - power * 16] {
+    font.extend_from_slice(&(value as u16).to_be_bytes());
+  }
+  let mut offset = 12 + 16 * count;
+  let mut head_offset = None;
+  for (tag, table) in tables {
+    if tag == b"head" {
+      head_offset = Some(offset);
+    }
+    font.extend_from_slice(tag);
+    font.extend_from_slice(&checksum(table).to_be_bytes());
+    font.extend_from_slice(&(offset as u32).to_be_bytes());
+    font.extend_from_slice(&(table.len() as u32).to_be_bytes());
+    offset += table.len().next_multiple_of(4);
+  }
+  for table in tables.values() {
+    font.extend_from_slice(table);
+    font.resize(font.len().next_multiple_of(4), 0);
+  }
+  if let Some(head) = head_offset {
+    let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+    font[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+  }
+  font
+}
+
+// 子集化字体：只保留 chars 用到的字形（subset_glyphs 为 false 或不是 TrueType 轮廓时不做字形子集化），
+// 并把字体名改成 family。
+pub(crate) fn subset_font(
+  data: &[u8],
+  face_index: u32,
+  chars: &BTreeSet<char>,
+  family: &str,
+  subset_glyphs: bool,
+) -> Result<SubsetOutput, String> {
+  let (version, source) = read_tables(data, face_index)?;
+  let head = source.get(b"head").ok_or_else(|| "字体缺少 head 表".to_string())?;
+  let truetype = source.contains_key(b"glyf") && source.contains_key(b"loca");
+  let subsetted = subset_glyphs && truetype;
+
+  // 修改后原有的数字签名失效，直接去掉。
+  let mut tables: BTreeMap<[u8; 4], Vec<u8>> = source
+    .iter()
+    .filter(|(tag, _)| *tag != b"DSIG")
+    .map(|(tag, table)| (*tag, table.to_vec()))
+    .collect();
+  let mut new_head = head.to_vec();
+  new_head
+    .get_mut(8..12)
+    .ok_or_else(|| "字体的 head 表不完整".to_string())?
+    .fill(0);
+
+  if subsetted {
+    let maxp = source.get(b"maxp").ok_or_else(|| "字体缺少 maxp 表".to_string())?;
+    let cmap = source.get(b"cmap").ok_or_else(|| "字体缺少 cmap 表".to_string())?;
+    let long = read_u16(head, 50)? != 0;
+    let num_glyphs = read_u16(maxp, 4)?;
+    let mapping = map_chars(cmap, chars)?;
+    // 0 号字形（.notdef）必须保留。
+    let keep = glyph_closure(
+      source[b"glyf"],
+      source[b"loca"],
+      long,
+      num_glyphs,
+      std::iter::once(0).chain(mapping.values().copied()),
+    )?;
+    let (glyf, loca) = build_glyf(source[b"glyf"], source[b"loca"], long, num_glyphs, &keep)?;
+    tables.insert(*b"glyf", glyf);
+    tables.insert(*b"loca", loca);
+    new_head
+      .get_mut(50..52)
+      .ok_or_else(|| "字体的 head 表不完整".to_string())?
+      .copy_from_slice(&1u16.to_be_bytes());
+    tables.insert(*b"cmap", build_cmap(&mapping));
+    if let Some(post) = source.get(b"post").filter(|post| post.len() >= 32) {
+      let mut post = post[..32].to_vec();
+      post[..4].copy_from_slice(&0x0003_0000u32.to_be_bytes());
+      tables.insert(*b"post", post);
+    }
+    for tag in DROPPED_TABLES {
+      tables.remove(tag);
+    }
+  }
+  tables.insert(*b"head", new_head);
+
+  let subfamily = source
+    .get(b"name")
+    .and_then(|name| read_name(name, 2))
+    .unwrap_or_else(|| "Regular".to_string());
+  tables.insert(*b"name", build_name(family, &subfamily));
+  Ok(SubsetOutput {
+    data: write_font(version, &tables),
+    subsetted,
+    truetype,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // 测试用的字形：0、1、3、4、5 为简单字形，2 为引用 3 与 4 的复合字形，5 没有被用到。
+  fn glyphs() -> Vec<Vec<u8>> {
+    let simple = |len: usize, fill: u8| {
+      let mut data = vec![fill; len];
+      data[..2].copy_from_slice(&1u16.to_be_bytes());
+      data
+    };
+    let mut composite = vec![0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0];
+    // 第一个部件：参数为 16 位、带缩放、后面还有部件。
+    let flags = ARG_1_AND_2_ARE_WORDS | WE_HAVE_A_SCALE | MORE_COMPONENTS;
+    composite.extend_from_slice(&flags.to_be_bytes());
+    composite.extend_from_slice(&3u16.to_be_bytes());
+    composite.extend_from_slice(&[0, 1, 0, 2, 0x40, 0]);
+    // 第二个部件：参数为 8 位。
+    composite.extend_from_slice(&0u16.to_be_bytes());
+    composite.extend_from_slice(&4u16.to_be_bytes());
+    composite.extend_from_slice(&[1, 2]);
+    vec![simple(12, 0xA0), simple(6, 0xA1), composite, simple(4, 0xA3), simple(8, 0xA4), simple(4, 0xA5)]
+  }
+
+  // 拼出 glyf 与 loca（long 为 false 时是短格式，偏移量除以 2）。
+  fn build_source(glyphs: &[Vec<u8>], long: bool) -> (Vec<u8>, Vec<u8>) {
+    let mut glyf = Vec::new();
+    let mut loca = Vec::new();
+    let push_offset = |loca: &mut Vec<u8>, offset: usize| {
+      if long {
+        loca.extend_from_slice(&(offset as u32).to_be_bytes());
+      } else {
+        loca.extend_from_slice(&((offset / 2) as u16).to_be_bytes());
+      }
+    };
+    for glyph in glyphs {
+      push_offset(&mut loca, glyf.len());
+      glyf.extend_from_slice(glyph);
+    }
+    push_offset(&mut loca, glyf.len());
+    (glyf, loca)
+  }
+
+  fn read_loca(loca: &[u8]) -> Vec<u32> {
+    loca.chunks(4).map(|chunk| read_u32(chunk, 0).unwrap()).collect()
+  }
+
+  #[test]
+  fn rebuilds_glyf_and_loca() {
+    let glyphs = glyphs();
+    for long in [false, true] {
+      let (glyf, loca) = build_source(&glyphs, long);
+      let keep = glyph_closure(&glyf, &loca, long, 6, [0, 2].into_iter()).unwrap();
+      assert_eq!(keep.into_iter().collect::<Vec<_>>(), [0, 2, 3, 4]);
+
+      let keep = BTreeSet::from([0, 2, 3, 4]);
+      let (new_glyf, new_loca) = build_glyf(&glyf, &loca, long, 6, &keep).unwrap();
+      // 复合字形 26 字节，按 4 字节对齐到 28；没保留的字形长度为 0。
+      assert_eq!(read_loca(&new_loca), [0, 12, 12, 40, 44, 52, 52]);
+      assert_eq!(new_glyf.len(), 52);
+      assert_eq!(&new_glyf[0..12], glyphs[0].as_slice());
+      assert_eq!(&new_glyf[12..38], glyphs[2].as_slice());
+      assert_eq!(&new_glyf[38..40], &[0, 0]);
+      assert_eq!(&new_glyf[40..44], glyphs[3].as_slice());
+      assert_eq!(&new_glyf[44..52], glyphs[4].as_slice());
+    }
+  }
+
+  #[test]
+  fn closure_ignores_out_of_range_glyphs() {
+    let (glyf, loca) = build_source(&glyphs(), false);
+    // 只有 4 个字形时复合字形引用的 4 号超出范围，不收录。
+    let keep = glyph_closure(&glyf, &loca, false, 4, [2, 9].into_iter()).unwrap();
+    assert_eq!(keep.into_iter().collect::<Vec<_>>(), [2, 3]);
+  }
+
+  #[test]
+  fn rebuilt_cmap_maps_back() {
+    let mapping: BTreeMap<u32, u16> = [(0x41, 1), (0x42, 2), (0x44, 3), (0x4E2D, 7), (0x1F600, 9)].into();
+    let chars: BTreeSet<char> = mapping.keys().filter_map(|code| char::from_u32(*code)).chain(['Z']).collect();
+    let cmap = build_cmap(&mapping);
+    assert_eq!(map_chars(&cmap, &chars).unwrap(), mapping);
+    // 只看格式 4 子表时，BMP 以外的字符查不到。
+    let format4 = read_u32(&cmap, 8).unwrap() as usize;
+    assert_eq!(lookup_format4(&cmap, format4, 0x4E2D).unwrap(), 7);
+    assert_eq!(lookup_format4(&cmap, format4, 0x43).unwrap(), 0);
+    assert_eq!(lookup_format4(&cmap, format4, 0x1F600).unwrap(), 0);
+  }
+}
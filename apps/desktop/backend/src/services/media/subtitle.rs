@@ -0,0 +1,1156 @@
+/*
+  字幕文本处理：
+  - 编码识别：有 BOM（UTF-8 / UTF-16）时按 BOM，其次是合法的 UTF-8；否则分别按 GB18030、Big5、Shift-JIS 解码，
+    解码出错的排除，其余按常用汉字、假名与全角标点的比例打分（类似 chardet 的做法），取得分最高的，
+    网上下载的 GBK / Big5 字幕在播放器里不再显示成乱码；
+  - 格式转换：ASS / SSA、SRT、WebVTT 互转，先解析成统一的字幕条目（起止时间 + 文本，文本里只保留
+    <i> <b> <u> 三种标签）再输出；ASS 转 ASS 只转换编码、保留原文；ASS 转其它格式时丢掉特效标签，
+    注释行与绘图（\p）不输出；其它格式转 ASS 时使用默认样式；
+  - 输出编码为 UTF-8（可带 BOM）或 UTF-16LE（带 BOM）；
+  - 时间调整：整体偏移与按比例拉伸（修正不同剪辑版本或 23.976 / 25 帧的片源），只改写时间，
+    样式、标签与 VTT 的位置设置原样保留；可以先预览首尾几条调整后的时间再写入；
+  - 繁简转换：只转换对白文本（规则同 bangumi 的 convert_chinese，基于 OpenCC 词表），
+    时间、样式表与标签（覆盖标签里可能有字体名）原样保留。
+*/
+
+use encoding_rs::{Encoding, BIG5, GB18030, SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::fonts::{column, parse_format, section_name};
+use crate::services::bangumi::convert_chinese_text;
+use crate::services::error::AppError;
+use crate::services::paths::ensure_path_allowed;
+
+// 没有 BOM 且不是 UTF-8 时尝试的编码（得分相同时靠前的优先）。
+const LEGACY_ENCODINGS: [&Encoding; 3] = [GB18030, BIG5, SHIFT_JIS];
+
+// 常用汉字（简体常用字与常见的繁体字形），用来判断解码结果是否像正常的中日文。
+const COMMON_HANZI: &str = concat!(
+  "的一是不了在人有我他这个们中来上大为和国地到以说时要就出会可也你对生能而子那得于着下自之年过发后作里",
+  "用道行所然家种事成方多经么去法学如都同现当没动面起看定天分还进好小部其些主样理心她本前开但因只从想实",
+  "日者意无力它与长把机十民第公此已工使情明性知全三又关点正业外将两高间由问很最重并物手应向头文体美相见",
+  "被利什二等产或新己制身果加月话合回特代内信表化老给世位次度门任常先海通教儿原东声提立及比员解水名真论",
+  "处走义各入几口认条平气题活更别打女变四神总何电数安少报才结反受目太量再感建务做接必场件计管期市直资命",
+  "山金指许统区保至形便空决治展马科司五基眼书非则听白却界达光放强即像难且权思王象完设式色路记南品住告类",
+  "求据程北边死张该交规万取拉格望觉术领共确传师观清今切让识候带导争运笑飞风步改收根干造言联持组每车亲极",
+  "林服快办议往元士证近失转夫令准布始怎呢存未远叫台单影具字爱击流备连调深商算质团集百需价花华城石级整离",
+  "况请技际约示复病息究线似官火断精满支视消越器容照须九增研写称八功吗包片史委乎查轻易早曾除农找装广显吧",
+  "李标谈吃图念六引历首医局突专费号尽另周较注语仅考落青随选列红這個們來為國會說時對發後裡經麼學現當沒動",
+  "還進樣開實從種過東車門見長頭話問間關點無與應電體機兩聲風愛聽讓給處變邊氣區覺條書題將讀寫買賣錢飛號誰",
+  "師親認識謝請幾萬歲場員辦該歡樂雖級陽陰嗎囉總義結戰軍運陣術導專業產質權強調難歷隊團燈黨據監養課論參報",
+  "紀藝際準備幣價貨優勢醫療轉輪議雙擊傳統鐘錶僅億廣龍鳥魚馬貓隻葉劍鐵銀錯鏡驚險驗顯離聞聯繫續織網絡濟願",
+  "隨陳陸雲靈飯館餘驅騎騙齊齒龜鬥鬧醜討訓記設許訪證評詞試詳誠談諸講貝負貢財責販貧貴費貿賀資賞購趕趙軟輕",
+  "載較輸農遠違連遲遺鄉釋針鈴鋼錄鍵閃閉閒閱階隱雞須頂項順預領額顏類顧飲饒駕髮鮮鳴麥黃",
+);
+
+// 繁简转换时替换的文件名语言后缀（简体, 繁体）。
+const CHINESE_SUFFIXES: [(&str, &str); 4] = [("chs", "cht"), ("sc", "tc"), ("gb", "big5"), ("jpsc", "jptc")];
+
+// 条目文本里保留的标签。
+const STYLE_TAGS: [&str; 6] = ["<i>", "</i>", "<b>", "</b>", "<u>", "</u>"];
+
+// 其它格式转 ASS 时的文件头与默认样式。
+const ASS_HEADER: &str = "[Script Info]
+ScriptType: v4.00+
+WrapStyle: 0
+ScaledBorderAndShadow: yes
+PlayResX: 1920
+PlayResY: 1080
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, \
+Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, \
+MarginV, Encoding
+Style: Default,Arial,72,&H00FFFFFF,&H000000FF,&H00000000,&H64000000,0,0,0,0,100,100,0,0,1,3,1,2,60,60,50,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+";
+
+/// 字幕格式。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+  /// ASS / SSA。
+  Ass,
+  Srt,
+  /// WebVTT。
+  Vtt,
+}
+
+impl SubtitleFormat {
+  // 按扩展名识别（ssa 按 ass 处理），不支持的返回 None。
+  pub(crate) fn from_path(path: &Path) -> Option<Self> {
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+      "ass" | "ssa" => Some(Self::Ass),
+      "srt" => Some(Self::Srt),
+      "vtt" => Some(Self::Vtt),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn extension(self) -> &'static str {
+    match self {
+      Self::Ass => "ass",
+      Self::Srt => "srt",
+      Self::Vtt => "vtt",
+    }
+  }
+}
+
+/// 输出编码。
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SubtitleEncoding {
+  #[default]
+  Utf8,
+  /// UTF-8 带 BOM（部分旧播放器只认带 BOM 的 UTF-8）。
+  Utf8Bom,
+  /// UTF-16LE 带 BOM。
+  Utf16le,
+}
+
+impl SubtitleEncoding {
+  // 省略输出路径且与输入同名时加在文件名后的后缀。
+  fn suffix(self) -> &'static str {
+    match self {
+      Self::Utf8 | Self::Utf8Bom => "utf8",
+      Self::Utf16le => "utf16",
+    }
+  }
+
+  pub(crate) fn encode(self, text: &str) -> Vec<u8> {
+    match self {
+      Self::Utf8 => text.as_bytes().to_vec(),
+      Self::Utf8Bom => [&[0xEF, 0xBB, 0xBF][..], text.as_bytes()].concat(),
+      Self::Utf16le => {
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in text.encode_utf16() {
+          bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        bytes
+      }
+    }
+  }
+}
+
+/// convert_subtitle 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleConversion {
+  pub output_path: String,
+  pub source_format: SubtitleFormat,
+  /// 识别出的原编码（如 UTF-8、GBK 字幕为 gb18030、Big5、Shift_JIS）。
+  pub source_encoding: String,
+  /// 编码识别的可信度（0 ~ 1），有 BOM 或是合法 UTF-8 时为 1。
+  pub confidence: f64,
+  /// 输出的字幕条数。
+  pub cue_count: usize,
+}
+
+/// 调整时间前后的一条字幕（shift_subtitle 预览用），时间均为毫秒。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftedCue {
+  /// 序号（从 1 开始，按原开始时间排序）。
+  pub index: usize,
+  pub start: i64,
+  pub end: i64,
+  pub new_start: i64,
+  pub new_end: i64,
+  /// 文本（\n 分行，只含 <i> <b> <u> 标签）。
+  pub text: String,
+}
+
+/// shift_subtitle 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleShift {
+  /// 写入的字幕文件，预览时为 None。
+  pub output_path: Option<String>,
+  /// 调整后保留的字幕条数。
+  pub cue_count: usize,
+  /// 调整后结束时间不晚于 0、被删掉的字幕条数。
+  pub dropped: usize,
+  /// 预览时为前后各 N 条（不重复）调整前后的时间，否则为空。
+  pub preview: Vec<ShiftedCue>,
+}
+
+/// convert_subtitle_chinese 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleChineseConversion {
+  pub output_path: String,
+  /// 对白条数。
+  pub cue_count: usize,
+  /// 文本有改动的对白条数。
+  pub changed_count: usize,
+}
+
+// 一条字幕：起止时间（毫秒）与文本（\n 分行，只含 <i> <b> <u> 标签）。
+#[derive(Clone)]
+pub(crate) struct Cue {
+  pub start: i64,
+  pub end: i64,
+  pub text: String,
+}
+
+// 解析后的字幕：ASS 同时保留原文（转 ASS 时原样输出）。
+pub(crate) struct ParsedSubtitle {
+  pub format: SubtitleFormat,
+  pub content: String,
+  pub cues: Vec<Cue>,
+}
+
+// 解码结果的得分：常用汉字 2 分，假名与全角标点 1 分；半角片假名与私用区字符扣分
+// （GBK / Big5 按 Shift-JIS 解码时常出现大量半角片假名）。
+fn score_text(text: &str, common: &HashSet<char>) -> (i64, usize) {
+  let mut score = 0;
+  let mut total = 0;
+  for c in text.chars().filter(|c| !c.is_ascii()) {
+    total += 1;
+    score += match c {
+      _ if common.contains(&c) => 2,
+      '\u{3000}'..='\u{30FF}' | '\u{FF01}'..='\u{FF5E}' => 1,
+      '\u{FF61}'..='\u{FF9F}' | '\u{E000}'..='\u{F8FF}' => -2,
+      _ => 0,
+    };
+  }
+  (score, total)
+}
+
+// 识别字幕编码，返回编码与可信度（0 ~ 1）。
+pub(crate) fn detect_encoding(bytes: &[u8]) -> (&'static Encoding, f64) {
+  if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+    return (encoding, 1.0);
+  }
+  if std::str::from_utf8(bytes).is_ok() {
+    return (UTF_8, 1.0);
+  }
+  let common: HashSet<char> = COMMON_HANZI.chars().collect();
+  let mut best: Option<(&'static Encoding, i64, usize)> = None;
+  for encoding in LEGACY_ENCODINGS {
+    let Some(text) = encoding.decode_without_bom_handling_and_without_replacement(bytes) else {
+      continue;
+    };
+    let (score, total) = score_text(&text, &common);
+    let better = match best {
+      Some((_, best_score, _)) => score > best_score,
+      None => true,
+    };
+    if better {
+      best = Some((encoding, score, total));
+    }
+  }
+  match best {
+    Some((encoding, score, total)) if total > 0 => (encoding, (score as f64 / (2 * total) as f64).clamp(0.0, 1.0)),
+    Some((encoding, _, _)) => (encoding, 0.0),
+    // 都解码出错时按 GB18030 解码（出错的字节替换为 U+FFFD）。
+    None => (GB18030, 0.0),
+  }
+}
+
+// 按识别出的编码解码字幕文本（去掉 BOM）。
+pub(crate) fn decode_subtitle(bytes: &[u8]) -> String {
+  decode_with(bytes, detect_encoding(bytes).0)
+}
+
+fn decode_with(bytes: &[u8], encoding: &'static Encoding) -> String {
+  if encoding == UTF_16LE || encoding == UTF_16BE || encoding == UTF_8 {
+    return encoding.decode_with_bom_removal(bytes).0.into_owned();
+  }
+  encoding.decode_without_bom_handling(bytes).0.into_owned()
+}
+
+// 解析时间：[H:]MM:SS[.,]小数（SRT 的 00:00:01,000、VTT 的 00:01.000、ASS 的 0:00:01.00），返回毫秒。
+fn parse_time(value: &str) -> Option<i64> {
+  let value = value.trim();
+  let (clock, fraction) = match value.rfind(['.', ',']) {
+    Some(index) => (&value[..index], &value[index + 1..]),
+    None => (value, ""),
+  };
+  let parts: Vec<i64> = clock
+    .split(':')
+    .map(|part| part.trim().parse::<i64>().ok().filter(|number| *number >= 0))
+    .collect::<Option<_>>()?;
+  let seconds = match parts.as_slice() {
+    [minutes, seconds] => minutes * 60 + seconds,
+    [hours, minutes, seconds] => hours * 3600 + minutes * 60 + seconds,
+    _ => return None,
+  };
+  if !fraction.chars().all(|c| c.is_ascii_digit()) {
+    return None;
+  }
+  // 小数部分按毫秒取前三位，不足三位补零（ASS 的厘秒 "12" 为 120 毫秒）。
+  let millis = format!("{fraction:0<3}")[..3].parse::<i64>().unwrap_or(0);
+  Some(seconds * 1000 + millis)
+}
+
+// 毫秒拆成时、分、秒、毫秒（负数按 0 处理）。
+fn split_time(ms: i64) -> (i64, i64, i64, i64) {
+  let ms = ms.max(0);
+  (ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000)
+}
+
+pub(crate) fn format_srt_time(ms: i64) -> String {
+  let (hours, minutes, seconds, millis) = split_time(ms);
+  format!("{hours:02}:{minutes:02}:{seconds:02},{millis:03}")
+}
+
+pub(crate) fn format_vtt_time(ms: i64) -> String {
+  let (hours, minutes, seconds, millis) = split_time(ms);
+  format!("{hours:02}:{minutes:02}:{seconds:02}.{millis:03}")
+}
+
+// ASS 的时间精确到厘秒，四舍五入。
+pub(crate) fn format_ass_time(ms: i64) -> String {
+  let (hours, minutes, seconds, centis) = split_time((ms.max(0) + 5) / 10 * 10);
+  format!("{hours}:{minutes:02}:{seconds:02}.{:02}", centis / 10)
+}
+
+// 标签 <i> </i> <b> </b> <u> </u>（VTT 可以带 .class）：返回标签字母与是否为开始标签。
+fn style_tag(inner: &str) -> Option<(char, bool)> {
+  let (closing, name) = match inner.strip_prefix('/') {
+    Some(name) => (true, name),
+    None => (false, inner),
+  };
+  let name = name.split('.').next().unwrap_or("").trim().to_ascii_lowercase();
+  match name.as_str() {
+    "i" | "b" | "u" => name.chars().next().map(|tag| (tag, !closing)),
+    _ => None,
+  }
+}
+
+// 整理 SRT / VTT 的文本：保留 <i> <b> <u>，去掉其它标签（<font>、VTT 的 <c> <v> 与时间戳）
+// 与 SRT 里常见的 ASS 覆盖标签（如 {\an8}）；vtt 为真时还原 &amp; 等实体。
+fn clean_markup(text: &str, vtt: bool) -> String {
+  let mut result = String::new();
+  let mut rest = text;
+  while let Some(index) = rest.find(['<', '{']) {
+    result.push_str(&rest[..index]);
+    rest = &rest[index..];
+    let close = if rest.starts_with('<') { '>' } else { '}' };
+    let Some(end) = rest.find(close) else {
+      break;
+    };
+    let inner = &rest[1..end];
+    if close == '>' {
+      if let Some((tag, open)) = style_tag(inner) {
+        result.push_str(if open { "<" } else { "</" });
+        result.push(tag);
+        result.push('>');
+      }
+    } else if !inner.starts_with('\\') {
+      // 不是覆盖标签的花括号原样保留。
+      result.push_str(&rest[..=end]);
+    }
+    rest = &rest[end + 1..];
+  }
+  result.push_str(rest);
+  if vtt {
+    result = result
+      .replace("&lt;", "<")
+      .replace("&gt;", ">")
+      .replace("&nbsp;", " ")
+      .replace("&amp;", "&");
+  }
+  result.trim().to_string()
+}
+
+// 解析 SRT / VTT：按空行分块，含有 "-->" 的行为时间行，其后为文本；
+// 没有时间行的块（VTT 的文件头、NOTE、STYLE 等）跳过。
+fn parse_blocks(content: &str, vtt: bool) -> Vec<Cue> {
+  let mut cues = Vec::new();
+  let mut block: Vec<&str> = Vec::new();
+  for line in content.lines().chain(std::iter::once("")) {
+    if !line.trim().is_empty() {
+      block.push(line);
+      continue;
+    }
+    if let Some(index) = block.iter().position(|line| line.contains("-->")) {
+      let (start, rest) = block[index].split_once("-->").unwrap_or_default();
+      // VTT 的时间后面可以跟位置设置。
+      let end = rest.split_whitespace().next().unwrap_or("");
+      if let (Some(start), Some(end)) = (parse_time(start), parse_time(end)) {
+        let text = clean_markup(&block[index + 1..].join("\n"), vtt);
+        if !text.is_empty() {
+          cues.push(Cue { start, end, text });
+        }
+      }
+    }
+    block.clear();
+  }
+  cues
+}
+
+// ASS 对白文本转成条目文本：\N 换行，\n 与 \h 为空格；覆盖标签里只保留斜体、粗体、下划线，
+// \r 重置样式。含有绘图（\p1 等）的对白返回 None。
+fn ass_text(text: &str) -> Option<String> {
+  let mut result = String::new();
+  // 当前的斜体、粗体、下划线状态。
+  let mut state = [('i', false), ('b', false), ('u', false)];
+  let mut rest = text;
+  loop {
+    let Some(index) = rest.find('{') else {
+      result.push_str(rest);
+      break;
+    };
+    result.push_str(&rest[..index]);
+    let Some(end) = rest[index..].find('}') else {
+      result.push_str(&rest[index..]);
+      break;
+    };
+    let block = &rest[index + 1..index + end];
+    rest = &rest[index + end + 1..];
+    for tag in block.split('\\').skip(1) {
+      let name: String = tag.chars().take_while(|c| c.is_ascii_alphabetic()).collect();
+      let value = tag[name.len()..].trim();
+      if name == "p" && value.parse::<u32>().is_ok_and(|scale| scale > 0) {
+        return None;
+      }
+      // 不带数值的 \i \b \u 恢复样式的设置，按关闭处理；\b 也可以是字重（\b700）。
+      let target = match name.as_str() {
+        "i" | "u" => Some(value == "1"),
+        "b" => Some(value.parse::<u32>().is_ok_and(|weight| weight == 1 || weight >= 600)),
+        _ => None,
+      };
+      let changes: Vec<(char, bool)> = match target {
+        Some(on) => vec![(name.chars().next().unwrap_or('i'), on)],
+        // 样式重置（\r 或 \r样式名）。
+        None if name.starts_with('r') => state.iter().map(|(tag, _)| (*tag, false)).collect(),
+        None => Vec::new(),
+      };
+      for (tag, on) in changes {
+        for item in state.iter_mut().filter(|(item, _)| *item == tag) {
+          if item.1 != on {
+            item.1 = on;
+            result.push_str(if on { "<" } else { "</" });
+            result.push(tag);
+            result.push('>');
+          }
+        }
+      }
+    }
+  }
+  for (tag, on) in state.iter().rev() {
+    if *on {
+      result.push_str(&format!("</{tag}>"));
+    }
+  }
+  let result = result.replace("\\N", "\n").replace("\\n", " ").replace("\\h", " ");
+  let trimmed = result.trim();
+  // 只有标签、没有文字的对白不输出。
+  let plain = STYLE_TAGS
+    .iter()
+    .fold(trimmed.to_string(), |text, tag| text.replace(tag, ""));
+  (!plain.trim().is_empty()).then(|| trimmed.to_string())
+}
+
+// 解析 ASS 的 [Events]：只取 Dialogue 行（跳过 Comment），按开始时间排序（时间相同保持原顺序）。
+fn parse_ass(content: &str) -> Vec<Cue> {
+  let mut cues = Vec::new();
+  let mut section = String::new();
+  let mut format: Vec<String> = Vec::new();
+  for line in content.lines() {
+    let line = line.trim();
+    if let Some(name) = section_name(line) {
+      section = name;
+      continue;
+    }
+    if section != "[events]" {
+      continue;
+    }
+    let Some((key, value)) = line.split_once(':') else {
+      continue;
+    };
+    match key.trim().to_ascii_lowercase().as_str() {
+      "format" => format = parse_format(value),
+      "dialogue" => {
+        let count = if format.is_empty() { 10 } else { format.len() };
+        let fields: Vec<&str> = value.trim_start().splitn(count, ',').collect();
+        let field = |name: &str, fallback: usize| fields.get(column(&format, name, fallback)).copied();
+        let (Some(start), Some(end), Some(text)) = (
+          field("start", 1).and_then(parse_time),
+          field("end", 2).and_then(parse_time),
+          field("text", 9),
+        ) else {
+          continue;
+        };
+        if let Some(text) = ass_text(text) {
+          cues.push(Cue { start, end, text });
+        }
+      }
+      _ => {}
+    }
+  }
+  cues.sort_by_key(|cue| cue.start);
+  cues
+}
+
+// 解析字幕文本（格式由调用方按扩展名决定）。
+pub(crate) fn parse_subtitle(content: String, format: SubtitleFormat) -> ParsedSubtitle {
+  let content = content.replace("\r\n", "\n");
+  let cues = match format {
+    SubtitleFormat::Ass => parse_ass(&content),
+    SubtitleFormat::Srt => parse_blocks(&content, false),
+    SubtitleFormat::Vtt => parse_blocks(&content, true),
+  };
+  ParsedSubtitle { format, content, cues }
+}
+
+// 条目文本转 ASS：换行为 \N，<i> 等标签转成覆盖标签。
+fn to_ass_text(text: &str) -> String {
+  let mut result = text.replace('\n', "\\N");
+  for tag in ['i', 'b', 'u'] {
+    result = result
+      .replace(&format!("<{tag}>"), &format!("{{\\{tag}1}}"))
+      .replace(&format!("</{tag}>"), &format!("{{\\{tag}0}}"));
+  }
+  result
+}
+
+// 条目文本转 VTT：转义 & 与不属于 <i> <b> <u> 的尖括号。
+fn to_vtt_text(text: &str) -> String {
+  let mut result = String::new();
+  let mut rest = text;
+  while let Some(c) = rest.chars().next() {
+    if let Some(tag) = STYLE_TAGS.iter().find(|tag| rest.starts_with(**tag)) {
+      result.push_str(tag);
+      rest = &rest[tag.len()..];
+      continue;
+    }
+    match c {
+      '&' => result.push_str("&amp;"),
+      '<' => result.push_str("&lt;"),
+      '>' => result.push_str("&gt;"),
+      _ => result.push(c),
+    }
+    rest = &rest[c.len_utf8()..];
+  }
+  result
+}
+
+// 按目标格式输出字幕文本。ASS 转 ASS 时输出原文。
+pub(crate) fn render_subtitle(subtitle: &ParsedSubtitle, format: SubtitleFormat) -> String {
+  let mut output = String::new();
+  match format {
+    SubtitleFormat::Ass if subtitle.format == SubtitleFormat::Ass => output.push_str(&subtitle.content),
+    SubtitleFormat::Ass => {
+      output.push_str(ASS_HEADER);
+      for cue in &subtitle.cues {
+        output.push_str(&format!(
+          "Dialogue: 0,{},{},Default,,0,0,0,,{}\n",
+          format_ass_time(cue.start),
+          format_ass_time(cue.end),
+          to_ass_text(&cue.text)
+        ));
+      }
+    }
+    SubtitleFormat::Srt => {
+      for (index, cue) in subtitle.cues.iter().enumerate() {
+        output.push_str(&format!(
+          "{}\n{} --> {}\n{}\n\n",
+          index + 1,
+          format_srt_time(cue.start),
+          format_srt_time(cue.end),
+          cue.text
+        ));
+      }
+    }
+    SubtitleFormat::Vtt => {
+      output.push_str("WEBVTT\n\n");
+      for cue in &subtitle.cues {
+        output.push_str(&format!(
+          "{} --> {}\n{}\n\n",
+          format_vtt_time(cue.start),
+          format_vtt_time(cue.end),
+          to_vtt_text(&cue.text)
+        ));
+      }
+    }
+  }
+  output
+}
+
+// 时间调整：先按比例拉伸，再整体偏移（毫秒）。
+#[derive(Clone, Copy)]
+struct TimeShift {
+  offset: i64,
+  scale: f64,
+}
+
+impl TimeShift {
+  fn apply(self, ms: i64) -> i64 {
+    (ms as f64 * self.scale).round() as i64 + self.offset
+  }
+
+  // 调整一条字幕：开始时间早于 0 的截到 0，结束时间不晚于 0 的整条删掉（返回 None）。
+  fn cue(self, start: i64, end: i64) -> Option<(i64, i64)> {
+    let end = self.apply(end);
+    (end > 0).then(|| (self.apply(start).max(0), end))
+  }
+}
+
+// 调整时间后的字幕文本，以及每条字幕调整前后的时间。
+struct ShiftOutput {
+  content: String,
+  cues: Vec<(Cue, Cue)>,
+  dropped: usize,
+}
+
+// 拆分 [Events] 里的一行（trimmed 为整行，value 为冒号后的部分）：返回 "Dialogue: " 这样的前缀与各列，
+// 最后一列（Text）可以含逗号。
+fn split_event<'a>(trimmed: &'a str, value: &'a str, format: &[String]) -> (&'a str, Vec<String>) {
+  let count = if format.is_empty() { 10 } else { format.len() };
+  let body = value.trim_start();
+  let prefix = &trimmed[..trimmed.len() - body.len()];
+  (prefix, body.splitn(count, ',').map(str::to_string).collect())
+}
+
+// 调整 ASS 的 [Events]：改写 Dialogue / Comment 行的起止时间，其它内容原样保留。
+fn shift_ass(content: &str, shift: TimeShift) -> ShiftOutput {
+  let mut lines: Vec<String> = Vec::new();
+  let mut cues = Vec::new();
+  let mut dropped = 0;
+  let mut section = String::new();
+  let mut format: Vec<String> = Vec::new();
+  for line in content.lines() {
+    let trimmed = line.trim();
+    if let Some(name) = section_name(trimmed) {
+      section = name;
+      lines.push(line.to_string());
+      continue;
+    }
+    let Some((key, value)) = trimmed.split_once(':').filter(|_| section == "[events]") else {
+      lines.push(line.to_string());
+      continue;
+    };
+    let key = key.trim().to_ascii_lowercase();
+    if key == "format" {
+      format = parse_format(value);
+    }
+    if key != "dialogue" && key != "comment" {
+      lines.push(line.to_string());
+      continue;
+    }
+    let (prefix, mut fields) = split_event(trimmed, value, &format);
+    let (start_column, end_column) = (column(&format, "start", 1), column(&format, "end", 2));
+    let start = fields.get(start_column).and_then(|value| parse_time(value));
+    let end = fields.get(end_column).and_then(|value| parse_time(value));
+    let (Some(start), Some(end)) = (start, end) else {
+      lines.push(line.to_string());
+      continue;
+    };
+    let Some((new_start, new_end)) = shift.cue(start, end) else {
+      if key == "dialogue" {
+        dropped += 1;
+      }
+      continue;
+    };
+    fields[start_column] = format_ass_time(new_start);
+    fields[end_column] = format_ass_time(new_end);
+    lines.push(format!("{prefix}{}", fields.join(",")));
+    if key == "dialogue" {
+      let text = fields
+        .get(column(&format, "text", 9))
+        .and_then(|text| ass_text(text))
+        .unwrap_or_default();
+      let old = Cue { start, end, text };
+      let new = Cue {
+        start: new_start,
+        end: new_end,
+        text: old.text.clone(),
+      };
+      cues.push((old, new));
+    }
+  }
+  ShiftOutput {
+    content: lines.join("\n") + "\n",
+    cues,
+    dropped,
+  }
+}
+
+// 调整 SRT / VTT：只改写时间行（VTT 时间后的位置设置保留），SRT 的序号按保留下来的条目重新编号。
+fn shift_blocks(content: &str, vtt: bool, shift: TimeShift) -> ShiftOutput {
+  let format_time: fn(i64) -> String = if vtt { format_vtt_time } else { format_srt_time };
+  let mut blocks: Vec<String> = Vec::new();
+  let mut cues = Vec::new();
+  let mut dropped = 0;
+  let mut block: Vec<String> = Vec::new();
+  for line in content.lines().chain(std::iter::once("")) {
+    if !line.trim().is_empty() {
+      block.push(line.to_string());
+      continue;
+    }
+    if block.is_empty() {
+      continue;
+    }
+    let mut lines = std::mem::take(&mut block);
+    if let Some(index) = lines.iter().position(|line| line.contains("-->")) {
+      let (start, rest) = lines[index].split_once("-->").unwrap_or_default();
+      let rest = rest.trim_start();
+      let end = rest.split_whitespace().next().unwrap_or("");
+      let settings = rest[end.len()..].to_string();
+      if let (Some(start), Some(end)) = (parse_time(start), parse_time(end)) {
+        let Some((new_start, new_end)) = shift.cue(start, end) else {
+          dropped += 1;
+          continue;
+        };
+        lines[index] = format!("{} --> {}{settings}", format_time(new_start), format_time(new_end));
+        if !vtt && index > 0 && lines[index - 1].trim().chars().all(|c| c.is_ascii_digit()) {
+          lines[index - 1] = (cues.len() + 1).to_string();
+        }
+        let text = clean_markup(&lines[index + 1..].join("\n"), vtt);
+        let old = Cue { start, end, text };
+        let new = Cue {
+          start: new_start,
+          end: new_end,
+          text: old.text.clone(),
+        };
+        cues.push((old, new));
+      }
+    }
+    blocks.push(lines.join("\n"));
+  }
+  ShiftOutput {
+    content: blocks.join("\n\n") + "\n",
+    cues,
+    dropped,
+  }
+}
+
+// 转换文本里不在标签内的部分：ASS 覆盖标签 {...}，以及 angle 为真时的 <...>（SRT / VTT 的 HTML 标签）。
+fn convert_outside_tags(text: &str, angle: bool, convert: &dyn Fn(&str) -> String) -> String {
+  let mut result = String::new();
+  let mut rest = text;
+  loop {
+    let open = if angle { rest.find(['{', '<']) } else { rest.find('{') };
+    let Some(index) = open else {
+      result.push_str(&convert(rest));
+      break;
+    };
+    result.push_str(&convert(&rest[..index]));
+    let close = if rest[index..].starts_with('{') { '}' } else { '>' };
+    let Some(end) = rest[index..].find(close) else {
+      result.push_str(&convert(&rest[index..]));
+      break;
+    };
+    result.push_str(&rest[index..=index + end]);
+    rest = &rest[index + end + 1..];
+  }
+  result
+}
+
+// 对白文本的繁简转换：ASS 只转换 Dialogue 行的 Text 列，SRT / VTT 只转换时间行之后的文本行，
+// 标签与时间原样保留。返回转换后的文本、对白条数与文本有改动的条数。
+fn convert_chinese_content(content: &str, format: SubtitleFormat, target: &str) -> (String, usize, usize) {
+  let convert = |text: &str| {
+    if text.is_empty() {
+      return String::new();
+    }
+    convert_chinese_text(text, target).unwrap_or_else(|_| text.to_string())
+  };
+  let mut cue_count = 0;
+  let mut changed = 0;
+  if format == SubtitleFormat::Ass {
+    let mut lines: Vec<String> = Vec::new();
+    let mut section = String::new();
+    let mut columns: Vec<String> = Vec::new();
+    for line in content.lines() {
+      let trimmed = line.trim();
+      if let Some(name) = section_name(trimmed) {
+        section = name;
+        lines.push(line.to_string());
+        continue;
+      }
+      let Some((key, value)) = trimmed.split_once(':').filter(|_| section == "[events]") else {
+        lines.push(line.to_string());
+        continue;
+      };
+      match key.trim().to_ascii_lowercase().as_str() {
+        "format" => {
+          columns = parse_format(value);
+          lines.push(line.to_string());
+        }
+        "dialogue" => {
+          let (prefix, mut fields) = split_event(trimmed, value, &columns);
+          if let Some(text) = fields.get_mut(column(&columns, "text", 9)) {
+            let converted = convert_outside_tags(text, false, &convert);
+            if converted != *text {
+              changed += 1;
+              *text = converted;
+            }
+          }
+          cue_count += 1;
+          lines.push(format!("{prefix}{}", fields.join(",")));
+        }
+        _ => lines.push(line.to_string()),
+      }
+    }
+    return (lines.join("\n") + "\n", cue_count, changed);
+  }
+
+  let mut blocks: Vec<String> = Vec::new();
+  let mut block: Vec<String> = Vec::new();
+  for line in content.lines().chain(std::iter::once("")) {
+    if !line.trim().is_empty() {
+      block.push(line.to_string());
+      continue;
+    }
+    if block.is_empty() {
+      continue;
+    }
+    let mut lines = std::mem::take(&mut block);
+    if let Some(index) = lines.iter().position(|line| line.contains("-->")) {
+      let mut cue_changed = false;
+      for text in lines.iter_mut().skip(index + 1) {
+        let converted = convert_outside_tags(text, true, &convert);
+        if converted != *text {
+          cue_changed = true;
+          *text = converted;
+        }
+      }
+      cue_count += 1;
+      changed += usize::from(cue_changed);
+    }
+    blocks.push(lines.join("\n"));
+  }
+  (blocks.join("\n\n") + "\n", cue_count, changed)
+}
+
+// 繁简转换的默认输出路径：文件名最后的语言后缀（如 .chs / .tc）换成目标对应的后缀，
+// 没有语言后缀时加上 .chs / .cht。
+fn chinese_output_path(input: &Path, simplified: bool) -> PathBuf {
+  let extension = input
+    .extension()
+    .map(|ext| ext.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let stem = input
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let (base, suffix) = match stem.rsplit_once('.') {
+    Some((base, suffix)) => {
+      let lower = suffix.to_ascii_lowercase();
+      let pair = CHINESE_SUFFIXES
+        .iter()
+        .find(|(hans, hant)| lower == *hans || lower == *hant);
+      match pair {
+        Some((hans, hant)) => {
+          let replaced = if simplified { *hans } else { *hant };
+          let replaced = if suffix.chars().all(|c| !c.is_ascii_lowercase()) {
+            replaced.to_ascii_uppercase()
+          } else {
+            replaced.to_string()
+          };
+          (base.to_string(), replaced)
+        }
+        None => (stem.clone(), String::new()),
+      }
+    }
+    None => (stem.clone(), String::new()),
+  };
+  let suffix = if suffix.is_empty() {
+    (if simplified { "chs" } else { "cht" }).to_string()
+  } else {
+    suffix
+  };
+  input.with_file_name(format!("{base}.{suffix}.{extension}"))
+}
+
+// 预览：按原开始时间排序后取前后各 count 条（不重复）。
+fn preview_cues(mut cues: Vec<(Cue, Cue)>, count: usize) -> Vec<ShiftedCue> {
+  cues.sort_by_key(|(old, _)| old.start);
+  let total = cues.len();
+  cues
+    .into_iter()
+    .enumerate()
+    .filter(|(index, _)| *index < count || *index + count >= total)
+    .map(|(index, (old, new))| ShiftedCue {
+      index: index + 1,
+      start: old.start,
+      end: old.end,
+      new_start: new.start,
+      new_end: new.end,
+      text: old.text,
+    })
+    .collect()
+}
+
+// 读取并解析字幕文件，返回解析结果、识别出的编码与可信度。
+pub(crate) fn read_subtitle(path: &Path) -> Result<(ParsedSubtitle, &'static Encoding, f64), AppError> {
+  let format = SubtitleFormat::from_path(path)
+    .ok_or_else(|| AppError::invalid_input(format!("只支持 ASS / SSA / SRT / VTT 字幕: {}", path.display())))?;
+  let bytes = fs::read(path).map_err(|e| AppError::io(format!("读取字幕 {} 失败: {e}", path.display())))?;
+  let (encoding, confidence) = detect_encoding(&bytes);
+  Ok((
+    parse_subtitle(decode_with(&bytes, encoding), format),
+    encoding,
+    confidence,
+  ))
+}
+
+// 校验字幕输入，并确定输出路径：省略时为输入旁同名、扩展名为目标格式的文件，
+// 与输入相同时在文件名后加 suffix（例如 "<文件名>.utf8.ass"），不会覆盖原字幕。
+pub(crate) fn resolve_subtitle_paths(
+  app: &tauri::AppHandle,
+  input: &str,
+  output: Option<String>,
+  format: Option<SubtitleFormat>,
+  suffix: &str,
+) -> Result<(PathBuf, PathBuf), AppError> {
+  let input = ensure_path_allowed(app, input).map_err(AppError::invalid_input)?;
+  if !input.is_file() {
+    return Err(AppError::invalid_input(format!("字幕文件不存在: {}", input.display())));
+  }
+  let source_format = SubtitleFormat::from_path(&input)
+    .ok_or_else(|| AppError::invalid_input(format!("只支持 ASS / SSA / SRT / VTT 字幕: {}", input.display())))?;
+  let extension = format.unwrap_or(source_format).extension();
+  let output = match output
+    .map(|output| output.trim().to_string())
+    .filter(|output| !output.is_empty())
+  {
+    Some(output) => ensure_path_allowed(app, &output).map_err(AppError::invalid_input)?,
+    None => {
+      let output = input.with_extension(extension);
+      if output == input {
+        let stem = input
+          .file_stem()
+          .map(|stem| stem.to_string_lossy().to_string())
+          .unwrap_or_default();
+        input.with_file_name(format!("{stem}.{suffix}.{extension}"))
+      } else {
+        output
+      }
+    }
+  };
+  if output == input {
+    return Err(AppError::invalid_input("输出文件不能与输入文件相同"));
+  }
+  Ok((input, output))
+}
+
+// 转换字幕格式与编码：自动识别原编码，输出为指定格式（省略时与输入相同）与编码（省略时为 UTF-8）。
+// output 省略时写在输入旁边（见 resolve_subtitle_paths）。
+#[tauri::command]
+pub async fn convert_subtitle(
+  app: tauri::AppHandle,
+  input: String,
+  target_format: Option<SubtitleFormat>,
+  target_encoding: Option<SubtitleEncoding>,
+  output: Option<String>,
+) -> Result<SubtitleConversion, AppError> {
+  let encoding = target_encoding.unwrap_or_default();
+  let (input, output) = resolve_subtitle_paths(&app, &input, output, target_format, encoding.suffix())?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let (subtitle, source_encoding, confidence) = read_subtitle(&input)?;
+    let format = target_format.unwrap_or(subtitle.format);
+    let text = render_subtitle(&subtitle, format);
+    if let Some(parent) = output.parent() {
+      fs::create_dir_all(parent).map_err(|e| AppError::io(format!("创建输出目录失败: {e}")))?;
+    }
+    fs::write(&output, encoding.encode(&text))
+      .map_err(|e| AppError::io(format!("写入字幕 {} 失败: {e}", output.display())))?;
+    Ok(SubtitleConversion {
+      output_path: output.to_string_lossy().to_string(),
+      source_format: subtitle.format,
+      source_encoding: source_encoding.name().to_string(),
+      confidence,
+      cue_count: subtitle.cues.len(),
+    })
+  })
+  .await
+  .map_err(|e| AppError::internal(format!("转换字幕失败: {e}")))?
+}
+
+// 调整字幕时间：每个时间先乘 scale_factor（省略时为 1）再加 offset_ms 毫秒，用于修正不同剪辑版本的整体偏移，
+// 或 23.976 / 25 帧片源之间的线性漂移。preview 为 N 时不写文件，只返回前后各 N 条调整前后的时间；
+// 否则写入 output（省略时为输入旁的“<文件名>.shifted.<扩展名>”，UTF-8 编码）。
+#[tauri::command]
+pub async fn shift_subtitle(
+  app: tauri::AppHandle,
+  input: String,
+  offset_ms: i64,
+  scale_factor: Option<f64>,
+  output: Option<String>,
+  preview: Option<usize>,
+) -> Result<SubtitleShift, AppError> {
+  let scale = scale_factor.unwrap_or(1.0);
+  if !scale.is_finite() || scale <= 0.0 {
+    return Err(AppError::invalid_input("拉伸系数必须是正数"));
+  }
+  let shift = TimeShift {
+    offset: offset_ms,
+    scale,
+  };
+  let (input, output) = resolve_subtitle_paths(&app, &input, output, None, "shifted")?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let (subtitle, _, _) = read_subtitle(&input)?;
+    let shifted = match subtitle.format {
+      SubtitleFormat::Ass => shift_ass(&subtitle.content, shift),
+      SubtitleFormat::Srt => shift_blocks(&subtitle.content, false, shift),
+      SubtitleFormat::Vtt => shift_blocks(&subtitle.content, true, shift),
+    };
+    let mut result = SubtitleShift {
+      output_path: None,
+      cue_count: shifted.cues.len(),
+      dropped: shifted.dropped,
+      preview: Vec::new(),
+    };
+    if let Some(count) = preview {
+      result.preview = preview_cues(shifted.cues, count);
+      return Ok(result);
+    }
+    if let Some(parent) = output.parent() {
+      fs::create_dir_all(parent).map_err(|e| AppError::io(format!("创建输出目录失败: {e}")))?;
+    }
+    fs::write(&output, SubtitleEncoding::Utf8.encode(&shifted.content))
+      .map_err(|e| AppError::io(format!("写入字幕 {} 失败: {e}", output.display())))?;
+    result.output_path = Some(output.to_string_lossy().to_string());
+    Ok(result)
+  })
+  .await
+  .map_err(|e| AppError::internal(format!("调整字幕时间失败: {e}")))?
+}
+
+// 字幕繁简转换：只转换对白文本，时间、样式表与标签原样保留，输出为 UTF-8。target 同 convert_chinese
+// （"simplified" / "traditional" 或地区代码如 "zh-tw"）；output 省略时把文件名的语言后缀换成目标对应的后缀。
+#[tauri::command]
+pub async fn convert_subtitle_chinese(
+  app: tauri::AppHandle,
+  input: String,
+  target: String,
+  output: Option<String>,
+) -> Result<SubtitleChineseConversion, AppError> {
+  convert_chinese_text("", &target).map_err(AppError::invalid_input)?;
+  let simplified = matches!(
+    target.trim().to_ascii_lowercase().as_str(),
+    "simplified" | "hans" | "zh-hans" | "zh-cn" | "zh-sg" | "zh-my"
+  );
+  let output = output.filter(|output| !output.trim().is_empty()).unwrap_or_else(|| {
+    chinese_output_path(Path::new(input.trim()), simplified)
+      .to_string_lossy()
+      .to_string()
+  });
+  let suffix = if simplified { "chs" } else { "cht" };
+  let (input, output) = resolve_subtitle_paths(&app, &input, Some(output), None, suffix)?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let (subtitle, _, _) = read_subtitle(&input)?;
+    let (content, cue_count, changed_count) = convert_chinese_content(&subtitle.content, subtitle.format, &target);
+    if let Some(parent) = output.parent() {
+      fs::create_dir_all(parent).map_err(|e| AppError::io(format!("创建输出目录失败: {e}")))?;
+    }
+    fs::write(&output, SubtitleEncoding::Utf8.encode(&content))
+      .map_err(|e| AppError::io(format!("写入字幕 {} 失败: {e}", output.display())))?;
+    Ok(SubtitleChineseConversion {
+      output_path: output.to_string_lossy().to_string(),
+      cue_count,
+      changed_count,
+    })
+  })
+  .await
+  .map_err(|e| AppError::internal(format!("字幕繁简转换失败: {e}")))?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SRT: &str = "1\n00:00:01,000 --> 00:00:02,500\n<i>第一句</i>\n\n2\n00:01:02,340 --> 00:01:04,000\n两行\n文本 & 符号\n\n";
+
+  #[test]
+  fn parses_times() {
+    let cases = [
+      ("00:00:01,000", Some(1_000)),
+      ("00:01.500", Some(1_500)),
+      ("0:00:01.12", Some(1_120)),
+      ("1:02:03.4", Some(3_723_400)),
+      ("01:02:03", Some(3_723_000)),
+      ("12", None),
+      ("00:aa:01.000", None),
+      ("00:-1:01.000", None),
+      ("00:00:01.5x", None),
+    ];
+    for (value, expected) in cases {
+      assert_eq!(parse_time(value), expected, "{value}");
+    }
+    assert_eq!(format_srt_time(3_723_400), "01:02:03,400");
+    assert_eq!(format_vtt_time(3_723_400), "01:02:03.400");
+    assert_eq!(format_ass_time(3_723_456), "1:02:03.46");
+    assert_eq!(format_srt_time(-5), "00:00:00,000");
+  }
+
+  #[test]
+  fn converts_between_formats() {
+    let srt = parse_subtitle(SRT.to_string(), SubtitleFormat::Srt);
+    assert_eq!(srt.cues.len(), 2);
+    // SRT -> VTT -> ASS -> SRT 之后时间与文本不变（时间都是整厘秒）。
+    let vtt = parse_subtitle(render_subtitle(&srt, SubtitleFormat::Vtt), SubtitleFormat::Vtt);
+    let ass = parse_subtitle(render_subtitle(&vtt, SubtitleFormat::Ass), SubtitleFormat::Ass);
+    let back = parse_subtitle(render_subtitle(&ass, SubtitleFormat::Srt), SubtitleFormat::Srt);
+    for parsed in [&vtt, &ass, &back] {
+      assert_eq!(parsed.cues.len(), srt.cues.len());
+      for (cue, original) in parsed.cues.iter().zip(&srt.cues) {
+        assert_eq!((cue.start, cue.end), (original.start, original.end));
+        assert_eq!(cue.text, original.text);
+      }
+    }
+    assert_eq!(render_subtitle(&back, SubtitleFormat::Srt), SRT);
+  }
+
+  #[test]
+  fn converts_ass_markup() {
+    let cases = [
+      ("{\\i1}斜体{\\i0}正常", Some("<i>斜体</i>正常")),
+      ("{\\b700}粗{\\r}体", Some("<b>粗</b>体")),
+      ("第一行\\N第二行\\h空格", Some("第一行\n第二行 空格")),
+      ("{\\pos(10,10)\\fnArial}文字", Some("文字")),
+      ("{\\p1}m 0 0 l 10 10{\\p0}", None),
+      ("{\\i1}{\\i0}", None),
+    ];
+    for (text, expected) in cases {
+      assert_eq!(ass_text(text).as_deref(), expected, "{text}");
+    }
+  }
+
+  #[test]
+  fn detects_legacy_encodings() {
+    let cases = [
+      (GB18030, "1\n00:00:01,000 --> 00:00:02,000\n这是一个中文字幕，我们在看动画。\n"),
+      (BIG5, "1\n00:00:01,000 --> 00:00:02,000\n這是一個中文字幕，我們在看動畫。\n"),
+    ];
+    for (encoding, text) in cases {
+      let (bytes, _, _) = encoding.encode(text);
+      let (detected, _) = detect_encoding(&bytes);
+      assert_eq!(detected, encoding);
+      assert_eq!(decode_subtitle(&bytes), text);
+    }
+    let (detected, confidence) = detect_encoding(cases[0].1.as_bytes());
+    assert_eq!((detected, confidence), (UTF_8, 1.0));
+  }
+
+  #[test]
+  fn shifts_and_scales_times() {
+    let shift = TimeShift {
+      offset: -1_500,
+      scale: 2.0,
+    };
+    let output = shift_blocks(SRT, false, shift);
+    assert_eq!(output.dropped, 0);
+    assert_eq!(output.cues[0].1.start, 500);
+    assert_eq!(output.cues[0].1.end, 3_500);
+    assert_eq!(output.cues[1].1.start, 123_180);
+    // 结束时间不晚于 0 的条目整条删掉，其余条目重新编号。
+    let shift = TimeShift {
+      offset: -3_000,
+      scale: 1.0,
+    };
+    let output = shift_blocks(SRT, false, shift);
+    assert_eq!(output.dropped, 1);
+    assert!(output.content.starts_with("1\n00:00:59,340 --> 00:01:01,000\n"));
+  }
+}
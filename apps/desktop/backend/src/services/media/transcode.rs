@@ -0,0 +1,696 @@
+/*
+  视频与音频转码（使用内置的 ffmpeg）：
+  - 内置几种常用预设（x264 slow CRF 18、x265 10bit、SVT-AV1、手机 1080p），也可以完全自定义编码参数；
+  - 转码作为媒体作业在后台执行，与混流共用作业队列和并发上限，进度解析自 ffmpeg -progress 的输出，
+    总时长事先用 ffprobe 读取（读不到时只报告开始与结束）；
+  - 除手机预设外，输出保留全部音频、字幕与附件（字体）并直接复制，只重新编码第一条视频轨道
+    （封面图片不算视频轨道）；手机预设输出 MP4，只保留第一条音轨并转成立体声 AAC；
+  - 音轨可以单独转成 AAC / Opus（例如把 TrueHD、FLAC 无损音轨转小），可选缩混为立体声，
+    输出只含这一条音轨的 mka；混流时也可以按轨道指定转码，转码结果替换原音轨（见 mod.rs 的 transcode_inputs）；
+  - 转码视频时可以烧录硬字幕（给不能渲染 ASS 软字幕的设备）：外挂 ASS 用 ass 滤镜，SRT 与输入文件内的字幕用
+    subtitles 滤镜（内封字幕会带上 MKV 里的字体附件）；libass 只读一个字体目录且不查找子目录，
+    因此把字体目录里用到的字体整理到作业的临时目录后再作为 fontsdir 传入。
+*/
+
+use encoding_rs::{UTF_16BE, UTF_16LE, UTF_8};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use super::fonts::{collect_font_files, scan};
+use super::jobs::{add_cleanup, create_job, run_ffmpeg_with_progress, spawn_job, JobStep};
+use super::subtitle::detect_encoding;
+use super::{create_temp_dir, resolve_tool_path};
+use crate::services::error::AppError;
+use crate::services::limits::{acquire, LimitKind};
+use crate::services::paths::ensure_path_allowed;
+use crate::services::power::keep_awake;
+
+// 音频码率范围（kbps）与省略时的默认值。
+const MIN_AUDIO_BITRATE: u32 = 32;
+const MAX_AUDIO_BITRATE: u32 = 1024;
+const DEFAULT_AAC_BITRATE: u32 = 256;
+const DEFAULT_OPUS_BITRATE: u32 = 192;
+
+// 保留全部音频、字幕与附件并直接复制，只重新编码第一条视频轨道（0:V 不含封面图片）；
+// 烧录硬字幕时不再保留字幕与附件（字体）。
+const KEEP_STREAMS_ARGS: &[&str] = &["-map", "0:V:0", "-map", "0:a?"];
+const KEEP_SUBTITLE_ARGS: &[&str] = &["-map", "0:s?", "-map", "0:t?"];
+const COPY_ARGS: &[&str] = &["-c", "copy"];
+
+// 可以烧录的外挂字幕格式。
+const HARDSUB_EXTENSIONS: [&str; 3] = ["ass", "ssa", "srt"];
+
+/// 转码预设。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TranscodePreset {
+  /// x264 slow，CRF 18（接近无损的 AVC）。
+  X264Slow,
+  /// x265 slow，CRF 20，10bit（Main10）。
+  X265TenBit,
+  /// SVT-AV1 preset 6，CRF 30，10bit。
+  Av1Svt,
+  /// 手机观看：AVC High@4.1，最高 1080p，立体声 AAC，MP4。
+  Phone1080p,
+  /// 使用 customArgs 作为全部编码参数。
+  Custom,
+}
+
+/// 预设说明（list_transcode_presets 返回）。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscodePresetInfo {
+  pub preset: TranscodePreset,
+  pub label: String,
+  /// 省略输出扩展名时使用的扩展名。
+  pub extension: String,
+  /// 预设的 ffmpeg 参数（不含输入与输出，自定义预设为空）。
+  pub args: Vec<String>,
+}
+
+impl TranscodePreset {
+  const ALL: [TranscodePreset; 5] = [
+    Self::X264Slow,
+    Self::X265TenBit,
+    Self::Av1Svt,
+    Self::Phone1080p,
+    Self::Custom,
+  ];
+
+  fn label(self) -> &'static str {
+    match self {
+      Self::X264Slow => "x264 slow CRF 18",
+      Self::X265TenBit => "x265 10bit CRF 20",
+      Self::Av1Svt => "SVT-AV1 10bit CRF 30",
+      Self::Phone1080p => "手机 1080p（MP4）",
+      Self::Custom => "自定义参数",
+    }
+  }
+
+  fn extension(self) -> &'static str {
+    match self {
+      Self::Phone1080p => "mp4",
+      _ => "mkv",
+    }
+  }
+
+  // 预设的 ffmpeg 参数（不含输入与输出）；keep_subtitles 为否时不保留字幕与附件。
+  fn args(self, keep_subtitles: bool) -> Vec<String> {
+    let encode: &[&str] = match self {
+      Self::X264Slow => &[
+        "-c:v:0", "libx264", "-preset", "slow", "-crf", "18", "-pix_fmt", "yuv420p",
+      ],
+      Self::X265TenBit => &[
+        "-c:v:0",
+        "libx265",
+        "-preset",
+        "slow",
+        "-crf",
+        "20",
+        "-pix_fmt",
+        "yuv420p10le",
+        "-profile:v",
+        "main10",
+      ],
+      Self::Av1Svt => &[
+        "-c:v:0",
+        "libsvtav1",
+        "-preset",
+        "6",
+        "-crf",
+        "30",
+        "-pix_fmt",
+        "yuv420p10le",
+      ],
+      Self::Phone1080p => &[
+        "-map",
+        "0:V:0",
+        "-map",
+        "0:a:0?",
+        "-vf",
+        "scale=-2:'min(1080,ih)'",
+        "-c:v",
+        "libx264",
+        "-preset",
+        "medium",
+        "-crf",
+        "23",
+        "-profile:v",
+        "high",
+        "-level",
+        "4.1",
+        "-pix_fmt",
+        "yuv420p",
+        "-c:a",
+        "aac",
+        "-b:a",
+        "160k",
+        "-ac",
+        "2",
+        "-movflags",
+        "+faststart",
+      ],
+      Self::Custom => &[],
+    };
+    let keep: Vec<&str> = match self {
+      Self::Phone1080p | Self::Custom => Vec::new(),
+      _ if keep_subtitles => [KEEP_STREAMS_ARGS, KEEP_SUBTITLE_ARGS, COPY_ARGS].concat(),
+      _ => [KEEP_STREAMS_ARGS, COPY_ARGS].concat(),
+    };
+    keep.iter().chain(encode).map(|arg| arg.to_string()).collect()
+  }
+}
+
+/// 硬字幕选项（transcode_video 的 hardsub）。
+#[derive(Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HardsubOptions {
+  /// 外挂字幕文件（ASS / SSA / SRT）；省略时烧录输入文件内的字幕轨道。
+  pub subtitle: Option<String>,
+  /// 输入文件内的第几条字幕轨道（从 0 开始，只计字幕轨道），省略时为第一条；指定了 subtitle 时忽略。
+  pub subtitle_index: Option<u32>,
+  /// 字体目录（包括子目录，可以多个）；省略时只使用系统字体与输入文件里的字体附件。
+  #[serde(default)]
+  pub font_dirs: Vec<String>,
+}
+
+// 校验后的硬字幕来源：外挂字幕文件或输入文件内的第几条字幕轨道。
+enum HardsubSource {
+  File(PathBuf),
+  Stream(u32),
+}
+
+// 校验后的硬字幕选项。
+struct Hardsub {
+  source: HardsubSource,
+  font_dirs: Vec<PathBuf>,
+}
+
+impl Hardsub {
+  // 外挂的 ASS / SSA 字幕（只带上实际用到的字体）。
+  fn ass_file(&self) -> Option<&Path> {
+    match &self.source {
+      HardsubSource::File(path) if is_ass(path) => Some(path),
+      _ => None,
+    }
+  }
+}
+
+fn is_ass(path: &Path) -> bool {
+  path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .is_some_and(|ext| ext.eq_ignore_ascii_case("ass") || ext.eq_ignore_ascii_case("ssa"))
+}
+
+// 转义 ffmpeg 滤镜的参数值：先按选项值转义 \ ' :，再按滤镜图转义 \ ' [ ] , ;
+// （两层转义，见 ffmpeg-filters 文档的 Notes on filtergraph escaping），Windows 路径里的盘符与反斜杠也能原样传入。
+fn escape_filter_value(value: &str) -> String {
+  let mut option = String::new();
+  for c in value.chars() {
+    if matches!(c, '\\' | '\'' | ':') {
+      option.push('\\');
+    }
+    option.push(c);
+  }
+  let mut graph = String::new();
+  for c in option.chars() {
+    if matches!(c, '\\' | '\'' | '[' | ']' | ',' | ';') {
+      graph.push('\\');
+    }
+    graph.push(c);
+  }
+  graph
+}
+
+// 生成烧录字幕的滤镜：UTF-8 的外挂 ASS 直接交给 libass（ass 滤镜），其它字幕经 ffmpeg 解码（subtitles 滤镜）；
+// 外挂字幕的编码自动识别（见 subtitle.rs），GBK / Big5 / Shift-JIS 字幕通过 charenc 指定。
+fn hardsub_filter(input: &Path, source: &HardsubSource, fonts_dir: Option<&Path>) -> Result<String, AppError> {
+  let mut filter = match source {
+    HardsubSource::File(path) => {
+      let bytes = fs::read(path).map_err(|e| AppError::io(format!("读取字幕 {} 失败: {e}", path.display())))?;
+      let (encoding, _) = detect_encoding(&bytes);
+      let file = escape_filter_value(&path.to_string_lossy());
+      if encoding == UTF_8 && is_ass(path) {
+        format!("ass=filename={file}")
+      } else if encoding == UTF_8 || encoding == UTF_16LE || encoding == UTF_16BE {
+        format!("subtitles=filename={file}")
+      } else {
+        format!("subtitles=filename={file}:charenc={}", encoding.name())
+      }
+    }
+    HardsubSource::Stream(index) => format!(
+      "subtitles=filename={}:si={index}",
+      escape_filter_value(&input.to_string_lossy())
+    ),
+  };
+  if let Some(dir) = fonts_dir {
+    filter.push_str(&format!(":fontsdir={}", escape_filter_value(&dir.to_string_lossy())));
+  }
+  Ok(filter)
+}
+
+// 把滤镜加在视频滤镜链的最前面（字幕按原始分辨率渲染后再缩放）；参数里已有 -vf 时以最后一个为准并与之合并。
+fn add_video_filter(args: &mut Vec<String>, filter: &str) {
+  let position = args
+    .iter()
+    .rposition(|arg| matches!(arg.as_str(), "-vf" | "-filter:v" | "-filter:v:0"));
+  match position.and_then(|index| args.get_mut(index + 1)) {
+    Some(value) => *value = format!("{filter},{value}"),
+    None => args.extend(["-vf".to_string(), filter.to_string()]),
+  }
+}
+
+// 把字体目录（包括子目录）里的字体链接到 target：ASS 字幕只带上实际用到的字体，其它字幕带上全部字体。
+// 优先硬链接，失败（例如跨分区）时复制；文件名加序号，避免不同目录里的同名字体互相覆盖。返回整理的字体数量。
+fn prepare_fonts_dir(ass_file: Option<&Path>, font_dirs: &[PathBuf], target: &Path) -> Result<usize, AppError> {
+  let files: Vec<PathBuf> = match ass_file {
+    Some(ass_file) => scan(ass_file, font_dirs)?
+      .font_files
+      .into_iter()
+      .map(PathBuf::from)
+      .collect(),
+    None => {
+      let mut files = Vec::new();
+      for dir in font_dirs {
+        collect_font_files(dir, &mut files);
+      }
+      files.sort();
+      files.dedup();
+      files
+    }
+  };
+  fs::create_dir_all(target).map_err(|e| AppError::io(format!("创建字体目录失败: {e}")))?;
+  for (index, file) in files.iter().enumerate() {
+    let name = file
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+    let link = target.join(format!("{index:03}-{name}"));
+    if fs::hard_link(file, &link).is_err() {
+      fs::copy(file, &link).map_err(|e| AppError::io(format!("复制字体 {} 失败: {e}", file.display())))?;
+    }
+  }
+  Ok(files.len())
+}
+
+/// 音频编码。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioCodec {
+  Aac,
+  Opus,
+}
+
+/// 音轨转码选项（transcode_audio 与混流的 trackOptions.transcode 共用）。
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioTranscode {
+  pub codec: AudioCodec,
+  /// 码率（kbps，32 ~ 1024）；省略时 AAC 为 256，Opus 为 192。
+  pub bitrate: Option<u32>,
+  /// 是否缩混为立体声（5.1 等多声道转成 2 声道）。
+  #[serde(default)]
+  pub downmix: bool,
+}
+
+impl AudioTranscode {
+  pub(crate) fn validate(&self) -> Result<(), String> {
+    match self.bitrate {
+      Some(bitrate) if !(MIN_AUDIO_BITRATE..=MAX_AUDIO_BITRATE).contains(&bitrate) => Err(format!(
+        "音频码率必须在 {MIN_AUDIO_BITRATE} ~ {MAX_AUDIO_BITRATE} kbps 之间"
+      )),
+      _ => Ok(()),
+    }
+  }
+
+  // 只选取 track_id 这一条音轨并转码的 ffmpeg 参数（不含输入与输出）。
+  fn args(&self, track_id: &str) -> Vec<String> {
+    let (encoder, default_bitrate) = match self.codec {
+      AudioCodec::Aac => ("aac", DEFAULT_AAC_BITRATE),
+      AudioCodec::Opus => ("libopus", DEFAULT_OPUS_BITRATE),
+    };
+    let bitrate = self.bitrate.unwrap_or(default_bitrate);
+    let mut args: Vec<String> = vec![
+      "-map".to_string(),
+      format!("0:{track_id}"),
+      "-vn".to_string(),
+      "-sn".to_string(),
+      "-dn".to_string(),
+      "-c:a".to_string(),
+      encoder.to_string(),
+      "-b:a".to_string(),
+      format!("{bitrate}k"),
+    ];
+    if self.downmix {
+      args.extend(["-ac".to_string(), "2".to_string()]);
+    } else if self.codec == AudioCodec::Opus {
+      // libopus 不接受 5.1(side) 这类声道布局（TrueHD 常见），先转成它支持的布局。
+      args.extend([
+        "-af".to_string(),
+        "aformat=channel_layouts=7.1|5.1|stereo|mono".to_string(),
+      ]);
+    }
+    args
+  }
+}
+
+// 校验轨道 ID（文件内的流序号，同 parse_media_tracks 返回的 trackId）。
+pub(crate) fn check_track_id(track_id: &str) -> Result<String, String> {
+  let track_id = track_id.trim();
+  if track_id.is_empty() || !track_id.chars().all(|c| c.is_ascii_digit()) {
+    return Err(format!("轨道 ID 无效: {track_id}"));
+  }
+  Ok(track_id.to_string())
+}
+
+// 把 input 里的一条音轨转码输出到 output（只含这一条音轨），step 报告进度；失败时删除不完整的输出。
+pub(crate) async fn transcode_audio_track(
+  ffmpeg_path: &Path,
+  ffprobe_path: Option<&Path>,
+  input: &Path,
+  track_id: &str,
+  options: &AudioTranscode,
+  output: &Path,
+  step: &JobStep<'_>,
+) -> Result<(), AppError> {
+  let duration = match ffprobe_path {
+    Some(path) => probe_duration(path, input).await,
+    None => None,
+  };
+  let mut args: Vec<String> = vec!["-y".to_string(), "-i".to_string(), input.to_string_lossy().to_string()];
+  args.extend(options.args(track_id));
+  args.push(output.to_string_lossy().to_string());
+  let (success, code, errors) = run_ffmpeg_with_progress(ffmpeg_path, &args, duration, step).await?;
+  if !success {
+    let _ = fs::remove_file(output);
+    return Err(AppError::tool_failed(
+      "ffmpeg",
+      format!(
+        "转码 {} 的轨道 {track_id} 失败 (code {code:?}): {errors}",
+        input.display()
+      ),
+    ));
+  }
+  Ok(())
+}
+
+// 用 ffprobe 读取总时长（秒），读不到时返回 None（进度只报告开始与结束）。
+pub(crate) async fn probe_duration(ffprobe_path: &Path, input: &Path) -> Option<f64> {
+  let output = Command::new(ffprobe_path)
+    .args([
+      "-v",
+      "error",
+      "-show_entries",
+      "format=duration",
+      "-of",
+      "default=noprint_wrappers=1:nokey=1",
+    ])
+    .arg(input)
+    .output()
+    .await
+    .ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  String::from_utf8_lossy(&output.stdout)
+    .trim()
+    .parse()
+    .ok()
+    .filter(|duration: &f64| duration.is_finite() && *duration > 0.0)
+}
+
+// 转码作业本体：等待并发许可后读取时长并运行 ffmpeg。烧录硬字幕并指定了字体目录时，
+// 先把字体整理到作业的临时目录，转码结束后删除。
+#[allow(clippy::too_many_arguments)]
+async fn run_transcode(
+  app: &tauri::AppHandle,
+  job_id: u64,
+  input: PathBuf,
+  output: PathBuf,
+  mut args: Vec<String>,
+  hardsub: Option<Hardsub>,
+  ffmpeg_path: PathBuf,
+  ffprobe_path: Option<PathBuf>,
+) -> Result<(), AppError> {
+  let _job_permit = acquire(LimitKind::MediaJob).await?;
+  // 转码耗时很长，期间阻止系统睡眠。
+  let _awake = keep_awake("正在转码视频");
+
+  // 中途取消或失败时输出文件不完整，一并删除。
+  add_cleanup(job_id, &output);
+  let temp_root = match &hardsub {
+    Some(hardsub) if !hardsub.font_dirs.is_empty() => Some(create_temp_dir(app, job_id)?),
+    _ => None,
+  };
+  let result = async {
+    if let Some(hardsub) = &hardsub {
+      let fonts_dir = match &temp_root {
+        Some(temp_root) => {
+          let fonts_dir = temp_root.join("fonts");
+          let ass_file = hardsub.ass_file().map(Path::to_path_buf);
+          let font_dirs = hardsub.font_dirs.clone();
+          let target = fonts_dir.clone();
+          tauri::async_runtime::spawn_blocking(move || prepare_fonts_dir(ass_file.as_deref(), &font_dirs, &target))
+            .await
+            .map_err(|e| AppError::internal(format!("整理字体失败: {e}")))??;
+          Some(fonts_dir)
+        }
+        None => None,
+      };
+      add_video_filter(
+        &mut args,
+        &hardsub_filter(&input, &hardsub.source, fonts_dir.as_deref())?,
+      );
+    }
+    let duration = match &ffprobe_path {
+      Some(path) => probe_duration(path, &input).await,
+      None => None,
+    };
+    let mut full_args: Vec<String> = vec!["-y".to_string(), "-i".to_string(), input.to_string_lossy().to_string()];
+    full_args.extend(args);
+    full_args.push(output.to_string_lossy().to_string());
+    let step = JobStep {
+      app,
+      job_id,
+      index: 0,
+      count: 1,
+      phase: "转码",
+    };
+    let (success, code, errors) = run_ffmpeg_with_progress(&ffmpeg_path, &full_args, duration, &step).await?;
+    if !success {
+      let _ = fs::remove_file(&output);
+      return Err(AppError::tool_failed(
+        "ffmpeg",
+        format!("ffmpeg 执行失败 (code {code:?}): {errors}"),
+      ));
+    }
+    Ok(())
+  }
+  .await;
+  if let Some(temp_root) = temp_root {
+    let _ = fs::remove_dir_all(&temp_root);
+  }
+  result
+}
+
+// 返回可用的转码预设及其参数。
+#[tauri::command]
+pub fn list_transcode_presets() -> Vec<TranscodePresetInfo> {
+  TranscodePreset::ALL
+    .into_iter()
+    .map(|preset| TranscodePresetInfo {
+      preset,
+      label: preset.label().to_string(),
+      extension: preset.extension().to_string(),
+      args: preset.args(true),
+    })
+    .collect()
+}
+
+// 校验硬字幕选项：外挂字幕必须是存在的 ASS / SSA / SRT 文件，字体目录必须存在。
+fn check_hardsub(app: &tauri::AppHandle, options: HardsubOptions) -> Result<Hardsub, AppError> {
+  let source = match options.subtitle.filter(|subtitle| !subtitle.trim().is_empty()) {
+    Some(subtitle) => {
+      let path = ensure_path_allowed(app, &subtitle).map_err(AppError::invalid_input)?;
+      if !path.is_file() {
+        return Err(AppError::invalid_input(format!("字幕文件不存在: {}", path.display())));
+      }
+      let supported = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| HARDSUB_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+      if !supported {
+        return Err(AppError::invalid_input(format!(
+          "只支持烧录 ASS / SSA / SRT 字幕: {}",
+          path.display()
+        )));
+      }
+      HardsubSource::File(path)
+    }
+    None => HardsubSource::Stream(options.subtitle_index.unwrap_or(0)),
+  };
+  let mut font_dirs = Vec::new();
+  for dir in options.font_dirs.iter().filter(|dir| !dir.trim().is_empty()) {
+    let dir = ensure_path_allowed(app, dir).map_err(AppError::invalid_input)?;
+    if !dir.is_dir() {
+      return Err(AppError::invalid_input(format!("字体目录不存在: {}", dir.display())));
+    }
+    font_dirs.push(dir);
+  }
+  Ok(Hardsub { source, font_dirs })
+}
+
+// 按预设转码视频：校验输入后创建媒体作业并立即返回作业 ID，转码在后台执行，
+// 进度通过 media://mux-progress 事件推送。自定义预设使用 customArgs 作为全部编码参数，
+// 其它预设把 customArgs 追加在预设参数之后（同一选项以后出现的为准）。
+// 指定 hardsub 时把字幕烧进画面，输出不再保留字幕轨道与字体附件。
+#[tauri::command]
+pub async fn transcode_video(
+  app: tauri::AppHandle,
+  input: String,
+  preset: TranscodePreset,
+  output: String,
+  custom_args: Option<Vec<String>>,
+  hardsub: Option<HardsubOptions>,
+) -> Result<u64, AppError> {
+  let input = ensure_path_allowed(&app, &input).map_err(AppError::invalid_input)?;
+  if !input.is_file() {
+    return Err(AppError::invalid_input(format!("输入文件不存在: {}", input.display())));
+  }
+  let mut output = PathBuf::from(output.trim());
+  if output.as_os_str().is_empty() {
+    return Err(AppError::invalid_input("未指定输出文件"));
+  }
+  if output.extension().is_none() {
+    output.set_extension(preset.extension());
+  }
+  let output = ensure_path_allowed(&app, &output.to_string_lossy()).map_err(AppError::invalid_input)?;
+  if output == input {
+    return Err(AppError::invalid_input("输出文件不能与输入文件相同"));
+  }
+
+  // 输入与输出由命令决定，自定义参数里不能再指定输入。
+  let custom_args: Vec<String> = custom_args
+    .unwrap_or_default()
+    .into_iter()
+    .map(|arg| arg.trim().to_string())
+    .filter(|arg| !arg.is_empty())
+    .collect();
+  if custom_args.iter().any(|arg| arg == "-i") {
+    return Err(AppError::invalid_input(
+      "自定义参数不能包含 -i（输入文件由 input 指定）",
+    ));
+  }
+  if preset == TranscodePreset::Custom && custom_args.is_empty() {
+    return Err(AppError::invalid_input("自定义预设需要提供编码参数"));
+  }
+  let hardsub = hardsub.map(|options| check_hardsub(&app, options)).transpose()?;
+  let mut args = preset.args(hardsub.is_none());
+  args.extend(custom_args);
+
+  if let Some(parent) = output.parent() {
+    fs::create_dir_all(parent).map_err(|e| AppError::io(format!("创建输出目录失败: {e}")))?;
+  }
+  let ffmpeg_path = resolve_tool_path(&app, "ffmpeg")?;
+  // 没有 ffprobe 时仍可转码，只是没有百分比进度。
+  let ffprobe_path = resolve_tool_path(&app, "ffprobe").ok();
+
+  let job_id = create_job(&app, "transcode", &output.to_string_lossy());
+  let task_app = app.clone();
+  spawn_job(&app, job_id, async move {
+    run_transcode(
+      &task_app,
+      job_id,
+      input,
+      output,
+      args,
+      hardsub,
+      ffmpeg_path,
+      ffprobe_path,
+    )
+    .await
+  });
+  Ok(job_id)
+}
+
+// 把一条音轨转成 AAC / Opus（可选缩混为立体声），输出只含这一条音轨的 mka；
+// 校验输入后创建媒体作业并立即返回作业 ID。output 省略时写在输入旁边（<文件名>.track<轨道 ID>.mka）。
+#[tauri::command]
+pub async fn transcode_audio(
+  app: tauri::AppHandle,
+  input: String,
+  track_id: String,
+  codec: AudioCodec,
+  bitrate: Option<u32>,
+  downmix: Option<bool>,
+  output: Option<String>,
+) -> Result<u64, AppError> {
+  let input = ensure_path_allowed(&app, &input).map_err(AppError::invalid_input)?;
+  if !input.is_file() {
+    return Err(AppError::invalid_input(format!("输入文件不存在: {}", input.display())));
+  }
+  let track_id = check_track_id(&track_id).map_err(AppError::invalid_input)?;
+  let options = AudioTranscode {
+    codec,
+    bitrate,
+    downmix: downmix.unwrap_or(false),
+  };
+  options.validate().map_err(AppError::invalid_input)?;
+  let output = match output
+    .map(|output| output.trim().to_string())
+    .filter(|output| !output.is_empty())
+  {
+    Some(output) => {
+      let mut output = PathBuf::from(output);
+      if output.extension().is_none() {
+        output.set_extension("mka");
+      }
+      output
+    }
+    None => {
+      let stem = input
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+      input.with_file_name(format!("{stem}.track{track_id}.mka"))
+    }
+  };
+  let output = ensure_path_allowed(&app, &output.to_string_lossy()).map_err(AppError::invalid_input)?;
+  if output == input {
+    return Err(AppError::invalid_input("输出文件不能与输入文件相同"));
+  }
+  if let Some(parent) = output.parent() {
+    fs::create_dir_all(parent).map_err(|e| AppError::io(format!("创建输出目录失败: {e}")))?;
+  }
+  let ffmpeg_path = resolve_tool_path(&app, "ffmpeg")?;
+  let ffprobe_path = resolve_tool_path(&app, "ffprobe").ok();
+
+  let job_id = create_job(&app, "transcode", &output.to_string_lossy());
+  let task_app = app.clone();
+  spawn_job(&app, job_id, async move {
+    let _job_permit = acquire(LimitKind::MediaJob).await?;
+    let _awake = keep_awake("正在转码音频");
+    add_cleanup(job_id, &output);
+    let step = JobStep {
+      app: &task_app,
+      job_id,
+      index: 0,
+      count: 1,
+      phase: "转码音频",
+    };
+    transcode_audio_track(
+      &ffmpeg_path,
+      ffprobe_path.as_deref(),
+      &input,
+      &track_id,
+      &options,
+      &output,
+      &step,
+    )
+    .await
+  });
+  Ok(job_id)
+}
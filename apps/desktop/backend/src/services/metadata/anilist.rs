@@ -0,0 +1,204 @@
+/*
+  AniList 备用数据源（GraphQL）：
+  - 已有 ID 映射时按 AniList ID 查询；
+  - 没有映射时依次用各个标题搜索，命中且标题能对上才记录映射，
+    避免把搜索结果里“最接近但不是同一部”的作品当成同一条目。
+*/
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::services::bangumi::shared_client;
+use crate::services::limits::send_limited;
+
+use super::{
+  is_known_miss, mapped_id, normalize_title, remember_id, remember_miss, FallbackMetadata, MetadataProvider,
+};
+
+// AniList GraphQL 接口地址。
+const ANILIST_API: &str = "https://graphql.anilist.co";
+// 查询返回的字段（按 ID 与按标题搜索共用）。
+const MEDIA_FIELDS: &str = "id description(asHtml: false) averageScore \
+  startDate { year month day } coverImage { extraLarge large } title { romaji english native }";
+// 最多尝试搜索的标题数量。
+const MAX_SEARCH_TITLES: usize = 3;
+
+/// AniList 提供者。
+pub(crate) struct AniListProvider;
+
+#[derive(Deserialize)]
+struct GraphQlResponse {
+  data: Option<MediaData>,
+}
+
+#[derive(Deserialize)]
+struct MediaData {
+  #[serde(rename = "Media")]
+  media: Option<Media>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Media {
+  id: u64,
+  description: Option<String>,
+  average_score: Option<u32>,
+  start_date: Option<FuzzyDate>,
+  cover_image: Option<CoverImage>,
+  title: Option<MediaTitle>,
+}
+
+#[derive(Deserialize)]
+struct FuzzyDate {
+  year: Option<u32>,
+  month: Option<u32>,
+  day: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CoverImage {
+  extra_large: Option<String>,
+  large: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MediaTitle {
+  romaji: Option<String>,
+  english: Option<String>,
+  native: Option<String>,
+}
+
+impl Media {
+  // 所有非空标题。
+  fn titles(&self) -> Vec<&str> {
+    self
+      .title
+      .as_ref()
+      .map(|title| {
+        [&title.native, &title.romaji, &title.english]
+          .into_iter()
+          .filter_map(|value| value.as_deref())
+          .filter(|value| !value.trim().is_empty())
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
+  // 是否与给定标题之一对得上（规范化后相等，或一方包含另一方）。
+  fn matches(&self, titles: &[String]) -> bool {
+    let own: Vec<String> = self.titles().into_iter().map(normalize_title).collect();
+    titles
+      .iter()
+      .map(|title| normalize_title(title))
+      .filter(|title| !title.is_empty())
+      .any(|title| {
+        own
+          .iter()
+          .filter(|value| !value.is_empty())
+          .any(|value| *value == title || value.contains(&title) || title.contains(value.as_str()))
+      })
+  }
+}
+
+// AniList 的简介里可能带有 <br> 等 HTML 标签，这里去掉标签并整理空行。
+fn strip_html(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  let mut in_tag = false;
+  for c in text.chars() {
+    match c {
+      '<' => in_tag = true,
+      '>' if in_tag => in_tag = false,
+      _ if !in_tag => out.push(c),
+      _ => {}
+    }
+  }
+  out
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+// 把 AniList 的日期转换成 YYYY-MM-DD（缺少的部分省略）。
+fn format_date(date: &FuzzyDate) -> Option<String> {
+  let year = date.year?;
+  Some(match (date.month, date.day) {
+    (Some(month), Some(day)) => format!("{year:04}-{month:02}-{day:02}"),
+    (Some(month), None) => format!("{year:04}-{month:02}"),
+    _ => format!("{year:04}"),
+  })
+}
+
+// 执行一次 Media 查询；条目不存在（404）时返回 Ok(None)。
+async fn query_media(argument: &str, variables: serde_json::Value) -> Result<Option<Media>, String> {
+  let (declaration, filter) = match argument {
+    "id" => ("$id: Int", "id: $id"),
+    _ => ("$search: String", "search: $search"),
+  };
+  let query = format!("query ({declaration}) {{ Media({filter}, type: ANIME) {{ {MEDIA_FIELDS} }} }}");
+  let client = shared_client();
+  let request = client
+    .post(ANILIST_API)
+    .json(&json!({ "query": query, "variables": variables }));
+  let response = send_limited(request).await?;
+  if response.status() == reqwest::StatusCode::NOT_FOUND {
+    return Ok(None);
+  }
+  if !response.status().is_success() {
+    return Err(format!("AniList 请求失败: {}", response.status()));
+  }
+  let payload: GraphQlResponse = response.json().await.map_err(|e| e.to_string())?;
+  Ok(payload.data.and_then(|data| data.media))
+}
+
+// 把 AniList 条目转换成通用的补充元数据。
+fn to_metadata(media: Media) -> FallbackMetadata {
+  let title = media.titles().first().map(|value| value.to_string());
+  FallbackMetadata {
+    provider: "anilist",
+    title,
+    summary: media
+      .description
+      .as_deref()
+      .map(strip_html)
+      .filter(|value| !value.is_empty()),
+    image: media
+      .cover_image
+      .and_then(|cover| cover.extra_large.or(cover.large)),
+    date: media.start_date.as_ref().and_then(format_date),
+    rating: media.average_score.map(|score| score as f64 / 10.0),
+  }
+}
+
+impl MetadataProvider for AniListProvider {
+  fn name(&self) -> &'static str {
+    "anilist"
+  }
+
+  async fn lookup(&self, bangumi_id: u32, titles: &[String]) -> Result<Option<FallbackMetadata>, String> {
+    if let Some(anilist_id) = mapped_id(self.name(), bangumi_id) {
+      let media = query_media("id", json!({ "id": anilist_id })).await?;
+      return Ok(media.map(to_metadata));
+    }
+    if titles.is_empty() || is_known_miss(self.name(), bangumi_id) {
+      return Ok(None);
+    }
+
+    for title in titles
+      .iter()
+      .filter(|title| !title.trim().is_empty())
+      .take(MAX_SEARCH_TITLES)
+    {
+      let media = match query_media("search", json!({ "search": title.trim() })).await? {
+        Some(media) if media.matches(titles) => media,
+        _ => continue,
+      };
+      remember_id(self.name(), bangumi_id, media.id);
+      return Ok(Some(to_metadata(media)));
+    }
+    remember_miss(self.name(), bangumi_id);
+    Ok(None)
+  }
+}
@@ -0,0 +1,257 @@
+/*
+  Mikan Project（蜜柑计划）：
+  Mikan 按番剧整理各字幕组的发布，并为每个番剧/字幕组提供 RSS，适合直接做成订阅。
+  - 按标题（中文名、原名、别名）在 Mikan 搜索番剧；
+  - 番剧页面里有 Bangumi 条目链接，据此把 Mikan 番剧 ID 对应回 bgm.tv 条目 ID，
+    对上了才记录映射（ID 映射文件里的 "mikan"），之后直接打开番剧页；
+  - 番剧页面列出各字幕组及其 RSS 地址。
+  Mikan 没有公开 API，这里解析网页 HTML，页面结构变化时解析结果可能为空。
+*/
+
+use once_cell::sync::Lazy;
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::services::bangumi::commands::{get_subject_aliases, get_subject_brief};
+use crate::services::bangumi::shared_client;
+use crate::services::error::AppError;
+use crate::services::limits::send_limited;
+
+use super::{is_known_miss, mapped_id, remember_id, remember_miss};
+
+// Mikan 站点地址。
+const MIKAN_BASE: &str = "https://mikanani.me";
+// ID 映射里使用的提供者名称。
+const PROVIDER: &str = "mikan";
+// 最多尝试搜索的标题数量。
+const MAX_SEARCH_TITLES: usize = 3;
+// 每次搜索最多核对的结果数量（每个结果需要请求一次番剧页面）。
+const MAX_CANDIDATES: usize = 5;
+
+// 页面解析用到的选择器。
+static SEARCH_ITEM: Lazy<Selector> = Lazy::new(|| selector("ul.an-ul > li > a[href^='/Home/Bangumi/']"));
+static BANGUMI_TITLE: Lazy<Selector> = Lazy::new(|| selector("p.bangumi-title"));
+static BANGUMI_INFO_LINK: Lazy<Selector> = Lazy::new(|| selector("p.bangumi-info a"));
+static BANGUMI_COVER: Lazy<Selector> = Lazy::new(|| selector("div.bangumi-poster"));
+static SUBGROUP: Lazy<Selector> = Lazy::new(|| selector("div.subgroup-text"));
+static PUBLISH_GROUP_LINK: Lazy<Selector> = Lazy::new(|| selector("a[href^='/Home/PublishGroup/']"));
+static RSS_LINK: Lazy<Selector> = Lazy::new(|| selector("a.mikan-rss"));
+
+/// Mikan 番剧信息。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MikanBangumi {
+  /// Mikan 番剧 ID。
+  pub mikan_id: u64,
+  /// 对应的 Bangumi 条目 ID（页面没有 Bangumi 链接时为空）。
+  pub bangumi_id: Option<u32>,
+  pub title: String,
+  /// Mikan 番剧页面地址。
+  pub url: String,
+  pub cover: Option<String>,
+  /// 番剧的 RSS（包含所有字幕组的发布）。
+  pub rss_url: String,
+  /// 发布过该番剧的字幕组。
+  pub groups: Vec<MikanGroup>,
+}
+
+/// 字幕组及其 RSS。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MikanGroup {
+  /// Mikan 字幕组 ID。
+  pub id: u64,
+  pub name: String,
+  /// 只包含该字幕组发布的 RSS。
+  pub rss_url: String,
+}
+
+// 选择器都是常量，解析失败属于代码错误。
+fn selector(css: &str) -> Selector {
+  Selector::parse(css).expect("Mikan 选择器无效")
+}
+
+// 相对地址补全为绝对地址。
+fn absolute_url(href: &str) -> String {
+  url::Url::parse(MIKAN_BASE)
+    .and_then(|base| base.join(href))
+    .map(|url| url.to_string())
+    .unwrap_or_else(|_| href.to_string())
+}
+
+// 元素内的文本（去掉多余空白）。
+fn element_text(element: ElementRef<'_>) -> String {
+  element
+    .text()
+    .flat_map(str::split_whitespace)
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+// 请求 Mikan 页面并返回 HTML。
+async fn fetch_page(url: &str) -> Result<String, AppError> {
+  let response = send_limited(shared_client().get(url))
+    .await
+    .map_err(AppError::network)?;
+  if !response.status().is_success() {
+    return Err(AppError::api(
+      response.status().as_u16(),
+      format!("Mikan 请求失败: {}", response.status()),
+    ));
+  }
+  response
+    .text()
+    .await
+    .map_err(|e| AppError::network(format!("读取 Mikan 页面失败: {e}")))
+}
+
+// 解析搜索结果页，返回 Mikan 番剧 ID（按页面顺序）。
+fn parse_search_results(html: &str) -> Vec<u64> {
+  let document = Html::parse_document(html);
+  document
+    .select(&SEARCH_ITEM)
+    .filter_map(|link| link.value().attr("href")?.trim_start_matches("/Home/Bangumi/").parse().ok())
+    .collect()
+}
+
+// 从 Bangumi 链接（bgm.tv / bangumi.tv / chii.in 的 /subject/<id>）中取条目 ID。
+fn parse_subject_link(href: &str) -> Option<u32> {
+  let url = url::Url::parse(href).ok()?;
+  let host = url.host_str()?;
+  if !["bgm.tv", "bangumi.tv", "chii.in"].iter().any(|known| host.ends_with(known)) {
+    return None;
+  }
+  let mut segments = url.path_segments()?;
+  if segments.next()? != "subject" {
+    return None;
+  }
+  segments.next()?.parse().ok()
+}
+
+// 从 RSS 地址的查询参数里取字幕组 ID。
+fn subgroup_id(rss_url: &str) -> Option<u64> {
+  url::Url::parse(rss_url)
+    .ok()?
+    .query_pairs()
+    .find(|(key, _)| key.eq_ignore_ascii_case("subgroupid"))
+    .and_then(|(_, value)| value.parse().ok())
+}
+
+// 解析番剧页面：标题、封面、Bangumi 条目 ID 与字幕组列表。
+fn parse_bangumi_page(mikan_id: u64, html: &str) -> MikanBangumi {
+  let document = Html::parse_document(html);
+  let title = document
+    .select(&BANGUMI_TITLE)
+    .next()
+    .map(element_text)
+    .unwrap_or_default();
+  let bangumi_id = document
+    .select(&BANGUMI_INFO_LINK)
+    .filter_map(|link| link.value().attr("href"))
+    .find_map(parse_subject_link);
+  // 封面写在 style 的 background-image: url('...') 里。
+  let cover = document
+    .select(&BANGUMI_COVER)
+    .next()
+    .and_then(|poster| poster.value().attr("style"))
+    .and_then(|style| style.split_once("url(").map(|(_, rest)| rest))
+    .and_then(|rest| rest.split_once(')').map(|(inner, _)| inner))
+    .map(|inner| {
+      let path = inner.trim_matches(|c| c == '\'' || c == '"');
+      path.split('?').next().unwrap_or_default()
+    })
+    .filter(|path| !path.is_empty())
+    .map(absolute_url);
+
+  let mut seen = HashSet::new();
+  let groups = document
+    .select(&SUBGROUP)
+    .filter_map(|group| {
+      let rss_url = absolute_url(group.select(&RSS_LINK).next()?.value().attr("href")?);
+      let id = group
+        .value()
+        .attr("id")
+        .and_then(|id| id.parse().ok())
+        .or_else(|| subgroup_id(&rss_url))?;
+      // 联合发布的字幕组没有单独的链接，名称直接写在元素里。
+      let name = group
+        .select(&PUBLISH_GROUP_LINK)
+        .next()
+        .map(element_text)
+        .unwrap_or_else(|| element_text(group));
+      seen.insert(id).then_some(MikanGroup { id, name, rss_url })
+    })
+    .collect();
+
+  MikanBangumi {
+    mikan_id,
+    bangumi_id,
+    title,
+    url: format!("{MIKAN_BASE}/Home/Bangumi/{mikan_id}"),
+    cover,
+    rss_url: format!("{MIKAN_BASE}/RSS/Bangumi?bangumiId={mikan_id}"),
+    groups,
+  }
+}
+
+// 获取 Mikan 番剧页面信息。
+async fn fetch_bangumi(mikan_id: u64) -> Result<MikanBangumi, AppError> {
+  let html = fetch_page(&format!("{MIKAN_BASE}/Home/Bangumi/{mikan_id}")).await?;
+  Ok(parse_bangumi_page(mikan_id, &html))
+}
+
+// 用标题在 Mikan 搜索番剧，返回 Mikan 番剧 ID。
+async fn search_titles(title: &str) -> Result<Vec<u64>, AppError> {
+  let mut url = url::Url::parse(&format!("{MIKAN_BASE}/Home/Search")).map_err(|e| AppError::internal(e.to_string()))?;
+  url.query_pairs_mut().append_pair("searchstr", title);
+  let html = fetch_page(url.as_str()).await?;
+  Ok(parse_search_results(&html))
+}
+
+// 按 Bangumi 条目查找 Mikan 番剧：已有映射直接打开番剧页，
+// 否则依次用中文名、原名、别名搜索，逐个核对搜索结果页面里的 Bangumi 链接。
+async fn resolve_bangumi(subject_id: u32) -> Result<Option<MikanBangumi>, AppError> {
+  if let Some(mikan_id) = mapped_id(PROVIDER, subject_id) {
+    return fetch_bangumi(mikan_id).await.map(Some);
+  }
+  if is_known_miss(PROVIDER, subject_id) {
+    return Ok(None);
+  }
+
+  let brief = get_subject_brief(subject_id, None).await?;
+  let mut titles = vec![brief.name_cn, brief.name];
+  // 别名只是补充，获取失败不影响用主标题搜索。
+  if let Ok(aliases) = get_subject_aliases(subject_id, None).await {
+    titles.extend(aliases.aliases);
+  }
+  titles.retain(|title| !title.trim().is_empty());
+  titles.dedup();
+
+  let mut checked = HashSet::new();
+  for title in titles.iter().take(MAX_SEARCH_TITLES) {
+    let candidates = search_titles(title).await?;
+    for mikan_id in candidates.into_iter().take(MAX_CANDIDATES) {
+      if !checked.insert(mikan_id) {
+        continue;
+      }
+      let bangumi = fetch_bangumi(mikan_id).await?;
+      if let Some(bangumi_id) = bangumi.bangumi_id {
+        // 顺带记录核对过的其它番剧，之后查询那些条目时可以直接使用。
+        remember_id(PROVIDER, bangumi_id, mikan_id);
+        if bangumi_id == subject_id {
+          return Ok(Some(bangumi));
+        }
+      }
+    }
+  }
+  remember_miss(PROVIDER, subject_id);
+  Ok(None)
+}
+
+// 查找 Bangumi 条目在 Mikan 上对应的番剧及各字幕组的 RSS，找不到时返回 null。
+// 前端据此一键创建 RSS 订阅（add_rss_subscription）。
+#[tauri::command]
+pub async fn search_mikan(subject_id: u32) -> Result<Option<MikanBangumi>, AppError> {
+  resolve_bangumi(subject_id).await
+}
@@ -0,0 +1,139 @@
+/*
+  外部元数据模块：
+  Bangumi 不可用，或条目缺少简介/封面时，从其它站点补全数据。
+
+  - MetadataProvider：统一的提供者接口（按 Bangumi ID 或标题查找）；
+  - anilist：AniList GraphQL 实现，作为默认的备用数据源；
+  - tmdb：TMDB 图片（背景图/海报/标志），用于丰富详情页；
+  - offline_db：anime-offline-database，把 Bangumi ID 转换成 AniList / MAL / AniDB ID；
+  - mikan：蜜柑计划，按条目查找番剧与各字幕组的 RSS（用于创建订阅）；
+  - ID 映射：第一次通过标题搜索命中后，把 Bangumi ID → 外部 ID 保存到数据目录，
+    之后直接按 ID 查询（Bangumi 完全不可用、拿不到标题时也能使用）。
+*/
+
+mod anilist;
+pub mod mikan;
+pub mod offline_db;
+pub(crate) mod tmdb;
+
+use once_cell::sync::{Lazy, OnceCell};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+pub(crate) use anilist::AniListProvider;
+
+use crate::services::diagnostics::app_log;
+
+// ID 映射文件名（位于元数据目录下）。
+const ID_MAP_FILE: &str = "id-map.json";
+
+// 元数据目录（应用数据目录 / cache / metadata），启动时设置。
+static METADATA_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+// ID 映射：提供者名称 -> (Bangumi ID -> 外部 ID)。首次使用时从文件加载。
+static ID_MAP: Lazy<Mutex<HashMap<String, HashMap<u32, u64>>>> = Lazy::new(|| Mutex::new(load_id_map()));
+
+// 本次运行中搜索过但没有匹配结果的条目（提供者名称, Bangumi ID），避免反复搜索。
+static MISSES: Lazy<Mutex<HashSet<(String, u32)>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 备用数据源返回的补充元数据。
+pub(crate) struct FallbackMetadata {
+  /// 提供者名称（如 "anilist"）。
+  pub provider: &'static str,
+  pub title: Option<String>,
+  pub summary: Option<String>,
+  pub image: Option<String>,
+  /// 首播日期（YYYY-MM-DD，可能缺少月日）。
+  pub date: Option<String>,
+  /// 评分（已换算成 10 分制）。
+  pub rating: Option<f64>,
+}
+
+// 元数据提供者：已建立映射时按 ID 查询，否则按标题搜索并记录映射。
+// 找不到条目时返回 Ok(None)，网络或解析错误返回 Err。
+pub(crate) trait MetadataProvider {
+  fn name(&self) -> &'static str;
+  async fn lookup(&self, bangumi_id: u32, titles: &[String]) -> Result<Option<FallbackMetadata>, String>;
+}
+
+// 记录元数据目录（由 main.rs 在启动时调用）。
+pub(crate) fn init_metadata_dir(dir: PathBuf) {
+  let _ = METADATA_DIR.set(dir);
+}
+
+// 元数据目录（未初始化时为 None）。
+pub(crate) fn metadata_dir() -> Option<&'static PathBuf> {
+  METADATA_DIR.get()
+}
+
+// 从文件读取 ID 映射；文件不存在或损坏时从空映射开始。
+fn load_id_map() -> HashMap<String, HashMap<u32, u64>> {
+  METADATA_DIR
+    .get()
+    .and_then(|dir| fs::read_to_string(dir.join(ID_MAP_FILE)).ok())
+    .and_then(|content| serde_json::from_str(&content).ok())
+    .unwrap_or_default()
+}
+
+// 查询已记录的外部 ID。
+pub(crate) fn mapped_id(provider: &str, bangumi_id: u32) -> Option<u64> {
+  let map = ID_MAP.lock().unwrap_or_else(|e| e.into_inner());
+  map.get(provider).and_then(|ids| ids.get(&bangumi_id).copied())
+}
+
+// 记录外部 ID 并写回文件；写入失败只影响下次是否需要重新搜索，所以只打印日志。
+pub(crate) fn remember_id(provider: &str, bangumi_id: u32, external_id: u64) {
+  let mut map = ID_MAP.lock().unwrap_or_else(|e| e.into_inner());
+  map
+    .entry(provider.to_string())
+    .or_default()
+    .insert(bangumi_id, external_id);
+  let dir = match METADATA_DIR.get() {
+    Some(dir) => dir,
+    None => return,
+  };
+  let result = fs::create_dir_all(dir).and_then(|_| {
+    fs::write(
+      dir.join(ID_MAP_FILE),
+      serde_json::to_vec_pretty(&*map).unwrap_or_default(),
+    )
+  });
+  if let Err(err) = result {
+    app_log!("写入元数据 ID 映射失败: {err}");
+  }
+}
+
+// 记录一次没有匹配结果的搜索。
+pub(crate) fn remember_miss(provider: &str, bangumi_id: u32) {
+  let mut misses = MISSES.lock().unwrap_or_else(|e| e.into_inner());
+  misses.insert((provider.to_string(), bangumi_id));
+}
+
+// 本次运行中是否已经搜索过且没有结果。
+pub(crate) fn is_known_miss(provider: &str, bangumi_id: u32) -> bool {
+  let misses = MISSES.lock().unwrap_or_else(|e| e.into_inner());
+  misses.contains(&(provider.to_string(), bangumi_id))
+}
+
+// 把标题规范化后用于比较：只保留字母数字（含中日文），统一小写。
+pub(crate) fn normalize_title(title: &str) -> String {
+  title
+    .chars()
+    .filter(|c| c.is_alphanumeric())
+    .flat_map(|c| c.to_lowercase())
+    .collect()
+}
+
+// 依次尝试备用数据源，返回第一个有结果的；全部失败时返回 None（错误只打印日志）。
+pub(crate) async fn lookup_fallback(bangumi_id: u32, titles: &[String]) -> Option<FallbackMetadata> {
+  let provider = AniListProvider;
+  match provider.lookup(bangumi_id, titles).await {
+    Ok(found) => found,
+    Err(err) => {
+      app_log!("备用数据源 {} 查询失败 ({bangumi_id}): {err}", provider.name());
+      None
+    }
+  }
+}
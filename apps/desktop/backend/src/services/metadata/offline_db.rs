@@ -0,0 +1,218 @@
+/*
+  anime-offline-database ID 映射：
+  manami-project/anime-offline-database 汇总了各站点的条目链接（AniList / MAL / AniDB 等），
+  但不包含 Bangumi，所以这里以 AniList 为桥梁：
+  Bangumi ID →（AniList 映射，见 anilist.rs）→ AniList ID → 数据库条目 → MAL / AniDB ID。
+
+  - 数据库文件首次使用时下载到元数据目录，超过 DATABASE_MAX_AGE_DAYS 天重新下载，
+    下载失败时继续使用旧文件；
+  - 只把 ID 建成内存索引，不保留标题等其它字段。
+*/
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+
+use crate::services::bangumi::commands::get_subject_brief;
+use crate::services::bangumi::shared_client;
+use crate::services::error::AppError;
+use crate::services::limits::send_limited;
+
+use super::{mapped_id, metadata_dir, AniListProvider, MetadataProvider};
+
+// 数据库下载地址（压缩版 JSON）与本地文件名。
+const DATABASE_URL: &str =
+  "https://github.com/manami-project/anime-offline-database/releases/latest/download/anime-offline-database-minified.json";
+const DATABASE_FILE: &str = "anime-offline-database.json";
+// 数据库每周更新，超过这个天数重新下载。
+const DATABASE_MAX_AGE_DAYS: u64 = 7;
+// 文件较大（几十 MB），下载超时单独放宽。
+const DOWNLOAD_TIMEOUT_SECS: u64 = 300;
+
+// 各站点条目链接的前缀。
+const ANILIST_PREFIX: &str = "https://anilist.co/anime/";
+const MAL_PREFIX: &str = "https://myanimelist.net/anime/";
+const ANIDB_PREFIX: &str = "https://anidb.net/anime/";
+
+// 已加载的索引（首次使用时加载）。
+static INDEX: Lazy<Mutex<Option<Arc<OfflineIndex>>>> = Lazy::new(|| Mutex::new(None));
+
+/// 条目在各站点的 ID。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectIdMapping {
+  pub bangumi_id: u32,
+  pub anilist_id: Option<u64>,
+  pub mal_id: Option<u64>,
+  pub anidb_id: Option<u64>,
+  /// 数据库版本日期（如 "2026-10-12"），数据库不可用时为空。
+  pub database_updated: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DatabaseFile {
+  last_update: Option<String>,
+  data: Vec<DatabaseEntry>,
+}
+
+#[derive(Deserialize)]
+struct DatabaseEntry {
+  sources: Vec<String>,
+}
+
+// 一个作品在各站点的 ID。
+struct OfflineEntry {
+  mal: Option<u64>,
+  anidb: Option<u64>,
+}
+
+// 内存索引：AniList ID → 条目。
+struct OfflineIndex {
+  last_update: Option<String>,
+  by_anilist: HashMap<u64, OfflineEntry>,
+}
+
+// 从链接中取出站点 ID（如 https://anilist.co/anime/123 → 123）。
+fn source_id(sources: &[String], prefix: &str) -> Option<u64> {
+  sources
+    .iter()
+    .find_map(|source| source.strip_prefix(prefix))
+    .and_then(|id| id.trim_end_matches('/').parse().ok())
+}
+
+// 解析数据库文件并建立索引。
+fn build_index(content: &[u8]) -> Result<OfflineIndex, String> {
+  let file: DatabaseFile =
+    serde_json::from_slice(content).map_err(|e| format!("解析 anime-offline-database 失败: {e}"))?;
+  let by_anilist = file
+    .data
+    .into_iter()
+    .filter_map(|entry| {
+      let anilist = source_id(&entry.sources, ANILIST_PREFIX)?;
+      Some((
+        anilist,
+        OfflineEntry {
+          mal: source_id(&entry.sources, MAL_PREFIX),
+          anidb: source_id(&entry.sources, ANIDB_PREFIX),
+        },
+      ))
+    })
+    .collect();
+  Ok(OfflineIndex {
+    last_update: file.last_update,
+    by_anilist,
+  })
+}
+
+// 本地数据库文件路径。
+fn database_path() -> Result<PathBuf, String> {
+  metadata_dir()
+    .map(|dir| dir.join(DATABASE_FILE))
+    .ok_or_else(|| "元数据目录未初始化".to_string())
+}
+
+// 本地文件是否需要重新下载（不存在或超过有效期）。
+fn is_outdated(path: &Path) -> bool {
+  let max_age = Duration::from_secs(DATABASE_MAX_AGE_DAYS * 24 * 60 * 60);
+  fs::metadata(path)
+    .and_then(|meta| meta.modified())
+    .ok()
+    .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+    .map(|age| age > max_age)
+    .unwrap_or(true)
+}
+
+// 下载数据库文件（先写临时文件，校验能解析后再替换）。
+async fn download_database(path: &Path) -> Result<OfflineIndex, String> {
+  let request = shared_client()
+    .get(DATABASE_URL)
+    .timeout(Duration::from_secs(DOWNLOAD_TIMEOUT_SECS));
+  let response = send_limited(request).await?;
+  if !response.status().is_success() {
+    return Err(format!("下载 anime-offline-database 失败: {}", response.status()));
+  }
+  let bytes = response
+    .bytes()
+    .await
+    .map_err(|e| format!("下载 anime-offline-database 失败: {e}"))?;
+  let index = build_index(&bytes)?;
+
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).map_err(|e| format!("创建元数据目录失败: {e}"))?;
+  }
+  let temp = path.with_extension("part");
+  fs::write(&temp, &bytes).map_err(|e| format!("写入 anime-offline-database 失败: {e}"))?;
+  fs::rename(&temp, path).map_err(|e| format!("写入 anime-offline-database 失败: {e}"))?;
+  Ok(index)
+}
+
+// 获取索引：内存里有就直接用；否则读取本地文件，过期或不存在时重新下载。
+async fn load_index() -> Result<Arc<OfflineIndex>, String> {
+  let mut guard = INDEX.lock().await;
+  if let Some(index) = guard.as_ref() {
+    return Ok(index.clone());
+  }
+
+  let path = database_path()?;
+  let index = if is_outdated(&path) {
+    match download_database(&path).await {
+      Ok(index) => index,
+      // 下载失败时退回使用旧文件（如果有）。
+      Err(err) => {
+        let content = fs::read(&path).map_err(|_| err)?;
+        build_index(&content)?
+      }
+    }
+  } else {
+    let content = fs::read(&path).map_err(|e| format!("读取 anime-offline-database 失败: {e}"))?;
+    build_index(&content)?
+  };
+
+  let index = Arc::new(index);
+  *guard = Some(index.clone());
+  Ok(index)
+}
+
+// 查询 Bangumi 条目对应的 AniList ID：已有映射直接用，否则用条目标题在 AniList 上搜索。
+async fn resolve_anilist_id(bangumi_id: u32) -> Result<Option<u64>, AppError> {
+  let provider = AniListProvider;
+  if let Some(id) = mapped_id(provider.name(), bangumi_id) {
+    return Ok(Some(id));
+  }
+  let brief = get_subject_brief(bangumi_id, None).await?;
+  let titles = vec![brief.name, brief.name_cn];
+  provider.lookup(bangumi_id, &titles).await.map_err(AppError::network)?;
+  Ok(mapped_id(provider.name(), bangumi_id))
+}
+
+// 把 Bangumi 条目 ID 转换成 AniList / MAL / AniDB ID。
+// 找不到对应关系的字段为空；AniList 映射或下载 anime-offline-database 失败时返回 NetworkError。
+#[tauri::command]
+pub async fn map_subject_ids(bangumi_id: u32) -> Result<SubjectIdMapping, AppError> {
+  let anilist_id = resolve_anilist_id(bangumi_id).await?;
+  let mut mapping = SubjectIdMapping {
+    bangumi_id,
+    anilist_id,
+    mal_id: None,
+    anidb_id: None,
+    database_updated: None,
+  };
+  let anilist_id = match anilist_id {
+    Some(id) => id,
+    None => return Ok(mapping),
+  };
+
+  let index = load_index().await.map_err(AppError::network)?;
+  mapping.database_updated = index.last_update.clone();
+  if let Some(entry) = index.by_anilist.get(&anilist_id) {
+    mapping.mal_id = entry.mal;
+    mapping.anidb_id = entry.anidb;
+  }
+  Ok(mapping)
+}
@@ -0,0 +1,216 @@
+/*
+  TMDB 图片数据源：
+  根据条目的名称与别名在 TMDB 上查找对应的剧集/电影，
+  返回背景图（backdrops）、海报（posters）与标志（logos），用于丰富详情页。
+
+  - 密钥为 tmdb.api_key（credentials.toml 或动态库），
+    支持 v3 API Key 与 v4 读访问令牌（以 "eyJ" 开头的 JWT，走 Bearer 认证）；
+  - 标题能对上才记录 Bangumi ID → TMDB ID 的映射，剧集与电影分别记录。
+*/
+
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+
+use crate::services::bangumi::{get_secret, shared_client};
+use crate::services::limits::send_limited;
+
+use super::{is_known_miss, mapped_id, normalize_title, remember_id, remember_miss};
+
+// TMDB API 与图片地址。
+const TMDB_API: &str = "https://api.themoviedb.org/3";
+const TMDB_IMAGE_BASE: &str = "https://image.tmdb.org/t/p";
+// ID 映射里使用的提供者名称（剧集与电影的 ID 空间不同）。
+const PROVIDER_TV: &str = "tmdb-tv";
+const PROVIDER_MOVIE: &str = "tmdb-movie";
+// 每类图片最多返回的数量。
+const MAX_IMAGES_PER_KIND: usize = 20;
+// 最多尝试搜索的标题数量。
+const MAX_SEARCH_TITLES: usize = 3;
+
+/// 单张 TMDB 图片。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtworkImage {
+  /// 原图地址。
+  pub url: String,
+  /// 缩略图地址（宽 300）。
+  pub thumbnail: String,
+  pub width: u32,
+  pub height: u32,
+  /// 图片语言（如 "ja"），无文字的图片为空。
+  pub language: Option<String>,
+}
+
+/// TMDB 图片集合。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmdbArtwork {
+  pub tmdb_id: u64,
+  /// "tv" 或 "movie"。
+  pub media_type: String,
+  pub url: String,
+  pub backdrops: Vec<ArtworkImage>,
+  pub posters: Vec<ArtworkImage>,
+  pub logos: Vec<ArtworkImage>,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+  results: Vec<SearchResult>,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+  id: u64,
+  media_type: Option<String>,
+  // 剧集用 name / original_name，电影用 title / original_title。
+  name: Option<String>,
+  original_name: Option<String>,
+  title: Option<String>,
+  original_title: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ImagesResponse {
+  #[serde(default)]
+  backdrops: Vec<ImageItem>,
+  #[serde(default)]
+  posters: Vec<ImageItem>,
+  #[serde(default)]
+  logos: Vec<ImageItem>,
+}
+
+#[derive(Deserialize)]
+struct ImageItem {
+  file_path: String,
+  width: u32,
+  height: u32,
+  iso_639_1: Option<String>,
+}
+
+impl SearchResult {
+  // 是否与给定标题之一对得上（规范化后相等，或一方包含另一方）。
+  fn matches(&self, titles: &[String]) -> bool {
+    let own: Vec<String> = [&self.name, &self.original_name, &self.title, &self.original_title]
+      .into_iter()
+      .filter_map(|value| value.as_deref())
+      .map(normalize_title)
+      .filter(|value| !value.is_empty())
+      .collect();
+    titles
+      .iter()
+      .map(|title| normalize_title(title))
+      .filter(|title| !title.is_empty())
+      .any(|title| {
+        own
+          .iter()
+          .any(|value| *value == title || value.contains(&title) || title.contains(value.as_str()))
+      })
+  }
+}
+
+// 给请求加上认证信息：v4 令牌走 Bearer，v3 Key 走 api_key 参数。
+fn authorize(request: RequestBuilder, key: &str) -> RequestBuilder {
+  if key.starts_with("eyJ") {
+    request.bearer_auth(key)
+  } else {
+    request.query(&[("api_key", key)])
+  }
+}
+
+// 发送 TMDB 请求并解析 JSON。
+async fn get_json<T: for<'de> Deserialize<'de>>(request: RequestBuilder) -> Result<T, String> {
+  let response = send_limited(request).await?;
+  if !response.status().is_success() {
+    return Err(format!("TMDB 请求失败: {}", response.status()));
+  }
+  response.json().await.map_err(|e| e.to_string())
+}
+
+// 按标题搜索剧集或电影，返回第一个标题对得上的结果 (媒体类型, TMDB ID)。
+async fn search(key: &str, titles: &[String]) -> Result<Option<(&'static str, u64)>, String> {
+  let client = shared_client();
+  for title in titles
+    .iter()
+    .filter(|title| !title.trim().is_empty())
+    .take(MAX_SEARCH_TITLES)
+  {
+    let request = client
+      .get(format!("{TMDB_API}/search/multi"))
+      .query(&[("query", title.trim()), ("include_adult", "false")]);
+    let payload: SearchResponse = get_json(authorize(request, key)).await?;
+    let found = payload.results.into_iter().find_map(|item| {
+      let media_type = match item.media_type.as_deref() {
+        Some("tv") => "tv",
+        Some("movie") => "movie",
+        _ => return None,
+      };
+      item.matches(titles).then_some((media_type, item.id))
+    });
+    if found.is_some() {
+      return Ok(found);
+    }
+  }
+  Ok(None)
+}
+
+// 把 TMDB 图片列表转换成输出结构。
+fn to_images(items: Vec<ImageItem>) -> Vec<ArtworkImage> {
+  items
+    .into_iter()
+    .take(MAX_IMAGES_PER_KIND)
+    .map(|item| ArtworkImage {
+      url: format!("{TMDB_IMAGE_BASE}/original{}", item.file_path),
+      thumbnail: format!("{TMDB_IMAGE_BASE}/w300{}", item.file_path),
+      width: item.width,
+      height: item.height,
+      language: item.iso_639_1.filter(|value| !value.is_empty()),
+    })
+    .collect()
+}
+
+// 查找条目在 TMDB 上的图片。
+// 找不到对应作品时返回 Ok(None)；未配置密钥或请求失败时返回 Err。
+pub(crate) async fn lookup_artwork(bangumi_id: u32, titles: &[String]) -> Result<Option<TmdbArtwork>, String> {
+  let key = get_secret("tmdb", "api_key")
+    .map_err(|_| "未配置 TMDB 密钥，请在 credentials.toml 的 [tmdb] 中填写 api_key".to_string())?;
+
+  let mapped = mapped_id(PROVIDER_TV, bangumi_id)
+    .map(|id| ("tv", id))
+    .or_else(|| mapped_id(PROVIDER_MOVIE, bangumi_id).map(|id| ("movie", id)));
+  let (media_type, tmdb_id) = match mapped {
+    Some(found) => found,
+    None => {
+      if is_known_miss(PROVIDER_TV, bangumi_id) {
+        return Ok(None);
+      }
+      match search(&key, titles).await? {
+        Some((media_type, tmdb_id)) => {
+          let provider = if media_type == "tv" { PROVIDER_TV } else { PROVIDER_MOVIE };
+          remember_id(provider, bangumi_id, tmdb_id);
+          (media_type, tmdb_id)
+        }
+        None => {
+          remember_miss(PROVIDER_TV, bangumi_id);
+          return Ok(None);
+        }
+      }
+    }
+  };
+
+  // 只要无文字、中文、日文与英文的图片。
+  let client = shared_client();
+  let request = client
+    .get(format!("{TMDB_API}/{media_type}/{tmdb_id}/images"))
+    .query(&[("include_image_language", "zh,ja,en,null")]);
+  let images: ImagesResponse = get_json(authorize(request, &key)).await?;
+
+  Ok(Some(TmdbArtwork {
+    tmdb_id,
+    media_type: media_type.to_string(),
+    url: format!("https://www.themoviedb.org/{media_type}/{tmdb_id}"),
+    backdrops: to_images(images.backdrops),
+    posters: to_images(images.posters),
+    logos: to_images(images.logos),
+  }))
+}
@@ -5,11 +5,51 @@
 
 // Bangumi 相关功能（条目、季节、翻译等）。
 pub mod bangumi;
+// 文件校验（CRC32 / MD5 / SHA-1）。
+pub mod checksum;
+// 诊断信息（崩溃报告、诊断包导出）。
+pub mod diagnostics;
+// 命令统一使用的错误类型。
+pub mod error;
 // 打开外部链接的能力。
 pub mod external;
+// 下载完成后的处理（重命名、移入媒体库、解析轨道、运行命令）。
+pub mod hooks;
+// 封面图片本地缓存。
+pub mod images;
+// 全局并发限制（共享信号量）。
+pub mod limits;
+// 磁力链接（系统 magnet: 协议处理）。
+pub mod magnet;
 // 媒体轨道解析与混流。
 pub mod media;
+// 文件名模板（批量混流、后处理重命名共用）。
+pub mod naming;
+// 外部元数据（AniList 等备用数据源、ID 映射）。
+pub mod metadata;
+// 新剧集通知（后台轮询追番条目并发送事件）。
+pub mod notifications;
+// 路径访问策略（允许访问的目录、对话框授权）。
+pub mod paths;
+// 电源管理（任务期间保持唤醒）。
+pub mod power;
+// 发布标题解析（字幕组、集数、分辨率等）。
+pub mod release;
+// RSS 订阅（按过滤条件自动下载新发布的种子）。
+pub mod rss;
+// 自动下载规则（按偏好挑选每集的最佳版本）。
+pub mod rules;
+// 后台定时任务（季度刷新、元数据刷新、备份轮换）。
+pub mod scheduler;
+// 种子搜索（Nyaa / 動漫花園 / ACG.RIP 搜索结果解析）。
+pub mod search;
+// 应用设置（并发上限等）。
+pub mod settings;
+// 首次启动向导（工具检查、初始配置、数据导入）。
+pub mod setup;
 // 本地追番数据存储。
 pub mod storage;
+// 系统集成（数据目录链接等）。
+pub mod system;
 // 种子下载相关功能。
 pub mod torrent;
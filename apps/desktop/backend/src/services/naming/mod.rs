@@ -0,0 +1,429 @@
+/*
+  文件名模板（批量混流的输出文件名、后处理钩子的重命名共用）：
+  - 模板由普通文字与占位符组成，例如 "[{group}] {title} - {episode:02} [{resolution}][{crc32}]"；
+    "{{" 与 "}}" 表示字面的花括号，未知占位符或括号不配对时报错；
+  - 数字字段（集数、季数、版本）可以写宽度，例如 {episode:03} 补零到三位，{episode} 默认补零到两位；
+  - 没有值的占位符替换为空，替换后只剩空白的括号（例如 "[]"）一并去掉，连续空白合并为一个；
+  - 生成的文件名按目标系统的规则检查：非法字符替换为下划线，去掉首尾空白
+    （Windows 还有结尾的点与 CON、NUL 这类保留名），超出长度上限时截断，每处修改都记录在 issues 里。
+*/
+
+use serde::{Deserialize, Serialize};
+
+use super::error::AppError;
+use super::release::ReleaseInfo;
+
+// 文件名长度上限（Windows 按 UTF-16 单元，其它系统按 UTF-8 字节）。
+const MAX_NAME_LENGTH: usize = 255;
+// Windows 的保留设备名（不区分大小写，带扩展名也不行）。
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+  "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1", "LPT2",
+  "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+// 成对的括号（去掉空括号时使用）。
+const BRACKETS: &[(char, char)] = &[('[', ']'), ('(', ')'), ('【', '】'), ('（', '）')];
+
+/// 模板里可以使用的字段，没有的字段为 None（替换为空）。
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FilenameContext {
+  /// 作品名（{title}）。
+  pub title: Option<String>,
+  /// 中文作品名（{title_cn}），没有时使用 title。
+  pub title_cn: Option<String>,
+  pub episode: Option<u32>,
+  pub season: Option<u32>,
+  /// 修正版本（{version}，例如 v2 中的 2）。
+  pub version: Option<u32>,
+  pub group: Option<String>,
+  pub resolution: Option<String>,
+  /// 片源（{source}，例如 WebRip）。
+  pub source: Option<String>,
+  /// 视频编码（{codec}，例如 HEVC）。
+  pub codec: Option<String>,
+  /// CRC32（{crc32}，输出为大写的 8 位十六进制）。
+  pub crc32: Option<String>,
+  /// 原文件名（{name}，不含扩展名）。
+  pub name: Option<String>,
+}
+
+/// 文件名规则对应的系统。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilenamePlatform {
+  Windows,
+  Macos,
+  Linux,
+}
+
+impl FilenamePlatform {
+  // 当前运行的系统。
+  pub(crate) fn current() -> Self {
+    if cfg!(windows) {
+      Self::Windows
+    } else if cfg!(target_os = "macos") {
+      Self::Macos
+    } else {
+      Self::Linux
+    }
+  }
+
+  fn is_illegal(self, c: char) -> bool {
+    match self {
+      Self::Windows => matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c.is_control(),
+      // macOS 的 Finder 把 ":" 显示成 "/"，同样不允许。
+      Self::Macos => matches!(c, '/' | ':' | '\0'),
+      Self::Linux => matches!(c, '/' | '\0'),
+    }
+  }
+
+  fn length(self, name: &str) -> usize {
+    match self {
+      Self::Windows => name.encode_utf16().count(),
+      _ => name.len(),
+    }
+  }
+}
+
+/// render_filename_template 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedFilename {
+  /// 可以直接使用的文件名（不含扩展名）。
+  pub name: String,
+  /// 按系统规则做过的修改（中文说明），没有修改时为空。
+  pub issues: Vec<String>,
+}
+
+// 模板片段。
+enum Part {
+  Text(String),
+  Field { name: String, width: Option<usize> },
+}
+
+// 数字字段（可以写宽度）。
+fn is_numeric_field(name: &str) -> bool {
+  matches!(name, "episode" | "season" | "version")
+}
+
+fn is_known_field(name: &str) -> bool {
+  is_numeric_field(name)
+    || matches!(
+      name,
+      "title" | "title_cn" | "group" | "resolution" | "source" | "codec" | "crc32" | "name"
+    )
+}
+
+// 解析模板，返回片段；未知占位符、宽度写法不对或括号不配对时报错。
+fn parse_template(template: &str) -> Result<Vec<Part>, String> {
+  let mut parts = Vec::new();
+  let mut text = String::new();
+  let mut chars = template.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '{' if chars.peek() == Some(&'{') => {
+        chars.next();
+        text.push('{');
+      }
+      '}' if chars.peek() == Some(&'}') => {
+        chars.next();
+        text.push('}');
+      }
+      '}' => return Err("模板里有多余的 \"}\"（字面的花括号请写成 \"}}\"）".to_string()),
+      '{' => {
+        let mut field = String::new();
+        loop {
+          match chars.next() {
+            Some('}') => break,
+            Some(c) => field.push(c),
+            None => return Err(format!("占位符 {{{field} 缺少 \"}}\"")),
+          }
+        }
+        let (name, spec) = match field.split_once(':') {
+          Some((name, spec)) => (name.trim(), Some(spec.trim())),
+          None => (field.trim(), None),
+        };
+        if !is_known_field(name) {
+          return Err(format!("未知的占位符 {{{name}}}"));
+        }
+        let width = match spec {
+          Some(spec) if !is_numeric_field(name) => return Err(format!("占位符 {{{name}}} 不支持格式 \":{spec}\"")),
+          Some(spec) => Some(
+            spec
+              .parse::<usize>()
+              .ok()
+              .filter(|width| (1..=9).contains(width))
+              .ok_or_else(|| format!("占位符 {{{name}:{spec}}} 的宽度必须是 1 ~ 9 的数字，例如 {{{name}:02}}"))?,
+          ),
+          None => None,
+        };
+        if !text.is_empty() {
+          parts.push(Part::Text(std::mem::take(&mut text)));
+        }
+        parts.push(Part::Field {
+          name: name.to_string(),
+          width,
+        });
+      }
+      c => text.push(c),
+    }
+  }
+  if !text.is_empty() {
+    parts.push(Part::Text(text));
+  }
+  Ok(parts)
+}
+
+// 检查模板写法（保存配置时提前报错）。
+pub(crate) fn check_template(template: &str) -> Result<(), String> {
+  parse_template(template).map(|_| ())
+}
+
+// 字段的值；没有值时为空。
+fn field_value(context: &FilenameContext, name: &str, width: Option<usize>) -> String {
+  let text = |value: &Option<String>| value.as_deref().map(str::trim).unwrap_or("").to_string();
+  let number = |value: Option<u32>, default_width: usize| {
+    value
+      .map(|value| format!("{value:0width$}", width = width.unwrap_or(default_width)))
+      .unwrap_or_default()
+  };
+  match name {
+    "title" => text(&context.title),
+    "title_cn" => {
+      let title_cn = text(&context.title_cn);
+      if title_cn.is_empty() {
+        text(&context.title)
+      } else {
+        title_cn
+      }
+    }
+    "episode" => number(context.episode, 2),
+    "season" => number(context.season, 1),
+    "version" => number(context.version, 1),
+    "group" => text(&context.group),
+    "resolution" => text(&context.resolution),
+    "source" => text(&context.source),
+    "codec" => text(&context.codec),
+    "crc32" => text(&context.crc32).to_uppercase(),
+    "name" => text(&context.name),
+    _ => String::new(),
+  }
+}
+
+// 去掉只剩空白的括号（例如缺少 CRC32 时的 "[]"），并把连续空白合并为一个空格。
+fn tidy(rendered: &str) -> String {
+  let mut output: Vec<char> = Vec::new();
+  for c in rendered.chars() {
+    if let Some((open, _)) = BRACKETS.iter().find(|(_, close)| *close == c) {
+      if let Some(start) = output.iter().rposition(|item| item == open) {
+        if output[start + 1..].iter().all(|item| item.is_whitespace()) {
+          output.truncate(start);
+          continue;
+        }
+      }
+    }
+    output.push(c);
+  }
+  output
+    .into_iter()
+    .collect::<String>()
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+// 按系统规则整理文件名，返回整理后的文件名与修改说明。
+pub(crate) fn sanitize_filename(name: &str, platform: FilenamePlatform) -> (String, Vec<String>) {
+  let mut issues = Vec::new();
+  let mut illegal: Vec<char> = Vec::new();
+  let mut sanitized: String = name
+    .chars()
+    .map(|c| {
+      if platform.is_illegal(c) {
+        if !illegal.contains(&c) {
+          illegal.push(c);
+        }
+        '_'
+      } else {
+        c
+      }
+    })
+    .collect();
+  if !illegal.is_empty() {
+    let list: Vec<String> = illegal.iter().map(|c| format!("{c:?}")).collect();
+    issues.push(format!("非法字符 {} 已替换为下划线", list.join(" ")));
+  }
+
+  let trimmed = if platform == FilenamePlatform::Windows {
+    sanitized.trim().trim_end_matches(['.', ' '])
+  } else {
+    sanitized.trim()
+  };
+  if trimmed.len() != sanitized.len() {
+    issues.push(if platform == FilenamePlatform::Windows {
+      "去掉了首尾的空白与结尾的点".to_string()
+    } else {
+      "去掉了首尾的空白".to_string()
+    });
+    sanitized = trimmed.to_string();
+  }
+
+  if platform == FilenamePlatform::Windows {
+    let base = sanitized.split('.').next().unwrap_or("").trim_end().to_string();
+    if WINDOWS_RESERVED_NAMES
+      .iter()
+      .any(|reserved| base.eq_ignore_ascii_case(reserved))
+    {
+      issues.push(format!("{base} 是 Windows 的保留名，已在后面加上下划线"));
+      sanitized.insert(base.len(), '_');
+    }
+  }
+
+  if platform.length(&sanitized) > MAX_NAME_LENGTH {
+    while platform.length(&sanitized) > MAX_NAME_LENGTH {
+      sanitized.pop();
+    }
+    sanitized = sanitized.trim_end().to_string();
+    issues.push(format!("文件名过长，已截断到 {MAX_NAME_LENGTH} 个单位以内"));
+  }
+  (sanitized, issues)
+}
+
+// 按模板生成文件名（不含扩展名），并按系统规则整理；模板写法不对时报错。
+pub(crate) fn render(
+  template: &str,
+  context: &FilenameContext,
+  platform: FilenamePlatform,
+) -> Result<RenderedFilename, String> {
+  let rendered: String = parse_template(template)?
+    .iter()
+    .map(|part| match part {
+      Part::Text(text) => text.clone(),
+      Part::Field { name, width } => field_value(context, name, *width),
+    })
+    .collect();
+  let (name, issues) = sanitize_filename(&tidy(&rendered), platform);
+  Ok(RenderedFilename { name, issues })
+}
+
+// 作品名拆成外文名与中文名："中文名 / English" 这类写法按斜杠拆开，含汉字的部分作为中文名；
+// 拆不开时两者相同。
+fn split_title(title: &str) -> (String, String) {
+  let has_han = |text: &str| {
+    text
+      .chars()
+      .any(|c| ('\u{4E00}'..='\u{9FFF}').contains(&c) || ('\u{3400}'..='\u{4DBF}').contains(&c))
+  };
+  let parts: Vec<&str> = title
+    .split('/')
+    .map(str::trim)
+    .filter(|part| !part.is_empty())
+    .collect();
+  let title_cn = parts.iter().find(|part| has_han(part));
+  let other = parts.iter().find(|part| !has_han(part));
+  match (title_cn, other) {
+    (Some(title_cn), Some(other)) => (other.to_string(), title_cn.to_string()),
+    _ => (title.trim().to_string(), title.trim().to_string()),
+  }
+}
+
+impl FilenameContext {
+  // 由文件名（不含扩展名）与解析出的发布信息生成上下文。
+  pub(crate) fn from_release(name: &str, release: ReleaseInfo) -> Self {
+    let (title, title_cn) = split_title(&release.title);
+    Self {
+      title: Some(title),
+      title_cn: Some(title_cn),
+      episode: release.episode,
+      season: release.season,
+      version: release.version,
+      group: release.group,
+      resolution: release.resolution,
+      source: release.source,
+      codec: release.video_codec,
+      crc32: None,
+      name: Some(name.to_string()),
+    }
+  }
+}
+
+// 按模板生成文件名，用于设置界面预览；platform 省略时按当前系统的规则检查。
+#[tauri::command]
+pub fn render_filename_template(
+  template: String,
+  context: FilenameContext,
+  platform: Option<FilenamePlatform>,
+) -> Result<RenderedFilename, AppError> {
+  render(&template, &context, platform.unwrap_or_else(FilenamePlatform::current)).map_err(AppError::invalid_input)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn context() -> FilenameContext {
+    FilenameContext {
+      title: Some("Sousou no Frieren".to_string()),
+      title_cn: Some("葬送的芙莉莲".to_string()),
+      episode: Some(5),
+      season: Some(1),
+      group: Some("LoliHouse".to_string()),
+      resolution: Some("1080p".to_string()),
+      crc32: Some("1a2b3c4d".to_string()),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn renders_templates() {
+    let cases = [
+      ("[{group}] {title} - {episode} [{resolution}]", "[LoliHouse] Sousou no Frieren - 05 [1080p]"),
+      ("{title_cn} S{season:02}E{episode:03}", "葬送的芙莉莲 S01E005"),
+      ("{title} [{crc32}]", "Sousou no Frieren [1A2B3C4D]"),
+      // 没有值的字段替换为空，只剩空白的括号一并去掉。
+      ("{title} [{source}] ({codec})  v{version}", "Sousou no Frieren v"),
+      ("{{{group}}}", "{LoliHouse}"),
+    ];
+    for (template, expected) in cases {
+      let rendered = render(template, &context(), FilenamePlatform::Linux).unwrap();
+      assert_eq!(rendered.name, expected, "{template}");
+      assert!(rendered.issues.is_empty(), "{template}");
+    }
+  }
+
+  #[test]
+  fn title_cn_falls_back_to_title() {
+    let context = FilenameContext {
+      title: Some("Frieren".to_string()),
+      ..Default::default()
+    };
+    let rendered = render("{title_cn}", &context, FilenamePlatform::Linux).unwrap();
+    assert_eq!(rendered.name, "Frieren");
+  }
+
+  #[test]
+  fn rejects_bad_templates() {
+    for template in ["{unknown}", "{title", "title}", "{title:02}", "{episode:x}", "{episode:0}"] {
+      assert!(check_template(template).is_err(), "{template}");
+    }
+    assert!(check_template("{title} {{literal}} {episode:2}").is_ok());
+  }
+
+  #[test]
+  fn sanitizes_per_platform() {
+    let cases = [
+      ("a:b", FilenamePlatform::Windows, "a_b"),
+      ("a:b", FilenamePlatform::Macos, "a_b"),
+      ("a:b", FilenamePlatform::Linux, "a:b"),
+      ("a/b", FilenamePlatform::Linux, "a_b"),
+      ("name. ", FilenamePlatform::Windows, "name"),
+      ("con.mkv", FilenamePlatform::Windows, "con_.mkv"),
+    ];
+    for (name, platform, expected) in cases {
+      assert_eq!(sanitize_filename(name, platform).0, expected, "{name}");
+    }
+    let (long, issues) = sanitize_filename(&"字".repeat(100), FilenamePlatform::Linux);
+    assert!(long.len() <= MAX_NAME_LENGTH);
+    assert_eq!(issues.len(), 1);
+  }
+}
@@ -0,0 +1,214 @@
+/*
+  新剧集通知模块：
+  后台定期检查“正在追”条目的分集列表，发现自上次检查以来有新的正片播出时，
+  向前端发送 episode-aired 事件（由前端弹出系统通知）。
+
+  - 检查间隔与静音条目保存在设置里（AppSettings.episode_notifications）；
+  - 每个条目上次检查时的已播出集数保存在数据目录的 episode-notify-state.json，
+    应用关闭期间播出的集数在下次启动时也会通知；
+  - 第一次检查某个条目时只记录基准，不发送通知；
+  - 静音条目照常更新基准，只是不发送通知，取消静音后不会补发一堆旧通知。
+*/
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use super::bangumi::commands::get_next_episode_info;
+use super::diagnostics::app_log;
+use super::error::AppError;
+use super::settings::{load_settings, save_settings};
+use super::storage::load_tracked;
+
+// 发送给前端的事件名。
+const EPISODE_AIRED_EVENT: &str = "episode-aired";
+// 基准状态文件名（位于数据目录下）。
+const STATE_FILE: &str = "episode-notify-state.json";
+// 循环检查“是否到了下一次轮询”的间隔（修改轮询间隔后最多这么久生效）。
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+// 轮询间隔的下限（分钟），避免过于频繁地请求 Bangumi。
+const MIN_POLL_INTERVAL_MINUTES: u64 = 5;
+
+/// 新剧集通知设置（保存在设置里）。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EpisodeNotificationSettings {
+  /// 轮询间隔（分钟），0 表示关闭通知。
+  pub poll_interval_minutes: u64,
+  /// 不发送通知的条目 ID。
+  pub muted_subjects: Vec<u32>,
+}
+
+impl Default for EpisodeNotificationSettings {
+  fn default() -> Self {
+    Self {
+      poll_interval_minutes: 60,
+      muted_subjects: Vec::new(),
+    }
+  }
+}
+
+impl EpisodeNotificationSettings {
+  // 修正非法值：非 0 的间隔不低于下限，静音列表去重并排序。
+  pub(crate) fn normalized(mut self) -> Self {
+    if self.poll_interval_minutes > 0 {
+      self.poll_interval_minutes = self.poll_interval_minutes.max(MIN_POLL_INTERVAL_MINUTES);
+    }
+    self.muted_subjects.sort_unstable();
+    self.muted_subjects.dedup();
+    self
+  }
+}
+
+/// episode-aired 事件的内容。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeAiredEvent {
+  pub id: u32,
+  pub name: String,
+  pub name_cn: String,
+  pub image: String,
+  /// 上次检查时与现在的已播出正片集数。
+  pub previous_count: u32,
+  pub aired_count: u32,
+  pub total_count: u32,
+}
+
+// 基准状态文件的完整路径。
+fn state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+  let dir = app
+    .path()
+    .app_data_dir()
+    .map_err(|e| format!("无法获取数据目录: {e}"))?
+    .join("hanamirip-cn");
+  fs::create_dir_all(&dir).map_err(|e| format!("创建数据目录失败: {e}"))?;
+  Ok(dir.join(STATE_FILE))
+}
+
+// 读取基准状态（条目 ID -> 已播出集数）；文件不存在或损坏时从空开始。
+fn load_state(app: &tauri::AppHandle) -> HashMap<u32, u32> {
+  state_path(app)
+    .ok()
+    .and_then(|path| fs::read_to_string(path).ok())
+    .and_then(|content| serde_json::from_str(&content).ok())
+    .unwrap_or_default()
+}
+
+// 写回基准状态。
+fn persist_state(app: &tauri::AppHandle, state: &HashMap<u32, u32>) -> Result<(), String> {
+  let path = state_path(app)?;
+  let payload = serde_json::to_string_pretty(state).map_err(|e| format!("序列化通知状态失败: {e}"))?;
+  fs::write(&path, payload).map_err(|e| format!("写入通知状态失败: {e}"))
+}
+
+// 检查一次所有“正在追”的条目，返回需要通知的新剧集。
+// 单个条目请求失败时跳过（保留旧基准），下次再检查。
+async fn check_new_episodes(app: &tauri::AppHandle) -> Result<Vec<EpisodeAiredEvent>, AppError> {
+  let settings = load_settings(app).map_err(AppError::io)?.episode_notifications;
+  let subjects: Vec<_> = load_tracked(app)?
+    .into_values()
+    .filter(|subject| subject.watching)
+    .collect();
+
+  // 已不在追的条目不再需要基准。
+  let mut state = load_state(app);
+  state.retain(|id, _| subjects.iter().any(|subject| subject.id == *id));
+
+  let mut events = Vec::new();
+  for subject in subjects {
+    let info = match get_next_episode_info(subject.id).await {
+      Ok(info) => info,
+      Err(err) => {
+        app_log!("检查新剧集失败 ({}): {err}", subject.id);
+        continue;
+      }
+    };
+    let previous = state.insert(subject.id, info.aired_count);
+    let previous_count = match previous {
+      Some(count) if info.aired_count > count => count,
+      _ => continue,
+    };
+    if settings.muted_subjects.contains(&subject.id) {
+      continue;
+    }
+    events.push(EpisodeAiredEvent {
+      id: subject.id,
+      name: subject.name,
+      name_cn: subject.name_cn,
+      image: subject.image,
+      previous_count,
+      aired_count: info.aired_count,
+      total_count: info.total_count,
+    });
+  }
+
+  persist_state(app, &state).map_err(AppError::io)?;
+  Ok(events)
+}
+
+// 启动新剧集轮询（应用启动时调用一次）。
+// 启动后立即检查一次，之后按设置里的间隔轮询；间隔为 0 时暂停。
+pub(crate) fn start_episode_notifier(app: tauri::AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let mut last_check: Option<Instant> = None;
+    loop {
+      let interval = load_settings(&app)
+        .map(|settings| settings.episode_notifications.poll_interval_minutes)
+        .unwrap_or_else(|_| EpisodeNotificationSettings::default().poll_interval_minutes);
+      let due = interval > 0
+        && last_check
+          .map(|time| time.elapsed() >= Duration::from_secs(interval * 60))
+          .unwrap_or(true);
+      if due {
+        last_check = Some(Instant::now());
+        match check_new_episodes(&app).await {
+          Ok(events) => {
+            for event in events {
+              if let Err(err) = app.emit(EPISODE_AIRED_EVENT, &event) {
+                app_log!("发送新剧集事件失败: {err}");
+              }
+            }
+          }
+          Err(err) => app_log!("检查新剧集失败: {err}"),
+        }
+      }
+      tokio::time::sleep(TICK_INTERVAL).await;
+    }
+  });
+}
+
+// 返回当前的新剧集通知设置。
+#[tauri::command]
+pub fn get_episode_notification_settings(app: tauri::AppHandle) -> Result<EpisodeNotificationSettings, AppError> {
+  Ok(load_settings(&app).map_err(AppError::io)?.episode_notifications)
+}
+
+// 设置轮询间隔（分钟），0 表示关闭；返回修正后的设置。
+#[tauri::command]
+pub fn set_episode_poll_interval(
+  app: tauri::AppHandle,
+  minutes: u64,
+) -> Result<EpisodeNotificationSettings, AppError> {
+  let mut settings = load_settings(&app).map_err(AppError::io)?;
+  settings.episode_notifications.poll_interval_minutes = minutes;
+  Ok(save_settings(&app, settings).map_err(AppError::io)?.episode_notifications)
+}
+
+// 静音/取消静音某个条目的新剧集通知；返回修正后的设置。
+#[tauri::command]
+pub fn set_subject_notification_muted(
+  app: tauri::AppHandle,
+  id: u32,
+  muted: bool,
+) -> Result<EpisodeNotificationSettings, AppError> {
+  let mut settings = load_settings(&app).map_err(AppError::io)?;
+  let muted_subjects = &mut settings.episode_notifications.muted_subjects;
+  muted_subjects.retain(|subject| *subject != id);
+  if muted {
+    muted_subjects.push(id);
+  }
+  Ok(save_settings(&app, settings).map_err(AppError::io)?.episode_notifications)
+}
@@ -0,0 +1,320 @@
+/*
+  路径访问策略模块：
+  前端（webview）传来的任意路径都不能直接信任，
+  否则前端一旦被注入脚本，就能读取/移动磁盘上的任意文件。
+
+  这里集中维护“允许访问的范围”：
+  1) 设置里配置的下载目录、输出目录与额外允许目录；
+  2) 用户在本次运行中通过系统对话框（pick_path 命令）亲手选择的路径。
+  所有文件系统相关命令都应先调用 ensure_path_allowed 校验。
+  应用数据目录（设置、密钥覆盖文件、各类记录）总是拒绝，即使它位于上面的某个目录之下；
+  内部需要读写数据目录的地方自己拼出路径，不经过这里的校验。
+  工具目录（后处理钩子可以运行其中的程序）单独维护：只能通过 pick_path 的工具目录对话框添加，
+  不能与上面的目录重叠，其中的文件也不能通过文件命令访问，避免下载或生成的文件被当作程序运行。
+*/
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, FilePath};
+use tokio::sync::oneshot;
+
+use super::error::AppError;
+use super::settings::{load_settings, AppSettings};
+
+// 本次运行中通过对话框授权的路径（已规范化）。
+// 只保存在内存里：重启后需要重新选择，避免授权无限期累积。
+static GRANTED_PATHS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+// 本次运行中通过工具目录对话框选择的目录（已规范化），保存设置时只有这些目录可以加入工具目录。
+static TOOL_GRANTS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 对话框文件类型过滤器。
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DialogFilter {
+  pub name: String,
+  pub extensions: Vec<String>,
+}
+
+/// pick_path 的参数。
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickPathOptions {
+  pub title: Option<String>,
+  /// true 表示选择目录。
+  #[serde(default)]
+  pub directory: bool,
+  /// true 表示“另存为”对话框（可以选择尚不存在的文件）。
+  #[serde(default)]
+  pub save: bool,
+  /// true 表示选择工具目录（固定为目录对话框，所选目录只能加入设置里的工具目录，不加入普通授权）。
+  #[serde(default)]
+  pub tool_dir: bool,
+  pub default_path: Option<String>,
+  #[serde(default)]
+  pub filters: Vec<DialogFilter>,
+}
+
+// 把路径规范化成绝对、无符号链接、无 ".." 的形式。
+// 路径可能还不存在（例如输出文件），所以只规范化“已存在的最长前缀”，
+// 再把剩余部分原样拼回去；剩余部分里出现 ".." 等特殊组件直接拒绝。
+fn normalize(path: &Path) -> Result<PathBuf, String> {
+  if !path.is_absolute() {
+    return Err(format!("必须使用绝对路径: {}", path.display()));
+  }
+  let mut existing = path;
+  let mut rest = Vec::new();
+  while !existing.exists() {
+    // file_name() 遇到 ".." 会返回 None，正好用来拒绝这类路径。
+    match (existing.parent(), existing.file_name()) {
+      (Some(parent), Some(name)) => {
+        rest.push(name.to_os_string());
+        existing = parent;
+      }
+      _ => return Err(format!("路径无效: {}", path.display())),
+    }
+  }
+  let mut normalized = existing
+    .canonicalize()
+    .map_err(|e| format!("解析路径失败 ({}): {e}", path.display()))?;
+  for name in rest.into_iter().rev() {
+    normalized.push(name);
+  }
+  Ok(normalized)
+}
+
+// 记录用户授权的路径（目录授权其下所有内容，文件只授权该文件本身）。
+pub(crate) fn grant_path(path: &Path) {
+  if let Ok(normalized) = normalize(path) {
+    GRANTED_PATHS
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .insert(normalized);
+  }
+}
+
+// 判断路径是否是本次运行中通过对话框授权过的。
+fn is_granted(normalized: &Path) -> bool {
+  GRANTED_PATHS
+    .lock()
+    .unwrap_or_else(|e| e.into_inner())
+    .iter()
+    .any(|root| normalized.starts_with(root))
+}
+
+// 设置里的工具目录（已规范化）。
+fn tool_roots(app: &tauri::AppHandle) -> Vec<PathBuf> {
+  let Ok(settings) = load_settings(app) else {
+    return Vec::new();
+  };
+  settings
+    .tool_dirs
+    .iter()
+    .filter_map(|dir| normalize(Path::new(dir)).ok())
+    .collect()
+}
+
+// 设置里配置的目录（下载/输出/额外允许目录）。
+fn configured_dirs(settings: &AppSettings) -> Vec<String> {
+  settings
+    .download_dir
+    .iter()
+    .chain(settings.output_dir.iter())
+    .chain(settings.allowed_dirs.iter())
+    .cloned()
+    .collect()
+}
+
+// 当前允许访问的根目录（不含对话框授权）。
+fn allowed_roots(app: &tauri::AppHandle) -> Vec<PathBuf> {
+  let Ok(settings) = load_settings(app) else {
+    return Vec::new();
+  };
+  configured_dirs(&settings)
+    .into_iter()
+    .filter_map(|root| normalize(Path::new(&root)).ok())
+    .collect()
+}
+
+// 路径是否位于应用数据目录下（数据目录里是应用自己的设置与密钥，前端不能直接读写）。
+fn is_app_data(app: &tauri::AppHandle, normalized: &Path) -> bool {
+  app
+    .path()
+    .app_data_dir()
+    .ok()
+    .and_then(|dir| normalize(&dir).ok())
+    .is_some_and(|dir| normalized.starts_with(dir))
+}
+
+// 校验前端传来的路径是否在允许范围内。
+// 通过时返回规范化后的路径，调用方应使用这个返回值而不是原始字符串，
+// 避免校验之后路径被替换（例如中途被改成符号链接）造成偏差。
+pub(crate) fn ensure_path_allowed(app: &tauri::AppHandle, raw: &str) -> Result<PathBuf, String> {
+  let raw = raw.trim();
+  if raw.is_empty() {
+    return Err("路径为空".to_string());
+  }
+  let normalized = normalize(Path::new(raw))?;
+  if is_app_data(app, &normalized) {
+    return Err(format!("不能访问应用数据目录: {raw}"));
+  }
+  if tool_roots(app).iter().any(|root| normalized.starts_with(root)) {
+    return Err(format!("不能访问工具目录: {raw}"));
+  }
+  if is_granted(&normalized) || allowed_roots(app).iter().any(|root| normalized.starts_with(root)) {
+    return Ok(normalized);
+  }
+  Err(format!("无权访问该路径，请通过对话框重新选择: {raw}"))
+}
+
+// 校验路径是否位于设置里配置的目录下，不接受对话框的临时授权。
+// 用于需要跨重启长期使用的路径（例如 RSS 订阅的下载目录）：临时授权重启后就失效了。
+pub(crate) fn ensure_path_configured(app: &tauri::AppHandle, raw: &str) -> Result<PathBuf, String> {
+  let raw = raw.trim();
+  if raw.is_empty() {
+    return Err("路径为空".to_string());
+  }
+  let normalized = normalize(Path::new(raw))?;
+  if is_app_data(app, &normalized) {
+    return Err(format!("不能使用应用数据目录: {raw}"));
+  }
+  if tool_roots(app).iter().any(|root| normalized.starts_with(root)) {
+    return Err(format!("不能使用工具目录: {raw}"));
+  }
+  if allowed_roots(app).iter().any(|root| normalized.starts_with(root)) {
+    return Ok(normalized);
+  }
+  Err(format!("目录必须位于设置里的下载目录或额外允许目录下: {raw}"))
+}
+
+// 校验要运行的程序：必须位于设置里的工具目录下，且不在下载、输出、额外允许目录与数据目录下
+// （这些目录里的文件可能来自下载或由前端生成）。
+pub(crate) fn ensure_tool_program(app: &tauri::AppHandle, raw: &str) -> Result<PathBuf, String> {
+  let raw = raw.trim();
+  if raw.is_empty() {
+    return Err("路径为空".to_string());
+  }
+  let normalized = normalize(Path::new(raw))?;
+  if is_app_data(app, &normalized) || allowed_roots(app).iter().any(|root| normalized.starts_with(root)) {
+    return Err(format!("不能运行下载、输出、额外允许目录或应用数据目录下的程序: {raw}"));
+  }
+  if tool_roots(app).iter().any(|root| normalized.starts_with(root)) {
+    return Ok(normalized);
+  }
+  Err(format!("程序必须位于设置里的工具目录下（工具目录只能通过对话框添加）: {raw}"))
+}
+
+// 保存设置前的校验：新增的目录必须是用户通过对话框选择的（或本来就已配置），
+// 防止前端通过修改设置把任意目录加入允许范围。
+pub(crate) fn ensure_settings_dirs_allowed(previous: &AppSettings, next: &AppSettings) -> Result<(), String> {
+  let existing: Vec<PathBuf> = configured_dirs(previous)
+    .into_iter()
+    .filter_map(|dir| normalize(Path::new(&dir)).ok())
+    .collect();
+  for dir in configured_dirs(next) {
+    let normalized = normalize(Path::new(&dir))?;
+    if !existing.contains(&normalized) && !is_granted(&normalized) {
+      return Err(format!("目录未经授权，请通过对话框选择: {dir}"));
+    }
+  }
+  // 工具目录只能是工具目录对话框选择的，且不能与其它目录互相包含。
+  let existing_tools: Vec<PathBuf> = previous
+    .tool_dirs
+    .iter()
+    .filter_map(|dir| normalize(Path::new(dir)).ok())
+    .collect();
+  let configured: Vec<PathBuf> = configured_dirs(next)
+    .into_iter()
+    .filter_map(|dir| normalize(Path::new(&dir)).ok())
+    .collect();
+  for dir in &next.tool_dirs {
+    let normalized = normalize(Path::new(dir))?;
+    let granted = TOOL_GRANTS
+      .lock()
+      .unwrap_or_else(|e| e.into_inner())
+      .contains(&normalized);
+    if !existing_tools.contains(&normalized) && !granted {
+      return Err(format!("工具目录必须通过工具目录对话框选择: {dir}"));
+    }
+    if configured
+      .iter()
+      .any(|other| normalized.starts_with(other) || other.starts_with(&normalized))
+    {
+      return Err(format!("工具目录不能与下载、输出或额外允许目录重叠: {dir}"));
+    }
+  }
+  Ok(())
+}
+
+// 打开系统文件/目录对话框，返回用户选择的路径，并授权该路径。
+// 用户取消时返回 None。
+#[tauri::command]
+pub async fn pick_path(app: tauri::AppHandle, options: PickPathOptions) -> Result<Option<String>, AppError> {
+  let mut builder = app.dialog().file();
+  // 工具目录对话框使用固定的标题，让用户知道所选目录里的程序会被运行。
+  let title = if options.tool_dir {
+    Some("选择工具目录（后处理钩子可以运行其中的程序）".to_string())
+  } else {
+    options.title
+  };
+  if let Some(title) = title {
+    builder = builder.set_title(title);
+  }
+  for filter in &options.filters {
+    let extensions: Vec<&str> = filter.extensions.iter().map(|ext| ext.as_str()).collect();
+    builder = builder.add_filter(filter.name.clone(), &extensions);
+  }
+  // 默认路径：已存在的目录直接作为起始目录，否则拆成“目录 + 文件名”。
+  if let Some(default_path) = options.default_path {
+    let default_path = PathBuf::from(default_path);
+    if default_path.is_dir() {
+      builder = builder.set_directory(&default_path);
+    } else {
+      if let Some(parent) = default_path.parent().filter(|p| p.is_dir()) {
+        builder = builder.set_directory(parent);
+      }
+      if let Some(name) = default_path.file_name() {
+        builder = builder.set_file_name(name.to_string_lossy());
+      }
+    }
+  }
+
+  // 对话框是回调式 API，用 oneshot 通道把结果转成 async。
+  let (tx, rx) = oneshot::channel::<Option<FilePath>>();
+  if options.directory || options.tool_dir {
+    builder.pick_folder(move |path| {
+      let _ = tx.send(path);
+    });
+  } else if options.save {
+    builder.save_file(move |path| {
+      let _ = tx.send(path);
+    });
+  } else {
+    builder.pick_file(move |path| {
+      let _ = tx.send(path);
+    });
+  }
+  let picked = rx.await.map_err(|e| AppError::internal(format!("对话框异常关闭: {e}")))?;
+
+  match picked {
+    Some(file_path) => {
+      let path = file_path
+        .into_path()
+        .map_err(|e| AppError::invalid_input(format!("解析所选路径失败: {e}")))?;
+      if options.tool_dir {
+        let normalized = normalize(&path).map_err(AppError::invalid_input)?;
+        TOOL_GRANTS
+          .lock()
+          .unwrap_or_else(|e| e.into_inner())
+          .insert(normalized);
+      } else {
+        grant_path(&path);
+      }
+      Ok(Some(path.to_string_lossy().to_string()))
+    }
+    None => Ok(None),
+  }
+}
@@ -0,0 +1,64 @@
+/*
+  电源管理模块：
+  有下载或媒体任务在进行时，向系统申请“保持唤醒”，避免笔记本中途睡眠导致任务损坏。
+  - 采用引用计数：每个任务持有一个 AwakeGuard；
+  - 第一个 guard 创建时申请保持唤醒，最后一个 guard 释放时撤销；
+  - 只阻止系统空闲睡眠，不阻止屏幕关闭。
+*/
+
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+use crate::services::diagnostics::app_log;
+
+// 当前的保持唤醒状态。
+struct PowerState {
+  // 正在进行的任务数量。
+  active: usize,
+  // 系统“保持唤醒”句柄；drop 时自动撤销。
+  handle: Option<keepawake::KeepAwake>,
+}
+
+static POWER_STATE: Lazy<Mutex<PowerState>> = Lazy::new(|| {
+  Mutex::new(PowerState {
+    active: 0,
+    handle: None,
+  })
+});
+
+/// 保持唤醒的持有凭证：存在期间系统不会因空闲而睡眠。
+pub(crate) struct AwakeGuard {
+  _private: (),
+}
+
+// 申请保持唤醒。
+// reason 会显示在系统的电源诊断信息里（如 macOS 的 pmset -g assertions）。
+// 申请失败只打印日志，任务照常进行。
+pub(crate) fn keep_awake(reason: &str) -> AwakeGuard {
+  let mut state = POWER_STATE.lock().unwrap_or_else(|e| e.into_inner());
+  state.active += 1;
+  if state.handle.is_none() {
+    match keepawake::Builder::default()
+      .idle(true)
+      .reason(reason)
+      .app_name("HanamiRIP CN")
+      .app_reverse_domain("com.hanamirip.cn")
+      .create()
+    {
+      Ok(handle) => state.handle = Some(handle),
+      Err(err) => app_log!("failed to acquire keep-awake: {err}"),
+    }
+  }
+  AwakeGuard { _private: () }
+}
+
+impl Drop for AwakeGuard {
+  // 释放凭证；最后一个任务结束时撤销保持唤醒。
+  fn drop(&mut self) {
+    let mut state = POWER_STATE.lock().unwrap_or_else(|e| e.into_inner());
+    state.active = state.active.saturating_sub(1);
+    if state.active == 0 {
+      state.handle = None;
+    }
+  }
+}
@@ -0,0 +1,562 @@
+/*
+  发布标题解析（类似 anitomy）：
+  从字幕组发布的种子名/文件名中提取字幕组、作品名、季数、集数、分辨率、片源、视频编码与字幕语言。
+  常见的两类写法：
+  - [字幕组] 作品名 - 01 [1080p][WebRip][HEVC][CHS].mkv
+  - 【字幕组】★04月新番★[作品名][01][1080p][简日双语]
+  处理流程：
+  1. 去掉扩展名，把全角括号统一成半角，按方括号/圆括号切分成若干片段；
+  2. 开头的方括号是字幕组；
+  3. 所有片段按分隔符切成词，识别分辨率、片源、编码、语言、季数与集数；
+  4. 作品名取第一个不是纯标签的片段（集数标记之前的部分）。
+  解析是启发式的，识别不了的字段为空，不会报错。
+*/
+
+use serde::Serialize;
+
+/// 解析后的发布信息。
+#[derive(Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseInfo {
+  /// 字幕组（可能是多个组合作，如 "Nekomoe kissaten&LoliHouse"）。
+  pub group: Option<String>,
+  /// 作品名（可能同时包含中文名与外文名）。
+  pub title: String,
+  pub season: Option<u32>,
+  /// 集数；合集时为起始集。
+  pub episode: Option<u32>,
+  /// 合集的结束集（例如 01-12 中的 12）。
+  pub episode_end: Option<u32>,
+  /// 修正版本（v2 等）。
+  pub version: Option<u32>,
+  /// 分辨率，统一为 "1080p" 这种写法。
+  pub resolution: Option<String>,
+  /// 片源：WebRip / WEB-DL / BDRip / BD / DVDRip / TVRip 等。
+  pub source: Option<String>,
+  /// 视频编码：HEVC / AVC / AV1。
+  pub video_codec: Option<String>,
+  /// 字幕语言：chs（简体）/ cht（繁体）/ jpn / eng。
+  pub languages: Vec<String>,
+  /// 是否是合集（包含集数范围或合集关键词）。
+  pub batch: bool,
+}
+
+// 常见的视频/字幕扩展名（解析前去掉）。
+const EXTENSIONS: &[&str] = &[".mkv", ".mp4", ".avi", ".ts", ".m2ts", ".webm", ".ass", ".srt", ".torrent"];
+// 切词用的分隔符（不含 "-"，保留 WEB-DL、01-12 这类写法）。
+const DELIMITERS: &[char] = &[' ', '_', '.', '+', '&', '/', ',', ';', '|', '★', '☆', '「', '」'];
+// 表示合集的关键词。
+const BATCH_KEYWORDS: &[&str] = &["batch", "complete", "合集", "全集", "全话", "全話", "fin"];
+// 只由这些字组成的词视为字幕语言标签（例如 简日双语、简繁内封）。
+const LANGUAGE_CHARS: &str = "简簡繁日中英双雙语語体體内內封嵌外挂掛字幕文";
+
+// 一个片段：方括号/圆括号内的内容，或括号之间的普通文本。
+enum Segment {
+  Bracket(String),
+  Text(String),
+}
+
+// 去掉扩展名并统一括号写法。
+fn normalize(name: &str) -> String {
+  let mut name = name.trim().to_string();
+  let lower = name.to_lowercase();
+  if let Some(ext) = EXTENSIONS.iter().find(|ext| lower.ends_with(*ext)) {
+    name.truncate(name.len() - ext.len());
+  }
+  for (dotted, joined) in [("H.264", "H264"), ("h.264", "h264"), ("H.265", "H265"), ("h.265", "h265")] {
+    name = name.replace(dotted, joined);
+  }
+  name
+    .replace(['【', '〔'], "[")
+    .replace(['】', '〕'], "]")
+    .replace('（', "(")
+    .replace('）', ")")
+}
+
+// 按括号切分片段（不处理嵌套，遇到未闭合的括号时其余部分按普通文本处理）。
+fn split_segments(name: &str) -> Vec<Segment> {
+  let mut segments = Vec::new();
+  let mut text = String::new();
+  let mut chars = name.chars().peekable();
+  while let Some(c) = chars.next() {
+    let close = match c {
+      '[' => ']',
+      '(' => ')',
+      _ => {
+        text.push(c);
+        continue;
+      }
+    };
+    let inner: String = chars.clone().take_while(|next| *next != close).collect();
+    if chars.clone().nth(inner.chars().count()).is_none() {
+      text.push(c);
+      continue;
+    }
+    for _ in 0..=inner.chars().count() {
+      chars.next();
+    }
+    if !text.trim().is_empty() {
+      segments.push(Segment::Text(std::mem::take(&mut text)));
+    }
+    text.clear();
+    if !inner.trim().is_empty() {
+      segments.push(Segment::Bracket(inner.trim().to_string()));
+    }
+  }
+  if !text.trim().is_empty() {
+    segments.push(Segment::Text(text));
+  }
+  segments
+}
+
+// 按分隔符切词（统一为小写）。
+fn tokens(text: &str) -> Vec<String> {
+  text
+    .to_lowercase()
+    .split(DELIMITERS)
+    .map(|token| token.trim_matches('-').to_string())
+    .filter(|token| !token.is_empty())
+    .collect()
+}
+
+// 分辨率：1080p / 1920x1080 / 4K（传入小写的词，下同）。
+pub(crate) fn parse_resolution(token: &str) -> Option<String> {
+  if token == "4k" || token == "uhd" {
+    return Some("2160p".to_string());
+  }
+  let height = match token.split_once('x') {
+    Some((width, height)) if width.parse::<u32>().is_ok() => height,
+    _ => token.strip_suffix('p')?,
+  };
+  let height: u32 = height.parse().ok()?;
+  matches!(height, 360 | 480 | 540 | 576 | 720 | 1080 | 1440 | 2160).then(|| format!("{height}p"))
+}
+
+// 片源。
+fn parse_source(token: &str) -> Option<&'static str> {
+  Some(match token {
+    "webrip" | "web-rip" => "WebRip",
+    "web-dl" | "webdl" => "WEB-DL",
+    "web" => "WEB",
+    "bdrip" | "bd-rip" => "BDRip",
+    "bd" | "bluray" | "blu-ray" | "bdmv" | "bdremux" => "BD",
+    "dvdrip" => "DVDRip",
+    "dvd" => "DVD",
+    "tvrip" => "TVRip",
+    "hdtv" => "HDTV",
+    _ => return None,
+  })
+}
+
+// 视频编码（HEVC-10bit 这类写法按前缀识别）。
+pub(crate) fn parse_codec(token: &str) -> Option<&'static str> {
+  let base = token.split('-').next().unwrap_or(token);
+  Some(match base {
+    "hevc" | "x265" | "h265" => "HEVC",
+    "avc" | "x264" | "h264" => "AVC",
+    "av1" => "AV1",
+    _ => return None,
+  })
+}
+
+// 字幕语言，一个词可能对应多种语言（简繁、简日双语）。
+pub(crate) fn parse_languages(token: &str) -> Vec<&'static str> {
+  let single = match token {
+    "chs" | "gb" | "sc" | "简" | "简体" | "简中" | "簡體" => Some("chs"),
+    "cht" | "big5" | "tc" | "繁" | "繁体" | "繁體" | "繁中" => Some("cht"),
+    "jp" | "jpn" | "jap" | "日" | "日语" | "日文" => Some("jpn"),
+    "eng" | "en" | "english" => Some("eng"),
+    _ => None,
+  };
+  if let Some(language) = single {
+    return vec![language];
+  }
+  if !token.chars().all(|c| LANGUAGE_CHARS.contains(c)) {
+    return Vec::new();
+  }
+  let mut languages = Vec::new();
+  if token.contains(['简', '簡']) {
+    languages.push("chs");
+  }
+  if token.contains('繁') {
+    languages.push("cht");
+  }
+  if token.contains('日') {
+    languages.push("jpn");
+  }
+  if token.contains('英') {
+    languages.push("eng");
+  }
+  languages
+}
+
+// 纯数字（最多 4 位）。
+fn parse_number(text: &str) -> Option<u32> {
+  (!text.is_empty() && text.len() <= 4 && text.chars().all(|c| c.is_ascii_digit()))
+    .then(|| text.parse().ok())
+    .flatten()
+}
+
+// 集数（可带版本号）："01" / "01v2" / "ep01" / "e01"。返回 (集数, 版本)。
+fn parse_episode(token: &str) -> Option<(u32, Option<u32>)> {
+  let token = token
+    .strip_prefix("ep")
+    .or_else(|| token.strip_prefix('e'))
+    .unwrap_or(token);
+  let (number, version) = match token.split_once('v') {
+    Some((number, version)) => (number, Some(parse_number(version)?)),
+    None => (token, None),
+  };
+  let episode = parse_number(number)?;
+  // 四位数的一般是年份。
+  (number.len() < 4).then_some((episode, version))
+}
+
+// 集数范围："01-12" / "01~12" / "01-28TV全集"（结束集后面的文字忽略）。
+fn parse_episode_range(token: &str) -> Option<(u32, u32)> {
+  let (start, end) = token.split_once(['-', '~'])?;
+  let (start, _) = parse_episode(start)?;
+  let digits = end.find(|c: char| !c.is_ascii_digit()).unwrap_or(end.len());
+  let (end, suffix) = end.split_at(digits);
+  if suffix.chars().any(|c| c.is_ascii_digit()) {
+    return None;
+  }
+  let (end, _) = parse_episode(end)?;
+  (start < end).then_some((start, end))
+}
+
+// 中文集数/季数：第01话 / 第1集 / 第2季。返回 (数字, 是否为季)。
+fn parse_cjk_number(token: &str) -> Option<(u32, bool)> {
+  let rest = token.strip_prefix('第')?;
+  let (number, unit) = rest.split_at(rest.find(|c: char| !c.is_ascii_digit())?);
+  let number = parse_number(number)?;
+  match unit {
+    "话" | "話" | "集" => Some((number, false)),
+    "季" | "期" => Some((number, true)),
+    _ => None,
+  }
+}
+
+// 季数：s2 / s02，或 s01e05 这种合写。返回 (季, 集)。
+fn parse_season(token: &str) -> Option<(u32, Option<u32>)> {
+  let rest = token.strip_prefix('s')?;
+  match rest.split_once('e') {
+    Some((season, episode)) => Some((parse_number(season)?, Some(parse_number(episode)?))),
+    None => Some((parse_number(rest).filter(|season| *season < 100)?, None)),
+  }
+}
+
+// 标签类片段里常见的无关内容（CRC、招募公告、季度标记）。
+fn is_noise(text: &str) -> bool {
+  let is_crc = text.len() == 8 && text.chars().all(|c| c.is_ascii_hexdigit());
+  is_crc || text.contains("新番") || text.contains("招募") || text.contains('★')
+}
+
+// 记录一个词的识别结果，返回是否识别为标签。
+fn classify(info: &mut ReleaseInfo, token: &str) -> bool {
+  if let Some(resolution) = parse_resolution(token) {
+    info.resolution.get_or_insert(resolution);
+    return true;
+  }
+  if let Some(source) = parse_source(token) {
+    info.source.get_or_insert_with(|| source.to_string());
+    return true;
+  }
+  if let Some(codec) = parse_codec(token) {
+    info.video_codec.get_or_insert_with(|| codec.to_string());
+    return true;
+  }
+  let languages = parse_languages(token);
+  if !languages.is_empty() {
+    for language in languages {
+      if !info.languages.iter().any(|known| known == language) {
+        info.languages.push(language.to_string());
+      }
+    }
+    return true;
+  }
+  if BATCH_KEYWORDS.contains(&token) {
+    info.batch = true;
+    return true;
+  }
+  if let Some((season, episode)) = parse_season(token) {
+    info.season.get_or_insert(season);
+    if let Some(episode) = episode {
+      info.episode.get_or_insert(episode);
+    }
+    return true;
+  }
+  if let Some((number, is_season)) = parse_cjk_number(token) {
+    if is_season {
+      info.season.get_or_insert(number);
+    } else {
+      info.episode.get_or_insert(number);
+    }
+    return true;
+  }
+  false
+}
+
+// 整个片段是集数或集数范围（例如 [01]、[01v2]、[01-12]）时记录并返回 true。
+fn classify_episode(info: &mut ReleaseInfo, text: &str) -> bool {
+  let text = text.trim().to_lowercase();
+  if let Some((start, end)) = parse_episode_range(&text) {
+    info.episode.get_or_insert(start);
+    info.episode_end.get_or_insert(end);
+    info.batch = true;
+    return true;
+  }
+  if let Some((episode, version)) = parse_episode(&text) {
+    info.episode.get_or_insert(episode);
+    if version.is_some() {
+      info.version = version;
+    }
+    return true;
+  }
+  false
+}
+
+// 在普通文本中找 " - 01" 形式的集数标记，返回 (标记之前的作品名, 标记之后的文本)。
+fn split_dash_episode(info: &mut ReleaseInfo, text: &str) -> Option<(String, String)> {
+  let mut search_end = text.len();
+  while let Some(index) = text[..search_end].rfind(" - ") {
+    let after = text[index + 3..].trim_start();
+    let word = after.split_whitespace().next().unwrap_or_default();
+    if classify_episode(info, word) {
+      return Some((text[..index].to_string(), after[word.len()..].to_string()));
+    }
+    search_end = index;
+  }
+  None
+}
+
+// 没有 " - 01" 标记时，作品名取第一个标签词或集数之前的部分（集数会顺带记录），
+// 返回 (作品名, 其余文本)。Sousou.no.Frieren.S01E05 这种没有空格的写法按点号切分。
+fn split_leading_title(info: &mut ReleaseInfo, text: &str) -> (String, String) {
+  let words: Vec<&str> = if text.trim().contains(char::is_whitespace) {
+    text.split_whitespace().collect()
+  } else {
+    text.split(['.', '_']).filter(|word| !word.is_empty()).collect()
+  };
+  for (index, word) in words.iter().enumerate().skip(1) {
+    let lower = word.to_lowercase();
+    let mut probe = ReleaseInfo::default();
+    if classify(&mut probe, &lower) {
+      return (words[..index].join(" "), words[index..].join(" "));
+    }
+    if let Some((episode, version)) = parse_episode(&lower) {
+      if info.episode.is_none() {
+        info.episode = Some(episode);
+        info.version = version;
+      }
+      return (words[..index].join(" "), words[index + 1..].join(" "));
+    }
+  }
+  (words.join(" "), String::new())
+}
+
+// 去掉作品名首尾的空白与连接符。
+fn trim_title(title: &str) -> String {
+  title
+    .trim_matches(|c: char| c == '-' || c == '_' || c == '/' || c.is_whitespace())
+    .to_string()
+}
+
+// 解析发布标题。
+pub(crate) fn parse_release(name: &str) -> ReleaseInfo {
+  let mut info = ReleaseInfo::default();
+  let segments = split_segments(&normalize(name));
+  let mut title: Option<String> = None;
+
+  for (index, segment) in segments.iter().enumerate() {
+    match segment {
+      Segment::Bracket(text) => {
+        if is_noise(text) || classify_episode(&mut info, text) {
+          continue;
+        }
+        // 全部由标签或集数组成的片段（例如 [1080p][WebRip]、[01-28 Fin]）只提取信息；
+        // 已经有作品名后，其余片段也按标签处理（例如 [WebRip 1080p HEVC-10bit AAC]）。
+        let words = tokens(text);
+        let mut probe = ReleaseInfo::default();
+        let is_tags = words
+          .iter()
+          .all(|word| classify(&mut probe, word) || classify_episode(&mut probe, word));
+        if index == 0 && !is_tags {
+          // 开头的方括号是字幕组。
+          info.group = Some(text.clone());
+          continue;
+        }
+        let is_title = title.is_none() && !is_tags;
+        if is_title {
+          title = Some(trim_title(text));
+        }
+        for word in &words {
+          // 作品名里的数字不当作集数（例如 [Kaiju No. 8]）。
+          if !classify(&mut info, word) && !is_title {
+            classify_episode(&mut info, word);
+          }
+        }
+      }
+      Segment::Text(text) => {
+        if is_noise(text) {
+          continue;
+        }
+        let (leading, rest) = match split_dash_episode(&mut info, text) {
+          Some(split) => split,
+          None => split_leading_title(&mut info, text),
+        };
+        for word in tokens(&rest) {
+          classify(&mut info, &word);
+        }
+        let leading = trim_title(&leading);
+        if title.is_none() && !leading.is_empty() {
+          title = Some(leading);
+        }
+      }
+    }
+  }
+
+  info.title = title.unwrap_or_default();
+  info
+}
+
+// 解析种子名/文件名中的字幕组、集数、分辨率等信息。
+#[tauri::command]
+pub fn parse_release_title(name: String) -> ReleaseInfo {
+  parse_release(&name)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // 期望的解析结果（只列出关心的字段）。
+  struct Expected {
+    group: Option<&'static str>,
+    title: &'static str,
+    season: Option<u32>,
+    episode: Option<u32>,
+    episode_end: Option<u32>,
+    version: Option<u32>,
+    resolution: Option<&'static str>,
+    batch: bool,
+  }
+
+  const EMPTY: Expected = Expected {
+    group: None,
+    title: "",
+    season: None,
+    episode: None,
+    episode_end: None,
+    version: None,
+    resolution: None,
+    batch: false,
+  };
+
+  #[test]
+  fn parses_common_release_names() {
+    let cases = [
+      (
+        "[LoliHouse] Sousou no Frieren - 05 [WebRip 1080p HEVC-10bit AAC][简繁内封字幕].mkv",
+        Expected {
+          group: Some("LoliHouse"),
+          title: "Sousou no Frieren",
+          episode: Some(5),
+          resolution: Some("1080p"),
+          ..EMPTY
+        },
+      ),
+      (
+        "【喵萌奶茶屋】★04月新番★[葬送的芙莉莲][05][1080p][简日双语]",
+        Expected {
+          group: Some("喵萌奶茶屋"),
+          title: "葬送的芙莉莲",
+          episode: Some(5),
+          resolution: Some("1080p"),
+          ..EMPTY
+        },
+      ),
+      (
+        "[Nekomoe kissaten&LoliHouse] Kusuriya no Hitorigoto - 03v2 [WebRip 1080p HEVC-10bit AAC ASSx2].mkv",
+        Expected {
+          group: Some("Nekomoe kissaten&LoliHouse"),
+          title: "Kusuriya no Hitorigoto",
+          episode: Some(3),
+          version: Some(2),
+          resolution: Some("1080p"),
+          ..EMPTY
+        },
+      ),
+      (
+        "[Sakurato] Spy x Family [01-12][BDRip 1920x1080 HEVC-10bit FLAC][CHS]",
+        Expected {
+          group: Some("Sakurato"),
+          title: "Spy x Family",
+          episode: Some(1),
+          episode_end: Some(12),
+          resolution: Some("1080p"),
+          batch: true,
+          ..EMPTY
+        },
+      ),
+      (
+        "Sousou.no.Frieren.S01E05.1080p.WEB-DL.H.264.mkv",
+        Expected {
+          title: "Sousou no Frieren",
+          season: Some(1),
+          episode: Some(5),
+          resolution: Some("1080p"),
+          ..EMPTY
+        },
+      ),
+      (
+        "[ANi] 葬送的芙莉莲 第2季 - 第03话 [720P][Baha][WEB-DL][AAC AVC][CHT].mp4",
+        Expected {
+          group: Some("ANi"),
+          title: "葬送的芙莉莲",
+          season: Some(2),
+          episode: Some(3),
+          resolution: Some("720p"),
+          ..EMPTY
+        },
+      ),
+    ];
+    for (name, expected) in cases {
+      let info = parse_release(name);
+      assert_eq!(info.group.as_deref(), expected.group, "group: {name}");
+      assert_eq!(info.title, expected.title, "title: {name}");
+      assert_eq!(info.season, expected.season, "season: {name}");
+      assert_eq!(info.episode, expected.episode, "episode: {name}");
+      assert_eq!(info.episode_end, expected.episode_end, "episode_end: {name}");
+      assert_eq!(info.version, expected.version, "version: {name}");
+      assert_eq!(info.resolution.as_deref(), expected.resolution, "resolution: {name}");
+      assert_eq!(info.batch, expected.batch, "batch: {name}");
+    }
+  }
+
+  #[test]
+  fn parses_tags() {
+    let info = parse_release("[Group] Title - 01 [BDRip][HEVC-10bit][简繁日]");
+    assert_eq!(info.source.as_deref(), Some("BDRip"));
+    assert_eq!(info.video_codec.as_deref(), Some("HEVC"));
+    assert_eq!(info.languages, ["chs", "cht", "jpn"]);
+    assert!(parse_release("[Group] Title [Batch]").batch);
+  }
+
+  #[test]
+  fn parses_episode_tokens() {
+    let cases = [
+      ("01", Some((1, None))),
+      ("12v2", Some((12, Some(2)))),
+      ("ep07", Some((7, None))),
+      ("2024", None),
+      ("abc", None),
+    ];
+    for (token, expected) in cases {
+      assert_eq!(parse_episode(token), expected, "{token}");
+    }
+    assert_eq!(parse_episode_range("01-12"), Some((1, 12)));
+    assert_eq!(parse_episode_range("01~28tv全集"), Some((1, 28)));
+    assert_eq!(parse_episode_range("12-01"), None);
+  }
+}
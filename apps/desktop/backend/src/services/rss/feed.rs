@@ -0,0 +1,128 @@
+/*
+  RSS 源的拉取与解析：
+  把各站点的条目统一整理成 FeedItem（标题、下载地址、info hash 等）。
+  - 下载地址优先使用磁力链接，其次是 enclosure 里的 .torrent 地址；
+  - info hash 依次从磁力链接、Nyaa 的 nyaa:infoHash 扩展、
+    链接/GUID 里的 40 位十六进制串（Mikan 的 Episode 地址）中提取。
+*/
+
+use serde::Serialize;
+
+use crate::services::bangumi::shared_client;
+use crate::services::error::AppError;
+use crate::services::limits::send_limited;
+use crate::services::magnet::parse_magnet;
+use crate::services::release::{parse_release, ReleaseInfo};
+
+/// RSS 源中的一个条目。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedItem {
+  pub title: String,
+  pub guid: Option<String>,
+  /// 磁力链接或 .torrent 地址；条目没有可下载的地址时为空。
+  pub download_url: Option<String>,
+  /// 小写十六进制的 info hash（无法提取时为空）。
+  pub info_hash: Option<String>,
+  pub pub_date: Option<String>,
+  /// 大小（字节），来自 enclosure 的 length，站点未提供时为空。
+  pub size: Option<u64>,
+  /// 从标题解析出的字幕组、集数、分辨率等信息。
+  pub release: ReleaseInfo,
+}
+
+impl FeedItem {
+  // 去重用的键：GUID 与 info hash（加前缀区分，便于跨订阅按 info hash 去重）。
+  pub(super) fn dedupe_keys(&self) -> Vec<String> {
+    let mut keys = Vec::new();
+    if let Some(guid) = &self.guid {
+      keys.push(format!("guid:{guid}"));
+    }
+    if let Some(hash) = &self.info_hash {
+      keys.push(format!("btih:{hash}"));
+    }
+    keys
+  }
+}
+
+// 在文本中找 40 位十六进制串（按非字母数字字符切分）。
+fn find_hex_hash(text: &str) -> Option<String> {
+  text
+    .split(|c: char| !c.is_ascii_alphanumeric())
+    .find(|part| part.len() == 40 && part.chars().all(|c| c.is_ascii_hexdigit()))
+    .map(|part| part.to_ascii_lowercase())
+}
+
+// 把一个 RSS 条目整理成 FeedItem；没有标题的条目忽略。
+fn parse_item(item: &rss::Item) -> Option<FeedItem> {
+  let title = item.title()?.trim().to_string();
+  let guid = item.guid().map(|guid| guid.value().to_string());
+  let enclosure = item.enclosure();
+  let candidates: Vec<&str> = [item.link(), enclosure.map(|e| e.url())]
+    .into_iter()
+    .flatten()
+    .collect();
+
+  let magnet = candidates.iter().find(|url| url.starts_with("magnet:")).copied();
+  let torrent = candidates
+    .iter()
+    .copied()
+    .find(|url| url.starts_with("http") && url.ends_with(".torrent"))
+    .or_else(|| {
+      enclosure
+        .filter(|e| e.mime_type().contains("bittorrent"))
+        .map(|e| e.url())
+    });
+  let download_url = magnet.or(torrent).map(|url| url.to_string());
+
+  let nyaa_hash = item
+    .extensions()
+    .get("nyaa")
+    .and_then(|ext| ext.get("infoHash"))
+    .and_then(|values| values.first())
+    .and_then(|value| value.value())
+    .map(|hash| hash.trim().to_ascii_lowercase());
+  let info_hash = magnet
+    .and_then(|uri| parse_magnet(uri).ok())
+    .map(|info| info.info_hash.to_ascii_lowercase())
+    .or(nyaa_hash)
+    .or_else(|| {
+      candidates
+        .iter()
+        .copied()
+        .chain(guid.as_deref())
+        .find_map(find_hex_hash)
+    });
+
+  Some(FeedItem {
+    release: parse_release(&title),
+    title,
+    guid,
+    download_url,
+    info_hash,
+    pub_date: item.pub_date().map(|date| date.to_string()),
+    size: enclosure
+      .and_then(|e| e.length().parse::<u64>().ok())
+      .filter(|size| *size > 0),
+  })
+}
+
+// 拉取并解析 RSS 源，按源中的顺序返回条目（通常最新的在前）。
+pub(super) async fn fetch_feed(url: &str) -> Result<Vec<FeedItem>, AppError> {
+  let response = send_limited(shared_client().get(url))
+    .await
+    .map_err(AppError::network)?;
+  if !response.status().is_success() {
+    return Err(AppError::api(
+      response.status().as_u16(),
+      format!("RSS 请求失败: {}", response.status()),
+    ));
+  }
+  let body = response
+    .bytes()
+    .await
+    .map_err(|e| AppError::network(format!("读取 RSS 失败: {e}")))?;
+  let channel =
+    rss::Channel::read_from(&body[..]).map_err(|e| AppError::invalid_input(format!("RSS 格式无法识别: {e}")))?;
+  Ok(channel.items().iter().filter_map(parse_item).collect())
+}
@@ -0,0 +1,86 @@
+/*
+  订阅过滤条件：按标题匹配（不区分大小写）。
+  - keywords：全部出现才匹配；
+  - group：字幕组名出现在解析出的字幕组中（解析不出时在整个标题里找）；
+  - resolution：分辨率，例如 1080p，也接受 1920x1080 这种写法（优先比较解析出的分辨率）；
+  - exclude：任意一个出现就不匹配（例如 "合集"、"HEVC"）。
+*/
+
+use serde::{Deserialize, Serialize};
+
+use super::feed::FeedItem;
+
+/// 订阅的过滤条件，各项都为空表示匹配全部条目。
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RssFilter {
+  pub keywords: Vec<String>,
+  pub group: Option<String>,
+  pub resolution: Option<String>,
+  pub exclude: Vec<String>,
+}
+
+// 去掉空白项并统一为小写。
+fn clean(values: Vec<String>) -> Vec<String> {
+  values
+    .into_iter()
+    .map(|value| value.trim().to_lowercase())
+    .filter(|value| !value.is_empty())
+    .collect()
+}
+
+// 空白字符串视为未设置。
+fn clean_option(value: Option<String>) -> Option<String> {
+  value
+    .map(|value| value.trim().to_lowercase())
+    .filter(|value| !value.is_empty())
+}
+
+impl RssFilter {
+  // 修正输入：去掉空白项，统一为小写。
+  pub(super) fn normalized(self) -> Self {
+    Self {
+      keywords: clean(self.keywords),
+      group: clean_option(self.group),
+      resolution: clean_option(self.resolution),
+      exclude: clean(self.exclude),
+    }
+  }
+
+  // 条目是否满足过滤条件。
+  pub(super) fn matches(&self, item: &FeedItem) -> bool {
+    let title = item.title.to_lowercase();
+    if !self.keywords.iter().all(|keyword| title.contains(keyword.as_str())) {
+      return false;
+    }
+    if self.exclude.iter().any(|pattern| title.contains(pattern.as_str())) {
+      return false;
+    }
+    if let Some(group) = &self.group {
+      let matched = match &item.release.group {
+        Some(parsed) => parsed.to_lowercase().contains(group.as_str()),
+        None => title.contains(group.as_str()),
+      };
+      if !matched {
+        return false;
+      }
+    }
+    if let Some(resolution) = &self.resolution {
+      // 1080p / 1080 都按高度匹配：解析出的分辨率相同，或标题里出现 1080p / x1080 即可；
+      // 4K 等写法直接按文字匹配。
+      let height = resolution.trim_end_matches('p');
+      let matched = if !height.is_empty() && height.chars().all(|c| c.is_ascii_digit()) {
+        match &item.release.resolution {
+          Some(parsed) => *parsed == format!("{height}p"),
+          None => title.contains(&format!("{height}p")) || title.contains(&format!("x{height}")),
+        }
+      } else {
+        title.contains(resolution.as_str())
+      };
+      if !matched {
+        return false;
+      }
+    }
+    true
+  }
+}
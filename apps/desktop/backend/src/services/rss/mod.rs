@@ -0,0 +1,479 @@
+/*
+  RSS 订阅模块：
+  订阅 Mikan / Nyaa / 动漫花园等站点的 RSS，按订阅的过滤条件（关键词、字幕组、分辨率、排除词）
+  挑出新发布的种子并自动开始下载。
+  - 订阅保存在数据目录的 rss_subscriptions.json；
+  - 定时任务（TaskKind::RssRefresh）定期检查所有启用的订阅，也可以用 run_rss_now 立即检查；
+  - 以 GUID 与 info hash 去重：处理过的条目（包括被过滤掉的）记在订阅的 seen 里，
+    实际开始下载的 info hash 另记在 downloaded_hashes 里，其它订阅下载过的 info hash 也会跳过；
+    开始下载失败的条目不记录，下次重试；
+  - 下载目录为订阅的 outputDir（或设置里的下载目录），关联了追番条目时放到以条目名命名的子目录；
+  - 关联的追番条目设置了自动下载规则（见 rules 模块）时，同一集只下载规则选出的最佳版本；
+  - 开始下载后发送 rss://downloaded 事件，前端据此把任务加入下载列表并在完成后收尾；
+  - 订阅设置了后处理钩子时，开始的下载带上这些钩子，收尾后执行（见 hooks 模块）。
+*/
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Emitter;
+use tokio::sync::Mutex as AsyncMutex;
+
+use super::diagnostics::app_log;
+use super::error::AppError;
+use super::hooks::{assign_hooks, normalize_hooks, PostDownloadHook};
+use super::paths::ensure_path_configured;
+use super::release::ReleaseInfo;
+use super::rules::{find_rule, record_episodes, select_releases};
+use super::settings::load_settings;
+use super::storage::{data_file, load_tracked};
+use super::torrent::start_torrent_download;
+
+mod feed;
+mod filter;
+
+pub use feed::FeedItem;
+pub use filter::RssFilter;
+
+// 订阅数据文件名。
+const SUBSCRIPTIONS_FILE: &str = "rss_subscriptions.json";
+// 开始下载后发送给前端的事件名。
+const DOWNLOADED_EVENT: &str = "rss://downloaded";
+// 每个订阅最多保留的去重记录数（RSS 源通常只包含最近几十条）。
+const MAX_SEEN: usize = 1000;
+
+// 订阅文件读-改-写期间加锁。
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+// 同一时间只运行一次检查（定时任务与手动检查不会重复下载同一条目）。
+static RUN_LOCK: Lazy<AsyncMutex<()>> = Lazy::new(|| AsyncMutex::new(()));
+
+/// 一个 RSS 订阅。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RssSubscription {
+  pub id: u64,
+  pub name: String,
+  pub url: String,
+  /// 关联的追番条目（下载到以条目名命名的子目录）。
+  pub subject_id: Option<u32>,
+  /// 下载目录，为空时使用设置里的下载目录。
+  pub output_dir: Option<String>,
+  #[serde(default)]
+  pub filter: RssFilter,
+  pub enabled: bool,
+  /// 下载完成后执行的后处理钩子。
+  #[serde(default)]
+  pub hooks: Vec<PostDownloadHook>,
+  /// 已处理过的条目（guid:<GUID> / btih:<info hash>），按处理顺序。
+  #[serde(default)]
+  pub seen: Vec<String>,
+  /// 实际开始下载过的 info hash（小写），按下载顺序；跨订阅去重只看这里。
+  #[serde(default)]
+  pub downloaded_hashes: Vec<String>,
+  pub last_checked: Option<String>,
+  pub last_error: Option<String>,
+}
+
+/// 新增/修改订阅时前端传入的内容。
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RssSubscriptionInput {
+  pub name: String,
+  pub url: String,
+  pub subject_id: Option<u32>,
+  pub output_dir: Option<String>,
+  #[serde(default)]
+  pub filter: RssFilter,
+  #[serde(default = "default_enabled")]
+  pub enabled: bool,
+  #[serde(default)]
+  pub hooks: Vec<PostDownloadHook>,
+  /// 新增时是否下载源里已有的匹配条目；默认不下载，只从之后发布的条目开始。
+  #[serde(default)]
+  pub download_existing: bool,
+}
+
+fn default_enabled() -> bool {
+  true
+}
+
+/// 预览时的单个条目。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RssPreviewItem {
+  #[serde(flatten)]
+  pub item: FeedItem,
+  /// 是否满足过滤条件。
+  pub matched: bool,
+}
+
+/// rss://downloaded 事件内容（也是 run_rss_now 的返回值）。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RssDownloadedEvent {
+  pub subscription_id: u64,
+  pub subject_id: Option<u32>,
+  pub title: String,
+  pub url: String,
+  pub torrent_id: usize,
+  pub info_hash: String,
+  /// 临时下载目录与最终目录（完成后调用 finalize_torrent_download）。
+  pub output_folder: String,
+  pub final_folder: String,
+}
+
+// 读取全部订阅（文件不存在或为空时返回空列表）。
+fn load_subscriptions(app: &tauri::AppHandle) -> Result<Vec<RssSubscription>, AppError> {
+  let path = data_file(app, SUBSCRIPTIONS_FILE)?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| AppError::io(format!("读取 RSS 订阅失败: {e}")))?;
+  if content.trim().is_empty() {
+    return Ok(Vec::new());
+  }
+  serde_json::from_str(&content).map_err(|e| AppError::io(format!("解析 RSS 订阅失败: {e}")))
+}
+
+// 写回全部订阅。
+fn persist_subscriptions(app: &tauri::AppHandle, subscriptions: &[RssSubscription]) -> Result<(), AppError> {
+  let path = data_file(app, SUBSCRIPTIONS_FILE)?;
+  let payload =
+    serde_json::to_string_pretty(subscriptions).map_err(|e| AppError::internal(format!("序列化 RSS 订阅失败: {e}")))?;
+  fs::write(&path, payload).map_err(|e| AppError::io(format!("写入 RSS 订阅失败: {e}")))
+}
+
+// 在文件锁内读取、修改并写回订阅。
+fn modify_subscriptions<T>(
+  app: &tauri::AppHandle,
+  f: impl FnOnce(&mut Vec<RssSubscription>) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+  let _guard = FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let mut subscriptions = load_subscriptions(app)?;
+  let result = f(&mut subscriptions)?;
+  persist_subscriptions(app, &subscriptions)?;
+  Ok(result)
+}
+
+// 校验订阅内容：地址必须是 http(s)，下载目录必须位于设置里配置的目录下（重启后仍可访问）。
+fn validate_input(app: &tauri::AppHandle, input: &mut RssSubscriptionInput) -> Result<(), AppError> {
+  input.name = input.name.trim().to_string();
+  input.url = input.url.trim().to_string();
+  let url = url::Url::parse(&input.url).map_err(|e| AppError::invalid_input(format!("RSS 地址无效: {e}")))?;
+  if !matches!(url.scheme(), "http" | "https") {
+    return Err(AppError::invalid_input(format!("RSS 地址必须是 http(s): {}", input.url)));
+  }
+  if input.name.is_empty() {
+    input.name = url.host_str().unwrap_or("RSS").to_string();
+  }
+  input.output_dir = input
+    .output_dir
+    .take()
+    .map(|dir| dir.trim().to_string())
+    .filter(|dir| !dir.is_empty());
+  if let Some(dir) = &input.output_dir {
+    ensure_path_configured(app, dir).map_err(AppError::invalid_input)?;
+  }
+  input.filter = std::mem::take(&mut input.filter).normalized();
+  input.hooks = normalize_hooks(app, std::mem::take(&mut input.hooks))?;
+  Ok(())
+}
+
+// 文件名中不能出现的字符替换为下划线。
+pub(crate) fn sanitize_dir_name(name: &str) -> String {
+  name
+    .chars()
+    .map(|c| if matches!(c, '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+    .collect::<String>()
+    .trim()
+    .trim_end_matches('.')
+    .to_string()
+}
+
+// 订阅的下载目录：订阅目录或设置里的下载目录，关联了追番条目时加上条目名子目录。
+fn target_dir(app: &tauri::AppHandle, subscription: &RssSubscription) -> Result<String, AppError> {
+  let base = match &subscription.output_dir {
+    Some(dir) => dir.clone(),
+    None => load_settings(app).map_err(AppError::io)?
+      .download_dir
+      .ok_or_else(|| AppError::invalid_input("订阅未设置下载目录，设置里也没有默认下载目录"))?,
+  };
+  let mut dir = PathBuf::from(base);
+  if let Some(subject_id) = subscription.subject_id {
+    if let Some(subject) = load_tracked(app)?.get(&subject_id) {
+      let name = if subject.name_cn.trim().is_empty() {
+        &subject.name
+      } else {
+        &subject.name_cn
+      };
+      let name = sanitize_dir_name(name);
+      if !name.is_empty() {
+        dir.push(name);
+      }
+    }
+  }
+  Ok(dir.to_string_lossy().to_string())
+}
+
+// 检查结果：新处理的去重键、开始的下载与错误信息。
+struct CheckOutcome {
+  seen: Vec<String>,
+  downloaded: Vec<RssDownloadedEvent>,
+  error: Option<String>,
+}
+
+// 检查单个订阅：从旧到新处理源中未见过的条目，匹配的开始下载（有下载规则时先按规则挑选）。
+// downloaded_hashes 为所有订阅实际下载过的 info hash（跨订阅去重），会随本次下载更新。
+async fn check_subscription(
+  app: &tauri::AppHandle,
+  subscription: &RssSubscription,
+  downloaded_hashes: &mut HashSet<String>,
+) -> CheckOutcome {
+  let mut outcome = CheckOutcome {
+    seen: Vec::new(),
+    downloaded: Vec::new(),
+    error: None,
+  };
+  let items = match feed::fetch_feed(&subscription.url).await {
+    Ok(items) => items,
+    Err(err) => {
+      outcome.error = Some(err.to_string());
+      return outcome;
+    }
+  };
+  // 关联的追番条目设置了下载规则时，每集只下载规则选出的版本。
+  let rule = match subscription.subject_id.map(|id| find_rule(app, id)).transpose() {
+    Ok(rule) => rule.flatten(),
+    Err(err) => {
+      outcome.error = Some(err.to_string());
+      return outcome;
+    }
+  };
+  let seen: HashSet<&String> = subscription.seen.iter().collect();
+  // 先从旧到新挑出满足过滤条件的新条目。
+  let mut candidates = Vec::new();
+  for item in items.iter().rev() {
+    let keys = item.dedupe_keys();
+    if keys.is_empty() || keys.iter().any(|key| seen.contains(key)) {
+      continue;
+    }
+    let already_downloaded = item
+      .info_hash
+      .as_ref()
+      .is_some_and(|hash| downloaded_hashes.contains(hash));
+    let Some(url) = item.download_url.clone() else {
+      outcome.seen.extend(keys);
+      continue;
+    };
+    if !subscription.filter.matches(item) || already_downloaded {
+      outcome.seen.extend(keys);
+      continue;
+    }
+    candidates.push((item, url, keys));
+  }
+  if let Some(rule) = &rule {
+    let releases: Vec<&ReleaseInfo> = candidates.iter().map(|(item, _, _)| &item.release).collect();
+    let verdicts = select_releases(rule, &releases);
+    let mut selected = Vec::new();
+    for (candidate, reason) in candidates.into_iter().zip(verdicts) {
+      match reason {
+        None => selected.push(candidate),
+        Some(_) => outcome.seen.extend(candidate.2),
+      }
+    }
+    candidates = selected;
+  }
+
+  for (item, url, keys) in candidates {
+    let started = match target_dir(app, subscription) {
+      Ok(dir) => start_torrent_download(app.clone(), url.clone(), dir, None, None).await,
+      Err(err) => Err(err),
+    };
+    match started {
+      Ok(started) => {
+        outcome.seen.extend(keys);
+        downloaded_hashes.insert(started.info_hash.to_ascii_lowercase());
+        if let Err(err) = assign_hooks(app, &started.info_hash, &subscription.hooks) {
+          app_log!("设置后处理钩子失败: {err}");
+        }
+        if let Some(rule) = &rule {
+          if let Err(err) = record_episodes(app, rule.subject_id, &item.release) {
+            app_log!("记录已下载集数失败: {err}");
+          }
+        }
+        let event = RssDownloadedEvent {
+          subscription_id: subscription.id,
+          subject_id: subscription.subject_id,
+          title: item.title.clone(),
+          url,
+          torrent_id: started.id,
+          info_hash: started.info_hash,
+          output_folder: started.output_folder,
+          final_folder: started.final_folder,
+        };
+        if let Err(err) = app.emit(DOWNLOADED_EVENT, &event) {
+          app_log!("发送 RSS 下载事件失败: {err}");
+        }
+        outcome.downloaded.push(event);
+      }
+      // 开始失败的条目不记录，下次检查时重试。
+      Err(err) => outcome.error = Some(format!("{}: {err}", item.title)),
+    }
+  }
+  outcome
+}
+
+// 检查订阅（only 为空时检查全部启用的订阅），返回本次开始的下载。
+async fn run_subscriptions(app: &tauri::AppHandle, only: Option<u64>) -> Result<Vec<RssDownloadedEvent>, AppError> {
+  let _running = RUN_LOCK.lock().await;
+  let subscriptions = load_subscriptions(app)?;
+  // 只用真正开始过的下载去重：seen 里还有被过滤条件或下载规则跳过的条目，
+  // 用它去重会让其它订阅也跳过这些从没下载过的种子。
+  let mut downloaded_hashes: HashSet<String> = subscriptions
+    .iter()
+    .flat_map(|subscription| subscription.downloaded_hashes.iter().cloned())
+    .collect();
+
+  let mut results = Vec::new();
+  for subscription in &subscriptions {
+    let selected = match only {
+      Some(id) => subscription.id == id,
+      None => subscription.enabled,
+    };
+    if !selected {
+      continue;
+    }
+    let outcome = check_subscription(app, subscription, &mut downloaded_hashes).await;
+    // 检查期间订阅可能被修改或删除，按 ID 合并结果。
+    modify_subscriptions(app, |all| {
+      if let Some(target) = all.iter_mut().find(|s| s.id == subscription.id) {
+        target.seen.extend(outcome.seen);
+        let overflow = target.seen.len().saturating_sub(MAX_SEEN);
+        target.seen.drain(..overflow);
+        target.downloaded_hashes.extend(
+          outcome
+            .downloaded
+            .iter()
+            .map(|event| event.info_hash.to_ascii_lowercase()),
+        );
+        let overflow = target.downloaded_hashes.len().saturating_sub(MAX_SEEN);
+        target.downloaded_hashes.drain(..overflow);
+        target.last_checked = Some(Utc::now().to_rfc3339());
+        target.last_error = outcome.error;
+      }
+      Ok(())
+    })?;
+    results.extend(outcome.downloaded);
+  }
+  Ok(results)
+}
+
+// 定时任务：检查所有启用的订阅。
+pub(crate) async fn refresh_rss_subscriptions(app: &tauri::AppHandle) -> Result<(), AppError> {
+  run_subscriptions(app, None).await?;
+  Ok(())
+}
+
+// 返回所有订阅。
+#[tauri::command]
+pub fn list_rss_subscriptions(app: tauri::AppHandle) -> Result<Vec<RssSubscription>, AppError> {
+  load_subscriptions(&app)
+}
+
+// 新增订阅。默认把源里已有的条目标记为已处理，只下载之后发布的条目。
+#[tauri::command]
+pub async fn add_rss_subscription(
+  app: tauri::AppHandle,
+  subscription: RssSubscriptionInput,
+) -> Result<RssSubscription, AppError> {
+  let mut input = subscription;
+  validate_input(&app, &mut input)?;
+  let seen = if input.download_existing {
+    Vec::new()
+  } else {
+    feed::fetch_feed(&input.url)
+      .await?
+      .iter()
+      .flat_map(|item| item.dedupe_keys())
+      .collect()
+  };
+  modify_subscriptions(&app, |all| {
+    let id = all.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+    let created = RssSubscription {
+      id,
+      name: input.name,
+      url: input.url,
+      subject_id: input.subject_id,
+      output_dir: input.output_dir,
+      filter: input.filter,
+      enabled: input.enabled,
+      hooks: input.hooks,
+      seen,
+      downloaded_hashes: Vec::new(),
+      last_checked: None,
+      last_error: None,
+    };
+    all.push(created.clone());
+    Ok(created)
+  })
+}
+
+// 修改订阅（已处理记录保留）。
+#[tauri::command]
+pub fn update_rss_subscription(
+  app: tauri::AppHandle,
+  id: u64,
+  subscription: RssSubscriptionInput,
+) -> Result<RssSubscription, AppError> {
+  let mut input = subscription;
+  validate_input(&app, &mut input)?;
+  modify_subscriptions(&app, |all| {
+    let target = all
+      .iter_mut()
+      .find(|s| s.id == id)
+      .ok_or_else(|| AppError::invalid_input(format!("订阅不存在: {id}")))?;
+    target.name = input.name;
+    target.url = input.url;
+    target.subject_id = input.subject_id;
+    target.output_dir = input.output_dir;
+    target.filter = input.filter;
+    target.enabled = input.enabled;
+    target.hooks = input.hooks;
+    Ok(target.clone())
+  })
+}
+
+// 删除订阅（不影响已开始的下载）。
+#[tauri::command]
+pub fn delete_rss_subscription(app: tauri::AppHandle, id: u64) -> Result<(), AppError> {
+  modify_subscriptions(&app, |all| {
+    all.retain(|s| s.id != id);
+    Ok(())
+  })
+}
+
+// 预览 RSS 源中的条目以及哪些满足过滤条件（不下载、不修改订阅）。
+#[tauri::command]
+pub async fn preview_rss_matches(url: String, filter: Option<RssFilter>) -> Result<Vec<RssPreviewItem>, AppError> {
+  let filter = filter.unwrap_or_default().normalized();
+  let items = feed::fetch_feed(url.trim()).await?;
+  Ok(
+    items
+      .into_iter()
+      .map(|item| RssPreviewItem {
+        matched: item.download_url.is_some() && filter.matches(&item),
+        item,
+      })
+      .collect(),
+  )
+}
+
+// 立即检查订阅（不传 id 时检查全部启用的订阅），返回本次开始的下载。
+#[tauri::command]
+pub async fn run_rss_now(app: tauri::AppHandle, id: Option<u64>) -> Result<Vec<RssDownloadedEvent>, AppError> {
+  run_subscriptions(&app, id).await
+}
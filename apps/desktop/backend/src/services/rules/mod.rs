@@ -0,0 +1,312 @@
+/*
+  自动下载规则：
+  为追番条目设置偏好的字幕组、分辨率、视频编码与字幕语言，RSS 订阅关联了该条目时，
+  每一集只下载规则选出的最佳版本，同一集的其它版本（包括之后发布的）都跳过。
+  - 规则保存在数据目录的 download_rules.json，每个追番条目一条；
+  - 各偏好列表越靠前越优先，为空表示不限；
+    标题里识别出的值不在列表中时不下载，识别不出时可以下载，但排在列表中的值之后；
+  - 比较顺序：字幕组 > 分辨率 > 视频编码 > 字幕语言 > 修正版本（v2 优先），完全相同时取先发布的；
+  - 识别不出集数的条目不下载；合集默认不下载（allowBatch 为 true 时按其包含的集数处理）；
+  - 已下载的集数记在规则的 downloadedEpisodes 里，开始下载后由 RSS 模块记录。
+*/
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::fs;
+use std::sync::Mutex;
+
+use super::error::AppError;
+use super::release::{parse_codec, parse_languages, parse_release, parse_resolution, ReleaseInfo};
+use super::storage::{data_file, load_tracked};
+
+// 规则数据文件名。
+const RULES_FILE: &str = "download_rules.json";
+
+// 规则文件读-改-写期间加锁。
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 一个追番条目的自动下载规则。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadRule {
+  pub subject_id: u32,
+  #[serde(default = "default_enabled")]
+  pub enabled: bool,
+  /// 偏好的字幕组（标题解析出的字幕组包含该名称即可），越靠前越优先。
+  #[serde(default)]
+  pub groups: Vec<String>,
+  /// 偏好的分辨率（1080p / 720p / 2160p）。
+  #[serde(default)]
+  pub resolutions: Vec<String>,
+  /// 偏好的视频编码（HEVC / AVC / AV1）。
+  #[serde(default)]
+  pub codecs: Vec<String>,
+  /// 偏好的字幕语言（chs / cht / jpn / eng）。
+  #[serde(default)]
+  pub languages: Vec<String>,
+  /// 是否接受合集。
+  #[serde(default)]
+  pub allow_batch: bool,
+  /// 已下载的集数（新增规则时可以传入已有的集数，修改规则时保留原有记录）。
+  #[serde(default)]
+  pub downloaded_episodes: Vec<u32>,
+}
+
+fn default_enabled() -> bool {
+  true
+}
+
+/// test_rule 中单个标题的判断结果。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestItem {
+  pub title: String,
+  pub release: ReleaseInfo,
+  /// 是否会被下载。
+  pub selected: bool,
+  /// 不下载的原因。
+  pub reason: Option<String>,
+}
+
+// 排序用的分数，越小越优先。
+type Score = (usize, usize, usize, usize, Reverse<u32>);
+
+// 读取全部规则（文件不存在或为空时返回空列表）。
+fn load_rules(app: &tauri::AppHandle) -> Result<Vec<DownloadRule>, AppError> {
+  let path = data_file(app, RULES_FILE)?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| AppError::io(format!("读取下载规则失败: {e}")))?;
+  if content.trim().is_empty() {
+    return Ok(Vec::new());
+  }
+  serde_json::from_str(&content).map_err(|e| AppError::io(format!("解析下载规则失败: {e}")))
+}
+
+// 在文件锁内读取、修改并写回规则。
+fn modify_rules<T>(
+  app: &tauri::AppHandle,
+  f: impl FnOnce(&mut Vec<DownloadRule>) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+  let _guard = FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let mut rules = load_rules(app)?;
+  let result = f(&mut rules)?;
+  let path = data_file(app, RULES_FILE)?;
+  let payload = serde_json::to_string_pretty(&rules).map_err(|e| AppError::internal(format!("序列化下载规则失败: {e}")))?;
+  fs::write(&path, payload).map_err(|e| AppError::io(format!("写入下载规则失败: {e}")))?;
+  Ok(result)
+}
+
+// 去掉空白项与重复项，保持原有顺序。
+fn clean(values: Vec<String>, normalize: impl Fn(&str) -> String) -> Vec<String> {
+  let mut cleaned: Vec<String> = Vec::new();
+  for value in values {
+    let value = normalize(value.trim());
+    if !value.is_empty() && !cleaned.contains(&value) {
+      cleaned.push(value);
+    }
+  }
+  cleaned
+}
+
+// 分辨率统一为 1080p 写法（只写 1080 也可以）。
+fn normalize_resolution(value: &str) -> String {
+  let value = value.to_lowercase();
+  let value = if value.chars().all(|c| c.is_ascii_digit()) {
+    format!("{value}p")
+  } else {
+    value
+  };
+  parse_resolution(&value).unwrap_or(value)
+}
+
+// 编码统一为 HEVC / AVC / AV1（也接受 x265、h264 等写法）。
+fn normalize_codec(value: &str) -> String {
+  let value = value.to_lowercase();
+  parse_codec(&value).map(str::to_string).unwrap_or(value)
+}
+
+// 语言统一为 chs / cht / jpn / eng（也接受 简体、繁體 等写法）。
+fn normalize_language(value: &str) -> String {
+  let value = value.to_lowercase();
+  match parse_languages(&value).as_slice() {
+    [language] => language.to_string(),
+    _ => value,
+  }
+}
+
+impl DownloadRule {
+  // 修正输入：去掉空白项，统一各项写法。
+  fn normalized(self) -> Self {
+    Self {
+      groups: clean(self.groups, |value| value.to_string()),
+      resolutions: clean(self.resolutions, normalize_resolution),
+      codecs: clean(self.codecs, normalize_codec),
+      languages: clean(self.languages, normalize_language),
+      ..self
+    }
+  }
+
+  // 判断一个发布是否可以下载，返回 (包含的集数, 分数)，不能下载时返回原因。
+  fn evaluate(&self, release: &ReleaseInfo) -> Result<(Vec<u32>, Score), String> {
+    let Some(episode) = release.episode else {
+      return Err("无法识别集数".to_string());
+    };
+    if release.batch && !self.allow_batch {
+      return Err("规则不接受合集".to_string());
+    }
+    // 合集按其包含的每一集处理。
+    let episodes = match release.episode_end {
+      Some(end) => (episode..=end).collect(),
+      None => vec![episode],
+    };
+    let group = rank(&self.groups, release.group.as_deref(), |preferred, value| {
+      value.to_lowercase().contains(&preferred.to_lowercase())
+    })
+    .ok_or_else(|| format!("字幕组不在偏好列表中: {}", release.group.as_deref().unwrap_or_default()))?;
+    let resolution = rank(&self.resolutions, release.resolution.as_deref(), |preferred, value| {
+      preferred == value
+    })
+    .ok_or_else(|| format!("分辨率不在偏好列表中: {}", release.resolution.as_deref().unwrap_or_default()))?;
+    let codec = rank(&self.codecs, release.video_codec.as_deref(), |preferred, value| {
+      preferred.eq_ignore_ascii_case(value)
+    })
+    .ok_or_else(|| format!("视频编码不在偏好列表中: {}", release.video_codec.as_deref().unwrap_or_default()))?;
+    let language = if release.languages.is_empty() {
+      rank(&self.languages, None, |_, _| false)
+    } else {
+      release
+        .languages
+        .iter()
+        .filter_map(|value| rank(&self.languages, Some(value.as_str()), |preferred, value| preferred == value))
+        .min()
+    }
+    .ok_or_else(|| format!("字幕语言不在偏好列表中: {}", release.languages.join("/")))?;
+    Ok((episodes, (group, resolution, codec, language, Reverse(release.version.unwrap_or(1)))))
+  }
+}
+
+// 值在偏好列表中的位置：列表为空时为 0，识别不出时排在所有偏好之后，不在列表中时为 None。
+fn rank(preferred: &[String], value: Option<&str>, matches: impl Fn(&str, &str) -> bool) -> Option<usize> {
+  if preferred.is_empty() {
+    return Some(0);
+  }
+  let Some(value) = value else {
+    return Some(preferred.len());
+  };
+  preferred.iter().position(|item| matches(item, value))
+}
+
+// 按规则从一批发布（按发布时间从旧到新）中挑出要下载的，返回每个发布不下载的原因（None 表示下载）。
+// 同一集只选一个版本，已下载过的集数全部跳过。
+pub(crate) fn select_releases(rule: &DownloadRule, releases: &[&ReleaseInfo]) -> Vec<Option<String>> {
+  let evaluated: Vec<_> = releases.iter().map(|release| rule.evaluate(release)).collect();
+  let mut verdicts: Vec<Option<String>> = evaluated
+    .iter()
+    .map(|result| result.as_ref().err().cloned())
+    .collect();
+
+  // 分数相同时保留原有顺序（先发布的优先）。
+  let mut order: Vec<usize> = (0..releases.len()).filter(|index| evaluated[*index].is_ok()).collect();
+  order.sort_by_key(|index| evaluated[*index].as_ref().map(|(_, score)| *score).ok());
+
+  let downloaded: BTreeSet<u32> = rule.downloaded_episodes.iter().copied().collect();
+  let mut taken = BTreeSet::new();
+  for index in order {
+    let Ok((episodes, _)) = &evaluated[index] else {
+      continue;
+    };
+    if let Some(episode) = episodes.iter().find(|episode| downloaded.contains(*episode)) {
+      verdicts[index] = Some(format!("第 {episode} 集已下载"));
+    } else if let Some(episode) = episodes.iter().find(|episode| taken.contains(*episode)) {
+      verdicts[index] = Some(format!("第 {episode} 集已选择更合适的版本"));
+    } else {
+      taken.extend(episodes.iter().copied());
+    }
+  }
+  verdicts
+}
+
+// 追番条目启用的规则（没有规则或已停用时为 None）。
+pub(crate) fn find_rule(app: &tauri::AppHandle, subject_id: u32) -> Result<Option<DownloadRule>, AppError> {
+  Ok(
+    load_rules(app)?
+      .into_iter()
+      .find(|rule| rule.subject_id == subject_id && rule.enabled),
+  )
+}
+
+// 记录已开始下载的集数。
+pub(crate) fn record_episodes(app: &tauri::AppHandle, subject_id: u32, release: &ReleaseInfo) -> Result<(), AppError> {
+  let Some(start) = release.episode else {
+    return Ok(());
+  };
+  let end = release.episode_end.unwrap_or(start).max(start);
+  modify_rules(app, |rules| {
+    if let Some(rule) = rules.iter_mut().find(|rule| rule.subject_id == subject_id) {
+      let mut episodes: BTreeSet<u32> = rule.downloaded_episodes.iter().copied().collect();
+      episodes.extend(start..=end);
+      rule.downloaded_episodes = episodes.into_iter().collect();
+    }
+    Ok(())
+  })
+}
+
+// 返回所有规则。
+#[tauri::command]
+pub fn list_download_rules(app: tauri::AppHandle) -> Result<Vec<DownloadRule>, AppError> {
+  load_rules(&app)
+}
+
+// 新增或修改追番条目的规则（修改时已下载的集数保留原有记录）。
+#[tauri::command]
+pub fn save_download_rule(app: tauri::AppHandle, rule: DownloadRule) -> Result<DownloadRule, AppError> {
+  if !load_tracked(&app)?.contains_key(&rule.subject_id) {
+    return Err(AppError::invalid_input(format!("追番条目不存在: {}", rule.subject_id)));
+  }
+  let mut rule = rule.normalized();
+  modify_rules(&app, |rules| {
+    match rules.iter_mut().find(|existing| existing.subject_id == rule.subject_id) {
+      Some(existing) => {
+        rule.downloaded_episodes = std::mem::take(&mut existing.downloaded_episodes);
+        *existing = rule.clone();
+      }
+      None => {
+        let episodes: BTreeSet<u32> = rule.downloaded_episodes.iter().copied().collect();
+        rule.downloaded_episodes = episodes.into_iter().collect();
+        rules.push(rule.clone());
+      }
+    }
+    Ok(rule)
+  })
+}
+
+// 删除追番条目的规则。
+#[tauri::command]
+pub fn delete_download_rule(app: tauri::AppHandle, subject_id: u32) -> Result<(), AppError> {
+  modify_rules(&app, |rules| {
+    rules.retain(|rule| rule.subject_id != subject_id);
+    Ok(())
+  })
+}
+
+// 用示例标题预览规则的选择结果（按传入顺序视为发布顺序，不修改规则）。
+#[tauri::command]
+pub fn test_rule(rule: DownloadRule, sample_titles: Vec<String>) -> Vec<RuleTestItem> {
+  let rule = rule.normalized();
+  let releases: Vec<ReleaseInfo> = sample_titles.iter().map(|title| parse_release(title)).collect();
+  let verdicts = select_releases(&rule, &releases.iter().collect::<Vec<_>>());
+  sample_titles
+    .into_iter()
+    .zip(releases)
+    .zip(verdicts)
+    .map(|((title, release), reason)| RuleTestItem {
+      title,
+      release,
+      selected: reason.is_none(),
+      reason,
+    })
+    .collect()
+}
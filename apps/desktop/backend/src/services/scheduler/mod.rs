@@ -0,0 +1,356 @@
+/*
+  后台定时任务模块：
+  按 cron 表达式周期性执行维护类任务，例如：
+  - 季度番剧列表刷新；
+  - 追番条目元数据（已播/总集数）刷新；
+  - 本地数据备份与轮换；
+  - 公共 tracker 列表拉取（设置里配置了地址时）；
+  - RSS 订阅检查（自动下载新发布的剧集）；
+  - IP 黑名单重新拉取（设置了黑名单来源时）。
+  任务的启用状态与周期保存在设置里（AppSettings.scheduled_tasks），
+  运行状态（上次运行、下次运行、错误）只保存在内存中。
+
+  cron 表达式使用 6 段格式：秒 分 时 日 月 周，按本地时间计算。
+  例如 "0 0 3 * * *" 表示每天凌晨 3 点。
+*/
+
+use chrono::{DateTime, Datelike, Local};
+use cron::Schedule;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Manager;
+
+use super::bangumi::commands::{get_season_subjects, get_subject_aired_count};
+use super::diagnostics::app_log;
+use super::error::AppError;
+use super::rss::refresh_rss_subscriptions;
+use super::settings::load_settings;
+use super::storage::{load_tracked, persist_tracked};
+use super::torrent::{refresh_blocklist, refresh_tracker_list};
+
+// 调度循环的检查间隔。
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+// 备份目录名（相对于数据目录）。
+const BACKUP_DIR: &str = "backups";
+// 最多保留的备份份数。
+const BACKUP_KEEP: usize = 7;
+// 需要备份的数据文件。
+const BACKUP_FILES: &[&str] = &["watchlist.json", "settings.json"];
+
+/// 定时任务类型。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskKind {
+  /// 刷新当前季度的番剧列表。
+  SeasonRefresh,
+  /// 刷新追番条目的已播/总集数。
+  TrackedMetadataRefresh,
+  /// 备份本地数据并删除过旧的备份。
+  BackupRotation,
+  /// 拉取公共 tracker 列表。
+  TrackerListRefresh,
+  /// 检查 RSS 订阅并下载新条目。
+  RssRefresh,
+  /// 重新拉取 IP 黑名单。
+  BlocklistRefresh,
+}
+
+impl TaskKind {
+  // 所有任务类型（决定列表展示顺序）。
+  const ALL: [TaskKind; 6] = [
+    TaskKind::SeasonRefresh,
+    TaskKind::TrackedMetadataRefresh,
+    TaskKind::BackupRotation,
+    TaskKind::TrackerListRefresh,
+    TaskKind::RssRefresh,
+    TaskKind::BlocklistRefresh,
+  ];
+
+  // 默认周期（cron 表达式）。
+  fn default_schedule(self) -> &'static str {
+    match self {
+      TaskKind::SeasonRefresh => "0 0 3 * * *",
+      TaskKind::TrackedMetadataRefresh => "0 30 */6 * * *",
+      TaskKind::BackupRotation => "0 0 4 * * *",
+      TaskKind::TrackerListRefresh => "0 0 5 * * *",
+      TaskKind::RssRefresh => "0 */30 * * * *",
+      // 每周一早上 6 点。
+      TaskKind::BlocklistRefresh => "0 0 6 * * Mon",
+    }
+  }
+}
+
+/// 单个定时任务的配置（保存在设置里）。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTaskConfig {
+  pub kind: TaskKind,
+  pub enabled: bool,
+  pub schedule: String,
+}
+
+/// 定时任务信息（返回给前端）。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTaskInfo {
+  pub kind: TaskKind,
+  pub enabled: bool,
+  pub schedule: String,
+  pub running: bool,
+  pub next_run: Option<String>,
+  pub last_run: Option<String>,
+  pub last_error: Option<String>,
+}
+
+// 单个任务的运行状态（仅内存）。
+#[derive(Default)]
+struct TaskRuntime {
+  // 计算 next_run 时使用的表达式；配置变化时据此重新计算。
+  schedule: String,
+  next_run: Option<DateTime<Local>>,
+  last_run: Option<DateTime<Local>>,
+  last_error: Option<String>,
+  running: bool,
+}
+
+static RUNTIME: Lazy<Mutex<HashMap<TaskKind, TaskRuntime>>> =
+  Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 默认任务列表：全部启用，使用默认周期。
+pub(crate) fn default_scheduled_tasks() -> Vec<ScheduledTaskConfig> {
+  TaskKind::ALL
+    .iter()
+    .map(|kind| ScheduledTaskConfig {
+      kind: *kind,
+      enabled: true,
+      schedule: kind.default_schedule().to_string(),
+    })
+    .collect()
+}
+
+// 校验 cron 表达式是否合法。
+pub(crate) fn validate_schedule(expr: &str) -> Result<(), String> {
+  Schedule::from_str(expr)
+    .map(|_| ())
+    .map_err(|e| format!("定时任务周期格式错误 ({expr}): {e}"))
+}
+
+// 计算某个表达式在 after 之后的下一次触发时间。
+fn next_after(expr: &str, after: DateTime<Local>) -> Option<DateTime<Local>> {
+  Schedule::from_str(expr).ok()?.after(&after).next()
+}
+
+// 合并设置与默认值：设置里缺失的任务类型（例如新版本新增的任务）使用默认配置。
+fn effective_tasks(app: &tauri::AppHandle) -> Vec<ScheduledTaskConfig> {
+  let configured = load_settings(app)
+    .map(|settings| settings.scheduled_tasks)
+    .unwrap_or_default();
+  default_scheduled_tasks()
+    .into_iter()
+    .map(|default| {
+      configured
+        .iter()
+        .find(|task| task.kind == default.kind)
+        .cloned()
+        .unwrap_or(default)
+    })
+    .collect()
+}
+
+// 当前季度：(年份, 季节字符串)。
+fn current_season() -> (u32, String) {
+  let now = Local::now();
+  let season = match now.month() {
+    1..=3 => "winter",
+    4..=6 => "spring",
+    7..=9 => "summer",
+    _ => "autumn",
+  };
+  (now.year() as u32, season.to_string())
+}
+
+// 任务：刷新当前季度番剧列表（跳过缓存，顺便更新磁盘缓存）。
+async fn run_season_refresh() -> Result<(), AppError> {
+  let (year, season) = current_season();
+  get_season_subjects(year, season, None, Some(true)).await.map(|_| ())
+}
+
+// 任务：刷新“正在追”条目的已播/总集数并写回本地。
+// 单个条目失败不影响其它条目，最后汇总报告失败数量。
+async fn run_tracked_metadata_refresh(app: &tauri::AppHandle) -> Result<(), AppError> {
+  let ids: Vec<u32> = load_tracked(app)?
+    .values()
+    .filter(|subject| subject.watching)
+    .map(|subject| subject.id)
+    .collect();
+  let mut updates = Vec::new();
+  let mut failed = 0usize;
+  for id in ids {
+    match get_subject_aired_count(id).await {
+      Ok(aired) => updates.push(aired),
+      Err(_) => failed += 1,
+    }
+  }
+  // 网络请求期间用户可能修改了追番列表，所以重新读取后再合并。
+  let mut data = load_tracked(app)?;
+  for aired in updates {
+    if let Some(subject) = data.get_mut(&aired.id) {
+      subject.aired_count = Some(aired.aired_count);
+      subject.total_count = Some(aired.total_count);
+    }
+  }
+  persist_tracked(app, &data)?;
+  if failed > 0 {
+    return Err(AppError::network(format!("{failed} 个条目刷新失败")));
+  }
+  Ok(())
+}
+
+// 任务：把数据文件复制到 backups/<时间戳>/，只保留最近 BACKUP_KEEP 份。
+fn run_backup_rotation(app: &tauri::AppHandle) -> Result<(), String> {
+  let data_dir = app
+    .path()
+    .app_data_dir()
+    .map_err(|e| format!("无法获取数据目录: {e}"))?
+    .join("hanamirip-cn");
+  let backup_root = data_dir.join(BACKUP_DIR);
+  let target = backup_root.join(Local::now().format("%Y%m%d-%H%M%S").to_string());
+  fs::create_dir_all(&target).map_err(|e| format!("创建备份目录失败: {e}"))?;
+  for name in BACKUP_FILES {
+    let source = data_dir.join(name);
+    if source.exists() {
+      fs::copy(&source, target.join(name)).map_err(|e| format!("备份 {name} 失败: {e}"))?;
+    }
+  }
+
+  // 目录名就是时间戳，按名称排序即按时间排序，删除最旧的多余备份。
+  let mut backups: Vec<_> = fs::read_dir(&backup_root)
+    .map_err(|e| format!("读取备份目录失败: {e}"))?
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| entry.path().is_dir())
+    .map(|entry| entry.path())
+    .collect();
+  backups.sort();
+  let excess = backups.len().saturating_sub(BACKUP_KEEP);
+  for path in backups.into_iter().take(excess) {
+    let _ = fs::remove_dir_all(path);
+  }
+  Ok(())
+}
+
+// 执行某个任务，并更新运行状态。
+// 同一任务已在运行时直接返回错误，避免重复执行。
+async fn execute(app: &tauri::AppHandle, kind: TaskKind) -> Result<(), AppError> {
+  {
+    let mut runtime = RUNTIME.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = runtime.entry(kind).or_default();
+    if entry.running {
+      return Err(AppError::invalid_input("任务正在运行"));
+    }
+    entry.running = true;
+  }
+
+  let result = match kind {
+    TaskKind::SeasonRefresh => run_season_refresh().await,
+    TaskKind::TrackedMetadataRefresh => run_tracked_metadata_refresh(app).await,
+    TaskKind::BackupRotation => run_backup_rotation(app).map_err(AppError::io),
+    TaskKind::TrackerListRefresh => refresh_tracker_list(app).await,
+    TaskKind::RssRefresh => refresh_rss_subscriptions(app).await,
+    TaskKind::BlocklistRefresh => refresh_blocklist(app).await,
+  };
+
+  let mut runtime = RUNTIME.lock().unwrap_or_else(|e| e.into_inner());
+  let entry = runtime.entry(kind).or_default();
+  let now = Local::now();
+  entry.running = false;
+  entry.last_run = Some(now);
+  entry.last_error = result.as_ref().err().map(AppError::to_string);
+  entry.next_run = next_after(&entry.schedule, now);
+  result
+}
+
+// 一次调度检查：找出已到期的启用任务并依次执行。
+async fn tick(app: &tauri::AppHandle) {
+  let now = Local::now();
+  let mut due = Vec::new();
+  {
+    let mut runtime = RUNTIME.lock().unwrap_or_else(|e| e.into_inner());
+    for task in effective_tasks(app) {
+      let entry = runtime.entry(task.kind).or_default();
+      // 周期变化（或首次出现）时重新计算下次运行时间。
+      if entry.schedule != task.schedule {
+        entry.schedule = task.schedule.clone();
+        entry.next_run = next_after(&task.schedule, now);
+      }
+      if !task.enabled || entry.running {
+        continue;
+      }
+      if entry.next_run.map(|next| next <= now).unwrap_or(false) {
+        due.push(task.kind);
+      }
+    }
+  }
+  for kind in due {
+    if let Err(err) = execute(app, kind).await {
+      app_log!("scheduled task failed: {err}");
+    }
+  }
+}
+
+// 启动调度循环（应用启动时调用一次）。
+pub(crate) fn start_scheduler(app: tauri::AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tick(&app).await;
+      tokio::time::sleep(TICK_INTERVAL).await;
+    }
+  });
+}
+
+// 列出所有定时任务及其运行状态。
+#[tauri::command]
+pub fn list_scheduled_tasks(app: tauri::AppHandle) -> Result<Vec<ScheduledTaskInfo>, AppError> {
+  let now = Local::now();
+  let runtime = RUNTIME.lock().unwrap_or_else(|e| e.into_inner());
+  let tasks = effective_tasks(&app)
+    .into_iter()
+    .map(|task| {
+      let entry = runtime.get(&task.kind);
+      // 调度循环尚未计算过（或周期刚改过）时，这里临时算一次下次运行时间。
+      let next_run = match entry {
+        Some(entry) if entry.schedule == task.schedule => entry.next_run,
+        _ => next_after(&task.schedule, now),
+      };
+      ScheduledTaskInfo {
+        kind: task.kind,
+        enabled: task.enabled,
+        schedule: task.schedule,
+        running: entry.map(|e| e.running).unwrap_or(false),
+        next_run: next_run.filter(|_| task.enabled).map(|t| t.to_rfc3339()),
+        last_run: entry.and_then(|e| e.last_run).map(|t| t.to_rfc3339()),
+        last_error: entry.and_then(|e| e.last_error.clone()),
+      }
+    })
+    .collect();
+  Ok(tasks)
+}
+
+// 立即执行某个任务（不影响其周期），完成后返回执行结果；任务正在运行时返回 InvalidInput。
+#[tauri::command]
+pub async fn run_scheduled_task_now(app: tauri::AppHandle, kind: TaskKind) -> Result<(), AppError> {
+  let running = RUNTIME
+    .lock()
+    .unwrap_or_else(|e| e.into_inner())
+    .get(&kind)
+    .map(|entry| entry.running)
+    .unwrap_or(false);
+  if running {
+    return Err(AppError::invalid_input("任务正在运行"));
+  }
+  execute(&app, kind).await
+}
@@ -0,0 +1,93 @@
+/*
+  ACG.RIP 搜索结果解析：
+  列表在 table.post-index 中，每行依次为发布时间（time 的 datetime 为 Unix 时间戳）、
+  标题（前面可能有字幕组标签）、.torrent 下载链接、大小、做种/下载/完成数。
+  列表里没有磁力链接，只能下载种子文件。
+*/
+
+use scraper::Html;
+
+use super::{
+  absolute_url, element_text, format_timestamp, parse_release, selector, SearchAdapter, SearchResult, SearchSource,
+};
+
+// ACG.RIP 站点地址。
+const ACGRIP_BASE: &str = "https://acg.rip/";
+
+/// ACG.RIP 适配器。
+pub(super) struct AcgRip;
+
+impl SearchAdapter for AcgRip {
+  fn source(&self) -> SearchSource {
+    SearchSource::AcgRip
+  }
+
+  fn supports_operators(&self) -> bool {
+    false
+  }
+
+  fn search_url(&self, query: &str, page: u32) -> String {
+    let mut url = url::Url::parse(ACGRIP_BASE)
+      .and_then(|base| base.join(&format!("page/{page}")))
+      .expect("ACG.RIP 地址无效");
+    url.query_pairs_mut().append_pair("term", query);
+    url.to_string()
+  }
+
+  fn parse(&self, html: &str) -> Vec<SearchResult> {
+    let rows = selector("table.post-index tr");
+    let time_tag = selector("td.date time");
+    let title_link = selector("td.title span.title a");
+    let team_label = selector("td.title span.label-team");
+    let torrent_link = selector("td.action a[href$='.torrent']");
+    let size_cell = selector("td.size");
+    let seed_count = selector("td.peers .up, td.peers div:first-child");
+
+    let document = Html::parse_document(html);
+    document
+      .select(&rows)
+      .filter_map(|row| {
+        let link = row.select(&title_link).next()?;
+        let title = element_text(link);
+        if title.is_empty() {
+          return None;
+        }
+        let release = parse_release(&title);
+        let published_at = row
+          .select(&time_tag)
+          .next()
+          .and_then(|time| time.value().attr("datetime"))
+          .and_then(|value| value.parse::<i64>().ok());
+        Some(SearchResult {
+          group: row
+            .select(&team_label)
+            .next()
+            .map(element_text)
+            .filter(|team| !team.is_empty())
+            .or_else(|| release.group.clone()),
+          detail_url: link.value().attr("href").map(|href| absolute_url(ACGRIP_BASE, href)),
+          magnet: None,
+          torrent_url: row
+            .select(&torrent_link)
+            .next()
+            .and_then(|a| a.value().attr("href"))
+            .map(|href| absolute_url(ACGRIP_BASE, href)),
+          size: row
+            .select(&size_cell)
+            .next()
+            .map(element_text)
+            .filter(|text| !text.is_empty()),
+          seeders: row
+            .select(&seed_count)
+            .next()
+            .and_then(|cell| element_text(cell).parse().ok()),
+          pub_date: published_at.and_then(format_timestamp),
+          published_at,
+          source: self.source(),
+          title,
+          release,
+        })
+      })
+      .collect()
+  }
+}
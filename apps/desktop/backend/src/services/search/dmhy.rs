@@ -0,0 +1,107 @@
+/*
+  動漫花園（dmhy）搜索结果解析：
+  列表在 table#topic_list 中，每行依次为发布时间、分类、标题（前面是字幕组标签）、
+  磁力链接、大小、做种数、下载数、完成数、发布人。
+  发布时间写在第一列隐藏的 span 里（格式 2024/04/01 12:34，北京时间）；列表里没有 .torrent 地址。
+*/
+
+use chrono::{FixedOffset, NaiveDateTime, TimeZone};
+use scraper::Html;
+
+use super::{
+  absolute_url, element_text, format_timestamp, parse_release, selector, SearchAdapter, SearchResult, SearchSource,
+};
+
+// 動漫花園站点地址。
+const DMHY_BASE: &str = "https://share.dmhy.org/";
+// 站点时间使用的时区（UTC+8）。
+const SITE_OFFSET_SECS: i32 = 8 * 3600;
+
+/// 動漫花園适配器。
+pub(super) struct Dmhy;
+
+// 把站点的发布时间转换为 Unix 时间戳。
+fn parse_site_time(text: &str) -> Option<i64> {
+  let naive = NaiveDateTime::parse_from_str(text.trim(), "%Y/%m/%d %H:%M").ok()?;
+  let offset = FixedOffset::east_opt(SITE_OFFSET_SECS)?;
+  offset
+    .from_local_datetime(&naive)
+    .single()
+    .map(|time| time.timestamp())
+}
+
+impl SearchAdapter for Dmhy {
+  fn source(&self) -> SearchSource {
+    SearchSource::Dmhy
+  }
+
+  fn supports_operators(&self) -> bool {
+    false
+  }
+
+  fn search_url(&self, query: &str, page: u32) -> String {
+    let mut url = url::Url::parse(DMHY_BASE)
+      .and_then(|base| base.join(&format!("topics/list/page/{page}")))
+      .expect("動漫花園地址无效");
+    url.query_pairs_mut().append_pair("keyword", query);
+    url.to_string()
+  }
+
+  fn parse(&self, html: &str) -> Vec<SearchResult> {
+    let rows = selector("table#topic_list tbody tr");
+    let td = selector("td");
+    let hidden_time = selector("td:first-child span");
+    let title_link = selector("td.title > a[href^='/topics/view/']");
+    let team_tag = selector("td.title span.tag a");
+    let magnet_link = selector("a[href^='magnet:']");
+    let seed_count = selector("span.btl_1");
+
+    let document = Html::parse_document(html);
+    document
+      .select(&rows)
+      .filter_map(|row| {
+        let link = row.select(&title_link).next()?;
+        let title = element_text(link);
+        if title.is_empty() {
+          return None;
+        }
+        let release = parse_release(&title);
+        let published_at = row
+          .select(&hidden_time)
+          .next()
+          .and_then(|span| parse_site_time(&element_text(span)));
+        let size = row
+          .select(&td)
+          .nth(4)
+          .map(element_text)
+          .filter(|text| !text.is_empty());
+        Some(SearchResult {
+          group: row
+            .select(&team_tag)
+            .next()
+            .map(element_text)
+            .filter(|team| !team.is_empty())
+            .or_else(|| release.group.clone()),
+          detail_url: link.value().attr("href").map(|href| absolute_url(DMHY_BASE, href)),
+          magnet: row
+            .select(&magnet_link)
+            .next()
+            .and_then(|a| a.value().attr("href"))
+            .map(str::to_string),
+          torrent_url: None,
+          size,
+          // 没有人做种时显示为 "-"。
+          seeders: row
+            .select(&seed_count)
+            .next()
+            .and_then(|span| element_text(span).parse().ok()),
+          pub_date: published_at.and_then(format_timestamp),
+          published_at,
+          source: self.source(),
+          title,
+          release,
+        })
+      })
+      .collect()
+  }
+}
@@ -0,0 +1,244 @@
+/*
+  种子搜索模块：
+  在后端请求各资源站的搜索页并解析成统一的结构，前端不再自己解析 HTML。
+  标题统一用 release 模块解析出字幕组、集数、分辨率等信息，随结果一起返回。
+  - 每个站点一个适配器（SearchAdapter）：负责构造搜索地址、解析列表；
+  - 目前支持 Nyaa、動漫花園（dmhy）、ACG.RIP；
+  - 搜索语句使用 Nyaa 的语法（见 query.rs），不支持运算符的站点只提交关键词，再在本地过滤标题；
+  - 多个站点同时请求，结果合并后按发布时间从新到旧排序，同一个种子（info hash 相同）只保留一条；
+    单个站点失败不影响其它站点，错误随结果一起返回。
+*/
+
+use chrono::{DateTime, Local};
+use futures::future::join_all;
+use scraper::{ElementRef, Selector};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use super::bangumi::shared_client;
+use super::error::AppError;
+use super::limits::send_limited;
+use super::magnet::parse_magnet;
+use super::release::{parse_release, ReleaseInfo};
+
+mod acgrip;
+mod dmhy;
+mod nyaa;
+mod query;
+
+use query::SearchQuery;
+
+/// 搜索站点。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum SearchSource {
+  /// Nyaa（nyaa.si 镜像）。
+  Nyaa,
+  /// 動漫花園（share.dmhy.org）。
+  Dmhy,
+  /// ACG.RIP。
+  AcgRip,
+}
+
+impl SearchSource {
+  // 所有站点（未指定站点时全部搜索）。
+  const ALL: [SearchSource; 3] = [SearchSource::Nyaa, SearchSource::Dmhy, SearchSource::AcgRip];
+
+  // 站点对应的适配器。
+  fn adapter(self) -> &'static dyn SearchAdapter {
+    match self {
+      SearchSource::Nyaa => &nyaa::Nyaa,
+      SearchSource::Dmhy => &dmhy::Dmhy,
+      SearchSource::AcgRip => &acgrip::AcgRip,
+    }
+  }
+}
+
+/// 统一的搜索结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+  pub title: String,
+  /// 详情页地址。
+  pub detail_url: Option<String>,
+  pub magnet: Option<String>,
+  /// .torrent 下载地址。
+  pub torrent_url: Option<String>,
+  /// 大小（站点原文，例如 "1.2 GiB"）。
+  pub size: Option<String>,
+  /// 做种数（站点未提供时为空）。
+  pub seeders: Option<u32>,
+  /// 发布时间（本地时间，YYYY-MM-DD HH:MM）。
+  pub pub_date: Option<String>,
+  /// 发布时间（Unix 时间戳，秒），用于排序。
+  pub published_at: Option<i64>,
+  /// 字幕组（站点的字幕组标签，或从标题解析出的字幕组）。
+  pub group: Option<String>,
+  pub source: SearchSource,
+  /// 从标题解析出的集数、分辨率、片源等信息。
+  pub release: ReleaseInfo,
+}
+
+/// 某个站点的搜索错误。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSourceError {
+  pub source: SearchSource,
+  pub message: String,
+}
+
+/// 搜索结果与各站点的错误。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentSearchResponse {
+  pub results: Vec<SearchResult>,
+  pub errors: Vec<SearchSourceError>,
+}
+
+// 站点适配器：构造搜索地址并解析搜索结果页。
+trait SearchAdapter: Sync {
+  fn source(&self) -> SearchSource;
+  // 是否直接支持 Nyaa 的搜索语法（引号、|、-）。
+  fn supports_operators(&self) -> bool;
+  fn search_url(&self, query: &str, page: u32) -> String;
+  fn parse(&self, html: &str) -> Vec<SearchResult>;
+}
+
+// 选择器都是常量，解析失败属于代码错误。
+fn selector(css: &str) -> Selector {
+  Selector::parse(css).expect("搜索结果选择器无效")
+}
+
+// 元素内的文本（去掉多余空白）。
+fn element_text(element: ElementRef<'_>) -> String {
+  element
+    .text()
+    .flat_map(str::split_whitespace)
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+// 相对地址补全为绝对地址。
+fn absolute_url(base: &str, href: &str) -> String {
+  url::Url::parse(base)
+    .and_then(|base| base.join(href))
+    .map(|url| url.to_string())
+    .unwrap_or_else(|_| href.to_string())
+}
+
+// 是否是大小文本（数字加单位，例如 1.2 GiB / 350MB）。
+fn is_size_text(text: &str) -> bool {
+  const UNITS: [&str; 9] = ["KiB", "MiB", "GiB", "TiB", "KB", "MB", "GB", "TB", "B"];
+  UNITS.iter().any(|unit| {
+    text
+      .strip_suffix(unit)
+      .is_some_and(|number| number.trim().parse::<f64>().is_ok())
+  })
+}
+
+// Unix 时间戳转成本地时间文本。
+fn format_timestamp(timestamp: i64) -> Option<String> {
+  let time = DateTime::from_timestamp(timestamp, 0)?.with_timezone(&Local);
+  Some(time.format("%Y-%m-%d %H:%M").to_string())
+}
+
+// 请求搜索页 HTML。
+async fn fetch_html(url: &str) -> Result<String, AppError> {
+  let response = send_limited(shared_client().get(url))
+    .await
+    .map_err(AppError::network)?;
+  if !response.status().is_success() {
+    return Err(AppError::api(
+      response.status().as_u16(),
+      format!("搜索站点请求失败: {}", response.status()),
+    ));
+  }
+  response
+    .text()
+    .await
+    .map_err(|e| AppError::network(format!("读取搜索结果失败: {e}")))
+}
+
+// 在单个站点搜索，并按搜索条件过滤标题。
+async fn search_source(
+  adapter: &dyn SearchAdapter,
+  query: &SearchQuery,
+  page: u32,
+) -> Result<Vec<SearchResult>, AppError> {
+  let keywords = if adapter.supports_operators() {
+    vec![query.raw().to_string()]
+  } else {
+    query.keyword_queries()
+  };
+  let mut results = Vec::new();
+  for keyword in keywords {
+    let html = fetch_html(&adapter.search_url(&keyword, page)).await?;
+    results.extend(adapter.parse(&html));
+  }
+  results.retain(|result| query.matches(&result.title));
+  Ok(results)
+}
+
+// 按 info hash（没有磁力链接时按详情页地址）去重。
+fn dedupe_key(result: &SearchResult) -> String {
+  result
+    .magnet
+    .as_deref()
+    .and_then(|uri| parse_magnet(uri).ok())
+    .map(|info| format!("btih:{}", info.info_hash.to_ascii_lowercase()))
+    .or_else(|| result.detail_url.clone())
+    .unwrap_or_else(|| result.title.clone())
+}
+
+// 在指定站点搜索种子（省略 sources 时搜索全部站点），page 从 1 开始。
+// 所有站点都失败时返回第一个错误。
+#[tauri::command]
+pub async fn search_torrents(
+  query: String,
+  sources: Option<Vec<SearchSource>>,
+  page: Option<u32>,
+) -> Result<TorrentSearchResponse, AppError> {
+  let query = SearchQuery::parse(&query);
+  if query.raw().is_empty() {
+    return Err(AppError::invalid_input("搜索关键词为空"));
+  }
+  let page = page.unwrap_or(1).max(1);
+  let mut sources = sources
+    .filter(|sources| !sources.is_empty())
+    .unwrap_or_else(|| SearchSource::ALL.to_vec());
+  let mut unique = HashSet::new();
+  sources.retain(|source| unique.insert(*source));
+
+  let outcomes = join_all(
+    sources
+      .iter()
+      .map(|source| search_source(source.adapter(), &query, page)),
+  )
+  .await;
+
+  let mut results = Vec::new();
+  let mut errors = Vec::new();
+  let mut first_error = None;
+  for (source, outcome) in sources.iter().zip(outcomes) {
+    match outcome {
+      Ok(found) => results.extend(found),
+      Err(err) => {
+        errors.push(SearchSourceError {
+          source: *source,
+          message: err.to_string(),
+        });
+        first_error.get_or_insert(err);
+      }
+    }
+  }
+  if let Some(err) = first_error.filter(|_| errors.len() == sources.len()) {
+    return Err(err);
+  }
+
+  // 按发布时间从新到旧排列，没有时间的放最后。
+  results.sort_by_key(|result| Reverse(result.published_at));
+  let mut seen = HashSet::new();
+  results.retain(|result| seen.insert(dedupe_key(result)));
+  Ok(TorrentSearchResponse { results, errors })
+}
@@ -0,0 +1,99 @@
+/*
+  Nyaa 搜索结果解析：
+  列表在 table.torrent-list 中，每行依次为分类、标题、下载链接（.torrent 与磁力）、
+  大小、发布时间（data-timestamp）、做种数、下载数、完成数。
+  镜像站的列可能略有差别，所以大小与做种数按内容定位，而不是固定列号。
+*/
+
+use scraper::Html;
+
+use super::{
+  absolute_url, element_text, format_timestamp, is_size_text, parse_release, selector, SearchAdapter,
+  SearchResult, SearchSource,
+};
+
+// Nyaa 站点地址（与之前前端使用的镜像一致）。
+const NYAA_BASE: &str = "https://nyaa.vaciller.top/";
+
+/// Nyaa 适配器。
+pub(super) struct Nyaa;
+
+impl SearchAdapter for Nyaa {
+  fn source(&self) -> SearchSource {
+    SearchSource::Nyaa
+  }
+
+  fn supports_operators(&self) -> bool {
+    true
+  }
+
+  fn search_url(&self, query: &str, page: u32) -> String {
+    let mut url = url::Url::parse(NYAA_BASE).expect("Nyaa 地址无效");
+    url
+      .query_pairs_mut()
+      .append_pair("f", "0")
+      .append_pair("c", "0_0")
+      .append_pair("q", query)
+      .append_pair("p", &page.to_string());
+    url.to_string()
+  }
+
+  fn parse(&self, html: &str) -> Vec<SearchResult> {
+    let rows = selector("table tbody tr");
+    let td = selector("td");
+    let name_link = selector("a[href^='/view/']:not(.comments)");
+    let magnet_link = selector("a[href^='magnet:']");
+    let torrent_link = selector("a[href$='.torrent']");
+
+    let document = Html::parse_document(html);
+    document
+      .select(&rows)
+      .filter_map(|row| {
+        // 标题所在单元格可能还有评论链接，取最后一个 /view/ 链接。
+        let link = row.select(&name_link).last()?;
+        let title = link
+          .value()
+          .attr("title")
+          .map(str::to_string)
+          .unwrap_or_else(|| element_text(link));
+        if title.is_empty() {
+          return None;
+        }
+        let release = parse_release(&title);
+        let cells: Vec<_> = row.select(&td).collect();
+        let date_index = cells.iter().position(|cell| cell.value().attr("data-timestamp").is_some());
+        let published_at = date_index
+          .and_then(|index| cells[index].value().attr("data-timestamp"))
+          .and_then(|value| value.parse::<i64>().ok());
+        let seeders = date_index
+          .and_then(|index| cells.get(index + 1))
+          .and_then(|cell| element_text(*cell).parse().ok());
+        let size = cells
+          .iter()
+          .map(|cell| element_text(*cell))
+          .find(|text| is_size_text(text));
+        Some(SearchResult {
+          group: release.group.clone(),
+          detail_url: link.value().attr("href").map(|href| absolute_url(NYAA_BASE, href)),
+          magnet: row
+            .select(&magnet_link)
+            .next()
+            .and_then(|a| a.value().attr("href"))
+            .map(str::to_string),
+          torrent_url: row
+            .select(&torrent_link)
+            .next()
+            .and_then(|a| a.value().attr("href"))
+            .map(|href| absolute_url(NYAA_BASE, href)),
+          size,
+          seeders,
+          pub_date: published_at.and_then(format_timestamp),
+          published_at,
+          source: self.source(),
+          title,
+          release,
+        })
+      })
+      .collect()
+  }
+}
@@ -0,0 +1,100 @@
+/*
+  搜索语句解析：
+  前端拼出的语句使用 Nyaa 的语法：
+  - "a" "b"：同时包含；
+  - "a"|"b"：包含其一；
+  - -"c"：排除。
+  Nyaa 直接使用原语句；其它站点不支持这些运算符，只提交关键词，
+  再按同样的规则在本地过滤标题。
+*/
+
+// 最多为“或”条件单独发起的请求数量（其它站点没有“与”条件时使用）。
+const MAX_ALTERNATIVES: usize = 3;
+
+// 解析后的搜索条件（均为小写）。
+pub(super) struct SearchQuery {
+  // 原始语句（Nyaa 使用）。
+  raw: String,
+  // 必须全部出现。
+  all: Vec<String>,
+  // 至少出现其一（为空表示不限制）。
+  any: Vec<String>,
+  // 出现任意一个就排除。
+  none: Vec<String>,
+}
+
+// 按空白切分，引号内的空白不切分。
+fn split_outside_quotes(text: &str, separator: impl Fn(char) -> bool) -> Vec<String> {
+  let mut parts = Vec::new();
+  let mut current = String::new();
+  let mut quoted = false;
+  for c in text.chars() {
+    if c == '"' {
+      quoted = !quoted;
+      current.push(c);
+    } else if !quoted && separator(c) {
+      if !current.is_empty() {
+        parts.push(std::mem::take(&mut current));
+      }
+    } else {
+      current.push(c);
+    }
+  }
+  if !current.is_empty() {
+    parts.push(current);
+  }
+  parts
+}
+
+// 去掉引号、空白并统一为小写。
+fn clean_term(term: &str) -> String {
+  term.replace('"', "").trim().to_lowercase()
+}
+
+impl SearchQuery {
+  pub(super) fn parse(raw: &str) -> Self {
+    let mut query = SearchQuery {
+      raw: raw.trim().to_string(),
+      all: Vec::new(),
+      any: Vec::new(),
+      none: Vec::new(),
+    };
+    for token in split_outside_quotes(raw, char::is_whitespace) {
+      if let Some(excluded) = token.strip_prefix('-') {
+        query.none.push(clean_term(excluded));
+      } else if token.contains('|') {
+        query
+          .any
+          .extend(split_outside_quotes(&token, |c| c == '|').iter().map(|term| clean_term(term)));
+      } else {
+        query.all.push(clean_term(&token));
+      }
+    }
+    query.all.retain(|term| !term.is_empty());
+    query.any.retain(|term| !term.is_empty());
+    query.none.retain(|term| !term.is_empty());
+    query
+  }
+
+  // 原始语句。
+  pub(super) fn raw(&self) -> &str {
+    &self.raw
+  }
+
+  // 提交给不支持运算符的站点的关键词：有“与”条件时只请求一次，
+  // 否则为每个“或”条件分别请求（最多 MAX_ALTERNATIVES 个）。
+  pub(super) fn keyword_queries(&self) -> Vec<String> {
+    if !self.all.is_empty() || self.any.is_empty() {
+      return vec![self.all.join(" ")];
+    }
+    self.any.iter().take(MAX_ALTERNATIVES).cloned().collect()
+  }
+
+  // 标题是否满足全部条件。
+  pub(super) fn matches(&self, title: &str) -> bool {
+    let title = title.to_lowercase();
+    self.all.iter().all(|term| title.contains(term.as_str()))
+      && (self.any.is_empty() || self.any.iter().any(|term| title.contains(term.as_str())))
+      && !self.none.iter().any(|term| title.contains(term.as_str()))
+  }
+}
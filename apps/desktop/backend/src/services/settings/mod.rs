@@ -0,0 +1,360 @@
+/*
+  应用设置模块：
+  保存用户可调整的全局配置（并发上限、定时任务、默认目录、代理、翻译服务等）。
+  数据以 JSON 文件形式落地在应用数据目录中，
+  所有字段都有默认值，旧版本的设置文件缺少字段时也能正常读取。
+*/
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::Manager;
+
+use super::bangumi::{
+  apply_cache_settings,
+  apply_network_settings,
+  apply_request_settings,
+  apply_translation_settings,
+  deobfuscate_credential_value,
+  obfuscate_credential_value,
+  TRANSLATION_PROVIDERS,
+};
+use super::diagnostics::app_log;
+use super::error::AppError;
+use super::limits::apply_limits;
+use super::notifications::EpisodeNotificationSettings;
+use super::paths::ensure_settings_dirs_allowed;
+use super::scheduler::{default_scheduled_tasks, validate_schedule, ScheduledTaskConfig};
+use super::torrent::{BandwidthSettings, BlocklistSettings, SeedingSettings, TorrentSessionSettings, TrackerSettings};
+
+// 设置文件名。
+const SETTINGS_FILE: &str = "settings.json";
+
+/// 全局并发上限。
+/// 低配机器可以调小这些值，避免批量操作把机器拖垮。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConcurrencyLimits {
+  /// 同时进行的 HTTP 请求数（Bangumi / 翻译 / 搜索站点）。
+  pub max_http_requests: usize,
+  /// 同时运行的媒体任务数（混流等调用外部工具的任务）。
+  pub max_media_jobs: usize,
+  /// 同时初始化的种子数（解析元数据、校验已有文件）。
+  pub max_torrent_inits: usize,
+  /// 同时下载的种子数，超出的新任务进入下载队列。
+  pub max_active_downloads: usize,
+}
+
+impl Default for ConcurrencyLimits {
+  fn default() -> Self {
+    Self {
+      max_http_requests: 8,
+      max_media_jobs: 2,
+      max_torrent_inits: 8,
+      max_active_downloads: 3,
+    }
+  }
+}
+
+/// 网络请求设置（作用于共享的 HTTP 客户端）。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkSettings {
+  /// 建立连接的超时时间（秒）。
+  pub connect_timeout_secs: u64,
+  /// 单个请求的总超时时间（秒），包含读取响应体。
+  pub request_timeout_secs: u64,
+  /// 发往 Bangumi 的每秒请求数上限，0 表示不限制。
+  pub bangumi_requests_per_second: u32,
+  /// 遇到 429 / 5xx / 网络错误时的最大重试次数。
+  pub max_retries: u32,
+}
+
+impl Default for NetworkSettings {
+  fn default() -> Self {
+    Self {
+      connect_timeout_secs: 10,
+      request_timeout_secs: 30,
+      bangumi_requests_per_second: 4,
+      max_retries: 3,
+    }
+  }
+}
+
+/// 缓存设置。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CacheSettings {
+  /// Bangumi 响应缓存的有效期（分钟），0 表示不使用缓存。
+  pub bangumi_ttl_minutes: u64,
+}
+
+impl CacheSettings {
+  // 默认有效期：12 小时。
+  pub(crate) const DEFAULT_TTL_MINUTES: u64 = 12 * 60;
+}
+
+impl Default for CacheSettings {
+  fn default() -> Self {
+    Self {
+      bangumi_ttl_minutes: Self::DEFAULT_TTL_MINUTES,
+    }
+  }
+}
+
+/// 应用设置（整体读写）。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+  pub concurrency: ConcurrencyLimits,
+  pub network: NetworkSettings,
+  pub cache: CacheSettings,
+  /// 后台定时任务的启用状态与周期。
+  pub scheduled_tasks: Vec<ScheduledTaskConfig>,
+  /// 新剧集通知的轮询间隔与静音条目。
+  pub episode_notifications: EpisodeNotificationSettings,
+  /// 已完成下载的做种规则（分享率/时间上限与单个任务的覆盖规则）。
+  pub seeding: SeedingSettings,
+  /// 默认 tracker 列表与公共 tracker 列表地址。
+  pub trackers: TrackerSettings,
+  /// 下载会话参数（监听端口、UPnP、DHT、peer 超时、写盘缓冲）。
+  pub torrent_session: TorrentSessionSettings,
+  /// 按时段的下载/上传限速。
+  pub bandwidth: BandwidthSettings,
+  /// IP 黑名单来源与加载结果。
+  pub blocklist: BlocklistSettings,
+  /// 是否已完成首次启动向导。
+  pub setup_completed: bool,
+  /// 默认下载目录（为空时由前端每次询问）。
+  pub download_dir: Option<String>,
+  /// 默认输出目录（混流/转码结果）。
+  pub output_dir: Option<String>,
+  /// 额外允许后端访问的目录（见 paths 模块）。
+  pub allowed_dirs: Vec<String>,
+  /// 工具目录：后处理钩子只能运行这些目录下的程序（只能经 pick_path 的工具目录对话框添加，见 paths 模块）。
+  pub tool_dirs: Vec<String>,
+  /// HTTP 代理地址，例如 http://127.0.0.1:7890 或 socks5://127.0.0.1:1080。
+  /// 作用于所有 HTTP 请求（Bangumi / 翻译 / 搜索站点）；SOCKS5 代理也可以供下载会话复用。
+  pub proxy_url: Option<String>,
+  /// 代理账号（可选）。
+  pub proxy_username: Option<String>,
+  /// 代理密码（可选），可以是明文，也可以是 obfuscate_credential 生成的 "enc:<hex>"；
+  /// 明文在保存时转换成 "enc:<hex>"。
+  pub proxy_password: Option<String>,
+  /// 首选翻译服务名称（对应密钥库中的 provider，如 "baidu"）。
+  pub translation_provider: String,
+  /// 首选服务失败（或未配置密钥）时依次尝试的其它服务。
+  pub translation_fallbacks: Vec<String>,
+}
+
+impl Default for AppSettings {
+  fn default() -> Self {
+    Self {
+      concurrency: ConcurrencyLimits::default(),
+      network: NetworkSettings::default(),
+      cache: CacheSettings::default(),
+      scheduled_tasks: default_scheduled_tasks(),
+      episode_notifications: EpisodeNotificationSettings::default(),
+      seeding: SeedingSettings::default(),
+      trackers: TrackerSettings::default(),
+      torrent_session: TorrentSessionSettings::default(),
+      bandwidth: BandwidthSettings::default(),
+      blocklist: BlocklistSettings::default(),
+      setup_completed: false,
+      download_dir: None,
+      output_dir: None,
+      allowed_dirs: Vec::new(),
+      tool_dirs: Vec::new(),
+      proxy_url: None,
+      proxy_username: None,
+      proxy_password: None,
+      translation_provider: "baidu".to_string(),
+      translation_fallbacks: TRANSLATION_PROVIDERS.iter().map(|name| name.to_string()).collect(),
+    }
+  }
+}
+
+impl AppSettings {
+  // 修正非法值：并发上限至少为 1，否则所有任务都会永远等待。
+  // 同时把空白的目录/代理设置归一化为 None。
+  fn normalized(mut self) -> Self {
+    self.concurrency.max_http_requests = self.concurrency.max_http_requests.max(1);
+    self.concurrency.max_media_jobs = self.concurrency.max_media_jobs.max(1);
+    self.concurrency.max_torrent_inits = self.concurrency.max_torrent_inits.max(1);
+    self.concurrency.max_active_downloads = self.concurrency.max_active_downloads.max(1);
+    // 超时为 0 会让所有请求立即失败。
+    self.network.connect_timeout_secs = self.network.connect_timeout_secs.max(1);
+    self.network.request_timeout_secs = self.network.request_timeout_secs.max(1);
+    // 空白字符串等同于“未设置”。
+    for value in [
+      &mut self.download_dir,
+      &mut self.output_dir,
+      &mut self.proxy_url,
+      &mut self.proxy_username,
+      &mut self.proxy_password,
+    ] {
+      *value = value
+        .take()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    }
+    // 代理密码不以明文落盘（已处理过的值保持不变）。
+    self.proxy_password = self.proxy_password.map(obfuscate_credential_value);
+    for dirs in [&mut self.allowed_dirs, &mut self.tool_dirs] {
+      *dirs = std::mem::take(dirs)
+        .into_iter()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect();
+    }
+    self.episode_notifications = self.episode_notifications.normalized();
+    self.seeding = self.seeding.normalized();
+    self.trackers = self.trackers.normalized();
+    self.torrent_session = self.torrent_session.normalized();
+    self.bandwidth = self.bandwidth.normalized();
+    self.blocklist = self.blocklist.normalized();
+    self.translation_provider = self.translation_provider.trim().to_string();
+    if self.translation_provider.is_empty() {
+      self.translation_provider = "baidu".to_string();
+    }
+    // 回退列表只保留认识的服务名。
+    self.translation_fallbacks = self
+      .translation_fallbacks
+      .into_iter()
+      .map(|v| v.trim().to_string())
+      .filter(|v| TRANSLATION_PROVIDERS.contains(&v.as_str()))
+      .collect();
+    self
+  }
+
+  // 根据代理设置构建 reqwest 代理；未配置代理时返回 None。
+  pub(crate) fn proxy(&self) -> Result<Option<reqwest::Proxy>, String> {
+    let url = match self.proxy_url_with_credentials()? {
+      Some(url) => url,
+      None => return Ok(None),
+    };
+    reqwest::Proxy::all(url.as_str())
+      .map(Some)
+      .map_err(|e| format!("代理地址无效: {e}"))
+  }
+
+  // 解析代理地址并带上账号密码；未配置代理时返回 None。
+  // 账号密码写进 URL 里，这样 HTTP 与 SOCKS5 代理都能使用（下载会话也复用这个地址）。
+  pub(crate) fn proxy_url_with_credentials(&self) -> Result<Option<url::Url>, String> {
+    let raw = match self.proxy_url.as_deref() {
+      Some(raw) => raw,
+      None => return Ok(None),
+    };
+    let mut url = url::Url::parse(raw).map_err(|e| format!("代理地址无效: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+      return Err(format!("不支持的代理协议: {}", url.scheme()));
+    }
+    if let Some(username) = self.proxy_username.as_deref() {
+      url
+        .set_username(username)
+        .map_err(|_| "代理地址不支持设置账号".to_string())?;
+      let password = match self.proxy_password.as_deref() {
+        Some(password) => Some(deobfuscate_credential_value(password)?),
+        None => None,
+      };
+      url
+        .set_password(password.as_deref())
+        .map_err(|_| "代理地址不支持设置密码".to_string())?;
+    }
+    Ok(Some(url))
+  }
+}
+
+// 获取设置文件的完整路径。
+// 如果目录不存在会自动创建。
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+  let dir = app
+    .path()
+    .app_data_dir()
+    .map_err(|e| format!("无法获取数据目录: {e}"))?
+    .join("hanamirip-cn");
+  fs::create_dir_all(&dir).map_err(|e| format!("创建数据目录失败: {e}"))?;
+  Ok(dir.join(SETTINGS_FILE))
+}
+
+// 读取设置；文件不存在或为空时返回默认设置。
+pub(crate) fn load_settings(app: &tauri::AppHandle) -> Result<AppSettings, String> {
+  let path = settings_path(app)?;
+  if !path.exists() {
+    return Ok(AppSettings::default());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| format!("读取设置失败: {e}"))?;
+  if content.trim().is_empty() {
+    return Ok(AppSettings::default());
+  }
+  let parsed: AppSettings =
+    serde_json::from_str(&content).map_err(|e| format!("解析设置失败: {e}"))?;
+  Ok(parsed.normalized())
+}
+
+// 把设置写回 JSON 文件（格式化输出）。
+fn persist_settings(app: &tauri::AppHandle, settings: &AppSettings) -> Result<(), String> {
+  let path = settings_path(app)?;
+  let payload =
+    serde_json::to_string_pretty(settings).map_err(|e| format!("序列化设置失败: {e}"))?;
+  fs::write(&path, payload).map_err(|e| format!("写入设置失败: {e}"))
+}
+
+// 启动时读取设置并应用到各个服务。
+// 读取失败只打印日志并使用默认值，不阻止应用启动。
+pub(crate) fn init_settings(app: &tauri::AppHandle) {
+  let settings = load_settings(app).unwrap_or_else(|err| {
+    app_log!("{err}");
+    AppSettings::default()
+  });
+  apply_limits(&settings.concurrency);
+  apply_network_settings(&settings);
+  apply_request_settings(&settings.network);
+  apply_cache_settings(&settings.cache);
+  apply_translation_settings(&settings);
+}
+
+// 校验设置里的周期、代理、限速与翻译服务（settings 应已整理过）。
+fn validate_settings(settings: &AppSettings) -> Result<(), String> {
+  for task in &settings.scheduled_tasks {
+    validate_schedule(&task.schedule)?;
+  }
+  settings.proxy()?;
+  settings.torrent_session.resolve_proxy(settings)?;
+  settings.bandwidth.validate()?;
+  if !TRANSLATION_PROVIDERS.contains(&settings.translation_provider.as_str()) {
+    return Err(format!("不支持的翻译服务: {}", settings.translation_provider));
+  }
+  Ok(())
+}
+
+// 校验并保存设置，随后立即应用，返回修正后的设置。
+// 供其它模块（如首次启动向导）复用同一套保存流程。
+pub(crate) fn save_settings(app: &tauri::AppHandle, settings: AppSettings) -> Result<AppSettings, String> {
+  let settings = settings.normalized();
+  validate_settings(&settings)?;
+  persist_settings(app, &settings)?;
+  apply_limits(&settings.concurrency);
+  apply_network_settings(&settings);
+  apply_request_settings(&settings.network);
+  apply_cache_settings(&settings.cache);
+  apply_translation_settings(&settings);
+  Ok(settings)
+}
+
+// 返回当前设置。
+#[tauri::command]
+pub fn get_app_settings(app: tauri::AppHandle) -> Result<AppSettings, AppError> {
+  load_settings(&app).map_err(AppError::io)
+}
+
+// 保存设置并立即生效，返回修正后的设置。
+// 新增的目录必须经过对话框授权，见 paths 模块；目录未授权或设置不合法时返回 InvalidInput。
+#[tauri::command]
+pub fn save_app_settings(app: tauri::AppHandle, settings: AppSettings) -> Result<AppSettings, AppError> {
+  let previous = load_settings(&app).map_err(AppError::io)?;
+  let settings = settings.normalized();
+  ensure_settings_dirs_allowed(&previous, &settings).map_err(AppError::invalid_input)?;
+  validate_settings(&settings).map_err(AppError::invalid_input)?;
+  save_settings(&app, settings).map_err(AppError::io)
+}
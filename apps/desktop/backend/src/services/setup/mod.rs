@@ -0,0 +1,226 @@
+/*
+  首次启动向导（后端部分）：
+  - 检查内置工具（ffmpeg / ffprobe / mkvmerge / mkvinfo / mkvextract / mkvpropedit）是否可用；
+  - 一次性保存向导里填写的默认目录、代理、翻译服务；
+  - 可选：从旧安装目录导入追番数据。
+  所有配置都通过设置模块保存，apply_initial_setup 成功后标记向导已完成。
+*/
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use super::bangumi::TRANSLATION_PROVIDERS;
+use super::error::AppError;
+use super::media::resolve_tool_path;
+use super::paths::{ensure_path_allowed, ensure_settings_dirs_allowed};
+use super::settings::{load_settings, save_settings, AppSettings};
+use super::storage::{load_tracked, persist_tracked, TrackedSubject};
+
+// 应用依赖的外部工具，以及查询版本使用的参数。
+const REQUIRED_TOOLS: &[(&str, &str)] = &[
+  ("ffmpeg", "-version"),
+  ("ffprobe", "-version"),
+  ("mkvmerge", "--version"),
+  ("mkvinfo", "--version"),
+  ("mkvextract", "--version"),
+  ("mkvpropedit", "--version"),
+];
+
+
+/// 单个工具的检查结果。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolStatus {
+  pub name: String,
+  pub available: bool,
+  pub path: Option<String>,
+  pub version: Option<String>,
+  pub error: Option<String>,
+}
+
+/// 向导初始状态。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupStatusResponse {
+  pub completed: bool,
+  pub tools: Vec<ToolStatus>,
+  pub settings: AppSettings,
+  pub translation_providers: Vec<String>,
+}
+
+/// 向导提交的配置。
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitialSetupConfig {
+  pub download_dir: Option<String>,
+  pub output_dir: Option<String>,
+  pub proxy_url: Option<String>,
+  pub translation_provider: Option<String>,
+  /// 旧数据位置：可以是旧的数据目录，也可以直接是 watchlist.json 文件。
+  pub import_path: Option<String>,
+}
+
+/// 向导完成后的结果。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitialSetupResponse {
+  pub settings: AppSettings,
+  pub imported_subjects: usize,
+}
+
+// 检查单个工具：能找到文件且能执行版本查询才算可用。
+async fn check_tool(app: &tauri::AppHandle, name: &str, version_arg: &str) -> ToolStatus {
+  let path = match resolve_tool_path(app, name) {
+    Ok(path) => path,
+    Err(err) => {
+      return ToolStatus {
+        name: name.to_string(),
+        available: false,
+        path: None,
+        version: None,
+        error: Some(err.to_string()),
+      }
+    }
+  };
+  let (version, error) = match Command::new(&path).arg(version_arg).output().await {
+    // 版本信息取输出的第一行，例如 "ffmpeg version 6.1 ..."。
+    Ok(output) if output.status.success() => (
+      String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string()),
+      None,
+    ),
+    Ok(output) => (None, Some(format!("{name} 执行失败 (code {:?})", output.status.code()))),
+    Err(err) => (None, Some(format!("调用 {name} 失败: {err}"))),
+  };
+  ToolStatus {
+    name: name.to_string(),
+    available: error.is_none(),
+    path: Some(path.to_string_lossy().to_string()),
+    version,
+    error,
+  }
+}
+
+// 检查所有依赖工具。
+pub(crate) async fn check_required_tools(app: &tauri::AppHandle) -> Vec<ToolStatus> {
+  let mut result = Vec::new();
+  for (name, version_arg) in REQUIRED_TOOLS {
+    result.push(check_tool(app, name, version_arg).await);
+  }
+  result
+}
+
+// 确保目录存在（不存在则创建），返回规范化后的字符串。
+fn ensure_dir(value: &str, label: &str) -> Result<String, AppError> {
+  let path = PathBuf::from(value.trim());
+  fs::create_dir_all(&path).map_err(|e| AppError::io(format!("创建{label}失败: {e}")))?;
+  Ok(path.to_string_lossy().to_string())
+}
+
+// 从旧数据导入追番条目，返回新增的条目数。
+// 已存在的条目以当前数据为准，不会被覆盖。
+fn import_tracked(app: &tauri::AppHandle, source: &Path) -> Result<usize, AppError> {
+  let file = if source.is_dir() {
+    source.join("watchlist.json")
+  } else {
+    source.to_path_buf()
+  };
+  if !file.exists() {
+    return Err(AppError::invalid_input(format!("未找到可导入的数据: {}", file.display())));
+  }
+  let content = fs::read_to_string(&file).map_err(|e| AppError::io(format!("读取导入数据失败: {e}")))?;
+  let imported: Vec<TrackedSubject> = serde_json::from_str(&content)
+    .map_err(|e| AppError::invalid_input(format!("解析导入数据失败: {e}")))?;
+  let mut data = load_tracked(app)?;
+  let mut added = 0usize;
+  for subject in imported {
+    // 已追踪的条目保持原样，只补上新条目。
+    data.entry(subject.id).or_insert_with(|| {
+      added += 1;
+      subject
+    });
+  }
+  persist_tracked(app, &data)?;
+  Ok(added)
+}
+
+// 返回向导需要的初始信息：是否已完成、工具检查结果、当前设置。
+#[tauri::command]
+pub async fn get_setup_status(app: tauri::AppHandle) -> Result<SetupStatusResponse, AppError> {
+  let settings = load_settings(&app).map_err(AppError::io)?;
+  Ok(SetupStatusResponse {
+    completed: settings.setup_completed,
+    tools: check_required_tools(&app).await,
+    settings,
+    translation_providers: TRANSLATION_PROVIDERS.iter().map(|p| p.to_string()).collect(),
+  })
+}
+
+// 重新检查工具（用户手动放入工具后可再次检测）。
+#[tauri::command]
+pub async fn check_tools(app: tauri::AppHandle) -> Result<Vec<ToolStatus>, AppError> {
+  Ok(check_required_tools(&app).await)
+}
+
+// 一次性应用向导配置：
+// 1) 校验并创建目录、校验代理与翻译服务；
+// 2) 可选导入旧数据；
+// 3) 保存设置并标记向导已完成。
+// 任一步失败都会返回错误，设置不会被部分写入；目录未授权、代理或翻译服务无效时返回 InvalidInput。
+#[tauri::command]
+pub fn apply_initial_setup(
+  app: tauri::AppHandle,
+  config: InitialSetupConfig,
+) -> Result<InitialSetupResponse, AppError> {
+  let previous = load_settings(&app).map_err(AppError::io)?;
+  let mut settings = previous.clone();
+
+  settings.download_dir = config.download_dir.filter(|dir| !dir.trim().is_empty());
+  settings.output_dir = config.output_dir.filter(|dir| !dir.trim().is_empty());
+  // 目录必须是用户通过对话框选择的，校验通过后才创建。
+  ensure_settings_dirs_allowed(&previous, &settings).map_err(AppError::invalid_input)?;
+  let download_dir = match settings.download_dir.as_deref() {
+    Some(dir) => Some(ensure_dir(dir, "下载目录")?),
+    None => None,
+  };
+  let output_dir = match settings.output_dir.as_deref() {
+    Some(dir) => Some(ensure_dir(dir, "输出目录")?),
+    None => None,
+  };
+  let proxy_url = match config.proxy_url.as_deref().map(str::trim) {
+    Some(url) if !url.is_empty() => {
+      reqwest::Proxy::all(url).map_err(|e| AppError::invalid_input(format!("代理地址无效: {e}")))?;
+      Some(url.to_string())
+    }
+    _ => None,
+  };
+  if let Some(provider) = config.translation_provider.as_deref().map(str::trim) {
+    if !TRANSLATION_PROVIDERS.contains(&provider) {
+      return Err(AppError::invalid_input(format!("不支持的翻译服务: {provider}")));
+    }
+    settings.translation_provider = provider.to_string();
+  }
+
+  // 导入放在保存设置之前：导入失败时向导保持未完成，用户可以修正后重试。
+  let imported_subjects = match config.import_path.as_deref().map(str::trim) {
+    Some(path) if !path.is_empty() => {
+      let source = ensure_path_allowed(&app, path).map_err(AppError::invalid_input)?;
+      import_tracked(&app, &source)?
+    }
+    _ => 0,
+  };
+
+  settings.download_dir = download_dir;
+  settings.output_dir = output_dir;
+  settings.proxy_url = proxy_url;
+  settings.setup_completed = true;
+  let settings = save_settings(&app, settings).map_err(AppError::io)?;
+  Ok(InitialSetupResponse {
+    settings,
+    imported_subjects,
+  })
+}
@@ -0,0 +1,86 @@
+/*
+  下载历史：
+  每个下载完成（文件移动到最终目录）的任务记录一条，
+  方便查看某部番之前下载过哪个字幕组的版本。
+  数据保存在 download_history.json，按完成时间先后追加。
+*/
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Mutex;
+
+use super::data_file;
+use crate::services::error::AppError;
+
+// 下载历史文件名。
+const HISTORY_FILE: &str = "download_history.json";
+
+// 读-改-写期间加锁，避免同时完成的两个下载互相覆盖记录。
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// 一条下载记录。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadRecord {
+  /// 对应的 Bangumi 条目 ID（下载时未关联条目为空）。
+  pub subject_id: Option<u32>,
+  /// 种子名称（通常包含字幕组、分辨率等信息）。
+  pub name: String,
+  pub info_hash: String,
+  /// 选中文件的总大小（字节）。
+  pub size: u64,
+  /// 完成时间（RFC 3339）。
+  pub completed_at: String,
+  /// 文件所在的最终目录。
+  pub output_path: String,
+}
+
+// 读取全部记录（文件不存在或为空时返回空列表）。
+fn load_history(app: &tauri::AppHandle) -> Result<Vec<DownloadRecord>, AppError> {
+  let path = data_file(app, HISTORY_FILE)?;
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+  let content = fs::read_to_string(&path).map_err(|e| AppError::io(format!("读取下载历史失败: {e}")))?;
+  if content.trim().is_empty() {
+    return Ok(Vec::new());
+  }
+  serde_json::from_str(&content).map_err(|e| AppError::io(format!("解析下载历史失败: {e}")))
+}
+
+// 写回全部记录。
+fn persist_history(app: &tauri::AppHandle, records: &[DownloadRecord]) -> Result<(), AppError> {
+  let path = data_file(app, HISTORY_FILE)?;
+  let payload =
+    serde_json::to_string_pretty(records).map_err(|e| AppError::internal(format!("序列化下载历史失败: {e}")))?;
+  fs::write(&path, payload).map_err(|e| AppError::io(format!("写入下载历史失败: {e}")))
+}
+
+// 追加一条下载记录（下载完成收尾时调用）。
+pub(crate) fn record_download(app: &tauri::AppHandle, record: DownloadRecord) -> Result<(), AppError> {
+  let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let mut records = load_history(app)?;
+  records.push(record);
+  persist_history(app, &records)
+}
+
+// 返回下载历史（最新的在前），传入 subjectId 时只返回该条目的记录。
+#[tauri::command]
+pub fn list_download_history(
+  app: tauri::AppHandle,
+  subject_id: Option<u32>,
+) -> Result<Vec<DownloadRecord>, AppError> {
+  let mut records = load_history(&app)?;
+  if let Some(subject_id) = subject_id {
+    records.retain(|record| record.subject_id == Some(subject_id));
+  }
+  records.reverse();
+  Ok(records)
+}
+
+// 清空下载历史（不影响已下载的文件）。
+#[tauri::command]
+pub fn clear_download_history(app: tauri::AppHandle) -> Result<(), AppError> {
+  let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  persist_history(&app, &[])
+}
@@ -0,0 +1,271 @@
+/*
+  系统集成模块：
+  处理与操作系统相关的杂项能力。
+  1) 在安装目录旁创建指向数据目录的 user-data 链接：
+     - Windows：目录 junction（不需要管理员权限，也不依赖 cmd）；
+     - macOS / Linux：符号链接。
+  2) 汇总本机硬件能力（CPU / 内存 / GPU / 硬件编码器），
+     供编码流程挑选默认预设，也让前端在任务过重时提前提醒。
+  3) 查询某个路径所在磁盘的剩余空间（下载前的空间检查）。
+*/
+
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use sysinfo::{Disks, System};
+use tauri::Manager;
+use tokio::process::Command;
+
+use super::diagnostics::app_log;
+use super::error::AppError;
+use super::media::probe_hardware_encoders;
+
+/// 本机硬件能力报告。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemInfoResponse {
+  pub os: String,
+  pub cpu_model: String,
+  pub physical_cores: Option<usize>,
+  pub logical_cores: usize,
+  pub total_memory_bytes: u64,
+  pub available_memory_bytes: u64,
+  pub gpus: Vec<String>,
+  pub hardware_encoders: Vec<String>,
+}
+
+// 链接名称（创建在安装目录下）。
+const DATA_LINK_NAME: &str = "user-data";
+
+// 应用数据目录（系统推荐的位置 / hanamirip-cn），不存在时自动创建。
+fn data_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+  let dir = app
+    .path()
+    .app_data_dir()
+    .map_err(|e| format!("无法获取数据目录: {e}"))?
+    .join("hanamirip-cn");
+  fs::create_dir_all(&dir).map_err(|e| format!("创建数据目录失败: {e}"))?;
+  Ok(dir)
+}
+
+// 计算链接应该放在哪个目录：
+// - 一般情况下是 exe 所在目录（真实安装位置）；
+// - macOS 下 exe 位于 xxx.app/Contents/MacOS，往 .app 里写文件会破坏签名，
+//   所以改为放在 .app 所在的目录。
+fn link_parent_dir() -> Result<PathBuf, String> {
+  let exe_path = env::current_exe().map_err(|e| format!("无法获取程序路径: {e}"))?;
+  let exe_dir = exe_path
+    .parent()
+    .map(|p| p.to_path_buf())
+    .unwrap_or_else(|| exe_path.clone());
+  if cfg!(target_os = "macos") {
+    let bundle = exe_dir
+      .ancestors()
+      .find(|p| p.extension().map(|ext| ext == "app").unwrap_or(false));
+    if let Some(parent) = bundle.and_then(|b| b.parent()) {
+      return Ok(parent.to_path_buf());
+    }
+  }
+  Ok(exe_dir)
+}
+
+// 判断路径本身是否是一个“目录链接”（junction 或符号链接），
+// 而不是真实存在的普通目录。用 symlink_metadata 才不会跟随链接。
+fn is_dir_link(path: &Path) -> bool {
+  #[cfg(target_os = "windows")]
+  if junction::exists(path).unwrap_or(false) {
+    return true;
+  }
+  fs::symlink_metadata(path)
+    .map(|meta| meta.file_type().is_symlink())
+    .unwrap_or(false)
+}
+
+// 创建目录链接：link 指向 target。
+fn create_dir_link(target: &Path, link: &Path) -> io::Result<()> {
+  #[cfg(target_os = "windows")]
+  {
+    junction::create(target, link)
+  }
+  #[cfg(unix)]
+  {
+    std::os::unix::fs::symlink(target, link)
+  }
+  #[cfg(not(any(target_os = "windows", unix)))]
+  {
+    let _ = (target, link);
+    Err(io::Error::new(io::ErrorKind::Unsupported, "当前平台不支持目录链接"))
+  }
+}
+
+// 删除目录链接本身（不会删除链接指向的数据）。
+fn remove_dir_link(link: &Path) -> io::Result<()> {
+  #[cfg(target_os = "windows")]
+  {
+    // junction 是带重解析点的空目录：先去掉重解析点，再删掉空目录。
+    if junction::exists(link).unwrap_or(false) {
+      junction::delete(link)?;
+    }
+    fs::remove_dir(link)
+  }
+  #[cfg(not(target_os = "windows"))]
+  {
+    fs::remove_file(link)
+  }
+}
+
+// 在安装目录下创建一个指向数据目录的 user-data 链接。
+// 目的：让用户在安装目录下看到 user-data，方便查找/迁移。
+// 启动时调用；失败只打印日志，不影响应用运行。
+pub(crate) fn ensure_data_link(app: &tauri::AppHandle) {
+  let data_dir = match data_root(app) {
+    Ok(dir) => dir,
+    Err(err) => {
+      app_log!("{err}");
+      return;
+    }
+  };
+  let link_path = match link_parent_dir() {
+    Ok(dir) => dir.join(DATA_LINK_NAME),
+    Err(err) => {
+      app_log!("{err}");
+      return;
+    }
+  };
+
+  // 已存在（无论是链接还是普通目录）就跳过，避免覆盖用户文件。
+  // 注意：指向已失效目标的链接 exists() 为 false，但链接本身仍在。
+  if link_path.exists() || is_dir_link(&link_path) {
+    return;
+  }
+
+  match create_dir_link(&data_dir, &link_path) {
+    Ok(()) => app_log!("created data link at: {}", link_path.display()),
+    Err(err) => app_log!("failed to create data link at {}: {err}", link_path.display()),
+  }
+}
+
+// 重新创建 user-data 链接。
+// 数据目录迁移后旧链接会失效，调用此命令删除旧链接并指向当前数据目录。
+// 成功返回链接路径；如果该位置是真实目录（不是链接），拒绝覆盖并报错。
+#[tauri::command]
+pub fn recreate_data_link(app: tauri::AppHandle) -> Result<String, AppError> {
+  let data_dir = data_root(&app).map_err(AppError::io)?;
+  let link_path = link_parent_dir().map_err(AppError::io)?.join(DATA_LINK_NAME);
+
+  if is_dir_link(&link_path) {
+    remove_dir_link(&link_path).map_err(|e| AppError::io(format!("删除旧数据链接失败: {e}")))?;
+  } else if link_path.exists() {
+    return Err(AppError::io(format!(
+      "{} 已存在且不是链接，请手动处理后重试",
+      link_path.display()
+    )));
+  }
+
+  create_dir_link(&data_dir, &link_path).map_err(|e| AppError::io(format!("创建数据链接失败: {e}")))?;
+  Ok(link_path.to_string_lossy().to_string())
+}
+
+// 路径所在磁盘（分区）的剩余可用空间；找不到对应磁盘时返回 None。
+// 按挂载点取最长前缀匹配，路径不存在时先规范化最近的已存在的上级目录。
+pub(crate) fn available_space(path: &Path) -> Option<u64> {
+  let existing = path.ancestors().find(|dir| dir.exists())?;
+  let resolved = existing.canonicalize().ok()?;
+  let disks = Disks::new_with_refreshed_list();
+  disks
+    .list()
+    .iter()
+    .filter(|disk| resolved.starts_with(disk.mount_point()))
+    .max_by_key(|disk| disk.mount_point().as_os_str().len())
+    .map(|disk| disk.available_space())
+}
+
+// 执行一个查询命令并返回标准输出；失败时返回 None。
+async fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+  let mut command = Command::new(program);
+  command.args(args);
+  // Windows 下不要弹出控制台窗口。
+  #[cfg(target_os = "windows")]
+  {
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    command.creation_flags(CREATE_NO_WINDOW);
+  }
+  let output = command.output().await.ok()?;
+  if !output.status.success() {
+    return None;
+  }
+  Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// 列出显卡名称（尽力而为，查询失败返回空列表）。
+// 不同系统使用各自自带的工具：
+// - Windows：PowerShell 查询 Win32_VideoController；
+// - macOS：system_profiler 的 "Chipset Model" 行；
+// - Linux：lspci 中的 VGA / 3D / Display 设备。
+async fn list_gpus() -> Vec<String> {
+  let names: Vec<String> = if cfg!(target_os = "windows") {
+    command_stdout(
+      "powershell",
+      &[
+        "-NoProfile",
+        "-Command",
+        "Get-CimInstance Win32_VideoController | Select-Object -ExpandProperty Name",
+      ],
+    )
+    .await
+    .map(|out| out.lines().map(|l| l.trim().to_string()).collect())
+    .unwrap_or_default()
+  } else if cfg!(target_os = "macos") {
+    command_stdout("system_profiler", &["SPDisplaysDataType"])
+      .await
+      .map(|out| {
+        out
+          .lines()
+          .filter_map(|l| l.trim().strip_prefix("Chipset Model:"))
+          .map(|l| l.trim().to_string())
+          .collect()
+      })
+      .unwrap_or_default()
+  } else {
+    command_stdout("lspci", &[])
+      .await
+      .map(|out| {
+        out
+          .lines()
+          .filter(|l| l.contains("VGA") || l.contains("3D controller") || l.contains("Display controller"))
+          // 行格式："01:00.0 VGA compatible controller: NVIDIA ..."，取冒号后的设备名。
+          .filter_map(|l| l.splitn(3, ':').nth(2))
+          .map(|l| l.trim().to_string())
+          .collect()
+      })
+      .unwrap_or_default()
+  };
+  names.into_iter().filter(|name| !name.is_empty()).collect()
+}
+
+// 返回本机硬件能力报告。
+// 硬件编码器需要实际调用 ffmpeg 试编码，首次调用可能需要几秒。
+#[tauri::command]
+pub async fn get_system_info(app: tauri::AppHandle) -> Result<SystemInfoResponse, AppError> {
+  let mut sys = System::new();
+  sys.refresh_cpu();
+  sys.refresh_memory();
+  let cpu_model = sys
+    .cpus()
+    .first()
+    .map(|cpu| cpu.brand().trim().to_string())
+    .unwrap_or_default();
+
+  Ok(SystemInfoResponse {
+    os: env::consts::OS.to_string(),
+    cpu_model,
+    physical_cores: sys.physical_core_count(),
+    logical_cores: sys.cpus().len(),
+    total_memory_bytes: sys.total_memory(),
+    available_memory_bytes: sys.available_memory(),
+    gpus: list_gpus().await,
+    hardware_encoders: probe_hardware_encoders(&app).await,
+  })
+}
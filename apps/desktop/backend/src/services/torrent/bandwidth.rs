@@ -0,0 +1,187 @@
+/*
+  带宽计划：
+  按一天中的时段限制下载/上传速率（例如 01:00–08:00 不限速，其余时间限速 2 MB/s），
+  适合白天按流量计费、夜间不限量的网络。
+  - 计划保存在设置里（AppSettings.bandwidth）：默认限速 + 若干时段规则；
+  - 时段按本地时间计算，结束时间早于开始时间表示跨过午夜（例如 23:00–07:00），
+    开始与结束相同表示全天；多条规则同时生效时取排在前面的；
+  - 后台巡检定期按当前时段设置会话的限速，创建/重建会话时也会立即应用；
+    使用 aria2 或 qBittorrent 引擎时设置的是对方的全局限速。
+*/
+
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+use super::current_engine;
+use super::engine::Engine;
+use crate::services::diagnostics::app_log;
+use crate::services::error::AppError;
+use crate::services::settings::{load_settings, save_settings};
+
+// 检查时段是否变化的间隔。
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+// 时间格式（24 小时制）。
+const TIME_FORMAT: &str = "%H:%M";
+
+/// 一个时段的限速规则。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthRule {
+  /// 开始时间（HH:MM，本地时间）。
+  pub start: String,
+  /// 结束时间（HH:MM，不包含），早于开始时间表示跨过午夜。
+  pub end: String,
+  /// 下载限速（KiB/s），为空表示不限速。
+  pub download_limit_kib: Option<u32>,
+  /// 上传限速（KiB/s），为空表示不限速。
+  pub upload_limit_kib: Option<u32>,
+  #[serde(default = "default_enabled")]
+  pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+  true
+}
+
+/// 带宽计划（保存在设置里）。
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BandwidthSettings {
+  /// 不在任何时段内时的下载限速（KiB/s），为空表示不限速。
+  pub download_limit_kib: Option<u32>,
+  /// 不在任何时段内时的上传限速（KiB/s），为空表示不限速。
+  pub upload_limit_kib: Option<u32>,
+  /// 时段规则，排在前面的优先。
+  pub rules: Vec<BandwidthRule>,
+}
+
+/// 当前生效的限速。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthStatus {
+  pub schedule: BandwidthSettings,
+  /// 当前生效的规则序号，不在任何时段内时为空（使用默认限速）。
+  pub active_rule: Option<usize>,
+  pub download_limit_kib: Option<u32>,
+  pub upload_limit_kib: Option<u32>,
+}
+
+// 解析 HH:MM。
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+  NaiveTime::parse_from_str(value.trim(), TIME_FORMAT).map_err(|_| format!("时间格式无效（应为 HH:MM）: {value}"))
+}
+
+impl BandwidthRule {
+  // 时刻是否落在时段内。
+  fn contains(&self, now: NaiveTime) -> bool {
+    let (Ok(start), Ok(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+      return false;
+    };
+    if start < end {
+      start <= now && now < end
+    } else {
+      // 跨过午夜；开始与结束相同时为全天。
+      now >= start || now < end
+    }
+  }
+}
+
+impl BandwidthSettings {
+  // 修正非法值：限速为 0 视为不限速，时间去掉首尾空白。
+  pub(crate) fn normalized(mut self) -> Self {
+    self.download_limit_kib = self.download_limit_kib.filter(|limit| *limit > 0);
+    self.upload_limit_kib = self.upload_limit_kib.filter(|limit| *limit > 0);
+    for rule in &mut self.rules {
+      rule.start = rule.start.trim().to_string();
+      rule.end = rule.end.trim().to_string();
+      rule.download_limit_kib = rule.download_limit_kib.filter(|limit| *limit > 0);
+      rule.upload_limit_kib = rule.upload_limit_kib.filter(|limit| *limit > 0);
+    }
+    self
+  }
+
+  // 校验时段的时间格式。
+  pub(crate) fn validate(&self) -> Result<(), String> {
+    for rule in &self.rules {
+      parse_time(&rule.start)?;
+      parse_time(&rule.end)?;
+    }
+    Ok(())
+  }
+
+  // 某一时刻生效的规则序号与限速（下载, 上传）。
+  fn active_at(&self, now: NaiveTime) -> (Option<usize>, Option<u32>, Option<u32>) {
+    match self
+      .rules
+      .iter()
+      .position(|rule| rule.enabled && rule.contains(now))
+    {
+      Some(index) => {
+        let rule = &self.rules[index];
+        (Some(index), rule.download_limit_kib, rule.upload_limit_kib)
+      }
+      None => (None, self.download_limit_kib, self.upload_limit_kib),
+    }
+  }
+
+  // 当前的限速状态。
+  fn status(self) -> BandwidthStatus {
+    let (active_rule, download_limit_kib, upload_limit_kib) = self.active_at(Local::now().time());
+    BandwidthStatus {
+      schedule: self,
+      active_rule,
+      download_limit_kib,
+      upload_limit_kib,
+    }
+  }
+}
+
+// KiB/s 转换为引擎使用的字节/秒（为空表示不限速）。
+fn to_bps(limit_kib: Option<u32>) -> Option<NonZeroU32> {
+  limit_kib.and_then(|kib| NonZeroU32::new(kib.saturating_mul(1024)))
+}
+
+// 按当前时段设置会话的限速。
+pub(super) async fn apply_schedule(engine: &Engine, settings: &BandwidthSettings) {
+  let (_, download, upload) = settings.active_at(Local::now().time());
+  if let Err(err) = engine.set_rate_limits(to_bps(download), to_bps(upload)).await {
+    app_log!("设置下载限速失败: {err}");
+  }
+}
+
+// 启动带宽计划巡检（下载会话初始化时调用一次）。
+pub(super) fn spawn_bandwidth_worker(app: tauri::AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(CHECK_INTERVAL).await;
+      let Some(engine) = current_engine().await else {
+        continue;
+      };
+      let settings = load_settings(&app).unwrap_or_default().bandwidth;
+      apply_schedule(&engine, &settings).await;
+    }
+  });
+}
+
+// 返回带宽计划与当前生效的限速。
+#[tauri::command]
+pub fn get_bandwidth_schedule(app: tauri::AppHandle) -> Result<BandwidthStatus, AppError> {
+  Ok(load_settings(&app).map_err(AppError::io)?.bandwidth.status())
+}
+
+// 保存带宽计划并立即应用（会话尚未初始化时在初始化时应用），返回修正后的计划与当前生效的限速。
+#[tauri::command]
+pub async fn set_bandwidth_schedule(
+  app: tauri::AppHandle,
+  schedule: BandwidthSettings,
+) -> Result<BandwidthStatus, AppError> {
+  let mut settings = load_settings(&app).map_err(AppError::io)?;
+  settings.bandwidth = schedule;
+  let saved = save_settings(&app, settings).map_err(AppError::invalid_input)?.bandwidth;
+  if let Some(engine) = current_engine().await {
+    apply_schedule(&engine, &saved).await;
+  }
+  Ok(saved.status())
+}
@@ -0,0 +1,207 @@
+/*
+  IP 黑名单：
+  加载 eMule（ipfilter.dat）或 P2P 纯文本格式的黑名单，下载会话不再连接其中的 peer。
+  - 来源可以是本地文件（必须位于允许访问的目录内），也可以是 http(s) 地址；
+    网络地址由定时任务（TaskKind::BlocklistRefresh，默认每周一次）重新拉取；
+  - 两种格式都只支持 IPv4 段，不支持压缩文件：
+      eMule：001.002.003.000 - 001.002.003.255 , 000 , 说明（访问级别大于 127 的行表示允许，跳过）
+      P2P：  说明:1.2.3.0-1.2.3.255
+  - 解析结果统一写成 P2P 格式保存在数据目录的 blocklist.p2p，创建会话时交给引擎加载；
+  - 引擎只在创建会话时读取黑名单，所以来源变化（或拉取到的内容变化）后会重建会话，任务 ID 会变化；
+  - 只有 librqbit 引擎支持，使用 aria2 或 qBittorrent 时黑名单不生效。
+*/
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::Ipv4Addr;
+
+use super::rebuild_session;
+use crate::services::bangumi::shared_client;
+use crate::services::error::AppError;
+use crate::services::limits::send_limited;
+use crate::services::paths::ensure_path_allowed;
+use crate::services::settings::{load_settings, save_settings};
+use crate::services::storage::data_file;
+
+// 解析后的黑名单文件名（P2P 格式）。
+const BLOCKLIST_FILE: &str = "blocklist.p2p";
+// eMule 格式中访问级别大于该值的行表示允许访问。
+const EMULE_BLOCK_LEVEL: u32 = 127;
+
+/// IP 黑名单设置（保存在设置里）。
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BlocklistSettings {
+  /// 黑名单来源：本地文件路径或 http(s) 地址，为空表示不使用黑名单。
+  pub source: Option<String>,
+  /// 已加载的 IP 段数（加载或定时拉取后更新）。
+  pub range_count: usize,
+  /// 上次加载成功的时间（RFC 3339）。
+  pub updated_at: Option<String>,
+}
+
+impl BlocklistSettings {
+  // 空白来源视为未设置。
+  pub(crate) fn normalized(mut self) -> Self {
+    self.source = self
+      .source
+      .map(|source| source.trim().to_string())
+      .filter(|source| !source.is_empty());
+    if self.source.is_none() {
+      self.range_count = 0;
+      self.updated_at = None;
+    }
+    self
+  }
+}
+
+// 来源是否是网络地址。
+fn is_remote(source: &str) -> bool {
+  source.starts_with("http://") || source.starts_with("https://")
+}
+
+// 解析 IPv4 地址（eMule 格式的每段可能带前导零，例如 001.002.003.004）。
+fn parse_ipv4(text: &str) -> Option<Ipv4Addr> {
+  let octets: Vec<u8> = text
+    .trim()
+    .split('.')
+    .map(|part| part.parse::<u8>().ok())
+    .collect::<Option<_>>()?;
+  let octets: [u8; 4] = octets.try_into().ok()?;
+  Some(Ipv4Addr::from(octets))
+}
+
+// 解析 "起始 - 结束" 形式的 IP 段。
+fn parse_range(range: &str) -> Option<(Ipv4Addr, Ipv4Addr)> {
+  let (start, end) = range.split_once('-')?;
+  let (start, end) = (parse_ipv4(start)?, parse_ipv4(end)?);
+  (start <= end).then_some((start, end))
+}
+
+// 解析一行，返回 IP 段；注释、空行、允许访问的行与无法识别的行返回 None。
+fn parse_line(line: &str) -> Option<(Ipv4Addr, Ipv4Addr)> {
+  let line = line.trim();
+  if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+    return None;
+  }
+  // eMule：范围 , 访问级别 , 说明。
+  if let Some((range, rest)) = line.split_once(',') {
+    if let Some(parsed) = parse_range(range) {
+      let level = rest.split(',').next()?.trim().parse::<u32>().ok()?;
+      return (level <= EMULE_BLOCK_LEVEL).then_some(parsed);
+    }
+  }
+  // P2P：说明:范围（说明里可能也有冒号或逗号）。
+  parse_range(line.rsplit_once(':')?.1)
+}
+
+// 读取来源内容：网络地址直接下载，本地文件需位于允许访问的目录内。
+async fn read_source(app: &tauri::AppHandle, source: &str) -> Result<String, AppError> {
+  if !is_remote(source) {
+    let path = ensure_path_allowed(app, source).map_err(AppError::invalid_input)?;
+    let bytes = fs::read(&path).map_err(|e| AppError::io(format!("读取黑名单文件失败: {e}")))?;
+    return Ok(String::from_utf8_lossy(&bytes).into_owned());
+  }
+  let response = send_limited(shared_client().get(source))
+    .await
+    .map_err(AppError::network)?;
+  if !response.status().is_success() {
+    return Err(AppError::api(
+      response.status().as_u16(),
+      format!("下载黑名单失败: {}", response.status()),
+    ));
+  }
+  response
+    .text()
+    .await
+    .map_err(|e| AppError::network(format!("下载黑名单失败: {e}")))
+}
+
+// 读取并解析来源，写入 P2P 格式的黑名单文件，返回 (IP 段数, 内容是否有变化)。
+async fn load_blocklist(app: &tauri::AppHandle, source: &str) -> Result<(usize, bool), AppError> {
+  let text = read_source(app, source).await?;
+  let ranges: Vec<(Ipv4Addr, Ipv4Addr)> = text.lines().filter_map(parse_line).collect();
+  if ranges.is_empty() {
+    return Err(AppError::invalid_input("黑名单为空或格式无法识别（支持 eMule 与 P2P 纯文本格式）"));
+  }
+  let content: String = ranges
+    .iter()
+    .map(|(start, end)| format!("blocklist:{start}-{end}\n"))
+    .collect();
+  let path = data_file(app, BLOCKLIST_FILE)?;
+  let changed = fs::read_to_string(&path).ok().as_deref() != Some(content.as_str());
+  if changed {
+    fs::write(&path, content).map_err(|e| AppError::io(format!("写入黑名单失败: {e}")))?;
+  }
+  Ok((ranges.len(), changed))
+}
+
+// 记录加载结果（请求期间设置可能被修改，重新读取后只更新黑名单部分）。
+fn store_status(
+  app: &tauri::AppHandle,
+  source: Option<String>,
+  range_count: usize,
+) -> Result<BlocklistSettings, AppError> {
+  let mut settings = load_settings(app).map_err(AppError::io)?;
+  settings.blocklist = BlocklistSettings {
+    updated_at: source.as_ref().map(|_| Utc::now().to_rfc3339()),
+    source,
+    range_count,
+  };
+  Ok(save_settings(app, settings).map_err(AppError::io)?.blocklist)
+}
+
+// 创建会话时使用的黑名单地址（file://），未设置来源或文件不存在时为 None。
+pub(super) fn session_blocklist_url(app: &tauri::AppHandle) -> Option<String> {
+  load_settings(app).ok()?.blocklist.source.as_ref()?;
+  let path = data_file(app, BLOCKLIST_FILE).ok()?;
+  if !path.exists() {
+    return None;
+  }
+  url::Url::from_file_path(&path).ok().map(|url| url.to_string())
+}
+
+// 定时任务：重新拉取网络来源的黑名单，内容有变化时重建会话；
+// 未设置来源或来源是本地文件时什么都不做（本地文件在设置来源时已经加载）。
+pub(crate) async fn refresh_blocklist(app: &tauri::AppHandle) -> Result<(), AppError> {
+  let Some(source) = load_settings(app).map_err(AppError::io)?.blocklist.source.filter(|source| is_remote(source)) else {
+    return Ok(());
+  };
+  let (range_count, changed) = load_blocklist(app, &source).await?;
+  store_status(app, Some(source), range_count)?;
+  if changed {
+    rebuild_session(app).await?;
+  }
+  Ok(())
+}
+
+// 返回黑名单来源与已加载的 IP 段数。
+#[tauri::command]
+pub fn get_blocklist_status(app: tauri::AppHandle) -> Result<BlocklistSettings, AppError> {
+  Ok(load_settings(&app).map_err(AppError::io)?.blocklist)
+}
+
+// 设置黑名单来源（为空时停用黑名单），加载后重建会话（任务 ID 会变化），返回加载结果。
+#[tauri::command]
+pub async fn set_blocklist_source(
+  app: tauri::AppHandle,
+  source: Option<String>,
+) -> Result<BlocklistSettings, AppError> {
+  let source = source
+    .map(|source| source.trim().to_string())
+    .filter(|source| !source.is_empty());
+  let range_count = match &source {
+    Some(source) => load_blocklist(&app, source).await?.0,
+    None => {
+      let path = data_file(&app, BLOCKLIST_FILE)?;
+      if path.exists() {
+        fs::remove_file(&path).map_err(|e| AppError::io(format!("删除黑名单失败: {e}")))?;
+      }
+      0
+    }
+  };
+  let saved = store_status(&app, source, range_count)?;
+  rebuild_session(&app).await?;
+  Ok(saved)
+}
@@ -0,0 +1,405 @@
+/*
+  制作种子（给发布压制成品的用户使用）：
+  - 输入可以是单个文件或目录（目录下的文件按相对路径排序后全部收录）；
+  - 生成 BitTorrent v1 种子，写在输入旁边（<文件或目录名>.torrent）；
+    种子路径同样要经过路径校验，已存在时直接报错，不会覆盖；
+  - 分块大小可以指定（16 KiB ~ 64 MiB 之间的 2 的幂），省略时按总大小自动选择，
+    让分块数量不超过 TARGET_PIECE_COUNT；
+  - 计算分块哈希需要完整读一遍文件，放在阻塞线程池里执行，并受媒体任务并发上限约束。
+*/
+
+use chrono::Utc;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use super::trackers::clean_trackers;
+use crate::services::error::AppError;
+use crate::services::limits::{acquire, LimitKind};
+use crate::services::paths::ensure_path_allowed;
+use crate::services::power::keep_awake;
+
+// 分块大小的范围与自动选择时的起点。
+const MIN_PIECE_SIZE: u64 = 16 * 1024;
+const MAX_PIECE_SIZE: u64 = 64 * 1024 * 1024;
+const AUTO_MIN_PIECE_SIZE: u64 = 256 * 1024;
+const AUTO_MAX_PIECE_SIZE: u64 = 16 * 1024 * 1024;
+// 自动选择分块大小时的目标分块数上限。
+const TARGET_PIECE_COUNT: u64 = 2000;
+// 写入种子的制作程序名。
+const CREATED_BY: &str = "HanamiRIP";
+
+/// create_torrent 的结果。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedTorrent {
+  /// 生成的 .torrent 文件路径。
+  pub torrent_path: String,
+  /// info hash（40 位小写十六进制）。
+  pub info_hash: String,
+  pub name: String,
+  pub piece_size: u64,
+  pub piece_count: usize,
+  pub total_size: u64,
+  pub file_count: usize,
+}
+
+// 收录的文件：绝对路径、种子里的相对路径（各级名称）、大小。
+struct SourceFile {
+  path: PathBuf,
+  components: Vec<String>,
+  size: u64,
+}
+
+// bencode 值（字典用 BTreeMap，键按字节序排列，符合规范要求）。
+enum Bencode {
+  Int(i64),
+  Bytes(Vec<u8>),
+  List(Vec<Bencode>),
+  Dict(BTreeMap<Vec<u8>, Bencode>),
+}
+
+impl Bencode {
+  fn text(value: &str) -> Self {
+    Bencode::Bytes(value.as_bytes().to_vec())
+  }
+
+  fn encode(&self, out: &mut Vec<u8>) {
+    match self {
+      Bencode::Int(value) => out.extend_from_slice(format!("i{value}e").as_bytes()),
+      Bencode::Bytes(bytes) => {
+        out.extend_from_slice(format!("{}:", bytes.len()).as_bytes());
+        out.extend_from_slice(bytes);
+      }
+      Bencode::List(items) => {
+        out.push(b'l');
+        for item in items {
+          item.encode(out);
+        }
+        out.push(b'e');
+      }
+      Bencode::Dict(entries) => {
+        out.push(b'd');
+        for (key, value) in entries {
+          Bencode::Bytes(key.clone()).encode(out);
+          value.encode(out);
+        }
+        out.push(b'e');
+      }
+    }
+  }
+
+  fn to_bytes(&self) -> Vec<u8> {
+    let mut out = Vec::new();
+    self.encode(&mut out);
+    out
+  }
+}
+
+// 由键值对构造字典。
+fn dict(entries: Vec<(&str, Bencode)>) -> Bencode {
+  Bencode::Dict(
+    entries
+      .into_iter()
+      .map(|(key, value)| (key.as_bytes().to_vec(), value))
+      .collect(),
+  )
+}
+
+// 文件名必须是合法 UTF-8，种子里的路径按 UTF-8 保存。
+fn name_of(path: &Path) -> Result<String, AppError> {
+  path
+    .file_name()
+    .and_then(|name| name.to_str())
+    .map(str::to_string)
+    .ok_or_else(|| AppError::invalid_input(format!("文件名无法识别: {}", path.display())))
+}
+
+// 递归收集目录下的文件（跳过符号链接），按相对路径排序。
+fn collect_files(root: &Path) -> Result<Vec<SourceFile>, AppError> {
+  let mut files = Vec::new();
+  let mut pending = vec![root.to_path_buf()];
+  while let Some(dir) = pending.pop() {
+    let entries = fs::read_dir(&dir).map_err(|e| AppError::io(format!("读取目录 {} 失败: {e}", dir.display())))?;
+    for entry in entries {
+      let entry = entry.map_err(|e| AppError::io(format!("读取目录 {} 失败: {e}", dir.display())))?;
+      let file_type = entry
+        .file_type()
+        .map_err(|e| AppError::io(format!("读取 {} 失败: {e}", entry.path().display())))?;
+      let path = entry.path();
+      if file_type.is_dir() {
+        pending.push(path);
+      } else if file_type.is_file() {
+        let size = entry
+          .metadata()
+          .map_err(|e| AppError::io(format!("读取 {} 失败: {e}", path.display())))?
+          .len();
+        let components = path
+          .strip_prefix(root)
+          .unwrap_or(&path)
+          .components()
+          .filter_map(|component| match component {
+            Component::Normal(name) => Some(
+              name
+                .to_str()
+                .map(str::to_string)
+                .ok_or_else(|| AppError::invalid_input(format!("文件名无法识别: {}", path.display()))),
+            ),
+            _ => None,
+          })
+          .collect::<Result<Vec<_>, _>>()?;
+        files.push(SourceFile { path, components, size });
+      }
+    }
+  }
+  files.sort_by(|a, b| a.components.cmp(&b.components));
+  Ok(files)
+}
+
+// 按总大小选择分块大小：从 AUTO_MIN_PIECE_SIZE 开始翻倍，直到分块数不超过目标。
+fn auto_piece_size(total: u64) -> u64 {
+  let mut size = AUTO_MIN_PIECE_SIZE;
+  while size < AUTO_MAX_PIECE_SIZE && total.div_ceil(size) > TARGET_PIECE_COUNT {
+    size *= 2;
+  }
+  size
+}
+
+// 依次读取全部文件（跨文件连续分块），返回每块的 SHA-1 拼接结果。
+fn hash_pieces(files: &[SourceFile], piece_size: u64) -> Result<Vec<u8>, AppError> {
+  let mut pieces = Vec::new();
+  let mut buffer = vec![0u8; piece_size as usize];
+  let mut filled = 0usize;
+  for file in files {
+    let mut reader =
+      File::open(&file.path).map_err(|e| AppError::io(format!("打开 {} 失败: {e}", file.path.display())))?;
+    let mut read_total = 0u64;
+    loop {
+      let read = reader
+        .read(&mut buffer[filled..])
+        .map_err(|e| AppError::io(format!("读取 {} 失败: {e}", file.path.display())))?;
+      if read == 0 {
+        break;
+      }
+      filled += read;
+      read_total += read as u64;
+      if filled == buffer.len() {
+        pieces.extend_from_slice(&Sha1::digest(&buffer));
+        filled = 0;
+      }
+    }
+    // 读取期间文件被修改时，分块与文件长度对不上，生成的种子无法使用。
+    if read_total != file.size {
+      return Err(AppError::io(format!("{} 在读取期间发生了变化", file.path.display())));
+    }
+  }
+  if filled > 0 {
+    pieces.extend_from_slice(&Sha1::digest(&buffer[..filled]));
+  }
+  Ok(pieces)
+}
+
+// 生成种子文件内容，返回 (种子内容, info hash)。
+fn build_torrent(
+  name: &str,
+  files: &[SourceFile],
+  single_file: bool,
+  piece_size: u64,
+  pieces: Vec<u8>,
+  trackers: &[String],
+  private: bool,
+) -> (Vec<u8>, String) {
+  let mut info = vec![
+    ("name", Bencode::text(name)),
+    ("piece length", Bencode::Int(piece_size as i64)),
+    ("pieces", Bencode::Bytes(pieces)),
+  ];
+  if single_file {
+    info.push(("length", Bencode::Int(files[0].size as i64)));
+  } else {
+    let entries = files
+      .iter()
+      .map(|file| {
+        dict(vec![
+          ("length", Bencode::Int(file.size as i64)),
+          (
+            "path",
+            Bencode::List(file.components.iter().map(|part| Bencode::text(part)).collect()),
+          ),
+        ])
+      })
+      .collect();
+    info.push(("files", Bencode::List(entries)));
+  }
+  if private {
+    info.push(("private", Bencode::Int(1)));
+  }
+  let info = dict(info);
+  let info_hash: String = Sha1::digest(info.to_bytes())
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect();
+
+  let mut root = vec![
+    ("created by", Bencode::text(CREATED_BY)),
+    ("creation date", Bencode::Int(Utc::now().timestamp())),
+    ("info", info),
+  ];
+  if let Some(first) = trackers.first() {
+    root.push(("announce", Bencode::text(first)));
+  }
+  // 多个 tracker 时每个一层，客户端按顺序尝试。
+  if trackers.len() > 1 {
+    let tiers = trackers
+      .iter()
+      .map(|tracker| Bencode::List(vec![Bencode::text(tracker)]))
+      .collect();
+    root.push(("announce-list", Bencode::List(tiers)));
+  }
+  (dict(root).to_bytes(), info_hash)
+}
+
+// 为文件或目录制作种子，写在输入旁边，返回种子路径与 info hash。
+#[tauri::command]
+pub async fn create_torrent(
+  app: tauri::AppHandle,
+  path: String,
+  trackers: Option<Vec<String>>,
+  piece_size: Option<u64>,
+  private: Option<bool>,
+) -> Result<CreatedTorrent, AppError> {
+  let source = ensure_path_allowed(&app, &path).map_err(AppError::invalid_input)?;
+  let metadata = fs::metadata(&source).map_err(|e| AppError::io(format!("读取 {} 失败: {e}", source.display())))?;
+  if let Some(size) = piece_size {
+    if !size.is_power_of_two() || !(MIN_PIECE_SIZE..=MAX_PIECE_SIZE).contains(&size) {
+      return Err(AppError::invalid_input("分块大小必须是 16 KiB ~ 64 MiB 之间的 2 的幂"));
+    }
+  }
+  let trackers = clean_trackers(trackers.unwrap_or_default());
+  let private = private.unwrap_or(false);
+  // 种子文件写在输入旁边：输出路径也要在允许的目录里，且不能覆盖已有文件。
+  let name = name_of(&source)?;
+  let torrent_path = source.with_file_name(format!("{name}.torrent"));
+  let torrent_path =
+    ensure_path_allowed(&app, &torrent_path.to_string_lossy()).map_err(AppError::invalid_input)?;
+  if torrent_path.exists() {
+    return Err(AppError::invalid_input(format!("种子文件已存在: {}", torrent_path.display())));
+  }
+
+  let _job_permit = acquire(LimitKind::MediaJob).await?;
+  let _awake = keep_awake("正在制作种子");
+  tauri::async_runtime::spawn_blocking(move || {
+    let single_file = metadata.is_file();
+    let files = if single_file {
+      vec![SourceFile {
+        path: source.clone(),
+        components: vec![name.clone()],
+        size: metadata.len(),
+      }]
+    } else {
+      collect_files(&source)?
+    };
+    let total_size: u64 = files.iter().map(|file| file.size).sum();
+    if total_size == 0 {
+      return Err(AppError::invalid_input(
+        "没有可以收录的文件（文件为空或目录下没有文件）",
+      ));
+    }
+    let piece_size = piece_size.unwrap_or_else(|| auto_piece_size(total_size));
+    let pieces = hash_pieces(&files, piece_size)?;
+    let piece_count = pieces.len() / 20;
+    let (content, info_hash) = build_torrent(&name, &files, single_file, piece_size, pieces, &trackers, private);
+    // 计算哈希期间可能有同名文件出现，用 create_new 保证不会覆盖。
+    let mut file = fs::OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .open(&torrent_path)
+      .map_err(|e| AppError::io(format!("创建种子文件 {} 失败: {e}", torrent_path.display())))?;
+    file
+      .write_all(&content)
+      .map_err(|e| AppError::io(format!("写入种子文件失败: {e}")))?;
+    Ok(CreatedTorrent {
+      torrent_path: torrent_path.to_string_lossy().to_string(),
+      info_hash,
+      name,
+      piece_size,
+      piece_count,
+      total_size,
+      file_count: files.len(),
+    })
+  })
+  .await
+  .map_err(|e| AppError::internal(format!("制作种子任务失败: {e}")))?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+  }
+
+  #[test]
+  fn encodes_bencode() {
+    let cases = [
+      (Bencode::Int(-42), "i-42e"),
+      (Bencode::text("spam"), "4:spam"),
+      (Bencode::text(""), "0:"),
+      (Bencode::List(vec![Bencode::text("a"), Bencode::Int(1)]), "l1:ai1ee"),
+      // 字典的键按字节序输出，与插入顺序无关。
+      (dict(vec![("zz", Bencode::Int(1)), ("a b", Bencode::text("x"))]), "d3:a b1:x2:zzi1ee"),
+    ];
+    for (value, expected) in cases {
+      assert_eq!(String::from_utf8(value.to_bytes()).unwrap(), expected);
+    }
+  }
+
+  #[test]
+  fn computes_known_info_hashes() {
+    // 期望值由独立的 bencode 实现计算。
+    let pieces = Sha1::digest(b"hello world\n").to_vec();
+    let files = [SourceFile {
+      path: PathBuf::new(),
+      components: vec!["hello.txt".to_string()],
+      size: 12,
+    }];
+    let (content, info_hash) = build_torrent("hello.txt", &files, true, 16384, pieces, &[], false);
+    assert_eq!(info_hash, "7b5e918f364908afab937ecdd84059dfb61102b7");
+    assert!(content.starts_with(b"d10:created by9:HanamiRIP13:creation datei"));
+
+    let dir = std::env::temp_dir().join(format!("hanamirip-create-test-{}", std::process::id()));
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("a.txt"), b"abc").unwrap();
+    fs::write(dir.join("sub").join("b.txt"), b"defg").unwrap();
+    let files = collect_files(&dir).unwrap();
+    let pieces = hash_pieces(&files, 4).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    // 分块跨越文件边界："abcd" 与 "efg"。
+    assert_eq!(
+      hex(&pieces),
+      "81fe8bfe87576c3ecb22426f8e57847382917acfcbf019b764b9477080c5a9a748a2911a5fa6d614"
+    );
+    let trackers = ["udp://a/announce".to_string(), "udp://b/announce".to_string()];
+    let (content, info_hash) = build_torrent("pack", &files, false, 4, pieces, &trackers, true);
+    assert_eq!(info_hash, "20f0b711c56273f9930a92bcf0b4bf8b595b17cc");
+    let content = String::from_utf8_lossy(&content);
+    assert!(content.contains("8:announce16:udp://a/announce13:announce-listll16:udp://a/announceel16:udp://b/announceee"));
+  }
+
+  #[test]
+  fn picks_piece_sizes() {
+    let cases = [
+      (0, AUTO_MIN_PIECE_SIZE),
+      (AUTO_MIN_PIECE_SIZE * TARGET_PIECE_COUNT, AUTO_MIN_PIECE_SIZE),
+      (AUTO_MIN_PIECE_SIZE * TARGET_PIECE_COUNT + 1, AUTO_MIN_PIECE_SIZE * 2),
+      (u64::MAX / 2, AUTO_MAX_PIECE_SIZE),
+    ];
+    for (total, expected) in cases {
+      assert_eq!(auto_piece_size(total), expected, "{total}");
+    }
+  }
+}
@@ -0,0 +1,534 @@
+/*
+  aria2 引擎：通过 JSON-RPC 操作已经运行的 aria2（需以 --enable-rpc 启动）。
+  - GID 是 64 位数，直接换算会超出前端数字的精度，任务 ID 由 IdTable 按 GID 首次出现的顺序分配；
+  - 磁力链接先由 aria2 下载元数据，完成后生成真正的下载任务（新的 GID），加入时等到这一步完成；
+    种子地址（.torrent 结尾）由应用下载后通过 aria2.addTorrent 加入，其它地址交给 aria2 直接下载；
+  - aria2 自己保存任务，应用重建会话时不需要重新加入；会话参数（端口、DHT、代理等）与 IP 黑名单
+    以 aria2 自己的配置为准；
+  - 非 BT 任务没有 info hash，用 GID 代替。
+*/
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use once_cell::sync::Lazy;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::{
+  format_remaining, AddOptions, AddedTorrent, DownloadEngine, EngineDetails, EngineFile, EngineFuture, EngineKind,
+  EngineStats, EngineTorrent, IdTable,
+};
+use crate::services::bangumi::shared_client;
+use crate::services::bangumi::deobfuscate_credential_value;
+use crate::services::error::AppError;
+use crate::services::limits::send_limited;
+use crate::services::media::format_bytes_readable;
+use crate::services::torrent::TorrentSessionSettings;
+
+// RPC 请求的连接超时与总超时。
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// 等待磁力链接元数据的间隔与上限。
+const METADATA_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const METADATA_TIMEOUT: Duration = Duration::from_secs(600);
+// 移除任务后等待 aria2 释放文件的上限。
+const REMOVE_TIMEOUT: Duration = Duration::from_secs(5);
+// 列出等待中/已停止任务时的数量上限。
+const LIST_LIMIT: u32 = 1000;
+
+// GID 与任务 ID 的对应关系（重建会话后保持不变）。
+static IDS: Lazy<IdTable> = Lazy::new(IdTable::default);
+
+// 连接到的 aria2。
+pub(super) struct Aria2Engine {
+  client: Client,
+  url: String,
+  secret: Option<String>,
+}
+
+// aria2.tellStatus 返回的任务信息（数字都以字符串表示）。
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct Aria2Status {
+  gid: String,
+  status: String,
+  total_length: String,
+  completed_length: String,
+  upload_length: String,
+  download_speed: String,
+  upload_speed: String,
+  info_hash: Option<String>,
+  dir: String,
+  files: Vec<Aria2File>,
+  bittorrent: Option<Aria2Bittorrent>,
+  error_message: Option<String>,
+  followed_by: Vec<String>,
+  bitfield: Option<String>,
+  piece_length: String,
+  verify_integrity_pending: Option<String>,
+  verified_length: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct Aria2File {
+  path: String,
+  length: String,
+  completed_length: String,
+  selected: String,
+  uris: Vec<Aria2Uri>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Aria2Uri {
+  uri: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct Aria2Bittorrent {
+  announce_list: Vec<Vec<String>>,
+  // 磁力链接的元数据尚未下载完成时为空。
+  info: Option<Aria2Info>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Aria2Info {
+  name: String,
+}
+
+// aria2 以字符串表示数字，无法解析时视为 0。
+fn number(text: &str) -> u64 {
+  text.parse().unwrap_or(0)
+}
+
+// 位图（十六进制）开头连续为 1 的位数，即从开头起连续下载完成的分块数。
+fn leading_pieces(bitfield: &str) -> u64 {
+  let mut pieces = 0;
+  for c in bitfield.chars() {
+    let Some(nibble) = c.to_digit(16) else {
+      break;
+    };
+    let ones = (nibble << 28).leading_ones() as u64;
+    pieces += ones;
+    if ones < 4 {
+      break;
+    }
+  }
+  pieces
+}
+
+// 地址是否指向种子文件。
+fn is_torrent_url(url: &str) -> bool {
+  url::Url::parse(url).is_ok_and(|url| {
+    matches!(url.scheme(), "http" | "https") && url.path().to_ascii_lowercase().ends_with(".torrent")
+  })
+}
+
+impl Aria2Status {
+  // 磁力链接的元数据下载（完成后会生成新的任务）。
+  fn is_metadata(&self) -> bool {
+    !self.followed_by.is_empty() || self.bittorrent.as_ref().is_some_and(|bt| bt.info.is_none())
+  }
+
+  fn info_hash(&self) -> String {
+    self.info_hash.clone().unwrap_or_else(|| self.gid.clone())
+  }
+
+  // BT 任务取种子名，其它任务取第一个文件的文件名。
+  fn name(&self) -> Option<String> {
+    if let Some(info) = self.bittorrent.as_ref().and_then(|bt| bt.info.as_ref()) {
+      return Some(info.name.clone());
+    }
+    self
+      .files
+      .first()
+      .and_then(|file| Path::new(&file.path).file_name())
+      .map(|name| name.to_string_lossy().to_string())
+  }
+
+  // 状态名统一成 librqbit 的写法；已完成（aria2 已停止做种）视为暂停。
+  fn state(&self) -> String {
+    let state = match self.status.as_str() {
+      "active" if self.verify_integrity_pending.is_some() || self.verified_length.is_some() => "Initializing",
+      "active" => "Live",
+      "waiting" => "Waiting",
+      "error" => "Error",
+      _ => "Paused",
+    };
+    state.to_string()
+  }
+
+  fn finished(&self) -> bool {
+    let total = number(&self.total_length);
+    self.status == "complete" || (total > 0 && number(&self.completed_length) >= total)
+  }
+
+  // 文件列表（路径相对于下载目录）；路径尚未确定（HTTP 下载开始前）时为 None。
+  fn files(&self) -> Option<Vec<EngineFile>> {
+    if self.files.iter().any(|file| file.path.is_empty()) {
+      return None;
+    }
+    let files = self
+      .files
+      .iter()
+      .map(|file| {
+        let relative = Path::new(&file.path).strip_prefix(&self.dir).unwrap_or(Path::new(&file.path));
+        let components: Vec<String> = relative
+          .components()
+          .map(|part| part.as_os_str().to_string_lossy().to_string())
+          .collect();
+        EngineFile {
+          name: components.join("/"),
+          components,
+          length: number(&file.length),
+          included: file.selected == "true",
+        }
+      })
+      .collect();
+    Some(files)
+  }
+
+  fn stats(&self) -> EngineStats {
+    let total = number(&self.total_length);
+    let completed = number(&self.completed_length);
+    let download = number(&self.download_speed);
+    let (download_speed, upload_speed, time_remaining) = if self.status == "active" {
+      (
+        Some(format!("{}/s", format_bytes_readable(download))),
+        Some(format!("{}/s", format_bytes_readable(number(&self.upload_speed)))),
+        (download > 0 && completed < total).then(|| format_remaining((total - completed) / download)),
+      )
+    } else {
+      (None, None, None)
+    };
+    EngineStats {
+      state: self.state(),
+      progress_bytes: completed,
+      total_bytes: total,
+      uploaded_bytes: number(&self.upload_length),
+      finished: self.finished(),
+      error: self.error_message.clone().filter(|message| !message.is_empty()),
+      download_speed,
+      upload_speed,
+      time_remaining,
+      file_progress: self.files.iter().map(|file| number(&file.completed_length)).collect(),
+    }
+  }
+
+  fn details(&self) -> EngineDetails {
+    EngineDetails {
+      info_hash: self.info_hash(),
+      name: self.name(),
+      output_folder: self.dir.clone(),
+      files: self.files(),
+      source: match self.bittorrent {
+        Some(_) => None,
+        None => self
+          .files
+          .first()
+          .and_then(|file| file.uris.first())
+          .map(|uri| uri.uri.clone()),
+      },
+    }
+  }
+}
+
+impl Aria2Engine {
+  // 连接 aria2 并确认 RPC 可用。
+  pub(super) async fn connect(settings: &TorrentSessionSettings) -> Result<Self, AppError> {
+    // RPC 一般在本机，不走应用的代理。
+    let client = Client::builder()
+      .no_proxy()
+      .connect_timeout(CONNECT_TIMEOUT)
+      .timeout(REQUEST_TIMEOUT)
+      .build()
+      .map_err(|e| AppError::torrent(format!("创建 aria2 客户端失败: {e}")))?;
+    let secret = settings
+      .aria2_secret
+      .as_deref()
+      .map(deobfuscate_credential_value)
+      .transpose()
+      .map_err(|e| AppError::invalid_input(format!("aria2 RPC 密钥无效: {e}")))?;
+    let engine = Self {
+      client,
+      url: settings.aria2_rpc_url.clone(),
+      secret,
+    };
+    engine
+      .call::<Value>("aria2.getVersion", Vec::new())
+      .await
+      .map_err(|e| AppError::torrent(format!("连接 aria2 失败（{}）: {e}", engine.url)))?;
+    Ok(engine)
+  }
+
+  // 调用 RPC 方法（设置了密钥时自动带上）。
+  async fn call<T: DeserializeOwned>(&self, method: &str, params: Vec<Value>) -> Result<T, AppError> {
+    let mut all = Vec::with_capacity(params.len() + 1);
+    if let Some(secret) = &self.secret {
+      all.push(json!(format!("token:{secret}")));
+    }
+    all.extend(params);
+    let body = json!({ "jsonrpc": "2.0", "id": "hanamirip", "method": method, "params": all });
+    let response = self
+      .client
+      .post(&self.url)
+      .json(&body)
+      .send()
+      .await
+      .map_err(|e| AppError::network(format!("aria2 请求失败: {e}")))?;
+    // 出错时 aria2 同样返回 JSON（HTTP 状态码为 400），按内容判断。
+    let mut value: Value = response
+      .json()
+      .await
+      .map_err(|e| AppError::torrent(format!("aria2 返回的内容无法解析: {e}")))?;
+    if let Some(error) = value.get("error") {
+      let message = error.get("message").and_then(Value::as_str).unwrap_or("未知错误");
+      return Err(AppError::torrent(format!("aria2 报错: {message}")));
+    }
+    let result = value.get_mut("result").map(Value::take).unwrap_or(Value::Null);
+    serde_json::from_value(result)
+      .map_err(|e| AppError::torrent(format!("aria2 返回的内容无法解析: {e}")))
+  }
+
+  async fn status(&self, gid: &str) -> Result<Aria2Status, AppError> {
+    self.call("aria2.tellStatus", vec![json!(gid)]).await
+  }
+
+  async fn status_of(&self, id: usize) -> Result<Aria2Status, AppError> {
+    self.status(&IDS.key(id)?).await
+  }
+
+  // 等待磁力链接的元数据下载完成，返回生成的下载任务的 GID。
+  async fn wait_for_metadata(&self, gid: &str) -> Result<String, AppError> {
+    let started = Instant::now();
+    loop {
+      let status = self.status(gid).await?;
+      if let Some(followed) = status.followed_by.first() {
+        let _ = self.call::<Value>("aria2.removeDownloadResult", vec![json!(gid)]).await;
+        return Ok(followed.clone());
+      }
+      if matches!(status.status.as_str(), "error" | "removed") {
+        let _ = self.call::<Value>("aria2.removeDownloadResult", vec![json!(gid)]).await;
+        return Err(AppError::torrent(
+          status.error_message.unwrap_or_else(|| "获取元数据失败".to_string()),
+        ));
+      }
+      if started.elapsed() >= METADATA_TIMEOUT {
+        let _ = self.remove(gid).await;
+        return Err(AppError::torrent("获取元数据超时"));
+      }
+      tokio::time::sleep(METADATA_POLL_INTERVAL).await;
+    }
+  }
+
+  // 下载种子文件后加入。
+  async fn add_torrent_file(&self, url: &str, options: Map<String, Value>) -> Result<String, AppError> {
+    let response = send_limited(shared_client().get(url))
+      .await
+      .map_err(AppError::network)?;
+    if !response.status().is_success() {
+      return Err(AppError::api(
+        response.status().as_u16(),
+        format!("下载种子失败: {}", response.status()),
+      ));
+    }
+    let bytes = response
+      .bytes()
+      .await
+      .map_err(|e| AppError::network(format!("下载种子失败: {e}")))?;
+    self
+      .call("aria2.addTorrent", vec![json!(BASE64.encode(&bytes)), json!([]), Value::Object(options)])
+      .await
+  }
+
+  // 移除任务（保留文件）并清除结果；运行中的任务等到 aria2 真正停止（释放文件）后再返回。
+  async fn remove(&self, gid: &str) -> Result<(), AppError> {
+    let status = self.status(gid).await?;
+    if matches!(status.status.as_str(), "active" | "waiting" | "paused") {
+      self.call::<Value>("aria2.forceRemove", vec![json!(gid)]).await?;
+      let started = Instant::now();
+      while started.elapsed() < REMOVE_TIMEOUT {
+        match self.status(gid).await {
+          Ok(status) if status.status != "removed" => {}
+          _ => break,
+        }
+        tokio::time::sleep(METADATA_POLL_INTERVAL).await;
+      }
+    }
+    let _ = self.call::<Value>("aria2.removeDownloadResult", vec![json!(gid)]).await;
+    Ok(())
+  }
+
+  // 修改任务要下载的文件（aria2 的文件序号从 1 开始）。
+  async fn select_files(&self, gid: &str, files: impl Iterator<Item = usize>) -> Result<(), AppError> {
+    let mut files: Vec<usize> = files.collect();
+    files.sort_unstable();
+    let selected: Vec<String> = files.iter().map(|index| (index + 1).to_string()).collect();
+    self
+      .call::<Value>("aria2.changeOption", vec![json!(gid), json!({ "select-file": selected.join(",") })])
+      .await?;
+    Ok(())
+  }
+}
+
+impl DownloadEngine for Aria2Engine {
+  fn kind(&self) -> EngineKind {
+    EngineKind::Aria2
+  }
+
+  // 运行中、等待中与已停止的任务（不含已移除的任务与元数据下载）。
+  fn list(&self) -> EngineFuture<'_, Vec<EngineTorrent>> {
+    Box::pin(async move {
+      let mut all: Vec<Aria2Status> = self.call("aria2.tellActive", Vec::new()).await?;
+      all.extend(
+        self
+          .call::<Vec<Aria2Status>>("aria2.tellWaiting", vec![json!(0), json!(LIST_LIMIT)])
+          .await?,
+      );
+      all.extend(
+        self
+          .call::<Vec<Aria2Status>>("aria2.tellStopped", vec![json!(0), json!(LIST_LIMIT)])
+          .await?,
+      );
+      Ok(
+        all
+          .into_iter()
+          .filter(|status| status.status != "removed" && !status.is_metadata())
+          .map(|status| EngineTorrent {
+            id: IDS.id(&status.gid),
+            info_hash: status.info_hash(),
+            name: status.name(),
+            output_folder: status.dir,
+          })
+          .collect(),
+      )
+    })
+  }
+
+  fn stats(&self, id: usize) -> EngineFuture<'_, EngineStats> {
+    Box::pin(async move { Ok(self.status_of(id).await?.stats()) })
+  }
+
+  fn details(&self, id: usize) -> EngineFuture<'_, EngineDetails> {
+    Box::pin(async move { Ok(self.status_of(id).await?.details()) })
+  }
+
+  fn add(&self, url: String, opts: AddOptions) -> EngineFuture<'_, AddedTorrent> {
+    Box::pin(async move {
+      let paused = (opts.paused || opts.list_only).to_string();
+      let mut options = Map::new();
+      if let Some(folder) = opts.output_folder {
+        options.insert("dir".to_string(), json!(folder));
+      }
+      if let Some(trackers) = opts.trackers.filter(|trackers| !trackers.is_empty()) {
+        options.insert("bt-tracker".to_string(), json!(trackers.join(",")));
+      }
+      // 目录里已有文件时先校验、接着下载（重新加入任务时沿用已下载的部分）。
+      options.insert("check-integrity".to_string(), json!("true"));
+      options.insert("continue".to_string(), json!("true"));
+
+      let gid: String = if url.starts_with("magnet:") {
+        // 元数据下载本身不能暂停，暂停的是之后生成的下载任务。
+        options.insert("pause-metadata".to_string(), json!(paused));
+        let gid: String = self.call("aria2.addUri", vec![json!([url]), Value::Object(options)]).await?;
+        self.wait_for_metadata(&gid).await?
+      } else if is_torrent_url(&url) {
+        options.insert("pause".to_string(), json!(paused));
+        self.add_torrent_file(&url, options).await?
+      } else {
+        options.insert("pause".to_string(), json!(paused));
+        self.call("aria2.addUri", vec![json!([url]), Value::Object(options)]).await?
+      };
+
+      if let Some(files) = &opts.only_files {
+        if let Err(err) = self.select_files(&gid, files.iter().copied()).await {
+          let _ = self.remove(&gid).await;
+          return Err(err);
+        }
+      }
+      let details = self.status(&gid).await?.details();
+      if opts.list_only {
+        self.remove(&gid).await?;
+        return Ok(AddedTorrent { id: None, details });
+      }
+      Ok(AddedTorrent {
+        id: Some(IDS.id(&gid)),
+        details,
+      })
+    })
+  }
+
+  fn start(&self, id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move {
+      self.call::<Value>("aria2.unpause", vec![json!(IDS.key(id)?)]).await?;
+      Ok(())
+    })
+  }
+
+  fn pause(&self, id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move {
+      self.call::<Value>("aria2.forcePause", vec![json!(IDS.key(id)?)]).await?;
+      Ok(())
+    })
+  }
+
+  fn forget(&self, id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move { self.remove(&IDS.key(id)?).await })
+  }
+
+  fn update_only_files<'a>(&'a self, id: usize, files: &'a HashSet<usize>) -> EngineFuture<'a, ()> {
+    Box::pin(async move { self.select_files(&IDS.key(id)?, files.iter().copied()).await })
+  }
+
+  fn trackers(&self, id: usize) -> EngineFuture<'_, Vec<String>> {
+    Box::pin(async move {
+      let status = self.status_of(id).await?;
+      let mut trackers: Vec<String> = status
+        .bittorrent
+        .map(|bt| bt.announce_list.into_iter().flatten().collect())
+        .unwrap_or_default();
+      trackers.sort();
+      trackers.dedup();
+      Ok(trackers)
+    })
+  }
+
+  fn contiguous_bytes(&self, id: usize) -> EngineFuture<'_, Option<u64>> {
+    Box::pin(async move {
+      let status = self.status_of(id).await?;
+      Ok(
+        status
+          .bitfield
+          .as_deref()
+          .map(|bitfield| leading_pieces(bitfield) * number(&status.piece_length)),
+      )
+    })
+  }
+
+  // 对应 aria2 的全局限速（0 表示不限速）。
+  fn set_rate_limits(&self, download: Option<NonZeroU32>, upload: Option<NonZeroU32>) -> EngineFuture<'_, ()> {
+    Box::pin(async move {
+      let limit = |bps: Option<NonZeroU32>| bps.map_or(0, NonZeroU32::get).to_string();
+      let options = json!({
+        "max-overall-download-limit": limit(download),
+        "max-overall-upload-limit": limit(upload),
+      });
+      self.call::<Value>("aria2.changeGlobalOption", vec![options]).await?;
+      Ok(())
+    })
+  }
+
+  // aria2 由用户自己运行，任务也由它保存，这里不做任何事。
+  fn stop(&self) -> EngineFuture<'_, ()> {
+    Box::pin(async move { Ok(()) })
+  }
+}
@@ -0,0 +1,234 @@
+/*
+  下载引擎：
+  下载模块的命令与后台巡检都通过 DownloadEngine trait 操作任务，目前有两种实现：
+  - librqbit（默认）：在应用内创建会话（见 rqbit 子模块）；
+  - aria2：通过 JSON-RPC 连接已经运行的 aria2（见 aria2 子模块），
+    适合本来就在用 aria2 的用户，也可以用它下载普通的 HTTP/FTP 地址；
+  - qBittorrent：通过 Web API 连接外部的 qBittorrent（见 qbittorrent 子模块），
+    适合让 NAS 上的 qBittorrent 负责下载，文件保存在 NAS 上。
+  使用哪个引擎由会话设置（TorrentSessionSettings.engine）决定，在创建会话时选择，修改后重建会话。
+  命令的参数与返回内容不随引擎变化：任务 ID 都是 usize，状态名统一使用 librqbit 的
+  Initializing / Live / Paused / Error（外部引擎排队中的任务为 Waiting）。
+  peer 列表、边下边看与 IP 黑名单依赖 librqbit 的接口，使用外部引擎时不可用。
+*/
+
+use futures::future::BoxFuture;
+use librqbit::api::Api;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex};
+
+use super::TorrentSessionSettings;
+use crate::services::error::AppError;
+use crate::services::settings::AppSettings;
+
+mod aria2;
+mod qbittorrent;
+mod rqbit;
+
+/// 下载引擎类型。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum EngineKind {
+  /// 内置的 librqbit。
+  #[default]
+  Librqbit,
+  /// 通过 JSON-RPC 连接的 aria2。
+  Aria2,
+  /// 通过 Web API 连接的 qBittorrent。
+  Qbittorrent,
+}
+
+impl EngineKind {
+  // 文件是否保存在其它机器上（例如 NAS 上的 qBittorrent）：
+  // 这类引擎忽略传入的本地目录，本机也不做空间检查与文件移动。
+  pub(super) fn is_remote(self) -> bool {
+    self == EngineKind::Qbittorrent
+  }
+}
+
+// 引擎方法返回的 future（trait 以 dyn 方式使用，所以手动装箱）。
+pub(super) type EngineFuture<'a, T> = BoxFuture<'a, Result<T, AppError>>;
+
+// 当前使用的引擎。
+pub(super) type Engine = Arc<dyn DownloadEngine>;
+
+// 外部引擎的任务标识（aria2 的 GID、qBittorrent 的 info hash）与任务 ID 的对应关系。
+// 标识换算成数字可能超出前端数字的精度，所以按首次出现的顺序分配 ID；
+// 对应关系在本次运行内保持不变（重建会话后任务 ID 也不变）。
+#[derive(Default)]
+pub(super) struct IdTable {
+  keys: Mutex<Vec<String>>,
+}
+
+impl IdTable {
+  // 标识对应的任务 ID（首次出现时分配）。
+  pub fn id(&self, key: &str) -> usize {
+    let mut keys = self.keys.lock().unwrap_or_else(|e| e.into_inner());
+    match keys.iter().position(|existing| existing == key) {
+      Some(id) => id,
+      None => {
+        keys.push(key.to_string());
+        keys.len() - 1
+      }
+    }
+  }
+
+  // 任务 ID 对应的标识。
+  pub fn key(&self, id: usize) -> Result<String, AppError> {
+    let keys = self.keys.lock().unwrap_or_else(|e| e.into_inner());
+    keys.get(id).cloned().ok_or_else(|| AppError::torrent(format!("任务不存在: {id}")))
+  }
+}
+
+// 会话中的一个任务。
+pub(super) struct EngineTorrent {
+  pub id: usize,
+  pub info_hash: String,
+  pub name: Option<String>,
+  pub output_folder: String,
+}
+
+// 任务的实时状态。
+pub(super) struct EngineStats {
+  pub state: String,
+  pub progress_bytes: u64,
+  pub total_bytes: u64,
+  pub uploaded_bytes: u64,
+  pub finished: bool,
+  pub error: Option<String>,
+  pub download_speed: Option<String>,
+  pub upload_speed: Option<String>,
+  pub time_remaining: Option<String>,
+  // 每个文件已下载的字节数（与文件序号对应）。
+  pub file_progress: Vec<u64>,
+}
+
+// 外部引擎的剩余时间（与 librqbit 的写法一致，例如 1h 5m、3m 20s）。
+fn format_remaining(secs: u64) -> String {
+  let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+  if hours > 0 {
+    format!("{hours}h {minutes}m")
+  } else if minutes > 0 {
+    format!("{minutes}m {seconds}s")
+  } else {
+    format!("{seconds}s")
+  }
+}
+
+// 任务中的一个文件。
+pub(super) struct EngineFile {
+  // 相对于输出目录的路径。
+  pub name: String,
+  pub components: Vec<String>,
+  pub length: u64,
+  pub included: bool,
+}
+
+// 任务详情。
+pub(super) struct EngineDetails {
+  pub info_hash: String,
+  pub name: Option<String>,
+  pub output_folder: String,
+  // 元数据尚未解析时为 None。
+  pub files: Option<Vec<EngineFile>>,
+  // 非 BT 任务（aria2 的 HTTP/FTP 下载）的下载地址，重新加入任务时使用。
+  pub source: Option<String>,
+}
+
+// 加入任务的选项。
+#[derive(Default)]
+pub(super) struct AddOptions {
+  pub output_folder: Option<String>,
+  // 只下载这些文件（文件序号），为空表示全部。
+  pub only_files: Option<Vec<usize>>,
+  pub paused: bool,
+  pub trackers: Option<Vec<String>>,
+  // 只解析元数据，不加入任务（预览用）。
+  pub list_only: bool,
+}
+
+// 加入任务的结果。
+pub(super) struct AddedTorrent {
+  // list_only 时为 None。
+  pub id: Option<usize>,
+  pub details: EngineDetails,
+}
+
+// 下载引擎需要提供的操作（任务以 ID 定位）。
+// 出错时返回引擎的原始说明，由调用方补充是哪个操作失败。
+pub(super) trait DownloadEngine: Send + Sync {
+  // 引擎类型。
+  fn kind(&self) -> EngineKind;
+
+  // 会话中的全部任务。
+  fn list(&self) -> EngineFuture<'_, Vec<EngineTorrent>>;
+
+  fn stats(&self, id: usize) -> EngineFuture<'_, EngineStats>;
+
+  fn details(&self, id: usize) -> EngineFuture<'_, EngineDetails>;
+
+  // 加入任务：url 为磁力链接或种子地址（aria2 还支持其它 HTTP/FTP 地址），会等到元数据解析完成。
+  fn add(&self, url: String, opts: AddOptions) -> EngineFuture<'_, AddedTorrent>;
+
+  fn start(&self, id: usize) -> EngineFuture<'_, ()>;
+
+  fn pause(&self, id: usize) -> EngineFuture<'_, ()>;
+
+  // 移除任务（保留文件）。
+  fn forget(&self, id: usize) -> EngineFuture<'_, ()>;
+
+  // 移除任务并删除已下载的文件（文件不在本机的引擎需要实现，本机的文件由调用方删除）。
+  fn forget_with_files(&self, _id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move { Err(AppError::torrent("当前下载引擎不支持删除文件")) })
+  }
+
+  // 原地重新校验已有文件（任务 ID 不变）；引擎没有这个操作时为 None，由调用方重新加入任务来校验。
+  fn recheck(&self, _id: usize) -> Option<EngineFuture<'_, ()>> {
+    None
+  }
+
+  // 修改任务要下载的文件。
+  fn update_only_files<'a>(&'a self, id: usize, files: &'a HashSet<usize>) -> EngineFuture<'a, ()>;
+
+  // 任务当前使用的 tracker（已排序）。
+  fn trackers(&self, id: usize) -> EngineFuture<'_, Vec<String>>;
+
+  // 从开头起连续下载完成的字节数（引擎不提供时可以是估算值），无法获取时为 None。
+  fn contiguous_bytes(&self, id: usize) -> EngineFuture<'_, Option<u64>>;
+
+  // 设置全局限速（字节/秒），None 表示不限速。
+  fn set_rate_limits(&self, download: Option<NonZeroU32>, upload: Option<NonZeroU32>) -> EngineFuture<'_, ()>;
+
+  // 停止会话（重建会话前调用）。
+  fn stop(&self) -> EngineFuture<'_, ()>;
+
+  // librqbit 的 API（peer 列表、边下边看等专有功能使用），其它引擎为 None。
+  fn librqbit(&self) -> Option<&Api> {
+    None
+  }
+}
+
+// 按设置创建引擎：librqbit 创建新会话，外部引擎连接并确认可用。
+pub(super) async fn create_engine(app: &tauri::AppHandle, settings: &AppSettings) -> Result<Engine, AppError> {
+  Ok(match settings.torrent_session.engine {
+    EngineKind::Librqbit => Arc::new(rqbit::RqbitEngine::create(app, settings).await?),
+    EngineKind::Aria2 => Arc::new(aria2::Aria2Engine::connect(&settings.torrent_session).await?),
+    EngineKind::Qbittorrent => Arc::new(qbittorrent::QbittorrentEngine::connect(&settings.torrent_session).await?),
+  })
+}
+
+// 检查设置里的引擎是否可用（切换到外部引擎前确认能连上）。
+pub(super) async fn check_engine(settings: &TorrentSessionSettings) -> Result<(), AppError> {
+  match settings.engine {
+    EngineKind::Librqbit => {}
+    EngineKind::Aria2 => {
+      aria2::Aria2Engine::connect(settings).await?;
+    }
+    EngineKind::Qbittorrent => {
+      qbittorrent::QbittorrentEngine::connect(settings).await?;
+    }
+  }
+  Ok(())
+}
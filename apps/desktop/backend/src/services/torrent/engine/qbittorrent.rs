@@ -0,0 +1,614 @@
+/*
+  qBittorrent 引擎：通过 Web API（v2）操作外部的 qBittorrent（例如 NAS 上运行的实例），
+  文件下载到 qBittorrent 所在的机器上。
+  - 设置了用户名时先登录，会话 cookie 失效（返回 403）后自动重新登录一次；
+    qBittorrent 开启了“对本地主机上的客户端跳过身份验证”等选项时可以不填；
+  - qBittorrent 以 info hash 标识任务，任务 ID 由 IdTable 按首次出现的顺序分配；
+  - 文件保存在 qBittorrent 所在的机器上：保存目录取自设置（为空时用 qBittorrent 的默认目录），
+    忽略应用传入的本地目录；删除文件也由 qBittorrent 完成；
+  - 加入任务的接口不返回 info hash，所以加入时带上一个临时标签，再按标签找到新任务；
+    加入时要求拿到元数据后停止（stopCondition），设置好文件选择后再按需开始；
+  - qBittorrent 5 把 pause/resume 改名为 stop/start，旧接口不存在（404）时回退到旧名字；
+  - 重新校验直接使用 qBittorrent 的 recheck，任务 ID 不变；
+  - qBittorrent 自己保存任务，应用重建会话时不需要重新加入；会话参数与 IP 黑名单
+    以 qBittorrent 自己的配置为准；只支持 BT 任务。
+*/
+
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use reqwest::header::{COOKIE, SET_COOKIE};
+use reqwest::{Client, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::{
+  format_remaining, AddOptions, AddedTorrent, DownloadEngine, EngineDetails, EngineFile, EngineFuture, EngineKind,
+  EngineStats, EngineTorrent, IdTable,
+};
+use crate::services::bangumi::deobfuscate_credential_value;
+use crate::services::error::AppError;
+use crate::services::media::format_bytes_readable;
+use crate::services::torrent::TorrentSessionSettings;
+
+// 请求的连接超时与总超时。
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// 等待新任务出现并拿到元数据的间隔与上限。
+const METADATA_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const METADATA_TIMEOUT: Duration = Duration::from_secs(600);
+// 请求重新校验后等待任务进入校验状态的间隔与上限。
+const RECHECK_START_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const RECHECK_START_TIMEOUT: Duration = Duration::from_secs(5);
+// qBittorrent 用这个值表示剩余时间未知。
+const ETA_UNKNOWN: i64 = 8_640_000;
+// 加入任务时使用的临时标签前缀。
+const TAG_PREFIX: &str = "hanamirip-";
+
+// info hash 与任务 ID 的对应关系（重建会话后保持不变）。
+static IDS: Lazy<IdTable> = Lazy::new(IdTable::default);
+// 临时标签的序号（同一毫秒内加入多个任务时区分）。
+static NEXT_TAG: AtomicU64 = AtomicU64::new(0);
+
+// 连接到的 qBittorrent。
+pub(super) struct QbittorrentEngine {
+  client: Client,
+  // Web UI 地址（不带末尾的 /）。
+  url: String,
+  username: Option<String>,
+  password: Option<String>,
+  // qBittorrent 上的保存目录，None 表示使用 qBittorrent 的默认目录。
+  save_path: Option<String>,
+  // 登录后拿到的会话 cookie（name=value）。
+  cookie: Mutex<Option<String>>,
+}
+
+// torrents/info 返回的任务信息。
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct QbTorrent {
+  hash: String,
+  name: String,
+  state: String,
+  // 选中文件的总大小。
+  size: i64,
+  completed: i64,
+  uploaded: i64,
+  progress: f64,
+  dlspeed: i64,
+  upspeed: i64,
+  eta: i64,
+  save_path: String,
+}
+
+// torrents/files 返回的文件信息。
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct QbFile {
+  // 相对于保存目录的路径（以 / 分隔）。
+  name: String,
+  size: i64,
+  progress: f64,
+  // 0 表示不下载。
+  priority: i64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct QbTracker {
+  url: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct QbProperties {
+  piece_size: i64,
+}
+
+// qBittorrent 的数字可能为 -1（未知），按 0 处理。
+fn bytes(value: i64) -> u64 {
+  value.max(0) as u64
+}
+
+// 文件序号列表（qBittorrent 以 | 分隔）。
+fn join_indexes(indexes: &[usize]) -> String {
+  indexes.iter().map(usize::to_string).collect::<Vec<_>>().join("|")
+}
+
+impl QbTorrent {
+  // 状态名统一成 librqbit 的写法；排队中的任务为 Waiting。
+  fn state(&self) -> String {
+    let state = match self.state.as_str() {
+      "error" | "missingFiles" => "Error",
+      "pausedDL" | "pausedUP" | "stoppedDL" | "stoppedUP" => "Paused",
+      "queuedDL" | "queuedUP" => "Waiting",
+      "checkingDL" | "checkingUP" | "checkingResumeData" | "queuedForChecking" | "allocating" | "metaDL"
+      | "forcedMetaDL" | "moving" => "Initializing",
+      _ => "Live",
+    };
+    state.to_string()
+  }
+
+  // 还在获取元数据（没有文件列表）。
+  fn is_metadata(&self) -> bool {
+    matches!(self.state.as_str(), "metaDL" | "forcedMetaDL")
+  }
+
+  // 正在（或排队等待）校验已有文件。
+  fn is_checking(&self) -> bool {
+    matches!(
+      self.state.as_str(),
+      "checkingDL" | "checkingUP" | "checkingResumeData" | "queuedForChecking"
+    )
+  }
+
+  fn stats(&self, files: &[QbFile]) -> EngineStats {
+    let state = self.state();
+    let finished = !self.is_metadata() && self.progress >= 1.0;
+    let (download_speed, upload_speed, time_remaining) = if state == "Live" {
+      (
+        Some(format!("{}/s", format_bytes_readable(bytes(self.dlspeed)))),
+        Some(format!("{}/s", format_bytes_readable(bytes(self.upspeed)))),
+        (!finished && (0..ETA_UNKNOWN).contains(&self.eta)).then(|| format_remaining(self.eta as u64)),
+      )
+    } else {
+      (None, None, None)
+    };
+    let error = match self.state.as_str() {
+      "error" => Some("qBittorrent 报告任务出错".to_string()),
+      "missingFiles" => Some("qBittorrent 找不到任务的文件".to_string()),
+      _ => None,
+    };
+    EngineStats {
+      state,
+      progress_bytes: bytes(self.completed),
+      total_bytes: bytes(self.size),
+      uploaded_bytes: bytes(self.uploaded),
+      finished,
+      error,
+      download_speed,
+      upload_speed,
+      time_remaining,
+      file_progress: files
+        .iter()
+        .map(|file| (bytes(file.size) as f64 * file.progress) as u64)
+        .collect(),
+    }
+  }
+
+  fn details(&self, files: &[QbFile]) -> EngineDetails {
+    let files = (!self.is_metadata() && !files.is_empty()).then(|| {
+      files
+        .iter()
+        .map(|file| EngineFile {
+          name: file.name.clone(),
+          components: file.name.split('/').map(str::to_string).collect(),
+          length: bytes(file.size),
+          included: file.priority > 0,
+        })
+        .collect()
+    });
+    EngineDetails {
+      info_hash: self.hash.clone(),
+      name: (!self.name.is_empty()).then(|| self.name.clone()),
+      output_folder: self.save_path.clone(),
+      files,
+      source: None,
+    }
+  }
+}
+
+impl QbittorrentEngine {
+  // 连接 qBittorrent（设置了用户名时先登录）并确认 Web API 可用。
+  pub(super) async fn connect(settings: &TorrentSessionSettings) -> Result<Self, AppError> {
+    // Web UI 一般在本机或局域网，不走应用的代理。
+    let client = Client::builder()
+      .no_proxy()
+      .connect_timeout(CONNECT_TIMEOUT)
+      .timeout(REQUEST_TIMEOUT)
+      .build()
+      .map_err(|e| AppError::torrent(format!("创建 qBittorrent 客户端失败: {e}")))?;
+    let password = settings
+      .qbittorrent_password
+      .as_deref()
+      .map(deobfuscate_credential_value)
+      .transpose()
+      .map_err(|e| AppError::invalid_input(format!("qBittorrent 密码无效: {e}")))?;
+    let engine = Self {
+      client,
+      url: settings.qbittorrent_url.clone(),
+      username: settings.qbittorrent_username.clone(),
+      password,
+      save_path: settings.qbittorrent_save_path.clone(),
+      cookie: Mutex::new(None),
+    };
+    let connected = async {
+      if engine.username.is_some() {
+        engine.login().await?;
+      }
+      engine.request("app/version", &[], false).await
+    };
+    connected
+      .await
+      .map_err(|e| AppError::torrent(format!("连接 qBittorrent 失败（{}）: {e}", engine.url)))?;
+    Ok(engine)
+  }
+
+  // 登录并保存会话 cookie。
+  async fn login(&self) -> Result<(), AppError> {
+    let form = [
+      ("username", self.username.clone().unwrap_or_default()),
+      ("password", self.password.clone().unwrap_or_default()),
+    ];
+    let response = self
+      .client
+      .post(format!("{}/api/v2/auth/login", self.url))
+      .form(&form)
+      .send()
+      .await
+      .map_err(|e| AppError::network(format!("qBittorrent 请求失败: {e}")))?;
+    if response.status() == StatusCode::FORBIDDEN {
+      return Err(AppError::torrent("登录失败次数过多，qBittorrent 暂时拒绝了本机的访问"));
+    }
+    let cookie = response
+      .headers()
+      .get_all(SET_COOKIE)
+      .iter()
+      .filter_map(|value| value.to_str().ok())
+      .filter_map(|value| value.split(';').next())
+      .map(|pair| pair.trim().to_string())
+      .next();
+    let text = response.text().await.unwrap_or_default();
+    match cookie {
+      Some(cookie) if text.trim() != "Fails." => {
+        *self.cookie.lock().unwrap_or_else(|e| e.into_inner()) = Some(cookie);
+        Ok(())
+      }
+      _ => Err(AppError::torrent("qBittorrent 用户名或密码错误")),
+    }
+  }
+
+  // 发送请求（带上会话 cookie），返回 403 时重新登录一次后重试。
+  async fn request(&self, path: &str, params: &[(&str, &str)], post: bool) -> Result<Response, AppError> {
+    let url = format!("{}/api/v2/{path}", self.url);
+    let mut relogged = false;
+    loop {
+      let mut builder = if post {
+        self.client.post(&url).form(params)
+      } else {
+        self.client.get(&url).query(params)
+      };
+      let cookie = self.cookie.lock().unwrap_or_else(|e| e.into_inner()).clone();
+      if let Some(cookie) = cookie {
+        builder = builder.header(COOKIE, cookie);
+      }
+      let response = builder
+        .send()
+        .await
+        .map_err(|e| AppError::network(format!("qBittorrent 请求失败: {e}")))?;
+      let status = response.status();
+      if status == StatusCode::FORBIDDEN {
+        if self.username.is_some() && !relogged {
+          self.login().await?;
+          relogged = true;
+          continue;
+        }
+        return Err(AppError::api(status.as_u16(), "qBittorrent 拒绝访问，请检查用户名与密码"));
+      }
+      if !status.is_success() {
+        return Err(AppError::api(status.as_u16(), format!("qBittorrent 请求失败（{path}）: {status}")));
+      }
+      return Ok(response);
+    }
+  }
+
+  // 读取接口（GET），返回解析后的 JSON。
+  async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T, AppError> {
+    self
+      .request(path, query, false)
+      .await?
+      .json()
+      .await
+      .map_err(|e| AppError::torrent(format!("qBittorrent 返回的内容无法解析: {e}")))
+  }
+
+  // 操作接口（POST），返回响应文本。
+  async fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String, AppError> {
+    self
+      .request(path, form, true)
+      .await?
+      .text()
+      .await
+      .map_err(|e| AppError::network(format!("qBittorrent 请求失败: {e}")))
+  }
+
+  // 调用改过名的接口：新名字不存在（qBittorrent 5 以前）时使用旧名字。
+  async fn post_renamed(&self, path: &str, old_path: &str, form: &[(&str, &str)]) -> Result<String, AppError> {
+    match self.post(path, form).await {
+      Err(AppError::ApiError { status: 404, .. }) => self.post(old_path, form).await,
+      result => result,
+    }
+  }
+
+  async fn torrent(&self, hash: &str) -> Result<QbTorrent, AppError> {
+    self
+      .get::<Vec<QbTorrent>>("torrents/info", &[("hashes", hash)])
+      .await?
+      .into_iter()
+      .next()
+      .ok_or_else(|| AppError::torrent(format!("任务不存在: {hash}")))
+  }
+
+  async fn files(&self, hash: &str) -> Result<Vec<QbFile>, AppError> {
+    self.get("torrents/files", &[("hash", hash)]).await
+  }
+
+  // 任务与文件列表。
+  async fn torrent_with_files(&self, id: usize) -> Result<(QbTorrent, Vec<QbFile>), AppError> {
+    let hash = IDS.key(id)?;
+    let torrent = self.torrent(&hash).await?;
+    let files = self.files(&hash).await?;
+    Ok((torrent, files))
+  }
+
+  // 等待带有该标签的任务出现并拿到元数据，返回任务的 info hash。
+  async fn wait_for_tagged(&self, tag: &str) -> Result<String, AppError> {
+    let started = Instant::now();
+    loop {
+      let torrents: Vec<QbTorrent> = self.get("torrents/info", &[("tag", tag)]).await?;
+      if let Some(torrent) = torrents.into_iter().next() {
+        if !torrent.is_metadata() && !self.files(&torrent.hash).await?.is_empty() {
+          return Ok(torrent.hash);
+        }
+        if started.elapsed() >= METADATA_TIMEOUT {
+          let _ = self.delete(&torrent.hash, false).await;
+          return Err(AppError::torrent("获取元数据超时"));
+        }
+      } else if started.elapsed() >= METADATA_TIMEOUT {
+        return Err(AppError::torrent("qBittorrent 没有加入任务（地址无效或下载种子失败）"));
+      }
+      tokio::time::sleep(METADATA_POLL_INTERVAL).await;
+    }
+  }
+
+  // 移除任务，delete_files 为 true 时同时删除已下载的文件。
+  async fn delete(&self, hash: &str, delete_files: bool) -> Result<(), AppError> {
+    let delete_files = delete_files.to_string();
+    self
+      .post("torrents/delete", &[("hashes", hash), ("deleteFiles", delete_files.as_str())])
+      .await?;
+    Ok(())
+  }
+
+  // 设置文件优先级：选中的文件为普通，其它不下载。
+  async fn select_files(&self, hash: &str, selected: &HashSet<usize>) -> Result<(), AppError> {
+    let count = self.files(hash).await?.len();
+    let (included, excluded): (Vec<usize>, Vec<usize>) = (0..count).partition(|index| selected.contains(index));
+    for (indexes, priority) in [(included, "1"), (excluded, "0")] {
+      if !indexes.is_empty() {
+        let ids = join_indexes(&indexes);
+        self
+          .post("torrents/filePrio", &[("hash", hash), ("id", ids.as_str()), ("priority", priority)])
+          .await?;
+      }
+    }
+    Ok(())
+  }
+
+  async fn stop_torrent(&self, hash: &str) -> Result<(), AppError> {
+    self.post_renamed("torrents/stop", "torrents/pause", &[("hashes", hash)]).await?;
+    Ok(())
+  }
+
+  async fn start_torrent(&self, hash: &str) -> Result<(), AppError> {
+    self.post_renamed("torrents/start", "torrents/resume", &[("hashes", hash)]).await?;
+    Ok(())
+  }
+
+  // 重新校验已有文件。qBittorrent 收到请求后稍后才切换到校验状态，
+  // 这里等到任务进入校验状态再返回，避免调用方马上查询时拿到校验前的状态；
+  // 文件很小时校验可能在两次查询之间就结束了，所以等不到也不算失败。
+  async fn recheck_torrent(&self, hash: &str) -> Result<(), AppError> {
+    self.post("torrents/recheck", &[("hashes", hash)]).await?;
+    let deadline = Instant::now() + RECHECK_START_TIMEOUT;
+    while Instant::now() < deadline {
+      if self.torrent(hash).await?.is_checking() {
+        break;
+      }
+      tokio::time::sleep(RECHECK_START_POLL_INTERVAL).await;
+    }
+    Ok(())
+  }
+
+  // 加入任务并等到拿到元数据，返回 info hash。
+  async fn add_url(&self, url: &str) -> Result<String, AppError> {
+    let tag = format!(
+      "{TAG_PREFIX}{}-{}",
+      Utc::now().timestamp_millis(),
+      NEXT_TAG.fetch_add(1, Ordering::Relaxed)
+    );
+    // 旧版本用 paused，qBittorrent 5 用 stopped；不认识的参数会被忽略。
+    let mut form = vec![
+      ("urls", url),
+      ("tags", tag.as_str()),
+      ("paused", "false"),
+      ("stopped", "false"),
+      ("stopCondition", "MetadataReceived"),
+    ];
+    if let Some(folder) = &self.save_path {
+      form.push(("savepath", folder.as_str()));
+    }
+    let text = self.post("torrents/add", &form).await?;
+    if text.trim() == "Fails." {
+      return Err(AppError::torrent("qBittorrent 拒绝加入任务（任务可能已经存在）"));
+    }
+    let result = self.wait_for_tagged(&tag).await;
+    // 临时标签用完即删（同时从任务上移除）。
+    let _ = self.post("torrents/deleteTags", &[("tags", tag.as_str())]).await;
+    let hash = result?;
+    // 不支持 stopCondition 的旧版本此时已经开始下载，统一先停止。
+    self.stop_torrent(&hash).await?;
+    Ok(hash)
+  }
+}
+
+impl DownloadEngine for QbittorrentEngine {
+  fn kind(&self) -> EngineKind {
+    EngineKind::Qbittorrent
+  }
+
+  // 已拿到元数据的任务（获取元数据中的任务没有文件列表，不列出）。
+  fn list(&self) -> EngineFuture<'_, Vec<EngineTorrent>> {
+    Box::pin(async move {
+      let torrents: Vec<QbTorrent> = self.get("torrents/info", &[]).await?;
+      Ok(
+        torrents
+          .into_iter()
+          .filter(|torrent| !torrent.is_metadata())
+          .map(|torrent| EngineTorrent {
+            id: IDS.id(&torrent.hash),
+            name: (!torrent.name.is_empty()).then(|| torrent.name.clone()),
+            info_hash: torrent.hash,
+            output_folder: torrent.save_path,
+          })
+          .collect(),
+      )
+    })
+  }
+
+  fn stats(&self, id: usize) -> EngineFuture<'_, EngineStats> {
+    Box::pin(async move {
+      let (torrent, files) = self.torrent_with_files(id).await?;
+      Ok(torrent.stats(&files))
+    })
+  }
+
+  fn details(&self, id: usize) -> EngineFuture<'_, EngineDetails> {
+    Box::pin(async move {
+      let (torrent, files) = self.torrent_with_files(id).await?;
+      Ok(torrent.details(&files))
+    })
+  }
+
+  fn add(&self, url: String, opts: AddOptions) -> EngineFuture<'_, AddedTorrent> {
+    // 本地目录对 qBittorrent 没有意义，保存目录取自设置。
+    let AddOptions {
+      output_folder: _,
+      only_files,
+      paused,
+      trackers,
+      list_only,
+    } = opts;
+    Box::pin(async move {
+      let hash = self.add_url(&url).await?;
+      let prepared = async {
+        if let Some(trackers) = trackers.filter(|trackers| !trackers.is_empty()) {
+          let urls = trackers.join("\n");
+          self
+            .post("torrents/addTrackers", &[("hash", hash.as_str()), ("urls", urls.as_str())])
+            .await?;
+        }
+        if let Some(files) = &only_files {
+          self.select_files(&hash, &files.iter().copied().collect()).await?;
+        }
+        let torrent = self.torrent(&hash).await?;
+        let files = self.files(&hash).await?;
+        Ok::<_, AppError>(torrent.details(&files))
+      };
+      let details = match prepared.await {
+        Ok(details) => details,
+        Err(err) => {
+          let _ = self.delete(&hash, false).await;
+          return Err(err);
+        }
+      };
+      if list_only {
+        self.delete(&hash, false).await?;
+        return Ok(AddedTorrent { id: None, details });
+      }
+      if !paused {
+        self.start_torrent(&hash).await?;
+      }
+      Ok(AddedTorrent {
+        id: Some(IDS.id(&hash)),
+        details,
+      })
+    })
+  }
+
+  fn start(&self, id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move { self.start_torrent(&IDS.key(id)?).await })
+  }
+
+  fn pause(&self, id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move { self.stop_torrent(&IDS.key(id)?).await })
+  }
+
+  fn forget(&self, id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move { self.delete(&IDS.key(id)?, false).await })
+  }
+
+  fn forget_with_files(&self, id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move { self.delete(&IDS.key(id)?, true).await })
+  }
+
+  fn recheck(&self, id: usize) -> Option<EngineFuture<'_, ()>> {
+    Some(Box::pin(async move { self.recheck_torrent(&IDS.key(id)?).await }))
+  }
+
+  fn update_only_files<'a>(&'a self, id: usize, files: &'a HashSet<usize>) -> EngineFuture<'a, ()> {
+    Box::pin(async move { self.select_files(&IDS.key(id)?, files).await })
+  }
+
+  // 不含 qBittorrent 列出的 DHT / PeX / LSD 伪条目（以 ** 开头）。
+  fn trackers(&self, id: usize) -> EngineFuture<'_, Vec<String>> {
+    Box::pin(async move {
+      let hash = IDS.key(id)?;
+      let trackers: Vec<QbTracker> = self.get("torrents/trackers", &[("hash", hash.as_str())]).await?;
+      let mut trackers: Vec<String> = trackers
+        .into_iter()
+        .map(|tracker| tracker.url)
+        .filter(|url| !url.starts_with("**"))
+        .collect();
+      trackers.sort();
+      trackers.dedup();
+      Ok(trackers)
+    })
+  }
+
+  // 分块状态为 2 表示已下载完成。
+  fn contiguous_bytes(&self, id: usize) -> EngineFuture<'_, Option<u64>> {
+    Box::pin(async move {
+      let hash = IDS.key(id)?;
+      let states: Vec<u8> = self.get("torrents/pieceStates", &[("hash", hash.as_str())]).await?;
+      let properties: QbProperties = self.get("torrents/properties", &[("hash", hash.as_str())]).await?;
+      if properties.piece_size <= 0 {
+        return Ok(None);
+      }
+      let pieces = states.iter().take_while(|state| **state == 2).count() as u64;
+      Ok(Some(pieces * bytes(properties.piece_size)))
+    })
+  }
+
+  // 对应 qBittorrent 的全局限速（0 表示不限速）。
+  fn set_rate_limits(&self, download: Option<NonZeroU32>, upload: Option<NonZeroU32>) -> EngineFuture<'_, ()> {
+    Box::pin(async move {
+      let limit = |bps: Option<NonZeroU32>| bps.map_or(0, NonZeroU32::get).to_string();
+      let (download, upload) = (limit(download), limit(upload));
+      self
+        .post("transfer/setDownloadLimit", &[("limit", download.as_str())])
+        .await?;
+      self.post("transfer/setUploadLimit", &[("limit", upload.as_str())]).await?;
+      Ok(())
+    })
+  }
+
+  // qBittorrent 由用户自己运行，任务也由它保存，这里不做任何事。
+  fn stop(&self) -> EngineFuture<'_, ()> {
+    Box::pin(async move { Ok(()) })
+  }
+}
@@ -0,0 +1,279 @@
+/*
+  librqbit 引擎：在应用内创建 librqbit 会话。
+  会话参数、默认 tracker 与 IP 黑名单只能在创建会话时指定，修改后需要重建会话。
+*/
+
+use librqbit::api::{Api, TorrentDetailsResponse, TorrentIdOrHash};
+use librqbit::{AddTorrent, AddTorrentOptions, Session, SessionOptions};
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fs;
+use std::num::NonZeroU32;
+use tauri::Manager;
+
+use super::{
+  AddOptions, AddedTorrent, DownloadEngine, EngineDetails, EngineFile, EngineFuture, EngineKind, EngineStats,
+  EngineTorrent,
+};
+use crate::services::error::AppError;
+use crate::services::settings::AppSettings;
+use crate::services::torrent::{blocklist, trackers, TORRENT_OUTPUT_DIR};
+
+// librqbit 会话。
+pub(super) struct RqbitEngine {
+  api: Api,
+}
+
+// 引擎报错转换为 AppError。
+fn engine_error(err: impl Display) -> AppError {
+  AppError::torrent(err.to_string())
+}
+
+// 种子详情转换为通用结构。
+fn engine_details(details: TorrentDetailsResponse) -> EngineDetails {
+  EngineDetails {
+    info_hash: details.info_hash,
+    name: details.name,
+    output_folder: details.output_folder,
+    files: details.files.map(|files| {
+      files
+        .into_iter()
+        .map(|file| EngineFile {
+          name: file.name,
+          components: file.components,
+          length: file.length,
+          included: file.included,
+        })
+        .collect()
+    }),
+    source: None,
+  }
+}
+
+impl RqbitEngine {
+  // 按当前设置创建下载会话。
+  pub(super) async fn create(app: &tauri::AppHandle, settings: &AppSettings) -> Result<Self, AppError> {
+    // 下载文件保存目录（应用数据目录 / hanamirip-cn / downloads）。
+    let base_dir = app
+      .path()
+      .app_data_dir()
+      .map_err(|e| AppError::io(format!("无法获取下载目录: {e}")))?
+      .join("hanamirip-cn")
+      .join(TORRENT_OUTPUT_DIR);
+    fs::create_dir_all(&base_dir).map_err(|e| AppError::io(format!("创建下载目录失败: {e}")))?;
+
+    // 配置下载会话：端口、UPnP、DHT、超时等取自会话设置。
+    let mut opts = SessionOptions::default();
+    settings.torrent_session.apply(&mut opts);
+    opts.socks_proxy_url = settings
+      .torrent_session
+      .resolve_proxy(settings)
+      .map_err(AppError::invalid_input)?;
+    // 引擎内部的初始化并发数同样取自设置。
+    opts.concurrent_init_limit = Some(settings.concurrency.max_torrent_inits);
+    // 会话默认 tracker 取自设置（用于提高种子可连接性）。
+    opts.trackers = trackers::configured_tracker_set(app);
+    // 设置了黑名单时由引擎加载解析后的黑名单文件。
+    opts.blocklist_url = blocklist::session_blocklist_url(app);
+
+    let session = Session::new_with_opts(base_dir, opts)
+      .await
+      .map_err(|e| AppError::torrent(format!("初始化下载会话失败: {e}")))?;
+    Ok(Self {
+      api: Api::new(session, None),
+    })
+  }
+}
+
+impl DownloadEngine for RqbitEngine {
+  fn kind(&self) -> EngineKind {
+    EngineKind::Librqbit
+  }
+
+  fn list(&self) -> EngineFuture<'_, Vec<EngineTorrent>> {
+    let torrents = self
+      .api
+      .api_torrent_list()
+      .torrents
+      .into_iter()
+      .filter_map(|torrent| {
+        Some(EngineTorrent {
+          id: torrent.id?,
+          info_hash: torrent.info_hash,
+          name: torrent.name,
+          output_folder: torrent.output_folder,
+        })
+      })
+      .collect();
+    Box::pin(async move { Ok(torrents) })
+  }
+
+  fn stats(&self, id: usize) -> EngineFuture<'_, EngineStats> {
+    let result = self
+      .api
+      .api_stats_v1(TorrentIdOrHash::Id(id))
+      .map_err(engine_error)
+      .map(|stats| {
+        // 可能没有实时数据，所以这里用 Option 包裹。
+        let (download_speed, upload_speed, time_remaining) = stats
+          .live
+          .as_ref()
+          .map(|live| {
+            (
+              Some(format!("{}", live.download_speed)),
+              Some(format!("{}", live.upload_speed)),
+              live.time_remaining.as_ref().map(|v| v.to_string()),
+            )
+          })
+          .unwrap_or((None, None, None));
+        EngineStats {
+          state: format!("{:?}", stats.state),
+          progress_bytes: stats.progress_bytes,
+          total_bytes: stats.total_bytes,
+          uploaded_bytes: stats.uploaded_bytes,
+          finished: stats.finished,
+          error: stats.error,
+          download_speed,
+          upload_speed,
+          time_remaining,
+          file_progress: stats.file_progress,
+        }
+      });
+    Box::pin(async move { result })
+  }
+
+  fn details(&self, id: usize) -> EngineFuture<'_, EngineDetails> {
+    let result = self
+      .api
+      .api_torrent_details(TorrentIdOrHash::Id(id))
+      .map(engine_details)
+      .map_err(engine_error);
+    Box::pin(async move { result })
+  }
+
+  fn add(&self, url: String, opts: AddOptions) -> EngineFuture<'_, AddedTorrent> {
+    Box::pin(async move {
+      let options = AddTorrentOptions {
+        list_only: opts.list_only,
+        output_folder: opts.output_folder,
+        overwrite: true,
+        only_files: opts.only_files,
+        paused: opts.paused,
+        trackers: opts.trackers,
+        ..Default::default()
+      };
+      let response = self
+        .api
+        .api_add_torrent(AddTorrent::from_url(url), Some(options))
+        .await
+        .map_err(engine_error)?;
+      // 任务 ID 可能在不同字段里，做一次兜底处理。
+      Ok(AddedTorrent {
+        id: response.id.or(response.details.id),
+        details: engine_details(response.details),
+      })
+    })
+  }
+
+  fn start(&self, id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move {
+      self
+        .api
+        .api_torrent_action_start(TorrentIdOrHash::Id(id))
+        .await
+        .map_err(engine_error)?;
+      Ok(())
+    })
+  }
+
+  fn pause(&self, id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move {
+      self
+        .api
+        .api_torrent_action_pause(TorrentIdOrHash::Id(id))
+        .await
+        .map_err(engine_error)?;
+      Ok(())
+    })
+  }
+
+  fn forget(&self, id: usize) -> EngineFuture<'_, ()> {
+    Box::pin(async move {
+      self
+        .api
+        .api_torrent_action_forget(TorrentIdOrHash::Id(id))
+        .await
+        .map_err(engine_error)?;
+      Ok(())
+    })
+  }
+
+  fn update_only_files<'a>(&'a self, id: usize, files: &'a HashSet<usize>) -> EngineFuture<'a, ()> {
+    Box::pin(async move {
+      self
+        .api
+        .api_torrent_action_update_only_files(TorrentIdOrHash::Id(id), files)
+        .await
+        .map_err(engine_error)?;
+      Ok(())
+    })
+  }
+
+  // 种子/磁力链接自带的 tracker，加上会话默认的。
+  fn trackers(&self, id: usize) -> EngineFuture<'_, Vec<String>> {
+    let result = self
+      .api
+      .mgr_handle(TorrentIdOrHash::Id(id))
+      .map_err(engine_error)
+      .map(|handle| {
+        let mut trackers: Vec<String> = handle.shared().trackers.iter().map(|url| url.to_string()).collect();
+        trackers.sort();
+        trackers
+      });
+    Box::pin(async move { result })
+  }
+
+  // 引擎没有公开分块位图，按文件进度估算：前面已完成的文件，加上第一个未完成文件已下载的部分。
+  // 引擎在文件内先取首尾两块、其余按顺序请求，所以未完成文件的已下载字节扣掉一个分块后基本是连续的。
+  fn contiguous_bytes(&self, id: usize) -> EngineFuture<'_, Option<u64>> {
+    let contiguous = (|| {
+      let handle = self.api.mgr_handle(TorrentIdOrHash::Id(id)).ok()?;
+      let (piece_length, lengths) = handle
+        .with_metadata(|metadata| {
+          let lengths: Vec<u64> = metadata.file_infos.iter().map(|file| file.len).collect();
+          (metadata.lengths.default_piece_length() as u64, lengths)
+        })
+        .ok()?;
+      let progress = handle.stats().file_progress;
+      let mut contiguous = 0;
+      for (length, have) in lengths.into_iter().zip(progress) {
+        if have >= length {
+          contiguous += length;
+          continue;
+        }
+        contiguous += have.saturating_sub(piece_length);
+        break;
+      }
+      Some(contiguous)
+    })();
+    Box::pin(async move { Ok(contiguous) })
+  }
+
+  fn set_rate_limits(&self, download: Option<NonZeroU32>, upload: Option<NonZeroU32>) -> EngineFuture<'_, ()> {
+    let ratelimits = &self.api.session().ratelimits;
+    ratelimits.set_download_bps(download);
+    ratelimits.set_upload_bps(upload);
+    Box::pin(async move { Ok(()) })
+  }
+
+  fn stop(&self) -> EngineFuture<'_, ()> {
+    Box::pin(async move {
+      self.api.session().stop().await;
+      Ok(())
+    })
+  }
+
+  fn librqbit(&self) -> Option<&Api> {
+    Some(&self.api)
+  }
+}
@@ -0,0 +1,186 @@
+/*
+  下载完成后的收尾：把临时目录里的文件移动到最终目录。
+  - 同一磁盘（分区）上直接重命名，瞬间完成；
+  - 临时目录与最终目录不在同一磁盘时重命名会失败（EXDEV），
+    这时退回为流式复制 + 校验大小 + 删除源文件，
+    并通过 torrent://finalize-progress 事件报告进度，避免移动几十 GB 时界面像卡死一样。
+*/
+
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::services::diagnostics::app_log;
+use crate::services::error::AppError;
+
+// 复制进度事件名。
+const PROGRESS_EVENT: &str = "torrent://finalize-progress";
+// 复制缓冲区大小。
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+// 两次进度事件之间的最短间隔。
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// torrent://finalize-progress 事件内容（只在跨磁盘复制时发送）。
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalizeProgressEvent {
+  pub temp_folder: String,
+  pub final_folder: String,
+  /// 正在复制的文件（相对临时目录）。
+  pub current_file: String,
+  pub copied_bytes: u64,
+  pub total_bytes: u64,
+}
+
+// 跨磁盘复制的进度状态。
+struct CopyProgress<'a> {
+  app: &'a tauri::AppHandle,
+  temp_folder: &'a Path,
+  final_folder: &'a Path,
+  copied: u64,
+  total: u64,
+  last_emit: Option<Instant>,
+}
+
+impl CopyProgress<'_> {
+  // 发送进度事件；未到间隔时跳过（force 为 true 时总是发送）。
+  fn report(&mut self, current: &Path, force: bool) {
+    if !force && self.last_emit.is_some_and(|time| time.elapsed() < PROGRESS_EMIT_INTERVAL) {
+      return;
+    }
+    self.last_emit = Some(Instant::now());
+    let event = FinalizeProgressEvent {
+      temp_folder: self.temp_folder.to_string_lossy().to_string(),
+      final_folder: self.final_folder.to_string_lossy().to_string(),
+      current_file: current
+        .strip_prefix(self.temp_folder)
+        .unwrap_or(current)
+        .to_string_lossy()
+        .to_string(),
+      copied_bytes: self.copied,
+      total_bytes: self.total,
+    };
+    if let Err(err) = self.app.emit(PROGRESS_EVENT, &event) {
+      app_log!("发送移动进度事件失败: {err}");
+    }
+  }
+}
+
+// 判断重命名失败是否因为源和目标不在同一磁盘。
+fn is_cross_device(err: &io::Error) -> bool {
+  // Windows: ERROR_NOT_SAME_DEVICE；其它平台: EXDEV。
+  #[cfg(windows)]
+  const CROSS_DEVICE: i32 = 17;
+  #[cfg(not(windows))]
+  const CROSS_DEVICE: i32 = 18;
+  err.raw_os_error() == Some(CROSS_DEVICE)
+}
+
+// 统计文件或目录的总大小。
+fn total_size(path: &Path) -> io::Result<u64> {
+  let meta = fs::metadata(path)?;
+  if !meta.is_dir() {
+    return Ok(meta.len());
+  }
+  let mut total = 0;
+  for entry in fs::read_dir(path)? {
+    total += total_size(&entry?.path())?;
+  }
+  Ok(total)
+}
+
+// 流式复制单个文件，复制完成后校验大小。
+fn copy_file(source: &Path, dest: &Path, progress: &mut CopyProgress) -> Result<(), AppError> {
+  let io_err = |action: &str, e: io::Error| AppError::io(format!("{action} {} 失败: {e}", source.display()));
+  let expected = fs::metadata(source).map_err(|e| io_err("读取", e))?.len();
+  let mut reader = File::open(source).map_err(|e| io_err("打开", e))?;
+  let mut writer = File::create(dest).map_err(|e| io_err("创建目标文件", e))?;
+  let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+  loop {
+    let read = reader.read(&mut buffer).map_err(|e| io_err("读取", e))?;
+    if read == 0 {
+      break;
+    }
+    writer.write_all(&buffer[..read]).map_err(|e| io_err("写入", e))?;
+    progress.copied += read as u64;
+    progress.report(source, false);
+  }
+  writer.sync_all().map_err(|e| io_err("写入", e))?;
+  drop(writer);
+
+  // 大小不一致说明复制不完整，删除残缺的目标文件，保留源文件。
+  let actual = fs::metadata(dest).map_err(|e| io_err("校验", e))?.len();
+  if actual != expected {
+    let _ = fs::remove_file(dest);
+    return Err(AppError::io(format!(
+      "复制 {} 校验失败: 期望 {expected} 字节，实际 {actual} 字节",
+      source.display()
+    )));
+  }
+  Ok(())
+}
+
+// 递归复制文件或目录。
+fn copy_entry(source: &Path, dest: &Path, progress: &mut CopyProgress) -> Result<(), AppError> {
+  if !source.is_dir() {
+    return copy_file(source, dest, progress);
+  }
+  fs::create_dir_all(dest).map_err(|e| AppError::io(format!("创建目录 {} 失败: {e}", dest.display())))?;
+  let entries =
+    fs::read_dir(source).map_err(|e| AppError::io(format!("读取目录 {} 失败: {e}", source.display())))?;
+  for entry in entries {
+    let entry = entry.map_err(|e| AppError::io(format!("读取目录 {} 失败: {e}", source.display())))?;
+    copy_entry(&entry.path(), &dest.join(entry.file_name()), progress)?;
+  }
+  Ok(())
+}
+
+// 把临时目录下的所有条目移动到最终目录（不删除临时目录本身）。
+// 能重命名的直接重命名，跨磁盘的条目统一复制，全部复制并校验成功后才删除源文件。
+pub(super) fn move_entries(app: &tauri::AppHandle, temp_folder: &Path, final_folder: &Path) -> Result<(), AppError> {
+  let entries =
+    fs::read_dir(temp_folder).map_err(|e| AppError::io(format!("读取临时目录失败: {e}")))?;
+  let mut cross_device: Vec<(PathBuf, PathBuf)> = Vec::new();
+  for entry in entries {
+    let entry = entry.map_err(|e| AppError::io(format!("读取临时目录失败: {e}")))?;
+    let source = entry.path();
+    let dest = final_folder.join(entry.file_name());
+    match fs::rename(&source, &dest) {
+      Ok(()) => {}
+      Err(err) if is_cross_device(&err) => cross_device.push((source, dest)),
+      Err(err) => return Err(AppError::io(format!("移动下载文件失败: {err}"))),
+    }
+  }
+  if cross_device.is_empty() {
+    return Ok(());
+  }
+
+  let mut total = 0;
+  for (source, _) in &cross_device {
+    total += total_size(source).map_err(|e| AppError::io(format!("统计文件大小失败: {e}")))?;
+  }
+  let mut progress = CopyProgress {
+    app,
+    temp_folder,
+    final_folder,
+    copied: 0,
+    total,
+    last_emit: None,
+  };
+  for (source, dest) in &cross_device {
+    copy_entry(source, dest, &mut progress)?;
+  }
+  progress.report(temp_folder, true);
+  for (source, _) in &cross_device {
+    let removed = if source.is_dir() {
+      fs::remove_dir_all(source)
+    } else {
+      fs::remove_file(source)
+    };
+    removed.map_err(|e| AppError::io(format!("删除已复制的源文件失败: {e}")))?;
+  }
+  Ok(())
+}
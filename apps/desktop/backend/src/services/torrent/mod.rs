@@ -1,25 +1,88 @@
 /*
   种子下载模块：
-  - 使用 librqbit 作为下载引擎；
+  - 默认使用 librqbit 作为下载引擎，也可以在会话设置中改用 aria2 或 qBittorrent（见 engine 子模块）；
   - 提供开始/暂停/继续/删除等命令；
+  - 有前端订阅时，每秒采样全部任务，通过 torrent://progress 事件推送有变化的任务；
+  - 按做种策略自动停止已完成任务的做种（见 seeding 子模块）；
+  - 同时下载数超过上限时新任务排队，有空位时自动开始（见 queue 子模块）；
+  - 可按文件顺序逐个下载，便于边下边看（见 sequential 子模块）；
+  - 下载中的文件可通过本地 HTTP 地址边下边看（见 stream 子模块）；
+  - 可查看任务连接的 peer 与各自的速率（见 peers 子模块）；
+  - 会话参数可在设置中修改，修改后重建会话（见 session 子模块）；
+  - 按时段自动调整下载/上传限速（见 bandwidth 子模块）；
+  - 按 IP 黑名单过滤 peer（见 blocklist 子模块）；
+  - 开始下载前检查目标磁盘的剩余空间；
   - 管理临时下载目录与最终输出目录。
 */
 
 use chrono::Utc;
-use librqbit::api::{Api, TorrentIdOrHash};
-use librqbit::{AddTorrent, AddTorrentOptions, PeerConnectionOptions, Session, SessionOptions};
-use std::collections::HashSet;
+use librqbit::api::Api;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Once;
 use std::time::Duration;
-use tauri::Manager;
-use tokio::sync::OnceCell as AsyncOnceCell;
+use tauri::Emitter;
+use tokio::sync::Mutex as AsyncMutex;
+
+use super::diagnostics::app_log;
+use super::error::AppError;
+use super::hooks::{run_hooks, HookContext};
+use super::limits::{acquire, LimitKind};
+use super::paths::ensure_path_allowed;
+use super::power::{keep_awake, AwakeGuard};
+use super::settings::load_settings;
+use super::storage::history::{record_download, DownloadRecord};
+use super::system::available_space;
+
+pub mod bandwidth;
+pub mod blocklist;
+pub mod create;
+mod engine;
+mod finalize;
+pub mod peers;
+pub mod queue;
+pub mod seeding;
+pub mod sequential;
+pub mod session;
+pub mod stream;
+pub mod trackers;
+
+pub(crate) use bandwidth::BandwidthSettings;
+pub(crate) use blocklist::{refresh_blocklist, BlocklistSettings};
+use engine::{create_engine, AddOptions, Engine, EngineDetails, EngineKind};
+pub(crate) use seeding::SeedingSettings;
+pub(crate) use session::TorrentSessionSettings;
+pub(crate) use trackers::{refresh_tracker_list, TrackerSettings};
 
 // 默认下载目录名（相对于应用数据目录）。
 const TORRENT_OUTPUT_DIR: &str = "downloads";
+// 临时下载目录名（位于最终目录下）。
+const TEMP_DIR_NAME: &str = ".downloading";
+
+// 后台巡检间隔：多久检查一次是否还有种子在下载。
+const AWAKE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+// 进度事件名与采样间隔。
+const PROGRESS_EVENT: &str = "torrent://progress";
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+// 当前订阅进度事件的数量（每个下载页面订阅一次）；为 0 时不采样。
+static PROGRESS_SUBSCRIBERS: AtomicUsize = AtomicUsize::new(0);
+// 有新订阅时置位：下一次推送全部任务，而不只是变化的部分。
+static PROGRESS_RESET: AtomicBool = AtomicBool::new(false);
+
+// 重新校验时检查是否校验完毕的间隔与等待上限。
+// 磁力链接重新获取元数据时同样处于初始化阶段，可能一直等不到，超过上限就先返回。
+const RECHECK_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const RECHECK_TIMEOUT: Duration = Duration::from_secs(60);
 
-// 全局共享的下载 API（异步懒加载，线程安全）。
-static TORRENT_API: AsyncOnceCell<Api> = AsyncOnceCell::const_new();
+// 全局共享的下载引擎（异步懒加载；会话参数修改后会替换成新会话）。
+static TORRENT_ENGINE: Lazy<AsyncMutex<Option<Engine>>> = Lazy::new(|| AsyncMutex::new(None));
+// 后台任务只启动一次，每次循环取当前的引擎，会话重建后自动跟随新会话。
+static WORKERS_STARTED: Once = Once::new();
 
 /// 开始下载后返回给前端的信息。
 #[derive(serde::Serialize)]
@@ -30,10 +93,12 @@ pub struct TorrentStartResponse {
   pub name: Option<String>,
   pub output_folder: String,
   pub final_folder: String,
+  /// 是否进入了下载队列（以暂停状态加入，有空位时自动开始）。
+  pub queued: bool,
 }
 
 /// 下载状态信息。
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TorrentStatusResponse {
   pub id: usize,
@@ -46,193 +111,990 @@ pub struct TorrentStatusResponse {
   pub download_speed: Option<String>,
   pub upload_speed: Option<String>,
   pub time_remaining: Option<String>,
+  /// 在下载队列中的位置（从 0 开始），不在队列中时为空。
+  pub queue_position: Option<usize>,
+  /// 每个文件的进度（合集里哪一集已经下载完成）。
+  pub files: Vec<TorrentFileStatus>,
+  /// 是否开启了顺序下载。
+  pub sequential: bool,
+  /// 从种子开头起连续下载完成的字节数（可以播放到的位置），无法获取时为 0。
+  pub contiguous_bytes: u64,
 }
 
-// 默认 tracker 列表（用于提高种子可连接性）。
-fn default_tracker_set() -> HashSet<url::Url> {
-  [
-    "udp://tracker.openbittorrent.com:80/announce",
-    "udp://tracker.opentrackr.org:1337/announce",
-    "udp://tracker.internetwarriors.net:1337/announce",
-    "udp://tracker.coppersurfer.tk:6969/announce",
-    "udp://tracker.cyberia.is:6969/announce",
-    "https://tracker2.ctix.cn/announce",
-  ]
-  .into_iter()
-  .filter_map(|url| url::Url::parse(url).ok())
-  .collect()
-}
-
-// 懒加载 Torrent API：首次调用时初始化 session，之后复用。
-async fn torrent_api(app: &tauri::AppHandle) -> Result<Api, String> {
-  TORRENT_API
-    .get_or_try_init(|| async {
-      // 下载文件保存目录（应用数据目录 / hanamirip-cn / downloads）。
-      let base_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("无法获取下载目录: {e}"))?
-        .join("hanamirip-cn")
-        .join(TORRENT_OUTPUT_DIR);
-      fs::create_dir_all(&base_dir).map_err(|e| format!("创建下载目录失败: {e}"))?;
-
-      // 配置下载会话：端口、UPnP、超时、并发等。
-      let mut opts = SessionOptions::default();
-      opts.listen_port_range = Some(40000..50000);
-      opts.enable_upnp_port_forwarding = true;
-      opts.peer_opts = Some(PeerConnectionOptions {
-        connect_timeout: Some(Duration::from_secs(5)),
-        read_write_timeout: Some(Duration::from_secs(15)),
-        keep_alive_interval: Some(Duration::from_secs(60)),
-      });
-      opts.defer_writes_up_to = Some(128);
-      opts.concurrent_init_limit = Some(8);
-      opts.trackers = default_tracker_set();
-
-      // 创建下载会话。
-      let session = Session::new_with_opts(base_dir, opts)
-        .await
-        .map_err(|e| format!("初始化下载会话失败: {e}"))?;
-      Ok(Api::new(session, None))
+/// 文件下载优先级（引擎只支持“下载”与“跳过”两种）。
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FilePriority {
+  Normal,
+  /// 未选择下载（见 start_torrent_download 的 only_files）。
+  Skip,
+}
+
+/// 单个文件的下载进度。
+#[derive(serde::Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentFileStatus {
+  pub index: usize,
+  /// 种子内的相对路径。
+  pub name: String,
+  pub size: u64,
+  pub downloaded_bytes: u64,
+  pub priority: FilePriority,
+}
+
+/// 重新校验后的结果。
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentRecheckResponse {
+  /// 校验后的任务 ID（重新加入会话时与原 ID 不同）。
+  pub id: usize,
+  pub previous_id: usize,
+  /// 校验通过的字节数。
+  pub progress_bytes: u64,
+  pub total_bytes: u64,
+  pub finished: bool,
+  /// 等待超时时仍在校验（或获取元数据），进度以之后的任务状态为准。
+  pub checking: bool,
+}
+
+/// 种子中的一个文件（预览用）。
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentFileItem {
+  /// 文件序号，开始下载时通过 only_files 传回。
+  pub index: usize,
+  /// 种子内的相对路径。
+  pub name: String,
+  pub size: u64,
+}
+
+/// 种子预览：元数据与文件列表（不会开始下载）。
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentPreviewResponse {
+  pub info_hash: String,
+  pub name: Option<String>,
+  pub total_bytes: u64,
+  pub files: Vec<TorrentFileItem>,
+}
+
+/// 下载列表中的一项（会话中的全部任务）。
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentListItem {
+  pub id: usize,
+  pub name: Option<String>,
+  pub info_hash: String,
+  pub state: String,
+  pub progress_bytes: u64,
+  pub total_bytes: u64,
+  pub finished: bool,
+  /// 实际写入文件的目录（下载中为临时目录）。
+  pub output_folder: String,
+}
+
+/// torrent://progress 事件内容（相对上一次推送的增量）。
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentProgressEvent {
+  /// 新出现或状态有变化的任务。
+  pub updated: Vec<TorrentStatusResponse>,
+  /// 已从会话中移除的任务 ID。
+  pub removed: Vec<usize>,
+}
+
+// 统计正在下载的种子数（初始化中或下载中，且尚未完成）。
+// 已暂停（包括排队中）、出错或已完成（做种）的任务不算在内。
+async fn active_download_count(engine: &Engine) -> usize {
+  let mut count = 0;
+  for torrent in engine.list().await.unwrap_or_default() {
+    let Ok(stats) = engine.stats(torrent.id).await else {
+      continue;
+    };
+    if !stats.finished && (stats.state == "Live" || stats.state == "Initializing") {
+      count += 1;
+    }
+  }
+  count
+}
+
+// 判断当前是否有种子正在下载。
+async fn has_active_downloads(engine: &Engine) -> bool {
+  active_download_count(engine).await > 0
+}
+
+// 启动后台巡检：有种子在下载时保持系统唤醒，全部空闲后释放。
+fn spawn_awake_watcher() {
+  tauri::async_runtime::spawn(async move {
+    let mut guard: Option<AwakeGuard> = None;
+    loop {
+      let active = match current_engine().await {
+        Some(engine) => has_active_downloads(&engine).await,
+        None => false,
+      };
+      if !active {
+        // 空闲：drop 掉凭证，允许系统睡眠。
+        guard = None;
+      } else if guard.is_none() {
+        guard = Some(keep_awake("正在下载种子"));
+      }
+      tokio::time::sleep(AWAKE_CHECK_INTERVAL).await;
+    }
+  });
+}
+
+// 读取单个任务的状态。
+async fn torrent_status(engine: &Engine, id: usize) -> Result<TorrentStatusResponse, AppError> {
+  let stats = engine
+    .stats(id)
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载状态失败: {e}")))?;
+
+  // 文件列表来自种子元数据，进度按同样的序号对应（元数据尚未解析时为空）。
+  let details = engine.details(id).await.ok();
+  let sequential = details
+    .as_ref()
+    .is_some_and(|details| sequential::is_enabled(&details.info_hash));
+  let files = details
+    .and_then(|details| details.files)
+    .unwrap_or_default()
+    .into_iter()
+    .enumerate()
+    .map(|(index, file)| TorrentFileStatus {
+      index,
+      downloaded_bytes: stats.file_progress.get(index).copied().unwrap_or(0),
+      priority: if file.included {
+        FilePriority::Normal
+      } else {
+        FilePriority::Skip
+      },
+      name: file.name,
+      size: file.length,
     })
+    .collect();
+  // 从种子开头起连续下载完成的字节数（最后一块可能偏大，按总大小截断）。
+  let contiguous_bytes = engine.contiguous_bytes(id).await.ok().flatten().unwrap_or(0);
+
+  Ok(TorrentStatusResponse {
+    id,
+    state: stats.state,
+    progress_bytes: stats.progress_bytes,
+    total_bytes: stats.total_bytes,
+    uploaded_bytes: stats.uploaded_bytes,
+    finished: stats.finished,
+    error: stats.error,
+    download_speed: stats.download_speed,
+    upload_speed: stats.upload_speed,
+    time_remaining: stats.time_remaining,
+    queue_position: queue::queue_position(id),
+    files,
+    sequential,
+    contiguous_bytes: contiguous_bytes.min(stats.total_bytes),
+  })
+}
+
+// 启动进度推送：有订阅时每秒采样全部任务，与上一次推送比较后只发送变化的部分。
+// 没有订阅时清空上一次的快照，下次订阅时从全量开始。
+fn spawn_progress_emitter(app: tauri::AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let mut last: HashMap<usize, TorrentStatusResponse> = HashMap::new();
+    loop {
+      tokio::time::sleep(PROGRESS_INTERVAL).await;
+      if PROGRESS_SUBSCRIBERS.load(Ordering::SeqCst) == 0 {
+        last.clear();
+        continue;
+      }
+      if PROGRESS_RESET.swap(false, Ordering::SeqCst) {
+        last.clear();
+      }
+      let Some(engine) = current_engine().await else {
+        continue;
+      };
+      // 引擎暂时不可用（例如 aria2 或 qBittorrent 未运行）时跳过这一轮，不把任务当成已移除。
+      let Ok(torrents) = engine.list().await else {
+        continue;
+      };
+
+      // 读取状态失败（例如任务刚被删除）的任务视为已移除。
+      let mut current: HashMap<usize, TorrentStatusResponse> = HashMap::new();
+      for torrent in torrents {
+        if let Ok(status) = torrent_status(&engine, torrent.id).await {
+          current.insert(torrent.id, status);
+        }
+      }
+      let mut updated: Vec<TorrentStatusResponse> = current
+        .values()
+        .filter(|status| last.get(&status.id) != Some(*status))
+        .cloned()
+        .collect();
+      updated.sort_by_key(|status| status.id);
+      let mut removed: Vec<usize> = last.keys().filter(|id| !current.contains_key(id)).copied().collect();
+      removed.sort_unstable();
+      last = current;
+
+      if updated.is_empty() && removed.is_empty() {
+        continue;
+      }
+      let event = TorrentProgressEvent { updated, removed };
+      if let Err(err) = app.emit(PROGRESS_EVENT, &event) {
+        app_log!("发送下载进度事件失败: {err}");
+      }
+    }
+  });
+}
+
+// 按当前设置创建下载引擎。
+async fn create_torrent_engine(app: &tauri::AppHandle) -> Result<Engine, AppError> {
+  let settings = load_settings(app).unwrap_or_default();
+  let engine = create_engine(app, &settings).await?;
+  // 限速按带宽计划的当前时段设置，之后由后台巡检随时段调整。
+  bandwidth::apply_schedule(&engine, &settings.bandwidth).await;
+  Ok(engine)
+}
+
+// 懒加载下载引擎：首次调用时初始化，之后复用。
+async fn torrent_engine(app: &tauri::AppHandle) -> Result<Engine, AppError> {
+  let mut current = TORRENT_ENGINE.lock().await;
+  if let Some(engine) = current.as_ref() {
+    return Ok(engine.clone());
+  }
+  let engine = create_torrent_engine(app).await?;
+  *current = Some(engine.clone());
+  WORKERS_STARTED.call_once(|| {
+    spawn_awake_watcher();
+    spawn_progress_emitter(app.clone());
+    seeding::spawn_seeding_watcher(app.clone());
+    queue::spawn_queue_worker(app.clone());
+    sequential::spawn_sequential_worker();
+    bandwidth::spawn_bandwidth_worker(app.clone());
+  });
+  Ok(engine)
+}
+
+// 已初始化的引擎（后台任务使用，不会触发初始化）。
+async fn current_engine() -> Option<Engine> {
+  TORRENT_ENGINE.lock().await.clone()
+}
+
+// librqbit 专有功能（peer 列表、边下边看）使用的 API；其它引擎返回错误。
+fn librqbit_api(engine: &Engine) -> Result<&Api, AppError> {
+  engine
+    .librqbit()
+    .ok_or_else(|| AppError::torrent("当前下载引擎不支持该功能（仅 librqbit 可用）"))
+}
+
+// 用当前设置重建下载会话（会话参数修改后调用），现有任务重新加入新会话并保持队列顺序。
+// aria2 与 qBittorrent 自己保存任务，引擎没有切换时不需要重新加入；切换引擎时把任务从旧引擎移到新引擎（文件保留），
+// 但本机引擎与 qBittorrent 之间切换时不移动任务（librqbit 的任务随旧会话消失，其它引擎的任务留在原处）。
+// 会话尚未初始化时什么都不做，下次初始化自然使用新设置。
+async fn rebuild_session(app: &tauri::AppHandle) -> Result<(), AppError> {
+  let mut current = TORRENT_ENGINE.lock().await;
+  let Some(old) = current.take() else {
+    return Ok(());
+  };
+  let kind = load_settings(app).unwrap_or_default().torrent_session.engine;
+  // 文件不在同一台机器上的两个引擎之间不移动任务（新引擎看不到旧引擎的文件）。
+  let migrate =
+    (old.kind() == EngineKind::Librqbit || old.kind() != kind) && old.kind().is_remote() == kind.is_remote();
+  let mut snapshots: Vec<(Option<usize>, TorrentSnapshot)> = Vec::new();
+  if migrate {
+    for torrent in old.list().await.unwrap_or_default() {
+      let Ok(snapshot) = snapshot_torrent(&old, torrent.id).await else {
+        continue;
+      };
+      // librqbit 的任务随会话停止而消失，其它引擎需要主动移除（文件保留）。
+      if old.kind() != EngineKind::Librqbit {
+        if let Err(err) = old.forget(torrent.id).await {
+          app_log!("从旧引擎移除任务失败 ({}): {err}", torrent.info_hash);
+        }
+      }
+      snapshots.push((queue::queue_position(torrent.id), snapshot));
+    }
+    queue::clear();
+  }
+  if let Err(err) = old.stop().await {
+    app_log!("停止下载会话失败: {err}");
+  }
+
+  let engine = create_torrent_engine(app).await?;
+  *current = Some(engine.clone());
+  drop(current);
+
+  let mut queued = Vec::new();
+  let mut failed = 0usize;
+  for (position, snapshot) in snapshots {
+    match add_snapshot(&engine, snapshot).await {
+      Ok(id) => {
+        if let Some(position) = position {
+          queued.push((position, id));
+        }
+      }
+      Err(err) => {
+        app_log!("{err}");
+        failed += 1;
+      }
+    }
+  }
+  queued.sort_unstable();
+  for (_, id) in queued {
+    queue::enqueue(id);
+  }
+  if failed > 0 {
+    return Err(AppError::torrent(format!("会话已重建，但 {failed} 个任务未能重新加入")));
+  }
+  Ok(())
+}
+
+// 解析种子元数据并返回文件列表（只读取信息，不添加任务、不下载）。
+// 合集种子可以先预览，再用 start_torrent_download 的 only_files 只下载需要的文件。
+#[tauri::command]
+pub async fn preview_torrent(app: tauri::AppHandle, url: String) -> Result<TorrentPreviewResponse, AppError> {
+  let engine = torrent_engine(&app).await?;
+  let opts = AddOptions {
+    list_only: true,
+    ..AddOptions::default()
+  };
+
+  // 磁力链接需要先从 DHT / peer 获取元数据，同样受种子初始化并发上限约束。
+  let _init_permit = acquire(LimitKind::TorrentInit).await?;
+  let response = engine
+    .add(url, opts)
     .await
-    .map(Clone::clone)
+    .map_err(|e| AppError::torrent(format!("解析种子失败: {e}")))?;
+
+  let files: Vec<TorrentFileItem> = response
+    .details
+    .files
+    .unwrap_or_default()
+    .into_iter()
+    .enumerate()
+    .map(|(index, file)| TorrentFileItem {
+      index,
+      name: file.name,
+      size: file.length,
+    })
+    .collect();
+  Ok(TorrentPreviewResponse {
+    info_hash: response.details.info_hash,
+    name: response.details.name,
+    total_bytes: files.iter().map(|file| file.size).sum(),
+    files,
+  })
 }
 
 // 开始一个新的下载任务。
 // 会把文件先下载到临时目录，完成后再移动到最终目录。
+// only_files 为 preview_torrent 返回的文件序号，不传时下载全部文件。
 #[tauri::command]
 pub async fn start_torrent_download(
   app: tauri::AppHandle,
   url: String,
   output_dir: String,
-) -> Result<TorrentStartResponse, String> {
-  // 获取下载 API（如未初始化则初始化）。
-  let api = torrent_api(&app).await?;
-  // 最终输出目录由前端决定。
-  let final_folder = PathBuf::from(&output_dir);
-  // 临时目录：放在最终目录下的 .downloading 子目录。
-  let temp_folder = final_folder
-    .join(".downloading")
-    .join(Utc::now().timestamp_millis().to_string());
-  fs::create_dir_all(&temp_folder).map_err(|e| format!("创建临时下载目录失败: {e}"))?;
-  // 下载选项：指定输出目录并允许覆盖。
-  let mut opts = AddTorrentOptions::default();
-  opts.output_folder = Some(temp_folder.to_string_lossy().to_string());
-  opts.overwrite = true;
-
-  // 添加下载任务。
-  let response = api
-    .api_add_torrent(AddTorrent::from_url(url), Some(opts))
+  only_files: Option<Vec<usize>>,
+  sequential: Option<bool>,
+) -> Result<TorrentStartResponse, AppError> {
+  if only_files.as_ref().is_some_and(|files| files.is_empty()) {
+    return Err(AppError::invalid_input("请至少选择一个文件"));
+  }
+  // 获取下载引擎（如未初始化则初始化）。
+  let engine = torrent_engine(&app).await?;
+  // 文件不在本机的引擎（qBittorrent）自己决定保存目录，本机不创建目录、不检查空间，
+  // 下载目录即最终目录。
+  let folders = if engine.kind().is_remote() {
+    None
+  } else {
+    // 最终输出目录由前端决定，但必须在授权范围内。
+    let final_folder = ensure_path_allowed(&app, &output_dir).map_err(AppError::invalid_input)?;
+    // 临时目录：放在最终目录下的 .downloading 子目录。
+    let temp_folder = final_folder
+      .join(TEMP_DIR_NAME)
+      .join(Utc::now().timestamp_millis().to_string());
+    fs::create_dir_all(&temp_folder).map_err(|e| AppError::io(format!("创建临时下载目录失败: {e}")))?;
+    Some((final_folder, temp_folder))
+  };
+  // 下载选项：指定输出目录（已有文件会沿用）。
+  let opts = AddOptions {
+    output_folder: folders
+      .as_ref()
+      .map(|(_, temp_folder)| temp_folder.to_string_lossy().to_string()),
+    only_files,
+    // 设置里的 tracker 可能在会话创建后修改过，新任务总是带上当前列表。
+    trackers: Some(trackers::configured_trackers(&app)),
+    // 先以暂停状态加入，拿到元数据、检查磁盘空间后再开始。
+    paused: true,
+    list_only: false,
+  };
+  // 正在下载的任务已达上限时保持暂停，进入队列。
+  let queued = queue::should_queue(&app, &engine).await;
+
+  // 添加下载任务（会解析元数据并校验已有文件，受种子初始化并发上限约束）。
+  let _init_permit = acquire(LimitKind::TorrentInit).await?;
+  let response = engine
+    .add(url, opts)
     .await
-    .map_err(|e| format!("添加下载失败: {e}"))?;
+    .map_err(|e| AppError::torrent(format!("添加下载失败: {e}")))?;
 
-  // 任务 ID 可能在不同字段里，做一次兜底处理。
   let id = response
     .id
-    .or(response.details.id)
-    .ok_or_else(|| "无法获取下载任务 ID".to_string())?;
+    .ok_or_else(|| AppError::torrent("无法获取下载任务 ID"))?;
+  // 空间不足时移除任务并删除刚创建的临时目录，避免下载到一半才因磁盘写满报错。
+  if let Some((_, temp_folder)) = &folders {
+    if let Err(err) = ensure_free_space(&engine, id, temp_folder).await {
+      let _ = engine.forget(id).await;
+      let _ = fs::remove_dir_all(temp_folder);
+      return Err(err);
+    }
+  }
+  if queued {
+    queue::enqueue(id);
+  } else {
+    engine
+      .start(id)
+      .await
+      .map_err(|e| AppError::torrent(format!("开始下载失败: {e}")))?;
+  }
+  // 顺序下载失败不影响任务本身，之后可以用 set_torrent_sequential 重试。
+  if sequential.unwrap_or(false) {
+    if let Err(err) = sequential::enable(&engine, id).await {
+      app_log!("开启顺序下载失败 ({id}): {err}");
+    }
+  }
 
+  let final_folder = match &folders {
+    Some((final_folder, _)) => final_folder.to_string_lossy().to_string(),
+    None => response.details.output_folder.clone(),
+  };
   Ok(TorrentStartResponse {
     id,
     info_hash: response.details.info_hash,
     name: response.details.name,
-    output_folder: response.output_folder,
-    final_folder: final_folder.to_string_lossy().to_string(),
+    output_folder: response.details.output_folder,
+    final_folder,
+    queued,
   })
 }
 
-// 将临时目录内文件移动到最终目录，并清理临时目录。
-#[tauri::command]
-pub fn finalize_torrent_download(temp_folder: String, final_folder: String) -> Result<(), String> {
-  let temp_path = PathBuf::from(temp_folder);
-  let final_path = PathBuf::from(final_folder);
-  // 临时目录不存在，说明无需处理。
-  if !temp_path.exists() {
+// 检查下载目录所在磁盘的剩余空间是否足够放下选中的文件（减去已有的部分）。
+// 无法确定所在磁盘时跳过检查。
+async fn ensure_free_space(engine: &Engine, id: usize, folder: &Path) -> Result<(), AppError> {
+  let details = engine
+    .details(id)
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载任务失败: {e}")))?;
+  let selected: u64 = details
+    .files
+    .unwrap_or_default()
+    .iter()
+    .filter(|file| file.included)
+    .map(|file| file.length)
+    .sum();
+  let downloaded = engine
+    .stats(id)
+    .await
+    .map(|stats| stats.progress_bytes)
+    .unwrap_or(0);
+  let required = selected.saturating_sub(downloaded);
+  let Some(available) = available_space(folder) else {
     return Ok(());
+  };
+  if available < required {
+    return Err(AppError::insufficient_space(
+      &folder.to_string_lossy(),
+      required,
+      available,
+    ));
   }
-  // 确保最终目录存在。
-  fs::create_dir_all(&final_path).map_err(|e| format!("创建最终目录失败: {e}"))?;
+  Ok(())
+}
 
-  // 把临时目录里的文件移动到最终目录。
-  for entry in fs::read_dir(&temp_path).map_err(|e| format!("读取临时目录失败: {e}"))? {
-    let entry = entry.map_err(|e| format!("读取临时目录失败: {e}"))?;
-    let dest = final_path.join(entry.file_name());
-    fs::rename(entry.path(), dest).map_err(|e| format!("移动下载文件失败: {e}"))?;
+// 将临时目录内文件移动到最终目录，并清理临时目录。
+// 跨磁盘时会退回为复制，可能耗时较长，所以放到阻塞线程池里执行，
+// 进度通过 torrent://finalize-progress 事件报告（见 finalize 子模块）。
+// 传入任务 ID 时，收尾后写入下载历史（subject_id 为关联的 Bangumi 条目）并执行任务设置的后处理。
+// 两个目录相同时不移动文件；文件不在本机的引擎（qBittorrent）只写入下载历史，不执行后处理。
+#[tauri::command]
+pub async fn finalize_torrent_download(
+  app: tauri::AppHandle,
+  temp_folder: String,
+  final_folder: String,
+  id: Option<usize>,
+  subject_id: Option<u32>,
+) -> Result<(), AppError> {
+  // 需要移动时 temp_path 为临时目录；local 表示文件在本机（可以执行后处理）。
+  let (temp_path, final_path, local) = if temp_folder == final_folder {
+    let remote = torrent_engine(&app).await?.kind().is_remote();
+    let final_path = if remote {
+      PathBuf::from(&final_folder)
+    } else {
+      ensure_path_allowed(&app, &final_folder).map_err(AppError::invalid_input)?
+    };
+    (None, final_path, !remote)
+  } else {
+    // 两个目录都必须在授权范围内，防止借此移动/删除任意目录。
+    let temp_path = ensure_path_allowed(&app, &temp_folder).map_err(AppError::invalid_input)?;
+    let final_path = ensure_path_allowed(&app, &final_folder).map_err(AppError::invalid_input)?;
+    // 临时目录不存在，说明无需处理。
+    if !temp_path.exists() {
+      return Ok(());
+    }
+    // 确保最终目录存在。
+    fs::create_dir_all(&final_path).map_err(|e| AppError::io(format!("创建最终目录失败: {e}")))?;
+    (Some(temp_path), final_path, true)
+  };
+
+  // 历史记录与后处理需要的信息在移动前读取（任务可能随后被移除）。
+  let details = match id {
+    Some(id) => torrent_engine(&app).await?.details(id).await.ok(),
+    None => None,
+  };
+
+  if let Some(temp_path) = temp_path {
+    let move_app = app.clone();
+    let move_final = final_path.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+      finalize::move_entries(&move_app, &temp_path, &move_final)?;
+      fs::remove_dir_all(&temp_path).map_err(|e| AppError::io(format!("清理临时目录失败: {e}")))
+    })
+    .await
+    .map_err(|e| AppError::internal(format!("移动下载文件失败: {e}")))??;
   }
 
-  fs::remove_dir_all(&temp_path).map_err(|e| format!("清理临时目录失败: {e}"))?;
+  if let Some(details) = details {
+    let context = local.then(|| hook_context(&details, subject_id, &final_path));
+    save_record(&app, Some(download_record(details, subject_id, &final_path)));
+    // 后处理在后台执行，结果见作业日志。
+    if let Some(context) = context {
+      if let Err(err) = run_hooks(&app, context) {
+        app_log!("加入后处理作业失败: {err}");
+      }
+    }
+  }
   Ok(())
 }
 
+// 写入下载历史（失败不影响下载本身）。
+fn save_record(app: &tauri::AppHandle, record: Option<DownloadRecord>) {
+  if let Some(record) = record {
+    if let Err(err) = record_download(app, record) {
+      app_log!("写入下载历史失败: {err}");
+    }
+  }
+}
+
+// 生成下载历史记录。
+fn download_record(details: EngineDetails, subject_id: Option<u32>, final_path: &Path) -> DownloadRecord {
+  let size = details
+    .files
+    .unwrap_or_default()
+    .iter()
+    .filter(|file| file.included)
+    .map(|file| file.length)
+    .sum();
+  DownloadRecord {
+    subject_id,
+    name: details.name.unwrap_or_else(|| details.info_hash.clone()),
+    info_hash: details.info_hash,
+    size,
+    completed_at: Utc::now().to_rfc3339(),
+    output_path: final_path.to_string_lossy().to_string(),
+  }
+}
+
+// 后处理的输入：选中下载的文件在最终目录里的路径（路径不安全的文件跳过，同 payload_paths）。
+fn hook_context(details: &EngineDetails, subject_id: Option<u32>, final_path: &Path) -> HookContext {
+  let is_plain_name = |part: &String| {
+    let mut parsed = Path::new(part).components();
+    matches!((parsed.next(), parsed.next()), (Some(Component::Normal(_)), None))
+  };
+  let files = details
+    .files
+    .iter()
+    .flatten()
+    .filter(|file| file.included && file.components.iter().all(is_plain_name))
+    .map(|file| file.components.iter().fold(final_path.to_path_buf(), |path, part| path.join(part)))
+    .collect();
+  HookContext {
+    info_hash: details.info_hash.clone(),
+    name: details.name.clone().unwrap_or_else(|| details.info_hash.clone()),
+    subject_id,
+    folder: final_path.to_path_buf(),
+    files,
+  }
+}
+
 // 查询下载状态。
 #[tauri::command]
 pub async fn get_torrent_status(
   app: tauri::AppHandle,
   id: usize,
-) -> Result<TorrentStatusResponse, String> {
-  let api = torrent_api(&app).await?;
-  // 读取下载统计信息。
-  let stats = api
-    .api_stats_v1(TorrentIdOrHash::Id(id))
-    .map_err(|e| format!("获取下载状态失败: {e}"))?;
-
-  // 可能没有实时数据，所以这里用 Option 包裹。
-  let (download_speed, upload_speed, time_remaining) = stats
-    .live
-    .as_ref()
-    .map(|live| {
-      (
-        Some(format!("{}", live.download_speed)),
-        Some(format!("{}", live.upload_speed)),
-        live.time_remaining.as_ref().map(|v| v.to_string()),
-      )
-    })
-    .unwrap_or((None, None, None));
+) -> Result<TorrentStatusResponse, AppError> {
+  let engine = torrent_engine(&app).await?;
+  torrent_status(&engine, id).await
+}
 
-  Ok(TorrentStatusResponse {
-    id,
-    state: format!("{:?}", stats.state),
-    progress_bytes: stats.progress_bytes,
-    total_bytes: stats.total_bytes,
-    uploaded_bytes: stats.uploaded_bytes,
-    finished: stats.finished,
-    error: stats.error,
-    download_speed,
-    upload_speed,
-    time_remaining,
+// 订阅 torrent://progress 事件（会顺带初始化下载会话）。
+// 订阅后的第一次推送包含全部任务，之后只推送变化的部分。
+#[tauri::command]
+pub async fn subscribe_torrent_events(app: tauri::AppHandle) -> Result<(), AppError> {
+  torrent_engine(&app).await?;
+  PROGRESS_SUBSCRIBERS.fetch_add(1, Ordering::SeqCst);
+  PROGRESS_RESET.store(true, Ordering::SeqCst);
+  Ok(())
+}
+
+// 取消订阅；所有订阅都取消后停止采样。
+#[tauri::command]
+pub fn unsubscribe_torrent_events() -> Result<(), AppError> {
+  // 多余的取消订阅直接忽略，计数不会小于 0。
+  let _ = PROGRESS_SUBSCRIBERS.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| count.checked_sub(1));
+  Ok(())
+}
+
+// 列出会话中的全部下载任务（按 ID 排序），用于界面重新加载后恢复下载列表。
+#[tauri::command]
+pub async fn list_torrents(app: tauri::AppHandle) -> Result<Vec<TorrentListItem>, AppError> {
+  let engine = torrent_engine(&app).await?;
+  let torrents = engine
+    .list()
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载列表失败: {e}")))?;
+  let mut items: Vec<TorrentListItem> = Vec::new();
+  for torrent in torrents {
+    // 读取状态失败（例如任务刚被删除）时跳过。
+    let Ok(stats) = engine.stats(torrent.id).await else {
+      continue;
+    };
+    items.push(TorrentListItem {
+      id: torrent.id,
+      name: torrent.name,
+      info_hash: torrent.info_hash,
+      state: stats.state,
+      progress_bytes: stats.progress_bytes,
+      total_bytes: stats.total_bytes,
+      finished: stats.finished,
+      output_folder: torrent.output_folder,
+    });
+  }
+  items.sort_by_key(|item| item.id);
+  Ok(items)
+}
+
+// 重新加入任务所需的信息。
+struct TorrentSnapshot {
+  info_hash: String,
+  // 重新加入时使用的地址：BT 任务为磁力链接，其它任务为原下载地址。
+  url: String,
+  output_folder: String,
+  // 有未选择的文件时为选择的文件序号。
+  only_files: Option<Vec<usize>>,
+  paused: bool,
+  trackers: Vec<String>,
+}
+
+// 记录任务的信息，用于之后重新加入。
+async fn snapshot_torrent(engine: &Engine, id: usize) -> Result<TorrentSnapshot, AppError> {
+  let details = engine
+    .details(id)
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载任务失败: {e}")))?;
+  let stats = engine
+    .stats(id)
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载状态失败: {e}")))?;
+  // 只在有未选择的文件时才需要保留文件选择；顺序下载的任务使用原本的完整选择。
+  let files = details.files.unwrap_or_default();
+  let only_files = match sequential::original_selection(&details.info_hash) {
+    Some(selection) => Some(selection),
+    None => files.iter().any(|file| !file.included).then(|| {
+      files
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| file.included)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>()
+    }),
+  };
+  Ok(TorrentSnapshot {
+    url: details
+      .source
+      .unwrap_or_else(|| format!("magnet:?xt=urn:btih:{}", details.info_hash)),
+    info_hash: details.info_hash,
+    output_folder: details.output_folder,
+    only_files,
+    paused: stats.state == "Paused",
+    trackers: engine.trackers(id).await.unwrap_or_default(),
+  })
+}
+
+// 按记录的信息重新加入任务（同一目录、同样的文件选择，已有文件会重新校验），返回新的任务 ID。
+// BT 任务的元数据通过磁力链接重新获取。
+async fn add_snapshot(engine: &Engine, snapshot: TorrentSnapshot) -> Result<usize, AppError> {
+  let opts = AddOptions {
+    output_folder: Some(snapshot.output_folder),
+    only_files: snapshot.only_files,
+    paused: snapshot.paused,
+    trackers: (!snapshot.trackers.is_empty()).then_some(snapshot.trackers),
+    list_only: false,
+  };
+  let _init_permit = acquire(LimitKind::TorrentInit).await?;
+  let response = engine
+    .add(snapshot.url, opts)
+    .await
+    .map_err(|e| {
+      AppError::torrent(format!(
+        "重新加入下载任务 {} 失败（文件仍保留）: {e}",
+        snapshot.info_hash
+      ))
+    })?;
+  response
+    .id
+    .ok_or_else(|| AppError::torrent("无法获取下载任务 ID"))
+}
+
+// 移除任务（保留文件）后重新加入，返回新的任务 ID。
+// 引擎不支持修改运行中任务的部分属性（tracker），也只在加入任务时校验已有文件，
+// 这些操作都通过重新加入实现。extra_trackers 为要追加的 tracker。
+async fn readd_torrent(engine: &Engine, id: usize, extra_trackers: Vec<String>) -> Result<usize, AppError> {
+  let mut snapshot = snapshot_torrent(engine, id).await?;
+  for tracker in extra_trackers {
+    if !snapshot.trackers.contains(&tracker) {
+      snapshot.trackers.push(tracker);
+    }
+  }
+  queue::dequeue(id);
+  engine
+    .forget(id)
+    .await
+    .map_err(|e| AppError::torrent(format!("移除下载任务失败: {e}")))?;
+  add_snapshot(engine, snapshot).await
+}
+
+// 重新校验任务数据（例如手动把文件移回来之后，或崩溃之后）。
+// 引擎支持原地校验时直接校验，任务 ID 不变；
+// 否则引擎只在加入任务时校验已有文件，这里重新加入任务，任务 ID 会变化。
+// 等校验结束（最多 RECHECK_TIMEOUT）后返回校验通过的进度。
+#[tauri::command]
+pub async fn recheck_torrent(app: tauri::AppHandle, id: usize) -> Result<TorrentRecheckResponse, AppError> {
+  let engine = torrent_engine(&app).await?;
+  let new_id = match engine.recheck(id) {
+    Some(recheck) => {
+      recheck
+        .await
+        .map_err(|e| AppError::torrent(format!("重新校验失败: {e}")))?;
+      id
+    }
+    None => readd_torrent(&engine, id, Vec::new()).await?,
+  };
+
+  // 初始化阶段就是校验已有文件，等它结束。
+  let stats = || async {
+    engine
+      .stats(new_id)
+      .await
+      .map_err(|e| AppError::torrent(format!("获取下载状态失败: {e}")))
+  };
+  let wait = async {
+    loop {
+      let current = stats().await?;
+      if current.state != "Initializing" {
+        return Ok::<_, AppError>(current);
+      }
+      tokio::time::sleep(RECHECK_POLL_INTERVAL).await;
+    }
+  };
+  let (current, checking) = match tokio::time::timeout(RECHECK_TIMEOUT, wait).await {
+    Ok(current) => (current?, false),
+    Err(_) => (stats().await?, true),
+  };
+  Ok(TorrentRecheckResponse {
+    id: new_id,
+    previous_id: id,
+    progress_bytes: current.progress_bytes,
+    total_bytes: current.total_bytes,
+    finished: current.finished,
+    checking,
   })
 }
 
 // 暂停下载任务。
 #[tauri::command]
-pub async fn pause_torrent(app: tauri::AppHandle, id: usize) -> Result<(), String> {
-  let api = torrent_api(&app).await?;
-  api
-    .api_torrent_action_pause(TorrentIdOrHash::Id(id))
+pub async fn pause_torrent(app: tauri::AppHandle, id: usize) -> Result<(), AppError> {
+  let engine = torrent_engine(&app).await?;
+  // 手动操作后任务离开下载队列，不再自动开始。
+  queue::dequeue(id);
+  engine
+    .pause(id)
     .await
-    .map_err(|e| format!("暂停下载失败: {e}"))?;
+    .map_err(|e| AppError::torrent(format!("暂停下载失败: {e}")))?;
   Ok(())
 }
 
 // 继续下载任务。
 #[tauri::command]
-pub async fn resume_torrent(app: tauri::AppHandle, id: usize) -> Result<(), String> {
-  let api = torrent_api(&app).await?;
-  api
-    .api_torrent_action_start(TorrentIdOrHash::Id(id))
+pub async fn resume_torrent(app: tauri::AppHandle, id: usize) -> Result<(), AppError> {
+  let engine = torrent_engine(&app).await?;
+  // 手动操作后任务离开下载队列，不再自动开始。
+  queue::dequeue(id);
+  engine
+    .start(id)
     .await
-    .map_err(|e| format!("继续下载失败: {e}"))?;
+    .map_err(|e| AppError::torrent(format!("继续下载失败: {e}")))?;
   Ok(())
 }
 
-// 删除下载任务（只删除任务，不一定删除文件）。
+// 删除任务文件时要删除的路径：(临时目录, 最终目录里的文件)。
+// 临时目录必须在授权范围内，其余检查见 payload_layout。
+fn payload_paths(
+  app: &tauri::AppHandle,
+  output_folder: &str,
+  files: &[Vec<String>],
+) -> Result<(PathBuf, Vec<PathBuf>), AppError> {
+  let temp_folder = ensure_path_allowed(app, output_folder).map_err(AppError::invalid_input)?;
+  payload_layout(temp_folder, files)
+}
+
+// 安全检查：
+// - 临时目录必须是 <最终目录>/.downloading/<时间戳> 的形式；
+// - 最终目录里只删除种子中列出的文件，且每一段路径都必须是普通文件名，不能跳出最终目录。
+fn payload_layout(temp_folder: PathBuf, files: &[Vec<String>]) -> Result<(PathBuf, Vec<PathBuf>), AppError> {
+  let final_folder = temp_folder
+    .parent()
+    .filter(|parent| parent.file_name().is_some_and(|name| name == TEMP_DIR_NAME))
+    .and_then(Path::parent)
+    .ok_or_else(|| {
+      AppError::invalid_input(format!("下载目录不是预期的结构，拒绝删除文件: {}", temp_folder.display()))
+    })?
+    .to_path_buf();
+
+  let mut finalized = Vec::new();
+  for components in files {
+    let mut path = final_folder.clone();
+    for part in components {
+      let mut parsed = Path::new(part).components();
+      match (parsed.next(), parsed.next()) {
+        (Some(Component::Normal(name)), None) => path.push(name),
+        _ => {
+          return Err(AppError::invalid_input(format!(
+            "种子中的文件路径不安全，拒绝删除文件: {}",
+            components.join("/")
+          )))
+        }
+      }
+    }
+    finalized.push(path);
+  }
+  Ok((temp_folder, finalized))
+}
+
+// 删除任务文件，并清理因此变空的目录（不会删除最终目录本身）。
+fn remove_payload(temp_folder: &Path, finalized: &[PathBuf]) -> Result<(), AppError> {
+  if temp_folder.exists() {
+    fs::remove_dir_all(temp_folder).map_err(|e| AppError::io(format!("删除临时目录失败: {e}")))?;
+  }
+  // .downloading 下没有其它任务时一并删除（非空时删除会失败，忽略即可）。
+  if let Some(parent) = temp_folder.parent() {
+    let _ = fs::remove_dir(parent);
+  }
+  let final_folder = temp_folder.parent().and_then(Path::parent);
+  for path in finalized {
+    if path.is_file() {
+      fs::remove_file(path).map_err(|e| AppError::io(format!("删除 {} 失败: {e}", path.display())))?;
+    }
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+      if Some(current) == final_folder || fs::remove_dir(current).is_err() {
+        break;
+      }
+      dir = current.parent();
+    }
+  }
+  Ok(())
+}
+
+// 删除下载任务。
+// delete_files 为 true 时同时删除已下载的文件（临时目录，以及已移动到最终目录的文件），
+// 否则只移除任务，文件保留在磁盘上。文件不在本机的引擎由引擎自己删除文件。
 #[tauri::command]
-pub async fn delete_torrent(app: tauri::AppHandle, id: usize) -> Result<(), String> {
-  let api = torrent_api(&app).await?;
-  api
-    .api_torrent_action_delete(TorrentIdOrHash::Id(id))
+pub async fn delete_torrent(
+  app: tauri::AppHandle,
+  id: usize,
+  delete_files: bool,
+) -> Result<(), AppError> {
+  let engine = torrent_engine(&app).await?;
+  if engine.kind().is_remote() && delete_files {
+    queue::dequeue(id);
+    return engine
+      .forget_with_files(id)
+      .await
+      .map_err(|e| AppError::torrent(format!("删除下载失败: {e}")));
+  }
+  // 要删除文件时，先在移除任务之前完成安全检查，检查不通过则什么都不做。
+  let payload = if delete_files {
+    let details = engine
+      .details(id)
+      .await
+      .map_err(|e| AppError::torrent(format!("获取下载任务失败: {e}")))?;
+    // 未选中下载的文件从未写入磁盘，最终目录里的同名文件不属于这个任务。
+    let files: Vec<Vec<String>> = details
+      .files
+      .unwrap_or_default()
+      .into_iter()
+      .filter(|file| file.included)
+      .map(|file| file.components)
+      .collect();
+    Some(payload_paths(&app, &details.output_folder, &files)?)
+  } else {
+    None
+  };
+
+  // 手动操作后任务离开下载队列，不再自动开始。
+  queue::dequeue(id);
+  // 先移除任务，让引擎释放文件句柄，再删除文件。
+  engine
+    .forget(id)
     .await
-    .map_err(|e| format!("删除下载失败: {e}"))?;
+    .map_err(|e| AppError::torrent(format!("删除下载失败: {e}")))?;
+  if let Some((temp_folder, finalized)) = payload {
+    remove_payload(&temp_folder, &finalized)?;
+  }
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn files(paths: &[&[&str]]) -> Vec<Vec<String>> {
+    paths
+      .iter()
+      .map(|parts| parts.iter().map(|part| part.to_string()).collect())
+      .collect()
+  }
+
+  #[test]
+  fn payload_layout_maps_files_into_final_folder() {
+    let temp = Path::new("lib").join(TEMP_DIR_NAME).join("1700000000");
+    let (temp_folder, finalized) = payload_layout(temp.clone(), &files(&[&["a.mkv"], &["Show", "b.ass"]])).unwrap();
+    assert_eq!(temp_folder, temp);
+    assert_eq!(
+      finalized,
+      vec![Path::new("lib").join("a.mkv"), Path::new("lib").join("Show").join("b.ass")]
+    );
+  }
+
+  #[test]
+  fn payload_layout_rejects_unexpected_temp_folder() {
+    for temp in [Path::new("lib").join("1700000000"), PathBuf::from(TEMP_DIR_NAME)] {
+      assert!(payload_layout(temp, &files(&[&["a.mkv"]])).is_err());
+    }
+  }
+
+  #[test]
+  fn payload_layout_rejects_unsafe_components() {
+    let temp = Path::new("lib").join(TEMP_DIR_NAME).join("1700000000");
+    let unsafe_parts: [&[&str]; 5] = [&[".."], &["Show", "..", "a.mkv"], &["/etc/passwd"], &["Show/a.mkv"], &[""]];
+    for parts in unsafe_parts {
+      assert!(payload_layout(temp.clone(), &files(&[parts])).is_err(), "{parts:?}");
+    }
+  }
+}
@@ -0,0 +1,154 @@
+/*
+  下载队列：
+  同时下载的任务数超过设置里的上限（ConcurrencyLimits.max_active_downloads）时，
+  新任务以暂停状态加入会话并进入队列，有空位时按队列顺序自动开始。
+  一次添加整季的种子时，不会同时抢占磁盘与带宽。
+
+  - 队列只保存在内存中，顺序可以通过 set_queue_position 调整；
+  - 用户手动继续、暂停或删除排队中的任务时，任务离开队列，之后不再自动开始。
+*/
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use super::engine::Engine;
+use super::{current_engine, torrent_engine};
+use crate::services::diagnostics::app_log;
+use crate::services::error::AppError;
+use crate::services::settings::load_settings;
+
+// 检查是否有空位的间隔。
+const CHECK_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 队列中的一项。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentQueueItem {
+  pub id: usize,
+  /// 队列位置（从 0 开始，0 最先开始）。
+  pub position: usize,
+  pub name: Option<String>,
+  pub info_hash: String,
+}
+
+// 排队中的任务 ID（按开始顺序）。
+static QUEUE: Lazy<Mutex<Vec<usize>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+// 锁定队列（锁中毒时数据仍然可用）。
+fn queue() -> MutexGuard<'static, Vec<usize>> {
+  QUEUE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// 同时下载的任务数上限。
+fn max_active_downloads(app: &tauri::AppHandle) -> usize {
+  load_settings(app).unwrap_or_default().concurrency.max_active_downloads
+}
+
+// 新任务是否需要排队（正在下载的任务已达到上限，或前面已有任务在排队）。
+pub(super) async fn should_queue(app: &tauri::AppHandle, engine: &Engine) -> bool {
+  let has_queued = !queue().is_empty();
+  has_queued || super::active_download_count(engine).await >= max_active_downloads(app)
+}
+
+// 把任务加入队尾。
+pub(super) fn enqueue(id: usize) {
+  let mut queue = queue();
+  if !queue.contains(&id) {
+    queue.push(id);
+  }
+}
+
+// 任务离开队列（手动继续/暂停/删除时调用）。
+pub(super) fn dequeue(id: usize) {
+  queue().retain(|queued| *queued != id);
+}
+
+// 清空队列（重建会话时调用，之后按新任务 ID 重新加入）。
+pub(super) fn clear() {
+  queue().clear();
+}
+
+// 任务在队列中的位置；不在队列中时为 None。
+pub(super) fn queue_position(id: usize) -> Option<usize> {
+  queue().iter().position(|queued| *queued == id)
+}
+
+// 有空位时按顺序开始排队中的任务；已不在会话中的任务直接移出队列。
+async fn start_next(app: &tauri::AppHandle, engine: &Engine) {
+  loop {
+    if super::active_download_count(engine).await >= max_active_downloads(app) {
+      return;
+    }
+    let Some(id) = queue().first().copied() else {
+      return;
+    };
+    dequeue(id);
+    if let Err(err) = engine.start(id).await {
+      app_log!("开始排队中的下载失败 ({id}): {err}");
+    }
+  }
+}
+
+// 启动队列巡检（下载会话初始化时调用一次）。
+pub(super) fn spawn_queue_worker(app: tauri::AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(CHECK_INTERVAL).await;
+      if let Some(engine) = current_engine().await {
+        start_next(&app, &engine).await;
+      }
+    }
+  });
+}
+
+// 当前队列（按开始顺序）。
+async fn queue_items(engine: &Engine) -> Result<Vec<TorrentQueueItem>, AppError> {
+  let torrents = engine
+    .list()
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载列表失败: {e}")))?;
+  let items = queue()
+    .iter()
+    .enumerate()
+    .filter_map(|(position, id)| {
+      let torrent = torrents.iter().find(|torrent| torrent.id == *id)?;
+      Some(TorrentQueueItem {
+        id: *id,
+        position,
+        name: torrent.name.clone(),
+        info_hash: torrent.info_hash.clone(),
+      })
+    })
+    .collect();
+  Ok(items)
+}
+
+// 返回排队中的任务。
+#[tauri::command]
+pub async fn get_queue(app: tauri::AppHandle) -> Result<Vec<TorrentQueueItem>, AppError> {
+  let engine = torrent_engine(&app).await?;
+  queue_items(&engine).await
+}
+
+// 调整任务在队列中的位置（超出范围时放到队尾），返回调整后的队列。
+#[tauri::command]
+pub async fn set_queue_position(
+  app: tauri::AppHandle,
+  id: usize,
+  position: usize,
+) -> Result<Vec<TorrentQueueItem>, AppError> {
+  let engine = torrent_engine(&app).await?;
+  {
+    let mut queue = queue();
+    let current = queue
+      .iter()
+      .position(|queued| *queued == id)
+      .ok_or_else(|| AppError::invalid_input(format!("任务不在队列中: {id}")))?;
+    queue.remove(current);
+    let position = position.min(queue.len());
+    queue.insert(position, id);
+  }
+  queue_items(&engine).await
+}
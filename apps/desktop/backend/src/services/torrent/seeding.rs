@@ -0,0 +1,210 @@
+/*
+  做种策略：
+  下载完成的任务默认会一直做种，这里按设置里的规则自动停止：
+  - 分享率（已上传 / 总大小）达到上限，或做种时间达到上限，任一条件满足即触发；
+  - 触发后按设置暂停任务，或移除任务（保留已下载的文件）；
+  - 单个任务可以用自己的规则覆盖全局规则（按 info_hash 保存在设置里）。
+
+  做种时间从本次启动后第一次发现任务已完成时开始计算，重启应用后重新计时。
+  已经处理过的任务不会再次处理，用户手动继续做种后不会被立刻暂停。
+*/
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::engine::Engine;
+use super::{current_engine, torrent_engine};
+use crate::services::diagnostics::app_log;
+use crate::services::error::AppError;
+use crate::services::settings::{load_settings, save_settings};
+
+// 巡检间隔。
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 做种规则。两项都为空表示不限制。
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SeedingRule {
+  /// 分享率上限（已上传 / 总大小），例如 2.0。
+  pub ratio_limit: Option<f64>,
+  /// 做种时间上限（分钟）。
+  pub time_limit_minutes: Option<u64>,
+}
+
+impl SeedingRule {
+  // 非正数（或 NaN）的上限视为不限制。
+  fn normalized(self) -> Self {
+    Self {
+      ratio_limit: self.ratio_limit.filter(|ratio| *ratio > 0.0),
+      time_limit_minutes: self.time_limit_minutes.filter(|minutes| *minutes > 0),
+    }
+  }
+}
+
+/// 达到上限后的处理方式。
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SeedingAction {
+  /// 暂停任务。
+  #[default]
+  Pause,
+  /// 移除任务（保留已下载的文件）。
+  Remove,
+}
+
+/// 做种策略设置（保存在设置里）。
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SeedingSettings {
+  /// 全局规则（默认不限制，即一直做种）。
+  pub rule: SeedingRule,
+  pub action: SeedingAction,
+  /// 单个任务的规则（info_hash -> 规则），优先于全局规则。
+  pub overrides: HashMap<String, SeedingRule>,
+}
+
+impl SeedingSettings {
+  // 修正非法值，info_hash 统一为小写。
+  pub(crate) fn normalized(mut self) -> Self {
+    self.rule = self.rule.normalized();
+    self.overrides = self
+      .overrides
+      .into_iter()
+      .map(|(hash, rule)| (hash.trim().to_lowercase(), rule.normalized()))
+      .filter(|(hash, _)| !hash.is_empty())
+      .collect();
+    self
+  }
+
+  // 某个任务实际生效的规则。
+  fn rule_for(&self, info_hash: &str) -> &SeedingRule {
+    self.overrides.get(&info_hash.to_lowercase()).unwrap_or(&self.rule)
+  }
+}
+
+// 做种状态（仅内存）：开始做种的时间，以及已经处理过的任务。
+#[derive(Default)]
+struct SeedingState {
+  since: HashMap<String, Instant>,
+  handled: HashSet<String>,
+}
+
+static STATE: Lazy<Mutex<SeedingState>> = Lazy::new(|| Mutex::new(SeedingState::default()));
+
+// 判断是否达到规则上限。
+fn limit_reached(rule: &SeedingRule, uploaded: u64, total: u64, seeded: Duration) -> bool {
+  let ratio_reached = rule
+    .ratio_limit
+    .is_some_and(|limit| total > 0 && uploaded as f64 / total as f64 >= limit);
+  let time_reached = rule
+    .time_limit_minutes
+    .is_some_and(|minutes| seeded >= Duration::from_secs(minutes * 60));
+  ratio_reached || time_reached
+}
+
+// 一次巡检：找出达到上限的任务（返回 ID 与 info_hash），并更新做种状态。
+async fn due_torrents(engine: &Engine, settings: &SeedingSettings) -> Vec<(usize, String)> {
+  // 引擎暂时不可用时跳过，不清空做种计时。
+  let Ok(torrents) = engine.list().await else {
+    return Vec::new();
+  };
+  let mut samples = Vec::new();
+  for torrent in torrents {
+    if let Ok(stats) = engine.stats(torrent.id).await {
+      samples.push((torrent.id, torrent.info_hash, stats));
+    }
+  }
+
+  let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+  let mut seen = HashSet::new();
+  let mut due = Vec::new();
+  for (id, hash, stats) in samples {
+    seen.insert(hash.clone());
+    // 未完成（或重新校验后变回未完成）的任务重新计时。
+    if !stats.finished {
+      state.since.remove(&hash);
+      state.handled.remove(&hash);
+      continue;
+    }
+    let since = *state.since.entry(hash.clone()).or_insert_with(Instant::now);
+    if state.handled.contains(&hash) || stats.state == "Paused" {
+      continue;
+    }
+    let rule = settings.rule_for(&hash);
+    if limit_reached(rule, stats.uploaded_bytes, stats.total_bytes, since.elapsed()) {
+      due.push((id, hash));
+    }
+  }
+  // 已不在会话中的任务不再需要状态。
+  state.since.retain(|hash, _| seen.contains(hash));
+  state.handled.retain(|hash| seen.contains(hash));
+  due
+}
+
+// 对达到上限的任务执行设置里的处理方式。
+async fn enforce(engine: &Engine, settings: &SeedingSettings) {
+  for (id, hash) in due_torrents(engine, settings).await {
+    let result = match settings.action {
+      SeedingAction::Pause => engine.pause(id).await,
+      SeedingAction::Remove => engine.forget(id).await,
+    };
+    match result {
+      Ok(_) => {
+        STATE
+          .lock()
+          .unwrap_or_else(|e| e.into_inner())
+          .handled
+          .insert(hash);
+      }
+      Err(err) => app_log!("停止做种失败 ({id}): {err}"),
+    }
+  }
+}
+
+// 启动做种策略巡检（下载会话初始化时调用一次）。
+pub(super) fn spawn_seeding_watcher(app: tauri::AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(CHECK_INTERVAL).await;
+      let Some(engine) = current_engine().await else {
+        continue;
+      };
+      let settings = load_settings(&app).unwrap_or_default().seeding;
+      enforce(&engine, &settings).await;
+    }
+  });
+}
+
+// 设置（rule 为空时清除）单个任务的做种规则，返回修正后的做种设置。
+#[tauri::command]
+pub async fn set_torrent_seeding_override(
+  app: tauri::AppHandle,
+  id: usize,
+  rule: Option<SeedingRule>,
+) -> Result<SeedingSettings, AppError> {
+  let engine = torrent_engine(&app).await?;
+  let hash = engine
+    .list()
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载列表失败: {e}")))?
+    .into_iter()
+    .find(|torrent| torrent.id == id)
+    .map(|torrent| torrent.info_hash.to_lowercase())
+    .ok_or_else(|| AppError::invalid_input(format!("下载任务不存在: {id}")))?;
+
+  let mut settings = load_settings(&app).map_err(AppError::io)?;
+  match rule {
+    Some(rule) => settings.seeding.overrides.insert(hash.clone(), rule),
+    None => settings.seeding.overrides.remove(&hash),
+  };
+  // 规则变化后允许重新判断（例如调高了上限后再次触发）。
+  STATE
+    .lock()
+    .unwrap_or_else(|e| e.into_inner())
+    .handled
+    .remove(&hash);
+  Ok(save_settings(&app, settings).map_err(AppError::io)?.seeding)
+}
@@ -0,0 +1,177 @@
+/*
+  顺序下载：
+  边下边看时，希望选中的文件按顺序一个一个下载完成，而不是所有文件同时推进。
+  引擎在一个文件内本来就按分块顺序请求，但每个 peer 只能提供自己拥有的分块，
+  多文件时仍会把带宽分散到后面的文件上。
+
+  顺序模式下，任务只保留“已完成的文件 + 第一个未完成的文件”为选中状态，
+  当前文件完成后由后台巡检自动选中下一个；关闭时恢复原来的完整选择。
+  - 原始选择按 info hash 保存在内存中，重新加入任务（ID 变化）后仍然有效；
+  - 任务被删除后，巡检时自动清理对应的记录。
+*/
+
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use super::engine::Engine;
+use super::{current_engine, torrent_engine, torrent_status, TorrentStatusResponse};
+use crate::services::diagnostics::app_log;
+use crate::services::error::AppError;
+
+// 检查当前文件是否完成的间隔。
+const CHECK_INTERVAL: Duration = Duration::from_secs(3);
+
+// 开启顺序模式的任务：info hash -> 用户原本选择的文件序号（按顺序）。
+static SELECTIONS: Lazy<Mutex<HashMap<String, Vec<usize>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 锁定记录（锁中毒时数据仍然可用）。
+fn selections() -> MutexGuard<'static, HashMap<String, Vec<usize>>> {
+  SELECTIONS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// 任务是否开启了顺序模式。
+pub(super) fn is_enabled(info_hash: &str) -> bool {
+  selections().contains_key(info_hash)
+}
+
+// 开启顺序模式时用户原本选择的文件（重新加入任务时应使用这个选择）。
+pub(super) fn original_selection(info_hash: &str) -> Option<Vec<usize>> {
+  selections().get(info_hash).cloned()
+}
+
+// 更新任务选中的文件（与当前选择相同时不做任何事）。
+async fn update_files(engine: &Engine, id: usize, wanted: HashSet<usize>, current: &HashSet<usize>) -> Result<(), AppError> {
+  if &wanted == current {
+    return Ok(());
+  }
+  engine
+    .update_only_files(id, &wanted)
+    .await
+    .map_err(|e| AppError::torrent(format!("更新下载文件失败: {e}")))
+}
+
+// 按顺序模式调整任务的文件选择：已完成的文件与第一个未完成的文件。
+async fn advance(engine: &Engine, id: usize, selection: &[usize]) -> Result<(), AppError> {
+  let details = engine
+    .details(id)
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载任务失败: {e}")))?;
+  // 元数据尚未解析时无法判断，下次巡检再处理。
+  let Some(files) = details.files else {
+    return Ok(());
+  };
+  let stats = engine
+    .stats(id)
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载状态失败: {e}")))?;
+  let current: HashSet<usize> = files
+    .iter()
+    .enumerate()
+    .filter(|(_, file)| file.included)
+    .map(|(index, _)| index)
+    .collect();
+
+  let mut wanted = HashSet::new();
+  for &index in selection {
+    let Some(file) = files.get(index) else {
+      continue;
+    };
+    wanted.insert(index);
+    if stats.file_progress.get(index).copied().unwrap_or(0) < file.length {
+      break;
+    }
+  }
+  update_files(engine, id, wanted, &current).await
+}
+
+// 开启顺序模式：记录当前的文件选择，然后只保留第一个未完成的文件。
+pub(super) async fn enable(engine: &Engine, id: usize) -> Result<(), AppError> {
+  let details = engine
+    .details(id)
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载任务失败: {e}")))?;
+  // 已经开启时保留原来的选择，不要把缩小后的选择当成原始选择。
+  let selection = match original_selection(&details.info_hash) {
+    Some(selection) => selection,
+    None => {
+      let selection: Vec<usize> = details
+        .files
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| file.included)
+        .map(|(index, _)| index)
+        .collect();
+      selections().insert(details.info_hash, selection.clone());
+      selection
+    }
+  };
+  advance(engine, id, &selection).await
+}
+
+// 关闭顺序模式：恢复原来的完整选择。
+async fn disable(engine: &Engine, id: usize) -> Result<(), AppError> {
+  let details = engine
+    .details(id)
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载任务失败: {e}")))?;
+  let Some(selection) = selections().remove(&details.info_hash) else {
+    return Ok(());
+  };
+  let current: HashSet<usize> = details
+    .files
+    .unwrap_or_default()
+    .iter()
+    .enumerate()
+    .filter(|(_, file)| file.included)
+    .map(|(index, _)| index)
+    .collect();
+  update_files(engine, id, selection.into_iter().collect(), &current).await
+}
+
+// 巡检一次：为顺序模式的任务推进到下一个文件，并清理已删除任务的记录。
+async fn check(engine: &Engine) {
+  // 引擎暂时不可用时跳过，不清理记录。
+  let Ok(torrents) = engine.list().await else {
+    return;
+  };
+  selections().retain(|hash, _| torrents.iter().any(|torrent| &torrent.info_hash == hash));
+  for torrent in &torrents {
+    let Some(selection) = original_selection(&torrent.info_hash) else {
+      continue;
+    };
+    if let Err(err) = advance(engine, torrent.id, &selection).await {
+      app_log!("顺序下载切换文件失败 ({}): {err}", torrent.id);
+    }
+  }
+}
+
+// 启动顺序下载巡检（下载会话初始化时调用一次）。
+pub(super) fn spawn_sequential_worker() {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(CHECK_INTERVAL).await;
+      if let Some(engine) = current_engine().await {
+        check(&engine).await;
+      }
+    }
+  });
+}
+
+// 开启或关闭任务的顺序下载，返回更新后的状态。
+#[tauri::command]
+pub async fn set_torrent_sequential(
+  app: tauri::AppHandle,
+  id: usize,
+  enabled: bool,
+) -> Result<TorrentStatusResponse, AppError> {
+  let engine = torrent_engine(&app).await?;
+  if enabled {
+    enable(&engine, id).await?;
+  } else {
+    disable(&engine, id).await?;
+  }
+  torrent_status(&engine, id).await
+}
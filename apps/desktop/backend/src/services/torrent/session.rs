@@ -0,0 +1,197 @@
+/*
+  下载会话设置：
+  监听端口范围、UPnP、DHT、peer 连接超时、写盘缓冲与代理等引擎参数，
+  保存在设置里（AppSettings.torrent_session）。
+
+  代理：引擎的 peer 连接只支持 SOCKS5，所以这里只接受 SOCKS5 代理；
+  设置后 peer 连接与 HTTP(S) tracker 请求都经过代理，UDP tracker 与 DHT 不经过代理。
+  可以单独填写，也可以复用应用的代理设置（需为 SOCKS5）。
+
+  这些参数只能在创建会话时指定，所以修改后会重建会话：
+  记录现有任务（info hash、目录、文件选择、暂停状态、tracker、队列顺序），
+  停止旧会话，用新参数创建会话后重新加入这些任务（文件保留并重新校验）。
+  重建后任务 ID 会变化，前端应通过 list_torrents 重新加载下载列表。
+
+  下载引擎（librqbit / aria2 / qBittorrent）同样在这里选择。使用 aria2 或 qBittorrent 时以上引擎参数
+  以对方自己的配置为准，这里只保存连接信息；它们自己保存任务，引擎不变时重建会话不会重新加入任务，任务 ID 不变。
+*/
+
+use librqbit::{PeerConnectionOptions, SessionOptions};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::engine::{check_engine, EngineKind};
+use super::rebuild_session;
+use crate::services::bangumi::obfuscate_credential_value;
+use crate::services::error::AppError;
+use crate::services::settings::{load_settings, save_settings, AppSettings};
+
+// aria2 默认的 JSON-RPC 地址。
+const DEFAULT_ARIA2_RPC_URL: &str = "http://127.0.0.1:6800/jsonrpc";
+// qBittorrent 默认的 Web UI 地址。
+const DEFAULT_QBITTORRENT_URL: &str = "http://127.0.0.1:8080";
+
+/// 下载会话设置（保存在设置里）。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TorrentSessionSettings {
+  /// 下载引擎。
+  pub engine: EngineKind,
+  /// aria2 的 JSON-RPC 地址。
+  pub aria2_rpc_url: String,
+  /// aria2 的 RPC 密钥（--rpc-secret），未设置时为空；保存时混淆为 "enc:<hex>"。
+  pub aria2_secret: Option<String>,
+  /// qBittorrent 的 Web UI 地址，例如 http://192.168.1.10:8080。
+  pub qbittorrent_url: String,
+  /// qBittorrent 的 Web UI 用户名，为空表示不登录（qBittorrent 设置了免验证时）。
+  pub qbittorrent_username: Option<String>,
+  /// qBittorrent 的 Web UI 密码，保存时混淆为 "enc:<hex>"。
+  pub qbittorrent_password: Option<String>,
+  /// qBittorrent 上的保存目录（qBittorrent 所在机器上的路径），为空表示使用 qBittorrent 的默认目录。
+  pub qbittorrent_save_path: Option<String>,
+  /// 监听端口范围（包含起点，不包含终点）。
+  pub listen_port_start: u16,
+  pub listen_port_end: u16,
+  /// 是否通过 UPnP 自动映射监听端口。
+  pub enable_upnp: bool,
+  /// 是否启用 DHT（关闭后磁力链接只能通过 tracker 获取元数据）。
+  pub enable_dht: bool,
+  /// 连接 peer 的超时（秒）。
+  pub peer_connect_timeout_secs: u64,
+  /// 与 peer 读写的超时（秒）。
+  pub peer_read_write_timeout_secs: u64,
+  /// 向 peer 发送 keep-alive 的间隔（秒）。
+  pub peer_keep_alive_interval_secs: u64,
+  /// 写盘前最多缓存的分块数，0 表示收到后立即写盘。
+  pub defer_writes_up_to: usize,
+  /// 下载会话使用的 SOCKS5 代理，例如 socks5://127.0.0.1:1080（可以带账号密码）。
+  pub proxy_url: Option<String>,
+  /// 未填写 proxy_url 时是否复用应用的代理设置（AppSettings.proxy_url，需为 SOCKS5）。
+  pub use_app_proxy: bool,
+}
+
+impl Default for TorrentSessionSettings {
+  fn default() -> Self {
+    Self {
+      engine: EngineKind::default(),
+      aria2_rpc_url: DEFAULT_ARIA2_RPC_URL.to_string(),
+      aria2_secret: None,
+      qbittorrent_url: DEFAULT_QBITTORRENT_URL.to_string(),
+      qbittorrent_username: None,
+      qbittorrent_password: None,
+      qbittorrent_save_path: None,
+      listen_port_start: 40000,
+      listen_port_end: 50000,
+      enable_upnp: true,
+      enable_dht: true,
+      peer_connect_timeout_secs: 5,
+      peer_read_write_timeout_secs: 15,
+      peer_keep_alive_interval_secs: 60,
+      defer_writes_up_to: 128,
+      proxy_url: None,
+      use_app_proxy: false,
+    }
+  }
+}
+
+impl TorrentSessionSettings {
+  // 修正非法值：端口范围至少包含一个端口，超时与间隔至少 1 秒，空白代理地址、aria2 密钥与
+  // qBittorrent 账号、保存目录视为未设置，aria2 与 qBittorrent 地址为空时使用默认地址；
+  // aria2 密钥与 qBittorrent 密码混淆后保存，避免设置文件里出现明文（连接时再还原）。
+  pub(crate) fn normalized(mut self) -> Self {
+    if self.listen_port_start > self.listen_port_end {
+      std::mem::swap(&mut self.listen_port_start, &mut self.listen_port_end);
+    }
+    if self.listen_port_start == self.listen_port_end {
+      self.listen_port_end = self.listen_port_start.saturating_add(1);
+    }
+    self.peer_connect_timeout_secs = self.peer_connect_timeout_secs.max(1);
+    self.peer_read_write_timeout_secs = self.peer_read_write_timeout_secs.max(1);
+    self.peer_keep_alive_interval_secs = self.peer_keep_alive_interval_secs.max(1);
+    self.proxy_url = self
+      .proxy_url
+      .map(|url| url.trim().to_string())
+      .filter(|url| !url.is_empty());
+    self.aria2_rpc_url = match self.aria2_rpc_url.trim() {
+      "" => DEFAULT_ARIA2_RPC_URL.to_string(),
+      url => url.to_string(),
+    };
+    self.aria2_secret = self
+      .aria2_secret
+      .filter(|secret| !secret.is_empty())
+      .map(obfuscate_credential_value);
+    self.qbittorrent_url = match self.qbittorrent_url.trim().trim_end_matches('/') {
+      "" => DEFAULT_QBITTORRENT_URL.to_string(),
+      url => url.to_string(),
+    };
+    self.qbittorrent_username = self
+      .qbittorrent_username
+      .map(|username| username.trim().to_string())
+      .filter(|username| !username.is_empty());
+    self.qbittorrent_password = self
+      .qbittorrent_password
+      .filter(|password| !password.is_empty())
+      .map(obfuscate_credential_value);
+    self.qbittorrent_save_path = self
+      .qbittorrent_save_path
+      .map(|path| path.trim().to_string())
+      .filter(|path| !path.is_empty());
+    self
+  }
+
+  // 下载会话实际使用的代理地址；未配置时返回 None，不是 SOCKS5 代理时返回错误。
+  // settings 为完整的应用设置（复用应用代理时需要读取其中的地址与账号密码）。
+  pub(crate) fn resolve_proxy(&self, settings: &AppSettings) -> Result<Option<String>, String> {
+    let mut url = match self.proxy_url.as_deref() {
+      Some(raw) => url::Url::parse(raw).map_err(|e| format!("下载代理地址无效: {e}"))?,
+      None if self.use_app_proxy => match settings.proxy_url_with_credentials()? {
+        Some(url) => url,
+        None => return Ok(None),
+      },
+      None => return Ok(None),
+    };
+    match url.scheme() {
+      "socks5" => {}
+      // 引擎只认 socks5，peer 地址本身就是 IP，远程解析没有区别。
+      "socks5h" => url
+        .set_scheme("socks5")
+        .map_err(|_| "下载代理地址无效".to_string())?,
+      scheme => return Err(format!("下载会话只支持 SOCKS5 代理（peer 连接无法通过 {scheme} 代理）")),
+    }
+    Ok(Some(url.to_string()))
+  }
+
+  // 写入会话选项。
+  pub(super) fn apply(&self, opts: &mut SessionOptions) {
+    opts.listen_port_range = Some(self.listen_port_start..self.listen_port_end);
+    opts.enable_upnp_port_forwarding = self.enable_upnp;
+    opts.disable_dht = !self.enable_dht;
+    opts.peer_opts = Some(PeerConnectionOptions {
+      connect_timeout: Some(Duration::from_secs(self.peer_connect_timeout_secs)),
+      read_write_timeout: Some(Duration::from_secs(self.peer_read_write_timeout_secs)),
+      keep_alive_interval: Some(Duration::from_secs(self.peer_keep_alive_interval_secs)),
+    });
+    opts.defer_writes_up_to = (self.defer_writes_up_to > 0).then_some(self.defer_writes_up_to);
+  }
+}
+
+// 返回当前的下载会话设置。
+#[tauri::command]
+pub fn get_torrent_settings(app: tauri::AppHandle) -> Result<TorrentSessionSettings, AppError> {
+  Ok(load_settings(&app).map_err(AppError::io)?.torrent_session)
+}
+
+// 保存下载会话设置并重建会话（现有任务会重新加入，任务 ID 会变化），返回修正后的设置。
+// 选择 aria2 或 qBittorrent 时先确认能连上，避免停掉当前会话后无法创建新会话。
+#[tauri::command]
+pub async fn set_torrent_settings(
+  app: tauri::AppHandle,
+  settings: TorrentSessionSettings,
+) -> Result<TorrentSessionSettings, AppError> {
+  check_engine(&settings.clone().normalized()).await?;
+  let mut app_settings = load_settings(&app).map_err(AppError::io)?;
+  app_settings.torrent_session = settings;
+  let saved = save_settings(&app, app_settings).map_err(AppError::invalid_input)?.torrent_session;
+  rebuild_session(&app).await?;
+  Ok(saved)
+}
@@ -0,0 +1,214 @@
+/*
+  边下边看：本地 HTTP 流媒体服务。
+  - 第一次请求播放地址时在 127.0.0.1 的随机端口启动一个小型 HTTP 服务；
+  - 播放地址形如 http://127.0.0.1:<端口>/stream/<令牌>/<info hash>/<文件序号>，
+    令牌每次启动应用随机生成，避免本机其它程序随意读取下载内容；
+  - 数据来自引擎的流式读取接口：播放器请求到尚未下载的位置时，引擎会优先下载该位置附近的分块，
+    请求会等到数据下载完成后再返回；
+  - 支持 Range 请求（拖动进度条），外部播放器（mpv / VLC / PotPlayer）可以直接打开。
+  任务以 info hash 定位，会话重建导致任务 ID 变化后地址仍然有效。
+  依赖 librqbit 的流式读取接口，使用 aria2 或 qBittorrent 引擎时不可用。
+*/
+
+use axum::body::Body;
+use axum::extract::Path;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::net::TcpListener;
+use tokio::sync::OnceCell as AsyncOnceCell;
+use tokio_util::io::ReaderStream;
+
+use librqbit::api::TorrentIdOrHash;
+
+use super::{current_engine, librqbit_api, torrent_engine};
+use crate::services::diagnostics::app_log;
+use crate::services::error::AppError;
+
+// 本地流媒体服务的地址信息。
+struct StreamServer {
+  port: u16,
+  token: String,
+}
+
+// 本地流媒体服务（首次使用时启动，之后复用）。
+static SERVER: AsyncOnceCell<StreamServer> = AsyncOnceCell::const_new();
+
+// 生成随机令牌：从系统随机数源取 128 位，转成 32 位十六进制。
+fn random_token() -> Result<String, AppError> {
+  let mut bytes = [0u8; 16];
+  getrandom::getrandom(&mut bytes).map_err(|e| AppError::torrent(format!("生成流媒体令牌失败: {e}")))?;
+  Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+// 比较令牌：不在第一个不同的字符处提前返回，避免通过响应时间逐位猜出令牌。
+fn token_matches(given: &str, expected: &str) -> bool {
+  let (given, expected) = (given.as_bytes(), expected.as_bytes());
+  given.len() == expected.len()
+    && given
+      .iter()
+      .zip(expected)
+      .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+      == 0
+}
+
+// 启动本地流媒体服务（已启动时直接返回）。
+async fn stream_server() -> Result<&'static StreamServer, AppError> {
+  SERVER
+    .get_or_try_init(|| async {
+      let token = random_token()?;
+      let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| AppError::io(format!("启动本地播放服务失败: {e}")))?;
+      let port = listener
+        .local_addr()
+        .map_err(|e| AppError::io(format!("启动本地播放服务失败: {e}")))?
+        .port();
+      let router = Router::new().route("/stream/:token/:info_hash/:file_index", get(serve_file));
+      tauri::async_runtime::spawn(async move {
+        if let Err(err) = axum::serve(listener, router).await {
+          app_log!("本地播放服务已停止: {err}");
+        }
+      });
+      Ok(StreamServer {
+        port,
+        token,
+      })
+    })
+    .await
+}
+
+// 按扩展名猜测媒体类型，播放器据此选择解码方式。
+fn content_type(name: &str) -> &'static str {
+  let extension = name.rsplit('.').next().unwrap_or_default().to_ascii_lowercase();
+  match extension.as_str() {
+    "mkv" => "video/x-matroska",
+    "mp4" | "m4v" => "video/mp4",
+    "webm" => "video/webm",
+    "avi" => "video/x-msvideo",
+    "ts" | "m2ts" => "video/mp2t",
+    "flac" => "audio/flac",
+    "mka" => "audio/x-matroska",
+    "mp3" => "audio/mpeg",
+    "m4a" => "audio/mp4",
+    _ => "application/octet-stream",
+  }
+}
+
+// 解析 Range 请求头，返回闭区间 [start, end]；不是单个 bytes 区间时返回 None（按整个文件返回）。
+// 区间超出文件范围时返回 Err，对应 416。
+fn parse_range(value: &str, len: u64) -> Option<Result<(u64, u64), ()>> {
+  let spec = value.trim().strip_prefix("bytes=")?;
+  if spec.contains(',') {
+    return None;
+  }
+  let (start, end) = spec.split_once('-')?;
+  let range = match (start.trim(), end.trim()) {
+    // bytes=-500：最后 500 字节。
+    ("", suffix) => {
+      let suffix: u64 = suffix.parse().ok()?;
+      if suffix == 0 || len == 0 {
+        return Some(Err(()));
+      }
+      (len.saturating_sub(suffix), len - 1)
+    }
+    (start, "") => (start.parse().ok()?, len.saturating_sub(1)),
+    (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(len.saturating_sub(1))),
+  };
+  if range.0 >= len || range.0 > range.1 {
+    return Some(Err(()));
+  }
+  Some(Ok(range))
+}
+
+// 处理播放请求：校验令牌后读取对应文件，支持 Range。
+async fn serve_file(
+  Path((token, info_hash, file_index)): Path<(String, String, usize)>,
+  headers: HeaderMap,
+) -> Result<Response, (StatusCode, String)> {
+  let not_found = || (StatusCode::NOT_FOUND, "未找到文件".to_string());
+  let server = SERVER.get().ok_or_else(not_found)?;
+  if !token_matches(&token, &server.token) {
+    return Err(not_found());
+  }
+  let engine = current_engine().await.ok_or_else(not_found)?;
+  let api = engine.librqbit().ok_or_else(not_found)?;
+  let id = api
+    .api_torrent_list()
+    .torrents
+    .into_iter()
+    .find(|torrent| torrent.info_hash.eq_ignore_ascii_case(&info_hash))
+    .and_then(|torrent| torrent.id)
+    .ok_or_else(not_found)?;
+  let name = api
+    .api_torrent_details(TorrentIdOrHash::Id(id))
+    .ok()
+    .and_then(|details| details.files)
+    .and_then(|files| files.into_iter().nth(file_index))
+    .map(|file| file.name)
+    .ok_or_else(not_found)?;
+
+  let mut stream = api
+    .api_stream(TorrentIdOrHash::Id(id), file_index)
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("打开文件失败: {e}")))?;
+  let len = stream.len();
+  let range = headers
+    .get(header::RANGE)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| parse_range(value, len));
+
+  let (status, start, end) = match range {
+    Some(Ok((start, end))) => (StatusCode::PARTIAL_CONTENT, start, end),
+    Some(Err(())) => {
+      let mut response = StatusCode::RANGE_NOT_SATISFIABLE.into_response();
+      if let Ok(value) = HeaderValue::from_str(&format!("bytes */{len}")) {
+        response.headers_mut().insert(header::CONTENT_RANGE, value);
+      }
+      return Ok(response);
+    }
+    None => (StatusCode::OK, 0, len.saturating_sub(1)),
+  };
+  let body_len = if len == 0 { 0 } else { end - start + 1 };
+  stream
+    .seek(std::io::SeekFrom::Start(start))
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("读取文件失败: {e}")))?;
+
+  let mut response = Response::new(Body::from_stream(ReaderStream::new(stream.take(body_len))));
+  *response.status_mut() = status;
+  let headers = response.headers_mut();
+  headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type(&name)));
+  headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+  headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body_len));
+  if status == StatusCode::PARTIAL_CONTENT {
+    if let Ok(value) = HeaderValue::from_str(&format!("bytes {start}-{end}/{len}")) {
+      headers.insert(header::CONTENT_RANGE, value);
+    }
+  }
+  Ok(response)
+}
+
+// 返回任务中某个文件的本地播放地址（外部播放器可直接打开，支持拖动进度）。
+#[tauri::command]
+pub async fn get_stream_url(app: tauri::AppHandle, id: usize, file_index: usize) -> Result<String, AppError> {
+  let engine = torrent_engine(&app).await?;
+  let details = librqbit_api(&engine)?
+    .api_torrent_details(TorrentIdOrHash::Id(id))
+    .map_err(|e| AppError::torrent(format!("获取下载任务失败: {e}")))?;
+  let files = details
+    .files
+    .ok_or_else(|| AppError::torrent("种子元数据尚未解析完成，请稍后再试"))?;
+  let file = files
+    .get(file_index)
+    .ok_or_else(|| AppError::invalid_input(format!("文件序号无效: {file_index}")))?;
+  if !file.included {
+    return Err(AppError::invalid_input(format!("文件未选择下载: {}", file.name)));
+  }
+  let server = stream_server().await?;
+  Ok(format!(
+    "http://127.0.0.1:{}/stream/{}/{}/{}",
+    server.port, server.token, details.info_hash, file_index
+  ))
+}
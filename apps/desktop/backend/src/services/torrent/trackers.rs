@@ -0,0 +1,173 @@
+/*
+  Tracker 管理：
+  - 默认 tracker 列表保存在设置里（AppSettings.trackers），可以随时修改，
+    新加入的任务都会带上当前列表；
+  - 可选配置一个公共 tracker 列表地址（例如 ngosang/trackerslist），
+    由定时任务（TaskKind::TrackerListRefresh）定期拉取，结果与默认列表合并使用；
+  - 已在下载的任务可以追加 tracker。引擎不支持修改运行中任务的 tracker，
+    所以追加时会移除任务（保留文件）并带上新列表重新加入，任务 ID 会变化。
+*/
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::{readd_torrent, torrent_engine};
+use crate::services::bangumi::shared_client;
+use crate::services::error::AppError;
+use crate::services::limits::send_limited;
+use crate::services::settings::{load_settings, save_settings};
+
+// 内置的默认 tracker（仍在运行的公共 tracker）。
+const DEFAULT_TRACKERS: &[&str] = &[
+  "udp://tracker.opentrackr.org:1337/announce",
+  "udp://open.stealth.si:80/announce",
+  "udp://tracker.torrent.eu.org:451/announce",
+  "udp://exodus.desync.com:6969/announce",
+  "udp://open.demonii.com:1337/announce",
+  "https://tracker2.ctix.cn/announce",
+];
+
+/// Tracker 设置（保存在设置里）。
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TrackerSettings {
+  /// 默认 tracker 列表，新任务都会使用。
+  pub default_trackers: Vec<String>,
+  /// 公共 tracker 列表地址（纯文本，每行一个），为空表示不自动拉取。
+  pub list_url: Option<String>,
+  /// 上次从 list_url 拉取到的 tracker（由定时任务更新）。
+  pub fetched_trackers: Vec<String>,
+}
+
+impl Default for TrackerSettings {
+  fn default() -> Self {
+    Self {
+      default_trackers: DEFAULT_TRACKERS.iter().map(|url| url.to_string()).collect(),
+      list_url: None,
+      fetched_trackers: Vec::new(),
+    }
+  }
+}
+
+impl TrackerSettings {
+  // 修正非法值：去掉无法解析或协议不支持的地址，并去重。
+  pub(crate) fn normalized(mut self) -> Self {
+    self.default_trackers = clean_trackers(self.default_trackers);
+    self.fetched_trackers = clean_trackers(self.fetched_trackers);
+    self.list_url = self
+      .list_url
+      .map(|url| url.trim().to_string())
+      .filter(|url| !url.is_empty());
+    self
+  }
+
+  // 实际使用的 tracker：默认列表在前，拉取到的在后。
+  pub(crate) fn all(&self) -> Vec<String> {
+    clean_trackers(
+      self
+        .default_trackers
+        .iter()
+        .chain(self.fetched_trackers.iter())
+        .cloned()
+        .collect(),
+    )
+  }
+}
+
+// 解析 tracker 地址，只接受 udp / http / https / ws / wss。
+fn parse_tracker(raw: &str) -> Option<url::Url> {
+  let url = url::Url::parse(raw.trim()).ok()?;
+  matches!(url.scheme(), "udp" | "http" | "https" | "ws" | "wss").then_some(url)
+}
+
+// 过滤无效地址并按出现顺序去重。
+pub(super) fn clean_trackers(trackers: Vec<String>) -> Vec<String> {
+  let mut seen = HashSet::new();
+  trackers
+    .iter()
+    .filter_map(|raw| parse_tracker(raw))
+    .map(|url| url.to_string())
+    .filter(|url| seen.insert(url.clone()))
+    .collect()
+}
+
+// 当前设置里的 tracker（初始化会话与添加任务时使用）。
+pub(super) fn configured_trackers(app: &tauri::AppHandle) -> Vec<String> {
+  load_settings(app).unwrap_or_default().trackers.all()
+}
+
+// 同上，转换成会话选项需要的集合。
+pub(super) fn configured_tracker_set(app: &tauri::AppHandle) -> HashSet<url::Url> {
+  configured_trackers(app)
+    .iter()
+    .filter_map(|raw| parse_tracker(raw))
+    .collect()
+}
+
+// 定时任务：从 list_url 拉取公共 tracker 列表并写回设置；未配置地址时什么都不做。
+pub(crate) async fn refresh_tracker_list(app: &tauri::AppHandle) -> Result<(), AppError> {
+  let settings = load_settings(app).map_err(AppError::io)?;
+  let Some(list_url) = settings.trackers.list_url.clone() else {
+    return Ok(());
+  };
+  let response = send_limited(shared_client().get(&list_url))
+    .await
+    .map_err(|e| AppError::network(format!("拉取 tracker 列表失败: {e}")))?;
+  let status = response.status();
+  if !status.is_success() {
+    return Err(AppError::api(status.as_u16(), format!("拉取 tracker 列表失败: {status}")));
+  }
+  let body = response
+    .text()
+    .await
+    .map_err(|e| AppError::network(format!("拉取 tracker 列表失败: {e}")))?;
+  let fetched = clean_trackers(body.lines().map(|line| line.to_string()).collect());
+  if fetched.is_empty() {
+    return Err(AppError::parse("tracker 列表为空或格式无法识别"));
+  }
+
+  // 请求期间设置可能被修改，重新读取后只替换拉取结果。
+  let mut settings = load_settings(app).map_err(AppError::io)?;
+  settings.trackers.fetched_trackers = fetched;
+  save_settings(app, settings).map_err(AppError::io)?;
+  Ok(())
+}
+
+/// 追加 tracker 后的结果。
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TorrentTrackersResponse {
+  /// 重新加入会话后的任务 ID（与原 ID 不同）。
+  pub id: usize,
+  pub trackers: Vec<String>,
+}
+
+// 返回任务当前使用的 tracker。
+#[tauri::command]
+pub async fn get_trackers(app: tauri::AppHandle, id: usize) -> Result<Vec<String>, AppError> {
+  let engine = torrent_engine(&app).await?;
+  engine
+    .trackers(id)
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载任务失败: {e}")))
+}
+
+// 给任务追加 tracker（移除后带上新列表重新加入，文件保留，任务 ID 会变化）。
+#[tauri::command]
+pub async fn add_trackers(
+  app: tauri::AppHandle,
+  id: usize,
+  urls: Vec<String>,
+) -> Result<TorrentTrackersResponse, AppError> {
+  let added = clean_trackers(urls);
+  if added.is_empty() {
+    return Err(AppError::invalid_input("没有有效的 tracker 地址"));
+  }
+  let engine = torrent_engine(&app).await?;
+  let new_id = readd_torrent(&engine, id, added).await?;
+  let trackers = engine
+    .trackers(new_id)
+    .await
+    .map_err(|e| AppError::torrent(format!("获取下载任务失败: {e}")))?;
+  Ok(TorrentTrackersResponse { id: new_id, trackers })
+}
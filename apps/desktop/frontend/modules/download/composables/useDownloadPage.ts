@@ -2,25 +2,37 @@
   下载页面的组合式逻辑：
   - 维护下载列表状态
   - 调用后端下载/暂停/恢复/删除接口
-  - 定时轮询下载状态并更新 UI
+  - 订阅后端的下载进度事件并更新 UI
+  - 接收系统转交的磁力链接并打开下载流程
 */
 import { computed, onBeforeUnmount, onMounted, ref } from "vue";
 import { invoke } from "@tauri-apps/api/core";
-import { open as openDialog } from "@tauri-apps/plugin-dialog";
-import type { DownloadItem } from "../types/download";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  DownloadItem,
+  MagnetInfo,
+  RssDownloadedEvent,
+  TorrentProgressEvent,
+  TorrentStatus,
+} from "../types/download";
 import { formatBytes, formatSpeed, parseSpeedToBps } from "../../../shared/utils/format";
 import type { SearchResult } from "../../search/types/search";
 
 /**
  * 下载页业务入口：返回给组件使用的状态与操作函数。
+ * showDownloadPage 用于收到磁力链接时切换到下载页。
  */
-export const useDownloadPage = () => {
+export const useDownloadPage = (options: { showDownloadPage: () => void }) => {
   // 所有下载任务的响应式数组（会驱动 UI 列表渲染）。
   const downloads = ref<DownloadItem[]>([]);
   // 本地自增 ID，用于在前端区分条目。
   let downloadSeq = 1;
-  // 轮询定时器句柄，避免重复创建。
-  let downloadPoller: number | null = null;
+  // 进度事件的取消监听函数，避免重复订阅。
+  let unlistenProgress: UnlistenFn | null = null;
+  // 磁力链接事件的取消监听函数。
+  let unlistenMagnet: UnlistenFn | null = null;
+  // RSS 自动下载事件的取消监听函数。
+  let unlistenRss: UnlistenFn | null = null;
 
   // 新增一个下载记录（立即显示在列表顶端）。
   const addDownload = (
@@ -75,7 +87,7 @@ export const useDownloadPage = () => {
     return item.status === "paused" || state.includes("paused") || state.includes("stopped");
   };
 
-  // 终态：已完成或失败（终态不再轮询/操作）。
+  // 终态：已完成或失败（终态不再操作）。
   const isDownloadTerminal = (item: DownloadItem) => item.status === "completed" || item.status === "failed";
 
   // 暂停单个下载（需要有 torrentId 且不是终态）。
@@ -104,7 +116,7 @@ export const useDownloadPage = () => {
   const handleDeleteDownload = async (item: DownloadItem) => {
     if (item.torrentId != null) {
       try {
-        await invoke("delete_torrent", { id: item.torrentId });
+        await invoke("delete_torrent", { id: item.torrentId, deleteFiles: false });
       } catch (err) {
         console.error("delete_torrent failed", err);
         return;
@@ -140,40 +152,14 @@ export const useDownloadPage = () => {
     await Promise.all(paused.map((item) => handleResumeDownload(item)));
   };
 
-  // 轮询后端状态并更新本地列表。
-  // 说明：这里会并发请求所有活动任务的状态。
-  const refreshDownloadStatuses = async () => {
-    const active = downloads.value.filter((item) => item.torrentId != null);
-    if (!active.length) return;
-
-    // 并发拉取每个任务的状态。
-    const updates = await Promise.all(
-      active.map(async (item) => {
-        try {
-          const stats = await invoke<{
-            id: number;
-            state: string;
-            progressBytes: number;
-            totalBytes: number;
-            uploadedBytes: number;
-            finished: boolean;
-            error?: string | null;
-            downloadSpeed?: string | null;
-            uploadSpeed?: string | null;
-            timeRemaining?: string | null;
-          }>("get_torrent_status", { id: item.torrentId });
-          return { id: item.id, stats };
-        } catch (err) {
-          console.error("get_torrent_status failed", err);
-          return { id: item.id, stats: null };
-        }
-      })
-    );
+  // 把后端推送的状态合并进本地列表（只处理有变化的任务）。
+  const applyStatusUpdates = async (updates: TorrentStatus[]) => {
+    if (!updates.length) return;
 
     // 是否需要触发“完成后移动文件”的收尾动作。
     let shouldFinalize = false;
     const updated = downloads.value.map((item) => {
-      const update = updates.find((u) => u.id === item.id)?.stats;
+      const update = item.torrentId == null ? undefined : updates.find((u) => u.id === item.torrentId);
       if (!update) return item;
       const completed = update.finished;
       const failed = Boolean(update.error);
@@ -208,6 +194,8 @@ export const useDownloadPage = () => {
             await invoke("finalize_torrent_download", {
               tempFolder: item.tempPath,
               finalFolder: item.finalPath,
+              id: item.torrentId,
+              subjectId: item.subjectId,
             });
             item.finalized = true;
           } catch (err) {
@@ -227,16 +215,17 @@ export const useDownloadPage = () => {
 
     let path: string | undefined;
     try {
-      // 弹出系统目录选择框。
-      const result = await openDialog({
-        title: kind === "magnet" ? "选择磁链下载目录" : "选择种子保存目录",
-        directory: true,
-        multiple: false,
+      // 通过后端弹出系统目录选择框（后端会把所选目录加入允许访问范围）。
+      const result = await invoke<string | null>("pick_path", {
+        options: {
+          title: kind === "magnet" ? "选择磁链下载目录" : "选择种子保存目录",
+          directory: true,
+        },
       });
-      if (!result || Array.isArray(result)) return;
-      path = String(result);
+      if (!result) return;
+      path = result;
     } catch (err) {
-      console.error("openDialog failed", err);
+      console.error("pick_path failed", err);
       return;
     }
 
@@ -261,18 +250,69 @@ export const useDownloadPage = () => {
     }
   };
 
-  // 组件挂载时开启定时轮询。
-  onMounted(() => {
-    if (downloadPoller == null) {
-      downloadPoller = window.setInterval(refreshDownloadStatuses, 1500);
+  // 取走后端队列中的磁力链接（点击系统中的 magnet: 链接时进入队列），
+  // 切到下载页后逐个走正常下载流程，标题预填为链接里的名称。
+  const processPendingMagnets = async () => {
+    let pending: MagnetInfo[];
+    try {
+      pending = await invoke<MagnetInfo[]>("take_pending_magnets");
+    } catch (err) {
+      console.error("take_pending_magnets failed", err);
+      return;
+    }
+    if (!pending.length) return;
+    options.showDownloadPage();
+    for (const magnet of pending) {
+      await handleDownloadClick({ title: magnet.name || magnet.infoHash, magnet: magnet.uri }, "magnet", magnet.uri);
+    }
+  };
+
+  // 组件挂载时订阅进度事件（先监听再订阅，避免漏掉第一次全量推送）。
+  onMounted(async () => {
+    if (unlistenProgress != null) return;
+    unlistenProgress = await listen<TorrentProgressEvent>("torrent://progress", (event) => {
+      void applyStatusUpdates(event.payload.updated);
+    });
+    try {
+      await invoke("subscribe_torrent_events");
+    } catch (err) {
+      console.error("subscribe_torrent_events failed", err);
     }
+    // 先监听再取队列：启动前到达的链接已在队列里，之后到达的会触发事件。
+    unlistenMagnet = await listen<MagnetInfo>("magnet-link", () => {
+      void processPendingMagnets();
+    });
+    await processPendingMagnets();
+    // RSS 订阅在后台开始的下载同样加入列表，完成后走相同的收尾流程。
+    unlistenRss = await listen<RssDownloadedEvent>("rss://downloaded", (event) => {
+      const started = event.payload;
+      const kind = started.url.startsWith("magnet:") ? "magnet" : "torrent";
+      addDownload(started.title, started.url, kind, started.finalFolder, {
+        torrentId: started.torrentId,
+        infoHash: started.infoHash,
+        tempPath: started.outputFolder,
+        finalPath: started.finalFolder,
+        subjectId: started.subjectId ?? undefined,
+      });
+    });
   });
 
-  // 组件卸载时清理定时器，避免内存泄漏。
+  // 组件卸载时取消监听与订阅，后端没有订阅时会停止采样。
   onBeforeUnmount(() => {
-    if (downloadPoller != null) {
-      window.clearInterval(downloadPoller);
-      downloadPoller = null;
+    if (unlistenProgress != null) {
+      unlistenProgress();
+      unlistenProgress = null;
+      invoke("unsubscribe_torrent_events").catch((err) => {
+        console.error("unsubscribe_torrent_events failed", err);
+      });
+    }
+    if (unlistenMagnet != null) {
+      unlistenMagnet();
+      unlistenMagnet = null;
+    }
+    if (unlistenRss != null) {
+      unlistenRss();
+      unlistenRss = null;
     }
   });
 
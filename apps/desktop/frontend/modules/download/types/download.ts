@@ -1,6 +1,6 @@
 /**
  * 下载任务在前端的完整状态结构。
- * 用于列表渲染与状态轮询。
+ * 用于列表渲染与进度更新。
  */
 export type DownloadItem = {
   // 前端自增 ID（仅用于 UI 标识）。
@@ -27,6 +27,8 @@ export type DownloadItem = {
   torrentId?: number;
   // 种子 infoHash。
   infoHash?: string;
+  // 关联的 Bangumi 条目 ID（RSS 订阅自动下载时带上，写入下载历史）。
+  subjectId?: number;
   // 已下载字节数。
   progressBytes?: number;
   // 总字节数。
@@ -44,3 +46,66 @@ export type DownloadItem = {
   // 剩余时间描述（字符串）。
   timeRemaining?: string;
 };
+
+/**
+ * 后端返回的单个下载任务状态（get_torrent_status / torrent://progress）。
+ */
+export type TorrentStatus = {
+  id: number;
+  state: string;
+  progressBytes: number;
+  totalBytes: number;
+  uploadedBytes: number;
+  finished: boolean;
+  error?: string | null;
+  downloadSpeed?: string | null;
+  uploadSpeed?: string | null;
+  timeRemaining?: string | null;
+  queuePosition?: number | null;
+  files: TorrentFileStatus[];
+  sequential: boolean;
+  // 从开头起连续下载完成的字节数。
+  contiguousBytes: number;
+};
+
+/**
+ * 单个文件的下载进度（priority 为 skip 表示未选择下载）。
+ */
+export type TorrentFileStatus = {
+  index: number;
+  name: string;
+  size: number;
+  downloadedBytes: number;
+  priority: "normal" | "skip";
+};
+
+/**
+ * torrent://progress 事件内容：相对上一次推送有变化的任务与已移除的任务 ID。
+ */
+export type TorrentProgressEvent = {
+  updated: TorrentStatus[];
+  removed: number[];
+};
+
+/**
+ * 从磁力链接中解析出的元数据（take_pending_magnets / magnet-link 事件）。
+ */
+export type MagnetInfo = {
+  uri: string;
+  infoHash: string;
+  name?: string | null;
+  size?: number | null;
+  trackers: string[];
+};
+
+// RSS 订阅自动开始下载时后端发送的事件（rss://downloaded）。
+export type RssDownloadedEvent = {
+  subscriptionId: number;
+  subjectId?: number | null;
+  title: string;
+  url: string;
+  torrentId: number;
+  infoHash: string;
+  outputFolder: string;
+  finalFolder: string;
+};
@@ -9,6 +9,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { NTag } from "naive-ui";
 import type { MonthAnime, SeasonResponse, StaffGroup, CharacterLink } from "../../tracking/types/anime";
 import type { ItemStatus } from "../../tracking/types/tracking";
+import { formatError } from "../../../shared/utils/error";
 
 // 当前月份（1-12），用于默认季节判断。
 const currentMonth = new Date().getMonth() + 1;
@@ -470,7 +471,8 @@ export const useQueryPage = (options: { ensureStatus: (id: number) => ItemStatus
     if (!payload || !Array.isArray(payload.months)) {
       throw new Error("季度数据格式不正确");
     }
-    dataCache.set(cacheKey, payload);
+    // 离线快照与部分失败的结果不放进内存缓存，网络恢复后再次查询即可拿到完整数据。
+    if (!payload.stale && !payload.errors?.length) dataCache.set(cacheKey, payload);
     return payload;
   };
 
@@ -588,7 +590,7 @@ export const useQueryPage = (options: { ensureStatus: (id: number) => ItemStatus
       }
     } catch (error) {
       if (token === queryToken.value) {
-        errorMessage.value = String(error);
+        errorMessage.value = formatError(error);
       }
     } finally {
       if (token === queryToken.value) {
@@ -678,7 +680,7 @@ export const useQueryPage = (options: { ensureStatus: (id: number) => ItemStatus
       item.origin = payload.origin ?? "";
     } catch (error) {
       // 出错时写入错误提示，同时给空值避免 UI 显示 undefined。
-      originError.value = String(error);
+      originError.value = formatError(error);
       item.origin = "";
     } finally {
       if (originLoadingId.value === item.id) {
@@ -705,7 +707,7 @@ export const useQueryPage = (options: { ensureStatus: (id: number) => ItemStatus
       item.totalCount = payload.totalCount ?? 0;
     } catch (error) {
       // 失败时也保证字段有默认值，避免 UI 读取失败。
-      airedError.value = String(error);
+      airedError.value = formatError(error);
       item.airedCount = 0;
       item.totalCount = 0;
     } finally {
@@ -729,7 +731,7 @@ export const useQueryPage = (options: { ensureStatus: (id: number) => ItemStatus
       staffCache.value = { ...staffCache.value, [item.id]: payload.groups || [] };
     } catch (error) {
       // 失败时也写入空数组，避免 UI 反复触发加载。
-      staffError.value = String(error);
+      staffError.value = formatError(error);
       staffCache.value = { ...staffCache.value, [item.id]: [] };
     } finally {
       if (staffLoadingId.value === item.id) {
@@ -753,7 +755,7 @@ export const useQueryPage = (options: { ensureStatus: (id: number) => ItemStatus
       characterCache.value = { ...characterCache.value, [item.id]: payload.characters || [] };
     } catch (error) {
       // 失败时写入空数组，避免重复请求。
-      characterError.value = String(error);
+      characterError.value = formatError(error);
       characterCache.value = { ...characterCache.value, [item.id]: [] };
     } finally {
       if (characterLoadingId.value === item.id) {
@@ -794,7 +796,7 @@ export const useQueryPage = (options: { ensureStatus: (id: number) => ItemStatus
       }
     } catch (error) {
       // 兜底：失败时提供空值并标记失败。
-      summaryError.value = String(error);
+      summaryError.value = formatError(error);
       item.summaryCn = "";
       item.summaryTranslated = false;
       item.summaryTranslateFailed = true;
@@ -1,17 +1,22 @@
 /*
   搜索页面组合式逻辑：
   - 维护搜索关键字与逻辑条件
-  - 获取别名并构造搜索 URL
-  - 解析搜索站点返回的 HTML
+  - 获取别名并构造搜索语句
+  - 调用后端在各资源站搜索（解析由后端完成）
 */
 import { computed, ref } from "vue";
 import { invoke } from "@tauri-apps/api/core";
 import type { Ref } from "vue";
-import type { SearchResult, SearchTerm, LogicOp } from "../types/search";
+import type { ReleaseInfo, SearchResult, SearchSource, SearchTerm, LogicOp, TorrentSearchResponse } from "../types/search";
 import type { TrackedItem } from "../../tracking/types/tracking";
+import { formatError } from "../../../shared/utils/error";
 
-// Nyaa 搜索站点基础地址。
-const NYAA_BASE = "https://nyaa.vaciller.top/?f=0&c=0_0&q=";
+// 可选的搜索站点。
+const sourceOptions: { label: string; value: SearchSource }[] = [
+  { label: "Nyaa", value: "nyaa" },
+  { label: "動漫花園", value: "dmhy" },
+  { label: "ACG.RIP", value: "acg-rip" },
+];
 // 预设常用关键词（便于一键添加）。
 const presetPhrases = ["SubsPlease", "LoliHouse", "内封", "外挂", "480", "720", "1080"];
 // 逻辑运算选项：与/或/非。
@@ -36,8 +41,16 @@ export const useSearchPage = (options: { trackedItems: Ref<TrackedItem[]> }) =>
   // 搜索请求状态与错误。
   const searchLoading = ref(false);
   const searchError = ref("");
-  const searchHtml = ref("");
   const searchResults = ref<SearchResult[]>([]);
+  // 选中的搜索站点（默认全部）。
+  const selectedSources = ref<SearchSource[]>(sourceOptions.map((option) => option.value));
+  // 部分站点失败时的提示（其它站点的结果照常显示）。
+  const sourceErrors = ref<string[]>([]);
+  // 当前已加载的页码，以及上一页是否还有结果（用于“加载更多”）。
+  const searchPage = ref(1);
+  const hasMore = ref(false);
+  // 是否已发起过搜索（用于显示“没有找到”的提示）。
+  const searched = ref(false);
   // 别名选择弹窗相关状态。
   const aliasModalVisible = ref(false);
   const aliasLoading = ref(false);
@@ -171,87 +184,82 @@ export const useSearchPage = (options: { trackedItems: Ref<TrackedItem[]> }) =>
     return parts.filter(Boolean);
   });
 
-  // 完整查询字符串与最终 URL。
+  // 完整查询字符串。
   const searchQuery = computed(() => queryParts.value.join(" "));
-  const searchUrl = computed(() => `${NYAA_BASE}${encodeURIComponent(searchQuery.value)}`);
 
-  // 注入 <base> 标签，保证页面内相对链接可用。
-  const injectBaseTag = (html: string, url: string) => {
-    const baseHref = url.split("?")[0];
-    const baseTag = `<base href="${baseHref}" />`;
-    const headMatch = html.match(/<head[^>]*>/i);
-    if (headMatch && headMatch.index !== undefined) {
-      const idx = headMatch.index + headMatch[0].length;
-      return `${html.slice(0, idx)}${baseTag}${html.slice(idx)}`;
-    }
-    return `<head>${baseTag}</head>${html}`;
-  };
+  // 站点错误转成可读文本。
+  const formatSourceErrors = (errors: TorrentSearchResponse["errors"]) =>
+    errors.map((err) => {
+      const label = sourceOptions.find((option) => option.value === err.source)?.label ?? err.source;
+      return `${label}：${err.message}`;
+    });
 
-  // 解析 HTML 表格为结构化搜索结果。
-  const parseSearchResults = (html: string): SearchResult[] => {
+  // 请求某一页的搜索结果；第 1 页替换列表，之后的页追加到列表末尾。
+  const fetchPage = async (page: number) => {
+    searchLoading.value = true;
+    searchError.value = "";
     try {
-      const parser = new DOMParser();
-      const doc = parser.parseFromString(html, "text/html");
-      const rows = Array.from(doc.querySelectorAll("table tbody tr"));
-      const toAbs = (href?: string | null) => {
-        if (!href) return undefined;
-        try {
-          return new URL(href, searchUrl.value).toString();
-        } catch (_) {
-          return href || undefined;
-        }
-      };
+      const response = await invoke<TorrentSearchResponse>("search_torrents", {
+        query: searchQuery.value,
+        sources: selectedSources.value,
+        page,
+      });
+      searchResults.value = page === 1 ? response.results : [...searchResults.value, ...response.results];
+      sourceErrors.value = formatSourceErrors(response.errors);
+      searchPage.value = page;
+      hasMore.value = response.results.length > 0;
+    } catch (err: any) {
+      searchError.value = formatError(err, "获取搜索结果失败");
+    } finally {
+      searchLoading.value = false;
+    }
+  };
 
-      return rows
-        .map((tr) => {
-          const nameCell = tr.querySelector("td:nth-child(2)");
-          const anchorCandidates = nameCell ? Array.from(nameCell.querySelectorAll("a")) : [];
-          const nameLink = anchorCandidates.filter((a) => a.getAttribute("href")?.includes("/view/")).pop();
-          if (!nameLink) return null;
-          const title = nameLink.textContent?.trim() || "";
-          const detailUrl = toAbs(nameLink.getAttribute("href"));
-          const magnet = toAbs(tr.querySelector("a[href^='magnet:']")?.getAttribute("href"));
-          const download = toAbs(tr.querySelector("a[href$='.torrent']")?.getAttribute("href"));
-          const size = tr.querySelector("td:nth-child(5)")?.textContent?.trim() || undefined;
-          const date = tr.querySelector("td:nth-child(6)")?.textContent?.trim() || undefined;
-          if (!title) return null;
-          return { title, detailUrl, magnet, download, size, date } as SearchResult;
-        })
-        .filter((v): v is SearchResult => Boolean(v));
-    } catch (e) {
-      console.error("parseSearchResults error", e);
-      return [];
+  // 标题解析结果的简短描述（集数、分辨率、编码），用于结果列表。
+  const formatRelease = (release?: ReleaseInfo) => {
+    if (!release) return "";
+    const pad = (value: number) => String(value).padStart(2, "0");
+    const parts: string[] = [];
+    if (release.episode != null) {
+      parts.push(release.episodeEnd != null ? `${pad(release.episode)}-${pad(release.episodeEnd)}` : `第 ${pad(release.episode)} 集`);
+    } else if (release.batch) {
+      parts.push("合集");
     }
+    if (release.resolution) parts.push(release.resolution);
+    if (release.videoCodec) parts.push(release.videoCodec);
+    return parts.join(" · ");
   };
 
   // 发起搜索请求并更新结果。
   const openSearch = async () => {
-    if (!searchQuery.value) return;
-    searchLoading.value = true;
-    searchError.value = "";
+    if (!searchQuery.value || !selectedSources.value.length) return;
     searchResults.value = [];
-    searchHtml.value = "";
-    try {
-      const html = await invoke<string>("fetch_search_html", { url: searchUrl.value });
-      searchHtml.value = injectBaseTag(html, searchUrl.value);
-      searchResults.value = parseSearchResults(html);
-    } catch (err: any) {
-      searchError.value = typeof err === "string" ? err : err?.message || "获取搜索结果失败";
-    } finally {
-      searchLoading.value = false;
-    }
+    sourceErrors.value = [];
+    hasMore.value = false;
+    searched.value = true;
+    await fetchPage(1);
+  };
+
+  // 加载下一页。
+  const loadMore = async () => {
+    if (searchLoading.value || !hasMore.value) return;
+    await fetchPage(searchPage.value + 1);
   };
 
   // 清空搜索结果（重置 UI）。
   const clearSearchResults = () => {
     searchResults.value = [];
-    searchHtml.value = "";
+    sourceErrors.value = [];
     searchError.value = "";
+    hasMore.value = false;
+    searched.value = false;
   };
 
   return {
     presetPhrases,
     logicOptions,
+    sourceOptions,
+    selectedSources,
     activeLogic,
     searchTerms,
     customSearchInput,
@@ -259,20 +267,23 @@ export const useSearchPage = (options: { trackedItems: Ref<TrackedItem[]> }) =>
     trackedOptions,
     searchLoading,
     searchError,
-    searchHtml,
     searchResults,
+    sourceErrors,
+    hasMore,
+    searched,
     aliasModalVisible,
     aliasLoading,
     aliasOptions,
     aliasSelected,
     pendingTrackedName,
     searchQuery,
-    searchUrl,
+    formatRelease,
     handleAddPreset,
     handleAddCustom,
     handleSelectTracked,
     removeSearchTerm,
     openSearch,
+    loadMore,
     clearSearchResults,
     cancelAliasSelection,
     confirmAliasSelection,
@@ -15,19 +15,69 @@ export type LogicOp = "and" | "or" | "not";
 export type SearchTerm = { value: string; op: LogicOp; source: "preset" | "custom" | "tracked" };
 
 /**
- * 搜索结果条目（从 HTML 解析而来）。
+ * 搜索站点：Nyaa / 動漫花園 / ACG.RIP。
+ */
+export type SearchSource = "nyaa" | "dmhy" | "acg-rip";
+
+/**
+ * 发布标题解析结果（后端 parse_release_title）。
+ */
+export type ReleaseInfo = {
+  // 字幕组。
+  group?: string | null;
+  // 作品名。
+  title: string;
+  season?: number | null;
+  // 集数；合集时为起始集。
+  episode?: number | null;
+  // 合集的结束集。
+  episodeEnd?: number | null;
+  // 修正版本（v2 等）。
+  version?: number | null;
+  // 分辨率（如 1080p）。
+  resolution?: string | null;
+  // 片源（WebRip / BDRip 等）。
+  source?: string | null;
+  // 视频编码（HEVC / AVC / AV1）。
+  videoCodec?: string | null;
+  // 字幕语言（chs / cht / jpn / eng）。
+  languages: string[];
+  // 是否是合集。
+  batch: boolean;
+};
+
+/**
+ * 搜索结果条目（后端 search_torrents 解析各站点页面得到）。
  */
 export type SearchResult = {
   // 标题文本。
   title: string;
   // 详情页链接。
-  detailUrl?: string;
+  detailUrl?: string | null;
   // 磁力链接。
-  magnet?: string;
+  magnet?: string | null;
   // 种子下载链接。
-  download?: string;
+  torrentUrl?: string | null;
   // 文件大小文本。
-  size?: string;
-  // 发布日期文本。
-  date?: string;
+  size?: string | null;
+  // 做种数。
+  seeders?: number | null;
+  // 发布时间文本（本地时间）。
+  pubDate?: string | null;
+  // 发布时间（Unix 时间戳，秒）。
+  publishedAt?: number | null;
+  // 字幕组。
+  group?: string | null;
+  // 来源站点。
+  source?: SearchSource;
+  // 标题解析结果（集数、分辨率等）。
+  release?: ReleaseInfo;
+};
+
+/**
+ * search_torrents 的返回值：结果与各站点的错误。
+ */
+export type TorrentSearchResponse = {
+  results: SearchResult[];
+  errors: { source: SearchSource; message: string }[];
 };
@@ -138,6 +138,18 @@ export const useTracking = () => {
     trackedItems.value = trackedItems.value.map((item) => (item.id === id ? { ...item, ...patch } : item));
   };
 
+  // 条目简要信息（get_subject_briefs 返回结构）。
+  type SubjectBrief = {
+    id: number;
+    name: string;
+    nameCn: string;
+    image: string;
+    date: string;
+    rating: number | null;
+    summary: string;
+    url: string;
+  };
+
   // 同时刷新条目数量上限，避免请求过多。
   const CONCURRENT_REFRESH_LIMIT = 6;
 
@@ -147,23 +159,26 @@ export const useTracking = () => {
     if (!queue.length) return;
     const updated: TrackedItem[] = [];
 
+    // 简要信息一次性批量获取（后端限量并发），失败的条目不会出现在 briefs 里。
+    let briefs: Record<string, SubjectBrief> = {};
+    try {
+      const result = await invoke<{ briefs: Record<string, SubjectBrief>; errors: Record<string, string> }>(
+        "get_subject_briefs",
+        { ids: queue.map((item) => item.id) }
+      );
+      briefs = result.briefs || {};
+    } catch (_) {
+      return;
+    }
+
     // worker 从队列中逐个取任务，直到队列为空。
     const worker = async () => {
       while (queue.length) {
         const item = queue.shift();
         if (!item) continue;
+        const payload = briefs[String(item.id)];
+        if (!payload) continue;
         try {
-          const payload = await invoke<{
-            id: number;
-            name: string;
-            nameCn: string;
-            image: string;
-            date: string;
-            rating: number | null;
-            summary: string;
-            url: string;
-          }>("get_subject_brief", { id: item.id });
-
           const count = await invoke<{ id: number; airedCount?: number | null; totalCount?: number | null }>(
             "get_subject_aired_count",
             { id: item.id }
@@ -38,6 +38,12 @@ export interface MonthAnime {
   summary?: string;
   // 条目链接。
   url?: string;
+  // 条目类型（1 书籍 / 2 动画 / 3 音乐 / 4 游戏 / 6 三次元）。
+  subjectType?: number;
+  // 平台（TV / 剧场版 / 漫画 / PS5 等）。
+  platform?: string | null;
+  // 卷数（书籍）。
+  volumes?: number | null;
   // 所属月份（用于筛选）。
   month?: number;
 }
@@ -85,7 +91,12 @@ export interface SeasonMonthData {
 export interface SeasonResponse {
   year: number;
   season: string;
+  subjectType?: number;
   fetchedAt: string;
   source: string;
   months: SeasonMonthData[];
+  // 拉取失败的月份（部分结果时非空）。
+  errors?: { month: number; error: string }[];
+  // 是否为离线快照（接口不可用时返回的历史数据）。
+  stale?: boolean;
 }
@@ -6,8 +6,9 @@
 */
 import { reactive, ref } from "vue";
 import { invoke } from "@tauri-apps/api/core";
-import { open as openDialog, save as saveDialog } from "@tauri-apps/plugin-dialog";
-import type { MixQueueItem, MixTrackInput, TrackFileResult, TrackInfo, TrackItem, TrackType } from "../types/tracks";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { MediaJob, MixQueueItem, MixTrackInput, TrackFileResult, TrackInfo, TrackItem, TrackType } from "../types/tracks";
+import { formatError } from "../../../shared/utils/error";
 
 // 轨道类型 -> 中文显示名称。
 const trackLabelMap: Record<TrackType, string> = {
@@ -92,15 +93,15 @@ export const useTracksPage = () => {
         audio: videoExt,
         subtitle: [...videoExt, ...subtitleExt],
       };
-      // 打开文件选择对话框。
-      const result = await openDialog({
-        title: `选择${trackLabelMap[type]}文件`,
-        directory: false,
-        multiple: false,
-        filters: [{ name: `${trackLabelMap[type]}文件`, extensions: extMap[type] }],
+      // 通过后端打开文件选择对话框（后端会把所选文件加入允许访问范围）。
+      const result = await invoke<string | null>("pick_path", {
+        options: {
+          title: `选择${trackLabelMap[type]}文件`,
+          filters: [{ name: `${trackLabelMap[type]}文件`, extensions: extMap[type] }],
+        },
       });
-      if (!result || Array.isArray(result)) return;
-      const file = String(result);
+      if (!result) return;
+      const file = result;
       // 可选读取文件大小（失败不阻塞）。
       let fileSize: string | undefined;
       try {
@@ -110,22 +111,34 @@ export const useTracksPage = () => {
         console.error("get_media_file_size failed", err);
         fileSize = undefined;
       }
-      trackFiles.value[type] = [
-        {
-          id: trackSeq++,
-          name: file.split(/[\\/]/).filter(Boolean).pop() || file,
-          path: file,
-          fileSize,
-        },
-      ];
+      const item: TrackItem = {
+        id: trackSeq++,
+        name: file.split(/[\\/]/).filter(Boolean).pop() || file,
+        path: file,
+        fileSize,
+      };
+      // 视频只用一个文件（替换）；音频、字幕可以添加多个文件，按添加顺序混流。
+      if (type === "video") {
+        trackFiles.value[type] = [item];
+      } else if (!trackFiles.value[type].some((existing) => existing.path === file)) {
+        trackFiles.value[type] = [...trackFiles.value[type], item];
+      }
       trackInfos.value[type] = [];
       trackErrors.value[type] = "";
       trackProgress.value[type] = 0;
     } catch (err) {
-      console.error("openDialog failed", err);
+      console.error("pick_path failed", err);
     }
   };
 
+  // 移除已添加的文件（需要重新检测轨道）。
+  const removeTrackFile = (type: TrackType, id: number) => {
+    trackFiles.value[type] = trackFiles.value[type].filter((file) => file.id !== id);
+    trackInfos.value[type] = [];
+    trackErrors.value[type] = "";
+    trackProgress.value[type] = 0;
+  };
+
   // 调用后端解析轨道信息。
   const detectTracks = async (type: TrackType) => {
     if (trackLoading.value[type]) return;
@@ -159,7 +172,7 @@ export const useTracksPage = () => {
         results.push({ file, tracks });
       } catch (err: any) {
         // 解析失败时写入错误提示。
-        trackErrors.value[type] = typeof err === "string" ? err : err?.message || "解析失败";
+        trackErrors.value[type] = formatError(err, "解析失败");
         results.push({ file, tracks: [] });
       } finally {
         trackProgress.value[type] = Math.round(((i + 1) / totalFiles) * 100);
@@ -174,34 +187,35 @@ export const useTracksPage = () => {
     const baseName = baseFile?.name ? baseFile.name.replace(/\.[^/.\\]+$/, "") : "mixed";
     const dir = baseFile?.path ? baseFile.path.replace(/[\\/][^\\/]+$/, "") : "";
     const defaultPath = dir ? `${dir}\\${baseName}_mixed.mkv` : `${baseName}_mixed.mkv`;
-    const result = await saveDialog({
-      title: "保存混合后的视频",
-      defaultPath,
-      filters: [{ name: "MKV", extensions: ["mkv"] }],
+    const result = await invoke<string | null>("pick_path", {
+      options: {
+        title: "保存混合后的视频",
+        save: true,
+        defaultPath,
+        filters: [{ name: "MKV", extensions: ["mkv"] }],
+      },
     });
     if (!result) return null;
     return result.endsWith(".mkv") ? result : `${result}.mkv`;
   };
 
-  // 从解析结果中收集“需要混流的轨道”。
-  const collectMixInput = (type: TrackType): MixTrackInput | null => {
-    const files = trackFiles.value[type];
-    if (!files.length) return null;
-    const file = files[0];
-    const group = trackInfos.value[type].find((item) => item.file.id === file.id);
-    if (!group || !group.tracks.length) return null;
-    const selected = group.tracks.filter((track) => track.selected !== false).map((track) => track.trackId);
-    if (!selected.length) return null;
-    const trackLangs: Record<string, string> = {};
-    group.tracks.forEach((track) => {
-      if (track.selected === false) return;
-      const lang = trackLangDefaults[type];
-      if (lang) {
-        trackLangs[track.trackId] = lang;
-      }
+  // 从解析结果中收集“需要混流的轨道”（每个文件一项，按添加顺序）。
+  const collectMixInputs = (type: TrackType): MixTrackInput[] =>
+    trackFiles.value[type].flatMap((file) => {
+      const group = trackInfos.value[type].find((item) => item.file.id === file.id);
+      if (!group || !group.tracks.length) return [];
+      const selected = group.tracks.filter((track) => track.selected !== false).map((track) => track.trackId);
+      if (!selected.length) return [];
+      const trackLangs: Record<string, string> = {};
+      group.tracks.forEach((track) => {
+        if (track.selected === false) return;
+        const lang = trackLangDefaults[type];
+        if (lang) {
+          trackLangs[track.trackId] = lang;
+        }
+      });
+      return [{ path: file.path, kind: type, trackIds: selected, trackLangs }];
     });
-    return { path: file.path, kind: type, trackIds: selected, trackLangs };
-  };
 
   // 把混流任务加入队列（不立即执行）。
   const enqueueMixTask = async () => {
@@ -209,12 +223,12 @@ export const useTracksPage = () => {
     trackMixError.value = "";
     trackMixResult.value = "";
 
-    const videoInput = collectMixInput("video");
-    const audioInput = collectMixInput("audio");
-    const subtitleInput = collectMixInput("subtitle");
+    const videoInputs = collectMixInputs("video");
+    const audioInputs = collectMixInputs("audio");
+    const subtitleInputs = collectMixInputs("subtitle");
 
     // 视频轨道是必须的。
-    if (!videoInput) {
+    if (!videoInputs.length) {
       trackMixError.value = "请先检测并选择至少一个视频轨道";
       return;
     }
@@ -225,9 +239,7 @@ export const useTracksPage = () => {
       const outputPath = await pickOutputPath(trackFiles.value.video[0]);
       if (!outputPath) return;
 
-      const inputs: MixTrackInput[] = [videoInput];
-      if (audioInput) inputs.push(audioInput);
-      if (subtitleInput) inputs.push(subtitleInput);
+      const inputs: MixTrackInput[] = [...videoInputs, ...audioInputs, ...subtitleInputs];
 
       // 生成队列项。
       mixQueue.value.push({
@@ -243,12 +255,40 @@ export const useTracksPage = () => {
       trackProgress.value = { video: 0, audio: 0, subtitle: 0 };
       trackMixResult.value = "已添加到混流任务队列";
     } catch (err: any) {
-      trackMixError.value = typeof err === "string" ? err : err?.message || "添加任务失败";
+      trackMixError.value = formatError(err, "添加任务失败");
     } finally {
       trackMixLoading.value = false;
     }
   };
 
+  // 等待媒体作业结束：监听进度事件更新队列项，作业结束时返回最终状态。
+  const waitForMediaJob = (jobId: number, item: MixQueueItem) =>
+    new Promise<MediaJob>((resolve, reject) => {
+      let unlisten: UnlistenFn | null = null;
+      let done = false;
+      const apply = (job: MediaJob) => {
+        if (done || job.id !== jobId) return;
+        item.progress = job.percent;
+        item.phase = job.phase;
+        if (job.status === "succeeded" || job.status === "failed" || job.status === "cancelled") {
+          done = true;
+          unlisten?.();
+          resolve(job);
+        }
+      };
+      listen<MediaJob>("media://mux-progress", (event) => apply(event.payload))
+        .then(async (fn) => {
+          unlisten = fn;
+          if (done) {
+            fn();
+            return;
+          }
+          // 注册监听之前作业可能已经有进展（甚至已经结束），补查一次。
+          apply(await invoke<MediaJob>("get_media_job", { jobId }));
+        })
+        .catch(reject);
+    });
+
   // 顺序执行队列中的混流任务。
   const startMixQueue = async () => {
     if (mixQueueRunning.value) return;
@@ -265,9 +305,11 @@ export const useTracksPage = () => {
       if (item.status !== "queued") continue;
       item.status = "running";
       item.message = undefined;
+      item.progress = 0;
+      item.phase = undefined;
       try {
-        // 调用后端执行混流。
-        const output = await invoke<string>("mix_media_tracks", {
+        // 调用后端创建混流作业，再等待作业结束。
+        const jobId = await invoke<number>("mix_media_tracks", {
           inputs: item.inputs.map((input) => ({
             path: input.path,
             kind: input.kind,
@@ -276,17 +318,38 @@ export const useTracksPage = () => {
           })),
           outputPath: item.outputPath,
         });
-        item.status = "success";
-        item.message = output;
+        item.jobId = jobId;
+        const job = await waitForMediaJob(jobId, item);
+        if (job.status === "succeeded") {
+          item.status = "success";
+          item.message = job.outputPath;
+        } else if (job.status === "cancelled") {
+          item.status = "cancelled";
+          item.message = "已取消";
+        } else {
+          item.status = "failed";
+          item.message = job.error ?? "合成失败";
+        }
       } catch (err: any) {
         // 捕获错误并写入提示。
         item.status = "failed";
-        item.message = typeof err === "string" ? err : err?.message || "合成失败";
+        item.message = formatError(err, "合成失败");
       }
     }
     mixQueueRunning.value = false;
   };
 
+  // 取消正在执行的混流任务（后端结束 mkvmerge 并清理临时文件），队列继续执行后面的任务。
+  const cancelMixTask = async () => {
+    const item = mixQueue.value.find((entry) => entry.status === "running");
+    if (item?.jobId == null) return;
+    try {
+      await invoke("cancel_media_job", { jobId: item.jobId });
+    } catch (err: any) {
+      trackMixError.value = formatError(err, "取消失败");
+    }
+  };
+
   // 清空队列（执行中则拒绝）。
   const clearMixQueue = () => {
     if (mixQueueRunning.value) return;
@@ -317,10 +380,12 @@ export const useTracksPage = () => {
     trackLangDefaults,
     trackLanguageOptions,
     addTrackFile,
+    removeTrackFile,
     detectTracks,
     enqueueMixTask,
     startMixQueue,
     clearMixQueue,
+    cancelMixTask,
     openMixTaskDetail,
   };
 };
@@ -48,12 +48,41 @@ export type MixTrackInput = {
   kind: TrackType;
   trackIds: string[];
   trackLangs?: Record<string, string>;
+  /** 按轨道 ID 指定的混流选项。 */
+  trackOptions?: Record<string, MixTrackOptions>;
+};
+
+/**
+ * 单条轨道的混流选项（省略的字段使用默认行为）。
+ */
+export type MixTrackOptions = {
+  name?: string;
+  default?: boolean;
+  forced?: boolean;
+  /** 延迟（毫秒，可为负数）。 */
+  delay?: number;
+  /** 时间戳拉伸系数（修正线性漂移）。 */
+  stretch?: number;
+  /** 混流前把音轨转码为 AAC / Opus（只用于音频轨道）。 */
+  transcode?: AudioTranscodeOptions;
+};
+
+/**
+ * 音轨转码选项。
+ */
+export type AudioTranscodeOptions = {
+  /** 音频编码。 */
+  codec: "aac" | "opus";
+  /** 码率（kbps），省略时使用编码默认值。 */
+  bitrate?: number;
+  /** 是否缩混为立体声。 */
+  downmix?: boolean;
 };
 
 /**
  * 混流队列状态。
  */
-export type MixQueueStatus = "queued" | "running" | "success" | "failed";
+export type MixQueueStatus = "queued" | "running" | "success" | "failed" | "cancelled";
 
 /**
  * 队列中的单个混流任务。
@@ -65,4 +94,25 @@ export type MixQueueItem = {
   inputs: MixTrackInput[];
   status: MixQueueStatus;
   message?: string;
+  /** 后端媒体作业 ID（开始执行后才有）。 */
+  jobId?: number;
+  /** 执行中的进度（0 ~ 100）与阶段说明。 */
+  progress?: number;
+  phase?: string;
+};
+
+/**
+ * 后端媒体作业（media://mux-progress 事件内容）。
+ */
+export type MediaJob = {
+  id: number;
+  kind: string;
+  status: "queued" | "running" | "succeeded" | "failed" | "cancelled";
+  phase: string;
+  percent: number;
+  outputPath: string;
+  error?: string | null;
+  log: string[];
+  createdAt: string;
+  finishedAt?: string | null;
 };
@@ -0,0 +1,33 @@
+/**
+ * 后端命令返回的结构化错误（对应 Rust 侧的 AppError）。
+ * code 用于区分错误类别，message 是给用户看的中文说明。
+ */
+export type AppError =
+  | { code: "NetworkError"; message: string }
+  | { code: "ApiError"; status: number; message: string }
+  | { code: "ToolMissing"; name: string; message: string }
+  | { code: "ToolFailed"; name: string; message: string }
+  | { code: "IoError"; message: string }
+  | { code: "ParseError"; message: string }
+  | { code: "TranslationError"; message: string }
+  | { code: "TorrentError"; message: string }
+  | { code: "InsufficientSpace"; path: string; required: number; available: number; message: string }
+  | { code: "InvalidInput"; message: string }
+  | { code: "Internal"; message: string };
+
+/**
+ * 判断 invoke 抛出的错误是否为结构化错误。
+ */
+export const isAppError = (value: unknown): value is AppError =>
+  typeof value === "object" && value !== null && "code" in value && "message" in value;
+
+/**
+ * 把 invoke 抛出的错误转换成可展示的文本。
+ * 兼容结构化错误、旧命令返回的字符串以及普通 Error。
+ */
+export const formatError = (value: unknown, fallback = "操作失败") => {
+  if (typeof value === "string") return value || fallback;
+  if (isAppError(value)) return value.message || fallback;
+  if (value instanceof Error) return value.message || fallback;
+  return fallback;
+};
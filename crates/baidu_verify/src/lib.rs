@@ -1,25 +1,264 @@
 /*
-  这个 crate 用于导出两个 C ABI 函数，
-  让其它模块（通过动态库加载）能读取百度翻译的密钥。
+  这个 crate 用于导出 C ABI 函数，
+  让其它模块（通过动态库加载）能读取各类第三方服务的密钥。
   密钥来自编译期环境变量，因此不会以明文形式写在源码里。
+
+  除了百度翻译以外，还支持 DeepL / OpenAI / WebDAV 等服务：
+  统一通过 secret_get(provider, key) 按“服务名 + 字段名”查询。
+
+  百度翻译可以嵌入多组 app_id / api_key（见 build.rs 的 BAIDU_TRANSLATE_CREDENTIALS），
+  通过 baidu_get_credential_count / baidu_get_credential(index) 逐组读取，
+  方便加载方在某组密钥当日额度用完时换下一组。
+
+  构建脚本（build.rs）会把密钥 XOR 混淆后再嵌入，
+  动态库里只保存混淆后的字节，首次查询时才在内存中还原。
+  每个值都附带明文的 FNV-1a 校验值：还原结果对不上时视为密钥不存在。
+
+  后端加载器使用 *_raw 系列接口：直接取回混淆后的字节、混淆密钥（secrets_nonce）
+  与校验值，在自己的进程里还原并校验，明文不经过本库的导出函数。
 */
 
+use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
+use std::sync::OnceLock;
+
+// 一个混淆后的值：(混淆后的字节, 明文校验值)。
+type EncodedSecret = (&'static [u8], u64);
+
+// 由 build.rs 生成：XOR_KEY（混淆密钥）、SECRETS（服务名, 字段名, (混淆后的值, 校验值)）
+// 与 BAIDU_CREDENTIALS（混淆后的百度密钥对列表，主密钥对在第一位）。
+// 服务名与字段名都用小写，查询时大小写不敏感。
+include!(concat!(env!("OUT_DIR"), "/secrets.rs"));
+
+// 密钥存储格式版本。
+// 导出接口或混淆方式发生不兼容变化时递增，加载方据此判断能否使用。
+const SECRETS_VERSION: u32 = 3;
+
+// 每个密钥还原后的 C 字符串，首次查询时统一生成，之后一直复用。
+// 放在静态变量里，返回给 C 侧的指针在整个进程生命周期内都有效。
+static SECRETS_C: OnceLock<Vec<Option<CString>>> = OnceLock::new();
+
+// 百度密钥对还原后的 C 字符串，规则同 SECRETS_C；无法还原或校验失败的密钥对为 None。
+static BAIDU_CREDENTIALS_C: OnceLock<Vec<Option<(CString, CString)>>> = OnceLock::new();
+
+/// 一组百度翻译密钥（返回给 C 侧的结构体）。
+/// 两个指针都指向静态数据；索引越界或校验失败时两者都为空指针。
+#[repr(C)]
+pub struct BaiduCredential {
+  /// APP ID（\0 结尾）。
+  pub app_id: *const c_char,
+  /// API Key（\0 结尾）。
+  pub api_key: *const c_char,
+}
+
+/// 一段混淆后的密钥（返回给 C 侧的结构体）。
+/// data 指向静态数据，共 len 字节；按字节与 secrets_nonce 循环 XOR 即得明文，
+/// 明文的 FNV-1a 64 位哈希应等于 checksum。密钥不存在时 data 为空指针、len 为 0。
+#[repr(C)]
+pub struct RawSecret {
+  /// 混淆后的字节。
+  pub data: *const u8,
+  /// 字节数。
+  pub len: usize,
+  /// 明文的校验值。
+  pub checksum: u64,
+}
+
+impl RawSecret {
+  // 不存在的密钥。
+  const MISSING: RawSecret = RawSecret {
+    data: std::ptr::null(),
+    len: 0,
+    checksum: 0,
+  };
+
+  fn new(encoded: &'static [u8], checksum: u64) -> Self {
+    Self {
+      data: encoded.as_ptr(),
+      len: encoded.len(),
+      checksum,
+    }
+  }
+}
+
+/// 一组混淆后的百度翻译密钥（返回给 C 侧的结构体）。
+#[repr(C)]
+pub struct RawBaiduCredential {
+  /// APP ID。
+  pub app_id: RawSecret,
+  /// API Key。
+  pub api_key: RawSecret,
+}
+
+// FNV-1a 64 位哈希：明文校验值，需要与 build.rs 的算法一致。
+fn fnv1a(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+    (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+  })
+}
+
+// 把混淆后的字节还原成明文，校验通过后再转换成 C 字符串。
+// 校验值对不上，或值里含有 \0（无法表示为 C 字符串）时返回 None。
+fn decode_secret(value: &EncodedSecret) -> Option<CString> {
+  let (encoded, checksum) = *value;
+  let plain: Vec<u8> = encoded
+    .iter()
+    .enumerate()
+    .map(|(i, b)| b ^ XOR_KEY[i % XOR_KEY.len()])
+    .collect();
+  if fnv1a(&plain) != checksum {
+    return None;
+  }
+  CString::new(plain).ok()
+}
+
+// 在密钥表中查找 (provider, key) 对应的 C 字符串。
+// 找不到、未在编译期提供或值非法时返回 None。
+fn lookup_secret(provider: &str, key: &str) -> Option<&'static CString> {
+  let values = SECRETS_C.get_or_init(|| {
+    SECRETS
+      .iter()
+      .map(|(_, _, value)| value.as_ref().and_then(decode_secret))
+      .collect()
+  });
+  find_secret(provider, key).and_then(|idx| values[idx].as_ref())
+}
+
+// 密钥表中 (provider, key) 的位置，大小写不敏感。
+fn find_secret(provider: &str, key: &str) -> Option<usize> {
+  SECRETS
+    .iter()
+    .position(|(p, k, _)| p.eq_ignore_ascii_case(provider) && k.eq_ignore_ascii_case(key))
+}
 
-// 把环境变量拼接成以 \0 结尾的 C 字符串。
-// 这样返回给 C 侧时不会越界。
-const APP_ID_C: &str = concat!(env!("BAIDU_TRANSLATE_APP_ID"), "\0");
-const API_KEY_C: &str = concat!(env!("BAIDU_TRANSLATE_API_KEY"), "\0");
+// 把 C 侧传入的两个参数转换成 &str；空指针或非 UTF-8 时返回 None。
+//
+// # Safety
+// 两个指针要么为空，要么指向合法的 \0 结尾字符串。
+unsafe fn read_args<'a>(provider: *const c_char, key: *const c_char) -> Option<(&'a str, &'a str)> {
+  if provider.is_null() || key.is_null() {
+    return None;
+  }
+  match (CStr::from_ptr(provider).to_str(), CStr::from_ptr(key).to_str()) {
+    (Ok(provider), Ok(key)) => Some((provider.trim(), key.trim())),
+    _ => None,
+  }
+}
+
+// 还原后的百度密钥对列表。
+fn baidu_credentials() -> &'static [Option<(CString, CString)>] {
+  BAIDU_CREDENTIALS_C.get_or_init(|| {
+    BAIDU_CREDENTIALS
+      .iter()
+      .map(|(app_id, api_key)| Some((decode_secret(app_id)?, decode_secret(api_key)?)))
+      .collect()
+  })
+}
+
+// 把查询结果转换成返回给 C 侧的指针，不存在时返回空指针。
+fn secret_ptr(provider: &str, key: &str) -> *const c_char {
+  match lookup_secret(provider, key) {
+    Some(value) => value.as_ptr(),
+    None => std::ptr::null(),
+  }
+}
+
+/// 导出 C ABI 函数：按服务名与字段名读取密钥。
+/// 返回以 \0 结尾的只读字符串指针；参数非法或密钥不存在时返回空指针。
+///
+/// # Safety
+/// 调用方传入的两个指针要么为空，要么指向合法的 \0 结尾字符串。
+/// 返回的指针指向静态数据，调用方不得释放或修改。
+#[no_mangle]
+pub unsafe extern "C" fn secret_get(provider: *const c_char, key: *const c_char) -> *const c_char {
+  // 非 UTF-8 的参数不可能匹配任何条目，直接视为不存在。
+  match read_args(provider, key) {
+    Some((provider, key)) => secret_ptr(provider, key),
+    None => std::ptr::null(),
+  }
+}
+
+/// 导出 C ABI 函数：按服务名与字段名读取混淆后的密钥及其校验值（不在本库内还原）。
+/// 参数非法或密钥不存在时返回 data 为空指针的 RawSecret。
+///
+/// # Safety
+/// 调用方传入的两个指针要么为空，要么指向合法的 \0 结尾字符串。
+/// 返回的 data 指向静态数据，调用方不得释放或修改。
+#[no_mangle]
+pub unsafe extern "C" fn secret_get_raw(provider: *const c_char, key: *const c_char) -> RawSecret {
+  read_args(provider, key)
+    .and_then(|(provider, key)| find_secret(provider, key))
+    .and_then(|idx| SECRETS[idx].2)
+    .map(|(encoded, checksum)| RawSecret::new(encoded, checksum))
+    .unwrap_or(RawSecret::MISSING)
+}
+
+// 导出 C ABI 函数：返回混淆密钥（checksum 为混淆密钥本身的校验值）。
+// 加载方用它还原 secret_get_raw / baidu_get_credential_raw 返回的字节。
+#[no_mangle]
+pub extern "C" fn secrets_nonce() -> RawSecret {
+  RawSecret {
+    data: XOR_KEY.as_ptr(),
+    len: XOR_KEY.len(),
+    checksum: fnv1a(&XOR_KEY),
+  }
+}
+
+// 导出 C ABI 函数：返回密钥存储格式版本。
+// 加载方应先调用它确认版本兼容，再调用 secret_get / secret_get_raw。
+#[no_mangle]
+pub extern "C" fn secrets_version() -> u32 {
+  SECRETS_VERSION
+}
 
 // 导出 C ABI 函数：返回 APP ID 指针。
 // #[no_mangle] 确保符号名不被 Rust 改写，方便动态库查找。
+// 保留给旧版加载器使用，新代码请改用 secret_get。
 #[no_mangle]
 pub extern "C" fn baidu_get_app_id() -> *const c_char {
-  APP_ID_C.as_ptr() as *const c_char
+  secret_ptr("baidu", "app_id")
 }
 
 // 导出 C ABI 函数：返回 API Key 指针。
 #[no_mangle]
 pub extern "C" fn baidu_get_api_key() -> *const c_char {
-  API_KEY_C.as_ptr() as *const c_char
+  secret_ptr("baidu", "api_key")
+}
+
+// 导出 C ABI 函数：返回嵌入的百度翻译密钥对数量（至少为 1，即主密钥对）。
+#[no_mangle]
+pub extern "C" fn baidu_get_credential_count() -> u32 {
+  BAIDU_CREDENTIALS.len() as u32
+}
+
+// 导出 C ABI 函数：按索引返回一组百度翻译密钥（0 为主密钥对）。
+// 索引越界或校验失败时返回的两个指针都为空。
+#[no_mangle]
+pub extern "C" fn baidu_get_credential(index: u32) -> BaiduCredential {
+  match baidu_credentials().get(index as usize).and_then(Option::as_ref) {
+    Some((app_id, api_key)) => BaiduCredential {
+      app_id: app_id.as_ptr(),
+      api_key: api_key.as_ptr(),
+    },
+    None => BaiduCredential {
+      app_id: std::ptr::null(),
+      api_key: std::ptr::null(),
+    },
+  }
+}
+
+// 导出 C ABI 函数：按索引返回一组混淆后的百度翻译密钥及校验值（不在本库内还原）。
+// 索引越界时两个 RawSecret 的 data 都为空指针。
+#[no_mangle]
+pub extern "C" fn baidu_get_credential_raw(index: u32) -> RawBaiduCredential {
+  match BAIDU_CREDENTIALS.get(index as usize) {
+    Some(((app_id, app_id_sum), (api_key, api_key_sum))) => RawBaiduCredential {
+      app_id: RawSecret::new(app_id, *app_id_sum),
+      api_key: RawSecret::new(api_key, *api_key_sum),
+    },
+    None => RawBaiduCredential {
+      app_id: RawSecret::MISSING,
+      api_key: RawSecret::MISSING,
+    },
+  }
 }
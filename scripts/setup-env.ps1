@@ -385,16 +385,18 @@ function Install-FFmpegTools {
     }
 }
 
-# 安装 MKVToolNix（封装/信息读取所需）。
+# 安装 MKVToolNix（封装/信息读取/轨道提取/属性修改所需）。
 function Install-MkvToolNixTools {
-    Write-Host '[CHECK] MKVToolNix tools (mkvmerge/mkvinfo) ...' -ForegroundColor Yellow
+    Write-Host '[CHECK] MKVToolNix tools (mkvmerge/mkvinfo/mkvextract/mkvpropedit) ...' -ForegroundColor Yellow
 
     $projectRoot = Split-Path -Parent $PSScriptRoot
     $binDir = Join-Path $projectRoot "apps\desktop\public\tools"
     $mkvmergeExe = Join-Path $binDir "mkvmerge.exe"
     $mkvinfoExe = Join-Path $binDir "mkvinfo.exe"
+    $mkvextractExe = Join-Path $binDir "mkvextract.exe"
+    $mkvpropeditExe = Join-Path $binDir "mkvpropedit.exe"
 
-    if ((Test-Path $mkvmergeExe) -and (Test-Path $mkvinfoExe)) {
+    if ((Test-Path $mkvmergeExe) -and (Test-Path $mkvinfoExe) -and (Test-Path $mkvextractExe) -and (Test-Path $mkvpropeditExe)) {
         Write-Host '[OK] MKVToolNix already exists' -ForegroundColor Green
         return
     }
@@ -446,10 +448,14 @@ function Install-MkvToolNixTools {
 
         $mkvmergeFound = Get-ChildItem -Path $tempDir -Recurse -Filter 'mkvmerge.exe' | Select-Object -First 1
         $mkvinfoFound = Get-ChildItem -Path $tempDir -Recurse -Filter 'mkvinfo.exe' | Select-Object -First 1
-        if (-not $mkvmergeFound -or -not $mkvinfoFound) { throw 'MKVToolNix zip extract failed' }
+        $mkvextractFound = Get-ChildItem -Path $tempDir -Recurse -Filter 'mkvextract.exe' | Select-Object -First 1
+        $mkvpropeditFound = Get-ChildItem -Path $tempDir -Recurse -Filter 'mkvpropedit.exe' | Select-Object -First 1
+        if (-not $mkvmergeFound -or -not $mkvinfoFound -or -not $mkvextractFound -or -not $mkvpropeditFound) { throw 'MKVToolNix zip extract failed' }
 
         Copy-Item $mkvmergeFound.FullName -Destination $mkvmergeExe -Force
         Copy-Item $mkvinfoFound.FullName -Destination $mkvinfoExe -Force
+        Copy-Item $mkvextractFound.FullName -Destination $mkvextractExe -Force
+        Copy-Item $mkvpropeditFound.FullName -Destination $mkvpropeditExe -Force
 
         Write-Host '[OK] MKVToolNix download done' -ForegroundColor Green
     } catch {